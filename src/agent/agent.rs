@@ -3,18 +3,20 @@ use crate::agent::dispatcher::{
 };
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
+use crate::approval::{ApprovalManager, ApprovalPrompter, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool, ToolSpec};
+use crate::tools::{self, Tool, ToolEffect, ToolSpec};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use std::io::Write as IoWrite;
 use std::sync::Arc;
 use std::time::Instant;
+use tracing::Instrument;
 
 pub struct Agent {
     provider: Box<dyn Provider>,
@@ -32,6 +34,10 @@ pub struct Agent {
     identity_config: crate::config::IdentityConfig,
     skills: Vec<crate::skills::Skill>,
     auto_save: bool,
+    approval: Option<(ApprovalManager, Arc<dyn ApprovalPrompter>)>,
+    /// When set, assistant text produced between tool calls is sent here
+    /// instead of being printed to stdout.
+    text_sink: Option<tokio::sync::mpsc::UnboundedSender<String>>,
     history: Vec<ConversationMessage>,
 }
 
@@ -50,6 +56,7 @@ pub struct AgentBuilder {
     identity_config: Option<crate::config::IdentityConfig>,
     skills: Option<Vec<crate::skills::Skill>>,
     auto_save: Option<bool>,
+    approval: Option<(ApprovalManager, Arc<dyn ApprovalPrompter>)>,
 }
 
 impl AgentBuilder {
@@ -69,6 +76,7 @@ impl AgentBuilder {
             identity_config: None,
             skills: None,
             auto_save: None,
+            approval: None,
         }
     }
 
@@ -142,6 +150,16 @@ impl AgentBuilder {
         self
     }
 
+    /// Require approval for tool calls (per `manager`), asking via `prompter`.
+    pub fn approval(
+        mut self,
+        manager: ApprovalManager,
+        prompter: Arc<dyn ApprovalPrompter>,
+    ) -> Self {
+        self.approval = Some((manager, prompter));
+        self
+    }
+
     pub fn build(self) -> Result<Agent> {
         let tools = self
             .tools
@@ -180,6 +198,8 @@ impl AgentBuilder {
             identity_config: self.identity_config.unwrap_or_default(),
             skills: self.skills.unwrap_or_default(),
             auto_save: self.auto_save.unwrap_or(false),
+            approval: self.approval,
+            text_sink: None,
             history: Vec::new(),
         })
     }
@@ -198,6 +218,32 @@ impl Agent {
         self.history.clear();
     }
 
+    /// Replace the conversation history, returning the previous one. Lets one
+    /// agent serve several independent sessions.
+    pub fn swap_history(&mut self, history: Vec<ConversationMessage>) -> Vec<ConversationMessage> {
+        std::mem::replace(&mut self.history, history)
+    }
+
+    pub fn memory(&self) -> Arc<dyn Memory> {
+        Arc::clone(&self.memory)
+    }
+
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    pub fn set_observer(&mut self, observer: Arc<dyn Observer>) {
+        self.observer = observer;
+    }
+
+    pub fn set_approval(&mut self, manager: ApprovalManager, prompter: Arc<dyn ApprovalPrompter>) {
+        self.approval = Some((manager, prompter));
+    }
+
+    pub fn set_text_sink(&mut self, sink: tokio::sync::mpsc::UnboundedSender<String>) {
+        self.text_sink = Some(sink);
+    }
+
     pub fn from_config(config: &Config) -> Result<Self> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
@@ -271,7 +317,9 @@ impl Agent {
             .memory(memory)
             .observer(observer)
             .tool_dispatcher(tool_dispatcher)
-            .memory_loader(Box::new(DefaultMemoryLoader::default()))
+            .memory_loader(Box::new(DefaultMemoryLoader::from_config(
+                &config.agent.memory_context,
+            )))
             .prompt_builder(SystemPromptBuilder::with_defaults())
             .config(config.agent.clone())
             .model_name(model_name)
@@ -323,18 +371,65 @@ impl Agent {
         self.prompt_builder.build(&ctx)
     }
 
+    /// Returns `false` if the user denied the call.
+    async fn approve_tool_call(&self, call: &ParsedToolCall) -> bool {
+        let Some((manager, prompter)) = &self.approval else {
+            return true;
+        };
+        let effect = self
+            .tools
+            .iter()
+            .find(|t| t.name() == call.name)
+            .map_or(ToolEffect::Unknown, |t| t.effect());
+        if !manager.needs_approval_for(&call.name, effect) {
+            return true;
+        }
+        let request = ApprovalRequest {
+            tool_name: call.name.clone(),
+            arguments: call.arguments.clone(),
+        };
+        let decision = prompter.prompt(&request).await;
+        manager.record_decision(&call.name, &call.arguments, decision, prompter.channel());
+        decision != ApprovalResponse::No
+    }
+
     async fn execute_tool_call(&self, call: &ParsedToolCall) -> ToolExecutionResult {
+        if !self.approve_tool_call(call).await {
+            return ToolExecutionResult {
+                name: call.name.clone(),
+                output: "Denied by user.".into(),
+                success: false,
+                tool_call_id: call.tool_call_id.clone(),
+            };
+        }
+
+        self.observer.record_event(&ObserverEvent::ToolCallStart {
+            tool: call.name.clone(),
+        });
         let start = Instant::now();
 
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
+            match tool
+                .execute(call.arguments.clone())
+                .instrument(tracing::info_span!("tool_call", tool = %call.name))
+                .await
+            {
                 Ok(r) => {
                     self.observer.record_event(&ObserverEvent::ToolCall {
                         tool: call.name.clone(),
                         duration: start.elapsed(),
                         success: r.success,
                     });
-                    if r.success {
+                    if r.success && !r.attachments.is_empty() {
+                        format!(
+                            "{}\n{}",
+                            r.output,
+                            crate::tools::traits::describe_attachments(
+                                &r.attachments,
+                                &crate::tools::traits::attachments_dir(),
+                            )
+                        )
+                    } else if r.success {
                         r.output
                     } else {
                         format!("Error: {}", r.error.unwrap_or(r.output))
@@ -377,7 +472,17 @@ impl Agent {
         results
     }
 
+    /// Run one user turn inside a `turn` span (`turn_id`, `model`).
     pub async fn turn(&mut self, user_message: &str) -> Result<String> {
+        let span = tracing::info_span!(
+            "turn",
+            turn_id = %uuid::Uuid::new_v4(),
+            model = %self.model_name,
+        );
+        self.run_turn(user_message).instrument(span).await
+    }
+
+    async fn run_turn(&mut self, user_message: &str) -> Result<String> {
         if self.history.is_empty() {
             let system_prompt = self.build_system_prompt()?;
             self.history
@@ -393,9 +498,17 @@ impl Agent {
                 .await;
         }
 
+        let recent_history: Vec<ChatMessage> = self
+            .history
+            .iter()
+            .filter_map(|msg| match msg {
+                ConversationMessage::Chat(chat) => Some(chat.clone()),
+                _ => None,
+            })
+            .collect();
         let context = self
             .memory_loader
-            .load_context(self.memory.as_ref(), user_message)
+            .load_context(self.memory.as_ref(), user_message, &recent_history)
             .await
             .unwrap_or_default();
 
@@ -424,6 +537,7 @@ impl Agent {
                     &self.model_name,
                     self.temperature,
                 )
+                .instrument(tracing::info_span!("llm_call", model = %self.model_name))
                 .await
             {
                 Ok(resp) => resp,
@@ -460,8 +574,15 @@ impl Agent {
                     .push(ConversationMessage::Chat(ChatMessage::assistant(
                         text.clone(),
                     )));
-                print!("{text}");
-                let _ = std::io::stdout().flush();
+                match &self.text_sink {
+                    Some(sink) => {
+                        let _ = sink.send(text);
+                    }
+                    None => {
+                        print!("{text}");
+                        let _ = std::io::stdout().flush();
+                    }
+                }
             }
 
             self.history.push(ConversationMessage::AssistantToolCalls {
@@ -624,6 +745,7 @@ mod tests {
                 success: true,
                 output: "tool-out".into(),
                 error: None,
+                attachments: Vec::new(),
             })
         }
     }
@@ -705,4 +827,85 @@ mod tests {
             .iter()
             .any(|msg| matches!(msg, ConversationMessage::ToolResults(_))));
     }
+
+    struct DenyAll;
+
+    #[async_trait]
+    impl ApprovalPrompter for DenyAll {
+        async fn prompt(&self, _request: &ApprovalRequest) -> ApprovalResponse {
+            ApprovalResponse::No
+        }
+
+        fn channel(&self) -> &str {
+            "test"
+        }
+    }
+
+    #[tokio::test]
+    async fn denied_tool_calls_are_not_executed_and_text_goes_to_sink() {
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![crate::providers::ChatResponse {
+                text: Some("let me check".into()),
+                tool_calls: vec![crate::providers::ToolCall {
+                    id: "tc1".into(),
+                    name: "echo".into(),
+                    arguments: "{}".into(),
+                }],
+            }]),
+        });
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+        );
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let manager = ApprovalManager::from_config(&crate::config::AutonomyConfig::default());
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![Box::new(MockTool)])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(NativeToolDispatcher))
+            .approval(manager, Arc::new(DenyAll))
+            .build()
+            .unwrap();
+        let (sink, mut texts) = tokio::sync::mpsc::unbounded_channel();
+        agent.set_text_sink(sink);
+
+        let response = agent.turn("hi").await.unwrap();
+        assert_eq!(response, "done");
+        assert_eq!(texts.try_recv().unwrap(), "let me check");
+
+        let results = agent.history().iter().find_map(|msg| match msg {
+            ConversationMessage::ToolResults(results) => Some(results),
+            _ => None,
+        });
+        assert!(results.unwrap()[0].content.contains("Denied by user"));
+    }
+
+    #[test]
+    fn swap_history_exchanges_sessions() {
+        let memory_cfg = crate::config::MemoryConfig {
+            backend: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> = Arc::from(
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+        );
+        let mut agent = Agent::builder()
+            .provider(Box::new(MockProvider {
+                responses: Mutex::new(vec![]),
+            }))
+            .tools(vec![])
+            .memory(mem)
+            .observer(Arc::from(crate::observability::NoopObserver {}))
+            .tool_dispatcher(Box::new(XmlToolDispatcher))
+            .build()
+            .unwrap();
+        let other = vec![ConversationMessage::Chat(ChatMessage::user("other"))];
+        assert!(agent.swap_history(other).is_empty());
+        assert_eq!(agent.history().len(), 1);
+    }
 }
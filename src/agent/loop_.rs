@@ -1,3 +1,4 @@
+use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
@@ -5,14 +6,16 @@ use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, Provider, ToolCall};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, Tool, ToolEffect};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use regex::{Regex, RegexSet};
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::io::Write as _;
 use std::sync::{Arc, LazyLock};
 use std::time::Instant;
+use tracing::Instrument;
 use uuid::Uuid;
 
 /// Maximum agentic tool-use iterations per user message to prevent runaway loops.
@@ -35,10 +38,15 @@ static SENSITIVE_KV_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r#"(?i)(token|api[_-]?key|password|secret|user[_-]?key|bearer|credential)["']?\s*[:=]\s*(?:"([^"]{8,})"|'([^']{8,})'|([a-zA-Z0-9_\-\.]{8,}))"#).unwrap()
 });
 
+/// Whether a JSON/config key name looks like it holds a credential.
+pub(crate) fn is_sensitive_key(key: &str) -> bool {
+    SENSITIVE_KEY_PATTERNS.is_match(key)
+}
+
 /// Scrub credentials from tool output to prevent accidental exfiltration.
 /// Replaces known credential patterns with a redacted placeholder while preserving
 /// a small prefix for context.
-fn scrub_credentials(input: &str) -> String {
+pub(crate) fn scrub_credentials(input: &str) -> String {
     SENSITIVE_KV_REGEX
         .replace_all(input, |caps: &regex::Captures| {
             let full_match = &caps[0];
@@ -197,24 +205,6 @@ async fn auto_compact_history(
     Ok(true)
 }
 
-/// Build context preamble by searching memory for relevant entries
-async fn build_context(mem: &dyn Memory, user_msg: &str) -> String {
-    let mut context = String::new();
-
-    // Pull relevant memories for this message
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
-        if !entries.is_empty() {
-            context.push_str("[Memory context]\n");
-            for entry in &entries {
-                let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
-            }
-            context.push('\n');
-        }
-    }
-
-    context
-}
-
 /// Build hardware datasheet context from RAG when peripherals are enabled.
 /// Includes pin-alias lookup (e.g. "red_led" → 13) when query matches, plus retrieved chunks.
 fn build_hardware_context(
@@ -534,8 +524,56 @@ pub(crate) async fn agent_turn(
 
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
+///
+/// Runs inside a `turn` span (`turn_id`, `channel`, `provider`, `model`) so
+/// every log line of the turn can be correlated.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_tool_call_loop(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    provider_name: &str,
+    model: &str,
+    temperature: f64,
+    silent: bool,
+    approval: Option<&ApprovalManager>,
+    channel_name: &str,
+    tool_records: Option<&mut Vec<ToolCallRecord>>,
+) -> Result<String> {
+    let span = tracing::info_span!(
+        "turn",
+        turn_id = %Uuid::new_v4(),
+        channel = channel_name,
+        provider = provider_name,
+        model,
+    );
+    let result = tool_call_loop(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        provider_name,
+        model,
+        temperature,
+        silent,
+        approval,
+        channel_name,
+        tool_records,
+    )
+    .instrument(span)
+    .await;
+    if let Err(e) = &result {
+        crate::events::emit(crate::events::Event::Error {
+            component: format!("agent:{channel_name}"),
+            message: crate::providers::sanitize_api_error(&e.to_string()),
+        });
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn tool_call_loop(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
     tools_registry: &[Box<dyn Tool>],
@@ -556,6 +594,7 @@ pub(crate) async fn run_tool_call_loop(
         Vec::new()
     };
 
+    let mut tool_call_count = 0;
     for _iteration in 0..MAX_TOOL_ITERATIONS {
         observer.record_event(&ObserverEvent::LlmRequest {
             provider: provider_name.to_string(),
@@ -564,12 +603,14 @@ pub(crate) async fn run_tool_call_loop(
         });
 
         let llm_started_at = Instant::now();
+        let llm_span = tracing::info_span!("llm_call", provider = provider_name, model);
 
         // Choose between native tool-call API and prompt-based tool use.
         let (response_text, parsed_text, tool_calls, assistant_history_content) =
             if use_native_tools {
                 match provider
                     .chat_with_tools(history, &tool_definitions, model, temperature)
+                    .instrument(llm_span)
                     .await
                 {
                     Ok(resp) => {
@@ -619,6 +660,7 @@ pub(crate) async fn run_tool_call_loop(
             } else {
                 match provider
                     .chat_with_history(history, model, temperature)
+                    .instrument(llm_span)
                     .await
                 {
                     Ok(resp) => {
@@ -658,6 +700,11 @@ pub(crate) async fn run_tool_call_loop(
         if tool_calls.is_empty() {
             // No tool calls — this is the final response
             history.push(ChatMessage::assistant(response_text.clone()));
+            crate::events::emit(crate::events::Event::TurnCompleted {
+                channel: channel_name.to_string(),
+                response: crate::events::clip(&display_text),
+                tool_calls: tool_call_count,
+            });
             return Ok(display_text);
         }
 
@@ -672,7 +719,9 @@ pub(crate) async fn run_tool_call_loop(
         for call in &tool_calls {
             // ── Approval hook ────────────────────────────────
             if let Some(mgr) = approval {
-                if mgr.needs_approval(&call.name) {
+                let effect = find_tool(tools_registry, &call.name)
+                    .map_or(ToolEffect::Unknown, |t| t.effect());
+                if mgr.needs_approval_for(&call.name, effect) {
                     let request = ApprovalRequest {
                         tool_name: call.name.clone(),
                         arguments: call.arguments.clone(),
@@ -703,7 +752,11 @@ pub(crate) async fn run_tool_call_loop(
             });
             let start = Instant::now();
             let (result, tool_success) = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
+                match tool
+                    .execute(call.arguments.clone())
+                    .instrument(tracing::info_span!("tool_call", tool = %call.name))
+                    .await
+                {
                     Ok(r) => {
                         observer.record_event(&ObserverEvent::ToolCall {
                             tool: call.name.clone(),
@@ -711,7 +764,15 @@ pub(crate) async fn run_tool_call_loop(
                             success: r.success,
                         });
                         if r.success {
-                            (scrub_credentials(&r.output), true)
+                            let mut output = scrub_credentials(&r.output);
+                            if !r.attachments.is_empty() {
+                                output.push('\n');
+                                output.push_str(&crate::tools::traits::describe_attachments(
+                                    &r.attachments,
+                                    &crate::tools::traits::attachments_dir(),
+                                ));
+                            }
+                            (output, true)
                         } else {
                             (
                                 format!("Error: {}", r.error.unwrap_or_else(|| r.output)),
@@ -732,13 +793,23 @@ pub(crate) async fn run_tool_call_loop(
                 (format!("Unknown tool: {}", call.name), false)
             };
 
+            let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+            tool_call_count += 1;
+            crate::events::emit(crate::events::Event::ToolExecuted {
+                channel: channel_name.to_string(),
+                tool: call.name.clone(),
+                arguments: call.arguments.clone(),
+                success: tool_success,
+                duration_ms,
+                output: crate::events::clip(&result),
+            });
             if let Some(records) = &mut tool_records {
                 records.push(ToolCallRecord {
                     name: call.name.clone(),
                     arguments: call.arguments.clone(),
                     result: result.clone(),
                     success: tool_success,
-                    duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+                    duration_ms,
                 });
             }
 
@@ -757,6 +828,72 @@ pub(crate) async fn run_tool_call_loop(
     anyhow::bail!("Agent exceeded maximum tool iterations ({MAX_TOOL_ITERATIONS})")
 }
 
+/// Split `/prompt <server>/<name> [key=value ...]` arguments.
+fn parse_prompt_command(spec: &str) -> Result<(String, String, HashMap<String, String>)> {
+    let mut parts = spec.split_whitespace();
+    let Some((server, name)) = parts.next().and_then(|target| target.split_once('/')) else {
+        anyhow::bail!("Usage: /prompt <server>/<name> [key=value ...]");
+    };
+    let mut arguments = HashMap::new();
+    for part in parts {
+        let Some((key, value)) = part.split_once('=') else {
+            anyhow::bail!("Prompt arguments are key=value pairs, got \"{part}\"");
+        };
+        arguments.insert(key.to_string(), value.to_string());
+    }
+    Ok((server.to_string(), name.to_string(), arguments))
+}
+
+async fn expand_prompt_command(
+    mcp: &crate::mcp::McpManager,
+    spec: &str,
+) -> Result<Vec<ChatMessage>> {
+    let (server, name, arguments) = parse_prompt_command(spec)?;
+    let messages = mcp.expand_prompt(&server, &name, &arguments).await?;
+    if messages.is_empty() {
+        anyhow::bail!("Prompt {server}/{name} returned no text messages");
+    }
+    Ok(messages)
+}
+
+fn print_mcp_prompts(mcp: &crate::mcp::McpManager) {
+    let prompts = mcp.prompts();
+    if prompts.is_empty() {
+        println!("No MCP prompt templates available.\n");
+        return;
+    }
+    println!("MCP prompt templates (* = required argument):");
+    for (server, prompt) in prompts {
+        println!(
+            "  {server}/{}",
+            crate::mcp::bridge::prompt_signature(prompt)
+        );
+    }
+    println!("Use: /prompt <server>/<name> key=value ...\n");
+}
+
+/// Append a finished CLI turn to the session transcript; failures only warn.
+fn record_session_turn(
+    config: &Config,
+    session_id: &str,
+    user_message: &str,
+    response: &str,
+    tool_records: &[ToolCallRecord],
+) {
+    if !config.sessions.record_transcripts {
+        return;
+    }
+    if let Err(e) = crate::sessions::record_turn(
+        &config.workspace_dir,
+        session_id,
+        user_message,
+        response,
+        tool_records,
+    ) {
+        tracing::warn!("Failed to record session transcript: {e}");
+    }
+}
+
 /// Build the tool instruction block for the system prompt so the LLM knows
 /// how to invoke tools.
 pub(crate) fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
@@ -850,14 +987,28 @@ pub async fn run(
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     if !peripheral_tools.is_empty() {
         tracing::info!(count = peripheral_tools.len(), "Peripheral tools added");
-        tools_registry.extend(peripheral_tools);
+        tools_registry.extend(crate::security::tool_policy::enforce(
+            &config,
+            peripheral_tools,
+        ));
     }
 
     // MCP tools
-    let (_mcp_manager, mcp_tools) = crate::mcp::McpManager::create_mcp_tools(&config.mcp).await?;
+    let (mut mcp_manager, mcp_tools) =
+        crate::mcp::McpManager::create_mcp_tools(&config.mcp).await?;
     if !mcp_tools.is_empty() {
-        tools_registry.extend(mcp_tools);
-    }
+        tools_registry.extend(crate::security::tool_policy::enforce(&config, mcp_tools));
+    }
+    // Only the MCP tools of the CLI's profile (`[mcp.profiles]`) are
+    // registered; the others wait in `parked_tools`.
+    let mut mcp_profiles = config.mcp.profiles.clone();
+    let mut parked_tools = Vec::new();
+    crate::mcp::profiles::sort_tools(
+        &mcp_profiles,
+        crate::mcp::profiles::active(&mcp_profiles, "cli", "user").as_deref(),
+        &mut tools_registry,
+        &mut parked_tools,
+    );
 
     // ── Resolve provider ─────────────────────────────────────────
     let provider_name = provider_override
@@ -925,7 +1076,19 @@ pub async fn run(
         ),
         (
             "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
+            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context, or you need the exact words of a past conversation (use conversation_search).",
+        ),
+        (
+            "memory_list",
+            "List stored memories page by page, newest first. Use when: reviewing or cleaning up what is remembered. Don't use when: looking for something specific (use memory_recall).",
+        ),
+        (
+            "memory_tags",
+            "List the tags on stored memories. Use when: choosing tags for memory_store or a tag filter for memory_recall.",
+        ),
+        (
+            "memory_update",
+            "Replace the content of a stored memory by key or id. Use when: a remembered fact is wrong or outdated. Don't use when: adding a new fact (use memory_store).",
         ),
         (
             "memory_forget",
@@ -950,6 +1113,18 @@ pub async fn run(
         "Force-run a cron job immediately and record a run history entry.",
     ));
     tool_descs.push(("cron_runs", "Show recent run history for a cron job."));
+    tool_descs.push((
+        "session_transcript",
+        "Get this conversation's earlier turns with tool calls (secrets redacted). Use when: the user asks for a summary, recap, or export of the conversation.",
+    ));
+    tool_descs.push((
+        "conversation_search",
+        "Search recorded conversation turns by words, date range and role. Use when: the user asks what exactly was said, or when (\"what did I ask on Tuesday?\"). Don't use when: looking up a known fact or preference (use memory_recall).",
+    ));
+    tool_descs.push((
+        "docs_search",
+        "Search the user's ingested notes, docs and code (zeroclaw ingest) and cite the file and line. Use when: the question is about their documents or projects. Don't use when: recalling chat-derived facts (use memory_recall).",
+    ));
     tool_descs.push((
         "screenshot",
         "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
@@ -1030,8 +1205,13 @@ pub async fn run(
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = ApprovalManager::from_config(&config.autonomy);
 
+    // ── Per-turn memory context (top-k recall + pinned, budgeted) ─
+    let memory_loader = DefaultMemoryLoader::from_config(&config.agent.memory_context);
+
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
+    let session_id = Uuid::new_v4().to_string();
+    let session_span = tracing::info_span!("session", session_id = %session_id, channel = "cli");
 
     let mut final_output = String::new();
 
@@ -1045,7 +1225,10 @@ pub async fn run(
         }
 
         // Inject memory + hardware RAG context into user message
-        let mem_context = build_context(mem.as_ref(), &msg).await;
+        let mem_context = memory_loader
+            .load_context(mem.as_ref(), &msg, &[])
+            .await
+            .unwrap_or_default();
         let rag_limit = if config.agent.compact_context { 2 } else { 5 };
         let hw_context = hardware_rag
             .as_ref()
@@ -1063,22 +1246,36 @@ pub async fn run(
             ChatMessage::user(&enriched),
         ];
 
-        let response = run_tool_call_loop(
-            provider.as_ref(),
-            &mut history,
-            &tools_registry,
-            observer.as_ref(),
-            provider_name,
-            model_name,
-            temperature,
-            false,
-            Some(&approval_manager),
-            "cli",
-            None,
+        crate::events::emit_in_session(
+            &session_id,
+            crate::events::Event::MessageReceived {
+                channel: "cli".into(),
+                sender: "user".into(),
+                content: crate::events::clip(&msg),
+            },
+        );
+        let mut tool_records = Vec::new();
+        let response = crate::sessions::scope(
+            session_id.clone(),
+            run_tool_call_loop(
+                provider.as_ref(),
+                &mut history,
+                &tools_registry,
+                observer.as_ref(),
+                provider_name,
+                model_name,
+                temperature,
+                false,
+                Some(&approval_manager),
+                "cli",
+                Some(&mut tool_records),
+            ),
         )
+        .instrument(session_span.clone())
         .await?;
         final_output = response.clone();
         println!("{response}");
+        record_session_turn(&config, &session_id, &msg, &response, &tool_records);
         observer.record_event(&ObserverEvent::TurnComplete);
 
         // Auto-save assistant response to daily log
@@ -1091,8 +1288,14 @@ pub async fn run(
         }
     } else {
         println!("🦀 ZeroClaw Interactive Mode");
-        println!("Type /quit to exit.\n");
+        println!(
+            "Type /quit to exit, /prompts to list MCP prompt templates, /profile to pick MCP servers, Ctrl+C to stop a turn.\n"
+        );
         let cli = crate::channels::CliChannel::new();
+        let running_turn = cancel_turn_on_ctrl_c();
+        // Edits to [mcp] apply at the next turn without losing the history.
+        let (mut config_updates, config_watcher) =
+            crate::config::reload::spawn_watcher(config.clone());
 
         // Persistent conversation history across turns
         let mut history = vec![ChatMessage::system(&system_prompt)];
@@ -1118,6 +1321,51 @@ pub async fn run(
             if user_input == "/quit" || user_input == "/exit" {
                 break;
             }
+            if user_input == "/prompts" {
+                print_mcp_prompts(&mcp_manager);
+                continue;
+            }
+            if let Some(arg) = user_input
+                .strip_prefix("/profile")
+                .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+            {
+                println!(
+                    "{}\n",
+                    crate::mcp::profiles::command(&mcp_profiles, "cli", "user", arg)
+                );
+                crate::mcp::profiles::sort_tools(
+                    &mcp_profiles,
+                    crate::mcp::profiles::active(&mcp_profiles, "cli", "user").as_deref(),
+                    &mut tools_registry,
+                    &mut parked_tools,
+                );
+                continue;
+            }
+            let user_input = match user_input.strip_prefix("/prompt ") {
+                Some(spec) => match expand_prompt_command(&mcp_manager, spec).await {
+                    // The template's final user message runs as this turn;
+                    // anything before it joins the history as-is.
+                    Ok(mut messages) => {
+                        let last_user = match messages.last() {
+                            Some(last) if last.role == "user" => messages.pop(),
+                            _ => None,
+                        };
+                        history.extend(messages);
+                        match last_user {
+                            Some(last) => last.content,
+                            None => {
+                                println!("Prompt added to the conversation.\n");
+                                continue;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("\nError: {e}\n");
+                        continue;
+                    }
+                },
+                None => user_input,
+            };
 
             // Auto-save conversation turns
             if config.memory.auto_save {
@@ -1128,7 +1376,10 @@ pub async fn run(
             }
 
             // Inject memory + hardware RAG context into user message
-            let mem_context = build_context(mem.as_ref(), &user_input).await;
+            let mem_context = memory_loader
+                .load_context(mem.as_ref(), &user_input, &history)
+                .await
+                .unwrap_or_default();
             let rag_limit = if config.agent.compact_context { 2 } else { 5 };
             let hw_context = hardware_rag
                 .as_ref()
@@ -1141,30 +1392,105 @@ pub async fn run(
                 format!("{context}{user_input}")
             };
 
+            // A cancelled turn leaves no trace in the history.
+            let turn_start = history.len();
+
+            // Subscribed MCP resources that changed since the last turn.
+            let updates = mcp_manager.take_resource_updates().await;
+            if !updates.is_empty() {
+                for (server, uri) in &updates {
+                    println!("🔔 MCP resource updated: {uri} (server '{server}')");
+                }
+                history.push(ChatMessage::system(crate::mcp::resource_update_note(
+                    &updates,
+                )));
+            }
+
             history.push(ChatMessage::user(&enriched));
 
-            let response = match run_tool_call_loop(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                provider_name,
-                model_name,
-                temperature,
-                false,
-                Some(&approval_manager),
-                "cli",
-                None,
+            tools_registry.append(&mut parked_tools);
+            while let Ok(update) = config_updates.try_recv() {
+                if update.touches("mcp") {
+                    mcp_manager
+                        .apply_config(&update.config.mcp)
+                        .await
+                        .apply(&config, &mut tools_registry);
+                    mcp_profiles.clone_from(&update.config.mcp.profiles);
+                }
+            }
+
+            // Servers may have added or removed tools since the last turn.
+            mcp_manager
+                .refresh_changed_tools()
+                .await
+                .apply(&config, &mut tools_registry);
+            crate::mcp::profiles::sort_tools(
+                &mcp_profiles,
+                crate::mcp::profiles::active(&mcp_profiles, "cli", "user").as_deref(),
+                &mut tools_registry,
+                &mut parked_tools,
+            );
+
+            crate::events::emit_in_session(
+                &session_id,
+                crate::events::Event::MessageReceived {
+                    channel: "cli".into(),
+                    sender: "user".into(),
+                    content: crate::events::clip(&user_input),
+                },
+            );
+            let mut tool_records = Vec::new();
+            let cancel = tokio_util::sync::CancellationToken::new();
+            *running_turn
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(cancel.clone());
+            let turn = crate::sessions::scope(
+                session_id.clone(),
+                crate::mcp::scope(
+                    crate::mcp::Turn {
+                        cancel: Some(cancel.clone()),
+                        channel: Some("cli".into()),
+                        sender: std::env::var("USER").ok(),
+                        ..crate::mcp::Turn::default()
+                    },
+                    run_tool_call_loop(
+                        provider.as_ref(),
+                        &mut history,
+                        &tools_registry,
+                        observer.as_ref(),
+                        provider_name,
+                        model_name,
+                        temperature,
+                        false,
+                        Some(&approval_manager),
+                        "cli",
+                        Some(&mut tool_records),
+                    ),
+                ),
             )
-            .await
-            {
-                Ok(resp) => resp,
-                Err(e) => {
+            .instrument(session_span.clone());
+            let outcome = tokio::select! {
+                outcome = turn => Some(outcome),
+                () = cancel.cancelled() => None,
+            };
+            running_turn
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take();
+            let response = match outcome {
+                Some(Ok(resp)) => resp,
+                Some(Err(e)) => {
                     eprintln!("\nError: {e}\n");
                     continue;
                 }
+                None => {
+                    history.truncate(turn_start);
+                    println!("\n⏹ Turn cancelled\n");
+                    continue;
+                }
             };
             final_output = response.clone();
+            record_session_turn(&config, &session_id, &user_input, &response, &tool_records);
             if let Err(e) = crate::channels::Channel::send(
                 &cli,
                 &crate::channels::traits::SendMessage::new(format!("\n{response}\n"), "user"),
@@ -1195,6 +1521,14 @@ pub async fn run(
                     .await;
             }
         }
+
+        config_watcher.abort();
+
+        if config.sessions.record_transcripts && !final_output.is_empty() {
+            println!(
+                "💾 Session {session_id} — export with `zeroclaw sessions export {session_id}`"
+            );
+        }
     }
 
     let duration = start.elapsed();
@@ -1207,6 +1541,28 @@ pub async fn run(
     Ok(final_output)
 }
 
+/// Ctrl+C while a turn runs cancels the token stored in the returned slot,
+/// which stops the turn and the MCP calls it is waiting on. At the prompt,
+/// with no turn running, Ctrl+C exits as usual.
+fn cancel_turn_on_ctrl_c() -> Arc<std::sync::Mutex<Option<tokio_util::sync::CancellationToken>>> {
+    let running: Arc<std::sync::Mutex<Option<tokio_util::sync::CancellationToken>>> =
+        Arc::default();
+    let slot = Arc::clone(&running);
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            let turn = slot
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take();
+            match turn {
+                Some(cancel) => cancel.cancel(),
+                None => std::process::exit(130),
+            }
+        }
+    });
+    running
+}
+
 /// Process a single message through the full agent (with tools, peripherals, memory).
 /// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
 pub async fn process_message(config: Config, message: &str) -> Result<String> {
@@ -1248,12 +1604,15 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     );
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
-    tools_registry.extend(peripheral_tools);
+    tools_registry.extend(crate::security::tool_policy::enforce(
+        &config,
+        peripheral_tools,
+    ));
 
     // MCP tools
     let (_mcp_manager, mcp_tools) = crate::mcp::McpManager::create_mcp_tools(&config.mcp).await?;
     if !mcp_tools.is_empty() {
-        tools_registry.extend(mcp_tools);
+        tools_registry.extend(crate::security::tool_policy::enforce(&config, mcp_tools));
     }
 
     let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
@@ -1292,6 +1651,9 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         ("file_write", "Write file contents."),
         ("memory_store", "Save to memory."),
         ("memory_recall", "Search memory."),
+        ("memory_list", "List stored memories, newest first."),
+        ("memory_tags", "List memory tags."),
+        ("memory_update", "Correct a memory entry."),
         ("memory_forget", "Delete a memory entry."),
         ("screenshot", "Capture a screenshot."),
         ("image_info", "Read image metadata."),
@@ -1344,7 +1706,11 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     );
     system_prompt.push_str(&build_tool_instructions(&tools_registry));
 
-    let mem_context = build_context(mem.as_ref(), message).await;
+    let memory_loader = DefaultMemoryLoader::from_config(&config.agent.memory_context);
+    let mem_context = memory_loader
+        .load_context(mem.as_ref(), message, &[])
+        .await
+        .unwrap_or_default();
     let rag_limit = if config.agent.compact_context { 2 } else { 5 };
     let hw_context = hardware_rag
         .as_ref()
@@ -1380,6 +1746,18 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn prompt_command_parses_target_and_arguments() {
+        let (server, name, arguments) =
+            parse_prompt_command("docs/review code=main.rs focus=perf").unwrap();
+        assert_eq!((server.as_str(), name.as_str()), ("docs", "review"));
+        assert_eq!(arguments["code"], "main.rs");
+        assert_eq!(arguments["focus"], "perf");
+
+        assert!(parse_prompt_command("review").is_err());
+        assert!(parse_prompt_command("docs/review focus").is_err());
+    }
+
     #[test]
     fn test_scrub_credentials() {
         let input = "API_KEY=sk-1234567890abcdef; token: 1234567890; password=\"secret123456\"";
@@ -1,20 +1,40 @@
-use crate::memory::Memory;
+use crate::config::MemoryContextConfig;
+use crate::memory::namespace::{self, ALL_NAMESPACES, SHARED_NAMESPACE};
+use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+use crate::providers::ChatMessage;
 use async_trait::async_trait;
+use std::collections::HashSet;
 use std::fmt::Write;
 
+/// Memories stored under this custom category are injected on every turn,
+/// regardless of relevance to the current message.
+pub const PINNED_CATEGORY: &str = "pinned";
+
+/// Rough chars-per-token ratio used to enforce the context token budget.
+const CHARS_PER_TOKEN: usize = 4;
+
 #[async_trait]
 pub trait MemoryLoader: Send + Sync {
-    async fn load_context(&self, memory: &dyn Memory, user_message: &str)
-        -> anyhow::Result<String>;
+    /// Build the memory context block for `user_message`. `recent_history` is
+    /// the conversation so far; memories already visible there are skipped.
+    async fn load_context(
+        &self,
+        memory: &dyn Memory,
+        user_message: &str,
+        recent_history: &[ChatMessage],
+    ) -> anyhow::Result<String>;
 }
 
 pub struct DefaultMemoryLoader {
     limit: usize,
+    max_chars: usize,
+    include_pinned: bool,
+    dedup_window: usize,
 }
 
 impl Default for DefaultMemoryLoader {
     fn default() -> Self {
-        Self { limit: 5 }
+        Self::from_config(&MemoryContextConfig::default())
     }
 }
 
@@ -22,8 +42,56 @@ impl DefaultMemoryLoader {
     pub fn new(limit: usize) -> Self {
         Self {
             limit: limit.max(1),
+            ..Self::default()
         }
     }
+
+    pub fn from_config(config: &MemoryContextConfig) -> Self {
+        Self {
+            limit: config.top_k.max(1),
+            max_chars: config.max_tokens.saturating_mul(CHARS_PER_TOKEN),
+            include_pinned: config.include_pinned,
+            dedup_window: config.dedup_window,
+        }
+    }
+
+    /// True when `content` is already present in the current message or the
+    /// trailing `dedup_window` history messages (case-insensitive).
+    fn already_in_context(
+        &self,
+        content: &str,
+        user_message: &str,
+        recent_history: &[ChatMessage],
+    ) -> bool {
+        let needle = content.trim().to_lowercase();
+        if needle.is_empty() {
+            return true;
+        }
+        if user_message.to_lowercase().contains(&needle) {
+            return true;
+        }
+        let window_start = recent_history.len().saturating_sub(self.dedup_window);
+        recent_history[window_start..]
+            .iter()
+            .filter(|msg| msg.role != "system")
+            .any(|msg| msg.content.to_lowercase().contains(&needle))
+    }
+}
+
+/// Append `line` to `section` if it fits in the remaining character budget.
+fn push_within_budget(
+    section: &mut String,
+    line: &str,
+    used: &mut usize,
+    max_chars: usize,
+) -> bool {
+    let cost = line.chars().count();
+    if *used + cost > max_chars {
+        return false;
+    }
+    section.push_str(line);
+    *used += cost;
+    true
 }
 
 #[async_trait]
@@ -32,17 +100,60 @@ impl MemoryLoader for DefaultMemoryLoader {
         &self,
         memory: &dyn Memory,
         user_message: &str,
+        recent_history: &[ChatMessage],
     ) -> anyhow::Result<String> {
-        let entries = memory.recall(user_message, self.limit, None).await?;
-        if entries.is_empty() {
-            return Ok(String::new());
+        let pinned_category = MemoryCategory::Custom(PINNED_CATEGORY.into());
+        let mut pinned: Vec<MemoryEntry> = if self.include_pinned {
+            memory.list(Some(&pinned_category), None).await?
+        } else {
+            Vec::new()
+        };
+        // Shared standing instructions apply in every conversation
+        let active = namespace::current();
+        if self.include_pinned && active != SHARED_NAMESPACE && active != ALL_NAMESPACES {
+            let shared = memory.list(Some(&pinned_category), None);
+            pinned.extend(namespace::scope(SHARED_NAMESPACE.to_string(), shared).await?);
         }
+        let relevant = memory.recall(user_message, self.limit, None).await?;
+
+        let mut seen = HashSet::new();
+        let mut used = 0;
 
-        let mut context = String::from("[Memory context]\n");
-        for entry in entries {
-            let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
+        let mut pinned_block = String::new();
+        for entry in &pinned {
+            if !seen.insert(entry.content.trim().to_lowercase()) {
+                continue;
+            }
+            let line = format!("- {}\n", entry.content.trim());
+            if !push_within_budget(&mut pinned_block, &line, &mut used, self.max_chars) {
+                break;
+            }
+        }
+
+        let mut memory_block = String::new();
+        for entry in &relevant {
+            if entry.category == pinned_category
+                || !seen.insert(entry.content.trim().to_lowercase())
+                || self.already_in_context(&entry.content, user_message, recent_history)
+            {
+                continue;
+            }
+            let line = format!("- {}: {}\n", entry.key, entry.content.trim());
+            if !push_within_budget(&mut memory_block, &line, &mut used, self.max_chars) {
+                break;
+            }
+        }
+
+        let mut context = String::new();
+        if !pinned_block.is_empty() {
+            let _ = write!(context, "[Pinned instructions]\n{pinned_block}");
+        }
+        if !memory_block.is_empty() {
+            let _ = write!(context, "[Memory context]\n{memory_block}");
+        }
+        if !context.is_empty() {
+            context.push('\n');
         }
-        context.push('\n');
         Ok(context)
     }
 }
@@ -50,7 +161,7 @@ impl MemoryLoader for DefaultMemoryLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::memory::{Memory, MemoryCategory, MemoryEntry, SqliteMemory};
 
     struct MockMemory;
 
@@ -83,6 +194,9 @@ mod tests {
                 timestamp: "now".into(),
                 session_id: None,
                 score: None,
+                namespace: None,
+                tags: Vec::new(),
+                match_kind: None,
             }])
         }
 
@@ -118,8 +232,90 @@ mod tests {
     #[tokio::test]
     async fn default_loader_formats_context() {
         let loader = DefaultMemoryLoader::default();
-        let context = loader.load_context(&MockMemory, "hello").await.unwrap();
+        let context = loader
+            .load_context(&MockMemory, "hello", &[])
+            .await
+            .unwrap();
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("- k: v"));
     }
+
+    #[tokio::test]
+    async fn loader_injects_pinned_first_and_skips_recent_history() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store(
+            "tone",
+            "Always answer in French",
+            MemoryCategory::Custom(PINNED_CATEGORY.into()),
+            None,
+        )
+        .await
+        .unwrap();
+        mem.store("city", "User lives in Lyon", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "pet",
+            "User has a cat named Lyon",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let history = vec![ChatMessage::assistant("Noted: User has a cat named Lyon.")];
+        let context = DefaultMemoryLoader::default()
+            .load_context(&mem, "Lyon", &history)
+            .await
+            .unwrap();
+
+        assert!(context.starts_with("[Pinned instructions]\n- Always answer in French\n"));
+        assert!(context.contains("- city: User lives in Lyon"));
+        assert!(!context.contains("cat named Lyon"));
+    }
+
+    #[tokio::test]
+    async fn loader_adds_shared_pinned_to_every_conversation() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let pinned = MemoryCategory::Custom(PINNED_CATEGORY.into());
+        let store = mem.store("rules", "No spoilers", pinned.clone(), None);
+        namespace::scope(SHARED_NAMESPACE.into(), store)
+            .await
+            .unwrap();
+        let store = mem.store("tone", "Answer in French", pinned, None);
+        namespace::scope("discord:alice".into(), store)
+            .await
+            .unwrap();
+
+        let loader = DefaultMemoryLoader::default();
+        let load = loader.load_context(&mem, "hi", &[]);
+        let context = namespace::scope("discord:bob".into(), load).await.unwrap();
+        assert!(context.contains("- No spoilers"));
+        assert!(!context.contains("French"));
+    }
+
+    #[tokio::test]
+    async fn loader_respects_token_budget() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        for i in 0..5 {
+            mem.store(
+                &format!("note_{i}"),
+                &format!("rust note {i} {}", "x".repeat(40)),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let loader = DefaultMemoryLoader::from_config(&MemoryContextConfig {
+            max_tokens: 35,
+            ..MemoryContextConfig::default()
+        });
+        let context = loader.load_context(&mem, "rust", &[]).await.unwrap();
+        assert_eq!(context.matches("- note_").count(), 2);
+    }
 }
@@ -281,6 +281,7 @@ mod tests {
                 success: true,
                 output: "ok".into(),
                 error: None,
+                attachments: Vec::new(),
             })
         }
     }
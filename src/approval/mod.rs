@@ -5,6 +5,8 @@
 
 use crate::config::AutonomyConfig;
 use crate::security::AutonomyLevel;
+use crate::tools::ToolEffect;
+use async_trait::async_trait;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -42,11 +44,23 @@ pub struct ApprovalLogEntry {
     pub channel: String,
 }
 
+/// Answers approval requests for front-ends that cannot read stdin
+/// (e.g. the terminal UI, which owns the screen in raw mode).
+#[async_trait]
+pub trait ApprovalPrompter: Send + Sync {
+    /// Ask the user to approve `request` and wait for their decision.
+    async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse;
+
+    /// Channel name recorded in the audit log.
+    fn channel(&self) -> &str;
+}
+
 // ── ApprovalManager ──────────────────────────────────────────────
 
 /// Manages the interactive approval workflow.
 ///
 /// - Checks config-level `auto_approve` / `always_ask` lists
+/// - Prompts for destructive tools every time and lets read-only ones run
 /// - Maintains a session-scoped "always" allowlist
 /// - Records an audit trail of all decisions
 pub struct ApprovalManager {
@@ -78,6 +92,15 @@ impl ApprovalManager {
     ///
     /// Returns `true` if the call needs a prompt, `false` if it can proceed.
     pub fn needs_approval(&self, tool_name: &str) -> bool {
+        self.needs_approval_for(tool_name, ToolEffect::Unknown)
+    }
+
+    /// Like [`Self::needs_approval`], for a tool that declares `effect`.
+    ///
+    /// Destructive tools prompt on every call unless listed in
+    /// `auto_approve`; read-only tools never prompt unless listed in
+    /// `always_ask`.
+    pub fn needs_approval_for(&self, tool_name: &str, effect: ToolEffect) -> bool {
         // Full autonomy never prompts.
         if self.autonomy_level == AutonomyLevel::Full {
             return false;
@@ -98,6 +121,14 @@ impl ApprovalManager {
             return false;
         }
 
+        // Declared side effects come before the session allowlist, so an
+        // "Always" never covers a destructive tool.
+        match effect {
+            ToolEffect::Destructive => return true,
+            ToolEffect::ReadOnly => return false,
+            ToolEffect::Unknown => {}
+        }
+
         // Session allowlist (from prior "Always" responses).
         let allowlist = self
             .session_allowlist
@@ -194,7 +225,7 @@ fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
 }
 
 /// Produce a short human-readable summary of tool arguments.
-fn summarize_args(args: &serde_json::Value) -> String {
+pub fn summarize_args(args: &serde_json::Value) -> String {
     match args {
         serde_json::Value::Object(map) => {
             let parts: Vec<String> = map
@@ -292,6 +323,28 @@ mod tests {
         assert!(!mgr.needs_approval("shell"));
     }
 
+    #[test]
+    fn declared_effects_drive_prompts() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        assert!(!mgr.needs_approval_for("mcp__fs__read", ToolEffect::ReadOnly));
+        assert!(mgr.needs_approval_for("mcp__fs__delete", ToolEffect::Destructive));
+
+        // "Always" does not cover destructive tools.
+        mgr.record_decision(
+            "mcp__fs__delete",
+            &serde_json::json!({}),
+            ApprovalResponse::Always,
+            "cli",
+        );
+        assert!(mgr.needs_approval_for("mcp__fs__delete", ToolEffect::Destructive));
+
+        // Config lists still win.
+        assert!(mgr.needs_approval_for("shell", ToolEffect::ReadOnly));
+        assert!(!mgr.needs_approval_for("file_read", ToolEffect::Destructive));
+        let full = ApprovalManager::from_config(&full_config());
+        assert!(!full.needs_approval_for("mcp__fs__delete", ToolEffect::Destructive));
+    }
+
     // ── session allowlist ────────────────────────────────────
 
     #[test]
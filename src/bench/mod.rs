@@ -0,0 +1,725 @@
+//! `zeroclaw bench`: repeatable performance measurements.
+//!
+//! Three suites, each optional:
+//! - **provider** — time to first streamed token and total latency of a short
+//!   prompt against the raw provider (no retry/fallback wrapper).
+//! - **memory** — store and recall throughput of the SQLite backend at
+//!   several database sizes, in a throwaway workspace.
+//! - **tools** — dispatcher overhead for one round trip (parse the model
+//!   response, look up and run a no-op tool, format the results).
+//!
+//! The report is JSON (`--output`) and flattens to named metrics, so two runs
+//! can be compared with `--baseline`.
+
+use crate::agent::dispatcher::{
+    NativeToolDispatcher, ParsedToolCall, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
+};
+use crate::config::Config;
+use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+use crate::providers::traits::StreamOptions;
+use crate::providers::{ChatResponse, Provider, ToolCall};
+use crate::tools::{Tool, ToolResult};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Prompt sent to the provider; short so latency dominates generation time.
+const PROVIDER_PROMPT: &str = "Reply with the single word: ready";
+/// Recall queries issued per memory size.
+const RECALL_QUERIES: usize = 100;
+/// Dispatcher round trips per dispatcher.
+const DISPATCH_ITERATIONS: usize = 2_000;
+/// Tool calls in each simulated model response.
+const CALLS_PER_RESPONSE: usize = 3;
+/// Registry size for tool lookup (roughly the full built-in tool set).
+const REGISTRY_SIZE: usize = 32;
+
+const VOCABULARY: &[&str] = &[
+    "deploy", "invoice", "garden", "kernel", "recipe", "meeting", "rust", "travel", "budget",
+    "sensor", "backup", "playlist", "contract", "weather", "firmware", "dentist", "schema",
+    "birthday", "latency", "router", "novel", "tax", "python", "coffee",
+];
+
+/// Which suites to run and how hard to push them.
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub provider: bool,
+    pub memory: bool,
+    pub tools: bool,
+    pub provider_name: Option<String>,
+    pub model: Option<String>,
+    pub runs: usize,
+    pub memory_sizes: Vec<usize>,
+}
+
+/// Summary of a set of timings, in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    pub fn from_durations(durations: &[Duration]) -> Option<Self> {
+        if durations.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f64> = durations.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let rank = ((p * ms.len() as f64).ceil() as usize).clamp(1, ms.len());
+            ms[rank - 1]
+        };
+        Some(Self {
+            samples: ms.len(),
+            min_ms: ms[0],
+            mean_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+            p50_ms: percentile(0.50),
+            p95_ms: percentile(0.95),
+            max_ms: ms[ms.len() - 1],
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProviderReport {
+    pub provider: String,
+    pub model: String,
+    /// Whether first-token latency was measured from a real stream; when
+    /// false it equals total latency.
+    pub streaming: bool,
+    pub first_token: Option<LatencyStats>,
+    pub total: Option<LatencyStats>,
+    pub errors: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemoryReport {
+    pub entries: usize,
+    pub store_ops_per_sec: f64,
+    pub recall_ops_per_sec: f64,
+    pub recall: Option<LatencyStats>,
+    pub db_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DispatchReport {
+    pub dispatcher: String,
+    pub calls_per_response: usize,
+    pub registry_size: usize,
+    /// Mean cost of one full round trip, in microseconds.
+    pub round_trip_us: f64,
+}
+
+/// Full benchmark output, stable enough to diff between runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BenchReport {
+    pub version: String,
+    pub timestamp: String,
+    pub os: String,
+    pub arch: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderReport>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory: Vec<MemoryReport>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<DispatchReport>,
+}
+
+impl BenchReport {
+    /// Flatten to `(name, value)` pairs; names are stable across runs.
+    pub fn metrics(&self) -> Vec<(String, f64)> {
+        let mut out = Vec::new();
+        if let Some(provider) = &self.provider {
+            if let Some(stats) = &provider.first_token {
+                out.push(("provider.first_token.p50_ms".into(), stats.p50_ms));
+                out.push(("provider.first_token.p95_ms".into(), stats.p95_ms));
+            }
+            if let Some(stats) = &provider.total {
+                out.push(("provider.total.p50_ms".into(), stats.p50_ms));
+                out.push(("provider.total.p95_ms".into(), stats.p95_ms));
+            }
+        }
+        for memory in &self.memory {
+            let prefix = format!("memory.{}", memory.entries);
+            out.push((
+                format!("{prefix}.store_ops_per_sec"),
+                memory.store_ops_per_sec,
+            ));
+            out.push((
+                format!("{prefix}.recall_ops_per_sec"),
+                memory.recall_ops_per_sec,
+            ));
+            if let Some(stats) = &memory.recall {
+                out.push((format!("{prefix}.recall.p95_ms"), stats.p95_ms));
+            }
+        }
+        for dispatch in &self.tools {
+            out.push((
+                format!("tools.{}.round_trip_us", dispatch.dispatcher),
+                dispatch.round_trip_us,
+            ));
+        }
+        out
+    }
+}
+
+/// Whether a larger value of this metric is an improvement.
+fn higher_is_better(metric: &str) -> bool {
+    metric.ends_with("ops_per_sec")
+}
+
+/// Run the selected suites.
+pub async fn run(config: &Config, options: &BenchOptions) -> Result<BenchReport> {
+    if options.runs == 0 {
+        bail!("--runs must be at least 1");
+    }
+    let mut report = BenchReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        provider: None,
+        memory: Vec::new(),
+        tools: Vec::new(),
+    };
+
+    if options.provider {
+        let name = options
+            .provider_name
+            .as_deref()
+            .or(config.default_provider.as_deref())
+            .unwrap_or("openrouter");
+        let model = options
+            .model
+            .as_deref()
+            .or(config.default_model.as_deref())
+            .unwrap_or("anthropic/claude-sonnet-4");
+        println!("⏱️  provider: {name} / {model} ({} runs)", options.runs);
+        let provider = crate::providers::create_provider_with_url(
+            name,
+            config.api_key.as_deref(),
+            config.api_url.as_deref(),
+        )?;
+        report.provider = Some(
+            bench_provider(
+                provider.as_ref(),
+                name,
+                model,
+                config.default_temperature,
+                options.runs,
+            )
+            .await,
+        );
+    }
+
+    if options.memory {
+        for &entries in &options.memory_sizes {
+            println!("⏱️  memory: {entries} entries");
+            report.memory.push(bench_memory(entries).await?);
+        }
+    }
+
+    if options.tools {
+        println!("⏱️  tools: dispatcher round trips");
+        report.tools.push(
+            bench_dispatcher(
+                "xml",
+                &XmlToolDispatcher,
+                &xml_response(),
+                DISPATCH_ITERATIONS,
+            )
+            .await,
+        );
+        report.tools.push(
+            bench_dispatcher(
+                "native",
+                &NativeToolDispatcher,
+                &native_response(),
+                DISPATCH_ITERATIONS,
+            )
+            .await,
+        );
+    }
+
+    Ok(report)
+}
+
+async fn bench_provider(
+    provider: &dyn Provider,
+    name: &str,
+    model: &str,
+    temperature: f64,
+    runs: usize,
+) -> ProviderReport {
+    let streaming = provider.supports_streaming();
+    // Untimed warmup so TLS and DNS setup don't land in the first sample.
+    let _ = provider.warmup().await;
+
+    let mut first_token = Vec::with_capacity(runs);
+    let mut total = Vec::with_capacity(runs);
+    let mut errors = 0;
+    let mut last_error = None;
+    for _ in 0..runs {
+        let started = Instant::now();
+        let outcome = if streaming {
+            stream_once(provider, model, temperature, started).await
+        } else {
+            provider
+                .simple_chat(PROVIDER_PROMPT, model, temperature)
+                .await
+                .map(|_| {
+                    let elapsed = started.elapsed();
+                    (elapsed, elapsed)
+                })
+        };
+        match outcome {
+            Ok((first, all)) => {
+                first_token.push(first);
+                total.push(all);
+            }
+            Err(e) => {
+                errors += 1;
+                last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    ProviderReport {
+        provider: name.to_string(),
+        model: model.to_string(),
+        streaming,
+        first_token: LatencyStats::from_durations(&first_token),
+        total: LatencyStats::from_durations(&total),
+        errors,
+        last_error,
+    }
+}
+
+/// Returns (time to first non-empty delta, time to final chunk).
+async fn stream_once(
+    provider: &dyn Provider,
+    model: &str,
+    temperature: f64,
+    started: Instant,
+) -> Result<(Duration, Duration)> {
+    let mut stream = provider.stream_chat_with_system(
+        None,
+        PROVIDER_PROMPT,
+        model,
+        temperature,
+        StreamOptions::new(true),
+    );
+    let mut first = None;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if first.is_none() && !chunk.delta.is_empty() {
+            first = Some(started.elapsed());
+        }
+        if chunk.is_final {
+            break;
+        }
+    }
+    let total = started.elapsed();
+    let first = first.context("Stream ended without any content")?;
+    Ok((first, total))
+}
+
+fn memory_content(i: usize) -> String {
+    let word = |offset: usize| VOCABULARY[(i * 7 + offset) % VOCABULARY.len()];
+    format!(
+        "Note {i}: the {} {} needs a follow-up about {} before {}.",
+        word(0),
+        word(3),
+        word(5),
+        word(11)
+    )
+}
+
+async fn bench_memory(entries: usize) -> Result<MemoryReport> {
+    let workspace = std::env::temp_dir().join(format!("zeroclaw-bench-{}", uuid::Uuid::new_v4()));
+    let result = bench_memory_in(&workspace, entries).await;
+    let _ = std::fs::remove_dir_all(&workspace);
+    result
+}
+
+async fn bench_memory_in(workspace: &Path, entries: usize) -> Result<MemoryReport> {
+    let memory = SqliteMemory::new(workspace)?;
+
+    let started = Instant::now();
+    for i in 0..entries {
+        memory
+            .store(
+                &format!("bench_{i}"),
+                &memory_content(i),
+                MemoryCategory::Core,
+                None,
+            )
+            .await?;
+    }
+    let store_elapsed = started.elapsed();
+
+    let mut recalls = Vec::with_capacity(RECALL_QUERIES);
+    for i in 0..RECALL_QUERIES {
+        let query = format!(
+            "{} {}",
+            VOCABULARY[i % VOCABULARY.len()],
+            VOCABULARY[(i * 5 + 1) % VOCABULARY.len()]
+        );
+        let started = Instant::now();
+        memory.recall(&query, 5, None).await?;
+        recalls.push(started.elapsed());
+    }
+    let recall_elapsed: Duration = recalls.iter().sum();
+
+    let db_bytes = std::fs::metadata(workspace.join("memory").join("brain.db"))
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    Ok(MemoryReport {
+        entries,
+        store_ops_per_sec: ops_per_sec(entries, store_elapsed),
+        recall_ops_per_sec: ops_per_sec(RECALL_QUERIES, recall_elapsed),
+        recall: LatencyStats::from_durations(&recalls),
+        db_bytes,
+    })
+}
+
+fn ops_per_sec(ops: usize, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        ops as f64 / secs
+    } else {
+        0.0
+    }
+}
+
+/// Tool that returns its arguments, so only dispatch cost is measured.
+struct EchoTool {
+    name: String,
+}
+
+#[async_trait]
+impl Tool for EchoTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        "Echo the arguments back"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({"type": "object", "properties": {"text": {"type": "string"}}})
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> Result<ToolResult> {
+        Ok(ToolResult {
+            success: true,
+            output: args.to_string(),
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+}
+
+fn registry() -> Vec<Box<dyn Tool>> {
+    (0..REGISTRY_SIZE)
+        .map(|i| {
+            Box::new(EchoTool {
+                name: format!("tool_{i}"),
+            }) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+/// Calls target the end of the registry, the worst case for a linear lookup.
+fn called_tool(i: usize) -> String {
+    format!("tool_{}", REGISTRY_SIZE - 1 - i % REGISTRY_SIZE)
+}
+
+fn xml_response() -> ChatResponse {
+    let mut text = String::from("Let me check a few things.\n");
+    for i in 0..CALLS_PER_RESPONSE {
+        let _ = writeln!(
+            text,
+            "<tool_call>\n{}\n</tool_call>",
+            serde_json::json!({"name": called_tool(i), "arguments": {"text": format!("call {i}")}})
+        );
+    }
+    ChatResponse {
+        text: Some(text),
+        tool_calls: Vec::new(),
+    }
+}
+
+fn native_response() -> ChatResponse {
+    ChatResponse {
+        text: Some("Let me check a few things.".into()),
+        tool_calls: (0..CALLS_PER_RESPONSE)
+            .map(|i| ToolCall {
+                id: format!("call_{i}"),
+                name: called_tool(i),
+                arguments: serde_json::json!({"text": format!("call {i}")}).to_string(),
+            })
+            .collect(),
+    }
+}
+
+async fn dispatch_once(
+    dispatcher: &dyn ToolDispatcher,
+    tools: &[Box<dyn Tool>],
+    response: &ChatResponse,
+) -> usize {
+    let (_text, calls): (String, Vec<ParsedToolCall>) = dispatcher.parse_response(response);
+    let mut results = Vec::with_capacity(calls.len());
+    for call in calls {
+        let output = match tools.iter().find(|t| t.name() == call.name) {
+            Some(tool) => match tool.execute(call.arguments).await {
+                Ok(result) => result.output,
+                Err(e) => e.to_string(),
+            },
+            None => format!("Unknown tool: {}", call.name),
+        };
+        results.push(ToolExecutionResult {
+            name: call.name,
+            output,
+            success: true,
+            tool_call_id: call.tool_call_id,
+        });
+    }
+    let _ = dispatcher.format_results(&results);
+    results.len()
+}
+
+async fn bench_dispatcher(
+    label: &str,
+    dispatcher: &dyn ToolDispatcher,
+    response: &ChatResponse,
+    iterations: usize,
+) -> DispatchReport {
+    let tools = registry();
+    let started = Instant::now();
+    for _ in 0..iterations {
+        std::hint::black_box(dispatch_once(dispatcher, &tools, response).await);
+    }
+    let elapsed = started.elapsed();
+    DispatchReport {
+        dispatcher: label.to_string(),
+        calls_per_response: CALLS_PER_RESPONSE,
+        registry_size: REGISTRY_SIZE,
+        round_trip_us: elapsed.as_secs_f64() * 1_000_000.0 / iterations.max(1) as f64,
+    }
+}
+
+fn print_report(report: &BenchReport) {
+    println!();
+    if let Some(provider) = &report.provider {
+        println!("Provider {} / {}", provider.provider, provider.model);
+        let fmt = |stats: &Option<LatencyStats>| match stats {
+            Some(s) => format!("p50 {:.0} ms, p95 {:.0} ms", s.p50_ms, s.p95_ms),
+            None => "no successful runs".to_string(),
+        };
+        if provider.streaming {
+            println!("  first token  {}", fmt(&provider.first_token));
+        } else {
+            println!("  first token  (provider does not stream)");
+        }
+        println!("  total        {}", fmt(&provider.total));
+        if let Some(error) = &provider.last_error {
+            println!("  ⚠️  {} failed run(s): {error}", provider.errors);
+        }
+    }
+    if !report.memory.is_empty() {
+        println!("Memory (sqlite)");
+        println!(
+            "  {:>8}  {:>12}  {:>12}  {:>11}  {:>10}",
+            "entries", "store/s", "recall/s", "recall p95", "db size"
+        );
+        for memory in &report.memory {
+            println!(
+                "  {:>8}  {:>12.0}  {:>12.0}  {:>8.2} ms  {:>7} KB",
+                memory.entries,
+                memory.store_ops_per_sec,
+                memory.recall_ops_per_sec,
+                memory.recall.as_ref().map_or(0.0, |s| s.p95_ms),
+                memory.db_bytes / 1024
+            );
+        }
+    }
+    if !report.tools.is_empty() {
+        println!("Tool dispatch");
+        for dispatch in &report.tools {
+            println!(
+                "  {:<8} {:.1} µs per round trip ({} calls, {} tools registered)",
+                dispatch.dispatcher,
+                dispatch.round_trip_us,
+                dispatch.calls_per_response,
+                dispatch.registry_size
+            );
+        }
+    }
+}
+
+/// Per-metric change against a baseline, as a percentage where positive
+/// means better. Metrics missing from either report are skipped.
+pub fn compare(current: &BenchReport, baseline: &BenchReport) -> Vec<(String, f64, f64, f64)> {
+    let previous: std::collections::HashMap<String, f64> = baseline.metrics().into_iter().collect();
+    current
+        .metrics()
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let before = *previous.get(&name)?;
+            if before == 0.0 {
+                return None;
+            }
+            let mut change = (value - before) / before * 100.0;
+            if !higher_is_better(&name) {
+                change = -change;
+            }
+            Some((name, before, value, change))
+        })
+        .collect()
+}
+
+fn print_comparison(path: &Path, rows: &[(String, f64, f64, f64)]) {
+    println!();
+    println!("Compared with {}:", path.display());
+    if rows.is_empty() {
+        println!("  no metrics in common");
+        return;
+    }
+    for (name, before, after, change) in rows {
+        let marker = if *change >= 5.0 {
+            "✅"
+        } else if *change <= -5.0 {
+            "❌"
+        } else {
+            "  "
+        };
+        println!("  {marker} {name:<36} {before:>12.2} → {after:>12.2}  ({change:+.1}%)");
+    }
+}
+
+pub async fn handle_command(
+    config: &Config,
+    options: &BenchOptions,
+    output: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+) -> Result<()> {
+    let previous = match &baseline {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Some(
+                serde_json::from_str::<BenchReport>(&raw)
+                    .with_context(|| format!("{} is not a bench report", path.display()))?,
+            )
+        }
+        None => None,
+    };
+
+    let report = run(config, options).await?;
+    print_report(&report);
+
+    if let (Some(path), Some(previous)) = (&baseline, &previous) {
+        print_comparison(path, &compare(&report, previous));
+    }
+
+    if let Some(path) = output {
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!();
+        println!("Report written to {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_stats_percentiles() {
+        let durations: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_durations(&durations).unwrap();
+        assert_eq!(stats.samples, 100);
+        assert_eq!(stats.min_ms, 1.0);
+        assert_eq!(stats.p50_ms, 50.0);
+        assert_eq!(stats.p95_ms, 95.0);
+        assert_eq!(stats.max_ms, 100.0);
+        assert!(LatencyStats::from_durations(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn memory_bench_reports_throughput() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = dir.path().join("ws");
+        let report = bench_memory_in(&workspace, 20).await.unwrap();
+        assert_eq!(report.entries, 20);
+        assert!(report.store_ops_per_sec > 0.0);
+        assert_eq!(report.recall.unwrap().samples, RECALL_QUERIES);
+        assert!(report.db_bytes > 0);
+    }
+
+    #[tokio::test]
+    async fn both_dispatchers_run_every_call() {
+        let tools = registry();
+        assert_eq!(
+            dispatch_once(&XmlToolDispatcher, &tools, &xml_response()).await,
+            CALLS_PER_RESPONSE
+        );
+        assert_eq!(
+            dispatch_once(&NativeToolDispatcher, &tools, &native_response()).await,
+            CALLS_PER_RESPONSE
+        );
+    }
+
+    #[test]
+    fn compare_signs_changes_by_direction() {
+        let base = BenchReport {
+            version: "0.1.0".into(),
+            timestamp: String::new(),
+            os: "linux".into(),
+            arch: "x86_64".into(),
+            provider: None,
+            memory: vec![MemoryReport {
+                entries: 100,
+                store_ops_per_sec: 1000.0,
+                recall_ops_per_sec: 500.0,
+                recall: None,
+                db_bytes: 0,
+            }],
+            tools: vec![DispatchReport {
+                dispatcher: "xml".into(),
+                calls_per_response: 3,
+                registry_size: 32,
+                round_trip_us: 10.0,
+            }],
+        };
+        let mut current = base.clone();
+        current.memory[0].store_ops_per_sec = 2000.0;
+        current.tools[0].round_trip_us = 20.0;
+
+        let rows = compare(&current, &base);
+        let change = |name: &str| rows.iter().find(|r| r.0 == name).unwrap().3;
+        assert_eq!(change("memory.100.store_ops_per_sec"), 100.0);
+        assert_eq!(change("memory.100.recall_ops_per_sec"), 0.0);
+        assert_eq!(change("tools.xml.round_trip_us"), -100.0);
+
+        let json = serde_json::to_string(&current).unwrap();
+        let parsed: BenchReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, current);
+    }
+}
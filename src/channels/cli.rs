@@ -74,6 +74,7 @@ mod tests {
                 content: "hello".into(),
                 recipient: "user".into(),
                 subject: None,
+                options: Vec::new(),
             })
             .await;
         assert!(result.is_ok());
@@ -87,6 +88,7 @@ mod tests {
                 content: String::new(),
                 recipient: String::new(),
                 subject: None,
+                options: Vec::new(),
             })
             .await;
         assert!(result.is_ok());
@@ -299,7 +299,7 @@ impl IrcChannel {
 
 /// Certificate verifier that accepts any certificate (for `verify_tls=false`).
 #[derive(Debug)]
-struct NoVerify;
+pub(crate) struct NoVerify;
 
 impl rustls::client::danger::ServerCertVerifier for NoVerify {
     fn verify_server_cert(
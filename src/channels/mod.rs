@@ -6,12 +6,14 @@ pub mod imessage;
 pub mod irc;
 pub mod lark;
 pub mod matrix;
+pub mod postprocess;
 pub mod qq;
 pub mod signal;
 pub mod slack;
 pub mod telegram;
 pub mod traits;
 pub mod whatsapp;
+pub mod workspaces;
 
 pub use cli::CliChannel;
 pub use dingtalk::DingTalkChannel;
@@ -21,6 +23,7 @@ pub use imessage::IMessageChannel;
 pub use irc::IrcChannel;
 pub use lark::LarkChannel;
 pub use matrix::MatrixChannel;
+pub use postprocess::ResponsePostprocessor;
 pub use qq::QQChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
@@ -29,8 +32,10 @@ pub use traits::{Channel, SendMessage};
 pub use whatsapp::WhatsAppChannel;
 
 use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
+use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::config::Config;
 use crate::identity;
+use crate::mcp::config::McpProfileConfig;
 use crate::memory::{self, Memory};
 use crate::observability::{self, Observer};
 use crate::providers::{self, ChatMessage, Provider};
@@ -43,8 +48,10 @@ use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::PathBuf;
 use std::process::Command;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
 use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tracing::Instrument;
 
 /// Maximum characters per injected workspace file (matches `OpenClaw` default).
 const BOOTSTRAP_MAX_CHARS: usize = 20_000;
@@ -54,6 +61,13 @@ const DEFAULT_CHANNEL_MAX_BACKOFF_SECS: u64 = 60;
 /// Timeout for processing a single channel message (LLM + tools).
 /// 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
 const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 300;
+/// How often the channel server checks MCP servers for changed tool lists.
+const MCP_TOOLS_CHECK_SECS: u64 = 30;
+/// Minimum gap between MCP tool progress messages posted to a channel; the
+/// first is posted only once a turn has run this long.
+const MCP_PROGRESS_POST_SECS: u64 = 20;
+/// How long a question an MCP server asks mid-call waits for the reply.
+const MCP_QUESTION_TIMEOUT_SECS: u64 = 120;
 const CHANNEL_PARALLELISM_PER_CHANNEL: usize = 4;
 const CHANNEL_MIN_IN_FLIGHT_MESSAGES: usize = 8;
 const CHANNEL_MAX_IN_FLIGHT_MESSAGES: usize = 64;
@@ -63,12 +77,72 @@ struct ChannelRuntimeContext {
     channels_by_name: Arc<HashMap<String, Arc<dyn Channel>>>,
     provider: Arc<dyn Provider>,
     memory: Arc<dyn Memory>,
+    memory_loader: Arc<dyn MemoryLoader>,
+    postprocessor: Option<Arc<ResponsePostprocessor>>,
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
     observer: Arc<dyn Observer>,
     system_prompt: Arc<String>,
     model: Arc<String>,
     temperature: f64,
     auto_save_memory: bool,
+    /// `[memory.namespaces]`: fixed memory namespaces by channel or sender
+    memory_namespaces: Arc<HashMap<String, String>>,
+    /// Workspace to record session transcripts in; `None` when
+    /// `sessions.record_transcripts` is off.
+    transcript_workspace: Option<PathBuf>,
+    /// Named workspace contexts (`[workspaces]`), picked per message by
+    /// `router`; both empty inside a named workspace's own context.
+    workspaces: Arc<HashMap<String, Arc<ChannelRuntimeContext>>>,
+    router: Arc<workspaces::WorkspaceRouter>,
+    /// MCP server groups (`[mcp.profiles]`), picked per conversation.
+    mcp_profiles: Arc<HashMap<String, McpProfileConfig>>,
+    /// Registry and prompt of each MCP profile, used in place of
+    /// `tools_registry` and `system_prompt` by conversations with it.
+    profile_tools: Arc<HashMap<String, ProfileTools>>,
+}
+
+/// Tool registry and system prompt of an MCP profile: the context's own,
+/// with only the MCP tools of the profile's servers.
+#[derive(Clone)]
+struct ProfileTools {
+    tools_registry: Arc<Vec<Box<dyn Tool>>>,
+    system_prompt: Arc<String>,
+}
+
+impl ChannelRuntimeContext {
+    /// The context that handles `msg`: its routed workspace, or this one.
+    /// Workspace contexts always see this context's channels, so channels
+    /// added by a config reload can be replied on from every workspace.
+    /// The conversation's MCP profile picks the tools and prompt.
+    fn for_message(self: &Arc<Self>, msg: &traits::ChannelMessage) -> Arc<Self> {
+        let routed = self
+            .router
+            .route(&msg.channel, &msg.sender)
+            .and_then(|name| self.workspaces.get(name));
+        let ctx = match routed {
+            Some(ws) => Arc::new(Self {
+                channels_by_name: Arc::clone(&self.channels_by_name),
+                ..ws.as_ref().clone()
+            }),
+            None => Arc::clone(self),
+        };
+        let profile = crate::mcp::profiles::active(&ctx.mcp_profiles, &msg.channel, &msg.sender);
+        match profile.and_then(|name| ctx.profile_tools.get(&name)) {
+            Some(tools) => Arc::new(Self {
+                tools_registry: Arc::clone(&tools.tools_registry),
+                system_prompt: Arc::clone(&tools.system_prompt),
+                ..ctx.as_ref().clone()
+            }),
+            None => ctx,
+        }
+    }
+}
+
+/// The argument of a `/profile [name]` message, which picks the
+/// conversation's MCP profile instead of starting a turn.
+fn profile_command(content: &str) -> Option<&str> {
+    let rest = content.trim().strip_prefix("/profile")?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some(rest)
 }
 
 fn conversation_memory_key(msg: &traits::ChannelMessage) -> String {
@@ -84,22 +158,6 @@ fn channel_delivery_instructions(channel_name: &str) -> Option<&'static str> {
     }
 }
 
-async fn build_memory_context(mem: &dyn Memory, user_msg: &str) -> String {
-    let mut context = String::new();
-
-    if let Ok(entries) = mem.recall(user_msg, 5, None).await {
-        if !entries.is_empty() {
-            context.push_str("[Memory context]\n");
-            for entry in &entries {
-                let _ = writeln!(context, "- {}: {}", entry.key, entry.content);
-            }
-            context.push('\n');
-        }
-    }
-
-    context
-}
-
 fn spawn_supervised_listener(
     ch: Arc<dyn Channel>,
     tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
@@ -155,6 +213,124 @@ fn log_worker_join_result(result: Result<(), tokio::task::JoinError>) {
     }
 }
 
+/// Post MCP messages raised during a turn (sign-in prompts, resource
+/// updates) to the channel it answers.
+fn mcp_notifier(
+    channel: Option<Arc<dyn Channel>>,
+    reply_target: &str,
+) -> Option<crate::mcp::Notifier> {
+    let channel = channel?;
+    let reply_target = reply_target.to_string();
+    Some(Arc::new(move |text: String| {
+        let channel = Arc::clone(&channel);
+        let reply_target = reply_target.clone();
+        tokio::spawn(async move {
+            if let Err(e) = channel.send(&SendMessage::new(text, &reply_target)).await {
+                tracing::warn!("Failed to post MCP message: {e}");
+            }
+        });
+    }))
+}
+
+/// Post progress of long-running MCP tools to the channel. The typing
+/// indicator already covers short calls, so nothing is posted until the turn
+/// has run for a while, and then at most one status message per interval.
+fn mcp_progress(
+    channel: Option<Arc<dyn Channel>>,
+    reply_target: &str,
+) -> Option<crate::mcp::ProgressReporter> {
+    let channel = channel?;
+    let reply_target = reply_target.to_string();
+    let last_post = Arc::new(std::sync::Mutex::new(Instant::now()));
+    Some(Arc::new(
+        move |tool: &str, progress: &crate::mcp::Progress| {
+            {
+                let mut last_post = last_post
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                if last_post.elapsed() < Duration::from_secs(MCP_PROGRESS_POST_SECS) {
+                    return;
+                }
+                *last_post = Instant::now();
+            }
+            let text = format!("⏳ {tool}: {}", progress.describe());
+            let channel = Arc::clone(&channel);
+            let reply_target = reply_target.clone();
+            tokio::spawn(async move {
+                if let Err(e) = channel.send(&SendMessage::new(text, &reply_target)).await {
+                    tracing::warn!("Failed to post MCP progress: {e}");
+                }
+            });
+        },
+    ))
+}
+
+/// Replies awaited by questions MCP servers asked, by channel and sender.
+/// The next message of that sender answers the question instead of
+/// starting a turn.
+static PENDING_REPLIES: LazyLock<Mutex<PendingReplies>> = LazyLock::new(Mutex::default);
+
+type PendingReplies = HashMap<(String, String), oneshot::Sender<String>>;
+
+/// Ask the sender of `msg` the questions MCP servers put during the turn
+/// and wait for the reply.
+fn mcp_asker(
+    channel: Option<Arc<dyn Channel>>,
+    msg: &traits::ChannelMessage,
+) -> Option<crate::mcp::Asker> {
+    let channel = channel?;
+    let key = (msg.channel.clone(), msg.sender.clone());
+    let reply_target = msg.reply_target.clone();
+    Some(Arc::new(move |question: String, options: Vec<String>| {
+        let (channel, key, reply_target) =
+            (Arc::clone(&channel), key.clone(), reply_target.clone());
+        Box::pin(async move {
+            let (tx, rx) = oneshot::channel();
+            PENDING_REPLIES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key.clone(), tx);
+            let mut text = question;
+            for (i, option) in options.iter().enumerate() {
+                let _ = write!(text, "\n{}. {option}", i + 1);
+            }
+            let message = SendMessage::new(text, &reply_target).with_options(options);
+            let reply = match channel.send(&message).await {
+                Ok(()) => tokio::time::timeout(Duration::from_secs(MCP_QUESTION_TIMEOUT_SECS), rx)
+                    .await
+                    .ok()
+                    .and_then(Result::ok),
+                Err(e) => {
+                    tracing::warn!("Failed to post MCP question: {e}");
+                    None
+                }
+            };
+            PENDING_REPLIES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&key);
+            reply
+        })
+    }))
+}
+
+/// Hand `msg` to the question its sender was asked, if any. Returns the
+/// message back when it starts a new turn.
+fn answer_pending_question(msg: traits::ChannelMessage) -> Option<traits::ChannelMessage> {
+    let key = (msg.channel.clone(), msg.sender.clone());
+    let pending = PENDING_REPLIES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&key);
+    match pending {
+        Some(reply) => match reply.send(msg.content) {
+            Ok(()) => None,
+            Err(content) => Some(traits::ChannelMessage { content, ..msg }),
+        },
+        None => Some(msg),
+    }
+}
+
 async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::ChannelMessage) {
     println!(
         "  💬 [{}] from {}: {}",
@@ -163,7 +339,25 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         truncate_with_ellipsis(&msg.content, 80)
     );
 
-    let memory_context = build_memory_context(ctx.memory.as_ref(), &msg.content).await;
+    if let Some(arg) = profile_command(&msg.content) {
+        let reply =
+            crate::mcp::profiles::command(&ctx.mcp_profiles, &msg.channel, &msg.sender, arg);
+        if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+            if let Err(e) = channel
+                .send(&SendMessage::new(reply, &msg.reply_target))
+                .await
+            {
+                eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+            }
+        }
+        return;
+    }
+
+    let memory_context = ctx
+        .memory_loader
+        .load_context(ctx.memory.as_ref(), &msg.content, &[])
+        .await
+        .unwrap_or_default();
 
     if ctx.auto_save_memory {
         let autosave_key = conversation_memory_key(&msg);
@@ -204,20 +398,43 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         history.push(ChatMessage::system(instructions));
     }
 
+    let session_id = format!("{}_{}", msg.channel, msg.sender);
+    crate::events::emit_in_session(
+        &session_id,
+        crate::events::Event::MessageReceived {
+            channel: msg.channel.clone(),
+            sender: msg.sender.clone(),
+            content: crate::events::clip(&msg.content),
+        },
+    );
+    let mut tool_records = Vec::new();
     let llm_result = tokio::time::timeout(
         Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
-        run_tool_call_loop(
-            ctx.provider.as_ref(),
-            &mut history,
-            ctx.tools_registry.as_ref(),
-            ctx.observer.as_ref(),
-            "channel-runtime",
-            ctx.model.as_str(),
-            ctx.temperature,
-            true, // silent — channels don't write to stdout
-            None,
-            msg.channel.as_str(),
-            None,
+        crate::sessions::scope(
+            session_id.clone(),
+            crate::mcp::scope(
+                crate::mcp::Turn {
+                    notify: mcp_notifier(target_channel.clone(), &msg.reply_target),
+                    progress: mcp_progress(target_channel.clone(), &msg.reply_target),
+                    cancel: None,
+                    ask: mcp_asker(target_channel.clone(), &msg),
+                    channel: Some(msg.channel.clone()),
+                    sender: Some(msg.sender.clone()),
+                },
+                run_tool_call_loop(
+                    ctx.provider.as_ref(),
+                    &mut history,
+                    ctx.tools_registry.as_ref(),
+                    ctx.observer.as_ref(),
+                    "channel-runtime",
+                    ctx.model.as_str(),
+                    ctx.temperature,
+                    true, // silent — channels don't write to stdout
+                    None,
+                    msg.channel.as_str(),
+                    Some(&mut tool_records),
+                ),
+            ),
         ),
     )
     .await;
@@ -230,11 +447,26 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
 
     match llm_result {
         Ok(Ok(response)) => {
+            let response = match ctx.postprocessor.as_ref() {
+                Some(pp) => pp.process(&msg.channel, &response),
+                None => response,
+            };
             println!(
                 "  🤖 Reply ({}ms): {}",
                 started_at.elapsed().as_millis(),
                 truncate_with_ellipsis(&response, 80)
             );
+            if let Some(workspace) = ctx.transcript_workspace.as_deref() {
+                if let Err(e) = crate::sessions::record_turn(
+                    workspace,
+                    &session_id,
+                    &msg.content,
+                    &response,
+                    &tool_records,
+                ) {
+                    tracing::warn!("Failed to record session transcript: {e}");
+                }
+            }
             if let Some(channel) = target_channel.as_ref() {
                 if let Err(e) = channel
                     .send(&SendMessage::new(response, &msg.reply_target))
@@ -263,6 +495,13 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                 "LLM response timed out after {}s",
                 CHANNEL_MESSAGE_TIMEOUT_SECS
             );
+            crate::events::emit_in_session(
+                &session_id,
+                crate::events::Event::Error {
+                    component: format!("channel:{}", msg.channel),
+                    message: timeout_msg.clone(),
+                },
+            );
             eprintln!(
                 "  ❌ {} (elapsed: {}ms)",
                 timeout_msg,
@@ -280,31 +519,101 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     }
 }
 
+/// Dispatch messages to workers. Each message is handled with the runtime
+/// context current when it arrived; config reloads swap the context for
+/// later messages only.
+///
+/// Once `shutdown` triggers, the bus is closed to new messages; messages
+/// already queued and in-flight replies still complete until `drain_timeout`,
+/// after which remaining workers are aborted.
 async fn run_message_dispatch_loop(
     mut rx: tokio::sync::mpsc::Receiver<traits::ChannelMessage>,
-    ctx: Arc<ChannelRuntimeContext>,
+    ctx: tokio::sync::watch::Receiver<Arc<ChannelRuntimeContext>>,
     max_in_flight_messages: usize,
+    shutdown: &crate::shutdown::Shutdown,
+    drain_timeout: Duration,
 ) {
     let semaphore = Arc::new(tokio::sync::Semaphore::new(max_in_flight_messages));
     let mut workers = tokio::task::JoinSet::new();
+    let mut drain_deadline = None;
+
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => msg,
+            () = shutdown.triggered(), if drain_deadline.is_none() => {
+                tracing::info!(
+                    in_flight = workers.len(),
+                    "Channels draining; no longer accepting messages"
+                );
+                drain_deadline = Some(tokio::time::Instant::now() + drain_timeout);
+                rx.close();
+                continue;
+            }
+        };
+        let Some(msg) = msg else { break };
+        // Answers to MCP questions go to the waiting turn, which may hold
+        // the last permit.
+        let Some(msg) = answer_pending_question(msg) else {
+            continue;
+        };
 
-    while let Some(msg) = rx.recv().await {
-        let permit = match Arc::clone(&semaphore).acquire_owned().await {
-            Ok(permit) => permit,
-            Err(_) => break,
+        let acquire = Arc::clone(&semaphore).acquire_owned();
+        let permit = match drain_deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, acquire).await {
+                Ok(permit) => permit,
+                Err(_) => break,
+            },
+            None => acquire.await,
         };
+        let Ok(permit) = permit else { break };
 
-        let worker_ctx = Arc::clone(&ctx);
-        workers.spawn(async move {
-            let _permit = permit;
-            process_channel_message(worker_ctx, msg).await;
-        });
+        let worker_ctx = ctx.borrow().for_message(&msg);
+        let namespace = crate::memory::namespace::for_sender(
+            &worker_ctx.memory_namespaces,
+            &msg.channel,
+            &msg.sender,
+        );
+        let span = tracing::info_span!(
+            "channel_message",
+            channel = %msg.channel,
+            session_id = %format!("{}_{}", msg.channel, msg.sender),
+            request_id = %msg.id,
+        );
+        workers.spawn(
+            async move {
+                let _permit = permit;
+                crate::memory::namespace::scope(
+                    namespace,
+                    process_channel_message(worker_ctx, msg),
+                )
+                .await;
+            }
+            .instrument(span),
+        );
 
         while let Some(result) = workers.try_join_next() {
             log_worker_join_result(result);
         }
     }
 
+    let Some(deadline) = drain_deadline else {
+        join_workers(&mut workers).await;
+        return;
+    };
+    if tokio::time::timeout_at(deadline, join_workers(&mut workers))
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            abandoned = workers.len(),
+            "Channel drain deadline ({}s) reached; abandoning in-flight messages",
+            drain_timeout.as_secs()
+        );
+        workers.shutdown().await;
+    }
+}
+
+async fn join_workers(workers: &mut tokio::task::JoinSet<()>) {
     while let Some(result) = workers.join_next().await {
         log_worker_join_result(result);
     }
@@ -773,8 +1082,9 @@ fn classify_health_result(
     }
 }
 
-/// Run health checks for configured channels.
-pub async fn doctor_channels(config: Config) -> Result<()> {
+/// Build every configured real-time channel (not started), including plugin
+/// channels, paired with its display name.
+pub(crate) fn configured_channels(config: &Config) -> Vec<(String, Arc<dyn Channel>)> {
     let mut channels: Vec<(&'static str, Arc<dyn Channel>)> = Vec::new();
 
     if let Some(ref tg) = config.channels_config.telegram {
@@ -904,6 +1214,16 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
         ));
     }
 
+    channels
+        .into_iter()
+        .map(|(name, channel)| (name.to_string(), channel))
+        .chain(crate::plugins::channels(config))
+        .collect()
+}
+
+pub async fn doctor_channels(config: Config) -> Result<()> {
+    let channels = configured_channels(&config);
+
     if channels.is_empty() {
         println!("No real-time channels configured. Run `zeroclaw onboard` first.");
         return Ok(());
@@ -952,11 +1272,12 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .default_provider
         .clone()
         .unwrap_or_else(|| "openrouter".into());
-    let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
+    let provider: Arc<dyn Provider> = Arc::from(providers::create_racing_provider(
         &provider_name,
         config.api_key.as_deref(),
         config.api_url.as_deref(),
         &config.reliability,
+        &config.racing,
     )?);
 
     // Warm up the provider connection pool (TLS handshake, DNS, HTTP/2 setup)
@@ -983,6 +1304,201 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
+    let (mcp_manager, mcp_tools) = crate::mcp::McpManager::create_mcp_tools(&config.mcp).await?;
+    let mcp_manager = Arc::new(tokio::sync::Mutex::new(mcp_manager));
+    let mcp_tools: Vec<Arc<dyn Tool>> = crate::security::tool_policy::enforce(&config, mcp_tools)
+        .into_iter()
+        .map(Arc::from)
+        .collect();
+
+    let skills = crate::skills::load_skills(&config.workspace_dir);
+    let (tools_registry, system_prompt) = build_tools_and_prompt(
+        &config, &security, &runtime, &mem, &model, &skills, &mcp_tools,
+    );
+    let profile_tools = build_profile_tools(
+        &config, &security, &runtime, &mem, &model, &skills, &mcp_tools,
+    );
+
+    if !skills.is_empty() {
+        println!(
+            "  🧩 Skills:   {}",
+            skills
+                .iter()
+                .map(|s| s.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    // Collect active channels
+    let channels: Vec<Arc<dyn Channel>> = configured_channels(&config)
+        .into_iter()
+        .map(|(_, ch)| ch)
+        .collect();
+
+    if channels.is_empty() {
+        println!("No channels configured. Run `zeroclaw onboard` to set up channels.");
+        return Ok(());
+    }
+
+    println!("🦀 ZeroClaw Channel Server");
+    println!("  🤖 Model:    {model}");
+    println!(
+        "  🧠 Memory:   {} (auto-save: {})",
+        config.memory.backend,
+        if config.memory.auto_save { "on" } else { "off" }
+    );
+    println!(
+        "  📡 Channels: {}",
+        channels
+            .iter()
+            .map(|c| c.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    println!();
+    println!("  Listening for messages... (Ctrl+C to stop)");
+    println!();
+
+    crate::health::mark_component_ok("channels");
+
+    let initial_backoff_secs = config
+        .reliability
+        .channel_initial_backoff_secs
+        .max(DEFAULT_CHANNEL_INITIAL_BACKOFF_SECS);
+    let max_backoff_secs = config
+        .reliability
+        .channel_max_backoff_secs
+        .max(DEFAULT_CHANNEL_MAX_BACKOFF_SECS);
+
+    // Single message bus — all channels send messages here
+    let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
+
+    // Spawn a listener for each channel
+    let mut handles = Vec::new();
+    for ch in &channels {
+        handles.push(spawn_supervised_listener(
+            ch.clone(),
+            tx.clone(),
+            initial_backoff_secs,
+            max_backoff_secs,
+        ));
+    }
+
+    let channels_by_name = Arc::new(
+        channels
+            .iter()
+            .map(|ch| (ch.name().to_string(), Arc::clone(ch)))
+            .collect::<HashMap<_, _>>(),
+    );
+    let max_in_flight_messages = compute_max_in_flight_messages(channels.len());
+
+    println!("  🚦 In-flight message limit: {max_in_flight_messages}");
+
+    let mut runtime_ctx = ChannelRuntimeContext {
+        channels_by_name,
+        provider: Arc::clone(&provider),
+        memory: Arc::clone(&mem),
+        memory_loader: Arc::new(DefaultMemoryLoader::from_config(
+            &config.agent.memory_context,
+        )),
+        postprocessor: ResponsePostprocessor::from_config(&config.postprocess)?.map(Arc::new),
+        tools_registry,
+        observer,
+        system_prompt: Arc::new(system_prompt),
+        model: Arc::new(model.clone()),
+        temperature,
+        auto_save_memory: config.memory.auto_save,
+        memory_namespaces: Arc::new(config.memory.namespaces.clone()),
+        transcript_workspace: config
+            .sessions
+            .record_transcripts
+            .then(|| config.workspace_dir.clone()),
+        workspaces: Arc::new(HashMap::new()),
+        router: Arc::new(workspaces::WorkspaceRouter::default()),
+        mcp_profiles: Arc::new(config.mcp.profiles.clone()),
+        profile_tools: Arc::new(profile_tools),
+    };
+    if !config.workspaces.is_empty() {
+        runtime_ctx.router = Arc::new(workspaces::WorkspaceRouter::from_config(&config)?);
+        runtime_ctx.workspaces = Arc::new(build_workspace_contexts(
+            &config,
+            &runtime_ctx,
+            &runtime,
+            &mcp_tools,
+        )?);
+        let mut names: Vec<&String> = config.workspaces.keys().collect();
+        names.sort();
+        for name in names {
+            println!(
+                "  🗂️  Workspace {name}: {} (routes: {})",
+                workspaces::workspace_dir(&config, name).display(),
+                config.workspaces[name].routes.join(", ")
+            );
+        }
+    }
+    let (ctx_tx, ctx_rx) = tokio::sync::watch::channel(Arc::new(runtime_ctx));
+
+    let reload_task = (config.reload.enabled || config.mcp.enabled).then(|| {
+        let reloader = ChannelReloader {
+            runtime,
+            security,
+            mcp: Arc::clone(&mcp_manager),
+            mcp_tools,
+            listener_tx: tx.clone(),
+            initial_backoff_secs,
+            max_backoff_secs,
+        };
+        tokio::spawn(reloader.run(config.clone(), ctx_tx))
+    });
+    drop(tx); // Drop our copy so rx closes when all channels stop
+
+    crate::shutdown::install_signal_handler();
+    let shutdown = crate::shutdown::global();
+    run_message_dispatch_loop(
+        rx,
+        ctx_rx.clone(),
+        max_in_flight_messages,
+        shutdown,
+        Duration::from_secs(config.shutdown.drain_timeout_secs),
+    )
+    .await;
+
+    if let Some(task) = reload_task {
+        task.abort();
+    }
+
+    if shutdown.is_triggered() {
+        // Listeners may be parked in a long poll; stop them rather than wait.
+        for h in &handles {
+            h.abort();
+        }
+        mcp_manager.lock().await.shutdown().await;
+        ctx_rx.borrow().observer.flush();
+        crate::health::mark_component_error("channels", "shut down");
+        println!("  👋 Channel server stopped");
+    }
+
+    // Wait for all channel tasks
+    for h in handles {
+        let _ = h.await;
+    }
+
+    Ok(())
+}
+
+/// Build the tool registry and system prompt for the channel server.
+/// `shared_tools` (connected MCP tools) are added as-is so a config reload
+/// can rebuild everything else without reconnecting them.
+fn build_tools_and_prompt(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn runtime::RuntimeAdapter>,
+    mem: &Arc<dyn Memory>,
+    model: &str,
+    skills: &[crate::skills::Skill],
+    shared_tools: &[Arc<dyn Tool>],
+) -> (Arc<Vec<Box<dyn Tool>>>, String) {
     let (composio_key, composio_entity_id) = if config.composio.enabled {
         (
             config.composio.api_key.as_deref(),
@@ -993,11 +1509,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
-        &security,
-        runtime,
-        Arc::clone(&mem),
+        security,
+        Arc::clone(runtime),
+        Arc::clone(mem),
         composio_key,
         composio_entity_id,
         &config.browser,
@@ -1005,10 +1521,13 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &workspace,
         &config.agents,
         config.api_key.as_deref(),
-        &config,
-    ));
-
-    let skills = crate::skills::load_skills(&workspace);
+        config,
+    );
+    tools_registry.extend(
+        shared_tools
+            .iter()
+            .map(|tool| Box::new(Arc::clone(tool)) as Box<dyn Tool>),
+    );
 
     // Collect tool descriptions for the prompt
     let mut tool_descs: Vec<(&str, &str)> = vec![
@@ -1030,7 +1549,19 @@ pub async fn start_channels(config: Config) -> Result<()> {
         ),
         (
             "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
+            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context, or you need the exact words of a past conversation (use conversation_search).",
+        ),
+        (
+            "memory_list",
+            "List stored memories page by page, newest first. Use when: reviewing or cleaning up what is remembered. Don't use when: looking for something specific (use memory_recall).",
+        ),
+        (
+            "memory_tags",
+            "List the tags on stored memories. Use when: choosing tags for memory_store or a tag filter for memory_recall.",
+        ),
+        (
+            "memory_update",
+            "Replace the content of a stored memory by key or id. Use when: a remembered fact is wrong or outdated. Don't use when: adding a new fact (use memory_store).",
         ),
         (
             "memory_forget",
@@ -1050,6 +1581,18 @@ pub async fn start_channels(config: Config) -> Result<()> {
             "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run (optionally with connected_account_id), 'connect' to OAuth.",
         ));
     }
+    tool_descs.push((
+        "session_transcript",
+        "Get this conversation's earlier turns with tool calls (secrets redacted). Use when: the user asks for a summary, recap, or export of the conversation.",
+    ));
+    tool_descs.push((
+        "conversation_search",
+        "Search recorded conversation turns by words, date range and role. Use when: the user asks what exactly was said, or when (\"what did I ask on Tuesday?\"). Don't use when: looking up a known fact or preference (use memory_recall).",
+    ));
+    tool_descs.push((
+        "docs_search",
+        "Search the user's ingested notes, docs and code (zeroclaw ingest) and cite the file and line. Use when: the question is about their documents or projects. Don't use when: recalling chat-derived facts (use memory_recall).",
+    ));
     tool_descs.push((
         "schedule",
         "Manage scheduled tasks (create/list/get/cancel/pause/resume). Supports recurring cron and one-shot delays.",
@@ -1072,205 +1615,344 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     let mut system_prompt = build_system_prompt(
         &workspace,
-        &model,
+        model,
         &tool_descs,
-        &skills,
+        skills,
         Some(&config.identity),
         bootstrap_max_chars,
     );
-    system_prompt.push_str(&build_tool_instructions(tools_registry.as_ref()));
-
-    if !skills.is_empty() {
-        println!(
-            "  🧩 Skills:   {}",
-            skills
-                .iter()
-                .map(|s| s.name.as_str())
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
-    }
-
-    // Collect active channels
-    let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
-
-    if let Some(ref tg) = config.channels_config.telegram {
-        channels.push(Arc::new(TelegramChannel::new(
-            tg.bot_token.clone(),
-            tg.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref dc) = config.channels_config.discord {
-        channels.push(Arc::new(DiscordChannel::new(
-            dc.bot_token.clone(),
-            dc.guild_id.clone(),
-            dc.allowed_users.clone(),
-            dc.listen_to_bots,
-            dc.mention_only,
-        )));
-    }
-
-    if let Some(ref sl) = config.channels_config.slack {
-        channels.push(Arc::new(SlackChannel::new(
-            sl.bot_token.clone(),
-            sl.channel_id.clone(),
-            sl.allowed_users.clone(),
-        )));
-    }
+    system_prompt.push_str(&build_tool_instructions(&tools_registry));
 
-    if let Some(ref im) = config.channels_config.imessage {
-        channels.push(Arc::new(IMessageChannel::new(im.allowed_contacts.clone())));
-    }
-
-    if let Some(ref mx) = config.channels_config.matrix {
-        channels.push(Arc::new(MatrixChannel::new(
-            mx.homeserver.clone(),
-            mx.access_token.clone(),
-            mx.room_id.clone(),
-            mx.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref sig) = config.channels_config.signal {
-        channels.push(Arc::new(SignalChannel::new(
-            sig.http_url.clone(),
-            sig.account.clone(),
-            sig.group_id.clone(),
-            sig.allowed_from.clone(),
-            sig.ignore_attachments,
-            sig.ignore_stories,
-        )));
-    }
-
-    if let Some(ref wa) = config.channels_config.whatsapp {
-        channels.push(Arc::new(WhatsAppChannel::new(
-            wa.access_token.clone(),
-            wa.phone_number_id.clone(),
-            wa.verify_token.clone(),
-            wa.allowed_numbers.clone(),
-        )));
-    }
+    (Arc::new(tools_registry), system_prompt)
+}
 
-    if let Some(ref email_cfg) = config.channels_config.email {
-        channels.push(Arc::new(EmailChannel::new(email_cfg.clone())));
-    }
+/// Registry and prompt of each `[mcp.profiles.<name>]`: those of
+/// [`build_tools_and_prompt`] with only the MCP tools of the profile's
+/// servers.
+fn build_profile_tools(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn runtime::RuntimeAdapter>,
+    mem: &Arc<dyn Memory>,
+    model: &str,
+    skills: &[crate::skills::Skill],
+    mcp_tools: &[Arc<dyn Tool>],
+) -> HashMap<String, ProfileTools> {
+    let profiles = &config.mcp.profiles;
+    profiles
+        .keys()
+        .map(|name| {
+            let profile_mcp_tools: Vec<Arc<dyn Tool>> = mcp_tools
+                .iter()
+                .filter(|tool| {
+                    let server = crate::mcp::names::origin(tool.name())
+                        .map(|(server, _)| server)
+                        .unwrap_or_default();
+                    crate::mcp::profiles::allows(profiles, Some(name), &server)
+                })
+                .cloned()
+                .collect();
+            let (tools_registry, system_prompt) = build_tools_and_prompt(
+                config,
+                security,
+                runtime,
+                mem,
+                model,
+                skills,
+                &profile_mcp_tools,
+            );
+            let tools = ProfileTools {
+                tools_registry,
+                system_prompt: Arc::new(system_prompt),
+            };
+            (name.clone(), tools)
+        })
+        .collect()
+}
 
-    if let Some(ref irc) = config.channels_config.irc {
-        channels.push(Arc::new(IrcChannel::new(irc::IrcChannelConfig {
-            server: irc.server.clone(),
-            port: irc.port,
-            nickname: irc.nickname.clone(),
-            username: irc.username.clone(),
-            channels: irc.channels.clone(),
-            allowed_users: irc.allowed_users.clone(),
-            server_password: irc.server_password.clone(),
-            nickserv_password: irc.nickserv_password.clone(),
-            sasl_password: irc.sasl_password.clone(),
-            verify_tls: irc.verify_tls.unwrap_or(true),
-        })));
-    }
+/// Build one runtime context per `[workspaces.<name>]`, each with its own
+/// memory, security policy, tools and prompt. Provider, observer, channels
+/// and reply post-processing are shared with `main`.
+fn build_workspace_contexts(
+    config: &Config,
+    main: &ChannelRuntimeContext,
+    runtime: &Arc<dyn runtime::RuntimeAdapter>,
+    mcp_tools: &[Arc<dyn Tool>],
+) -> Result<HashMap<String, Arc<ChannelRuntimeContext>>> {
+    let mut contexts = HashMap::new();
+    for name in config.workspaces.keys() {
+        let ws_config = workspaces::workspace_config(config, name)?;
+        let workspace = &ws_config.workspace_dir;
+        std::fs::create_dir_all(workspace).with_context(|| {
+            format!(
+                "Failed to create workspace {name} at {}",
+                workspace.display()
+            )
+        })?;
+
+        let security = Arc::new(SecurityPolicy::from_config(&ws_config.autonomy, workspace));
+        let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+            &ws_config.memory,
+            workspace,
+            ws_config.api_key.as_deref(),
+        )?);
+        let ws_mcp_tools: Vec<Arc<dyn Tool>> = mcp_tools
+            .iter()
+            .filter(|tool| {
+                let server = crate::mcp::names::origin(tool.name())
+                    .map(|(server, _)| server)
+                    .unwrap_or_default();
+                workspaces::allows_mcp_server(config, name, &server)
+            })
+            .cloned()
+            .collect();
+        let skills = crate::skills::load_skills(workspace);
+        let (tools_registry, system_prompt) = build_tools_and_prompt(
+            &ws_config,
+            &security,
+            runtime,
+            &mem,
+            &main.model,
+            &skills,
+            &ws_mcp_tools,
+        );
+        let profile_tools = build_profile_tools(
+            &ws_config,
+            &security,
+            runtime,
+            &mem,
+            &main.model,
+            &skills,
+            &ws_mcp_tools,
+        );
 
-    if let Some(ref lk) = config.channels_config.lark {
-        channels.push(Arc::new(LarkChannel::from_config(lk)));
+        let ctx = ChannelRuntimeContext {
+            channels_by_name: Arc::clone(&main.channels_by_name),
+            provider: Arc::clone(&main.provider),
+            memory: mem,
+            memory_loader: Arc::new(DefaultMemoryLoader::from_config(
+                &ws_config.agent.memory_context,
+            )),
+            postprocessor: main.postprocessor.clone(),
+            tools_registry,
+            observer: Arc::clone(&main.observer),
+            system_prompt: Arc::new(system_prompt),
+            model: Arc::clone(&main.model),
+            temperature: ws_config.default_temperature,
+            auto_save_memory: ws_config.memory.auto_save,
+            memory_namespaces: Arc::new(ws_config.memory.namespaces.clone()),
+            transcript_workspace: ws_config
+                .sessions
+                .record_transcripts
+                .then(|| workspace.clone()),
+            workspaces: Arc::new(HashMap::new()),
+            router: Arc::new(workspaces::WorkspaceRouter::default()),
+            mcp_profiles: Arc::new(ws_config.mcp.profiles.clone()),
+            profile_tools: Arc::new(profile_tools),
+        };
+        contexts.insert(name.clone(), Arc::new(ctx));
     }
+    Ok(contexts)
+}
 
-    if let Some(ref dt) = config.channels_config.dingtalk {
-        channels.push(Arc::new(DingTalkChannel::new(
-            dt.client_id.clone(),
-            dt.client_secret.clone(),
-            dt.allowed_users.clone(),
-        )));
-    }
+/// Applies config reloads to a running channel server: rebuilds the prompt,
+/// tools, and security policy, connects newly added MCP servers, and starts
+/// newly added channels. Listeners that are already running are left alone,
+/// so in-flight conversations are not interrupted.
+struct ChannelReloader {
+    runtime: Arc<dyn runtime::RuntimeAdapter>,
+    security: Arc<SecurityPolicy>,
+    mcp: Arc<tokio::sync::Mutex<crate::mcp::McpManager>>,
+    mcp_tools: Vec<Arc<dyn Tool>>,
+    listener_tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+}
 
-    if let Some(ref qq) = config.channels_config.qq {
-        channels.push(Arc::new(QQChannel::new(
-            qq.app_id.clone(),
-            qq.app_secret.clone(),
-            qq.allowed_users.clone(),
-        )));
+impl ChannelReloader {
+    async fn run(
+        mut self,
+        config: Config,
+        ctx_tx: tokio::sync::watch::Sender<Arc<ChannelRuntimeContext>>,
+    ) {
+        let mut current = Arc::new(config.clone());
+        let (mut updates, watcher) = if config.reload.enabled {
+            let (updates, watcher) = crate::config::reload::spawn_watcher(config);
+            (Some(updates), Some(watcher))
+        } else {
+            (None, None)
+        };
+        let mut mcp_check = tokio::time::interval(Duration::from_secs(MCP_TOOLS_CHECK_SECS));
+        mcp_check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                update = async {
+                    match updates.as_mut() {
+                        Some(updates) => updates.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    let Some(update) = update else { break };
+                    current = Arc::clone(&update.config);
+                    match self.apply(&update, &ctx_tx).await {
+                        Ok(()) => tracing::info!("Config reload applied to channel server"),
+                        Err(e) => tracing::warn!("Config reload could not be applied: {e:#}"),
+                    }
+                }
+                _ = mcp_check.tick() => {
+                    self.refresh_mcp_tools(&current, &ctx_tx).await;
+                    self.remember_resource_updates(&ctx_tx).await;
+                }
+            }
+        }
+        if let Some(watcher) = watcher {
+            watcher.abort();
+        }
     }
 
-    if channels.is_empty() {
-        println!("No channels configured. Run `zeroclaw onboard` to set up channels.");
-        return Ok(());
+    /// Pick up tools MCP servers added or removed at runtime and rebuild the
+    /// registry with the current config.
+    async fn refresh_mcp_tools(
+        &mut self,
+        config: &Arc<Config>,
+        ctx_tx: &tokio::sync::watch::Sender<Arc<ChannelRuntimeContext>>,
+    ) {
+        let changes = self.mcp.lock().await.refresh_changed_tools().await;
+        if changes.is_empty() {
+            return;
+        }
+        self.mcp_tools
+            .retain(|tool| !changes.removed.iter().any(|name| name == tool.name()));
+        self.mcp_tools.extend(
+            crate::security::tool_policy::enforce(config, changes.added)
+                .into_iter()
+                .map(Arc::<dyn Tool>::from),
+        );
+        let update = crate::config::reload::ConfigUpdate {
+            config: Arc::clone(config),
+            changes: Vec::new(),
+        };
+        if let Err(e) = self.apply(&update, ctx_tx).await {
+            tracing::warn!("MCP tool changes could not be applied: {e:#}");
+        }
     }
 
-    println!("🦀 ZeroClaw Channel Server");
-    println!("  🤖 Model:    {model}");
-    println!(
-        "  🧠 Memory:   {} (auto-save: {})",
-        config.memory.backend,
-        if config.memory.auto_save { "on" } else { "off" }
-    );
-    println!(
-        "  📡 Channels: {}",
-        channels
-            .iter()
-            .map(|c| c.name())
-            .collect::<Vec<_>>()
-            .join(", ")
-    );
-    println!();
-    println!("  Listening for messages... (Ctrl+C to stop)");
-    println!();
-
-    crate::health::mark_component_ok("channels");
-
-    let initial_backoff_secs = config
-        .reliability
-        .channel_initial_backoff_secs
-        .max(DEFAULT_CHANNEL_INITIAL_BACKOFF_SECS);
-    let max_backoff_secs = config
-        .reliability
-        .channel_max_backoff_secs
-        .max(DEFAULT_CHANNEL_MAX_BACKOFF_SECS);
-
-    // Single message bus — all channels send messages here
-    let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
-
-    // Spawn a listener for each channel
-    let mut handles = Vec::new();
-    for ch in &channels {
-        handles.push(spawn_supervised_listener(
-            ch.clone(),
-            tx.clone(),
-            initial_backoff_secs,
-            max_backoff_secs,
-        ));
+    /// Store a memory entry per updated MCP resource subscription, so the
+    /// next turn's memory context mentions the change. The conversation that
+    /// subscribed was already told when the update arrived.
+    async fn remember_resource_updates(
+        &self,
+        ctx_tx: &tokio::sync::watch::Sender<Arc<ChannelRuntimeContext>>,
+    ) {
+        let updates = self.mcp.lock().await.take_resource_updates().await;
+        if updates.is_empty() {
+            return;
+        }
+        let memory = Arc::clone(&ctx_tx.borrow().memory);
+        for update in &updates {
+            let key = format!("mcp_resource_{}_{}", update.0, update.1);
+            let note = crate::mcp::resource_update_note(std::slice::from_ref(update));
+            if let Err(e) = memory
+                .store(
+                    &key,
+                    &note,
+                    crate::memory::MemoryCategory::Conversation,
+                    None,
+                )
+                .await
+            {
+                tracing::warn!("Failed to remember MCP resource update: {e}");
+            }
+        }
     }
-    drop(tx); // Drop our copy so rx closes when all channels stop
-
-    let channels_by_name = Arc::new(
-        channels
-            .iter()
-            .map(|ch| (ch.name().to_string(), Arc::clone(ch)))
-            .collect::<HashMap<_, _>>(),
-    );
-    let max_in_flight_messages = compute_max_in_flight_messages(channels.len());
 
-    println!("  🚦 In-flight message limit: {max_in_flight_messages}");
+    async fn apply(
+        &mut self,
+        update: &crate::config::reload::ConfigUpdate,
+        ctx_tx: &tokio::sync::watch::Sender<Arc<ChannelRuntimeContext>>,
+    ) -> Result<()> {
+        let config = update.config.as_ref();
+        let prev = Arc::clone(&ctx_tx.borrow());
+
+        let postprocessor = ResponsePostprocessor::from_config(&config.postprocess)?.map(Arc::new);
+
+        // A no-op unless the workspace or `[mcp] roots` changed.
+        crate::mcp::roots::update(config);
+        if update.touches("mcp") {
+            let changes = self.mcp.lock().await.apply_config(&config.mcp).await;
+            self.mcp_tools
+                .retain(|tool| !changes.removed.iter().any(|name| name == tool.name()));
+            self.mcp_tools
+                .extend(changes.added.into_iter().map(Arc::<dyn Tool>::from));
+        }
 
-    let runtime_ctx = Arc::new(ChannelRuntimeContext {
-        channels_by_name,
-        provider: Arc::clone(&provider),
-        memory: Arc::clone(&mem),
-        tools_registry: Arc::clone(&tools_registry),
-        observer,
-        system_prompt: Arc::new(system_prompt),
-        model: Arc::new(model.clone()),
-        temperature,
-        auto_save_memory: config.memory.auto_save,
-    });
+        // Carry the rate-limit window over so a reload does not reset it.
+        let mut security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        security.tracker = self.security.tracker.clone();
+        self.security = Arc::new(security);
+
+        let skills = crate::skills::load_skills(&config.workspace_dir);
+        let (tools_registry, system_prompt) = build_tools_and_prompt(
+            config,
+            &self.security,
+            &self.runtime,
+            &prev.memory,
+            &prev.model,
+            &skills,
+            &self.mcp_tools,
+        );
+        let profile_tools = build_profile_tools(
+            config,
+            &self.security,
+            &self.runtime,
+            &prev.memory,
+            &prev.model,
+            &skills,
+            &self.mcp_tools,
+        );
 
-    run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
+        let mut channels_by_name = prev.channels_by_name.as_ref().clone();
+        for (_, ch) in configured_channels(config) {
+            if channels_by_name.contains_key(ch.name()) {
+                continue;
+            }
+            tracing::info!("Starting channel added by config reload: {}", ch.name());
+            spawn_supervised_listener(
+                Arc::clone(&ch),
+                self.listener_tx.clone(),
+                self.initial_backoff_secs,
+                self.max_backoff_secs,
+            );
+            channels_by_name.insert(ch.name().to_string(), ch);
+        }
 
-    // Wait for all channel tasks
-    for h in handles {
-        let _ = h.await;
+        ctx_tx.send_replace(Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider: Arc::clone(&prev.provider),
+            memory: Arc::clone(&prev.memory),
+            memory_loader: Arc::new(DefaultMemoryLoader::from_config(
+                &config.agent.memory_context,
+            )),
+            postprocessor,
+            tools_registry,
+            observer: Arc::clone(&prev.observer),
+            system_prompt: Arc::new(system_prompt),
+            model: Arc::clone(&prev.model),
+            temperature: config.default_temperature,
+            auto_save_memory: config.memory.auto_save,
+            memory_namespaces: Arc::new(config.memory.namespaces.clone()),
+            transcript_workspace: config
+                .sessions
+                .record_transcripts
+                .then(|| config.workspace_dir.clone()),
+            // Named workspaces are built once at startup.
+            workspaces: Arc::clone(&prev.workspaces),
+            router: Arc::clone(&prev.router),
+            mcp_profiles: Arc::new(config.mcp.profiles.clone()),
+            profile_tools: Arc::new(profile_tools),
+        }));
+        Ok(())
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -1333,6 +2015,34 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn mcp_questions_are_answered_by_the_senders_next_message() {
+        let channel = Arc::new(RecordingChannel::default());
+        let message = |sender: &str, content: &str| traits::ChannelMessage {
+            id: "q".to_string(),
+            sender: sender.to_string(),
+            reply_target: "chat-7".to_string(),
+            content: content.to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+        };
+        let target: Arc<dyn Channel> = channel.clone();
+        let ask = mcp_asker(Some(target), &message("carol", "deploy it")).unwrap();
+        let question = tokio::spawn(ask("Which one?".into(), vec!["web".into(), "api".into()]));
+        while channel.sent_messages.lock().await.is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(
+            *channel.sent_messages.lock().await,
+            ["chat-7:Which one?\n1. web\n2. api"]
+        );
+
+        assert!(answer_pending_question(message("dave", "hi")).is_some());
+        assert!(answer_pending_question(message("carol", "2")).is_none());
+        assert_eq!(question.await.unwrap().as_deref(), Some("2"));
+        assert!(answer_pending_question(message("carol", "thanks")).is_some());
+    }
+
     struct SlowProvider {
         delay: Duration,
     }
@@ -1418,6 +2128,7 @@ mod tests {
                     success: false,
                     output: String::new(),
                     error: Some("unexpected symbol".to_string()),
+                    attachments: Vec::new(),
                 });
             }
 
@@ -1425,6 +2136,7 @@ mod tests {
                 success: true,
                 output: r#"{"symbol":"BTC","price_usd":65000}"#.to_string(),
                 error: None,
+                attachments: Vec::new(),
             })
         }
     }
@@ -1441,12 +2153,20 @@ mod tests {
             channels_by_name: Arc::new(channels_by_name),
             provider: Arc::new(ToolCallingProvider),
             memory: Arc::new(NoopMemory),
+            memory_loader: Arc::new(DefaultMemoryLoader::default()),
+            postprocessor: None,
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_namespaces: Arc::new(HashMap::new()),
+            transcript_workspace: None,
+            workspaces: Arc::new(HashMap::new()),
+            router: Arc::new(workspaces::WorkspaceRouter::default()),
+            mcp_profiles: Arc::new(HashMap::new()),
+            profile_tools: Arc::new(HashMap::new()),
         });
 
         process_channel_message(
@@ -1470,6 +2190,65 @@ mod tests {
         assert!(!sent_messages[0].contains("mock_price"));
     }
 
+    #[tokio::test]
+    async fn profile_command_switches_the_conversations_tools() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+        let research = ProfileTools {
+            tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
+            system_prompt: Arc::new("research-prompt".to_string()),
+        };
+        let ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(HashMap::from([(channel.name().to_string(), channel)])),
+            provider: Arc::new(ToolCallingProvider),
+            memory: Arc::new(NoopMemory),
+            memory_loader: Arc::new(DefaultMemoryLoader::default()),
+            postprocessor: None,
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            memory_namespaces: Arc::new(HashMap::new()),
+            transcript_workspace: None,
+            workspaces: Arc::new(HashMap::new()),
+            router: Arc::new(workspaces::WorkspaceRouter::default()),
+            mcp_profiles: Arc::new(HashMap::from([(
+                "research".to_string(),
+                McpProfileConfig {
+                    servers: vec!["web".into()],
+                    channels: vec!["test-channel".into()],
+                },
+            )])),
+            profile_tools: Arc::new(HashMap::from([("research".to_string(), research)])),
+        });
+        let message = |content: &str| traits::ChannelMessage {
+            id: "p".to_string(),
+            sender: "dana".to_string(),
+            reply_target: "chat-9".to_string(),
+            content: content.to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+        };
+
+        let worker = ctx.for_message(&message("hi"));
+        assert_eq!(worker.system_prompt.as_str(), "research-prompt");
+        assert_eq!(worker.tools_registry.len(), 1);
+
+        process_channel_message(worker, message("/profile all")).await;
+        assert_eq!(
+            channel_impl.sent_messages.lock().await.as_slice(),
+            ["chat-9:Every MCP server's tools are on."]
+        );
+        let worker = ctx.for_message(&message("hi"));
+        assert_eq!(worker.system_prompt.as_str(), "test-system-prompt");
+        assert!(worker.tools_registry.is_empty());
+
+        assert_eq!(profile_command("/profile research"), Some(" research"));
+        assert_eq!(profile_command("/profiles"), None);
+    }
+
     struct NoopMemory;
 
     #[async_trait::async_trait]
@@ -1536,12 +2315,20 @@ mod tests {
                 delay: Duration::from_millis(250),
             }),
             memory: Arc::new(NoopMemory),
+            memory_loader: Arc::new(DefaultMemoryLoader::default()),
+            postprocessor: None,
             tools_registry: Arc::new(vec![]),
             observer: Arc::new(NoopObserver),
             system_prompt: Arc::new("test-system-prompt".to_string()),
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            memory_namespaces: Arc::new(HashMap::new()),
+            transcript_workspace: None,
+            workspaces: Arc::new(HashMap::new()),
+            router: Arc::new(workspaces::WorkspaceRouter::default()),
+            mcp_profiles: Arc::new(HashMap::new()),
+            profile_tools: Arc::new(HashMap::new()),
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
@@ -1568,7 +2355,14 @@ mod tests {
         drop(tx);
 
         let started = Instant::now();
-        run_message_dispatch_loop(rx, runtime_ctx, 2).await;
+        run_message_dispatch_loop(
+            rx,
+            tokio::sync::watch::channel(runtime_ctx).1,
+            2,
+            &crate::shutdown::Shutdown::new(),
+            Duration::from_secs(30),
+        )
+        .await;
         let elapsed = started.elapsed();
 
         assert!(
@@ -1581,6 +2375,66 @@ mod tests {
         assert_eq!(sent_messages.len(), 2);
     }
 
+    #[test]
+    fn messages_are_handled_by_their_routed_workspace() {
+        let context =
+            |prompt: &str, channels: HashMap<String, Arc<dyn Channel>>| ChannelRuntimeContext {
+                channels_by_name: Arc::new(channels),
+                provider: Arc::new(SlowProvider {
+                    delay: Duration::ZERO,
+                }),
+                memory: Arc::new(NoopMemory),
+                memory_loader: Arc::new(DefaultMemoryLoader::default()),
+                postprocessor: None,
+                tools_registry: Arc::new(vec![]),
+                observer: Arc::new(NoopObserver),
+                system_prompt: Arc::new(prompt.to_string()),
+                model: Arc::new("test-model".to_string()),
+                temperature: 0.0,
+                auto_save_memory: false,
+                memory_namespaces: Arc::new(HashMap::new()),
+                transcript_workspace: None,
+                workspaces: Arc::new(HashMap::new()),
+                router: Arc::new(workspaces::WorkspaceRouter::default()),
+                mcp_profiles: Arc::new(HashMap::new()),
+                profile_tools: Arc::new(HashMap::new()),
+            };
+        let mut config = crate::config::Config::default();
+        config.workspaces.insert(
+            "ops".into(),
+            crate::config::WorkspaceConfig {
+                routes: vec!["test-channel:bob".into()],
+                ..Default::default()
+            },
+        );
+
+        let channel: Arc<dyn Channel> = Arc::new(RecordingChannel::default());
+        let mut main = context(
+            "main-prompt",
+            HashMap::from([(channel.name().to_string(), channel)]),
+        );
+        main.workspaces = Arc::new(HashMap::from([(
+            "ops".to_string(),
+            Arc::new(context("ops-prompt", HashMap::new())),
+        )]));
+        main.router = Arc::new(workspaces::WorkspaceRouter::from_config(&config).unwrap());
+        let main = Arc::new(main);
+
+        let message = |sender: &str| traits::ChannelMessage {
+            id: "1".to_string(),
+            sender: sender.to_string(),
+            reply_target: sender.to_string(),
+            content: "hi".to_string(),
+            channel: "test-channel".to_string(),
+            timestamp: 1,
+        };
+        let ops = main.for_message(&message("bob"));
+        assert_eq!(ops.system_prompt.as_str(), "ops-prompt");
+        assert!(ops.channels_by_name.contains_key("test-channel"));
+        let other = main.for_message(&message("alice"));
+        assert_eq!(other.system_prompt.as_str(), "main-prompt");
+    }
+
     #[test]
     fn prompt_contains_all_sections() {
         let ws = make_workspace();
@@ -1908,7 +2762,10 @@ mod tests {
             .await
             .unwrap();
 
-        let context = build_memory_context(&mem, "age").await;
+        let context = DefaultMemoryLoader::default()
+            .load_context(&mem, "age", &[])
+            .await
+            .unwrap();
         assert!(context.contains("[Memory context]"));
         assert!(context.contains("Age is 45"));
     }
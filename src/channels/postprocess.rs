@@ -0,0 +1,216 @@
+//! Response post-processing applied before a reply is handed to a channel.
+//!
+//! Stages run in a fixed order: redaction, link rewriting, profanity masking,
+//! then the per-channel footer (so footers are never redacted or rewritten).
+
+use crate::config::PostprocessConfig;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Built-in secret patterns: provider API keys, bearer tokens, cloud keys, PEM private keys.
+const SECRET_PATTERNS: &[&str] = &[
+    r"\bsk-[A-Za-z0-9_\-]{20,}",
+    r"(?i)\bbearer\s+[A-Za-z0-9\-._~+/]{16,}=*",
+    r"\bAKIA[0-9A-Z]{16}\b",
+    r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+    r"\bxox[abprs]-[A-Za-z0-9\-]{10,}",
+    r"-----BEGIN [A-Z ]*PRIVATE KEY-----[\s\S]*?-----END [A-Z ]*PRIVATE KEY-----",
+];
+
+/// Built-in PII patterns: email addresses, payment card numbers, phone numbers.
+const PII_PATTERNS: &[&str] = &[
+    r"\b[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}\b",
+    r"\b(?:\d[ \-]?){12,15}\d\b",
+    r"(?:\+\d{1,3}[\s.\-]?)?\(?\b\d{3}\)?[\s.\-]?\d{3}[\s.\-]?\d{4}\b",
+];
+
+static SECRET_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    SECRET_PATTERNS
+        .iter()
+        .map(|p| Regex::new(p).unwrap())
+        .collect()
+});
+
+/// Replace built-in secret patterns with `[REDACTED]`, independent of the
+/// `[postprocess]` settings. Used where text leaves the agent in bulk
+/// (transcript exports).
+pub fn redact_secrets(text: &str) -> String {
+    SECRET_REGEXES
+        .iter()
+        .fold(text.to_string(), |acc, pattern| {
+            pattern.replace_all(&acc, "[REDACTED]").into_owned()
+        })
+}
+
+pub struct ResponsePostprocessor {
+    redactions: Vec<Regex>,
+    redaction_text: String,
+    link_rewrites: Vec<(String, String)>,
+    profanity: Option<Regex>,
+    footers: HashMap<String, String>,
+}
+
+impl ResponsePostprocessor {
+    /// Build a post-processor from config. Returns `None` when disabled.
+    pub fn from_config(config: &PostprocessConfig) -> Result<Option<Self>> {
+        if !config.enabled {
+            return Ok(None);
+        }
+
+        let mut redactions = Vec::new();
+        if config.redact_secrets {
+            redactions.extend(SECRET_PATTERNS.iter().map(|p| Regex::new(p).unwrap()));
+        }
+        if config.redact_pii {
+            redactions.extend(PII_PATTERNS.iter().map(|p| Regex::new(p).unwrap()));
+        }
+        for pattern in &config.redact_patterns {
+            redactions.push(Regex::new(pattern).with_context(|| {
+                format!("Invalid postprocess.redact_patterns entry: {pattern}")
+            })?);
+        }
+
+        let words: Vec<String> = config
+            .profanity_words
+            .iter()
+            .map(|w| regex::escape(w.trim()))
+            .filter(|w| !w.is_empty())
+            .collect();
+        let profanity = if config.profanity_filter && !words.is_empty() {
+            Some(Regex::new(&format!(r"(?i)\b(?:{})\b", words.join("|")))?)
+        } else {
+            None
+        };
+
+        Ok(Some(Self {
+            redactions,
+            redaction_text: config.redaction_text.clone(),
+            link_rewrites: config
+                .link_rewrites
+                .iter()
+                .filter(|r| !r.from.is_empty())
+                .map(|r| (r.from.clone(), r.to.clone()))
+                .collect(),
+            profanity,
+            footers: config.footers.clone(),
+        }))
+    }
+
+    /// Apply every configured stage to a response bound for `channel`.
+    pub fn process(&self, channel: &str, response: &str) -> String {
+        let mut text = response.to_string();
+
+        for pattern in &self.redactions {
+            text = pattern
+                .replace_all(&text, self.redaction_text.as_str())
+                .into_owned();
+        }
+
+        for (from, to) in &self.link_rewrites {
+            text = text.replace(from.as_str(), to);
+        }
+
+        if let Some(profanity) = &self.profanity {
+            text = profanity
+                .replace_all(&text, |caps: &regex::Captures| {
+                    "*".repeat(caps[0].chars().count())
+                })
+                .into_owned();
+        }
+
+        if let Some(footer) = self.footers.get(channel).filter(|f| !f.trim().is_empty()) {
+            text.push_str("\n\n");
+            text.push_str(footer);
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LinkRewriteConfig;
+
+    fn enabled() -> PostprocessConfig {
+        PostprocessConfig {
+            enabled: true,
+            ..PostprocessConfig::default()
+        }
+    }
+
+    #[test]
+    fn disabled_config_builds_nothing() {
+        let pp = ResponsePostprocessor::from_config(&PostprocessConfig::default()).unwrap();
+        assert!(pp.is_none());
+    }
+
+    #[test]
+    fn redacts_builtin_secrets_but_not_pii_by_default() {
+        let pp = ResponsePostprocessor::from_config(&enabled())
+            .unwrap()
+            .unwrap();
+        let out = pp.process(
+            "telegram",
+            "key sk-abcdefghijklmnopqrstuvwxyz123456 mail bob@example.com",
+        );
+        assert!(!out.contains("sk-abcdef"));
+        assert!(out.contains("[REDACTED]"));
+        assert!(out.contains("bob@example.com"));
+    }
+
+    #[test]
+    fn redacts_pii_and_custom_patterns() {
+        let pp = ResponsePostprocessor::from_config(&PostprocessConfig {
+            redact_pii: true,
+            redact_patterns: vec![r"PROJ-\d+".into()],
+            redaction_text: "<hidden>".into(),
+            ..enabled()
+        })
+        .unwrap()
+        .unwrap();
+        let out = pp.process(
+            "slack",
+            "Mail bob@example.com or call 555-123-4567 about PROJ-42",
+        );
+        assert_eq!(out, "Mail <hidden> or call <hidden> about <hidden>");
+    }
+
+    #[test]
+    fn invalid_custom_pattern_is_rejected() {
+        let err = ResponsePostprocessor::from_config(&PostprocessConfig {
+            redact_patterns: vec!["(".into()],
+            ..enabled()
+        });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn rewrites_links_masks_profanity_and_appends_channel_footer() {
+        let mut footers = HashMap::new();
+        footers.insert("discord".to_string(), "-- ZeroClaw".to_string());
+        let pp = ResponsePostprocessor::from_config(&PostprocessConfig {
+            link_rewrites: vec![LinkRewriteConfig {
+                from: "http://wiki.internal/".into(),
+                to: "https://docs.example.com/".into(),
+            }],
+            profanity_filter: true,
+            profanity_words: vec!["darn".into()],
+            footers,
+            ..enabled()
+        })
+        .unwrap()
+        .unwrap();
+
+        let out = pp.process("discord", "Darn, see http://wiki.internal/setup");
+        assert_eq!(
+            out,
+            "****, see https://docs.example.com/setup\n\n-- ZeroClaw"
+        );
+
+        let other = pp.process("telegram", "darnit is fine");
+        assert_eq!(other, "darnit is fine");
+    }
+}
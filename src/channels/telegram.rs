@@ -474,20 +474,15 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         }
     }
 
-    fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
-        let message = update.get("message")?;
-
-        let text = message.get("text").and_then(serde_json::Value::as_str)?;
-
-        let username = message
-            .get("from")
+    /// Identity of the `from` user of an update, `None` unless allowed.
+    fn allowed_sender(&self, from: Option<&serde_json::Value>) -> Option<String> {
+        let username = from
             .and_then(|from| from.get("username"))
             .and_then(serde_json::Value::as_str)
             .unwrap_or("unknown")
             .to_string();
 
-        let user_id = message
-            .get("from")
+        let user_id = from
             .and_then(|from| from.get("id"))
             .and_then(serde_json::Value::as_i64)
             .map(|id| id.to_string());
@@ -506,6 +501,15 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         if !self.is_any_user_allowed(identities.iter().copied()) {
             return None;
         }
+        Some(sender_identity)
+    }
+
+    fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let message = update.get("message")?;
+
+        let text = message.get("text").and_then(serde_json::Value::as_str)?;
+
+        let sender_identity = self.allowed_sender(message.get("from"))?;
 
         let chat_id = message
             .get("chat")
@@ -531,7 +535,44 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         })
     }
 
-    async fn send_text_chunks(&self, message: &str, chat_id: &str) -> anyhow::Result<()> {
+    /// A press of an inline keyboard button, as a message whose text is the
+    /// button's data (the number of the option).
+    fn parse_update_callback(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let callback = update.get("callback_query")?;
+        let data = callback.get("data").and_then(serde_json::Value::as_str)?;
+        let sender_identity = self.allowed_sender(callback.get("from"))?;
+        let chat_id = callback
+            .get("message")
+            .and_then(|message| message.get("chat"))
+            .and_then(|chat| chat.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())?;
+        let callback_id = callback
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+
+        Some(ChannelMessage {
+            id: format!("telegram_{chat_id}_callback_{callback_id}"),
+            sender: sender_identity,
+            reply_target: chat_id,
+            content: data.to_string(),
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+    }
+
+    /// Send `message`, split into chunks Telegram accepts. `reply_markup`
+    /// (e.g. an inline keyboard) is attached to the last chunk.
+    async fn send_text_chunks(
+        &self,
+        message: &str,
+        chat_id: &str,
+        reply_markup: Option<&serde_json::Value>,
+    ) -> anyhow::Result<()> {
         let chunks = split_message_for_telegram(message);
 
         for (index, chunk) in chunks.iter().enumerate() {
@@ -547,11 +588,15 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 chunk.to_string()
             };
 
-            let markdown_body = serde_json::json!({
+            let mut markdown_body = serde_json::json!({
                 "chat_id": chat_id,
                 "text": text,
                 "parse_mode": "Markdown"
             });
+            let markup = reply_markup.filter(|_| index == chunks.len() - 1);
+            if let Some(markup) = markup {
+                markdown_body["reply_markup"] = markup.clone();
+            }
 
             let markdown_resp = self
                 .client
@@ -574,10 +619,13 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 "Telegram sendMessage with Markdown failed; retrying without parse_mode"
             );
 
-            let plain_body = serde_json::json!({
+            let mut plain_body = serde_json::json!({
                 "chat_id": chat_id,
                 "text": text,
             });
+            if let Some(markup) = markup {
+                plain_body["reply_markup"] = markup.clone();
+            }
             let plain_resp = self
                 .client
                 .post(self.api_url("sendMessage"))
@@ -1040,6 +1088,17 @@ Allowlist Telegram username (without '@') or numeric user ID.",
     }
 }
 
+/// Inline keyboard with one button per option; a press sends the option's
+/// number back.
+fn inline_keyboard(options: &[String]) -> serde_json::Value {
+    let rows: Vec<serde_json::Value> = options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| serde_json::json!([{ "text": option, "callback_data": (i + 1).to_string() }]))
+        .collect();
+    serde_json::json!({ "inline_keyboard": rows })
+}
+
 #[async_trait]
 impl Channel for TelegramChannel {
     fn name(&self) -> &str {
@@ -1051,7 +1110,7 @@ impl Channel for TelegramChannel {
 
         if !attachments.is_empty() {
             if !text_without_markers.is_empty() {
-                self.send_text_chunks(&text_without_markers, &message.recipient)
+                self.send_text_chunks(&text_without_markers, &message.recipient, None)
                     .await?;
             }
 
@@ -1068,7 +1127,8 @@ impl Channel for TelegramChannel {
             return Ok(());
         }
 
-        self.send_text_chunks(&message.content, &message.recipient)
+        let keyboard = (!message.options.is_empty()).then(|| inline_keyboard(&message.options));
+        self.send_text_chunks(&message.content, &message.recipient, keyboard.as_ref())
             .await
     }
 
@@ -1082,7 +1142,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.client.post(&url).json(&body).send().await {
@@ -1140,6 +1200,26 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
+                    if let Some(callback_id) = update
+                        .pointer("/callback_query/id")
+                        .and_then(serde_json::Value::as_str)
+                    {
+                        // Stop the button's loading spinner.
+                        let _ = self
+                            .client
+                            .post(self.api_url("answerCallbackQuery"))
+                            .json(&serde_json::json!({ "callback_query_id": callback_id }))
+                            .send()
+                            .await;
+                        let Some(msg) = self.parse_update_callback(update) else {
+                            continue;
+                        };
+                        if tx.send(msg).await.is_err() {
+                            return Ok(());
+                        }
+                        continue;
+                    }
+
                     let Some(msg) = self.parse_update_message(update) else {
                         self.handle_unauthorized_message(update).await;
                         continue;
@@ -1382,6 +1462,34 @@ mod tests {
         assert_eq!(msg.id, "telegram_-100200300_33");
     }
 
+    #[test]
+    fn button_presses_arrive_as_the_option_number() {
+        let keyboard = inline_keyboard(&["Website".into(), "API server".into()]);
+        assert_eq!(keyboard["inline_keyboard"][1][0]["text"], "API server");
+        assert_eq!(keyboard["inline_keyboard"][1][0]["callback_data"], "2");
+
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()]);
+        let update = serde_json::json!({
+            "update_id": 2,
+            "callback_query": {
+                "id": "cb-9",
+                "data": "2",
+                "from": { "id": 555, "username": "alice" },
+                "message": { "message_id": 34, "chat": { "id": -100_200_300 } }
+            }
+        });
+        let msg = ch
+            .parse_update_callback(&update)
+            .expect("callback should parse");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "-100200300");
+        assert_eq!(msg.content, "2");
+        assert!(ch.parse_update_message(&update).is_none());
+
+        let stranger = TelegramChannel::new("token".into(), vec!["bob".into()]);
+        assert!(stranger.parse_update_callback(&update).is_none());
+    }
+
     #[test]
     fn parse_update_message_allows_numeric_id_without_username() {
         let ch = TelegramChannel::new("token".into(), vec!["555".into()]);
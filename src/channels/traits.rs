@@ -17,6 +17,9 @@ pub struct SendMessage {
     pub content: String,
     pub recipient: String,
     pub subject: Option<String>,
+    /// Suggested replies, shown as buttons by channels that have them. The
+    /// reply comes back as an ordinary message with the option's number.
+    pub options: Vec<String>,
 }
 
 impl SendMessage {
@@ -26,6 +29,7 @@ impl SendMessage {
             content: content.into(),
             recipient: recipient.into(),
             subject: None,
+            options: Vec::new(),
         }
     }
 
@@ -39,8 +43,16 @@ impl SendMessage {
             content: content.into(),
             recipient: recipient.into(),
             subject: Some(subject.into()),
+            options: Vec::new(),
         }
     }
+
+    /// Offer `options` as replies.
+    #[must_use]
+    pub fn with_options(mut self, options: Vec<String>) -> Self {
+        self.options = options;
+        self
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -0,0 +1,207 @@
+//! Named workspaces (`[workspaces.<name>]`) sharing one channel server.
+//!
+//! Each workspace gets its own directory, so its memory DB, session
+//! transcripts, persona files and skills never mix with the main
+//! workspace's, plus its own autonomy settings, tool policy set and MCP
+//! servers. [`WorkspaceRouter`] picks the workspace for an incoming message
+//! from its channel and sender; unrouted messages stay in the main workspace.
+
+use crate::config::Config;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Directory of workspace `name`: its `dir` (relative paths are resolved
+/// against the main workspace) or `<workspace>/workspaces/<name>`.
+pub fn workspace_dir(config: &Config, name: &str) -> PathBuf {
+    match config.workspaces.get(name).and_then(|ws| ws.dir.as_deref()) {
+        Some(dir) => config.workspace_dir.join(shellexpand::tilde(dir).as_ref()),
+        None => config.workspace_dir.join("workspaces").join(name),
+    }
+}
+
+/// The config a named workspace runs with: the main config pointed at the
+/// workspace directory, with its autonomy and tool policy set applied.
+pub fn workspace_config(config: &Config, name: &str) -> Result<Config> {
+    let Some(ws) = config.workspaces.get(name) else {
+        bail!("No [workspaces.{name}] is defined");
+    };
+    let mut derived = config.clone();
+    derived.workspace_dir = workspace_dir(config, name);
+    derived.workspaces.clear();
+    if let Some(autonomy) = &ws.autonomy {
+        derived.autonomy = autonomy.clone();
+    }
+    if let Some(set) = ws.policy.as_deref() {
+        let Some(overrides) = config.tool_policy.sets.get(set) else {
+            bail!("workspaces.{name}.policy: no [tool_policy.sets.{set}] is defined");
+        };
+        let policy = &mut derived.tool_policy;
+        if let Some(paths) = &overrides.allowed_paths {
+            policy.allowed_paths.clone_from(paths);
+        }
+        if let Some(domains) = &overrides.allowed_domains {
+            policy.allowed_domains.clone_from(domains);
+        }
+        if let Some(commands) = &overrides.allowed_commands {
+            policy.allowed_commands.clone_from(commands);
+        }
+        if let Some(max_output_bytes) = overrides.max_output_bytes {
+            policy.max_output_bytes = max_output_bytes;
+        }
+    }
+    Ok(derived)
+}
+
+/// Whether workspace `name` may use a tool from MCP server `server`.
+pub fn allows_mcp_server(config: &Config, name: &str, server: &str) -> bool {
+    config
+        .workspaces
+        .get(name)
+        .and_then(|ws| ws.mcp_servers.as_ref())
+        .is_none_or(|servers| servers.iter().any(|s| s == server))
+}
+
+/// Maps `(channel, sender)` to a workspace name.
+#[derive(Debug, Default)]
+pub struct WorkspaceRouter {
+    by_sender: HashMap<(String, String), String>,
+    by_channel: HashMap<String, String>,
+}
+
+impl WorkspaceRouter {
+    /// Build the routing table. A route claimed by two workspaces is an
+    /// error, since either choice would leak one workspace's messages into
+    /// the other.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut router = Self::default();
+        let mut names: Vec<&String> = config.workspaces.keys().collect();
+        names.sort();
+        for name in names {
+            for route in &config.workspaces[name].routes {
+                let previous = match route.split_once(':') {
+                    Some((channel, sender)) => router.by_sender.insert(
+                        (channel.trim().to_string(), sender.trim().to_string()),
+                        name.clone(),
+                    ),
+                    None => router
+                        .by_channel
+                        .insert(route.trim().to_string(), name.clone()),
+                };
+                if let Some(previous) = previous {
+                    bail!("Route \"{route}\" is claimed by workspaces {previous} and {name}");
+                }
+            }
+        }
+        Ok(router)
+    }
+
+    /// Workspace for a message, or `None` for the main workspace.
+    pub fn route(&self, channel: &str, sender: &str) -> Option<&str> {
+        self.by_sender
+            .get(&(channel.to_string(), sender.to_string()))
+            .or_else(|| self.by_channel.get(channel))
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AutonomyConfig, ToolPolicySet, WorkspaceConfig};
+
+    fn config_with(workspaces: &[(&str, WorkspaceConfig)]) -> Config {
+        let mut config = Config {
+            workspace_dir: PathBuf::from("/srv/zeroclaw"),
+            ..Config::default()
+        };
+        for (name, ws) in workspaces {
+            config.workspaces.insert((*name).to_string(), ws.clone());
+        }
+        config
+    }
+
+    fn routed(routes: &[&str]) -> WorkspaceConfig {
+        WorkspaceConfig {
+            routes: routes.iter().map(ToString::to_string).collect(),
+            ..WorkspaceConfig::default()
+        }
+    }
+
+    #[test]
+    fn sender_routes_win_over_channel_routes() {
+        let config = config_with(&[
+            ("ops", routed(&["slack", "telegram:42"])),
+            ("personal", routed(&["telegram:7"])),
+        ]);
+        let router = WorkspaceRouter::from_config(&config).unwrap();
+
+        assert_eq!(router.route("slack", "anyone"), Some("ops"));
+        assert_eq!(router.route("telegram", "42"), Some("ops"));
+        assert_eq!(router.route("telegram", "7"), Some("personal"));
+        assert_eq!(router.route("telegram", "99"), None);
+        assert_eq!(router.route("discord", "7"), None);
+    }
+
+    #[test]
+    fn sender_may_contain_colons() {
+        let config = config_with(&[("team", routed(&["matrix:@ops:example.org"]))]);
+        let router = WorkspaceRouter::from_config(&config).unwrap();
+        assert_eq!(router.route("matrix", "@ops:example.org"), Some("team"));
+    }
+
+    #[test]
+    fn duplicate_routes_are_rejected() {
+        let config = config_with(&[("a", routed(&["slack"])), ("b", routed(&["slack"]))]);
+        let err = WorkspaceRouter::from_config(&config).unwrap_err();
+        assert!(err.to_string().contains("claimed by workspaces a and b"));
+    }
+
+    #[test]
+    fn workspace_config_isolates_dir_and_permissions() {
+        let mut config = config_with(&[(
+            "ops",
+            WorkspaceConfig {
+                autonomy: Some(AutonomyConfig {
+                    allowed_commands: vec!["kubectl".into()],
+                    ..AutonomyConfig::default()
+                }),
+                policy: Some("ops".into()),
+                ..WorkspaceConfig::default()
+            },
+        )]);
+        config.tool_policy.sets.insert(
+            "ops".into(),
+            ToolPolicySet {
+                allowed_domains: Some(vec!["internal.example".into()]),
+                ..ToolPolicySet::default()
+            },
+        );
+
+        let derived = workspace_config(&config, "ops").unwrap();
+        assert_eq!(
+            derived.workspace_dir,
+            PathBuf::from("/srv/zeroclaw/workspaces/ops")
+        );
+        assert_eq!(derived.autonomy.allowed_commands, ["kubectl"]);
+        assert_eq!(derived.tool_policy.allowed_domains, ["internal.example"]);
+        assert!(derived.workspaces.is_empty());
+        assert!(workspace_config(&config, "missing").is_err());
+    }
+
+    #[test]
+    fn explicit_dir_and_mcp_allowlist() {
+        let config = config_with(&[(
+            "home",
+            WorkspaceConfig {
+                dir: Some("/data/home".into()),
+                mcp_servers: Some(vec!["calendar".into()]),
+                ..WorkspaceConfig::default()
+            },
+        )]);
+        assert_eq!(workspace_dir(&config, "home"), PathBuf::from("/data/home"));
+        assert!(allows_mcp_server(&config, "home", "calendar"));
+        assert!(!allows_mcp_server(&config, "home", "github"));
+        assert!(allows_mcp_server(&config, "other", "github"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Dynamic shell completions.
+//!
+//! `source <(COMPLETE=bash zeroclaw)` (or `zsh`, `fish`, `elvish`,
+//! `powershell`) registers a completer that calls back into the binary, so
+//! argument values come from the live config, workspace and databases:
+//! profiles, models, cron jobs, sessions, skills, plugins, MCP servers and
+//! integrations.
+//! Completers never create files and return nothing when there is no config.
+
+use crate::config::Config;
+use clap_complete::engine::CompletionCandidate;
+
+fn config() -> Option<Config> {
+    Config::load_existing().ok().flatten()
+}
+
+fn candidate(value: impl Into<String>, help: impl Into<String>) -> CompletionCandidate {
+    let help: String = help.into();
+    let candidate = CompletionCandidate::new(value.into());
+    if help.is_empty() {
+        candidate
+    } else {
+        candidate.help(Some(help.into()))
+    }
+}
+
+/// `--profile`: overlays in `profiles/*.toml` next to config.toml.
+pub fn profiles() -> Vec<CompletionCandidate> {
+    let Ok(config_path) = Config::resolve_config_path() else {
+        return Vec::new();
+    };
+    let dir = crate::config::schema::profile_overlay_path(&config_path, "_")
+        .parent()
+        .map(std::path::Path::to_path_buf);
+    let Some(Ok(entries)) = dir.map(std::fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".toml").map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    names.into_iter().map(|name| candidate(name, "")).collect()
+}
+
+/// `--model`: the default model and `hint:<name>` model routes.
+pub fn models() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    let mut candidates = Vec::new();
+    if let Some(model) = &config.default_model {
+        candidates.push(candidate(model.clone(), "default model"));
+    }
+    for route in &config.model_routes {
+        candidates.push(candidate(
+            format!("hint:{}", route.hint),
+            format!("{} via {}", route.model, route.provider),
+        ));
+    }
+    candidates
+}
+
+/// Cron job IDs, with the job name or command as help.
+pub fn cron_jobs() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    if !config.workspace_dir.join("cron").join("jobs.db").exists() {
+        return Vec::new();
+    }
+    crate::cron::list_jobs(&config)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|job| {
+            let help = job.name.clone().unwrap_or_else(|| job.command.clone());
+            candidate(job.id, help)
+        })
+        .collect()
+}
+
+/// Recorded session IDs, most recent first.
+pub fn sessions() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    crate::sessions::list(&config.workspace_dir)
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .map(|(order, session)| {
+            candidate(
+                session.id,
+                format!(
+                    "{} messages, {}",
+                    session.messages,
+                    session.last_active.format("%Y-%m-%d %H:%M")
+                ),
+            )
+            .display_order(Some(order))
+        })
+        .collect()
+}
+
+/// Installed skill names.
+pub fn skills() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    crate::skills::load_skills(&config.workspace_dir)
+        .into_iter()
+        .map(|skill| candidate(skill.name, skill.description))
+        .collect()
+}
+
+/// Installed plugin names.
+pub fn plugins() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    crate::plugins::discover(&crate::plugins::plugins_dir(&config))
+        .into_iter()
+        .flatten()
+        .map(|plugin| {
+            let help = plugin.manifest.plugin.description.clone();
+            candidate(plugin.name(), help)
+        })
+        .collect()
+}
+
+/// MCP servers that sign in with OAuth.
+pub fn mcp_servers() -> Vec<CompletionCandidate> {
+    let Some(config) = config() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = config
+        .mcp
+        .servers
+        .iter()
+        .filter(|(_, server)| server.oauth.is_some())
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names.into_iter().map(|name| candidate(name, "")).collect()
+}
+
+/// Integration names from the catalog.
+pub fn integrations() -> Vec<CompletionCandidate> {
+    crate::integrations::registry::all_integrations()
+        .into_iter()
+        .map(|entry| candidate(entry.name, entry.description))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integrations_complete_from_catalog() {
+        let names: Vec<String> = integrations()
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().into_owned())
+            .collect();
+        assert!(names.iter().any(|name| name == "Telegram"));
+    }
+}
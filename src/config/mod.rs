@@ -1,15 +1,19 @@
+pub mod reload;
 pub mod schema;
+pub mod validate;
 
 #[allow(unused_imports)]
 pub use schema::{
     AgentConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig, BrowserConfig,
     ChannelsConfig, ComposioConfig, Config, CostConfig, CronConfig, DelegateAgentConfig,
-    DiscordConfig, DockerRuntimeConfig, GatewayConfig, HardwareConfig, HardwareTransport,
-    HeartbeatConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
-    MemoryConfig, ModelRouteConfig, ObservabilityConfig, PeripheralBoardConfig, PeripheralsConfig,
-    ReliabilityConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
-    SchedulerConfig, SecretsConfig, SecurityConfig, SlackConfig, TelegramConfig, TunnelConfig,
-    WebhookConfig,
+    DiscordConfig, DockerRuntimeConfig, EventHookConfig, EventsConfig, GatewayConfig,
+    HardwareConfig, HardwareTransport, HeartbeatConfig, HookConfig, HookSource, HttpRequestConfig,
+    IMessageConfig, IdentityConfig, IsolatedRuntimeConfig, LarkConfig, LinkRewriteConfig,
+    MatrixConfig, MemoryConfig, MemoryContextConfig, ModelRouteConfig, ObservabilityConfig,
+    PeripheralBoardConfig, PeripheralsConfig, PostprocessConfig, RacingConfig, ReliabilityConfig,
+    ReloadConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
+    SchedulerConfig, SecretsConfig, SecurityConfig, SessionsConfig, ShutdownConfig, SlackConfig,
+    TelegramConfig, ToolPolicyConfig, ToolPolicySet, TunnelConfig, WebhookConfig, WorkspaceConfig,
 };
 
 #[cfg(test)]
@@ -0,0 +1,1287 @@
+//! `zeroclaw config validate` — parse the config file and check everything
+//! that would otherwise only fail at first use (providers, URLs, MCP server
+//! binaries, env var references, regexes), reporting file/line locations.
+
+use super::schema::Config;
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static ENV_REF_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// A single validation finding, located by dotted key path and (when it
+/// can be resolved) 1-based line number in the config file.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    pub key: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            IssueSeverity::Error => "error",
+            IssueSeverity::Warning => "warning",
+        };
+        if self.key.is_empty() {
+            write!(f, "{level}: {}", self.message)
+        } else {
+            write!(f, "{level}: {}: {}", self.key, self.message)
+        }
+    }
+}
+
+/// Validate the config file at `path`. Only I/O failures return `Err`;
+/// everything else is reported as a [`ConfigIssue`].
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigIssue>> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    Ok(validate_config_str(&raw))
+}
+
+/// Validate raw TOML config contents.
+pub fn validate_config_str(raw: &str) -> Vec<ConfigIssue> {
+    let config: Config = match toml::from_str(raw) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.span().map(|span| line_of_offset(raw, span.start));
+            return vec![ConfigIssue {
+                severity: IssueSeverity::Error,
+                key: String::new(),
+                line,
+                message: e.message().to_string(),
+            }];
+        }
+    };
+
+    let lines = KeyLines::index(raw);
+    let mut checker = Checker::default();
+    check_unknown_sections(raw, &mut checker);
+    check_providers(&config, &mut checker);
+    check_urls(&config, &mut checker);
+    check_memory_and_runtime(&config, &mut checker);
+    check_channels(&config, &mut checker);
+    check_mcp(&config, &mut checker);
+    check_postprocess(&config, &mut checker);
+    check_tool_policy(&config, &mut checker);
+    check_workspaces(&config, &mut checker);
+    check_events(&config, &mut checker);
+
+    checker
+        .issues
+        .into_iter()
+        .map(|(severity, key, message)| ConfigIssue {
+            line: lines.lookup(&key),
+            severity,
+            key,
+            message,
+        })
+        .collect()
+}
+
+/// Print issues compiler-style (`path:line: level: key: message`).
+/// Returns the number of errors.
+pub fn print_report(path: &Path, issues: &[ConfigIssue]) -> usize {
+    for issue in issues {
+        match issue.line {
+            Some(line) => println!("{}:{line}: {issue}", path.display()),
+            None => println!("{}: {issue}", path.display()),
+        }
+    }
+    let errors = issues
+        .iter()
+        .filter(|i| i.severity == IssueSeverity::Error)
+        .count();
+    let warnings = issues.len() - errors;
+    if issues.is_empty() {
+        println!("✅ {} is valid", path.display());
+    } else {
+        println!();
+        println!("{errors} error(s), {warnings} warning(s)");
+    }
+    errors
+}
+
+#[derive(Default)]
+struct Checker {
+    issues: Vec<(IssueSeverity, String, String)>,
+}
+
+impl Checker {
+    fn error(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.issues
+            .push((IssueSeverity::Error, key.into(), message.into()));
+    }
+
+    fn warn(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.issues
+            .push((IssueSeverity::Warning, key.into(), message.into()));
+    }
+
+    fn provider(&mut self, key: String, name: &str) {
+        if let Err(e) = crate::providers::create_provider(name, None) {
+            let reason = e.to_string();
+            let reason = reason.lines().next().unwrap_or("invalid provider");
+            self.error(key, format!("unknown provider \"{name}\": {reason}"));
+        }
+    }
+
+    fn url(&mut self, key: String, url: &str) {
+        if crate::security::secrets::MasterKey::is_sealed(url) {
+            return;
+        }
+        match reqwest::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            Ok(parsed) => self.error(
+                key,
+                format!(
+                    "unsupported URL scheme \"{}\" (expected http or https)",
+                    parsed.scheme()
+                ),
+            ),
+            Err(e) => self.error(key, format!("invalid URL \"{url}\": {e}")),
+        }
+    }
+
+    fn env_refs(&mut self, key: String, value: &str) {
+        for caps in ENV_REF_REGEX.captures_iter(value) {
+            let var = &caps[1];
+            if std::env::var_os(var).is_none() {
+                self.warn(
+                    key.clone(),
+                    format!("references ${{{var}}}, which is not set in the environment"),
+                );
+            }
+        }
+    }
+}
+
+fn check_unknown_sections(raw: &str, checker: &mut Checker) {
+    let Ok(table) = raw.parse::<toml::Table>() else {
+        return;
+    };
+    let Ok(toml::Value::Table(known)) = toml::Value::try_from(Config::default()) else {
+        return;
+    };
+    for (key, value) in &table {
+        if value.is_table() && !known.contains_key(key) {
+            checker.warn(key.clone(), format!("unknown section [{key}] is ignored"));
+        }
+    }
+}
+
+fn check_providers(config: &Config, checker: &mut Checker) {
+    match config.default_provider.as_deref() {
+        Some(name) => checker.provider("default_provider".into(), name),
+        None => checker.warn("default_provider", "not set; \"openrouter\" will be used"),
+    }
+    if !(0.0..=2.0).contains(&config.default_temperature) {
+        checker.error(
+            "default_temperature",
+            format!(
+                "{} is outside the supported range 0.0–2.0",
+                config.default_temperature
+            ),
+        );
+    }
+    for (i, name) in config.reliability.fallback_providers.iter().enumerate() {
+        checker.provider(format!("reliability.fallback_providers[{i}]"), name);
+    }
+    for (i, route) in config.model_routes.iter().enumerate() {
+        checker.provider(format!("model_routes[{i}].provider"), &route.provider);
+    }
+    for (name, agent) in &config.agents {
+        checker.provider(format!("agents.{name}.provider"), &agent.provider);
+    }
+    if config.racing.enabled {
+        if config.racing.provider.trim().is_empty() {
+            checker.error(
+                "racing.provider",
+                "racing is enabled but no challenger provider is set",
+            );
+        } else {
+            checker.provider("racing.provider".into(), &config.racing.provider);
+        }
+        if config
+            .racing
+            .model
+            .as_deref()
+            .map_or(true, |m| m.trim().is_empty())
+        {
+            checker.error(
+                "racing.model",
+                "racing is enabled but no challenger model is set",
+            );
+        }
+    }
+}
+
+fn check_urls(config: &Config, checker: &mut Checker) {
+    if let Some(url) = config.api_url.as_deref() {
+        checker.url("api_url".into(), url);
+    }
+    if let Some(url) = config.observability.otel_endpoint.as_deref() {
+        checker.url("observability.otel_endpoint".into(), url);
+    }
+    if config.browser.enabled && config.browser.backend == "computer_use" {
+        checker.url(
+            "browser.computer_use.endpoint".into(),
+            &config.browser.computer_use.endpoint,
+        );
+    }
+}
+
+fn check_memory_and_runtime(config: &Config, checker: &mut Checker) {
+    if crate::memory::classify_memory_backend(&config.memory.backend)
+        == crate::memory::MemoryBackendKind::Unknown
+    {
+        checker.error(
+            "memory.backend",
+            format!(
+                "unknown backend \"{}\" (expected sqlite, lucid, postgres, markdown, or none)",
+                config.memory.backend
+            ),
+        );
+    }
+    match &config.memory.postgres_url {
+        Some(url) => {
+            if let Err(e) = crate::memory::postgres::ConnectOptions::parse(url) {
+                checker.error("memory.postgres_url", format!("{e:#}"));
+            }
+        }
+        None if config.memory.backend == "postgres" => checker.error(
+            "memory.postgres_url",
+            "backend is \"postgres\" but no connection URL is set",
+        ),
+        None => {}
+    }
+    match config.memory.vector_store.as_str() {
+        "sqlite" => {}
+        store @ ("qdrant" | "lancedb") => {
+            if store == "qdrant" {
+                checker.url("memory.qdrant_url".into(), &config.memory.qdrant_url);
+            } else if !cfg!(feature = "lancedb") {
+                checker.error(
+                    "memory.vector_store",
+                    "this build has no LanceDB support (build with --features lancedb)",
+                );
+            } else if config.memory.encryption_key.is_some() {
+                checker.warn(
+                    "memory.vector_store",
+                    "embeddings in the LanceDB table are stored unencrypted",
+                );
+            }
+            if !matches!(
+                crate::memory::classify_memory_backend(&config.memory.backend),
+                crate::memory::MemoryBackendKind::Sqlite | crate::memory::MemoryBackendKind::Lucid
+            ) {
+                checker.warn(
+                    "memory.vector_store",
+                    format!(
+                        "only the sqlite and lucid backends use a vector store; \"{}\" ignores it",
+                        config.memory.backend
+                    ),
+                );
+            }
+        }
+        other => checker.error(
+            "memory.vector_store",
+            format!("unknown vector store \"{other}\" (expected sqlite, qdrant or lancedb)"),
+        ),
+    }
+    if let Some(schedule) = &config.memory.backup_schedule {
+        if let Err(e) = crate::memory::backup::next_backup(schedule, chrono::Local::now()) {
+            checker.error("memory.backup_schedule", format!("{e:#}"));
+        } else if !matches!(
+            crate::memory::classify_memory_backend(&config.memory.backend),
+            crate::memory::MemoryBackendKind::Sqlite | crate::memory::MemoryBackendKind::Lucid
+        ) {
+            checker.warn(
+                "memory.backup_schedule",
+                format!(
+                    "only the sqlite and lucid backends are backed up; \"{}\" ignores it",
+                    config.memory.backend
+                ),
+            );
+        }
+    }
+    if let Err(e) = config
+        .memory
+        .fts_tokenizer
+        .parse::<crate::memory::FtsTokenizer>()
+    {
+        checker.error("memory.fts_tokenizer", e.to_string());
+    }
+    match config.memory.reranker.as_str() {
+        "none" => {}
+        "local" => {
+            if !cfg!(feature = "local-embeddings") {
+                checker.warn(
+                    "memory.reranker",
+                    "\"local\" needs a build with --features local-embeddings; recall is not reranked",
+                );
+            }
+        }
+        "cohere" | "jina" => {
+            if config.memory.reranker_api_key.is_none() {
+                checker.error(
+                    "memory.reranker_api_key",
+                    format!(
+                        "reranker \"{}\" needs an API key",
+                        config.memory.reranker
+                    ),
+                );
+            }
+        }
+        other => match other.strip_prefix("custom:") {
+            Some(url) => checker.url("memory.reranker".into(), url),
+            None => checker.error(
+                "memory.reranker",
+                format!(
+                    "unknown reranker \"{other}\" (expected none, local, cohere, jina or custom:URL)"
+                ),
+            ),
+        },
+    }
+    if let Err(e) = config
+        .memory
+        .dedup_strategy
+        .parse::<crate::memory::DedupStrategy>()
+    {
+        checker.error("memory.dedup_strategy", e.to_string());
+    }
+    if let Err(e) = config
+        .memory
+        .budget_action
+        .parse::<crate::memory::BudgetAction>()
+    {
+        checker.error("memory.budget_action", e.to_string());
+    }
+    if config.memory.max_memories > 0 {
+        match crate::memory::classify_memory_backend(&config.memory.backend) {
+            crate::memory::MemoryBackendKind::Sqlite | crate::memory::MemoryBackendKind::Lucid => {}
+            crate::memory::MemoryBackendKind::Postgres => checker.error(
+                "memory.max_memories",
+                "the postgres backend cannot enforce a memory budget; set max_memories = 0",
+            ),
+            _ => checker.warn(
+                "memory.max_memories",
+                format!(
+                    "only the sqlite and lucid backends enforce a memory budget; \"{}\" ignores it",
+                    config.memory.backend
+                ),
+            ),
+        }
+    }
+    if let Some(source) = &config.memory.encryption_key {
+        match crate::memory::cipher::KeySource::parse(source) {
+            Err(e) => checker.error("memory.encryption_key", e.to_string()),
+            Ok(_) if !cfg!(feature = "sqlcipher") => checker.error(
+                "memory.encryption_key",
+                "this build has no SQLCipher support (build with --features sqlcipher)",
+            ),
+            Ok(crate::memory::cipher::KeySource::Keyring(_)) if !cfg!(feature = "keyring") => {
+                checker.error(
+                    "memory.encryption_key",
+                    "this build has no keyring support (build with --features keyring)",
+                );
+            }
+            Ok(crate::memory::cipher::KeySource::Env(var)) if std::env::var_os(var).is_none() => {
+                checker.warn(
+                    "memory.encryption_key",
+                    format!("${var} is not set in the environment"),
+                );
+            }
+            Ok(_) => {}
+        }
+    }
+    match config.runtime.kind.as_str() {
+        "native" => {}
+        "docker" => {
+            if find_command("docker").is_none() {
+                checker.warn(
+                    "runtime.kind",
+                    "runtime is \"docker\" but `docker` is not in PATH",
+                );
+            }
+        }
+        "isolated" => {
+            let backend = config.runtime.isolated.backend.as_str();
+            let engine = match backend {
+                "docker" => Some("docker"),
+                "podman" | "krun" => Some("podman"),
+                other => {
+                    checker.error(
+                        "runtime.isolated.backend",
+                        format!("unknown backend \"{other}\" (expected docker, podman, or krun)"),
+                    );
+                    None
+                }
+            };
+            if let Some(engine) = engine.filter(|engine| find_command(engine).is_none()) {
+                checker.warn(
+                    "runtime.isolated.backend",
+                    format!("backend is \"{backend}\" but `{engine}` is not in PATH"),
+                );
+            }
+        }
+        other => checker.error(
+            "runtime.kind",
+            format!("unknown runtime \"{other}\" (expected native, docker, or isolated)"),
+        ),
+    }
+    if !matches!(config.observability.log_format.as_str(), "text" | "json") {
+        checker.error(
+            "observability.log_format",
+            format!(
+                "unknown log format \"{}\" (expected text or json)",
+                config.observability.log_format
+            ),
+        );
+    }
+    if !(0.0..=1.0).contains(&config.observability.otel_sample_ratio) {
+        checker.error(
+            "observability.otel_sample_ratio",
+            format!(
+                "{} is outside the range 0.0–1.0",
+                config.observability.otel_sample_ratio
+            ),
+        );
+    }
+}
+
+fn check_channels(config: &Config, checker: &mut Checker) {
+    let channels = &config.channels_config;
+    let tokens = [
+        ("telegram", channels.telegram.as_ref().map(|c| &c.bot_token)),
+        ("discord", channels.discord.as_ref().map(|c| &c.bot_token)),
+        ("slack", channels.slack.as_ref().map(|c| &c.bot_token)),
+    ];
+    for (name, token) in tokens {
+        if token.is_some_and(|t| t.trim().is_empty()) {
+            checker.error(
+                format!("channels_config.{name}.bot_token"),
+                "bot_token is empty",
+            );
+        }
+    }
+}
+
+fn check_mcp(config: &Config, checker: &mut Checker) {
+    if !config.mcp.enabled && !config.mcp.servers.is_empty() {
+        checker.warn(
+            "mcp.enabled",
+            "MCP servers are configured but mcp.enabled = false",
+        );
+    }
+    for (name, server) in &config.mcp.servers {
+        let prefix = format!("mcp.servers.{name}");
+        match server.transport.as_str() {
+            "stdio" => match server.command.as_deref().map(str::trim) {
+                None | Some("") => {
+                    checker.error(
+                        format!("{prefix}.command"),
+                        "stdio transport requires a command",
+                    );
+                }
+                Some(command) => {
+                    checker.env_refs(format!("{prefix}.command"), command);
+                    // Isolated servers resolve their command inside the sandbox.
+                    if !server.isolated
+                        && !command.contains("${")
+                        && find_command(command).is_none()
+                    {
+                        checker.error(
+                            format!("{prefix}.command"),
+                            format!("command \"{command}\" not found (not in PATH and not an existing file)"),
+                        );
+                    }
+                }
+            },
+            "docker" => match &server.docker {
+                Some(docker) if !docker.image.trim().is_empty() => {
+                    for volume in &docker.volumes {
+                        checker.env_refs(format!("{prefix}.docker.volumes"), volume);
+                    }
+                    if find_command("docker").is_none() {
+                        checker.error(
+                            format!("{prefix}.transport"),
+                            "docker transport requires the docker command in PATH",
+                        );
+                    }
+                }
+                _ => checker.error(
+                    format!("{prefix}.docker.image"),
+                    "docker transport requires an image",
+                ),
+            },
+            transport @ ("sse" | "http") => match server.url.as_deref() {
+                Some(url) => {
+                    checker.env_refs(format!("{prefix}.url"), url);
+                    if !url.contains("${") {
+                        checker.url(format!("{prefix}.url"), url);
+                    }
+                }
+                None => checker.error(
+                    format!("{prefix}.url"),
+                    format!("{transport} transport requires a url"),
+                ),
+            },
+            other => checker.error(
+                format!("{prefix}.transport"),
+                format!("unknown transport \"{other}\" (expected stdio, docker, sse or http)"),
+            ),
+        }
+        for arg in &server.args {
+            checker.env_refs(format!("{prefix}.args"), arg);
+        }
+        for (var, value) in &server.env {
+            checker.env_refs(format!("{prefix}.env.{var}"), value);
+        }
+        for (name, value) in &server.headers {
+            checker.env_refs(format!("{prefix}.headers.{name}"), value);
+        }
+        let remote = matches!(server.transport.as_str(), "sse" | "http");
+        for (var, entry) in &server.env_from_keyring {
+            let key = format!("{prefix}.env_from_keyring.{var}");
+            if crate::mcp::config::keyring_entry(entry).is_none() {
+                checker.error(key, format!("\"{entry}\" is not a service/account entry"));
+            } else if !cfg!(feature = "keyring") {
+                checker.error(
+                    key,
+                    "this build has no keyring support (build with --features keyring)",
+                );
+            } else if remote {
+                checker.warn(
+                    key,
+                    "env only applies to stdio and docker transports; ignored",
+                );
+            } else if server.env.contains_key(var) {
+                checker.warn(key, format!("replaces {prefix}.env.{var}"));
+            }
+        }
+        if !server.headers.is_empty() && !remote {
+            checker.warn(
+                format!("{prefix}.headers"),
+                "headers only apply to sse and http transports; ignored",
+            );
+        }
+        if server.oauth.is_some() && !remote {
+            checker.warn(
+                format!("{prefix}.oauth"),
+                "OAuth only applies to sse and http transports; ignored",
+            );
+        } else if server.oauth.is_some()
+            && server
+                .headers
+                .keys()
+                .any(|h| h.eq_ignore_ascii_case("authorization"))
+        {
+            checker.warn(
+                format!("{prefix}.headers.Authorization"),
+                "replaced by the OAuth bearer token",
+            );
+        }
+        if let Some(tls) = &server.tls {
+            if !remote {
+                checker.warn(
+                    format!("{prefix}.tls"),
+                    "TLS settings only apply to sse and http transports; ignored",
+                );
+            } else if tls.client_cert.is_some() != tls.client_key.is_some() {
+                checker.error(
+                    format!("{prefix}.tls"),
+                    "client_cert and client_key must be set together",
+                );
+            }
+            for (key, path) in [
+                ("ca_cert", &tls.ca_cert),
+                ("client_cert", &tls.client_cert),
+                ("client_key", &tls.client_key),
+            ] {
+                if let Some(path) = path {
+                    if remote && !std::path::Path::new(shellexpand::tilde(path).as_ref()).is_file()
+                    {
+                        checker.error(format!("{prefix}.tls.{key}"), format!("no file at {path}"));
+                    }
+                }
+            }
+            if remote && tls.danger_accept_invalid_certs {
+                checker.warn(
+                    format!("{prefix}.tls.danger_accept_invalid_certs"),
+                    "server certificates are not checked; use only for testing",
+                );
+            }
+        }
+        if server.isolated && server.transport != "stdio" {
+            checker.warn(
+                format!("{prefix}.isolated"),
+                "only stdio servers can run in the sandbox; ignored",
+            );
+        } else if server.isolated && config.runtime.kind != "isolated" {
+            checker.error(
+                format!("{prefix}.isolated"),
+                "isolated MCP servers require runtime.kind = \"isolated\"",
+            );
+        }
+        if server.max_output_bytes == 0
+            && server.output_strategy != crate::mcp::config::McpOutputStrategy::Truncate
+        {
+            checker.warn(
+                format!("{prefix}.output_strategy"),
+                "only applies with max_output_bytes set; ignored",
+            );
+        }
+        if let Some(name_prefix) = &server.name_prefix {
+            let valid = !name_prefix.is_empty()
+                && name_prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid {
+                checker.error(
+                    format!("{prefix}.name_prefix"),
+                    "use only ASCII letters, digits, '_' and '-'",
+                );
+            } else if let Some(other) =
+                config.mcp.servers.iter().find(|(other, s)| {
+                    *other != name && s.name_prefix.as_deref() == Some(name_prefix)
+                })
+            {
+                checker.error(
+                    format!("{prefix}.name_prefix"),
+                    format!("also used by [mcp.servers.{}]", other.0),
+                );
+            }
+        }
+        for (key, patterns) in [
+            ("include_tools", &server.include_tools),
+            ("exclude_tools", &server.exclude_tools),
+        ] {
+            for pattern in patterns {
+                if let Err(e) = glob::Pattern::new(pattern) {
+                    checker.error(
+                        format!("{prefix}.{key}"),
+                        format!("invalid glob \"{pattern}\": {e}"),
+                    );
+                }
+            }
+        }
+        if server.timeout_secs == 0 {
+            checker.error(
+                format!("{prefix}.timeout_secs"),
+                "timeout must be greater than 0",
+            );
+        }
+    }
+    let mut names: Vec<&String> = config.mcp.profiles.keys().collect();
+    names.sort();
+    // Channel → the profile its conversations start with.
+    let mut channel_profiles: Vec<(&str, &str)> = Vec::new();
+    for name in names {
+        let profile = &config.mcp.profiles[name];
+        let prefix = format!("mcp.profiles.{name}");
+        if name == crate::mcp::profiles::ALL {
+            checker.error(
+                prefix.clone(),
+                "\"all\" is reserved for every server (/profile all)",
+            );
+        }
+        for server in &profile.servers {
+            if !config.mcp.servers.contains_key(server) {
+                checker.warn(
+                    format!("{prefix}.servers"),
+                    format!("no [mcp.servers.{server}] is defined"),
+                );
+            }
+        }
+        for channel in &profile.channels {
+            match channel_profiles.iter().find(|(c, _)| c == channel) {
+                Some((_, first)) => checker.warn(
+                    format!("{prefix}.channels"),
+                    format!(
+                        "\"{channel}\" is also listed by [mcp.profiles.{first}], which is used"
+                    ),
+                ),
+                None => channel_profiles.push((channel, name)),
+            }
+        }
+    }
+}
+
+fn check_postprocess(config: &Config, checker: &mut Checker) {
+    for (i, pattern) in config.postprocess.redact_patterns.iter().enumerate() {
+        if let Err(e) = Regex::new(pattern) {
+            checker.error(
+                format!("postprocess.redact_patterns[{i}]"),
+                format!("invalid regex: {e}"),
+            );
+        }
+    }
+}
+
+fn check_tool_policy(config: &Config, checker: &mut Checker) {
+    for (name, agent) in &config.agents {
+        if let Some(set) = agent.policy.as_deref() {
+            if !config.tool_policy.sets.contains_key(set) {
+                checker.error(
+                    format!("agents.{name}.policy"),
+                    format!("no [tool_policy.sets.{set}] is defined"),
+                );
+            }
+        }
+    }
+}
+
+fn check_workspaces(config: &Config, checker: &mut Checker) {
+    for (name, ws) in &config.workspaces {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            checker.error(
+                format!("workspaces.{name}"),
+                "workspace names may only use letters, digits, '-' and '_'",
+            );
+        }
+        if ws.routes.is_empty() {
+            checker.warn(
+                format!("workspaces.{name}.routes"),
+                "no routes; this workspace never receives messages",
+            );
+        }
+        if let Some(set) = ws.policy.as_deref() {
+            if !config.tool_policy.sets.contains_key(set) {
+                checker.error(
+                    format!("workspaces.{name}.policy"),
+                    format!("no [tool_policy.sets.{set}] is defined"),
+                );
+            }
+        }
+        for server in ws.mcp_servers.iter().flatten() {
+            if !config.mcp.servers.contains_key(server) {
+                checker.warn(
+                    format!("workspaces.{name}.mcp_servers"),
+                    format!("no [mcp.servers.{server}] is defined"),
+                );
+            }
+        }
+    }
+    if let Err(e) = crate::channels::workspaces::WorkspaceRouter::from_config(config) {
+        checker.error("workspaces", e.to_string());
+    }
+}
+
+fn check_events(config: &Config, checker: &mut Checker) {
+    for (name, hook) in &config.events.hooks {
+        let key = format!("events.hooks.{name}");
+        for event in &hook.on {
+            if event != "*" && !crate::events::EVENT_NAMES.contains(&event.as_str()) {
+                checker.error(
+                    format!("{key}.on"),
+                    format!(
+                        "unknown event \"{event}\" (expected one of: {}, or \"*\")",
+                        crate::events::EVENT_NAMES.join(", ")
+                    ),
+                );
+            }
+        }
+        if hook.on.is_empty() {
+            checker.warn(format!("{key}.on"), "no events; this hook never runs");
+        }
+        let actions = [
+            hook.command.is_some(),
+            hook.url.is_some(),
+            hook.tool.is_some(),
+        ];
+        if actions.iter().filter(|set| **set).count() != 1 {
+            checker.error(key.clone(), "set exactly one of command, url or tool");
+        }
+        if let Some(url) = hook.url.as_deref() {
+            checker.url(format!("{key}.url"), url);
+        }
+    }
+}
+
+/// Resolve `command` the way a subprocess spawn would: paths are checked
+/// directly, bare names are searched in `PATH`.
+fn find_command(command: &str) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let search = std::env::var_os("PATH")?;
+    std::env::split_paths(&search).find_map(|dir| {
+        let candidate = dir.join(command);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let exe = candidate.with_extension("exe");
+        exe.is_file().then_some(exe)
+    })
+}
+
+fn line_of_offset(raw: &str, offset: usize) -> usize {
+    raw[..offset.min(raw.len())].matches('\n').count() + 1
+}
+
+/// Maps dotted key paths (`mcp.servers.github.command`) to the line they are
+/// defined on, from `[table]` headers and `key = value` lines.
+struct KeyLines(HashMap<String, usize>);
+
+impl KeyLines {
+    fn index(raw: &str) -> Self {
+        let mut map = HashMap::new();
+        let mut table = String::new();
+        for (idx, line) in raw.lines().enumerate() {
+            let line_no = idx + 1;
+            let trimmed = line.trim();
+            if trimmed.starts_with('#') || trimmed.is_empty() {
+                continue;
+            }
+            if let Some(header) = trimmed
+                .strip_prefix("[[")
+                .and_then(|rest| rest.split("]]").next())
+                .or_else(|| {
+                    trimmed
+                        .strip_prefix('[')
+                        .and_then(|rest| rest.split(']').next())
+                })
+            {
+                table = normalize_key(header);
+                map.entry(table.clone()).or_insert(line_no);
+                continue;
+            }
+            if let Some((key, _)) = trimmed.split_once('=') {
+                let key = normalize_key(key);
+                let full = if table.is_empty() {
+                    key
+                } else {
+                    format!("{table}.{key}")
+                };
+                map.entry(full).or_insert(line_no);
+            }
+        }
+        Self(map)
+    }
+
+    /// Find the line for `key`, falling back to its closest defined parent
+    /// (array indices are dropped, so `[[model_routes]]` entries resolve to
+    /// the first route header).
+    fn lookup(&self, key: &str) -> Option<usize> {
+        let mut key = key.to_string();
+        if let Some(idx) = key.find('[') {
+            let rest = key[idx..].split_once(']').map_or("", |(_, r)| r);
+            key = format!("{}{rest}", &key[..idx]);
+        }
+        loop {
+            if let Some(line) = self.0.get(&key) {
+                return Some(*line);
+            }
+            let (parent, _) = key.rsplit_once('.')?;
+            key = parent.to_string();
+        }
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    key.split('.')
+        .map(|part| part.trim().trim_matches('"').trim_matches('\''))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(issues: &[ConfigIssue]) -> Vec<&ConfigIssue> {
+        issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .collect()
+    }
+
+    #[test]
+    fn syntax_error_reports_line() {
+        let raw = "default_temperature = 0.7\n[memory\nbackend = \"sqlite\"\n";
+        let issues = validate_config_str(raw);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+        assert_eq!(issues[0].line, Some(2));
+    }
+
+    #[test]
+    fn minimal_valid_config_has_no_errors() {
+        let raw = "default_provider = \"openrouter\"\ndefault_temperature = 0.7\n";
+        assert!(errors(&validate_config_str(raw)).is_empty());
+    }
+
+    #[test]
+    fn semantic_errors_are_located_by_key() {
+        let raw = r#"default_provider = "not-a-provider"
+default_temperature = 3.5
+
+[memory]
+backend = "postgresx"
+auto_save = true
+
+[mcp]
+enabled = true
+
+[mcp.servers.github]
+command = "definitely-not-a-real-binary-zc"
+
+[mcp.servers.remote]
+transport = "sse"
+url = "ftp://example.com"
+"#;
+        let issues = validate_config_str(raw);
+        let find = |key: &str| {
+            issues
+                .iter()
+                .find(|i| i.key == key)
+                .unwrap_or_else(|| panic!("missing issue for {key}: {issues:?}"))
+        };
+        assert_eq!(find("default_provider").line, Some(1));
+        assert_eq!(find("default_temperature").line, Some(2));
+        assert_eq!(find("memory.backend").line, Some(5));
+        assert_eq!(find("mcp.servers.github.command").line, Some(12));
+        assert!(find("mcp.servers.remote.url").message.contains("ftp"));
+        assert_eq!(find("mcp.servers.remote.url").line, Some(16));
+    }
+
+    #[test]
+    fn unknown_sections_and_unset_env_refs_warn() {
+        let raw = r#"default_temperature = 0.7
+
+[memroy]
+backend = "sqlite"
+
+[mcp]
+enabled = true
+
+[mcp.servers.tool]
+command = "sh"
+env = { TOKEN = "${ZC_VALIDATE_TEST_UNSET_VAR}" }
+"#;
+        let issues = validate_config_str(raw);
+        let memroy = issues.iter().find(|i| i.key == "memroy").unwrap();
+        assert_eq!(memroy.severity, IssueSeverity::Warning);
+        assert_eq!(memroy.line, Some(3));
+        let env = issues
+            .iter()
+            .find(|i| i.key == "mcp.servers.tool.env.TOKEN")
+            .unwrap();
+        assert_eq!(env.severity, IssueSeverity::Warning);
+        assert_eq!(env.line, Some(11));
+    }
+
+    #[test]
+    fn isolated_mcp_servers_need_isolated_runtime() {
+        let raw = r#"default_temperature = 0.7
+
+[mcp]
+enabled = true
+
+[mcp.servers.sandboxed]
+command = "only-inside-the-image-zc"
+isolated = true
+"#;
+        let issues = validate_config_str(raw);
+        assert!(!issues
+            .iter()
+            .any(|i| i.key == "mcp.servers.sandboxed.command"));
+        let isolated = issues
+            .iter()
+            .find(|i| i.key == "mcp.servers.sandboxed.isolated")
+            .unwrap();
+        assert_eq!(isolated.severity, IssueSeverity::Error);
+
+        let raw = format!("{raw}\n[runtime]\nkind = \"isolated\"\n");
+        let issues = validate_config_str(&raw);
+        assert!(!issues.iter().any(|i| i.key.starts_with("mcp.servers")));
+    }
+
+    #[test]
+    fn mcp_oauth_needs_a_remote_transport() {
+        let raw = r#"default_temperature = 0.7
+
+[mcp]
+enabled = true
+
+[mcp.servers.local]
+command = "sh"
+oauth = {}
+
+[mcp.servers.tracker]
+transport = "http"
+url = "https://mcp.example.com/mcp"
+headers = { Authorization = "Bearer static" }
+oauth = { scopes = ["read"] }
+"#;
+        let issues = validate_config_str(raw);
+        let keys: Vec<&str> = issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Warning)
+            .map(|i| i.key.as_str())
+            .collect();
+        assert!(keys.contains(&"mcp.servers.local.oauth"));
+        assert!(keys.contains(&"mcp.servers.tracker.headers.Authorization"));
+    }
+
+    #[test]
+    fn mcp_tls_settings_are_checked() {
+        let raw = r#"default_temperature = 0.7
+
+[mcp]
+enabled = true
+
+[mcp.servers.gateway]
+transport = "http"
+url = "https://mcp.internal/mcp"
+tls = { ca_cert = "/nonexistent/zc-ca.pem", client_key = "/nonexistent/zc-key.pem", danger_accept_invalid_certs = true }
+
+[mcp.servers.local]
+command = "sh"
+tls = { danger_accept_invalid_certs = true }
+"#;
+        let issues = validate_config_str(raw);
+        let find = |key: &str| issues.iter().find(|i| i.key == key).unwrap();
+        assert_eq!(
+            find("mcp.servers.gateway.tls").message,
+            "client_cert and client_key must be set together"
+        );
+        assert_eq!(
+            find("mcp.servers.gateway.tls.ca_cert").message,
+            "no file at /nonexistent/zc-ca.pem"
+        );
+        assert_eq!(
+            find("mcp.servers.gateway.tls.danger_accept_invalid_certs").severity,
+            IssueSeverity::Warning
+        );
+        assert_eq!(
+            find("mcp.servers.local.tls").severity,
+            IssueSeverity::Warning
+        );
+    }
+
+    #[test]
+    fn mcp_name_prefixes_are_valid_and_unique() {
+        let raw = r#"default_temperature = 0.7
+
+[mcp]
+enabled = true
+
+[mcp.servers.github]
+command = "sh"
+name_prefix = "gh_"
+
+[mcp.servers.gitlab]
+command = "sh"
+name_prefix = "gh_"
+
+[mcp.servers.docs]
+command = "sh"
+name_prefix = "docs."
+"#;
+        let issues = validate_config_str(raw);
+        let find = |key: &str| issues.iter().find(|i| i.key == key).unwrap();
+        assert_eq!(
+            find("mcp.servers.gitlab.name_prefix").message,
+            "also used by [mcp.servers.github]"
+        );
+        assert!(find("mcp.servers.docs.name_prefix")
+            .message
+            .contains("ASCII letters"));
+    }
+
+    #[test]
+    fn mcp_profiles_name_known_servers_and_distinct_channels() {
+        let raw = r#"default_temperature = 0.7
+
+[mcp]
+enabled = true
+
+[mcp.servers.github]
+command = "sh"
+
+[mcp.profiles.coding]
+servers = ["github"]
+channels = ["cli"]
+
+[mcp.profiles.research]
+servers = ["web"]
+channels = ["cli", "discord"]
+
+[mcp.profiles.all]
+servers = ["github"]
+"#;
+        let issues = validate_config_str(raw);
+        let find = |key: &str| issues.iter().find(|i| i.key == key).unwrap();
+        assert_eq!(
+            find("mcp.profiles.research.servers").message,
+            "no [mcp.servers.web] is defined"
+        );
+        assert_eq!(
+            find("mcp.profiles.research.channels").message,
+            "\"cli\" is also listed by [mcp.profiles.coding], which is used"
+        );
+        assert!(find("mcp.profiles.all").message.contains("reserved"));
+        assert!(!issues
+            .iter()
+            .any(|i| i.key.starts_with("mcp.profiles.coding")));
+    }
+
+    #[test]
+    fn workspace_routes_and_policy_sets_are_checked() {
+        let raw = r#"default_temperature = 0.7
+
+[workspaces.ops]
+routes = ["slack"]
+policy = "ops"
+
+[workspaces.team]
+routes = ["slack"]
+"#;
+        let issues = validate_config_str(raw);
+        let policy = issues
+            .iter()
+            .find(|i| i.key == "workspaces.ops.policy")
+            .unwrap();
+        assert_eq!(policy.severity, IssueSeverity::Error);
+        let routes = issues.iter().find(|i| i.key == "workspaces").unwrap();
+        assert!(routes
+            .message
+            .contains("claimed by workspaces ops and team"));
+    }
+
+    #[test]
+    fn event_hooks_need_known_events_and_one_action() {
+        let raw = r#"default_temperature = 0.7
+
+[events.hooks.notify]
+on = ["turn_completed", "tool_finished"]
+command = "notify-send done"
+url = "https://hooks.example.com/zeroclaw"
+"#;
+        let issues = validate_config_str(raw);
+        let on = issues
+            .iter()
+            .find(|i| i.key == "events.hooks.notify.on")
+            .unwrap();
+        assert!(on.message.contains("tool_finished"));
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "events.hooks.notify" && i.message.contains("exactly one")));
+    }
+
+    #[test]
+    fn postgres_memory_needs_a_valid_url() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"postgres\"\nauto_save = true\n";
+        let issues = validate_config_str(raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.postgres_url" && i.message.contains("no connection URL")));
+
+        let raw = format!("{raw}postgres_url = \"postgres://zc@db/zc?sslmode=sometimes\"\n");
+        let issues = validate_config_str(&raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.postgres_url" && i.message.contains("sslmode")));
+    }
+
+    #[test]
+    fn memory_budget_action_must_be_known() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nmax_memories = 500\nbudget_action = \"shred\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.budget_action" && i.message.contains("archive or delete")));
+    }
+
+    #[test]
+    fn memory_budget_is_rejected_on_postgres() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"postgres\"\nauto_save = true\npostgres_url = \"postgres://zc@db/zc\"\nmax_memories = 500\n";
+        let issues = validate_config_str(raw);
+        assert!(issues.iter().any(|i| i.key == "memory.max_memories"
+            && i.severity == IssueSeverity::Error
+            && i.message.contains("postgres backend cannot enforce")));
+    }
+
+    #[test]
+    fn memory_backup_schedule_must_parse() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nbackup_schedule = \"nightly\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.backup_schedule" && i.message.contains("cron expression")));
+    }
+
+    #[test]
+    fn reranker_needs_a_known_provider_and_key() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nreranker = \"cohere\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.reranker_api_key" && i.message.contains("API key")));
+
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nreranker = \"voyage\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues
+            .iter()
+            .any(|i| i.key == "memory.reranker" && i.message.contains("unknown reranker")));
+    }
+
+    #[test]
+    fn racing_needs_an_explicit_challenger_model() {
+        let raw = "default_temperature = 0.7\n[racing]\nenabled = true\nprovider = \"groq\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues.iter().any(|i| i.key == "racing.model"));
+
+        let raw = format!("{raw}model = \"llama-3.3-70b-versatile\"\n");
+        let issues = validate_config_str(&raw);
+        assert!(!issues.iter().any(|i| i.key.starts_with("racing.")));
+    }
+
+    #[test]
+    fn qdrant_vector_store_checks_its_url() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nvector_store = \"qdrant\"\nqdrant_url = \"localhost:6333\"\n";
+        let issues = validate_config_str(raw);
+        assert!(issues.iter().any(|i| i.key == "memory.qdrant_url"));
+
+        let raw = raw.replace("\"qdrant\"", "\"milvus\"");
+        let issues = validate_config_str(&raw);
+        assert!(issues.iter().any(|i| i.key == "memory.vector_store"
+            && i.message.contains("expected sqlite, qdrant or lancedb")));
+    }
+
+    #[test]
+    fn lancedb_vector_store_needs_the_feature() {
+        let raw = "default_temperature = 0.7\n[memory]\nbackend = \"sqlite\"\nauto_save = true\nvector_store = \"lancedb\"\n";
+        let issues = validate_config_str(raw);
+        assert_eq!(
+            issues.iter().any(|i| i.key == "memory.vector_store"
+                && i.message.contains("--features lancedb")),
+            !cfg!(feature = "lancedb")
+        );
+    }
+
+    #[test]
+    fn find_command_resolves_path_and_rejects_missing() {
+        assert!(find_command("sh").is_some());
+        assert!(find_command("/definitely/missing/binary").is_none());
+    }
+}
@@ -1,5 +1,7 @@
 pub mod tracker;
 pub mod types;
 
-pub use tracker::CostTracker;
+#[allow(unused_imports)]
+pub use tracker::{load_records, CostTracker};
+#[allow(unused_imports)]
 pub use types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
@@ -178,6 +178,14 @@ impl CostTracker {
     }
 }
 
+/// Every persisted cost record in `workspace_dir`, oldest first.
+pub fn load_records(workspace_dir: &Path) -> Result<Vec<CostRecord>> {
+    let storage = CostStorage::new(&resolve_storage_path(workspace_dir)?)?;
+    let mut records = Vec::new();
+    storage.for_each_record(|record| records.push(record))?;
+    Ok(records)
+}
+
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
     let storage_path = workspace_dir.join("state").join("costs.jsonl");
     let legacy_path = workspace_dir.join(".zeroclaw").join("costs.db");
@@ -9,14 +9,20 @@ pub mod scheduler;
 
 #[allow(unused_imports)]
 pub use schedule::{
-    next_run_for_schedule, normalize_expression, schedule_cron_expression, validate_schedule,
+    missed_runs, next_run_for_schedule, normalize_expression, schedule_cron_expression,
+    validate_schedule,
 };
+pub(crate) use store::MIGRATIONS;
 #[allow(unused_imports)]
 pub use store::{
-    add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    add_agent_job, add_job, add_pipeline_job, add_shell_job, due_jobs, get_job, list_jobs,
+    list_runs, record_last_run, record_run, remove_job, reschedule_after_run, skip_missed_runs,
+    update_job,
+};
+pub use types::{
+    CatchUp, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, PipelineStep, Schedule,
+    SessionTarget,
 };
-pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
@@ -50,6 +56,9 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 if let Some(prompt) = &job.prompt {
                     println!("    prompt: {prompt}");
                 }
+                for (idx, step) in job.steps.iter().enumerate() {
+                    println!("    step {}: {}", idx + 1, serde_json::to_string(step)?);
+                }
             }
             Ok(())
         }
@@ -147,7 +156,7 @@ pub fn resume_job(config: &Config, id: &str) -> Result<CronJob> {
     )
 }
 
-fn parse_delay(input: &str) -> Result<chrono::Duration> {
+pub(crate) fn parse_delay(input: &str) -> Result<chrono::Duration> {
     let input = input.trim();
     if input.is_empty() {
         anyhow::bail!("delay must not be empty");
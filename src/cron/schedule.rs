@@ -38,6 +38,28 @@ pub fn next_run_for_schedule(schedule: &Schedule, from: DateTime<Utc>) -> Result
     }
 }
 
+/// Number of scheduled times from `first_due` up to `now`, capped at `max`.
+/// `first_due` itself counts, so the result is at least 1.
+pub fn missed_runs(
+    schedule: &Schedule,
+    first_due: DateTime<Utc>,
+    now: DateTime<Utc>,
+    max: usize,
+) -> usize {
+    let mut count = 1;
+    let mut at = first_due;
+    while count < max {
+        match next_run_for_schedule(schedule, at) {
+            Ok(next) if next <= now && next > at => {
+                count += 1;
+                at = next;
+            }
+            _ => break,
+        }
+    }
+    count
+}
+
 pub fn validate_schedule(schedule: &Schedule, now: DateTime<Utc>) -> Result<()> {
     match schedule {
         Schedule::Cron { expr, .. } => {
@@ -1,26 +1,41 @@
 use crate::channels::{Channel, DiscordChannel, SendMessage, SlackChannel, TelegramChannel};
 use crate::config::Config;
 use crate::cron::{
-    due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    due_jobs, missed_runs, next_run_for_schedule, record_last_run, record_run, remove_job,
+    reschedule_after_run, skip_missed_runs, update_job, CatchUp, CronJob, CronJobPatch,
+    DeliveryConfig, JobType, PipelineStep, Schedule, SessionTarget,
 };
 use crate::security::SecurityPolicy;
+use crate::tools::Tool;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use std::sync::Arc;
 use tokio::process::Command;
 use tokio::time::{self, Duration};
 
 const MIN_POLL_SECONDS: u64 = 5;
+/// A job overdue by more than this was missed during downtime rather than
+/// just picked up late by the poll loop.
+const MIN_MISSED_GRACE_SECONDS: u64 = 60;
+/// Placeholder for the previous step's output in pipeline steps.
+const PIPELINE_OUTPUT_PLACEHOLDER: &str = "{{output}}";
 
 pub async fn run(config: Config) -> Result<()> {
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
     let mut interval = time::interval(Duration::from_secs(poll_secs));
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let missed_grace = chrono::Duration::seconds(
+        i64::try_from((poll_secs * 2).max(MIN_MISSED_GRACE_SECONDS)).unwrap_or(i64::MAX),
+    );
 
     crate::health::mark_component_ok("scheduler");
 
+    let shutdown = crate::shutdown::global();
     loop {
-        interval.tick().await;
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = shutdown.triggered() => return Ok(()),
+        }
 
         let jobs = match due_jobs(&config, Utc::now()) {
             Ok(jobs) => jobs,
@@ -35,19 +50,63 @@ pub async fn run(config: Config) -> Result<()> {
             crate::health::mark_component_ok("scheduler");
             warn_if_high_frequency_agent_job(&job);
 
-            let started_at = Utc::now();
-            let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-            let finished_at = Utc::now();
-            let success =
-                persist_job_result(&config, &job, success, &output, started_at, finished_at).await;
+            let runs = catch_up_runs(&config, &job, Utc::now(), missed_grace);
+            if runs == 0 {
+                tracing::info!(
+                    "Skipping runs of cron job '{}' missed since {}",
+                    job.id,
+                    job.next_run.to_rfc3339()
+                );
+                if let Err(e) = skip_missed_runs(&config, &job, Utc::now()) {
+                    tracing::warn!("Failed to reschedule cron job '{}': {e}", job.id);
+                }
+                continue;
+            }
+            if runs > 1 {
+                tracing::info!("Replaying {runs} missed runs of cron job '{}'", job.id);
+            }
 
-            if !success {
-                crate::health::mark_component_error("scheduler", format!("job {} failed", job.id));
+            for _ in 0..runs {
+                let started_at = Utc::now();
+                let (success, output) = execute_job_with_retry(&config, &security, &job).await;
+                let finished_at = Utc::now();
+                let success =
+                    persist_job_result(&config, &job, success, &output, started_at, finished_at)
+                        .await;
+
+                if !success {
+                    crate::health::mark_component_error(
+                        "scheduler",
+                        format!("job {} failed", job.id),
+                    );
+                }
             }
         }
     }
 }
 
+/// How many times to run a due job: 1 normally; after downtime, 0 (skip),
+/// 1 (once) or one per missed run (all), per the job's catch-up policy.
+fn catch_up_runs(
+    config: &Config,
+    job: &CronJob,
+    now: DateTime<Utc>,
+    missed_grace: chrono::Duration,
+) -> usize {
+    // One-shots always run, however late.
+    if matches!(job.schedule, Schedule::At { .. }) || now - job.next_run <= missed_grace {
+        return 1;
+    }
+    match job.catch_up.unwrap_or(config.cron.catch_up) {
+        CatchUp::Skip => 0,
+        CatchUp::Once => 1,
+        CatchUp::All => {
+            let max = usize::try_from(config.cron.catch_up_max_runs.max(1)).unwrap_or(usize::MAX);
+            missed_runs(&job.schedule, job.next_run, now, max)
+        }
+    }
+}
+
 pub async fn execute_job_now(config: &Config, job: &CronJob) -> (bool, String) {
     let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
     execute_job_with_retry(config, &security, job).await
@@ -66,6 +125,7 @@ async fn execute_job_with_retry(
         let (success, output) = match job.job_type {
             JobType::Shell => run_job_command(config, security, job).await,
             JobType::Agent => run_agent_job(config, job).await,
+            JobType::Pipeline => run_pipeline_job(config, security, job).await,
         };
         last_output = output;
 
@@ -89,24 +149,34 @@ async fn execute_job_with_retry(
 }
 
 async fn run_agent_job(config: &Config, job: &CronJob) -> (bool, String) {
-    let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
     let prompt = job.prompt.clone().unwrap_or_default();
-    let prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
-    let model_override = job.model.clone();
-
-    let run_result = match job.session_target {
+    match job.session_target {
         SessionTarget::Main | SessionTarget::Isolated => {
-            crate::agent::run(
-                config.clone(),
-                Some(prefixed_prompt),
-                None,
-                model_override,
-                config.default_temperature,
-                vec![],
-            )
-            .await
+            run_agent_prompt(config, &job_label(job), &prompt, job.model.clone()).await
         }
-    };
+    }
+}
+
+fn job_label(job: &CronJob) -> String {
+    let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
+    format!("cron:{} {name}", job.id)
+}
+
+async fn run_agent_prompt(
+    config: &Config,
+    label: &str,
+    prompt: &str,
+    model_override: Option<String>,
+) -> (bool, String) {
+    let run_result = crate::agent::run(
+        config.clone(),
+        Some(format!("[{label}] {prompt}")),
+        None,
+        model_override,
+        config.default_temperature,
+        vec![],
+    )
+    .await;
 
     match run_result {
         Ok(response) => (
@@ -121,6 +191,147 @@ async fn run_agent_job(config: &Config, job: &CronJob) -> (bool, String) {
     }
 }
 
+async fn run_pipeline_job(
+    config: &Config,
+    security: &SecurityPolicy,
+    job: &CronJob,
+) -> (bool, String) {
+    run_pipeline(
+        config,
+        security,
+        &job.steps,
+        &job_label(job),
+        job.model.clone(),
+        String::new(),
+    )
+    .await
+}
+
+/// Run pipeline steps in order, feeding each step's output into the next via
+/// `{{output}}` (`input` for the first step). Stops at the first failing step.
+pub(crate) async fn run_pipeline(
+    config: &Config,
+    security: &SecurityPolicy,
+    steps: &[PipelineStep],
+    label: &str,
+    default_model: Option<String>,
+    input: String,
+) -> (bool, String) {
+    if steps.is_empty() {
+        return (false, "pipeline has no steps".to_string());
+    }
+
+    let mut tools: Option<Vec<Box<dyn Tool>>> = None;
+    let mut output = input;
+    for (idx, step) in steps.iter().enumerate() {
+        let (success, step_output) = match step {
+            PipelineStep::Shell { command } => {
+                let command = command.replace(PIPELINE_OUTPUT_PLACEHOLDER, &output);
+                // Only stdout flows into the next step.
+                match spawn_shell_command(config, security, &command).await {
+                    Ok(out) if out.status.success() => (
+                        true,
+                        String::from_utf8_lossy(&out.stdout).trim().to_string(),
+                    ),
+                    Ok(out) => (false, format_shell_output(&out)),
+                    Err(message) => (false, message),
+                }
+            }
+            PipelineStep::Agent { prompt, model } => {
+                let prompt = prompt.replace(PIPELINE_OUTPUT_PLACEHOLDER, &output);
+                let model = model.clone().or_else(|| default_model.clone());
+                run_agent_prompt(config, label, &prompt, model).await
+            }
+            PipelineStep::Tool { name, args } => {
+                if tools.is_none() {
+                    match build_tool_registry(config) {
+                        Ok(registry) => tools = Some(registry),
+                        Err(e) => return (false, format!("failed to load tools: {e}")),
+                    }
+                }
+                let registry = tools.as_deref().unwrap_or_default();
+                run_tool_step(registry, name, substitute_output(args, &output)).await
+            }
+        };
+
+        if !success {
+            return (false, format!("step {} failed: {step_output}", idx + 1));
+        }
+        output = step_output;
+    }
+
+    (true, output)
+}
+
+fn substitute_output(value: &serde_json::Value, output: &str) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(s) => {
+            serde_json::Value::String(s.replace(PIPELINE_OUTPUT_PLACEHOLDER, output))
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(|v| substitute_output(v, output)).collect())
+        }
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute_output(v, output)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// The built-in tool registry, built the same way as for an agent run.
+pub(crate) fn build_tool_registry(config: &Config) -> Result<Vec<Box<dyn Tool>>> {
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let runtime: Arc<dyn crate::runtime::RuntimeAdapter> =
+        Arc::from(crate::runtime::create_runtime(&config.runtime)?);
+    let memory: Arc<dyn crate::memory::Memory> = Arc::from(crate::memory::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )?);
+    let (composio_key, composio_entity_id) = if config.composio.enabled {
+        (
+            config.composio.api_key.as_deref(),
+            Some(config.composio.entity_id.as_str()),
+        )
+    } else {
+        (None, None)
+    };
+    Ok(crate::tools::all_tools_with_runtime(
+        Arc::new(config.clone()),
+        &security,
+        runtime,
+        memory,
+        composio_key,
+        composio_entity_id,
+        &config.browser,
+        &config.http_request,
+        &config.workspace_dir,
+        &config.agents,
+        config.api_key.as_deref(),
+        config,
+    ))
+}
+
+async fn run_tool_step(
+    tools: &[Box<dyn Tool>],
+    name: &str,
+    args: serde_json::Value,
+) -> (bool, String) {
+    let Some(tool) = tools.iter().find(|tool| tool.name() == name) else {
+        return (false, format!("unknown tool: {name}"));
+    };
+    match tool.execute(args).await {
+        Ok(result) if result.success => (true, result.output),
+        Ok(result) => (false, result.error.unwrap_or(result.output)),
+        Err(e) => (false, format!("tool {name} failed: {e}")),
+    }
+}
+
 async fn persist_job_result(
     config: &Config,
     job: &CronJob,
@@ -224,6 +435,17 @@ async fn deliver_if_configured(config: &Config, job: &CronJob, output: &str) ->
         .as_deref()
         .ok_or_else(|| anyhow::anyhow!("delivery.to is required for announce mode"))?;
 
+    announce(config, channel, target, output).await
+}
+
+/// Send `output` to `target` on a configured Telegram, Discord or Slack
+/// channel.
+pub(crate) async fn announce(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    output: &str,
+) -> Result<()> {
     match channel.to_ascii_lowercase().as_str() {
         "telegram" => {
             let tg = config
@@ -331,65 +553,68 @@ async fn run_job_command(
     security: &SecurityPolicy,
     job: &CronJob,
 ) -> (bool, String) {
+    run_shell_command(config, security, &job.command).await
+}
+
+async fn run_shell_command(
+    config: &Config,
+    security: &SecurityPolicy,
+    command: &str,
+) -> (bool, String) {
+    match spawn_shell_command(config, security, command).await {
+        Ok(output) => (output.status.success(), format_shell_output(&output)),
+        Err(message) => (false, message),
+    }
+}
+
+/// Policy-check and run `command`; `Err` carries the block or spawn reason.
+async fn spawn_shell_command(
+    config: &Config,
+    security: &SecurityPolicy,
+    command: &str,
+) -> std::result::Result<std::process::Output, String> {
     if !security.can_act() {
-        return (
-            false,
-            "blocked by security policy: autonomy is read-only".to_string(),
-        );
+        return Err("blocked by security policy: autonomy is read-only".to_string());
     }
 
     if security.is_rate_limited() {
-        return (
-            false,
-            "blocked by security policy: rate limit exceeded".to_string(),
-        );
+        return Err("blocked by security policy: rate limit exceeded".to_string());
     }
 
-    if !security.is_command_allowed(&job.command) {
-        return (
-            false,
-            format!(
-                "blocked by security policy: command not allowed: {}",
-                job.command
-            ),
-        );
+    if !security.is_command_allowed(command) {
+        return Err(format!(
+            "blocked by security policy: command not allowed: {}",
+            command
+        ));
     }
 
-    if let Some(path) = forbidden_path_argument(security, &job.command) {
-        return (
-            false,
-            format!("blocked by security policy: forbidden path argument: {path}"),
-        );
+    if let Some(path) = forbidden_path_argument(security, command) {
+        return Err(format!(
+            "blocked by security policy: forbidden path argument: {path}"
+        ));
     }
 
     if !security.record_action() {
-        return (
-            false,
-            "blocked by security policy: action budget exhausted".to_string(),
-        );
+        return Err("blocked by security policy: action budget exhausted".to_string());
     }
 
-    let output = Command::new("sh")
-        .arg("-lc")
-        .arg(&job.command)
-        .current_dir(&config.workspace_dir)
-        .output()
-        .await;
-
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let combined = format!(
-                "status={}\nstdout:\n{}\nstderr:\n{}",
-                output.status,
-                stdout.trim(),
-                stderr.trim()
-            );
-            (output.status.success(), combined)
-        }
-        Err(e) => (false, format!("spawn error: {e}")),
-    }
+    let mut cmd = Command::new("sh");
+    cmd.arg("-lc")
+        .arg(command)
+        .current_dir(&config.workspace_dir);
+    crate::security::limits::apply(&mut cmd);
+    cmd.output().await.map_err(|e| format!("spawn error: {e}"))
+}
+
+fn format_shell_output(output: &std::process::Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    format!(
+        "status={}\nstdout:\n{}\nstderr:\n{}",
+        output.status,
+        stdout.trim(),
+        stderr.trim()
+    )
 }
 
 #[cfg(test)]
@@ -428,6 +653,8 @@ mod tests {
             enabled: true,
             delivery: DeliveryConfig::default(),
             delete_after_run: false,
+            steps: Vec::new(),
+            catch_up: None,
             created_at: Utc::now(),
             next_run: Utc::now(),
             last_run: None,
@@ -568,6 +795,60 @@ mod tests {
         assert!(output.contains("agent job failed:"));
     }
 
+    #[tokio::test]
+    async fn pipeline_feeds_each_step_output_into_the_next() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+        let mut job = test_job("");
+        job.job_type = JobType::Pipeline;
+        job.steps = vec![
+            PipelineStep::Shell {
+                command: "echo digest".into(),
+            },
+            PipelineStep::Shell {
+                command: "echo got-{{output}}".into(),
+            },
+        ];
+
+        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
+        assert!(success, "{output}");
+        assert_eq!(output, "got-digest");
+
+        job.steps.push(PipelineStep::Tool {
+            name: "no_such_tool".into(),
+            args: serde_json::json!({}),
+        });
+        let (success, output) = run_pipeline_job(&config, &security, &job).await;
+        assert!(!success);
+        assert!(output.contains("step 3 failed: unknown tool: no_such_tool"));
+    }
+
+    #[test]
+    fn catch_up_policy_decides_missed_runs() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.cron.catch_up_max_runs = 4;
+        let grace = ChronoDuration::seconds(60);
+        let now = Utc::now();
+        let mut job = test_job("echo ok");
+        job.schedule = Schedule::Every { every_ms: 60_000 };
+
+        job.next_run = now - ChronoDuration::seconds(10);
+        assert_eq!(catch_up_runs(&config, &job, now, grace), 1);
+
+        job.next_run = now - ChronoDuration::minutes(30);
+        assert_eq!(catch_up_runs(&config, &job, now, grace), 1);
+        job.catch_up = Some(CatchUp::Skip);
+        assert_eq!(catch_up_runs(&config, &job, now, grace), 0);
+        job.catch_up = Some(CatchUp::All);
+        assert_eq!(catch_up_runs(&config, &job, now, grace), 4);
+
+        job.schedule = Schedule::At { at: job.next_run };
+        job.catch_up = Some(CatchUp::Skip);
+        assert_eq!(catch_up_runs(&config, &job, now, grace), 1);
+    }
+
     #[tokio::test]
     async fn persist_job_result_records_run_and_reschedules_shell_job() {
         let tmp = TempDir::new().unwrap();
@@ -1,8 +1,9 @@
 use crate::config::Config;
 use crate::cron::{
-    next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
-    CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    next_run_for_schedule, schedule_cron_expression, validate_schedule, CatchUp, CronJob,
+    CronJobPatch, CronRun, DeliveryConfig, JobType, PipelineStep, Schedule, SessionTarget,
 };
+use crate::db::Migration;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection};
@@ -99,11 +100,56 @@ pub fn add_agent_job(
     get_job(config, &id)
 }
 
+pub fn add_pipeline_job(
+    config: &Config,
+    name: Option<String>,
+    schedule: Schedule,
+    steps: Vec<PipelineStep>,
+    delivery: Option<DeliveryConfig>,
+    delete_after_run: bool,
+) -> Result<CronJob> {
+    if steps.is_empty() {
+        anyhow::bail!("Pipeline job needs at least one step");
+    }
+    let now = Utc::now();
+    validate_schedule(&schedule, now)?;
+    let next_run = next_run_for_schedule(&schedule, now)?;
+    let id = Uuid::new_v4().to_string();
+    let expression = schedule_cron_expression(&schedule).unwrap_or_default();
+    let schedule_json = serde_json::to_string(&schedule)?;
+    let delivery = delivery.unwrap_or_default();
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_jobs (
+                id, expression, command, schedule, job_type, prompt, name, session_target, model,
+                enabled, delivery, delete_after_run, created_at, next_run, steps
+             ) VALUES (?1, ?2, '', ?3, 'pipeline', NULL, ?4, 'isolated', NULL, 1, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                id,
+                expression,
+                schedule_json,
+                name,
+                serde_json::to_string(&delivery)?,
+                if delete_after_run { 1 } else { 0 },
+                now.to_rfc3339(),
+                next_run.to_rfc3339(),
+                serde_json::to_string(&steps)?,
+            ],
+        )
+        .context("Failed to insert cron pipeline job")?;
+        Ok(())
+    })?;
+
+    get_job(config, &id)
+}
+
 pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    steps, catch_up
              FROM cron_jobs ORDER BY next_run ASC",
         )?;
 
@@ -121,7 +167,8 @@ pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    steps, catch_up
              FROM cron_jobs WHERE id = ?1",
         )?;
 
@@ -152,7 +199,8 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    steps, catch_up
              FROM cron_jobs WHERE enabled = 1 AND next_run <= ?1 ORDER BY next_run ASC",
         )?;
 
@@ -200,6 +248,12 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     if let Some(delete_after_run) = patch.delete_after_run {
         job.delete_after_run = delete_after_run;
     }
+    if let Some(steps) = patch.steps {
+        job.steps = steps;
+    }
+    if let Some(catch_up) = patch.catch_up {
+        job.catch_up = Some(catch_up);
+    }
 
     if schedule_changed {
         job.next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
@@ -210,8 +264,8 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
             "UPDATE cron_jobs
              SET expression = ?1, command = ?2, schedule = ?3, job_type = ?4, prompt = ?5, name = ?6,
                  session_target = ?7, model = ?8, enabled = ?9, delivery = ?10, delete_after_run = ?11,
-                 next_run = ?12
-             WHERE id = ?13",
+                 next_run = ?12, steps = ?13, catch_up = ?14
+             WHERE id = ?15",
             params![
                 job.expression,
                 job.command,
@@ -225,6 +279,8 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
                 serde_json::to_string(&job.delivery)?,
                 if job.delete_after_run { 1 } else { 0 },
                 job.next_run.to_rfc3339(),
+                encode_steps(&job.steps)?,
+                job.catch_up.map(CatchUp::as_str),
                 job.id,
             ],
         )
@@ -283,6 +339,20 @@ pub fn reschedule_after_run(
     })
 }
 
+/// Drop runs missed during downtime (`catch_up = "skip"`): move `next_run`
+/// to the first scheduled time after `now` without running the job.
+pub fn skip_missed_runs(config: &Config, job: &CronJob, now: DateTime<Utc>) -> Result<()> {
+    let next_run = next_run_for_schedule(&job.schedule, now)?;
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET next_run = ?1 WHERE id = ?2",
+            params![next_run.to_rfc3339(), job.id],
+        )
+        .context("Failed to skip missed cron runs")?;
+        Ok(())
+    })
+}
+
 pub fn record_run(
     config: &Config,
     job_id: &str,
@@ -401,9 +471,30 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
         },
         last_status: row.get(15)?,
         last_output: row.get(16)?,
+        steps: decode_steps(row.get::<_, Option<String>>(17)?.as_deref())
+            .map_err(sql_conversion_error)?,
+        catch_up: row
+            .get::<_, Option<String>>(18)?
+            .as_deref()
+            .and_then(CatchUp::parse),
     })
 }
 
+fn encode_steps(steps: &[PipelineStep]) -> Result<Option<String>> {
+    if steps.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(steps)?))
+}
+
+fn decode_steps(steps_raw: Option<&str>) -> Result<Vec<PipelineStep>> {
+    match steps_raw.map(str::trim) {
+        Some(raw) if !raw.is_empty() => serde_json::from_str(raw)
+            .with_context(|| format!("Failed to parse cron pipeline steps JSON: {raw}")),
+        _ => Ok(Vec::new()),
+    }
+}
+
 fn decode_schedule(schedule_raw: Option<&str>, expression: &str) -> Result<Schedule> {
     if let Some(raw) = schedule_raw {
         let trimmed = raw.trim();
@@ -452,19 +543,23 @@ fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Resul
     Ok(())
 }
 
-fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
-    let db_path = config.workspace_dir.join("cron").join("jobs.db");
-    if let Some(parent) = db_path.parent() {
-        std::fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create cron directory: {}", parent.display()))?;
-    }
-
-    let conn = Connection::open(&db_path)
-        .with_context(|| format!("Failed to open cron DB: {}", db_path.display()))?;
-
+/// Schema history of `cron/jobs.db`; see [`crate::db`].
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create cron_jobs and cron_runs",
+        up: create_base_schema,
+    },
+    Migration {
+        version: 2,
+        name: "add cron_jobs.steps and cron_jobs.catch_up",
+        up: add_pipeline_and_catch_up,
+    },
+];
+
+fn create_base_schema(conn: &Connection) -> Result<()> {
     conn.execute_batch(
-        "PRAGMA foreign_keys = ON;
-         CREATE TABLE IF NOT EXISTS cron_jobs (
+        "CREATE TABLE IF NOT EXISTS cron_jobs (
             id               TEXT PRIMARY KEY,
             expression       TEXT NOT NULL,
             command          TEXT NOT NULL,
@@ -500,15 +595,37 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
     )
     .context("Failed to initialize cron schema")?;
 
-    add_column_if_missing(&conn, "schedule", "TEXT")?;
-    add_column_if_missing(&conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
-    add_column_if_missing(&conn, "prompt", "TEXT")?;
-    add_column_if_missing(&conn, "name", "TEXT")?;
-    add_column_if_missing(&conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
-    add_column_if_missing(&conn, "model", "TEXT")?;
-    add_column_if_missing(&conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
-    add_column_if_missing(&conn, "delivery", "TEXT")?;
-    add_column_if_missing(&conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+    // Databases from before versioning may lack columns added over time.
+    add_column_if_missing(conn, "schedule", "TEXT")?;
+    add_column_if_missing(conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
+    add_column_if_missing(conn, "prompt", "TEXT")?;
+    add_column_if_missing(conn, "name", "TEXT")?;
+    add_column_if_missing(conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
+    add_column_if_missing(conn, "model", "TEXT")?;
+    add_column_if_missing(conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
+    add_column_if_missing(conn, "delivery", "TEXT")?;
+    add_column_if_missing(conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+    Ok(())
+}
+
+fn add_pipeline_and_catch_up(conn: &Connection) -> Result<()> {
+    add_column_if_missing(conn, "steps", "TEXT")?;
+    add_column_if_missing(conn, "catch_up", "TEXT")?;
+    Ok(())
+}
+
+fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = config.workspace_dir.join("cron").join("jobs.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create cron directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open cron DB: {}", db_path.display()))?;
+
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    crate::db::migrate(&conn, &db_path, MIGRATIONS)?;
 
     f(&conn)
 }
@@ -555,6 +672,45 @@ mod tests {
         assert!(list_jobs(&config).unwrap().is_empty());
     }
 
+    #[test]
+    fn pipeline_steps_and_catch_up_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let steps = vec![
+            PipelineStep::Shell {
+                command: "echo hi".into(),
+            },
+            PipelineStep::Agent {
+                prompt: "Summarize: {{output}}".into(),
+                model: None,
+            },
+        ];
+        let job = add_pipeline_job(
+            &config,
+            Some("digest".into()),
+            Schedule::Cron {
+                expr: "0 9 * * *".into(),
+                tz: None,
+            },
+            steps.clone(),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(job.job_type, JobType::Pipeline);
+
+        let patch = CronJobPatch {
+            catch_up: Some(CatchUp::All),
+            ..CronJobPatch::default()
+        };
+        update_job(&config, &job.id, patch).unwrap();
+
+        let stored = get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.steps, steps);
+        assert_eq!(stored.catch_up, Some(CatchUp::All));
+    }
+
     #[test]
     fn due_jobs_filters_by_timestamp_and_enabled() {
         let tmp = TempDir::new().unwrap();
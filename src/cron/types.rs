@@ -7,6 +7,8 @@ pub enum JobType {
     #[default]
     Shell,
     Agent,
+    /// Ordered [`PipelineStep`]s; each step sees the previous step's output.
+    Pipeline,
 }
 
 impl JobType {
@@ -14,18 +16,73 @@ impl JobType {
         match self {
             Self::Shell => "shell",
             Self::Agent => "agent",
+            Self::Pipeline => "pipeline",
         }
     }
 
     pub(crate) fn parse(raw: &str) -> Self {
         if raw.eq_ignore_ascii_case("agent") {
             Self::Agent
+        } else if raw.eq_ignore_ascii_case("pipeline") {
+            Self::Pipeline
         } else {
             Self::Shell
         }
     }
 }
 
+/// One step of a pipeline job. `{{output}}` in a command, prompt or string
+/// tool argument is replaced with the previous step's output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PipelineStep {
+    Shell {
+        command: String,
+    },
+    Agent {
+        prompt: String,
+        #[serde(default)]
+        model: Option<String>,
+    },
+    Tool {
+        name: String,
+        #[serde(default)]
+        args: serde_json::Value,
+    },
+}
+
+/// What to do with runs missed while the scheduler was not running.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CatchUp {
+    /// Drop missed runs and wait for the next scheduled time.
+    Skip,
+    /// Run once for all missed runs, then resume the schedule.
+    #[default]
+    Once,
+    /// Replay every missed run, up to `cron.catch_up_max_runs`.
+    All,
+}
+
+impl CatchUp {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::Once => "once",
+            Self::All => "all",
+        }
+    }
+
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "skip" => Some(Self::Skip),
+            "once" => Some(Self::Once),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum SessionTarget {
@@ -108,6 +165,12 @@ pub struct CronJob {
     pub enabled: bool,
     pub delivery: DeliveryConfig,
     pub delete_after_run: bool,
+    /// Steps of a [`JobType::Pipeline`] job; empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<PipelineStep>,
+    /// Per-job override of `cron.catch_up`.
+    #[serde(default)]
+    pub catch_up: Option<CatchUp>,
     pub created_at: DateTime<Utc>,
     pub next_run: DateTime<Utc>,
     pub last_run: Option<DateTime<Utc>>,
@@ -137,4 +200,6 @@ pub struct CronJobPatch {
     pub model: Option<String>,
     pub session_target: Option<SessionTarget>,
     pub delete_after_run: Option<bool>,
+    pub steps: Option<Vec<PipelineStep>>,
+    pub catch_up: Option<CatchUp>,
 }
@@ -7,6 +7,9 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
+/// Grace period on top of `shutdown.drain_timeout_secs` for components to
+/// close their transports after draining.
+const SHUTDOWN_GRACE_SECONDS: u64 = 5;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
@@ -66,7 +69,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = heartbeat_cfg.clone();
-                async move { run_heartbeat_worker(cfg).await }
+                async move { Box::pin(run_heartbeat_worker(cfg)).await }
             },
         ));
     }
@@ -79,7 +82,8 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
+                // Boxed like the heartbeat: the future holds a whole Config
+                async move { Box::pin(crate::cron::scheduler::run(cfg)).await }
             },
         ));
     } else {
@@ -87,21 +91,77 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if config.memory.prune_interval_minutes > 0
+        && matches!(
+            crate::memory::classify_memory_backend(&config.memory.backend),
+            crate::memory::MemoryBackendKind::Sqlite
+                | crate::memory::MemoryBackendKind::Lucid
+                | crate::memory::MemoryBackendKind::Postgres
+        )
+    {
+        let pruner_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "memory-pruner",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = pruner_cfg.clone();
+                async move { run_memory_pruner(cfg).await }
+            },
+        ));
+    }
+
+    if config.memory.backup_schedule.is_some()
+        && matches!(
+            crate::memory::classify_memory_backend(&config.memory.backend),
+            crate::memory::MemoryBackendKind::Sqlite | crate::memory::MemoryBackendKind::Lucid
+        )
+    {
+        let backup_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "memory-backup",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = backup_cfg.clone();
+                async move { run_memory_backups(cfg).await }
+            },
+        ));
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
     println!("   Ctrl+C to stop");
 
-    tokio::signal::ctrl_c().await?;
+    crate::shutdown::install_signal_handler();
+    crate::shutdown::global().triggered().await;
     crate::health::mark_component_error("daemon", "shutdown requested");
 
-    for handle in &handles {
-        handle.abort();
-    }
-    for handle in handles {
-        let _ = handle.await;
+    // Gateway and channels drain on the same signal, then their MCP
+    // servers; give them both drain windows before aborting whatever is left.
+    let state_writer = handles.remove(0);
+    state_writer.abort();
+    let wait = Duration::from_secs(
+        config.shutdown.drain_timeout_secs + config.mcp.drain_timeout_secs + SHUTDOWN_GRACE_SECONDS,
+    );
+    let abort_handles: Vec<_> = handles.iter().map(JoinHandle::abort_handle).collect();
+    if tokio::time::timeout(wait, futures::future::join_all(handles))
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "Daemon components did not stop within {}s; aborting",
+            wait.as_secs()
+        );
+        for handle in abort_handles {
+            handle.abort();
+        }
     }
 
+    write_state_file(&state_file_path(&config)).await;
+    println!("👋 ZeroClaw daemon stopped");
+
     Ok(())
 }
 
@@ -123,19 +183,23 @@ fn spawn_state_writer(config: Config) -> JoinHandle<()> {
         let mut interval = tokio::time::interval(Duration::from_secs(STATUS_FLUSH_SECONDS));
         loop {
             interval.tick().await;
-            let mut json = crate::health::snapshot_json();
-            if let Some(obj) = json.as_object_mut() {
-                obj.insert(
-                    "written_at".into(),
-                    serde_json::json!(Utc::now().to_rfc3339()),
-                );
-            }
-            let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
-            let _ = tokio::fs::write(&path, data).await;
+            write_state_file(&path).await;
         }
     })
 }
 
+async fn write_state_file(path: &std::path::Path) {
+    let mut json = crate::health::snapshot_json();
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert(
+            "written_at".into(),
+            serde_json::json!(Utc::now().to_rfc3339()),
+        );
+    }
+    let data = serde_json::to_vec_pretty(&json).unwrap_or_else(|_| b"{}".to_vec());
+    let _ = tokio::fs::write(path, data).await;
+}
+
 fn spawn_component_supervisor<F, Fut>(
     name: &'static str,
     initial_backoff_secs: u64,
@@ -152,7 +216,12 @@ where
 
         loop {
             crate::health::mark_component_ok(name);
-            match run_component().await {
+            let result = run_component().await;
+            if crate::shutdown::global().is_triggered() {
+                tracing::info!("Daemon component '{name}' stopped");
+                return;
+            }
+            match result {
                 Ok(()) => {
                     crate::health::mark_component_error(name, "component exited unexpectedly");
                     tracing::warn!("Daemon component '{name}' exited unexpectedly");
@@ -185,24 +254,77 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     let interval_mins = config.heartbeat.interval_minutes.max(5);
     let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_mins) * 60));
 
+    let shutdown = crate::shutdown::global();
     loop {
-        interval.tick().await;
-
-        let tasks = engine.collect_tasks().await?;
-        if tasks.is_empty() {
-            continue;
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = shutdown.triggered() => return Ok(()),
         }
 
-        for task in tasks {
-            let prompt = format!("[Heartbeat Task] {task}");
-            let temp = config.default_temperature;
-            if let Err(e) =
-                crate::agent::run(config.clone(), Some(prompt), None, None, temp, vec![]).await
-            {
+        match crate::heartbeat::wake::tick(&config, &engine).await {
+            Ok(_) => crate::health::mark_component_ok("heartbeat"),
+            Err(e) => {
                 crate::health::mark_component_error("heartbeat", e.to_string());
-                tracing::warn!("Heartbeat task failed: {e}");
-            } else {
-                crate::health::mark_component_ok("heartbeat");
+                tracing::warn!("Heartbeat wake failed: {e}");
+            }
+        }
+    }
+}
+
+/// Delete expired memories and enforce `memory.max_memories` every
+/// `memory.prune_interval_minutes`
+async fn run_memory_pruner(config: Config) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        u64::from(config.memory.prune_interval_minutes) * 60,
+    ));
+
+    let shutdown = crate::shutdown::global();
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            () = shutdown.triggered() => return Ok(()),
+        }
+
+        match crate::memory::prune_expired(&config).await {
+            Ok(pruned) => {
+                crate::health::mark_component_ok("memory-pruner");
+                if pruned > 0 {
+                    tracing::info!("Pruned {pruned} expired memories");
+                }
+                if let Err(e) = crate::memory::enforce_budget(&config) {
+                    crate::health::mark_component_error("memory-pruner", e.to_string());
+                    tracing::warn!("Memory budget pass failed: {e}");
+                }
+            }
+            Err(e) => {
+                crate::health::mark_component_error("memory-pruner", e.to_string());
+                tracing::warn!("Memory pruning failed: {e}");
+            }
+        }
+    }
+}
+
+/// Back up `brain.db` on `memory.backup_schedule`
+async fn run_memory_backups(config: Config) -> Result<()> {
+    let Some(schedule) = config.memory.backup_schedule.clone() else {
+        return Ok(());
+    };
+    let shutdown = crate::shutdown::global();
+    loop {
+        let now = chrono::Local::now();
+        let next = crate::memory::backup::next_backup(&schedule, now)?;
+        crate::health::mark_component_ok("memory-backup");
+        tokio::select! {
+            () = tokio::time::sleep((next - now).to_std().unwrap_or_default()) => {}
+            () = shutdown.triggered() => return Ok(()),
+        }
+
+        let cfg = config.clone();
+        match tokio::task::spawn_blocking(move || crate::memory::backup::create(&cfg)).await? {
+            Ok(path) => tracing::info!("Backed up memory to {}", path.display()),
+            Err(e) => {
+                crate::health::mark_component_error("memory-backup", e.to_string());
+                tracing::warn!("Memory backup failed: {e:#}");
             }
         }
     }
@@ -220,6 +342,7 @@ fn has_supervised_channels(config: &Config) -> bool {
         || config.channels_config.irc.is_some()
         || config.channels_config.lark.is_some()
         || config.channels_config.dingtalk.is_some()
+        || !crate::plugins::channels(config).is_empty()
 }
 
 #[cfg(test)]
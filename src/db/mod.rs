@@ -0,0 +1,403 @@
+//! Versioned schema migrations for ZeroClaw's SQLite databases.
+//!
+//! Each database declares its migrations in version order, next to the code
+//! that owns it. Applied versions are recorded in a `schema_migrations` table
+//! inside the database. Opening a database applies whatever is pending, after
+//! copying the file to `<name>.v<version>-<timestamp>.bak`, and refuses to
+//! touch a database written by a newer ZeroClaw. `zeroclaw db status` and
+//! `zeroclaw db migrate [--dry-run]` run the same steps explicitly.
+//!
+//! Session transcripts and the audit log are append-only JSON lines files,
+//! not tables; their readers ignore unknown fields, so they need no
+//! migrations.
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use rusqlite::{
+    params, Connection, OpenFlags, OptionalExtension, Transaction, TransactionBehavior,
+};
+use std::path::{Path, PathBuf};
+
+/// One schema change. `up` runs inside a transaction.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: fn(&Connection) -> Result<()>,
+}
+
+/// A database and the migrations that build its schema.
+pub struct Database {
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub migrations: &'static [Migration],
+}
+
+/// Every migrated database in the workspace.
+pub fn databases(config: &Config) -> Vec<Database> {
+    let workspace = &config.workspace_dir;
+    vec![
+        Database {
+            name: "memory",
+            path: workspace.join("memory").join("brain.db"),
+            migrations: crate::memory::sqlite::MIGRATIONS,
+        },
+        Database {
+            name: "response cache",
+            path: workspace.join("memory").join("response_cache.db"),
+            migrations: crate::memory::response_cache::MIGRATIONS,
+        },
+        Database {
+            name: "scheduler",
+            path: workspace.join("cron").join("jobs.db"),
+            migrations: crate::cron::MIGRATIONS,
+        },
+    ]
+}
+
+fn latest(migrations: &[Migration]) -> u32 {
+    migrations.last().map_or(0, |m| m.version)
+}
+
+/// The highest applied version, or 0 for a database that predates
+/// migrations (or is new).
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+            [],
+            |_| Ok(true),
+        )
+        .optional()?
+        .unwrap_or(false);
+    if !has_table {
+        return Ok(0);
+    }
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn pending(migrations: &[Migration], current: u32) -> impl Iterator<Item = &Migration> {
+    migrations.iter().filter(move |m| m.version > current)
+}
+
+fn has_user_tables(conn: &Connection) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != 'schema_migrations'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0)
+}
+
+fn check_not_newer(path: &Path, current: u32, migrations: &[Migration]) -> Result<()> {
+    let latest = latest(migrations);
+    if current > latest {
+        bail!(
+            "{} is at schema version {current} but this zeroclaw only knows up to {latest}; \
+             upgrade zeroclaw instead of opening it with an older build",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Copy the database next to itself before changing its schema.
+fn backup(conn: &Connection, path: &Path, version: u32) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "database".into());
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let target = path.with_file_name(format!("{file_name}.v{version}-{stamp}.bak"));
+    // VACUUM INTO writes a consistent copy, including pages still in the WAL.
+    conn.execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+        .with_context(|| format!("Failed to back up {} before migrating", path.display()))?;
+    Ok(target)
+}
+
+/// Apply pending migrations to `conn` (opened from `path`). Returns the
+/// versions applied.
+pub fn migrate(conn: &Connection, path: &Path, migrations: &[Migration]) -> Result<Vec<u32>> {
+    debug_assert!(
+        migrations.windows(2).all(|w| w[0].version < w[1].version),
+        "migrations must be in ascending version order"
+    );
+    let current = current_version(conn)?;
+    check_not_newer(path, current, migrations)?;
+    if pending(migrations, current).next().is_none() {
+        return Ok(Vec::new());
+    }
+
+    if path.exists() && has_user_tables(conn)? {
+        let copy = backup(conn, path, current)?;
+        tracing::info!(
+            "Backed up {} to {} before migrating",
+            path.display(),
+            copy.display()
+        );
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version    INTEGER PRIMARY KEY,
+            name       TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        );",
+    )?;
+
+    let mut applied = Vec::new();
+    for migration in pending(migrations, current) {
+        // IMMEDIATE takes the write lock first, so a second process opening
+        // the same database waits and then sees the migration as applied.
+        let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+        let done: bool = tx
+            .query_row(
+                "SELECT 1 FROM schema_migrations WHERE version = ?1",
+                params![migration.version],
+                |_| Ok(true),
+            )
+            .optional()?
+            .unwrap_or(false);
+        if done {
+            continue;
+        }
+        (migration.up)(&tx).with_context(|| {
+            format!(
+                "Migration {} ({}) failed on {}",
+                migration.version,
+                migration.name,
+                path.display()
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![
+                migration.version,
+                migration.name,
+                chrono::Local::now().to_rfc3339()
+            ],
+        )?;
+        tx.commit()?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// Schema version of a database file without modifying it; `None` when it
+/// does not exist yet.
+fn version_on_disk(path: &Path) -> Result<Option<u32>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    Ok(Some(current_version(&conn)?))
+}
+
+fn report(db: &Database) -> Result<u32> {
+    if db.path.exists() && !crate::memory::cipher::is_plaintext(&db.path) {
+        println!(
+            "- {}: encrypted, migrated when zeroclaw opens it ({})",
+            db.name,
+            db.path.display()
+        );
+        return Ok(0);
+    }
+    let Some(current) = version_on_disk(&db.path)? else {
+        println!("- {}: not created yet ({})", db.name, db.path.display());
+        return Ok(0);
+    };
+    check_not_newer(&db.path, current, db.migrations)?;
+    let pending: Vec<&Migration> = pending(db.migrations, current).collect();
+    println!(
+        "- {}: version {current} of {} ({})",
+        db.name,
+        latest(db.migrations),
+        db.path.display()
+    );
+    for migration in &pending {
+        println!("    pending {}: {}", migration.version, migration.name);
+    }
+    Ok(u32::try_from(pending.len()).unwrap_or(u32::MAX))
+}
+
+/// Print the schema version and pending migrations of each database.
+pub fn print_status(dbs: &[Database]) -> Result<()> {
+    println!("🗄️  Database schemas:");
+    let mut pending = 0;
+    for db in dbs {
+        pending += report(db)?;
+    }
+    if pending == 0 {
+        println!("All databases are up to date.");
+    } else {
+        println!("{pending} migration(s) pending; run `zeroclaw db migrate` to apply (a backup is taken first).");
+    }
+    Ok(())
+}
+
+/// Apply pending migrations to each database that exists and is plaintext.
+pub fn apply(dbs: &[Database]) -> Result<()> {
+    for db in dbs {
+        if !db.path.exists() {
+            println!("- {}: not created yet, skipped", db.name);
+            continue;
+        }
+        if !crate::memory::cipher::is_plaintext(&db.path) {
+            println!("- {}: encrypted, migrated when zeroclaw opens it", db.name);
+            continue;
+        }
+        let conn = Connection::open(&db.path)
+            .with_context(|| format!("Failed to open {}", db.path.display()))?;
+        let applied = migrate(&conn, &db.path, db.migrations)?;
+        if applied.is_empty() {
+            println!("- {}: up to date", db.name);
+        } else {
+            println!(
+                "- {}: applied {}",
+                db.name,
+                applied
+                    .iter()
+                    .map(u32::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    }
+    println!("✅ Migrations complete");
+    Ok(())
+}
+
+pub fn handle_command(command: crate::DbCommands, config: &Config) -> Result<()> {
+    let dbs = databases(config);
+    match command {
+        crate::DbCommands::Status | crate::DbCommands::Migrate { dry_run: true } => {
+            print_status(&dbs)
+        }
+        crate::DbCommands::Migrate { dry_run: false } => apply(&dbs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_notes(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS notes (id INTEGER PRIMARY KEY, body TEXT);",
+        )?;
+        Ok(())
+    }
+
+    fn add_pinned(conn: &Connection) -> Result<()> {
+        conn.execute_batch("ALTER TABLE notes ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;")?;
+        Ok(())
+    }
+
+    const V1: &[Migration] = &[Migration {
+        version: 1,
+        name: "create notes",
+        up: create_notes,
+    }];
+
+    const V2: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "create notes",
+            up: create_notes,
+        },
+        Migration {
+            version: 2,
+            name: "add notes.pinned",
+            up: add_pinned,
+        },
+    ];
+
+    fn backups(dir: &Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".bak"))
+            .collect()
+    }
+
+    #[test]
+    fn applies_pending_migrations_once_with_backup() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.db");
+        let conn = Connection::open(&path).unwrap();
+
+        assert_eq!(migrate(&conn, &path, V1).unwrap(), vec![1]);
+        assert!(backups(tmp.path()).is_empty(), "nothing to back up yet");
+        conn.execute("INSERT INTO notes (body) VALUES ('keep me')", [])
+            .unwrap();
+
+        assert_eq!(migrate(&conn, &path, V2).unwrap(), vec![2]);
+        assert_eq!(current_version(&conn).unwrap(), 2);
+        assert!(migrate(&conn, &path, V2).unwrap().is_empty());
+
+        let backups = backups(tmp.path());
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("notes.db.v1-"));
+        let copy = Connection::open(tmp.path().join(&backups[0])).unwrap();
+        let body: String = copy
+            .query_row("SELECT body FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(body, "keep me");
+        assert_eq!(current_version(&copy).unwrap(), 1);
+    }
+
+    #[test]
+    fn unversioned_database_is_backed_up_and_baselined() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.db");
+        let conn = Connection::open(&path).unwrap();
+        create_notes(&conn).unwrap();
+
+        assert_eq!(migrate(&conn, &path, V2).unwrap(), vec![1, 2]);
+        assert_eq!(backups(tmp.path()).len(), 1);
+    }
+
+    #[test]
+    fn refuses_database_from_newer_build() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.db");
+        let conn = Connection::open(&path).unwrap();
+        migrate(&conn, &path, V2).unwrap();
+
+        let err = migrate(&conn, &path, V1).unwrap_err();
+        assert!(err.to_string().contains("upgrade zeroclaw"));
+    }
+
+    #[test]
+    fn failed_migration_rolls_back() {
+        fn broken(conn: &Connection) -> Result<()> {
+            conn.execute_batch("ALTER TABLE notes ADD COLUMN tag TEXT;")?;
+            bail!("boom")
+        }
+        let migrations = &[
+            Migration {
+                version: 1,
+                name: "create notes",
+                up: create_notes,
+            },
+            Migration {
+                version: 2,
+                name: "broken",
+                up: broken,
+            },
+        ];
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("notes.db");
+        let conn = Connection::open(&path).unwrap();
+
+        assert!(migrate(&conn, &path, migrations).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        assert!(conn.prepare("SELECT tag FROM notes").is_err());
+    }
+}
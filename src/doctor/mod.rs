@@ -3,11 +3,16 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
 
 const DAEMON_STALE_SECONDS: i64 = 30;
 const SCHEDULER_STALE_SECONDS: i64 = 120;
 const CHANNEL_STALE_SECONDS: i64 = 300;
 const COMMAND_VERSION_PREVIEW_CHARS: usize = 60;
+const NETWORK_CHECK_TIMEOUT_SECS: u64 = 15;
+const CLOCK_SKEW_WARN_SECONDS: i64 = 30;
+const CLOCK_SKEW_ERROR_SECONDS: i64 = 300;
+const CLOCK_REFERENCE_URL: &str = "https://www.cloudflare.com";
 
 // ── Diagnostic item ──────────────────────────────────────────────
 
@@ -22,6 +27,8 @@ struct DiagItem {
     severity: Severity,
     category: &'static str,
     message: String,
+    /// Suggested remedy, printed under warnings and errors.
+    fix: Option<String>,
 }
 
 impl DiagItem {
@@ -30,6 +37,7 @@ impl DiagItem {
             severity: Severity::Ok,
             category,
             message: msg.into(),
+            fix: None,
         }
     }
     fn warn(category: &'static str, msg: impl Into<String>) -> Self {
@@ -37,6 +45,7 @@ impl DiagItem {
             severity: Severity::Warn,
             category,
             message: msg.into(),
+            fix: None,
         }
     }
     fn error(category: &'static str, msg: impl Into<String>) -> Self {
@@ -44,9 +53,15 @@ impl DiagItem {
             severity: Severity::Error,
             category,
             message: msg.into(),
+            fix: None,
         }
     }
 
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
     fn icon(&self) -> &'static str {
         match self.severity {
             Severity::Ok => "✅",
@@ -58,13 +73,22 @@ impl DiagItem {
 
 // ── Public entry point ───────────────────────────────────────────
 
-pub fn run(config: &Config) -> Result<()> {
+/// Run all diagnostics. Network checks (provider auth, channel tokens,
+/// MCP servers, clock skew) are skipped when `offline` is set.
+pub async fn run(config: &Config, offline: bool) -> Result<()> {
     let mut items: Vec<DiagItem> = Vec::new();
 
     check_config_semantics(config, &mut items);
     check_workspace(config, &mut items);
+    check_memory_db(config, &mut items);
     check_daemon_state(config, &mut items);
     check_environment(&mut items);
+    if !offline {
+        check_provider_connectivity(config, &mut items).await;
+        check_channel_tokens(config, &mut items).await;
+        check_mcp_servers(config, &mut items).await;
+        check_clock_skew(&mut items).await;
+    }
 
     // Print report
     println!("🩺 ZeroClaw Doctor (enhanced)");
@@ -77,6 +101,11 @@ pub fn run(config: &Config) -> Result<()> {
             println!("  [{current_cat}]");
         }
         println!("    {} {}", item.icon(), item.message);
+        if item.severity != Severity::Ok {
+            if let Some(fix) = &item.fix {
+                println!("       ↳ fix: {fix}");
+            }
+        }
     }
 
     let errors = items
@@ -321,10 +350,12 @@ fn check_workspace(config: &Config, items: &mut Vec<DiagItem>) {
                 format!("disk space: {avail_mb} MB available"),
             ));
         } else {
-            items.push(DiagItem::warn(
-                cat,
-                format!("low disk space: only {avail_mb} MB available"),
-            ));
+            items.push(
+                DiagItem::warn(cat, format!("low disk space: only {avail_mb} MB available"))
+                    .with_fix(
+                        "free space on this volume or move the workspace (ZEROCLAW_WORKSPACE)",
+                    ),
+            );
         }
     }
 
@@ -380,6 +411,148 @@ fn workspace_probe_path(workspace_dir: &Path) -> std::path::PathBuf {
     ))
 }
 
+// ── Memory database integrity ────────────────────────────────────
+
+fn check_memory_db(config: &Config, items: &mut Vec<DiagItem>) {
+    use crate::memory::MemoryBackendKind;
+
+    let cat = "memory";
+    if !matches!(
+        crate::memory::classify_memory_backend(&config.memory.backend),
+        MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+    ) {
+        items.push(DiagItem::ok(
+            cat,
+            format!(
+                "backend \"{}\" has no database to check",
+                config.memory.backend
+            ),
+        ));
+        return;
+    }
+
+    let db_path = config.workspace_dir.join("memory").join("brain.db");
+    if !db_path.exists() {
+        items.push(DiagItem::warn(
+            cat,
+            format!("database not created yet: {}", db_path.display()),
+        ));
+        return;
+    }
+    let conn = match rusqlite::Connection::open_with_flags(
+        &db_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    ) {
+        Ok(conn) => conn,
+        Err(e) => {
+            items.push(
+                DiagItem::error(cat, format!("cannot open {}: {e}", db_path.display()))
+                    .with_fix("check file permissions on the workspace memory/ directory"),
+            );
+            return;
+        }
+    };
+    if let Some(source) = &config.memory.encryption_key {
+        let unlocked = crate::memory::cipher::resolve_key(source)
+            .and_then(|key| crate::memory::cipher::unlock(&conn, &db_path, &key));
+        match unlocked {
+            Ok(()) => items.push(DiagItem::ok(cat, "database is encrypted")),
+            Err(e) => {
+                items.push(
+                    DiagItem::error(cat, format!("{e:#}"))
+                        .with_fix("check memory.encryption_key and the key it points to"),
+                );
+                return;
+            }
+        }
+    } else if !crate::memory::cipher::is_plaintext(&db_path) {
+        items.push(
+            DiagItem::error(
+                cat,
+                "database is encrypted but memory.encryption_key is not set",
+            )
+            .with_fix("set memory.encryption_key to where the key is kept"),
+        );
+        return;
+    }
+    let _ = conn.busy_timeout(Duration::from_secs(5));
+
+    let embeddings_enabled = config.memory.embedding_provider.trim() != "none";
+    for item in inspect_memory_db(&conn, embeddings_enabled) {
+        items.push(item);
+    }
+}
+
+/// Integrity, FTS index, and embedding coverage checks on an open memory DB.
+fn inspect_memory_db(conn: &rusqlite::Connection, embeddings_enabled: bool) -> Vec<DiagItem> {
+    let cat = "memory";
+    let mut items = Vec::new();
+
+    match conn.query_row("PRAGMA quick_check", [], |row| row.get::<_, String>(0)) {
+        Ok(result) if result == "ok" => items.push(DiagItem::ok(cat, "database integrity ok")),
+        Ok(result) => {
+            items.push(
+                DiagItem::error(cat, format!("database integrity check failed: {result}"))
+                    .with_fix(
+                        "restore memory/brain.db from a backup, or move it aside to start fresh",
+                    ),
+            );
+            return items;
+        }
+        Err(e) => {
+            items.push(DiagItem::error(
+                cat,
+                format!("integrity check failed to run: {e}"),
+            ));
+            return items;
+        }
+    }
+
+    let fts_ok = conn.execute(
+        "INSERT INTO memories_fts(memories_fts, rank) VALUES('integrity-check', 1)",
+        [],
+    );
+    match fts_ok {
+        Ok(_) => items.push(DiagItem::ok(cat, "full-text index consistent")),
+        Err(e) => items.push(
+            DiagItem::error(cat, format!("full-text index is out of sync: {e}")).with_fix(
+                "sqlite3 memory/brain.db \"INSERT INTO memories_fts(memories_fts) VALUES('rebuild')\"",
+            ),
+        ),
+    }
+
+    let counts = conn.query_row(
+        "SELECT COUNT(*), COUNT(embedding) FROM memories",
+        [],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+    );
+    match counts {
+        Ok((0, _)) => items.push(DiagItem::ok(cat, "no memories stored yet")),
+        Ok((total, _)) if !embeddings_enabled => items.push(DiagItem::ok(
+            cat,
+            format!("{total} memories (embeddings disabled, keyword search only)"),
+        )),
+        Ok((total, embedded)) => {
+            let coverage = embedded * 100 / total;
+            let message = format!("embedding coverage {coverage}% ({embedded}/{total} memories)");
+            if embedded == total {
+                items.push(DiagItem::ok(cat, message));
+            } else {
+                items.push(DiagItem::warn(cat, message).with_fix(
+                    "memories without embeddings are found by keyword search only; \
+                     check memory.embedding_provider credentials, then re-save them",
+                ));
+            }
+        }
+        Err(e) => items.push(DiagItem::error(
+            cat,
+            format!("cannot read memories table: {e}"),
+        )),
+    }
+
+    items
+}
+
 // ── Daemon state (original logic, preserved) ─────────────────────
 
 fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
@@ -544,6 +717,192 @@ fn check_environment(items: &mut Vec<DiagItem>) {
     check_command_available("curl", &["--version"], cat, items);
 }
 
+// ── Network checks ───────────────────────────────────────────────
+
+async fn check_provider_connectivity(config: &Config, items: &mut Vec<DiagItem>) {
+    let cat = "provider";
+    let Some(name) = config.default_provider.as_deref() else {
+        return;
+    };
+    let Ok(provider) = crate::providers::create_provider_with_url(
+        name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+    ) else {
+        // Already reported as invalid by the config checks.
+        return;
+    };
+    let model = config
+        .default_model
+        .as_deref()
+        .unwrap_or("anthropic/claude-sonnet-4-20250514");
+
+    let probe = async {
+        provider.warmup().await?;
+        provider
+            .chat_with_system(None, "Reply with OK.", model, 0.0)
+            .await
+    };
+    match tokio::time::timeout(Duration::from_secs(NETWORK_CHECK_TIMEOUT_SECS), probe).await {
+        Ok(Ok(_)) => items.push(DiagItem::ok(
+            cat,
+            format!("{name} reachable and authenticated (model {model})"),
+        )),
+        Ok(Err(e)) => {
+            let reason = e.to_string();
+            let reason = truncate_for_display(reason.lines().next().unwrap_or(""), 160);
+            items.push(
+                DiagItem::error(cat, format!("{name} request failed: {reason}"))
+                    .with_fix(provider_fix_hint(&reason)),
+            );
+        }
+        Err(_) => items.push(
+            DiagItem::error(
+                cat,
+                format!("{name} did not respond within {NETWORK_CHECK_TIMEOUT_SECS}s"),
+            )
+            .with_fix("check network access, proxies, and api_url"),
+        ),
+    }
+}
+
+fn provider_fix_hint(reason: &str) -> &'static str {
+    let lower = reason.to_ascii_lowercase();
+    if lower.contains("401")
+        || lower.contains("403")
+        || lower.contains("unauthorized")
+        || lower.contains("api key")
+    {
+        "set a valid api_key (or the provider's API key env var), or run `zeroclaw onboard`"
+    } else if lower.contains("404") || lower.contains("model") {
+        "check default_model — run `zeroclaw models refresh` to list available models"
+    } else {
+        "check network access, proxies, and api_url"
+    }
+}
+
+async fn check_channel_tokens(config: &Config, items: &mut Vec<DiagItem>) {
+    let cat = "channels";
+    for (name, channel) in crate::channels::configured_channels(config) {
+        let result = tokio::time::timeout(
+            Duration::from_secs(NETWORK_CHECK_TIMEOUT_SECS),
+            channel.health_check(),
+        )
+        .await;
+        match result {
+            Ok(true) => items.push(DiagItem::ok(cat, format!("{name} credentials accepted"))),
+            Ok(false) => items.push(
+                DiagItem::error(cat, format!("{name} health check failed"))
+                    .with_fix(format!(
+                        "verify the {name} token/credentials, or re-run `zeroclaw onboard --channels-only`"
+                    )),
+            ),
+            Err(_) => items.push(
+                DiagItem::warn(
+                    cat,
+                    format!("{name} timed out after {NETWORK_CHECK_TIMEOUT_SECS}s"),
+                )
+                .with_fix("check network access to the channel's API"),
+            ),
+        }
+    }
+}
+
+async fn check_mcp_servers(config: &Config, items: &mut Vec<DiagItem>) {
+    let cat = "mcp";
+    if !config.mcp.enabled {
+        return;
+    }
+    let mut servers: Vec<_> = config.mcp.servers.iter().collect();
+    servers.sort_by_key(|(name, _)| name.as_str());
+    for (name, server) in servers {
+        let timeout = Duration::from_secs(server.timeout_secs.max(NETWORK_CHECK_TIMEOUT_SECS));
+        match tokio::time::timeout(timeout, crate::mcp::probe_server(name, server)).await {
+            Ok(Ok(tools)) => items.push(DiagItem::ok(
+                cat,
+                format!("server \"{name}\" started ({tools} tools)"),
+            )),
+            Ok(Err(e)) => {
+                let fix = match server.transport.as_str() {
+                    "stdio" => {
+                        "check mcp.servers.<name>.command/args and run it by hand to see its output"
+                    }
+                    "docker" => {
+                        "check that Docker is running and mcp.servers.<name>.docker.image exists"
+                    }
+                    _ => "check that the server is running and mcp.servers.<name>.url is correct",
+                };
+                items.push(
+                    DiagItem::error(cat, format!("server \"{name}\" failed: {e}")).with_fix(fix),
+                );
+            }
+            Err(_) => items.push(
+                DiagItem::error(
+                    cat,
+                    format!(
+                        "server \"{name}\" did not finish the handshake within {}s",
+                        timeout.as_secs()
+                    ),
+                )
+                .with_fix("make sure the command speaks MCP over stdio, or raise timeout_secs"),
+            ),
+        }
+    }
+}
+
+async fn check_clock_skew(items: &mut Vec<DiagItem>) {
+    let cat = "clock";
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_secs(NETWORK_CHECK_TIMEOUT_SECS))
+        .build()
+    else {
+        return;
+    };
+    let remote = match client.head(CLOCK_REFERENCE_URL).send().await {
+        Ok(response) => response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok()),
+        Err(e) => {
+            items.push(DiagItem::warn(
+                cat,
+                format!("could not reach time reference: {e}"),
+            ));
+            return;
+        }
+    };
+    let Some(remote) = remote else {
+        items.push(DiagItem::warn(
+            cat,
+            "time reference returned no Date header",
+        ));
+        return;
+    };
+    items.push(classify_clock_skew(
+        Utc::now()
+            .signed_duration_since(remote.with_timezone(&Utc))
+            .num_seconds(),
+    ));
+}
+
+fn classify_clock_skew(skew_secs: i64) -> DiagItem {
+    let cat = "clock";
+    let message = format!("clock skew {skew_secs:+}s vs. network time");
+    let fix = "enable NTP time sync (e.g. `timedatectl set-ntp true`)";
+    if skew_secs.abs() <= CLOCK_SKEW_WARN_SECONDS {
+        DiagItem::ok(cat, message)
+    } else if skew_secs.abs() <= CLOCK_SKEW_ERROR_SECONDS {
+        DiagItem::warn(cat, message).with_fix(fix)
+    } else {
+        DiagItem::error(
+            cat,
+            format!("{message} — signed requests and token expiry will fail"),
+        )
+        .with_fix(fix)
+    }
+}
+
 fn check_command_available(cmd: &str, args: &[&str], cat: &'static str, items: &mut Vec<DiagItem>) {
     match std::process::Command::new(cmd)
         .args(args)
@@ -737,6 +1096,51 @@ mod tests {
         assert_eq!(git_item.unwrap().severity, Severity::Ok);
     }
 
+    #[test]
+    fn memory_db_inspection_reports_fts_and_embedding_coverage() {
+        let tmp = TempDir::new().unwrap();
+        let memory = crate::memory::SqliteMemory::new(tmp.path()).unwrap();
+        drop(memory);
+        let conn = rusqlite::Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+        conn.execute(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
+             VALUES ('1', 'a', 'alpha', 'core', x'00', 'now', 'now'),
+                    ('2', 'b', 'beta', 'core', NULL, 'now', 'now')",
+            [],
+        )
+        .unwrap();
+
+        let items = inspect_memory_db(&conn, true);
+        assert!(items
+            .iter()
+            .any(|i| i.severity == Severity::Ok && i.message.contains("full-text index")));
+        let coverage = items
+            .iter()
+            .find(|i| i.message.contains("embedding coverage 50%"))
+            .unwrap();
+        assert_eq!(coverage.severity, Severity::Warn);
+        assert!(coverage.fix.is_some());
+
+        let items = inspect_memory_db(&conn, false);
+        assert!(items.iter().all(|i| i.severity == Severity::Ok));
+    }
+
+    #[test]
+    fn clock_skew_thresholds() {
+        assert_eq!(classify_clock_skew(5).severity, Severity::Ok);
+        assert_eq!(classify_clock_skew(-90).severity, Severity::Warn);
+        let bad = classify_clock_skew(3600);
+        assert_eq!(bad.severity, Severity::Error);
+        assert!(bad.fix.unwrap().contains("NTP"));
+    }
+
+    #[test]
+    fn provider_fix_hint_matches_failure_kind() {
+        assert!(provider_fix_hint("HTTP 401 Unauthorized").contains("api_key"));
+        assert!(provider_fix_hint("404: model not found").contains("default_model"));
+        assert!(provider_fix_hint("connection refused").contains("network"));
+    }
+
     #[test]
     fn parse_df_available_mb_uses_last_data_line() {
         let stdout =
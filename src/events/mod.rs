@@ -0,0 +1,416 @@
+//! Lifecycle event bus (`[events.hooks.<name>]`).
+//!
+//! The agent loop, channels, the memory tool and MCP resource subscriptions
+//! [`emit`] events — `message_received`, `turn_completed`, `tool_executed`,
+//! `memory_stored`, `resource_updated` and `error` — and every hook subscribed to one runs a script, POSTs the
+//! event to a webhook, or invokes a tool. Hooks run in the background: a
+//! slow or failing hook never delays or fails the turn that emitted it.
+
+use crate::config::{Config, EventHookConfig};
+use crate::tools::Tool;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, OnceCell};
+
+/// Event names hooks can subscribe to.
+pub const EVENT_NAMES: &[&str] = &[
+    "message_received",
+    "turn_completed",
+    "tool_executed",
+    "memory_stored",
+    "resource_updated",
+    "error",
+];
+
+/// Longest text field (message, reply, tool output) carried in an event.
+const MAX_TEXT_CHARS: usize = 4000;
+
+static BUS: OnceLock<mpsc::UnboundedSender<Envelope>> = OnceLock::new();
+
+tokio::task_local! {
+    /// Set while a hook runs, so a hook's own tool calls emit nothing.
+    static IN_HOOK: ();
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    MessageReceived {
+        channel: String,
+        sender: String,
+        content: String,
+    },
+    TurnCompleted {
+        channel: String,
+        response: String,
+        tool_calls: usize,
+    },
+    ToolExecuted {
+        channel: String,
+        tool: String,
+        arguments: Value,
+        success: bool,
+        duration_ms: u64,
+        output: String,
+    },
+    MemoryStored {
+        key: String,
+        category: String,
+    },
+    ResourceUpdated {
+        server: String,
+        uri: String,
+    },
+    Error {
+        component: String,
+        message: String,
+    },
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MessageReceived { .. } => "message_received",
+            Self::TurnCompleted { .. } => "turn_completed",
+            Self::ToolExecuted { .. } => "tool_executed",
+            Self::MemoryStored { .. } => "memory_stored",
+            Self::ResourceUpdated { .. } => "resource_updated",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    fn channel(&self) -> Option<&str> {
+        match self {
+            Self::MessageReceived { channel, .. }
+            | Self::TurnCompleted { channel, .. }
+            | Self::ToolExecuted { channel, .. } => Some(channel),
+            Self::MemoryStored { .. } | Self::ResourceUpdated { .. } | Self::Error { .. } => None,
+        }
+    }
+}
+
+/// An event as delivered to hooks: the event's fields plus when and in
+/// which session it happened.
+#[derive(Debug, Clone, Serialize)]
+struct Envelope {
+    #[serde(flatten)]
+    event: Event,
+    timestamp: String,
+    session_id: Option<String>,
+}
+
+/// Start the hook dispatcher. Without hooks this does nothing and
+/// [`emit`] stays a no-op. Must be called inside the tokio runtime.
+pub fn init(config: &Config) {
+    if config.events.hooks.is_empty() || BUS.get().is_some() {
+        return;
+    }
+    let (tx, rx) = mpsc::unbounded_channel();
+    if BUS.set(tx).is_ok() {
+        tokio::spawn(dispatch(Arc::new(config.clone()), rx));
+    }
+}
+
+/// Publish an event to subscribed hooks, tagged with the current session.
+pub fn emit(event: Event) {
+    publish(event, crate::sessions::current_session_id());
+}
+
+/// [`emit`] for callers outside a [`crate::sessions::scope`], such as a
+/// message that has just arrived.
+pub fn emit_in_session(session_id: &str, event: Event) {
+    publish(event, Some(session_id.to_string()));
+}
+
+fn publish(event: Event, session_id: Option<String>) {
+    let Some(tx) = BUS.get() else {
+        return;
+    };
+    if IN_HOOK.try_with(|()| ()).is_ok() {
+        return;
+    }
+    let _ = tx.send(Envelope {
+        event,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        session_id,
+    });
+}
+
+/// Shorten free text carried in an event.
+pub fn clip(text: &str) -> String {
+    crate::util::truncate_with_ellipsis(text, MAX_TEXT_CHARS)
+}
+
+async fn dispatch(config: Arc<Config>, mut rx: mpsc::UnboundedReceiver<Envelope>) {
+    let tools: Arc<OnceCell<Vec<Box<dyn Tool>>>> = Arc::new(OnceCell::new());
+    let mut hooks: Vec<(String, EventHookConfig)> = config
+        .events
+        .hooks
+        .iter()
+        .map(|(name, hook)| (name.clone(), hook.clone()))
+        .collect();
+    hooks.sort_by(|a, b| a.0.cmp(&b.0));
+
+    while let Some(envelope) = rx.recv().await {
+        let payload = match serde_json::to_value(&envelope) {
+            Ok(payload) => Arc::new(payload),
+            Err(e) => {
+                tracing::warn!("Failed to serialize {} event: {e}", envelope.event.name());
+                continue;
+            }
+        };
+        for (name, hook) in &hooks {
+            if !matches(hook, &envelope.event) {
+                continue;
+            }
+            let (name, hook) = (name.clone(), hook.clone());
+            let (config, tools, payload) = (config.clone(), tools.clone(), payload.clone());
+            let event = envelope.event.name();
+            tokio::spawn(IN_HOOK.scope((), async move {
+                let timeout = Duration::from_secs(hook.timeout_secs.max(1));
+                let run = run_hook(&config, &tools, &hook, event, &payload);
+                match tokio::time::timeout(timeout, run).await {
+                    Ok(Ok(())) => tracing::debug!(hook = %name, event, "Event hook ran"),
+                    Ok(Err(e)) => tracing::warn!(hook = %name, event, "Event hook failed: {e:#}"),
+                    Err(_) => tracing::warn!(
+                        hook = %name,
+                        event,
+                        "Event hook timed out after {}s",
+                        timeout.as_secs()
+                    ),
+                }
+            }));
+        }
+    }
+}
+
+/// Whether `hook` subscribes to `event`.
+fn matches(hook: &EventHookConfig, event: &Event) -> bool {
+    if !hook.on.iter().any(|on| on == "*" || on == event.name()) {
+        return false;
+    }
+    if !hook.channels.is_empty()
+        && !event
+            .channel()
+            .is_some_and(|channel| hook.channels.iter().any(|c| c == channel))
+    {
+        return false;
+    }
+    match event {
+        Event::ToolExecuted { tool, .. } if !hook.tools.is_empty() => {
+            hook.tools.iter().any(|t| t == tool)
+        }
+        _ => true,
+    }
+}
+
+async fn run_hook(
+    config: &Config,
+    tools: &OnceCell<Vec<Box<dyn Tool>>>,
+    hook: &EventHookConfig,
+    event: &str,
+    payload: &Value,
+) -> Result<()> {
+    if let Some(command) = hook.command.as_deref() {
+        return run_command(config, command, event, payload).await;
+    }
+    if let Some(url) = hook.url.as_deref() {
+        let response = reqwest::Client::new()
+            .post(url)
+            .json(payload)
+            .timeout(Duration::from_secs(hook.timeout_secs.max(1)))
+            .send()
+            .await
+            .with_context(|| format!("POST {url} failed"))?;
+        if !response.status().is_success() {
+            bail!("POST {url} returned {}", response.status());
+        }
+        return Ok(());
+    }
+    if let Some(name) = hook.tool.as_deref() {
+        let registry = tools
+            .get_or_try_init(|| async { crate::cron::scheduler::build_tool_registry(config) })
+            .await?;
+        let Some(tool) = registry.iter().find(|tool| tool.name() == name) else {
+            bail!("unknown tool: {name}");
+        };
+        let result = tool.execute(render_value(&hook.args, payload)).await?;
+        if !result.success {
+            bail!(result.error.unwrap_or(result.output));
+        }
+        return Ok(());
+    }
+    bail!("hook has no command, url or tool")
+}
+
+async fn run_command(config: &Config, command: &str, event: &str, payload: &Value) -> Result<()> {
+    let mut cmd = tokio::process::Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .current_dir(&config.workspace_dir)
+        .env("ZEROCLAW_EVENT", event)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    crate::security::limits::apply(&mut cmd);
+    let mut child = cmd.spawn().context("Failed to start hook command")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A script that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(payload.to_string().as_bytes()).await;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        bail!(
+            "command exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Replace `{{field}}` placeholders in string values with top-level event
+/// fields; unknown placeholders are left as they are.
+fn render_value(value: &Value, payload: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(render(s, payload)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| render_value(v, payload)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_value(v, payload)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn render(template: &str, payload: &Value) -> String {
+    let mut out = template.to_string();
+    if let Value::Object(fields) = payload {
+        for (key, value) in fields {
+            let placeholder = format!("{{{{{key}}}}}");
+            if !out.contains(&placeholder) {
+                continue;
+            }
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            out = out.replace(&placeholder, &value);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn hook(on: &[&str]) -> EventHookConfig {
+        EventHookConfig {
+            on: on.iter().map(ToString::to_string).collect(),
+            timeout_secs: 5,
+            ..EventHookConfig::default()
+        }
+    }
+
+    fn tool_event(tool: &str) -> Event {
+        Event::ToolExecuted {
+            channel: "telegram".into(),
+            tool: tool.into(),
+            arguments: json!({}),
+            success: true,
+            duration_ms: 3,
+            output: "ok".into(),
+        }
+    }
+
+    #[test]
+    fn hooks_match_on_event_channel_and_tool() {
+        let error = Event::Error {
+            component: "agent".into(),
+            message: "boom".into(),
+        };
+        assert!(matches(&hook(&["*"]), &error));
+        assert!(matches(&hook(&["error"]), &error));
+        assert!(!matches(&hook(&["tool_executed"]), &error));
+
+        let mut shell_only = hook(&["tool_executed"]);
+        shell_only.tools = vec!["shell".into()];
+        assert!(matches(&shell_only, &tool_event("shell")));
+        assert!(!matches(&shell_only, &tool_event("file_read")));
+
+        let mut slack_only = hook(&["*"]);
+        slack_only.channels = vec!["slack".into()];
+        assert!(!matches(&slack_only, &tool_event("shell")));
+        assert!(!matches(&slack_only, &error));
+    }
+
+    #[test]
+    fn envelope_flattens_event_fields() {
+        let envelope = Envelope {
+            event: tool_event("shell"),
+            timestamp: "2026-01-01T00:00:00Z".into(),
+            session_id: Some("telegram_42".into()),
+        };
+        let payload = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(payload["event"], "tool_executed");
+        assert_eq!(payload["tool"], "shell");
+        assert_eq!(payload["session_id"], "telegram_42");
+    }
+
+    #[test]
+    fn tool_args_are_rendered_from_event_fields() {
+        let payload = json!({ "event": "error", "component": "agent", "message": "boom" });
+        let args = json!({
+            "message": "{{component}} failed: {{message}}",
+            "tags": ["{{event}}", "{{missing}}"],
+            "priority": 1,
+        });
+        assert_eq!(
+            render_value(&args, &payload),
+            json!({
+                "message": "agent failed: boom",
+                "tags": ["error", "{{missing}}"],
+                "priority": 1,
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hooks_get_the_event_on_stdin() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut hook = hook(&["error"]);
+        hook.command = Some("cat > event.json; echo $ZEROCLAW_EVENT > name".into());
+        let payload = json!({ "event": "error", "message": "boom" });
+
+        run_hook(&config, &OnceCell::new(), &hook, "error", &payload)
+            .await
+            .unwrap();
+
+        let written = std::fs::read_to_string(tmp.path().join("event.json")).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&written).unwrap(), payload);
+        let name = std::fs::read_to_string(tmp.path().join("name")).unwrap();
+        assert_eq!(name.trim(), "error");
+
+        hook.command = Some("exit 3".into());
+        let err = run_hook(&config, &OnceCell::new(), &hook, "error", &payload)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exited"));
+    }
+}
@@ -0,0 +1,453 @@
+//! `POST /hooks/<name>` — inbound webhook triggers.
+//!
+//! Each `[hooks.<name>]` entry verifies an HMAC-SHA256 signature over the raw
+//! body, renders the JSON payload into a prompt template (or a tool/agent
+//! pipeline), and runs it in the background. The result goes to the hook's
+//! channel, so slow agent runs never hold up the sender's delivery timeout.
+
+use super::{client_key_from_headers, AppState, RATE_LIMIT_WINDOW_SECS};
+use crate::agent::loop_::agent_turn;
+use crate::config::{HookConfig, HookSource};
+use crate::cron::PipelineStep;
+use crate::providers::{self, ChatMessage};
+use crate::security::SecurityPolicy;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Result};
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+use serde_json::Value;
+
+const GITHUB_EVENT_HEADER: &str = "X-GitHub-Event";
+const GITHUB_DELIVERY_HEADER: &str = "X-GitHub-Delivery";
+
+/// What a hook receives, for template rendering.
+pub(super) struct HookContext {
+    pub hook: String,
+    pub event: String,
+    pub payload: Value,
+}
+
+/// What a hook runs once its payload is accepted.
+#[derive(Debug)]
+pub(super) enum HookAction {
+    Prompt(String),
+    Pipeline(Vec<PipelineStep>),
+}
+
+fn default_prompt(source: HookSource) -> &'static str {
+    match source {
+        HookSource::Generic => {
+            "Webhook `{{hook}}` received this payload. Act on it as appropriate and \
+             summarize the outcome.\n\n{{payload}}"
+        }
+        HookSource::Github => {
+            "GitHub `{{event}}` event on {{payload.repository.full_name}}. Summarize \
+             what happened and anything that needs attention.\n\n{{payload}}"
+        }
+        HookSource::Grafana => {
+            "Grafana alert \"{{payload.title}}\" is {{payload.status}}. Explain the \
+             likely impact and suggest next steps.\n\n{{payload.message}}"
+        }
+    }
+}
+
+/// Check a hex HMAC-SHA256 of `body`, with or without a `sha256=` prefix.
+pub(super) fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let signature = signature.trim();
+    let hex_sig = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+fn value_at<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(payload, |value, key| match value {
+        Value::Object(map) => map.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|idx| items.get(idx)),
+        _ => None,
+    })
+}
+
+fn lookup(key: &str, ctx: &HookContext) -> Option<String> {
+    let value = match key {
+        "hook" => return Some(ctx.hook.clone()),
+        "event" => return Some(ctx.event.clone()),
+        "payload" => &ctx.payload,
+        _ => value_at(&ctx.payload, key.strip_prefix("payload.")?).unwrap_or(&Value::Null),
+    };
+    Some(match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    })
+}
+
+/// Fill `{{...}}` placeholders from the hook context. Missing payload fields
+/// render empty; unknown placeholders (like a pipeline's `{{output}}`) are
+/// left as they are.
+pub(super) fn render(template: &str, ctx: &HookContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let placeholder = &rest[start..start + 2 + len + 2];
+        out.push_str(&rest[..start]);
+        match lookup(placeholder[2..placeholder.len() - 2].trim(), ctx) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(placeholder),
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn render_value(value: &Value, ctx: &HookContext) -> Value {
+    match value {
+        Value::String(s) => Value::String(render(s, ctx)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| render_value(v, ctx)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_value(v, ctx)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Tools that run or schedule shell commands; hook payloads are untrusted,
+/// so pipelines may not call them.
+const COMMAND_TOOLS: &[&str] = &["shell", "cron_add", "cron_update", "schedule"];
+
+/// Render the hook's prompt or pipeline for one payload.
+pub(super) fn plan(hook: &HookConfig, ctx: &HookContext) -> Result<HookAction> {
+    if hook.steps.is_empty() {
+        let template = hook
+            .prompt
+            .as_deref()
+            .unwrap_or_else(|| default_prompt(hook.source));
+        return Ok(HookAction::Prompt(render(template, ctx)));
+    }
+
+    let mut steps = Vec::with_capacity(hook.steps.len());
+    for step in &hook.steps {
+        steps.push(match step {
+            PipelineStep::Shell { .. } => {
+                bail!("shell steps are not allowed in hooks (payloads are untrusted)")
+            }
+            PipelineStep::Agent { prompt, model } => PipelineStep::Agent {
+                prompt: render(prompt, ctx),
+                model: model.clone(),
+            },
+            PipelineStep::Tool { name, .. } if COMMAND_TOOLS.contains(&name.as_str()) => {
+                bail!("the {name} tool is not allowed in hooks (payloads are untrusted)")
+            }
+            PipelineStep::Tool { name, args } => PipelineStep::Tool {
+                name: name.clone(),
+                args: render_value(args, ctx),
+            },
+        });
+    }
+    Ok(HookAction::Pipeline(steps))
+}
+
+async fn run_action(state: &AppState, hook: &str, action: HookAction) -> Result<String> {
+    match action {
+        HookAction::Prompt(prompt) => {
+            let mut history = vec![
+                ChatMessage::system(state.system_prompt.as_ref()),
+                ChatMessage::user(prompt),
+            ];
+            agent_turn(
+                state.provider.as_ref(),
+                &mut history,
+                &state.tools_registry,
+                state.observer.as_ref(),
+                &state.provider_name,
+                &state.model,
+                state.temperature,
+                true,
+                None,
+            )
+            .await
+        }
+        HookAction::Pipeline(steps) => {
+            let config = &state.config;
+            let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+            let (success, output) = crate::cron::scheduler::run_pipeline(
+                config,
+                &security,
+                &steps,
+                &format!("hook:{hook}"),
+                None,
+                String::new(),
+            )
+            .await;
+            if !success {
+                bail!(output);
+            }
+            Ok(output)
+        }
+    }
+}
+
+/// Run a hook's action and send the result (or failure) to its channel.
+async fn run_and_deliver(state: AppState, name: String, hook: HookConfig, action: HookAction) {
+    let message = match run_action(&state, &name, action).await {
+        Ok(output) => {
+            tracing::info!(hook = %name, "Hook run finished");
+            output
+        }
+        Err(e) => {
+            let error = providers::sanitize_api_error(&e.to_string());
+            tracing::error!(hook = %name, "Hook run failed: {error}");
+            format!("⚠️ Hook `{name}` failed: {error}")
+        }
+    };
+
+    match (hook.channel.as_deref(), hook.to.as_deref()) {
+        (Some(channel), Some(to)) => {
+            if let Err(e) =
+                crate::cron::scheduler::announce(&state.config, channel, to, &message).await
+            {
+                tracing::error!(hook = %name, "Hook delivery to {channel} failed: {e}");
+            }
+        }
+        _ => tracing::info!(
+            hook = %name,
+            "Hook result (no channel configured): {}",
+            truncate_with_ellipsis(&message, 500)
+        ),
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> (StatusCode, Json<Value>) {
+    (status, Json(serde_json::json!({ "error": message })))
+}
+
+/// POST /hooks/<name> — signed webhook trigger
+pub(super) async fn handle_hook(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let client_key = client_key_from_headers(&headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        tracing::warn!("/hooks rate limit exceeded for key: {client_key}");
+        let err = serde_json::json!({
+            "error": "Too many webhook requests. Please retry later.",
+            "retry_after": RATE_LIMIT_WINDOW_SECS,
+        });
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+    }
+
+    let Some(hook) = state.config.hooks.get(&name).cloned() else {
+        return error(StatusCode::NOT_FOUND, "Unknown hook");
+    };
+
+    // ── HMAC signature over the raw body ──
+    let header_name = hook
+        .signature_header
+        .as_deref()
+        .unwrap_or_else(|| hook.source.signature_header());
+    let signature = headers
+        .get(header_name)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let secret = hook.secret.trim();
+    if secret.is_empty() || !verify_signature(secret, &body, signature) {
+        tracing::warn!(
+            hook = %name,
+            "Hook: rejected request — {} {header_name}",
+            if signature.is_empty() { "missing" } else { "invalid" }
+        );
+        return error(StatusCode::UNAUTHORIZED, "Invalid or missing signature");
+    }
+
+    let Ok(payload) = serde_json::from_slice::<Value>(&body) else {
+        return error(StatusCode::BAD_REQUEST, "Invalid JSON payload");
+    };
+
+    let event = headers
+        .get(GITHUB_EVENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    if hook.source == HookSource::Github {
+        if event == "ping" {
+            return (StatusCode::OK, Json(serde_json::json!({"status": "pong"})));
+        }
+        if !hook.events.is_empty() && !hook.events.contains(&event) {
+            let body = serde_json::json!({"status": "ignored", "event": event});
+            return (StatusCode::OK, Json(body));
+        }
+    }
+
+    // ── Redeliveries (GitHub retries, X-Idempotency-Key) ──
+    if let Some(delivery_id) = [GITHUB_DELIVERY_HEADER, "X-Idempotency-Key"]
+        .iter()
+        .find_map(|h| headers.get(*h).and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+    {
+        if !state
+            .idempotency_store
+            .record_if_new(&format!("hook:{name}:{delivery_id}"))
+        {
+            tracing::info!(hook = %name, "Hook duplicate ignored (delivery: {delivery_id})");
+            return (
+                StatusCode::OK,
+                Json(serde_json::json!({"status": "duplicate", "idempotent": true})),
+            );
+        }
+    }
+
+    let ctx = HookContext {
+        hook: name.clone(),
+        event,
+        payload,
+    };
+    let action = match plan(&hook, &ctx) {
+        Ok(action) => action,
+        Err(e) => {
+            tracing::error!(hook = %name, "Hook misconfigured: {e}");
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Hook is misconfigured");
+        }
+    };
+
+    tracing::info!(hook = %name, event = %ctx.event, "Hook accepted");
+    tokio::spawn(run_and_deliver(state, name.clone(), hook, action));
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"status": "accepted", "hook": name})),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ctx() -> HookContext {
+        HookContext {
+            hook: "deploys".into(),
+            event: "push".into(),
+            payload: json!({
+                "repository": {"full_name": "acme/api"},
+                "commits": [{"message": "fix login"}],
+                "forced": false,
+            }),
+        }
+    }
+
+    fn hook(steps: Vec<PipelineStep>) -> HookConfig {
+        HookConfig {
+            source: HookSource::Github,
+            secret: "s3cret".into(),
+            signature_header: None,
+            prompt: None,
+            steps,
+            events: Vec::new(),
+            channel: None,
+            to: None,
+        }
+    }
+
+    #[test]
+    fn render_fills_fields_and_keeps_unknown_placeholders() {
+        let out = render(
+            "{{event}} to {{ payload.repository.full_name }}: {{payload.commits.0.message}} \
+             forced={{payload.forced}} missing=[{{payload.nope}}] {{output}}",
+            &ctx(),
+        );
+        assert_eq!(
+            out,
+            "push to acme/api: fix login forced=false missing=[] {{output}}"
+        );
+        assert!(render("{{payload}}", &ctx()).contains("\"full_name\": \"acme/api\""));
+        assert_eq!(render("unclosed {{event", &ctx()), "unclosed {{event");
+    }
+
+    #[test]
+    fn signatures_accept_prefixed_and_bare_hex() {
+        use hmac::{Hmac, Mac};
+        let body = br#"{"ok":true}"#;
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(body);
+        let sig = hex::encode(mac.finalize().into_bytes());
+
+        assert!(verify_signature("s3cret", body, &format!("sha256={sig}")));
+        assert!(verify_signature("s3cret", body, &sig));
+        assert!(!verify_signature("other", body, &sig));
+        assert!(!verify_signature("s3cret", b"tampered", &sig));
+        assert!(!verify_signature("s3cret", body, ""));
+    }
+
+    #[test]
+    fn plan_renders_default_prompt_and_pipeline_steps() {
+        let HookAction::Prompt(prompt) = plan(&hook(Vec::new()), &ctx()).unwrap() else {
+            panic!("expected prompt");
+        };
+        assert!(prompt.starts_with("GitHub `push` event on acme/api."));
+
+        let steps = vec![
+            PipelineStep::Tool {
+                name: "memory_store".into(),
+                args: json!({"key": "last_push", "content": "{{payload.commits.0.message}}"}),
+            },
+            PipelineStep::Agent {
+                prompt: "Announce {{output}} for {{payload.repository.full_name}}".into(),
+                model: None,
+            },
+        ];
+        let HookAction::Pipeline(rendered) = plan(&hook(steps), &ctx()).unwrap() else {
+            panic!("expected pipeline");
+        };
+        assert_eq!(
+            rendered[0],
+            PipelineStep::Tool {
+                name: "memory_store".into(),
+                args: json!({"key": "last_push", "content": "fix login"}),
+            }
+        );
+        assert_eq!(
+            rendered[1],
+            PipelineStep::Agent {
+                prompt: "Announce {{output}} for acme/api".into(),
+                model: None,
+            }
+        );
+
+        let shell = hook(vec![PipelineStep::Shell {
+            command: "echo {{payload}}".into(),
+        }]);
+        assert!(plan(&shell, &ctx()).is_err());
+    }
+
+    #[test]
+    fn plan_refuses_tools_that_run_commands() {
+        for name in COMMAND_TOOLS {
+            let steps = vec![PipelineStep::Tool {
+                name: (*name).into(),
+                args: json!({"command": "echo {{payload.commits.0.message}}"}),
+            }];
+            let err = plan(&hook(steps), &ctx()).unwrap_err();
+            assert!(err.to_string().contains("not allowed in hooks"), "{name}");
+        }
+    }
+}
@@ -7,9 +7,13 @@
 //! - Request timeouts (30s) to prevent slow-loris attacks
 //! - Header sanitization (handled by axum/hyper)
 
+mod hooks;
+mod openai;
+
 use crate::agent::loop_::{agent_turn, build_tool_instructions, ToolCallRecord};
 use crate::channels::{Channel, SendMessage, WhatsAppChannel};
 use crate::config::Config;
+use crate::health::readiness::ReadinessReport;
 use crate::memory::{self, Memory, MemoryCategory};
 use crate::observability::{self, Observer};
 use crate::providers::{self, ChatMessage, Provider};
@@ -38,10 +42,18 @@ use uuid::Uuid;
 
 /// Maximum request body size (64KB) — prevents memory exhaustion
 pub const MAX_BODY_SIZE: usize = 65_536;
+/// Body limit for `/v1/chat/completions` (1MB) — clients resend the whole
+/// conversation, often with file contents, on every call
+pub const OPENAI_MAX_BODY_SIZE: usize = 1_048_576;
 /// Request timeout (120s) — agent tool execution needs time
 pub const REQUEST_TIMEOUT_SECS: u64 = 120;
 /// Sliding window used by gateway rate limiting.
 pub const RATE_LIMIT_WINDOW_SECS: u64 = 60;
+/// Rough chars-per-token ratio for transcript usage estimates.
+const CHARS_PER_TOKEN: usize = 4;
+/// How long a `/readyz` result is reused, so frequent probes don't hit the
+/// provider and MCP servers on every request.
+const READINESS_CACHE_SECS: u64 = 5;
 
 fn webhook_memory_key() -> String {
     format!("webhook_msg_{}", Uuid::new_v4())
@@ -203,6 +215,8 @@ pub struct AppState {
     pub config: Arc<Config>,
     /// MCP manager for graceful shutdown
     pub mcp_manager: Option<Arc<crate::mcp::McpManager>>,
+    /// Last `/readyz` report and when it was taken
+    pub readiness: Arc<tokio::sync::Mutex<Option<(Instant, ReadinessReport)>>>,
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
@@ -274,7 +288,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let mcp_manager = match crate::mcp::McpManager::create_mcp_tools(&config.mcp).await {
         Ok((mgr, mcp_tools)) => {
             if !mcp_tools.is_empty() {
-                tools_vec.extend(mcp_tools);
+                tools_vec.extend(crate::security::tool_policy::enforce(&config, mcp_tools));
             }
             Some(Arc::new(mgr))
         }
@@ -302,6 +316,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         ("file_write", "Write file contents."),
         ("memory_store", "Save to memory."),
         ("memory_recall", "Search memory."),
+        ("memory_list", "List stored memories, newest first."),
+        ("memory_tags", "List memory tags."),
+        ("memory_update", "Correct a memory entry."),
         ("memory_forget", "Delete a memory entry."),
         ("screenshot", "Capture a screenshot."),
         ("image_info", "Read image metadata."),
@@ -400,12 +417,27 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     }
     println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
     println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
+    if !config.hooks.is_empty() {
+        let mut names: Vec<&str> = config.hooks.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        println!(
+            "  POST /hooks/<name> — signed triggers: {}",
+            names.join(", ")
+        );
+    }
     if whatsapp_channel.is_some() {
         println!("  GET  /whatsapp  — Meta webhook verification");
         println!("  POST /whatsapp  — WhatsApp message webhook");
     }
+    if config.gateway.openai_compat {
+        println!("  POST /v1/chat/completions — OpenAI-compatible agent endpoint (base URL: http://{display_addr}/v1)");
+        println!("  GET  /v1/models — the served model");
+    }
     println!("  GET  /info      — runtime info (tools, agents, channels)");
+    println!("  GET  /metrics   — Prometheus metrics (MCP calls, errors, latency, restarts)");
     println!("  GET  /health    — health check");
+    println!("  GET  /healthz   — liveness (process and component status)");
+    println!("  GET  /readyz    — readiness (memory, provider, MCP, channels); 503 when not ready");
     if let Some(code) = pairing.pairing_code() {
         println!();
         println!("  🔐 PAIRING REQUIRED — use this one-time code:");
@@ -441,17 +473,25 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         system_prompt,
         config,
         mcp_manager,
+        readiness: Arc::new(tokio::sync::Mutex::new(None)),
     };
 
-    // Grab MCP ref for graceful shutdown before state is moved
+    // Grab refs for graceful shutdown before state is moved
     let mcp_shutdown_ref = state.mcp_manager.clone();
+    let observer_ref = Arc::clone(&state.observer);
+    let drain_timeout = Duration::from_secs(state.config.shutdown.drain_timeout_secs);
+    let openai_compat = state.config.gateway.openai_compat.then(|| state.clone());
 
     // Build router with middleware
-    let app = Router::new()
+    let mut app = Router::new()
         .route("/health", get(handle_health))
+        .route("/healthz", get(handle_healthz))
+        .route("/readyz", get(handle_readyz))
         .route("/info", get(handle_info))
+        .route("/metrics", get(handle_metrics))
         .route("/pair", post(handle_pair))
         .route("/webhook", post(handle_webhook))
+        .route("/hooks/{name}", post(hooks::handle_hook))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
         .with_state(state)
@@ -460,18 +500,52 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
         ));
+    if let Some(state) = openai_compat {
+        app = app.merge(
+            Router::new()
+                .route(
+                    "/v1/chat/completions",
+                    post(openai::handle_chat_completions),
+                )
+                .route("/v1/models", get(openai::handle_models))
+                .with_state(state)
+                .layer(RequestBodyLimitLayer::new(OPENAI_MAX_BODY_SIZE))
+                .layer(TimeoutLayer::with_status_code(
+                    StatusCode::REQUEST_TIMEOUT,
+                    Duration::from_secs(REQUEST_TIMEOUT_SECS),
+                )),
+        );
+    }
 
-    // Run the server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            let _ = tokio::signal::ctrl_c().await;
-            tracing::info!("Gateway shutdown signal received");
-            if let Some(mgr) = mcp_shutdown_ref {
-                mgr.shutdown().await;
-                tracing::info!("MCP servers shut down");
-            }
-        })
-        .await?;
+    // Run the server with graceful shutdown: stop accepting on SIGINT/SIGTERM,
+    // let in-flight requests finish until the drain deadline, then close
+    // MCP servers and the tunnel.
+    crate::shutdown::install_signal_handler();
+    let shutdown = crate::shutdown::global();
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        shutdown.triggered().await;
+        tracing::info!("Gateway shutdown signal received; no longer accepting requests");
+    });
+    tokio::select! {
+        result = server => result?,
+        () = shutdown.deadline(drain_timeout) => {
+            tracing::warn!(
+                "Gateway drain deadline ({}s) reached; abandoning in-flight requests",
+                drain_timeout.as_secs()
+            );
+        }
+    }
+
+    if let Some(mgr) = mcp_shutdown_ref {
+        mgr.shutdown().await;
+        tracing::info!("MCP servers shut down");
+    }
+    if let Some(tun) = tunnel {
+        if let Err(e) = tun.stop().await {
+            tracing::warn!("Failed to stop {} tunnel: {e}", tun.name());
+        }
+    }
+    observer_ref.flush();
 
     Ok(())
 }
@@ -490,6 +564,71 @@ async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     Json(body)
 }
 
+/// GET /healthz — liveness probe: 200 while the process is serving requests
+async fn handle_healthz() -> impl IntoResponse {
+    let mut body = crate::health::snapshot_json();
+    if let Some(obj) = body.as_object_mut() {
+        obj.insert("status".into(), serde_json::json!("ok"));
+    }
+    Json(body)
+}
+
+/// GET /readyz — readiness probe: 200 when every dependency check passes, 503 otherwise
+async fn handle_readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let mut cached = state.readiness.lock().await;
+    let report = match cached.as_ref() {
+        Some((at, report)) if at.elapsed() < Duration::from_secs(READINESS_CACHE_SECS) => {
+            report.clone()
+        }
+        _ => {
+            let report = crate::health::readiness::check(
+                state.mem.as_ref(),
+                state.provider.as_ref(),
+                state.mcp_manager.as_deref(),
+            )
+            .await;
+            *cached = Some((Instant::now(), report.clone()));
+            report
+        }
+    };
+    drop(cached);
+
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = serde_json::json!({
+        "status": if report.ready { "ready" } else { "not_ready" },
+        "checked_at": report.checked_at,
+        "checks": report.checks,
+    });
+    (status, Json(body))
+}
+
+/// GET /metrics — Prometheus metrics (protected by pairing)
+async fn handle_metrics(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if state.pairing.require_pairing() {
+        let auth = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let token = auth.strip_prefix("Bearer ").unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::CONTENT_TYPE, "text/plain".to_string())],
+                "Unauthorized\n".to_string(),
+            );
+        }
+    }
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, crate::mcp::metrics::content_type())],
+        crate::mcp::metrics::render(),
+    )
+}
+
 /// GET /info — runtime capabilities (protected by pairing)
 async fn handle_info(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     // ── Bearer token auth (pairing) ──
@@ -590,6 +729,61 @@ async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl
 #[derive(serde::Deserialize)]
 pub struct WebhookBody {
     pub message: String,
+    /// Response shape (`reply` by default).
+    #[serde(default)]
+    pub mode: WebhookMode,
+}
+
+/// What the webhook returns once the agent loop finishes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookMode {
+    /// Final response text plus tool call records.
+    #[default]
+    Reply,
+    /// Run to completion and return the full structured transcript: every
+    /// message, tool call (arguments and results), and estimated token usage.
+    Transcript,
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Build the structured transcript for the turn starting at `history[turn_start]`.
+///
+/// Providers do not report usage uniformly, so token counts are estimated from
+/// message sizes: each assistant message is one LLM call whose input is every
+/// message before it.
+fn build_transcript(
+    history: &[ChatMessage],
+    turn_start: usize,
+    tool_records: &[ToolCallRecord],
+) -> serde_json::Value {
+    let mut llm_calls = 0;
+    let mut input_tokens = 0;
+    let mut output_tokens = 0;
+    for (idx, msg) in history.iter().enumerate().skip(turn_start) {
+        if msg.role == "assistant" {
+            llm_calls += 1;
+            input_tokens += history[..idx]
+                .iter()
+                .map(|m| estimate_tokens(&m.content))
+                .sum::<usize>();
+            output_tokens += estimate_tokens(&msg.content);
+        }
+    }
+
+    serde_json::json!({
+        "messages": &history[turn_start.min(history.len())..],
+        "tool_calls": tool_records,
+        "usage": {
+            "llm_calls": llm_calls,
+            "estimated_input_tokens": input_tokens,
+            "estimated_output_tokens": output_tokens,
+            "estimated_total_tokens": input_tokens + output_tokens,
+        },
+    })
 }
 
 /// POST /webhook — main webhook endpoint
@@ -701,9 +895,11 @@ async fn handle_webhook(
         }
     }
 
+    let turn_start = history.len();
     history.push(ChatMessage::user(message.to_string()));
 
     // Run full agent loop with tools
+    let started_at = Instant::now();
     let mut tool_records: Vec<ToolCallRecord> = Vec::new();
     match agent_turn(
         state.provider.as_ref(),
@@ -719,11 +915,20 @@ async fn handle_webhook(
     .await
     {
         Ok(response) => {
-            let body = serde_json::json!({
-                "response": response,
-                "model": state.model,
-                "tool_calls": tool_records,
-            });
+            let body = match webhook_body.mode {
+                WebhookMode::Reply => serde_json::json!({
+                    "response": response,
+                    "model": state.model,
+                    "tool_calls": tool_records,
+                }),
+                WebhookMode::Transcript => serde_json::json!({
+                    "response": response,
+                    "model": state.model,
+                    "provider": state.provider_name,
+                    "duration_ms": u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+                    "transcript": build_transcript(&history, turn_start, &tool_records),
+                }),
+            };
             (StatusCode::OK, Json(body))
         }
         Err(e) => {
@@ -864,10 +1069,18 @@ async fn handle_whatsapp_message(
         // Auto-save to memory
         if state.auto_save {
             let key = whatsapp_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
+            let namespace = crate::memory::namespace::for_sender(
+                &state.config.memory.namespaces,
+                &msg.channel,
+                &msg.sender,
+            );
+            let _ = crate::memory::namespace::scope(
+                namespace,
+                state
+                    .mem
+                    .store(&key, &msg.content, MemoryCategory::Conversation, None),
+            )
+            .await;
         }
 
         // Call the LLM
@@ -936,6 +1149,20 @@ mod tests {
         assert!(parsed.is_err());
     }
 
+    #[test]
+    fn webhook_body_mode_defaults_to_reply() {
+        let parsed: WebhookBody = serde_json::from_str(r#"{"message": "hi"}"#).unwrap();
+        assert_eq!(parsed.mode, WebhookMode::Reply);
+
+        let parsed: WebhookBody =
+            serde_json::from_str(r#"{"message": "hi", "mode": "transcript"}"#).unwrap();
+        assert_eq!(parsed.mode, WebhookMode::Transcript);
+
+        let invalid: Result<WebhookBody, _> =
+            serde_json::from_str(r#"{"message": "hi", "mode": "bogus"}"#);
+        assert!(invalid.is_err());
+    }
+
     #[test]
     fn whatsapp_query_fields_are_optional() {
         let q = WhatsAppVerifyQuery {
@@ -1184,9 +1411,82 @@ mod tests {
             system_prompt: Arc::from("You are a helpful assistant."),
             config: Arc::new(Config::default()),
             mcp_manager: None,
+            readiness: Arc::new(tokio::sync::Mutex::new(None)),
         }
     }
 
+    #[tokio::test]
+    async fn hook_requires_signature_and_runs_prompt_in_background() {
+        use hmac::{Hmac, Mac};
+
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let mut state = test_state(provider, Arc::new(MockMemory));
+        let mut config = Config::default();
+        config.hooks.insert(
+            "alerts".into(),
+            crate::config::HookConfig {
+                source: crate::config::HookSource::Grafana,
+                secret: "s3cret".into(),
+                signature_header: None,
+                prompt: None,
+                steps: Vec::new(),
+                events: Vec::new(),
+                channel: None,
+                to: None,
+            },
+        );
+        state.config = Arc::new(config);
+
+        let body = Bytes::from_static(br#"{"title":"High CPU","status":"firing"}"#);
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(&body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let unknown = hooks::handle_hook(
+            State(state.clone()),
+            axum::extract::Path("nope".into()),
+            HeaderMap::new(),
+            body.clone(),
+        )
+        .await
+        .into_response();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+
+        let unsigned = hooks::handle_hook(
+            State(state.clone()),
+            axum::extract::Path("alerts".into()),
+            HeaderMap::new(),
+            body.clone(),
+        )
+        .await
+        .into_response();
+        assert_eq!(unsigned.status(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Grafana-Alerting-Signature",
+            HeaderValue::from_str(&signature).unwrap(),
+        );
+        let accepted = hooks::handle_hook(
+            State(state),
+            axum::extract::Path("alerts".into()),
+            headers,
+            body,
+        )
+        .await
+        .into_response();
+        assert_eq!(accepted.status(), StatusCode::ACCEPTED);
+
+        for _ in 0..50 {
+            if provider_impl.calls.load(Ordering::SeqCst) > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
+    }
+
     #[tokio::test]
     async fn webhook_idempotency_skips_duplicate_provider_calls() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -1200,6 +1500,7 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            mode: WebhookMode::Reply,
         }));
         let first = handle_webhook(State(state.clone()), headers.clone(), body)
             .await
@@ -1208,6 +1509,7 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            mode: WebhookMode::Reply,
         }));
         let second = handle_webhook(State(state), headers, body)
             .await
@@ -1221,6 +1523,116 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn webhook_transcript_mode_returns_messages_and_usage() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+        let state = test_state(provider, memory);
+
+        let body = Ok(Json(WebhookBody {
+            message: "hello".into(),
+            mode: WebhookMode::Transcript,
+        }));
+        let response = handle_webhook(State(state), HeaderMap::new(), body)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["response"], "ok");
+        let transcript = &parsed["transcript"];
+        let messages = transcript["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["role"], "user");
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(transcript["usage"]["llm_calls"], 1);
+        assert!(
+            transcript["usage"]["estimated_input_tokens"]
+                .as_u64()
+                .unwrap()
+                > 0
+        );
+        assert!(transcript["tool_calls"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn openai_chat_completions_runs_agent_and_reports_usage() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let state = test_state(provider, Arc::new(MockMemory));
+
+        let request: openai::ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "gpt-4o",
+            "messages": [
+                { "role": "system", "content": "be brief" },
+                { "role": "user", "content": [{ "type": "text", "text": "hello" }] },
+            ],
+        }))
+        .unwrap();
+        let response = openai::handle_chat_completions(
+            State(state.clone()),
+            HeaderMap::new(),
+            Ok(Json(request)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["object"], "chat.completion");
+        assert_eq!(parsed["model"], "test-model");
+        assert_eq!(parsed["choices"][0]["message"]["content"], "ok");
+        assert!(parsed["usage"]["prompt_tokens"].as_u64().unwrap() > 0);
+
+        let request: openai::ChatCompletionRequest = serde_json::from_value(
+            serde_json::json!({ "messages": [{ "role": "assistant", "content": "hi" }] }),
+        )
+        .unwrap();
+        let response =
+            openai::handle_chat_completions(State(state), HeaderMap::new(), Ok(Json(request)))
+                .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn openai_endpoints_require_paired_token() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let mut state = test_state(provider, Arc::new(MockMemory));
+        state.pairing = Arc::new(PairingGuard::new(true, &["zc_token".into()]));
+
+        let denied = openai::handle_models(State(state.clone()), HeaderMap::new()).await;
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer zc_token"),
+        );
+        let allowed = openai::handle_models(State(state), headers).await;
+        assert_eq!(allowed.status(), StatusCode::OK);
+        let payload = allowed.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["data"][0]["id"], "test-model");
+    }
+
+    #[tokio::test]
+    async fn readyz_reports_unwritable_memory_as_503() {
+        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
+        let missing = std::env::temp_dir().join(format!("zeroclaw-readyz-{}", Uuid::new_v4()));
+        let memory: Arc<dyn Memory> = Arc::new(crate::memory::MarkdownMemory::new(&missing));
+        let state = test_state(provider, memory);
+
+        let response = handle_readyz(State(state.clone())).await.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["status"], "not_ready");
+        assert_eq!(parsed["checks"]["memory"]["ok"], false);
+        assert_eq!(parsed["checks"]["provider"]["ok"], true);
+        assert!(state.readiness.lock().await.is_some());
+    }
+
     #[tokio::test]
     async fn webhook_autosave_stores_distinct_keys_per_request() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -1236,6 +1648,7 @@ mod tests {
 
         let body1 = Ok(Json(WebhookBody {
             message: "hello one".into(),
+            mode: WebhookMode::Reply,
         }));
         let first = handle_webhook(State(state.clone()), headers.clone(), body1)
             .await
@@ -1244,6 +1657,7 @@ mod tests {
 
         let body2 = Ok(Json(WebhookBody {
             message: "hello two".into(),
+            mode: WebhookMode::Reply,
         }));
         let second = handle_webhook(State(state), headers, body2)
             .await
@@ -1283,6 +1697,7 @@ mod tests {
             HeaderMap::new(),
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                mode: WebhookMode::Reply,
             })),
         )
         .await
@@ -1309,6 +1724,7 @@ mod tests {
             headers,
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                mode: WebhookMode::Reply,
             })),
         )
         .await
@@ -1335,6 +1751,7 @@ mod tests {
             headers,
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                mode: WebhookMode::Reply,
             })),
         )
         .await
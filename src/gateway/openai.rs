@@ -0,0 +1,328 @@
+//! OpenAI-compatible endpoints (`gateway.openai_compat` / `zeroclaw serve --openai-compat`).
+//!
+//! `POST /v1/chat/completions` runs the full agent loop — system prompt,
+//! memory context, every registered tool — over the client's conversation
+//! and answers in the Chat Completions format, so any OpenAI client, SDK or
+//! editor can point its base URL at zeroclaw. The paired bearer token is the
+//! API key. With `stream: true` the finished reply is sent as SSE chunks
+//! (tools run before the first byte, so there is no token-by-token output).
+
+use super::{client_key_from_headers, estimate_tokens, AppState, RATE_LIMIT_WINDOW_SECS};
+use crate::agent::loop_::agent_turn;
+use crate::memory::MemoryCategory;
+use crate::providers::{self, ChatMessage};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Chat Completions request body; unknown fields are ignored.
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<RequestMessage>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestMessage {
+    pub role: String,
+    /// A string or an array of content parts; only text parts are used.
+    #[serde(default)]
+    pub content: Value,
+}
+
+fn error_response(status: StatusCode, kind: &str, message: &str) -> Response {
+    let body = json!({
+        "error": { "message": message, "type": kind, "code": Value::Null },
+    });
+    (status, Json(body)).into_response()
+}
+
+/// Rate limit and bearer-token check shared by the `/v1` routes; `Some` is
+/// the rejection to return.
+fn reject(state: &AppState, headers: &HeaderMap) -> Option<Response> {
+    let client_key = client_key_from_headers(headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        tracing::warn!("/v1 rate limit exceeded for key: {client_key}");
+        return Some(error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_exceeded",
+            &format!("Too many requests. Retry in {RATE_LIMIT_WINDOW_SECS}s."),
+        ));
+    }
+    if state.pairing.require_pairing() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            return Some(error_response(
+                StatusCode::UNAUTHORIZED,
+                "invalid_api_key",
+                "Use a paired gateway token (POST /pair) as the API key",
+            ));
+        }
+    }
+    None
+}
+
+/// Flatten OpenAI message content (string or parts array) to plain text.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Map client messages onto agent history. `developer` counts as `system`;
+/// tool and function messages belong to the client's own tool loop and are
+/// dropped.
+fn client_messages(messages: &[RequestMessage]) -> Vec<ChatMessage> {
+    messages
+        .iter()
+        .filter_map(|msg| {
+            let text = content_text(&msg.content);
+            match msg.role.as_str() {
+                "system" | "developer" => Some(ChatMessage::system(text)),
+                "user" => Some(ChatMessage::user(text)),
+                "assistant" => Some(ChatMessage::assistant(text)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// The finished reply as SSE `chat.completion.chunk` events.
+fn stream_body(id: &str, created: u64, model: &str, content: &str) -> String {
+    let chunk = |delta: Value, finish: Value| {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish }],
+        })
+    };
+    let events = [
+        chunk(
+            json!({ "role": "assistant", "content": content }),
+            Value::Null,
+        ),
+        chunk(json!({}), json!("stop")),
+    ];
+    let mut body = String::new();
+    for event in events {
+        body.push_str("data: ");
+        body.push_str(&event.to_string());
+        body.push_str("\n\n");
+    }
+    body.push_str("data: [DONE]\n\n");
+    body
+}
+
+/// GET /v1/models — the configured model, the only one this server answers as
+pub async fn handle_models(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(response) = reject(&state, &headers) {
+        return response;
+    }
+    Json(json!({
+        "object": "list",
+        "data": [{
+            "id": state.model,
+            "object": "model",
+            "created": 0,
+            "owned_by": "zeroclaw",
+        }],
+    }))
+    .into_response()
+}
+
+/// POST /v1/chat/completions — run the agent over the client's conversation
+pub async fn handle_chat_completions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if let Some(response) = reject(&state, &headers) {
+        return response;
+    }
+    let request = match body {
+        Ok(Json(request)) => request,
+        Err(e) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                &format!("Invalid request body: {e}"),
+            );
+        }
+    };
+
+    let conversation = client_messages(&request.messages);
+    let Some(last_user) = conversation
+        .iter()
+        .rev()
+        .find(|msg| msg.role == "user")
+        .map(|msg| msg.content.clone())
+    else {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            "messages must include at least one user message",
+        );
+    };
+    if let Some(requested) = request.model.as_deref().filter(|m| *m != state.model) {
+        tracing::debug!(requested, serving = %state.model, "Ignoring requested model");
+    }
+
+    let mut history = vec![ChatMessage::system(state.system_prompt.as_ref())];
+    if state.auto_save {
+        let _ = state
+            .mem
+            .store(
+                &format!("openai_msg_{}", Uuid::new_v4()),
+                &last_user,
+                MemoryCategory::Conversation,
+                None,
+            )
+            .await;
+        if let Ok(entries) = state.mem.recall(&last_user, 5, None).await {
+            if !entries.is_empty() {
+                let context: Vec<String> = entries
+                    .iter()
+                    .map(|e| format!("- {}: {}", e.key, e.content))
+                    .collect();
+                history.push(ChatMessage::system(format!(
+                    "[Memory context]\n{}",
+                    context.join("\n")
+                )));
+            }
+        }
+    }
+    history.extend(conversation);
+    let prompt_tokens: usize = history.iter().map(|m| estimate_tokens(&m.content)).sum();
+
+    let reply = match agent_turn(
+        state.provider.as_ref(),
+        &mut history,
+        &state.tools_registry,
+        state.observer.as_ref(),
+        &state.provider_name,
+        &state.model,
+        request.temperature.unwrap_or(state.temperature),
+        true,
+        None,
+    )
+    .await
+    {
+        Ok(reply) => reply,
+        Err(e) => {
+            tracing::error!(
+                "/v1/chat/completions agent error: {}",
+                providers::sanitize_api_error(&e.to_string())
+            );
+            return error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "Agent request failed",
+            );
+        }
+    };
+
+    let id = format!("chatcmpl-{}", Uuid::new_v4().simple());
+    let created = unix_now();
+    if request.stream {
+        return (
+            [
+                (header::CONTENT_TYPE, "text/event-stream"),
+                (header::CACHE_CONTROL, "no-cache"),
+            ],
+            stream_body(&id, created, &state.model, &reply),
+        )
+            .into_response();
+    }
+
+    let completion_tokens = estimate_tokens(&reply);
+    Json(json!({
+        "id": id,
+        "object": "chat.completion",
+        "created": created,
+        "model": state.model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": reply },
+            "finish_reason": "stop",
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        },
+    }))
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_parts_are_flattened_to_text() {
+        let parts = json!([
+            { "type": "text", "text": "first" },
+            { "type": "image_url", "image_url": { "url": "data:..." } },
+            { "type": "text", "text": "second" },
+        ]);
+        assert_eq!(content_text(&parts), "first\nsecond");
+        assert_eq!(content_text(&json!("plain")), "plain");
+        assert_eq!(content_text(&Value::Null), "");
+    }
+
+    #[test]
+    fn client_roles_map_onto_agent_history() {
+        let messages: Vec<RequestMessage> = serde_json::from_value(json!([
+            { "role": "developer", "content": "be brief" },
+            { "role": "user", "content": "hi" },
+            { "role": "assistant", "content": "hello" },
+            { "role": "tool", "content": "{}" },
+        ]))
+        .unwrap();
+        let roles: Vec<String> = client_messages(&messages)
+            .into_iter()
+            .map(|m| m.role)
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant"]);
+    }
+
+    #[test]
+    fn stream_body_ends_with_done() {
+        let body = stream_body("chatcmpl-1", 1, "m", "hi");
+        let events: Vec<&str> = body.split("\n\n").filter(|e| !e.is_empty()).collect();
+        assert_eq!(events.len(), 3);
+        let first: Value = serde_json::from_str(&events[0]["data: ".len()..]).unwrap();
+        assert_eq!(first["choices"][0]["delta"]["content"], "hi");
+        let last: Value = serde_json::from_str(&events[1]["data: ".len()..]).unwrap();
+        assert_eq!(last["choices"][0]["finish_reason"], "stop");
+        assert_eq!(events[2], "data: [DONE]");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod readiness;
+
 use chrono::Utc;
 use serde::Serialize;
 use std::collections::BTreeMap;
@@ -0,0 +1,166 @@
+//! Readiness checks behind the gateway's `GET /readyz`.
+//!
+//! `/healthz` only says the process is up. Ready means a turn can actually be
+//! served: the memory backend takes writes, the provider answers, connected
+//! MCP servers respond to `ping`, and no supervised component (channel
+//! listeners, scheduler, ...) is in an error state.
+
+use crate::mcp::McpManager;
+use crate::memory::Memory;
+use crate::providers::Provider;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Upper bound per dependency, so one hung check cannot stall the probe.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct CheckResult {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CheckResult {
+    fn pass() -> Self {
+        Self {
+            ok: true,
+            error: None,
+        }
+    }
+
+    fn fail(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checked_at: String,
+    pub checks: BTreeMap<String, CheckResult>,
+}
+
+/// Run all readiness checks concurrently.
+pub async fn check(
+    memory: &dyn Memory,
+    provider: &dyn Provider,
+    mcp: Option<&McpManager>,
+) -> ReadinessReport {
+    let memory_check = async {
+        match tokio::time::timeout(CHECK_TIMEOUT, memory.health_check()).await {
+            Ok(true) => CheckResult::pass(),
+            Ok(false) => CheckResult::fail(format!("{} backend is not writable", memory.name())),
+            Err(_) => CheckResult::fail("timed out"),
+        }
+    };
+    let provider_check = async {
+        match tokio::time::timeout(CHECK_TIMEOUT, provider.warmup()).await {
+            Ok(Ok(())) => CheckResult::pass(),
+            Ok(Err(e)) => CheckResult::fail(crate::providers::sanitize_api_error(&e.to_string())),
+            Err(_) => CheckResult::fail("timed out"),
+        }
+    };
+    let mcp_check = async {
+        match mcp {
+            Some(manager) => tokio::time::timeout(CHECK_TIMEOUT, manager.ping_all())
+                .await
+                .map_err(|_| CheckResult::fail("timed out")),
+            None => Ok(Vec::new()),
+        }
+    };
+    let (memory_result, provider_result, mcp_result) =
+        tokio::join!(memory_check, provider_check, mcp_check);
+
+    let mut checks = BTreeMap::new();
+    checks.insert("memory".to_string(), memory_result);
+    checks.insert("provider".to_string(), provider_result);
+    match mcp_result {
+        Ok(servers) => {
+            for (name, result) in servers {
+                let result = result.map_or_else(CheckResult::fail, |()| CheckResult::pass());
+                checks.insert(format!("mcp:{name}"), result);
+            }
+        }
+        Err(timed_out) => {
+            checks.insert("mcp".to_string(), timed_out);
+        }
+    }
+    for (name, component) in super::snapshot().components {
+        let result = if component.status == "error" {
+            CheckResult::fail(component.last_error.unwrap_or_else(|| "error".into()))
+        } else {
+            CheckResult::pass()
+        };
+        checks.insert(name, result);
+    }
+
+    ReadinessReport {
+        ready: checks.values().all(|check| check.ok),
+        checked_at: super::now_rfc3339(),
+        checks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct StubProvider {
+        reachable: bool,
+    }
+
+    #[async_trait]
+    impl Provider for StubProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("ok".into())
+        }
+
+        async fn warmup(&self) -> anyhow::Result<()> {
+            if self.reachable {
+                Ok(())
+            } else {
+                anyhow::bail!("connection refused")
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn healthy_dependencies_pass() {
+        let memory = crate::memory::NoneMemory::new();
+        let provider = StubProvider { reachable: true };
+
+        let report = check(&memory, &provider, None).await;
+
+        assert_eq!(report.checks["memory"], CheckResult::pass());
+        assert_eq!(report.checks["provider"], CheckResult::pass());
+    }
+
+    #[tokio::test]
+    async fn failing_dependencies_make_report_not_ready() {
+        let missing =
+            std::env::temp_dir().join(format!("zeroclaw-missing-{}", uuid::Uuid::new_v4()));
+        let memory = crate::memory::MarkdownMemory::new(&missing);
+        let provider = StubProvider { reachable: false };
+
+        let report = check(&memory, &provider, None).await;
+
+        assert!(!report.ready);
+        assert!(!report.checks["memory"].ok);
+        assert!(report.checks["provider"]
+            .error
+            .as_deref()
+            .is_some_and(|e| e.contains("connection refused")));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod engine;
+pub mod wake;
 
 #[cfg(test)]
 mod tests {
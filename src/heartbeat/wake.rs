@@ -0,0 +1,280 @@
+//! One heartbeat wake: run the standing prompt and HEARTBEAT.md tasks through
+//! an agent limited to `heartbeat.tools`, a tool-call budget and a timeout,
+//! and alert the user only when the agent has something to say.
+
+use super::engine::HeartbeatEngine;
+use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
+use crate::config::Config;
+use crate::observability::{self, Observer};
+use crate::providers::{self, ChatMessage, Provider};
+use crate::tools::{Tool, ToolEffect, ToolResult};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Reply that means "nothing needs the user's attention".
+pub const SILENT_REPLY: &str = "HEARTBEAT_OK";
+
+/// The prompt for one wake, or `None` when there is nothing to do.
+pub fn build_prompt(standing: Option<&str>, tasks: &[String]) -> Option<String> {
+    let standing = standing.map(str::trim).filter(|s| !s.is_empty());
+    if standing.is_none() && tasks.is_empty() {
+        return None;
+    }
+
+    let mut prompt =
+        String::from("[Heartbeat] You woke up on a schedule; the user did not send a message.\n\n");
+    if let Some(standing) = standing {
+        let _ = write!(prompt, "Standing instructions:\n{standing}\n\n");
+    }
+    if !tasks.is_empty() {
+        prompt.push_str("Tasks:\n");
+        for task in tasks {
+            let _ = writeln!(prompt, "- {task}");
+        }
+        prompt.push('\n');
+    }
+    let _ = write!(
+        prompt,
+        "If nothing needs the user's attention, reply with exactly {SILENT_REPLY}. \
+         Otherwise reply with a short message for the user; it is sent to them as-is."
+    );
+    Some(prompt)
+}
+
+/// Whether a reply means "nothing to report": empty, or starting with
+/// [`SILENT_REPLY`].
+pub fn is_silent(response: &str) -> bool {
+    let response = response.trim();
+    response.is_empty() || response.starts_with(SILENT_REPLY)
+}
+
+/// Wraps a tool so that all wrapped tools share one call budget.
+struct BudgetedTool {
+    inner: Box<dyn Tool>,
+    used: Arc<AtomicU32>,
+    limit: u32,
+}
+
+#[async_trait]
+impl Tool for BudgetedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner.parameters_schema()
+    }
+
+    fn effect(&self) -> ToolEffect {
+        self.inner.effect()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if self.used.fetch_add(1, Ordering::SeqCst) >= self.limit {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "heartbeat tool budget of {} calls is used up; answer with what you have",
+                    self.limit
+                )),
+                attachments: Vec::new(),
+            });
+        }
+        self.inner.execute(args).await
+    }
+}
+
+/// Keep only the `allowed` tools and put them on a shared budget of `limit`
+/// calls.
+fn restrict_tools(tools: Vec<Box<dyn Tool>>, allowed: &[String], limit: u32) -> Vec<Box<dyn Tool>> {
+    let used = Arc::new(AtomicU32::new(0));
+    tools
+        .into_iter()
+        .filter(|tool| allowed.iter().any(|name| name == tool.name()))
+        .map(|inner| {
+            Box::new(BudgetedTool {
+                inner,
+                used: used.clone(),
+                limit,
+            }) as Box<dyn Tool>
+        })
+        .collect()
+}
+
+async fn run_agent(config: &Config, prompt: &str) -> Result<String> {
+    let heartbeat = &config.heartbeat;
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+
+    let mut tools = Vec::new();
+    let mut mcp = None;
+    if !heartbeat.tools.is_empty() {
+        tools = crate::cron::scheduler::build_tool_registry(config)?;
+        let (manager, mcp_tools) = crate::mcp::McpManager::create_mcp_tools(&config.mcp).await?;
+        tools.extend(crate::security::tool_policy::enforce(config, mcp_tools));
+        mcp = Some(manager);
+    }
+    let tools = restrict_tools(tools, &heartbeat.tools, heartbeat.max_tool_calls);
+
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let model_name = heartbeat
+        .model
+        .as_deref()
+        .or(config.default_model.as_deref())
+        .unwrap_or("anthropic/claude-sonnet-4");
+    let provider: Box<dyn Provider> = providers::create_routed_provider(
+        provider_name,
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model_name,
+    )?;
+
+    let mut system_prompt = crate::channels::build_system_prompt(
+        &config.workspace_dir,
+        model_name,
+        &[],
+        &[],
+        Some(&config.identity),
+        None,
+    );
+    if !tools.is_empty() {
+        system_prompt.push_str(&build_tool_instructions(&tools));
+    }
+    let mut history = vec![
+        ChatMessage::system(system_prompt),
+        ChatMessage::user(prompt),
+    ];
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(heartbeat.timeout_secs),
+        run_tool_call_loop(
+            provider.as_ref(),
+            &mut history,
+            &tools,
+            observer.as_ref(),
+            provider_name,
+            model_name,
+            config.default_temperature,
+            true,
+            None,
+            "heartbeat",
+            None,
+        ),
+    )
+    .await;
+
+    if let Some(manager) = mcp {
+        manager.shutdown().await;
+    }
+    result.with_context(|| format!("heartbeat timed out after {}s", heartbeat.timeout_secs))?
+}
+
+/// Run one wake. Returns the alert sent to the user, if any.
+pub async fn tick(config: &Config, engine: &HeartbeatEngine) -> Result<Option<String>> {
+    let tasks = engine.collect_tasks().await?;
+    let Some(prompt) = build_prompt(config.heartbeat.prompt.as_deref(), &tasks) else {
+        return Ok(None);
+    };
+
+    let response = run_agent(config, &prompt).await?;
+    if is_silent(&response) {
+        tracing::debug!("💓 Heartbeat: nothing to report");
+        return Ok(None);
+    }
+
+    let heartbeat = &config.heartbeat;
+    match (heartbeat.channel.as_deref(), heartbeat.to.as_deref()) {
+        (Some(channel), Some(to)) => {
+            crate::cron::scheduler::announce(config, channel, to, &response).await?;
+        }
+        _ => tracing::info!(
+            "💓 Heartbeat alert (set [heartbeat] channel and to for delivery): {response}"
+        ),
+    }
+    Ok(Some(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct EchoTool(&'static str);
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "echo"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: self.0.to_string(),
+                error: None,
+                attachments: Vec::new(),
+            })
+        }
+    }
+
+    #[test]
+    fn prompt_combines_standing_instructions_and_tasks() {
+        assert!(build_prompt(None, &[]).is_none());
+        assert!(build_prompt(Some("  "), &[]).is_none());
+
+        let prompt = build_prompt(
+            Some("Check my inbox and calendar"),
+            &["Water the plants".to_string()],
+        )
+        .unwrap();
+        assert!(prompt.contains("Standing instructions:\nCheck my inbox and calendar"));
+        assert!(prompt.contains("Tasks:\n- Water the plants"));
+        assert!(prompt.contains(SILENT_REPLY));
+    }
+
+    #[test]
+    fn silent_replies_are_not_delivered() {
+        assert!(is_silent("HEARTBEAT_OK"));
+        assert!(is_silent("  HEARTBEAT_OK — inbox is quiet\n"));
+        assert!(is_silent(""));
+        assert!(!is_silent("Your 3pm meeting moved to 2pm."));
+    }
+
+    #[tokio::test]
+    async fn restricted_tools_share_one_call_budget() {
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(EchoTool("memory_recall")),
+            Box::new(EchoTool("http_request")),
+            Box::new(EchoTool("shell")),
+        ];
+        let allowed = vec!["memory_recall".to_string(), "http_request".to_string()];
+        let tools = restrict_tools(tools, &allowed, 2);
+        assert_eq!(tools.len(), 2);
+        assert!(tools.iter().all(|tool| tool.name() != "shell"));
+
+        assert!(tools[0].execute(json!({})).await.unwrap().success);
+        assert!(tools[1].execute(json!({})).await.unwrap().success);
+        let refused = tools[0].execute(json!({})).await.unwrap();
+        assert!(!refused.success);
+        assert!(refused.error.unwrap().contains("budget"));
+    }
+}
@@ -36,16 +36,21 @@
 )]
 
 use clap::Subcommand;
+use clap_complete::engine::ArgValueCandidates;
 use serde::{Deserialize, Serialize};
 
 pub mod agent;
 pub mod approval;
+pub mod bench;
 pub mod channels;
+pub mod completions;
 pub mod config;
 pub mod cost;
 pub mod cron;
 pub mod daemon;
+pub mod db;
 pub mod doctor;
+pub mod events;
 pub mod gateway;
 pub mod hardware;
 pub mod health;
@@ -58,14 +63,21 @@ pub mod migration;
 pub mod observability;
 pub mod onboard;
 pub mod peripherals;
+pub mod plugins;
 pub mod providers;
 pub mod rag;
 pub mod runtime;
 pub mod security;
 pub mod service;
+pub mod sessions;
+pub mod shutdown;
 pub mod skills;
+pub mod stats;
 pub mod tools;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod tunnel;
+pub mod update;
 pub mod util;
 
 pub use config::Config;
@@ -198,6 +210,240 @@ pub enum CronCommands {
     },
 }
 
+/// Database schema subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DbCommands {
+    /// Show each database's schema version and pending migrations
+    Status,
+    /// Apply pending migrations (each database is backed up first)
+    Migrate {
+        /// Only list the migrations that would run
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// Session transcript subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List recorded sessions, most recently active first
+    List,
+    /// Export a session transcript with tool calls and results (secrets redacted)
+    Export {
+        /// Session ID (see `zeroclaw sessions list`)
+        #[arg(add = ArgValueCandidates::new(crate::completions::sessions))]
+        id: String,
+        /// Output format
+        #[arg(long, default_value = "md", value_parser = ["md", "json", "html"])]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+}
+
+/// Memory subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MemoryCommands {
+    /// List stored memories, newest first
+    List {
+        /// Entries to skip
+        #[arg(long, default_value = "0")]
+        offset: usize,
+        /// Entries to show
+        #[arg(long, default_value = "20")]
+        limit: usize,
+        /// Show the oldest entries first
+        #[arg(long)]
+        oldest: bool,
+        /// Memory namespace to list, or "*" for all
+        #[arg(long, default_value = "default")]
+        namespace: String,
+    },
+    /// Delete a memory by key or id, or pick one by search
+    Forget {
+        /// Key or id of the memory, or a search query with --query
+        target: String,
+        /// Search for the memory instead of matching a key or id
+        #[arg(long)]
+        query: bool,
+        /// Delete without asking for confirmation
+        #[arg(long, short)]
+        yes: bool,
+        /// Memory namespace to delete from
+        #[arg(long, default_value = "default")]
+        namespace: String,
+    },
+    /// Replace the content of a memory, re-embedding and re-indexing it
+    Update {
+        /// Key or id of the memory
+        target: String,
+        /// New content
+        content: String,
+        /// Memory namespace of the memory
+        #[arg(long, default_value = "default")]
+        namespace: String,
+    },
+    /// Write all memories to a file or stdout, for backups and migrations
+    Export {
+        /// Output format
+        #[arg(long, default_value = "jsonl", value_parser = ["jsonl", "markdown"])]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Memory namespace to export, or "*" for all
+        #[arg(long, default_value = "*")]
+        namespace: String,
+    },
+    /// Load memories from an export or a Markdown notes file
+    Import {
+        /// File written by `zeroclaw memory export`, or Markdown notes
+        file: std::path::PathBuf,
+        /// Input format (default: markdown for .md files, else jsonl)
+        #[arg(long, value_parser = ["jsonl", "markdown"])]
+        format: Option<String>,
+        /// Put every memory into this namespace instead of the recorded one
+        #[arg(long)]
+        namespace: Option<String>,
+    },
+    /// List memories archived by the `memory.max_memories` budget
+    Archived {
+        /// Memory namespace to list, or "*" for all
+        #[arg(long, default_value = "*")]
+        namespace: String,
+    },
+    /// Move an archived memory back, or replace the memory database with a backup
+    Restore {
+        /// Key of the archived memory
+        #[arg(required_unless_present = "backup", conflicts_with = "backup")]
+        key: Option<String>,
+        /// Restore `brain.db` from this backup (a path, or a file name in
+        /// `memory.backup_dir`) instead
+        #[arg(long)]
+        backup: Option<std::path::PathBuf>,
+        /// Memory namespace it was archived from
+        #[arg(long, default_value = "default")]
+        namespace: String,
+    },
+    /// Back up the memory database to `memory.backup_dir`
+    Backup {
+        /// List existing backups instead
+        #[arg(long)]
+        list: bool,
+    },
+    /// Rebuild the keyword index and embed memories that have no vector yet
+    Reindex {
+        /// Only embed memories that have no vector yet, with progress; an
+        /// interrupted run picks up where it stopped
+        #[arg(long)]
+        embeddings: bool,
+        /// With --embeddings: send at most this many memories per minute
+        /// to the embedding provider (0 for no limit)
+        #[arg(long, default_value = "0", requires = "embeddings")]
+        per_minute: usize,
+    },
+    /// Apply pending schema migrations to the memory database (backed up first)
+    Migrate {
+        /// Show the schema version and pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Encrypt the plaintext memory database with `memory.encryption_key`
+    Encrypt,
+    /// Download the local embedding model (`embedding_provider = "local"`)
+    Model {
+        /// Remove all downloaded local models instead
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+/// Plugin management subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PluginCommands {
+    /// List installed plugins and what they register
+    List,
+    /// Show a plugin's tools, channels and providers
+    Info {
+        /// Plugin name
+        #[arg(add = ArgValueCandidates::new(crate::completions::plugins))]
+        name: String,
+    },
+    /// Enable a disabled plugin
+    Enable {
+        /// Plugin name
+        #[arg(add = ArgValueCandidates::new(crate::completions::plugins))]
+        name: String,
+    },
+    /// Disable a plugin without removing it
+    Disable {
+        /// Plugin name
+        #[arg(add = ArgValueCandidates::new(crate::completions::plugins))]
+        name: String,
+    },
+}
+
+/// MCP server subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpCommands {
+    /// Add the MCP servers configured in Claude Desktop, Cursor or VS Code
+    Import {
+        /// Host whose config to read
+        #[arg(long, value_parser = crate::mcp::import::SOURCES)]
+        from: String,
+        /// Config file to read instead of the host's default location
+        #[arg(long)]
+        path: Option<std::path::PathBuf>,
+        /// Replace servers that are already configured under the same name
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// Connect to every configured MCP server and show whether it works
+    List,
+    /// Connect to an MCP server and show its tools with their parameters
+    Tools {
+        /// Server name from [mcp.servers]
+        #[arg(add = ArgValueCandidates::new(crate::completions::mcp_servers))]
+        server: String,
+    },
+    /// Call a tool on an MCP server directly and print the result
+    Call {
+        /// Server name from [mcp.servers]
+        #[arg(add = ArgValueCandidates::new(crate::completions::mcp_servers))]
+        server: String,
+        /// Tool name as the server lists it
+        tool: String,
+        /// Tool arguments as a JSON object
+        #[arg(long, default_value = "{}")]
+        args: String,
+    },
+    /// Sign in to an OAuth-protected MCP server and store its tokens
+    Login {
+        /// Server name from [mcp.servers]
+        #[arg(add = ArgValueCandidates::new(crate::completions::mcp_servers))]
+        server: String,
+        /// Use the device flow (enter a code on any device) instead of a browser redirect
+        #[arg(long)]
+        device: bool,
+    },
+    /// Show the last stderr lines of a stdio MCP server
+    Logs {
+        /// Server name from [mcp.servers]
+        #[arg(add = ArgValueCandidates::new(crate::completions::mcp_servers))]
+        server: String,
+        /// Number of lines to show
+        #[arg(short = 'n', long, default_value = "50")]
+        lines: usize,
+    },
+    /// Forget the stored tokens of an MCP server
+    Logout {
+        /// Server name from [mcp.servers]
+        #[arg(add = ArgValueCandidates::new(crate::completions::mcp_servers))]
+        server: String,
+    },
+}
+
 /// Integration subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum IntegrationCommands {
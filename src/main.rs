@@ -32,21 +32,27 @@
     dead_code
 )]
 
-use anyhow::{bail, Result};
-use clap::{Parser, Subcommand};
+use anyhow::{bail, Context, Result};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::engine::ArgValueCandidates;
 use tracing::info;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, EnvFilter};
 
 mod agent;
 mod approval;
+mod bench;
 mod channels;
+mod completions;
 mod rag {
     pub use zeroclaw::rag::*;
 }
 mod config;
+mod cost;
 mod cron;
 mod daemon;
+mod db;
 mod doctor;
+mod events;
 mod gateway;
 mod hardware;
 mod health;
@@ -59,20 +65,30 @@ mod migration;
 mod observability;
 mod onboard;
 mod peripherals;
+mod plugins;
 mod providers;
 mod runtime;
 mod security;
 mod service;
+mod sessions;
+mod shutdown;
 mod skillforge;
 mod skills;
+mod stats;
 mod tools;
+#[cfg(feature = "tui")]
+mod tui;
 mod tunnel;
+mod update;
 mod util;
 
 use config::Config;
 
 // Re-export so binary's hardware/peripherals modules can use crate::HardwareCommands etc.
-pub use zeroclaw::{HardwareCommands, PeripheralCommands};
+pub use zeroclaw::{
+    DbCommands, HardwareCommands, McpCommands, MemoryCommands, PeripheralCommands, PluginCommands,
+    SessionCommands,
+};
 
 /// `ZeroClaw` - Zero overhead. Zero compromise. 100% Rust.
 #[derive(Parser, Debug)]
@@ -81,6 +97,10 @@ pub use zeroclaw::{HardwareCommands, PeripheralCommands};
 #[command(version = "0.1.0")]
 #[command(about = "The fastest, smallest AI assistant.", long_about = None)]
 struct Cli {
+    /// Config profile: merge `profiles/<name>.toml` over config.toml
+    #[arg(long, global = true, add = ArgValueCandidates::new(completions::profiles))]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -135,7 +155,7 @@ enum Commands {
         provider: Option<String>,
 
         /// Model to use
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(completions::models))]
         model: Option<String>,
 
         /// Temperature (0.0 - 2.0)
@@ -145,6 +165,10 @@ enum Commands {
         /// Attach a peripheral (board:path, e.g. nucleo-f401re:/dev/ttyACM0)
         #[arg(long)]
         peripheral: Vec<String>,
+
+        /// Full-screen terminal UI (requires a build with `--features tui`)
+        #[arg(long, conflicts_with = "message")]
+        tui: bool,
     },
 
     /// Start the gateway server (webhooks, websockets)
@@ -158,6 +182,40 @@ enum Commands {
         host: Option<String>,
     },
 
+    /// Serve the agent over HTTP (the gateway, optionally with OpenAI-compatible endpoints)
+    Serve {
+        /// Expose /v1/chat/completions and /v1/models so OpenAI clients can use zeroclaw as a model
+        #[arg(long)]
+        openai_compat: bool,
+
+        /// Port to listen on (use 0 for random available port); defaults to config gateway.port
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Host to bind to; defaults to config gateway.host
+        #[arg(long)]
+        host: Option<String>,
+    },
+
+    /// Serve the built-in tools and session transcripts to MCP hosts (stdio by default)
+    McpServe {
+        /// Serve streamable HTTP on POST /mcp instead of stdio
+        #[arg(long)]
+        http: bool,
+
+        /// Port for --http (use 0 for random available port); defaults to config gateway.port
+        #[arg(short, long)]
+        port: Option<u16>,
+
+        /// Host for --http; defaults to config gateway.host
+        #[arg(long)]
+        host: Option<String>,
+
+        /// Only expose these tools (comma-separated; default: all built-in tools)
+        #[arg(long, value_delimiter = ',')]
+        tools: Vec<String>,
+    },
+
     /// Start long-running autonomous runtime (gateway + channels + heartbeat + scheduler)
     Daemon {
         /// Port to listen on (use 0 for random available port); defaults to config gateway.port
@@ -175,18 +233,119 @@ enum Commands {
         service_command: ServiceCommands,
     },
 
-    /// Run diagnostics for daemon/scheduler/channel freshness
-    Doctor,
+    /// Run diagnostics: config, memory DB, daemon freshness, providers, channels, MCP
+    Doctor {
+        /// Skip network checks (provider auth, channel tokens, MCP servers, clock skew)
+        #[arg(long)]
+        offline: bool,
+    },
+
+    /// Update to the newest release for this platform and restart the daemon service
+    SelfUpdate {
+        /// Release channel to follow
+        #[arg(long, default_value = "stable", value_parser = ["stable", "beta"])]
+        channel: String,
+        /// Only check whether an update is available
+        #[arg(long)]
+        check: bool,
+        /// Reinstall even if already up to date
+        #[arg(long)]
+        force: bool,
+        /// Fail unless the release signature is verified with cosign
+        #[arg(long)]
+        require_signature: bool,
+        /// Do not restart the daemon service afterwards
+        #[arg(long)]
+        no_restart: bool,
+    },
 
     /// Show system status (full details)
     Status,
 
+    /// Benchmark provider latency, memory throughput and tool dispatch overhead
+    Bench {
+        /// Suites to run (default: all)
+        #[arg(long, value_delimiter = ',', value_parser = ["provider", "memory", "tools"])]
+        only: Vec<String>,
+        /// Provider to benchmark (defaults to config default_provider)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Model to benchmark (defaults to config default_model)
+        #[arg(long, add = ArgValueCandidates::new(completions::models))]
+        model: Option<String>,
+        /// Timed provider requests
+        #[arg(long, default_value = "5")]
+        runs: usize,
+        /// Memory database sizes to test, in entries
+        #[arg(long, value_delimiter = ',', default_value = "100,1000,10000")]
+        memory_sizes: Vec<usize>,
+        /// Write the JSON report to this file
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// Compare against an earlier JSON report
+        #[arg(long)]
+        baseline: Option<std::path::PathBuf>,
+    },
+
+    /// Usage dashboard: messages, tokens and cost, top tools, memory growth, MCP errors
+    Stats {
+        /// Days to cover, ending today
+        #[arg(long, default_value = "14")]
+        days: u32,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Configure and manage scheduled tasks
     Cron {
         #[command(subcommand)]
         cron_command: CronCommands,
     },
 
+    /// List and export conversation transcripts
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
+    /// Browse stored memories
+    Memory {
+        #[command(subcommand)]
+        memory_command: MemoryCommands,
+    },
+
+    /// Index local files (Markdown, text, code, PDF) for the docs_search tool
+    Ingest {
+        /// File or directory to ingest; directories are walked recursively
+        #[arg(required_unless_present_any = ["list", "forget"])]
+        path: Option<std::path::PathBuf>,
+        /// List ingested documents instead
+        #[arg(long, conflicts_with_all = ["path", "forget"])]
+        list: bool,
+        /// Remove an ingested file, or every document under a directory
+        #[arg(long, conflicts_with = "path")]
+        forget: Option<std::path::PathBuf>,
+    },
+
+    /// Inspect and migrate database schemas (memory, scheduler, caches)
+    Db {
+        #[command(subcommand)]
+        db_command: DbCommands,
+    },
+
+    /// Manage plugins in ~/.zeroclaw/plugins (tools, channels, providers)
+    Plugins {
+        #[command(subcommand)]
+        plugin_command: PluginCommands,
+    },
+
+    /// Sign in to OAuth-protected MCP servers
+    Mcp {
+        #[command(subcommand)]
+        mcp_command: McpCommands,
+    },
+
     /// Manage provider model catalogs
     Models {
         #[command(subcommand)]
@@ -217,6 +376,12 @@ enum Commands {
         migrate_command: MigrateCommands,
     },
 
+    /// Validate the config file and manage encrypted config values
+    Config {
+        #[command(subcommand)]
+        config_command: ConfigCommands,
+    },
+
     /// Discover and introspect USB hardware
     Hardware {
         #[command(subcommand)]
@@ -230,6 +395,23 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Check config syntax, providers, URLs, env vars and MCP server commands
+    Validate {
+        /// Config file to check (defaults to the active config.toml)
+        #[arg(long)]
+        path: Option<std::path::PathBuf>,
+    },
+    /// Generate a master key for `encm:` encrypted config values
+    Keygen,
+    /// Encrypt a value with the master key and print it as `encm:...`
+    Encrypt {
+        /// Value to encrypt (read from stdin when omitted, keeping it out of shell history)
+        value: Option<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum MigrateCommands {
     /// Import memory from an `OpenClaw` workspace into this `ZeroClaw` workspace
@@ -282,16 +464,19 @@ enum CronCommands {
     /// Remove a scheduled task
     Remove {
         /// Task ID
+        #[arg(add = ArgValueCandidates::new(completions::cron_jobs))]
         id: String,
     },
     /// Pause a scheduled task
     Pause {
         /// Task ID
+        #[arg(add = ArgValueCandidates::new(completions::cron_jobs))]
         id: String,
     },
     /// Resume a paused task
     Resume {
         /// Task ID
+        #[arg(add = ArgValueCandidates::new(completions::cron_jobs))]
         id: String,
     },
 }
@@ -349,6 +534,7 @@ enum SkillCommands {
     /// Remove an installed skill
     Remove {
         /// Skill name
+        #[arg(add = ArgValueCandidates::new(completions::skills))]
         name: String,
     },
 }
@@ -358,6 +544,7 @@ enum IntegrationCommands {
     /// Show details about a specific integration
     Info {
         /// Integration name
+        #[arg(add = ArgValueCandidates::new(completions::integrations))]
         name: String,
     },
 }
@@ -365,6 +552,9 @@ enum IntegrationCommands {
 #[tokio::main]
 #[allow(clippy::too_many_lines)]
 async fn main() -> Result<()> {
+    // Answer shell completion requests (COMPLETE=<shell>) before any output.
+    clap_complete::CompleteEnv::with_factory(Cli::command).complete();
+
     // Install default crypto provider for Rustls TLS.
     // This prevents the error: "could not automatically determine the process-level CryptoProvider"
     // when both aws-lc-rs and ring features are available (or neither is explicitly selected).
@@ -374,15 +564,41 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
-    // Initialize logging - respects RUST_LOG env var, defaults to INFO
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .finish();
+    // Exported so every config load (including the onboarding wizard) sees it.
+    if let Some(profile) = &cli.profile {
+        std::env::set_var(config::schema::PROFILE_ENV, profile);
+    }
 
+    // Initialize logging - respects RUST_LOG env var, defaults to INFO.
+    // The OTel trace layer is installed once the config is loaded.
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let json_logs = observability::json_log::configured_log_format() == "json";
+    // Over stdio, stdout carries the MCP messages.
+    let stderr_logs = matches!(cli.command, Commands::McpServe { http: false, .. });
+    let (otel_layer, otel_handle) =
+        reload::Layer::new(None::<observability::otel_trace::OtelTraceLayer>);
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(otel_layer)
+        .with(
+            (json_logs && !stderr_logs)
+                .then(|| observability::json_log::JsonLayer::new(std::io::stdout)),
+        )
+        .with(
+            (json_logs && stderr_logs)
+                .then(|| observability::json_log::JsonLayer::new(std::io::stderr)),
+        )
+        .with((!json_logs && !stderr_logs).then(fmt::layer))
+        .with((!json_logs && stderr_logs).then(|| fmt::layer().with_writer(std::io::stderr)));
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
+    // Config commands must run before loading, so that parse errors are
+    // reported with locations and keys can be managed for a config that does
+    // not load yet.
+    if let Commands::Config { config_command } = &cli.command {
+        return handle_config_command(config_command, cli.profile.as_deref());
+    }
+
     // Onboard runs quick setup by default, or the interactive wizard with --interactive.
     // The onboard wizard uses reqwest::blocking internally, which creates its own
     // Tokio runtime. To avoid "Cannot drop a runtime in a context where blocking is
@@ -428,9 +644,52 @@ async fn main() -> Result<()> {
     // All other commands need config loaded first
     let mut config = Config::load_or_init()?;
     config.apply_env_overrides();
+    plugins::init(&config);
+    security::limits::init(&config.security.resources);
+    events::init(&config);
+    mcp::auth::init(&config);
+    mcp::stderr::init(&config);
+    mcp::audit::init(&config);
+    mcp::sampling::init(&config);
+    mcp::roots::update(&config);
+
+    let _otel_traces = if config.observability.otel_traces {
+        match observability::otel_trace::OtelTraceLayer::from_config(&config.observability) {
+            Ok((layer, guard)) => {
+                if let Err(e) = otel_handle.modify(|slot| *slot = Some(layer)) {
+                    tracing::warn!("Failed to enable OpenTelemetry traces: {e}");
+                }
+                Some(guard)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to enable OpenTelemetry traces: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     match cli.command {
-        Commands::Onboard { .. } => unreachable!(),
+        Commands::Onboard { .. } | Commands::Config { .. } => unreachable!(),
+
+        Commands::Agent {
+            tui: true,
+            provider,
+            model,
+            temperature,
+            ..
+        } => {
+            #[cfg(feature = "tui")]
+            {
+                tui::run(config, provider, model, temperature).await
+            }
+            #[cfg(not(feature = "tui"))]
+            {
+                let _ = (config, provider, model, temperature);
+                bail!("This build has no TUI support. Rebuild with `cargo build --features tui`.")
+            }
+        }
 
         Commands::Agent {
             message,
@@ -438,6 +697,7 @@ async fn main() -> Result<()> {
             model,
             temperature,
             peripheral,
+            tui: false,
         } => agent::run(config, message, provider, model, temperature, peripheral)
             .await
             .map(|_| ()),
@@ -453,6 +713,33 @@ async fn main() -> Result<()> {
             gateway::run_gateway(&host, port, config).await
         }
 
+        Commands::Serve {
+            openai_compat,
+            port,
+            host,
+        } => {
+            let port = port.unwrap_or(config.gateway.port);
+            let host = host.unwrap_or_else(|| config.gateway.host.clone());
+            config.gateway.openai_compat |= openai_compat;
+            info!("🚀 Serving ZeroClaw on {host}:{port}");
+            gateway::run_gateway(&host, port, config).await
+        }
+
+        Commands::McpServe {
+            http,
+            port,
+            host,
+            tools,
+        } => {
+            let http = http.then(|| {
+                (
+                    host.unwrap_or_else(|| config.gateway.host.clone()),
+                    port.unwrap_or(config.gateway.port),
+                )
+            });
+            mcp::server::run(config, &tools, http).await
+        }
+
         Commands::Daemon { port, host } => {
             let port = port.unwrap_or(config.gateway.port);
             let host = host.unwrap_or_else(|| config.gateway.host.clone());
@@ -544,8 +831,51 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
+        Commands::Bench {
+            only,
+            provider,
+            model,
+            runs,
+            memory_sizes,
+            output,
+            baseline,
+        } => {
+            let suite = |name: &str| only.is_empty() || only.iter().any(|s| s == name);
+            let options = bench::BenchOptions {
+                provider: suite("provider"),
+                memory: suite("memory"),
+                tools: suite("tools"),
+                provider_name: provider,
+                model,
+                runs,
+                memory_sizes,
+            };
+            bench::handle_command(&config, &options, output, baseline).await
+        }
+
+        Commands::Stats { days, json } => stats::handle_command(&config, days, json).await,
+
         Commands::Cron { cron_command } => cron::handle_command(cron_command, &config),
 
+        Commands::Sessions { session_command } => {
+            sessions::handle_command(session_command, &config)
+        }
+
+        Commands::Memory { memory_command } => {
+            memory::handle_command(memory_command, &config).await
+        }
+
+        Commands::Ingest { path, list, forget } => {
+            memory::documents::handle_command(&config, path.as_deref(), list, forget.as_deref())
+                .await
+        }
+
+        Commands::Db { db_command } => db::handle_command(db_command, &config),
+
+        Commands::Plugins { plugin_command } => plugins::handle_command(plugin_command, &config),
+
+        Commands::Mcp { mcp_command } => mcp::handle_command(mcp_command, &config).await,
+
         Commands::Models { model_command } => match model_command {
             ModelCommands::Refresh { provider, force } => {
                 onboard::run_models_refresh(&config, provider.as_deref(), force)
@@ -554,7 +884,27 @@ async fn main() -> Result<()> {
 
         Commands::Service { service_command } => service::handle_command(&service_command, &config),
 
-        Commands::Doctor => doctor::run(&config),
+        Commands::Doctor { offline } => doctor::run(&config, offline).await,
+
+        Commands::SelfUpdate {
+            channel,
+            check,
+            force,
+            require_signature,
+            no_restart,
+        } => {
+            update::run(
+                &config,
+                update::UpdateOptions {
+                    channel: channel.parse()?,
+                    check,
+                    force,
+                    require_signature,
+                    no_restart,
+                },
+            )
+            .await
+        }
 
         Commands::Channel { channel_command } => match channel_command {
             ChannelCommands::Start => channels::start_channels(config).await,
@@ -584,6 +934,81 @@ async fn main() -> Result<()> {
     }
 }
 
+fn handle_config_command(command: &ConfigCommands, profile: Option<&str>) -> Result<()> {
+    match command {
+        ConfigCommands::Validate { path } => {
+            let path = match path {
+                Some(path) => path.clone(),
+                None => Config::resolve_config_path()?,
+            };
+            let issues = config::validate::validate_config_file(&path)?;
+            let mut errors = config::validate::print_report(&path, &issues);
+            if let Some(profile) = profile {
+                // Lines in the merged document don't map back to either file.
+                let merged = config::schema::read_config_source(&path, Some(profile))?;
+                let issues: Vec<_> = config::validate::validate_config_str(&merged)
+                    .into_iter()
+                    .map(|issue| config::validate::ConfigIssue {
+                        line: None,
+                        ..issue
+                    })
+                    .collect();
+                println!();
+                let overlay = config::schema::profile_overlay_path(&path, profile);
+                errors += config::validate::print_report(&overlay, &issues);
+            }
+            if errors > 0 {
+                bail!("{} has {errors} error(s)", path.display());
+            }
+            Ok(())
+        }
+        ConfigCommands::Keygen => {
+            println!("{}", security::secrets::MasterKey::generate_hex());
+            eprintln!();
+            eprintln!(
+                "Store this key outside the config directory, then export it as {} \
+                 or point {} / secrets.master_key_file at a file containing it.",
+                security::secrets::MASTER_KEY_ENV,
+                security::secrets::MASTER_KEY_FILE_ENV
+            );
+            Ok(())
+        }
+        ConfigCommands::Encrypt { value } => {
+            let config_path = Config::resolve_config_path()?;
+            let key_file = std::fs::read_to_string(&config_path)
+                .ok()
+                .and_then(|raw| raw.parse::<toml::Table>().ok())
+                .and_then(|table| {
+                    table
+                        .get("secrets")?
+                        .get("master_key_file")?
+                        .as_str()
+                        .map(str::to_string)
+                });
+            let key = config::schema::master_key_for(&config_path, key_file.as_deref())?
+                .with_context(|| {
+                    format!(
+                        "No master key found; run `zeroclaw config keygen` and set {}",
+                        security::secrets::MASTER_KEY_ENV
+                    )
+                })?;
+            let value = match value {
+                Some(value) => value.clone(),
+                None => {
+                    let mut input = String::new();
+                    std::io::stdin().read_line(&mut input)?;
+                    input.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            if value.is_empty() {
+                bail!("Nothing to encrypt");
+            }
+            println!("{}", key.seal(&value)?);
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,207 @@
+//! Checking tool call arguments against a tool's `inputSchema` before they
+//! are sent to the server.
+//!
+//! Covers the JSON Schema keywords tool schemas use in practice: `type`,
+//! `required`, `properties`, `additionalProperties`, `items`, `enum`,
+//! `const`, `anyOf` / `oneOf`, and the length and range limits. Anything
+//! else, including `$ref`, is left for the server to check.
+
+use serde_json::Value;
+
+/// Problems with `args`, one per line-ready message naming the field, e.g.
+/// ``"`days`: expected integer, got string"``. Empty when they fit.
+pub fn problems(schema: &Value, args: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    check(schema, args, "", &mut problems);
+    problems
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        other => type_name(value) == other,
+    }
+}
+
+/// `path` for messages: the argument itself at the top level.
+fn label(path: &str) -> &str {
+    if path.is_empty() {
+        "arguments"
+    } else {
+        path
+    }
+}
+
+fn check(schema: &Value, value: &Value, path: &str, problems: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+    let at = label(path);
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(key).and_then(Value::as_array) {
+            let fits = branches.iter().any(|branch| {
+                let mut branch_problems = Vec::new();
+                check(branch, value, path, &mut branch_problems);
+                branch_problems.is_empty()
+            });
+            if !fits {
+                problems.push(format!("`{at}`: does not match any of the allowed forms"));
+                return;
+            }
+        }
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        problems.push(format!(
+            "`{at}`: expected {}, got {}",
+            types.join(" or "),
+            type_name(value)
+        ));
+        return;
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            problems.push(format!(
+                "`{at}`: must be one of {}, got {value}",
+                allowed.join(", ")
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            problems.push(format!("`{at}`: must be {expected}, got {value}"));
+        }
+    }
+
+    let limit = |key: &str| schema.get(key).and_then(Value::as_f64);
+    match value {
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = limit("minimum").filter(|min| n < *min) {
+                problems.push(format!("`{at}`: must be at least {min}, got {n}"));
+            }
+            if let Some(max) = limit("maximum").filter(|max| n > *max) {
+                problems.push(format!("`{at}`: must be at most {max}, got {n}"));
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as f64;
+            if let Some(min) = limit("minLength").filter(|min| len < *min) {
+                problems.push(format!("`{at}`: must be at least {min} characters long"));
+            }
+            if let Some(max) = limit("maxLength").filter(|max| len > *max) {
+                problems.push(format!("`{at}`: must be at most {max} characters long"));
+            }
+        }
+        Value::Array(items) => {
+            let len = items.len() as f64;
+            if let Some(min) = limit("minItems").filter(|min| len < *min) {
+                problems.push(format!("`{at}`: must have at least {min} items"));
+            }
+            if let Some(max) = limit("maxItems").filter(|max| len > *max) {
+                problems.push(format!("`{at}`: must have at most {max} items"));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{at}[{i}]"), problems);
+                }
+            }
+        }
+        Value::Object(fields) => {
+            let child = |name: &str| {
+                if path.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{path}.{name}")
+                }
+            };
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !fields.contains_key(name) {
+                        problems.push(format!("`{}`: missing required field", child(name)));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, field) in fields {
+                match properties.and_then(|p| p.get(name)) {
+                    Some(field_schema) => check(field_schema, field, &child(name), problems),
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        problems.push(format!("`{}`: unknown field", child(name)));
+                    }
+                    None => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn problems_name_the_offending_fields() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "city": { "type": "string", "minLength": 1 },
+                "days": { "type": "integer", "minimum": 1, "maximum": 14 },
+                "units": { "enum": ["metric", "imperial"] },
+                "tags": { "type": "array", "items": { "type": "string" } },
+                "at": { "anyOf": [{ "type": "string" }, { "type": "null" }] },
+            },
+            "required": ["city"],
+            "additionalProperties": false,
+        });
+        assert!(problems(&schema, &json!({ "city": "Oslo", "days": 3, "at": null })).is_empty());
+        assert_eq!(
+            problems(
+                &schema,
+                &json!({ "days": "3", "units": "kelvin", "tags": ["a", 1], "at": 5, "x": true })
+            ),
+            [
+                "`city`: missing required field",
+                "`at`: does not match any of the allowed forms",
+                "`days`: expected integer, got string",
+                "`tags[1]`: expected string, got integer",
+                "`units`: must be one of \"metric\", \"imperial\", got \"kelvin\"",
+                "`x`: unknown field",
+            ]
+        );
+        assert_eq!(
+            problems(&schema, &json!({ "city": "", "days": 30 })),
+            [
+                "`city`: must be at least 1 characters long",
+                "`days`: must be at most 14, got 30",
+            ]
+        );
+        assert_eq!(
+            problems(&schema, &json!("Oslo")),
+            ["`arguments`: expected object, got string"]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Audit log of MCP tool calls.
+//!
+//! With `[mcp.audit] enabled = true`, every `tools/call` is appended to a
+//! JSONL file (`mcp_audit.jsonl` next to the config file by default): when
+//! it ran, the server, tool and arguments, the result cut off at
+//! `max_result_chars`, whether it failed, how long it took, and the
+//! channel, sender and session of the turn that made it. The file is only
+//! ever appended to.
+
+use super::protocol::ToolCallResult;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+
+static LOG: OnceLock<AuditLog> = OnceLock::new();
+
+/// Start recording tool calls if the config enables it. Called once at
+/// startup.
+pub fn init(config: &Config) {
+    let audit = &config.mcp.audit;
+    if audit.enabled {
+        let _ = LOG.set(AuditLog::new(log_path(config), audit.max_result_chars));
+    }
+}
+
+/// The audit log file `config` writes to.
+pub fn log_path(config: &Config) -> PathBuf {
+    let dir = config
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    match &config.mcp.audit.path {
+        Some(path) => dir.join(shellexpand::tilde(path).as_ref()),
+        None => dir.join("mcp_audit.jsonl"),
+    }
+}
+
+/// Whether tool calls are being recorded.
+pub fn enabled() -> bool {
+    LOG.get().is_some()
+}
+
+/// Record a call of `tool` on `server`. Does nothing unless [`init`]
+/// enabled the log; failures to write are logged, never returned.
+pub fn record(
+    server: &str,
+    tool: &str,
+    arguments: &Value,
+    result: &Result<ToolCallResult>,
+    elapsed: Duration,
+) {
+    let Some(log) = LOG.get() else {
+        return;
+    };
+    if let Err(e) = log.record(server, tool, arguments, result, elapsed) {
+        tracing::warn!(
+            "Failed to write MCP audit log {}: {e:#}",
+            log.path.display()
+        );
+    }
+}
+
+/// An append-only JSONL file of tool calls.
+pub struct AuditLog {
+    path: PathBuf,
+    max_result_chars: usize,
+    /// Keeps lines written from different tasks whole.
+    write: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf, max_result_chars: usize) -> Self {
+        Self {
+            path,
+            max_result_chars,
+            write: Mutex::new(()),
+        }
+    }
+
+    /// Append one line for a call, with the channel, sender and session of
+    /// the running turn.
+    pub fn record(
+        &self,
+        server: &str,
+        tool: &str,
+        arguments: &Value,
+        result: &Result<ToolCallResult>,
+        elapsed: Duration,
+    ) -> Result<()> {
+        let (channel, sender) = super::caller();
+        let (output, error) = match result {
+            Ok(result) => (
+                Some(truncate(&result_text(result), self.max_result_chars)),
+                None,
+            ),
+            Err(e) => (None, Some(format!("{e:#}"))),
+        };
+        let entry = json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "server": server,
+            "tool": tool,
+            "arguments": arguments,
+            "result": output,
+            "success": result.as_ref().is_ok_and(|r| !r.is_error),
+            "error": error,
+            "duration_ms": u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            "channel": channel,
+            "sender": sender,
+            "session": crate::sessions::current_session_id(),
+        });
+
+        let _guard = self.write.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        writeln!(file, "{entry}")?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// The text of a result: its text items, a `[mime/type]` placeholder for
+/// other items, or the structured content when there are no items.
+fn result_text(result: &ToolCallResult) -> String {
+    if result.content.is_empty() {
+        return result
+            .structured_content
+            .as_ref()
+            .map(Value::to_string)
+            .unwrap_or_default();
+    }
+    result
+        .content
+        .iter()
+        .map(|item| match &item.text {
+            Some(text) => text.clone(),
+            None => format!(
+                "[{}]",
+                item.mime_type.as_deref().unwrap_or(&item.content_type)
+            ),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `text` cut off after `max` characters, marked with `…`.
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::protocol::McpContent;
+
+    #[tokio::test]
+    async fn calls_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit").join("mcp_audit.jsonl");
+        let log = AuditLog::new(path.clone(), 5);
+        let ok = ToolCallResult {
+            content: vec![McpContent {
+                content_type: "text".into(),
+                text: Some("sunny and warm".into()),
+                data: None,
+                mime_type: None,
+                resource: None,
+            }],
+            structured_content: None,
+            is_error: false,
+        };
+        let turn = crate::mcp::Turn {
+            channel: Some("telegram".into()),
+            sender: Some("alice".into()),
+            ..crate::mcp::Turn::default()
+        };
+        crate::mcp::scope(turn, async {
+            log.record(
+                "weather",
+                "forecast",
+                &json!({ "city": "Oslo" }),
+                &Ok(ok),
+                Duration::from_millis(42),
+            )
+            .unwrap();
+        })
+        .await;
+        log.record(
+            "weather",
+            "forecast",
+            &json!({}),
+            &Err(anyhow::anyhow!("MCP tools/call timed out after 30s")),
+            Duration::from_secs(30),
+        )
+        .unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["server"], "weather");
+        assert_eq!(lines[0]["tool"], "forecast");
+        assert_eq!(lines[0]["arguments"], json!({ "city": "Oslo" }));
+        assert_eq!(lines[0]["result"], "sunny…");
+        assert_eq!(lines[0]["success"], true);
+        assert_eq!(lines[0]["duration_ms"], 42);
+        assert_eq!(lines[0]["channel"], "telegram");
+        assert_eq!(lines[0]["sender"], "alice");
+        assert_eq!(lines[1]["success"], false);
+        assert_eq!(lines[1]["result"], Value::Null);
+        assert_eq!(lines[1]["error"], "MCP tools/call timed out after 30s");
+        assert_eq!(lines[1]["channel"], Value::Null);
+    }
+}
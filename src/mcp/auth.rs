@@ -0,0 +1,1055 @@
+//! OAuth 2.1 authorization for remote MCP servers.
+//!
+//! Servers with an `[mcp.servers.<name>.oauth]` section get their access
+//! token the way the MCP authorization spec describes: the authorization
+//! server is discovered from the server's protected-resource and
+//! authorization-server metadata, the client registers itself dynamically
+//! unless a `client_id` is configured, and the user signs in with the
+//! authorization code grant (PKCE, loopback redirect) or, when they are not
+//! at this machine and the server supports it, the device authorization
+//! grant. In a terminal the sign-in link opens in the browser; during a
+//! channel turn it is posted to that channel instead (see [`super::scope`]).
+//!
+//! Tokens are kept in `mcp_tokens.json` next to `config.toml`, encrypted
+//! with the secret store, and refreshed shortly before they expire.
+
+use crate::config::Config;
+use crate::mcp::config::{McpOAuthConfig, McpServerConfig};
+use crate::mcp::notifier;
+use crate::security::SecretStore;
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+const TOKENS_FILE: &str = "mcp_tokens.json";
+const CLIENT_NAME: &str = "ZeroClaw";
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// How long the user has to finish signing in.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Tokens this close to expiry are refreshed before use.
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+static STORE: OnceLock<TokenStore> = OnceLock::new();
+/// Serializes read-modify-write cycles on the token file.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Remember where tokens are stored. Called once at startup.
+pub fn init(config: &Config) {
+    let dir = config
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    let _ = STORE.set(TokenStore::new(dir, config.secrets.encrypt));
+}
+
+fn store() -> Result<TokenStore> {
+    if let Some(store) = STORE.get() {
+        return Ok(store.clone());
+    }
+    let dir = directories::UserDirs::new()
+        .map(|u| u.home_dir().join(".zeroclaw"))
+        .context("Could not find home directory")?;
+    Ok(TokenStore::new(&dir, true))
+}
+
+/// Whether the user sits at this machine, so a loopback redirect reaches us.
+fn is_local() -> bool {
+    notifier().is_none() && std::io::stderr().is_terminal()
+}
+
+/// Show `text` to the user. Returns `false` when nobody would see it.
+fn announce(text: &str) -> bool {
+    if let Some(notify) = notifier() {
+        notify(text.to_string());
+        return true;
+    }
+    if std::io::stderr().is_terminal() {
+        eprintln!("{text}");
+        return true;
+    }
+    false
+}
+
+fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let _ = std::process::Command::new(opener)
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+}
+
+// ── Token source ────────────────────────────────────────────────
+
+/// Bearer tokens for one OAuth-protected server, shared by its transport.
+pub struct OAuthSession {
+    server_name: String,
+    config: McpServerConfig,
+    token: tokio::sync::Mutex<Option<StoredToken>>,
+}
+
+impl OAuthSession {
+    /// A session for `config`, or `None` if the server does not use OAuth.
+    pub fn new(server_name: &str, config: &McpServerConfig) -> Option<Arc<Self>> {
+        config.oauth.as_ref()?;
+        Some(Arc::new(Self {
+            server_name: server_name.to_string(),
+            config: config.clone(),
+            token: tokio::sync::Mutex::new(None),
+        }))
+    }
+
+    /// A valid access token: the cached or stored one, a refreshed one, or
+    /// one from a new sign-in when refreshing is impossible.
+    pub async fn bearer(&self) -> Result<String> {
+        let resource = self.config.url.as_deref().unwrap_or_default();
+        let mut cached = self.token.lock().await;
+        if cached.is_none() {
+            *cached = store()?
+                .get(&self.server_name)?
+                .filter(|token| token.resource == resource);
+        }
+        if let Some(token) = cached.as_ref().filter(|t| t.is_fresh(Utc::now())) {
+            return Ok(token.access_token.clone());
+        }
+
+        let refreshed = match cached.take() {
+            Some(token) if token.refresh_token.is_some() => {
+                match refresh(&http_client(&self.config)?, &token).await {
+                    Ok(token) => Some(token),
+                    Err(e) => {
+                        tracing::warn!(
+                            server = %self.server_name,
+                            error = %e,
+                            "MCP OAuth refresh failed — signing in again"
+                        );
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let token = match refreshed {
+            Some(token) => token,
+            None => authorize(&self.server_name, &self.config, false).await?,
+        };
+        store()?.put(&self.server_name, &token)?;
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    /// Mark the cached token as expired after the server rejected it, so
+    /// the next [`bearer`](Self::bearer) refreshes it.
+    pub async fn invalidate(&self) {
+        if let Some(token) = self.token.lock().await.as_mut() {
+            token.expires_at = Some(Utc::now());
+        }
+    }
+}
+
+/// `zeroclaw mcp login`: sign in to `server_name` now and store its tokens.
+pub async fn login(server_name: &str, config: &McpServerConfig, device: bool) -> Result<()> {
+    let token = authorize(server_name, config, device).await?;
+    store()?.put(server_name, &token)
+}
+
+/// `zeroclaw mcp logout`: forget `server_name`'s tokens. Returns whether
+/// any were stored.
+pub fn logout(server_name: &str) -> Result<bool> {
+    store()?.remove(server_name)
+}
+
+// ── Authorization flows ─────────────────────────────────────────
+
+/// Run the full authorization flow for `server_name`, prompting the user.
+async fn authorize(
+    server_name: &str,
+    config: &McpServerConfig,
+    force_device: bool,
+) -> Result<StoredToken> {
+    let oauth = config.oauth.clone().unwrap_or_default();
+    let resource = config
+        .url
+        .as_deref()
+        .context("OAuth requires the server url")?;
+    let client = http_client(config)?;
+    let metadata = discover(&client, resource).await?;
+
+    let device_endpoint = metadata.device_authorization_endpoint.as_deref();
+    match device_endpoint {
+        Some(endpoint) if force_device || !is_local() => {
+            device_flow(&client, server_name, &oauth, &metadata, endpoint, resource).await
+        }
+        None if force_device => {
+            bail!("The authorization server for '{server_name}' does not support the device flow")
+        }
+        _ => code_flow(&client, server_name, &oauth, &metadata, resource).await,
+    }
+}
+
+/// Authorization code grant with PKCE and a loopback redirect.
+async fn code_flow(
+    client: &reqwest::Client,
+    server_name: &str,
+    oauth: &McpOAuthConfig,
+    metadata: &AuthServerMetadata,
+    resource: &str,
+) -> Result<StoredToken> {
+    let listener = TcpListener::bind(("127.0.0.1", oauth.redirect_port.unwrap_or(0)))
+        .await
+        .context("Failed to bind the OAuth redirect listener")?;
+    let redirect_uri = format!(
+        "http://127.0.0.1:{}/callback",
+        listener.local_addr()?.port()
+    );
+    let credentials = client_credentials(client, oauth, metadata, Some(&redirect_uri)).await?;
+    let verifier = random_token();
+    let state = random_token();
+    let url = authorization_url(
+        &metadata.authorization_endpoint,
+        &credentials.id,
+        &redirect_uri,
+        &pkce_challenge(&verifier),
+        &state,
+        &oauth.scopes,
+        resource,
+    )?;
+
+    if is_local() {
+        open_browser(&url);
+    }
+    if !announce(&format!(
+        "🔐 MCP server '{server_name}' needs authorization. Sign in here:\n{url}"
+    )) {
+        bail!("MCP server '{server_name}' needs authorization: run `zeroclaw mcp login {server_name}`");
+    }
+    let code = tokio::time::timeout(AUTHORIZE_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .with_context(|| format!("Timed out waiting for authorization of '{server_name}'"))??;
+
+    let response = post_token(
+        client,
+        &metadata.token_endpoint,
+        &credentials,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("code_verifier", &verifier),
+            ("resource", resource),
+        ],
+    )
+    .await??;
+    Ok(StoredToken::issued(
+        response,
+        None,
+        &metadata.token_endpoint,
+        credentials,
+        resource,
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+/// Device authorization grant (RFC 8628): the user signs in anywhere with a
+/// short code while we poll the token endpoint.
+async fn device_flow(
+    client: &reqwest::Client,
+    server_name: &str,
+    oauth: &McpOAuthConfig,
+    metadata: &AuthServerMetadata,
+    endpoint: &str,
+    resource: &str,
+) -> Result<StoredToken> {
+    let credentials = client_credentials(client, oauth, metadata, None).await?;
+    let scope = oauth.scopes.join(" ");
+    let mut form = vec![
+        ("client_id", credentials.id.as_str()),
+        ("resource", resource),
+    ];
+    if !scope.is_empty() {
+        form.push(("scope", &scope));
+    }
+    let resp = client
+        .post(endpoint)
+        .form(&form)
+        .send()
+        .await
+        .context("Device authorization request failed")?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        bail!("Device authorization request failed: HTTP {status}: {body}");
+    }
+    let device: DeviceAuthorization = resp
+        .json()
+        .await
+        .context("Invalid device authorization response")?;
+
+    let link = device
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&device.verification_uri);
+    if is_local() {
+        open_browser(link);
+    }
+    if !announce(&format!(
+        "🔐 MCP server '{server_name}' needs authorization. Visit {} and enter code {}",
+        device.verification_uri, device.user_code
+    )) {
+        bail!("MCP server '{server_name}' needs authorization: run `zeroclaw mcp login {server_name}`");
+    }
+
+    let expires_in = Duration::from_secs(device.expires_in.unwrap_or(u64::MAX));
+    let deadline = tokio::time::Instant::now() + AUTHORIZE_TIMEOUT.min(expires_in);
+    let mut interval = Duration::from_secs(device.interval.unwrap_or(5).max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        if tokio::time::Instant::now() >= deadline {
+            bail!("Timed out waiting for authorization of '{server_name}'");
+        }
+        let grant = post_token(
+            client,
+            &metadata.token_endpoint,
+            &credentials,
+            &[
+                ("grant_type", DEVICE_CODE_GRANT),
+                ("device_code", &device.device_code),
+                ("resource", resource),
+            ],
+        )
+        .await?;
+        match grant {
+            Ok(response) => {
+                return Ok(StoredToken::issued(
+                    response,
+                    None,
+                    &metadata.token_endpoint,
+                    credentials,
+                    resource,
+                ))
+            }
+            Err(e) if e.error == "authorization_pending" => {}
+            Err(e) if e.error == "slow_down" => interval += Duration::from_secs(5),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+async fn refresh(client: &reqwest::Client, token: &StoredToken) -> Result<StoredToken> {
+    let refresh_token = token
+        .refresh_token
+        .as_deref()
+        .context("No refresh token stored")?;
+    let credentials = ClientCredentials {
+        id: token.client_id.clone(),
+        secret: token.client_secret.clone(),
+    };
+    let response = post_token(
+        client,
+        &token.token_endpoint,
+        &credentials,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("resource", &token.resource),
+        ],
+    )
+    .await??;
+    Ok(StoredToken::issued(
+        response,
+        token.refresh_token.clone(),
+        &token.token_endpoint,
+        credentials,
+        &token.resource,
+    ))
+}
+
+/// Accept loopback requests until the redirect to `/callback` arrives, and
+/// return its authorization code.
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = vec![0u8; 8192];
+        let n = stream.read(&mut buf).await.unwrap_or(0);
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/");
+        let outcome = parse_callback(target, state);
+        let (status, page) = match &outcome {
+            None => ("404 Not Found", "Not found".to_string()),
+            Some(Ok(_)) => (
+                "200 OK",
+                "Authorized. You can close this tab and return to ZeroClaw.".to_string(),
+            ),
+            Some(Err(e)) => ("400 Bad Request", format!("Authorization failed: {e}")),
+        };
+        let _ = stream
+            .write_all(
+                format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+                    page.len()
+                )
+                .as_bytes(),
+            )
+            .await;
+        if let Some(outcome) = outcome {
+            return outcome;
+        }
+    }
+}
+
+/// The authorization code from a redirect request target, `None` for
+/// requests other than `/callback` (a browser fetching the favicon).
+fn parse_callback(target: &str, expected_state: &str) -> Option<Result<String>> {
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{target}")).ok()?;
+    if url.path() != "/callback" {
+        return None;
+    }
+    let params: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+    Some(if let Some(error) = params.get("error") {
+        let description = params.get("error_description").map_or("", String::as_str);
+        Err(anyhow::anyhow!("{error} {description}").context("Authorization was denied"))
+    } else if params.get("state").map(String::as_str) != Some(expected_state) {
+        Err(anyhow::anyhow!(
+            "Authorization redirect has a mismatched state"
+        ))
+    } else {
+        params
+            .get("code")
+            .cloned()
+            .context("Authorization redirect carries no code")
+    })
+}
+
+// ── Discovery and registration ──────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+struct AuthServerMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    registration_endpoint: Option<String>,
+    #[serde(default)]
+    device_authorization_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProtectedResourceMetadata {
+    #[serde(default)]
+    authorization_servers: Vec<String>,
+}
+
+/// Find the authorization server for `resource`: protected-resource
+/// metadata names it (else it is the resource's origin), then its own
+/// metadata lists the endpoints. Servers publishing neither get the
+/// spec's default `/authorize`, `/token` and `/register` paths.
+async fn discover(client: &reqwest::Client, resource: &str) -> Result<AuthServerMetadata> {
+    let mut issuer = origin(resource)?;
+    for url in well_known_urls(resource, "oauth-protected-resource")? {
+        if let Some(metadata) = fetch_json::<ProtectedResourceMetadata>(client, &url).await {
+            if let Some(server) = metadata.authorization_servers.into_iter().next() {
+                issuer = server;
+            }
+            break;
+        }
+    }
+    for suffix in ["oauth-authorization-server", "openid-configuration"] {
+        for url in well_known_urls(&issuer, suffix)? {
+            if let Some(metadata) = fetch_json(client, &url).await {
+                return Ok(metadata);
+            }
+        }
+    }
+    let base = origin(&issuer)?;
+    Ok(AuthServerMetadata {
+        authorization_endpoint: format!("{base}/authorize"),
+        token_endpoint: format!("{base}/token"),
+        registration_endpoint: Some(format!("{base}/register")),
+        device_authorization_endpoint: None,
+    })
+}
+
+fn origin(url: &str) -> Result<String> {
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid URL \"{url}\""))?;
+    Ok(parsed.origin().ascii_serialization())
+}
+
+/// RFC 8414 metadata locations for `base`: the well-known segment goes
+/// between the origin and the path, with the bare origin as fallback.
+fn well_known_urls(base: &str, suffix: &str) -> Result<Vec<String>> {
+    let origin = origin(base)?;
+    let parsed = reqwest::Url::parse(base)?;
+    let path = parsed.path().trim_end_matches('/');
+    let mut urls = Vec::new();
+    if !path.is_empty() {
+        urls.push(format!("{origin}/.well-known/{suffix}{path}"));
+    }
+    urls.push(format!("{origin}/.well-known/{suffix}"));
+    Ok(urls)
+}
+
+async fn fetch_json<T: DeserializeOwned>(client: &reqwest::Client, url: &str) -> Option<T> {
+    let resp = client.get(url).send().await.ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    resp.json().await.ok()
+}
+
+#[derive(Debug, Clone)]
+struct ClientCredentials {
+    id: String,
+    secret: Option<String>,
+}
+
+/// The configured client, or a newly registered one (RFC 7591).
+async fn client_credentials(
+    client: &reqwest::Client,
+    oauth: &McpOAuthConfig,
+    metadata: &AuthServerMetadata,
+    redirect_uri: Option<&str>,
+) -> Result<ClientCredentials> {
+    if let Some(id) = &oauth.client_id {
+        return Ok(ClientCredentials {
+            id: id.clone(),
+            secret: oauth.client_secret.clone(),
+        });
+    }
+    let Some(endpoint) = metadata.registration_endpoint.as_deref() else {
+        bail!("The authorization server does not support dynamic client registration; set oauth.client_id");
+    };
+    let mut body = json!({
+        "client_name": CLIENT_NAME,
+        "token_endpoint_auth_method": "none",
+    });
+    match redirect_uri {
+        Some(uri) => {
+            body["redirect_uris"] = json!([uri]);
+            body["grant_types"] = json!(["authorization_code", "refresh_token"]);
+            body["response_types"] = json!(["code"]);
+        }
+        None => body["grant_types"] = json!([DEVICE_CODE_GRANT, "refresh_token"]),
+    }
+    if !oauth.scopes.is_empty() {
+        body["scope"] = json!(oauth.scopes.join(" "));
+    }
+
+    #[derive(Deserialize)]
+    struct Registered {
+        client_id: String,
+        #[serde(default)]
+        client_secret: Option<String>,
+    }
+    let resp = client
+        .post(endpoint)
+        .json(&body)
+        .send()
+        .await
+        .context("Client registration failed")?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        bail!("Client registration failed: HTTP {status}: {body}");
+    }
+    let registered: Registered = resp
+        .json()
+        .await
+        .context("Invalid client registration response")?;
+    Ok(ClientCredentials {
+        id: registered.client_id,
+        secret: registered.client_secret,
+    })
+}
+
+fn authorization_url(
+    endpoint: &str,
+    client_id: &str,
+    redirect_uri: &str,
+    challenge: &str,
+    state: &str,
+    scopes: &[String],
+    resource: &str,
+) -> Result<String> {
+    let mut url = reqwest::Url::parse(endpoint)
+        .with_context(|| format!("Invalid authorization endpoint \"{endpoint}\""))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("code_challenge", challenge)
+            .append_pair("code_challenge_method", "S256")
+            .append_pair("state", state)
+            .append_pair("resource", resource);
+        if !scopes.is_empty() {
+            query.append_pair("scope", &scopes.join(" "));
+        }
+    }
+    Ok(url.into())
+}
+
+/// 256 random bits, base64url — used for the PKCE verifier and `state`.
+fn random_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+/// PKCE `S256` code challenge for `verifier`.
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Client for the authorization requests of the server with `config`,
+/// trusting the same certificates as its transport.
+fn http_client(config: &McpServerConfig) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(config.timeout_secs));
+    if let Some(tls) = &config.tls {
+        builder = tls.apply(builder)?;
+    }
+    Ok(builder.build()?)
+}
+
+// ── Token endpoint ──────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+/// Error body from the token endpoint (RFC 6749 §5.2).
+#[derive(Debug, Deserialize)]
+struct OAuthError {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "token request failed: {}", self.error)?;
+        if let Some(description) = &self.error_description {
+            write!(f, " ({description})")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for OAuthError {}
+
+/// POST a grant to the token endpoint. The outer error is a transport
+/// failure; the inner one an OAuth error the caller may act on.
+async fn post_token(
+    client: &reqwest::Client,
+    endpoint: &str,
+    credentials: &ClientCredentials,
+    params: &[(&str, &str)],
+) -> Result<Result<TokenResponse, OAuthError>> {
+    let mut form: Vec<(&str, &str)> = params.to_vec();
+    form.push(("client_id", &credentials.id));
+    if let Some(secret) = &credentials.secret {
+        form.push(("client_secret", secret));
+    }
+    let resp = client
+        .post(endpoint)
+        .form(&form)
+        .send()
+        .await
+        .context("Token request failed")?;
+    let status = resp.status();
+    let body = resp.text().await.context("Token request failed")?;
+    if status.is_success() {
+        return Ok(Ok(
+            serde_json::from_str(&body).context("Invalid token response")?
+        ));
+    }
+    match serde_json::from_str::<OAuthError>(&body) {
+        Ok(error) => Ok(Err(error)),
+        Err(_) => bail!("Token request failed: HTTP {status}: {body}"),
+    }
+}
+
+// ── Token storage ───────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredToken {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+    token_endpoint: String,
+    client_id: String,
+    #[serde(default)]
+    client_secret: Option<String>,
+    /// Server URL the token was issued for; a different URL needs a new
+    /// sign-in.
+    resource: String,
+}
+
+impl StoredToken {
+    /// Token from a grant. Servers may omit a new refresh token on refresh,
+    /// in which case `previous_refresh` stays valid.
+    fn issued(
+        response: TokenResponse,
+        previous_refresh: Option<String>,
+        token_endpoint: &str,
+        credentials: ClientCredentials,
+        resource: &str,
+    ) -> Self {
+        Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token.or(previous_refresh),
+            expires_at: response
+                .expires_in
+                .map(|secs| Utc::now() + chrono::Duration::seconds(secs)),
+            token_endpoint: token_endpoint.to_string(),
+            client_id: credentials.id,
+            client_secret: credentials.secret,
+            resource: resource.to_string(),
+        }
+    }
+
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .is_none_or(|at| at - now > chrono::Duration::seconds(EXPIRY_MARGIN_SECS))
+    }
+}
+
+/// `mcp_tokens.json`: tokens per server name, secrets encrypted.
+#[derive(Debug, Clone)]
+struct TokenStore {
+    path: PathBuf,
+    secrets: SecretStore,
+}
+
+impl TokenStore {
+    fn new(dir: &Path, encrypt: bool) -> Self {
+        Self {
+            path: dir.join(TOKENS_FILE),
+            secrets: SecretStore::new(dir, encrypt),
+        }
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, StoredToken>> {
+        match fs::read_to_string(&self.path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .with_context(|| format!("Invalid {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    fn write(&self, tokens: &BTreeMap<String, StoredToken>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(tokens)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&self.path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    fn get(&self, server: &str) -> Result<Option<StoredToken>> {
+        let Some(mut token) = self.read()?.remove(server) else {
+            return Ok(None);
+        };
+        token.access_token = self.secrets.decrypt(&token.access_token)?;
+        token.refresh_token = token
+            .refresh_token
+            .map(|t| self.secrets.decrypt(&t))
+            .transpose()?;
+        token.client_secret = token
+            .client_secret
+            .map(|s| self.secrets.decrypt(&s))
+            .transpose()?;
+        Ok(Some(token))
+    }
+
+    fn put(&self, server: &str, token: &StoredToken) -> Result<()> {
+        let mut sealed = token.clone();
+        sealed.access_token = self.secrets.encrypt(&token.access_token)?;
+        sealed.refresh_token = token
+            .refresh_token
+            .as_deref()
+            .map(|t| self.secrets.encrypt(t))
+            .transpose()?;
+        sealed.client_secret = token
+            .client_secret
+            .as_deref()
+            .map(|s| self.secrets.encrypt(s))
+            .transpose()?;
+        let _guard = STORE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut tokens = self.read()?;
+        tokens.insert(server.to_string(), sealed);
+        self.write(&tokens)
+    }
+
+    fn remove(&self, server: &str) -> Result<bool> {
+        let _guard = STORE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let mut tokens = self.read()?;
+        let removed = tokens.remove(server).is_some();
+        if removed {
+            self.write(&tokens)?;
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::Value;
+
+    #[test]
+    fn pkce_challenge_matches_rfc_7636_example() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(random_token().len(), 43);
+    }
+
+    #[test]
+    fn well_known_urls_insert_segment_before_path() {
+        assert_eq!(
+            well_known_urls(
+                "https://mcp.example.com/v1/mcp/",
+                "oauth-protected-resource"
+            )
+            .unwrap(),
+            [
+                "https://mcp.example.com/.well-known/oauth-protected-resource/v1/mcp",
+                "https://mcp.example.com/.well-known/oauth-protected-resource",
+            ]
+        );
+        assert_eq!(
+            well_known_urls("https://auth.example.com", "oauth-authorization-server").unwrap(),
+            ["https://auth.example.com/.well-known/oauth-authorization-server"]
+        );
+    }
+
+    #[test]
+    fn authorization_url_carries_pkce_and_resource() {
+        let url = authorization_url(
+            "https://auth.example.com/authorize?tenant=a",
+            "client-1",
+            "http://127.0.0.1:9000/callback",
+            "challenge",
+            "state-1",
+            &["read".into(), "write".into()],
+            "https://mcp.example.com/mcp",
+        )
+        .unwrap();
+        let parsed = reqwest::Url::parse(&url).unwrap();
+        let params: BTreeMap<String, String> = parsed.query_pairs().into_owned().collect();
+        assert_eq!(params["tenant"], "a");
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["redirect_uri"], "http://127.0.0.1:9000/callback");
+        assert_eq!(params["resource"], "https://mcp.example.com/mcp");
+        assert_eq!(params["scope"], "read write");
+    }
+
+    #[test]
+    fn parse_callback_checks_state_and_errors() {
+        assert!(parse_callback("/favicon.ico", "s").is_none());
+        assert_eq!(
+            parse_callback("/callback?code=abc&state=s", "s")
+                .unwrap()
+                .unwrap(),
+            "abc"
+        );
+        let mismatch = parse_callback("/callback?code=abc&state=other", "s").unwrap();
+        assert!(mismatch.unwrap_err().to_string().contains("state"));
+        let denied = parse_callback("/callback?error=access_denied&state=s", "s").unwrap();
+        assert!(format!("{:#}", denied.unwrap_err()).contains("access_denied"));
+    }
+
+    fn token(expires_at: Option<DateTime<Utc>>) -> StoredToken {
+        StoredToken {
+            access_token: "access-secret".into(),
+            refresh_token: Some("refresh-secret".into()),
+            expires_at,
+            token_endpoint: "https://auth.example.com/token".into(),
+            client_id: "client-1".into(),
+            client_secret: None,
+            resource: "https://mcp.example.com/mcp".into(),
+        }
+    }
+
+    #[test]
+    fn tokens_near_expiry_are_not_fresh() {
+        let now = Utc::now();
+        assert!(token(None).is_fresh(now));
+        assert!(token(Some(now + chrono::Duration::hours(1))).is_fresh(now));
+        assert!(!token(Some(now + chrono::Duration::seconds(30))).is_fresh(now));
+    }
+
+    #[test]
+    fn token_store_encrypts_and_round_trips() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = TokenStore::new(tmp.path(), true);
+        store.put("tracker", &token(None)).unwrap();
+
+        let raw = fs::read_to_string(tmp.path().join(TOKENS_FILE)).unwrap();
+        assert!(!raw.contains("access-secret"));
+        assert!(!raw.contains("refresh-secret"));
+
+        let loaded = store.get("tracker").unwrap().unwrap();
+        assert_eq!(loaded.access_token, "access-secret");
+        assert_eq!(loaded.refresh_token.as_deref(), Some("refresh-secret"));
+        assert!(store.get("other").unwrap().is_none());
+
+        assert!(store.remove("tracker").unwrap());
+        assert!(!store.remove("tracker").unwrap());
+        assert!(store.get("tracker").unwrap().is_none());
+    }
+
+    /// Authorization server that registers clients and issues tokens for
+    /// any authorization code or refresh token.
+    async fn serve_auth() -> String {
+        async fn token(body: String) -> Json<Value> {
+            let url = reqwest::Url::parse(&format!("http://x/?{body}")).unwrap();
+            let params: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+            assert_eq!(params["client_id"], "registered-1");
+            assert!(params["resource"].ends_with("/mcp"));
+            match params["grant_type"].as_str() {
+                "authorization_code" => {
+                    assert_eq!(params["code"], "code-1");
+                    assert_eq!(params["code_verifier"].len(), 43);
+                    Json(
+                        json!({ "access_token": "at-1", "refresh_token": "rt-1", "expires_in": 3600 }),
+                    )
+                }
+                _ => {
+                    assert_eq!(params["refresh_token"], "rt-1");
+                    Json(json!({ "access_token": "at-2", "expires_in": 3600 }))
+                }
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route(
+                "/.well-known/oauth-protected-resource/mcp",
+                get(|State(base): State<String>| async move {
+                    Json(json!({ "authorization_servers": [format!("{base}/issuer")] }))
+                }),
+            )
+            .route(
+                "/.well-known/oauth-authorization-server/issuer",
+                get(|State(base): State<String>| async move {
+                    Json(json!({
+                        "authorization_endpoint": format!("{base}/authorize"),
+                        "token_endpoint": format!("{base}/token"),
+                        "registration_endpoint": format!("{base}/register"),
+                    }))
+                }),
+            )
+            .route(
+                "/register",
+                post(|Json(body): Json<Value>| async move {
+                    assert_eq!(body["token_endpoint_auth_method"], "none");
+                    Json(json!({ "client_id": "registered-1" }))
+                }),
+            )
+            .route("/token", post(token))
+            .with_state(base.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn code_flow_registers_client_and_exchanges_code() {
+        let base = serve_auth().await;
+        let resource = format!("{base}/mcp");
+        let client = http_client(&McpServerConfig::default()).unwrap();
+        let metadata = discover(&client, &resource).await.unwrap();
+        assert_eq!(metadata.token_endpoint, format!("{base}/token"));
+
+        // The notifier plays the user: it follows the sign-in link's
+        // redirect back to the loopback listener.
+        let notify: crate::mcp::Notifier = Arc::new(|prompt: String| {
+            let link = prompt.lines().last().unwrap().to_string();
+            tokio::spawn(async move {
+                let url = reqwest::Url::parse(&link).unwrap();
+                let params: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+                let redirect = format!(
+                    "{}?code=code-1&state={}",
+                    params["redirect_uri"], params["state"]
+                );
+                reqwest::get(redirect).await.unwrap();
+            });
+        });
+        let oauth = McpOAuthConfig::default();
+        let token = crate::mcp::scope(
+            crate::mcp::Turn {
+                notify: Some(notify),
+                ..crate::mcp::Turn::default()
+            },
+            code_flow(&client, "tracker", &oauth, &metadata, &resource),
+        )
+        .await
+        .unwrap();
+        assert_eq!(token.access_token, "at-1");
+        assert_eq!(token.client_id, "registered-1");
+        assert_eq!(token.resource, resource);
+
+        let refreshed = refresh(&client, &token).await.unwrap();
+        assert_eq!(refreshed.access_token, "at-2");
+        assert_eq!(refreshed.refresh_token.as_deref(), Some("rt-1"));
+    }
+}
@@ -1,12 +1,32 @@
-use super::client::McpClient;
-use crate::tools::traits::{Tool, ToolResult};
+use super::arguments;
+use super::client::{McpClient, ProtocolFeature, ToolCallOptions};
+use super::completion;
+use super::names::qualified_name;
+use super::output;
+use super::protocol::{
+    McpContent, McpPromptDef, McpResourceTemplate, McpToolAnnotations, PromptGetResult,
+    ResourceReadResult,
+};
+use super::sanitize;
+use super::uri_template;
+use crate::tools::traits::{Tool, ToolAttachment, ToolEffect, ToolResult};
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Most results a bridged tool keeps cached; the oldest is evicted first.
+const MAX_CACHED_RESULTS: usize = 64;
+
+/// Most resources `list_resources` reads along with the list.
+const MAX_BATCH_READS: usize = 10;
 
 /// A bridged MCP tool exposed as a ZeroClaw `Tool` implementation.
 ///
-/// Each MCP server tool becomes one of these, named `mcp__<server>__<tool>`.
+/// Each MCP server tool becomes one of these, named `mcp__<server>__<tool>`
+/// (see [`super::names`]).
 pub struct McpBridgedTool {
     /// Qualified name: mcp__<server>__<tool>
     qualified_name: String,
@@ -18,8 +38,24 @@ pub struct McpBridgedTool {
     client: Arc<McpClient>,
     /// Original tool name on the MCP server
     mcp_tool_name: String,
+    /// Behaviour hints from the MCP server
+    annotations: Option<McpToolAnnotations>,
+    /// Arguments hash → when the result was cached and the result, used
+    /// while the server's `cache_ttl_secs` is set.
+    cache: Mutex<HashMap<u64, (Instant, ToolResult)>>,
 }
 
+/// Names of the tools added per server for its resources and prompts,
+/// besides the server's own tools.
+pub const SYNTHETIC_TOOLS: [&str; 6] = [
+    "list_resources",
+    "read_resource",
+    "subscribe_resource",
+    "list_prompts",
+    "get_prompt",
+    "read_output",
+];
+
 impl McpBridgedTool {
     pub fn new(
         server_name: &str,
@@ -28,9 +64,15 @@ impl McpBridgedTool {
         input_schema: Option<Value>,
         client: Arc<McpClient>,
     ) -> Self {
-        let qualified_name = format!("mcp__{server_name}__{mcp_tool_name}");
-        let description = description
-            .unwrap_or_else(|| format!("MCP tool '{mcp_tool_name}' from server '{server_name}'"));
+        let qualified_name = qualified_name(server_name, &mcp_tool_name);
+        let description = match description {
+            Some(text) => sanitize::sanitize(
+                text,
+                client.sanitize_mode(),
+                &format!("description of {qualified_name}"),
+            ),
+            None => format!("MCP tool '{mcp_tool_name}' from server '{server_name}'"),
+        };
         let input_schema =
             input_schema.unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
 
@@ -40,58 +82,254 @@ impl McpBridgedTool {
             input_schema,
             client,
             mcp_tool_name,
+            annotations: None,
+            cache: Mutex::default(),
         }
     }
-}
 
-#[async_trait]
-impl Tool for McpBridgedTool {
-    fn name(&self) -> &str {
-        &self.qualified_name
+    /// Keep the server's annotations, when the negotiated protocol has them;
+    /// they decide whether calls need approval if the server's
+    /// `trust_annotations` is set.
+    pub fn with_annotations(mut self, annotations: Option<McpToolAnnotations>) -> Self {
+        self.annotations =
+            annotations.filter(|_| self.client.supports(ProtocolFeature::ToolAnnotations));
+        self
     }
 
-    fn description(&self) -> &str {
-        &self.description
+    /// Annotations the server gave the tool, as received.
+    pub fn annotations(&self) -> Option<&McpToolAnnotations> {
+        self.annotations.as_ref()
     }
 
-    fn parameters_schema(&self) -> Value {
-        self.input_schema.clone()
+    /// Tell the model the JSON Schema of the tool's structured results, when
+    /// the server declares one and the negotiated protocol supports them.
+    pub fn with_output_schema(mut self, output_schema: Option<&Value>) -> Self {
+        if let Some(schema) =
+            output_schema.filter(|_| self.client.supports(ProtocolFeature::StructuredOutput))
+        {
+            self.description = format!(
+                "{}\n\nReturns JSON matching this schema: {schema}",
+                self.description
+            );
+        }
+        self
     }
 
-    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
-        match self.client.call_tool(&self.mcp_tool_name, args).await {
-            Ok(result) => {
-                // Concatenate all text content items
-                let output: String = result
-                    .content
-                    .iter()
-                    .filter_map(|c| c.text.as_deref())
-                    .collect::<Vec<_>>()
-                    .join("\n");
+    /// A result for `key` cached less than `ttl` ago.
+    fn cached(&self, key: u64, ttl: Duration) -> Option<ToolResult> {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.retain(|_, (at, _)| at.elapsed() < ttl);
+        cache.get(&key).map(|(_, result)| result.clone())
+    }
+
+    fn remember(&self, key: u64, result: &ToolResult) {
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() >= MAX_CACHED_RESULTS {
+            if let Some(oldest) = cache.iter().min_by_key(|(_, (at, _))| *at).map(|(k, _)| *k) {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(key, (Instant::now(), result.clone()));
+    }
+
+    /// `text` scanned for prompt injection and shortened to the server's
+    /// `max_output_bytes`, if it has one.
+    fn clean_output(&self, text: String) -> String {
+        let text = sanitize::sanitize(
+            text,
+            self.client.sanitize_mode(),
+            &format!("output of {}", self.qualified_name),
+        );
+        match self.client.output_limit() {
+            Some((max, strategy)) => {
+                let read_tool = qualified_name(&self.client.server_name, "read_output");
+                output::limit(text, max, strategy, &read_tool)
+            }
+            None => text,
+        }
+    }
 
+    async fn call(&self, args: Value) -> ToolResult {
+        let on_progress = super::progress_reporter().map(|report| {
+            let tool = self.qualified_name.clone();
+            Arc::new(move |progress: &super::Progress| report(&tool, progress))
+                as super::client::ProgressCallback
+        });
+        let options = ToolCallOptions {
+            on_progress,
+            cancel: super::cancel_token(),
+            idempotent: self
+                .annotations
+                .as_ref()
+                .is_some_and(|a| a.idempotent_hint == Some(true)),
+        };
+        match self
+            .client
+            .call_tool_with(&self.mcp_tool_name, args, options)
+            .await
+        {
+            Ok(result) => {
+                let (text, attachments) = split_content(&result.content);
                 if result.is_error {
-                    Ok(ToolResult {
+                    ToolResult {
                         success: false,
                         output: String::new(),
-                        error: Some(output),
-                    })
+                        error: Some(self.clean_output(text)),
+                        attachments,
+                    }
                 } else {
-                    Ok(ToolResult {
+                    // Structured results reach the model as JSON; the text
+                    // content is only their rendering for older clients.
+                    let output = match result.structured_content {
+                        Some(json) if self.client.supports(ProtocolFeature::StructuredOutput) => {
+                            json.to_string()
+                        }
+                        _ => text,
+                    };
+                    ToolResult {
                         success: true,
-                        output,
+                        output: self.clean_output(output),
                         error: None,
-                    })
+                        attachments,
+                    }
                 }
             }
-            Err(e) => Ok(ToolResult {
+            Err(e) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("MCP call failed: {e}")),
-            }),
+                attachments: Vec::new(),
+            },
         }
     }
 }
 
+/// Cache key of a call's arguments; object keys are sorted, so key order
+/// does not matter.
+fn args_hash(args: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    args.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[async_trait]
+impl Tool for McpBridgedTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        // The server may have changed the schema when it was restarted.
+        self.client
+            .tool_schema(&self.mcp_tool_name)
+            .unwrap_or_else(|| self.input_schema.clone())
+    }
+
+    fn effect(&self) -> ToolEffect {
+        if !self.client.trusts_annotations() {
+            return ToolEffect::Unknown;
+        }
+        match &self.annotations {
+            Some(a) if a.is_read_only() => ToolEffect::ReadOnly,
+            Some(a) if a.is_destructive() => ToolEffect::Destructive,
+            _ => ToolEffect::Unknown,
+        }
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        if !self.client.is_available() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "MCP server '{}' is unavailable and is being reconnected; try again later",
+                    self.client.server_name
+                )),
+                attachments: Vec::new(),
+            });
+        }
+        let problems = if args.is_null() {
+            arguments::problems(&self.parameters_schema(), &json!({}))
+        } else {
+            arguments::problems(&self.parameters_schema(), &args)
+        };
+        if !problems.is_empty() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Invalid arguments, not sent to the server:\n- {}",
+                    problems.join("\n- ")
+                )),
+                attachments: Vec::new(),
+            });
+        }
+        let Some(ttl) = self.client.cache_ttl() else {
+            return Ok(self.call(args).await);
+        };
+        let key = args_hash(&args);
+        if let Some(hit) = self.cached(key, ttl) {
+            tracing::debug!(tool = %self.qualified_name, "MCP tool result served from cache");
+            return Ok(hit);
+        }
+        let result = self.call(args).await;
+        if result.success {
+            self.remember(key, &result);
+        }
+        Ok(result)
+    }
+}
+
+/// Join the text of tool result content; images, audio and embedded binary
+/// resources become attachments, embedded text resources join the text.
+fn split_content(content: &[McpContent]) -> (String, Vec<ToolAttachment>) {
+    let mut texts = Vec::new();
+    let mut attachments = Vec::new();
+    for item in content {
+        match item.content_type.as_str() {
+            "image" | "audio" => {
+                if let Some(data) = &item.data {
+                    attachments.push(ToolAttachment {
+                        mime_type: item
+                            .mime_type
+                            .clone()
+                            .unwrap_or_else(|| "application/octet-stream".into()),
+                        data: data.clone(),
+                        uri: None,
+                    });
+                }
+            }
+            "resource" => {
+                let Some(resource) = &item.resource else {
+                    continue;
+                };
+                if let Some(text) = resource.get("text").and_then(Value::as_str) {
+                    texts.push(text.to_string());
+                } else if let Some(blob) = resource.get("blob").and_then(Value::as_str) {
+                    attachments.push(ToolAttachment {
+                        mime_type: resource
+                            .get("mimeType")
+                            .and_then(Value::as_str)
+                            .unwrap_or("application/octet-stream")
+                            .to_string(),
+                        data: blob.to_string(),
+                        uri: resource
+                            .get("uri")
+                            .and_then(Value::as_str)
+                            .map(ToOwned::to_owned),
+                    });
+                }
+            }
+            _ => texts.extend(item.text.clone()),
+        }
+    }
+    (texts.join("\n"), attachments)
+}
+
 /// Synthetic tool: list resources available on an MCP server.
 pub struct McpListResourcesTool {
     qualified_name: String,
@@ -102,8 +340,11 @@ pub struct McpListResourcesTool {
 impl McpListResourcesTool {
     pub fn new(server_name: &str, client: Arc<McpClient>) -> Self {
         Self {
-            qualified_name: format!("mcp__{server_name}__list_resources"),
-            description: format!("List available resources on MCP server '{server_name}'"),
+            qualified_name: qualified_name(server_name, "list_resources"),
+            description: format!(
+                "List available resources on MCP server '{server_name}'; set read to also read \
+                 the first {MAX_BATCH_READS}"
+            ),
             client,
         }
     }
@@ -120,23 +361,45 @@ impl Tool for McpListResourcesTool {
     }
 
     fn parameters_schema(&self) -> Value {
-        json!({ "type": "object", "properties": {} })
+        json!({
+            "type": "object",
+            "properties": {
+                "read": {
+                    "type": "boolean",
+                    "description": format!("Also read the contents of the first {MAX_BATCH_READS} resources")
+                }
+            }
+        })
     }
 
-    async fn execute(&self, _args: Value) -> anyhow::Result<ToolResult> {
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let read = args.get("read").and_then(Value::as_bool).unwrap_or(false);
         match self.client.list_resources().await {
             Ok(result) => {
-                let output = serde_json::to_string_pretty(&result.resources)?;
+                let mut output = serde_json::to_string_pretty(&result.resources)?;
+                if read {
+                    let uris: Vec<String> = result
+                        .resources
+                        .iter()
+                        .take(MAX_BATCH_READS)
+                        .map(|r| r.uri.clone())
+                        .collect();
+                    let contents = self.client.read_resources(&uris).await;
+                    output.push_str("\n\n");
+                    output.push_str(&resources_text(&uris, contents));
+                }
                 Ok(ToolResult {
                     success: true,
                     output,
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to list resources: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -152,8 +415,10 @@ pub struct McpReadResourceTool {
 impl McpReadResourceTool {
     pub fn new(server_name: &str, client: Arc<McpClient>) -> Self {
         Self {
-            qualified_name: format!("mcp__{server_name}__read_resource"),
-            description: format!("Read a resource by URI from MCP server '{server_name}'"),
+            qualified_name: qualified_name(server_name, "read_resource"),
+            description: format!(
+                "Read a resource by URI, or several at once with uris, from MCP server '{server_name}'"
+            ),
             client,
         }
     }
@@ -176,6 +441,262 @@ impl Tool for McpReadResourceTool {
                 "uri": {
                     "type": "string",
                     "description": "The URI of the resource to read"
+                },
+                "uris": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "URIs of several resources to read in one request, instead of uri"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let mut uris: Vec<String> = args
+            .get("uris")
+            .and_then(Value::as_array)
+            .map(|uris| {
+                uris.iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(uri) = args.get("uri").and_then(Value::as_str) {
+            uris.insert(0, uri.to_string());
+        }
+        uris.retain(|uri| !uri.is_empty());
+
+        if uris.len() > 1 {
+            let results = self.client.read_resources(&uris).await;
+            let success = results.iter().any(Result::is_ok);
+            let output = resources_text(&uris, results);
+            return Ok(ToolResult {
+                success,
+                error: (!success).then(|| "Failed to read resources".to_string()),
+                output,
+                attachments: Vec::new(),
+            });
+        }
+        let Some(uri) = uris.first() else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Missing required parameter: uri".into()),
+                attachments: Vec::new(),
+            });
+        };
+
+        match self.client.read_resource(uri).await {
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: resource_text(&result),
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to read resource: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// The text contents of a read resource, joined.
+fn resource_text(result: &ResourceReadResult) -> String {
+    result
+        .contents
+        .iter()
+        .filter_map(|c| c.text.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Texts of several read resources, each under its URI; failed reads say
+/// why.
+fn resources_text(uris: &[String], results: Vec<anyhow::Result<ResourceReadResult>>) -> String {
+    uris.iter()
+        .zip(results)
+        .map(|(uri, result)| match result {
+            Ok(result) => format!("── {uri} ──\n{}", resource_text(&result)),
+            Err(e) => format!("── {uri} ──\nFailed to read resource: {e}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Registry name of the tool reading resource template `template`:
+/// `mcp__<server>__resource_<name>` by default, with characters other than ASCII
+/// letters, digits, `-` and `_` in the name replaced by `_`.
+pub fn resource_template_tool_name(server: &str, template: &McpResourceTemplate) -> String {
+    let name: String = template
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    qualified_name(server, &format!("resource_{name}"))
+}
+
+/// A resource template exposed as a tool whose parameters are the
+/// template's variables; calls read the expanded URI.
+pub struct McpResourceTemplateTool {
+    qualified_name: String,
+    description: String,
+    uri_template: String,
+    client: Arc<McpClient>,
+}
+
+impl McpResourceTemplateTool {
+    pub fn new(server_name: &str, template: &McpResourceTemplate, client: Arc<McpClient>) -> Self {
+        let description = match template.description.as_deref() {
+            Some(description) => format!("{description} (reads {})", template.uri_template),
+            None => format!(
+                "Read resource '{}' ({}) from MCP server '{server_name}'",
+                template.name, template.uri_template
+            ),
+        };
+        Self {
+            qualified_name: resource_template_tool_name(server_name, template),
+            description,
+            uri_template: template.uri_template.clone(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpResourceTemplateTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let variables = uri_template::variables(&self.uri_template);
+        let properties: serde_json::Map<String, Value> = variables
+            .iter()
+            .map(|v| {
+                let description = format!("Value of {{{}}} in {}", v.name, self.uri_template);
+                (
+                    v.name.clone(),
+                    json!({ "type": "string", "description": description }),
+                )
+            })
+            .collect();
+        let required: Vec<&str> = variables
+            .iter()
+            .filter(|v| v.required)
+            .map(|v| v.name.as_str())
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let mut args = match args {
+            Value::Object(args) => args,
+            _ => serde_json::Map::new(),
+        };
+        let missing = completion::missing_template_variables(&self.uri_template, &args);
+        if let Err(e) = completion::ask_missing(
+            &self.client,
+            &completion::resource_ref(&self.uri_template),
+            &missing,
+            &mut args,
+        )
+        .await
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+                attachments: Vec::new(),
+            });
+        }
+        let missing = completion::missing_template_variables(&self.uri_template, &args);
+        if !missing.is_empty() {
+            let names: Vec<&str> = missing.iter().map(|m| m.name.as_str()).collect();
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Missing required parameter: {}", names.join(", "))),
+                attachments: Vec::new(),
+            });
+        }
+
+        let uri = uri_template::expand(&self.uri_template, &args);
+        match self.client.read_resource(&uri).await {
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: resource_text(&result),
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to read resource {uri}: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Synthetic tool: watch a resource on an MCP server for changes.
+pub struct McpSubscribeResourceTool {
+    qualified_name: String,
+    description: String,
+    client: Arc<McpClient>,
+}
+
+impl McpSubscribeResourceTool {
+    pub fn new(server_name: &str, client: Arc<McpClient>) -> Self {
+        Self {
+            qualified_name: qualified_name(server_name, "subscribe_resource"),
+            description: format!(
+                "Watch a resource by URI on MCP server '{server_name}'; you are told when it changes. \
+                 Set unsubscribe to stop watching"
+            ),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpSubscribeResourceTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "uri": {
+                    "type": "string",
+                    "description": "The URI of the resource to watch"
+                },
+                "unsubscribe": {
+                    "type": "boolean",
+                    "description": "Stop watching the resource instead"
                 }
             },
             "required": ["uri"]
@@ -184,35 +705,374 @@ impl Tool for McpReadResourceTool {
 
     async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
         let uri = args.get("uri").and_then(Value::as_str).unwrap_or_default();
+        let unsubscribe = args
+            .get("unsubscribe")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
 
         if uri.is_empty() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("Missing required parameter: uri".into()),
+                attachments: Vec::new(),
             });
         }
 
-        match self.client.read_resource(uri).await {
-            Ok(result) => {
-                let output: String = result
-                    .contents
-                    .iter()
-                    .filter_map(|c| c.text.as_deref())
-                    .collect::<Vec<_>>()
-                    .join("\n");
+        let result = if unsubscribe {
+            self.client.unsubscribe_resource(uri).await.map(|removed| {
+                if removed {
+                    format!("Stopped watching {uri}")
+                } else {
+                    format!("{uri} was not being watched")
+                }
+            })
+        } else {
+            self.client
+                .subscribe_resource(uri)
+                .await
+                .map(|()| format!("Watching {uri}"))
+        };
+        match result {
+            Ok(output) => Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to update subscription: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// One-line summary of a prompt template: `name(arg*, arg)` — description.
+/// Required arguments are starred.
+pub fn prompt_signature(prompt: &McpPromptDef) -> String {
+    let args: Vec<String> = prompt
+        .arguments
+        .iter()
+        .map(|arg| {
+            if arg.required {
+                format!("{}*", arg.name)
+            } else {
+                arg.name.clone()
+            }
+        })
+        .collect();
+    match prompt.description.as_deref() {
+        Some(description) => format!("{}({}) — {description}", prompt.name, args.join(", ")),
+        None => format!("{}({})", prompt.name, args.join(", ")),
+    }
+}
+
+/// An expanded prompt as text, one `[role]` block per message.
+pub fn format_prompt(result: &PromptGetResult) -> String {
+    let mut blocks: Vec<String> = Vec::new();
+    if let Some(description) = result.description.as_deref() {
+        blocks.push(description.to_string());
+    }
+    for message in &result.messages {
+        if let Some(text) = message.content.as_text() {
+            blocks.push(format!("[{}]\n{text}", message.role));
+        }
+    }
+    blocks.join("\n\n")
+}
 
+/// Synthetic tool: list prompt templates available on an MCP server.
+pub struct McpListPromptsTool {
+    qualified_name: String,
+    description: String,
+    client: Arc<McpClient>,
+}
+
+impl McpListPromptsTool {
+    pub fn new(server_name: &str, client: Arc<McpClient>) -> Self {
+        Self {
+            qualified_name: qualified_name(server_name, "list_prompts"),
+            description: format!("List prompt templates available on MCP server '{server_name}'"),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpListPromptsTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({ "type": "object", "properties": {} })
+    }
+
+    async fn execute(&self, _args: Value) -> anyhow::Result<ToolResult> {
+        match self.client.list_prompts().await {
+            Ok(result) => {
+                let output = serde_json::to_string_pretty(&result.prompts)?;
                 Ok(ToolResult {
                     success: true,
                     output,
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!("Failed to read resource: {e}")),
+                error: Some(format!("Failed to list prompts: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// Synthetic tool: expand a prompt template from an MCP server. The
+/// description lists the templates discovered at connect time.
+pub struct McpGetPromptTool {
+    qualified_name: String,
+    description: String,
+    prompt_names: Vec<String>,
+    client: Arc<McpClient>,
+}
+
+impl McpGetPromptTool {
+    pub fn new(server_name: &str, client: Arc<McpClient>) -> Self {
+        let mut description = format!(
+            "Expand a prompt template from MCP server '{server_name}' and follow the returned instructions"
+        );
+        if !client.prompts().is_empty() {
+            description.push_str(". Templates (* = required argument): ");
+            description.push_str(
+                &client
+                    .prompts()
+                    .iter()
+                    .map(prompt_signature)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            );
+        }
+        Self {
+            qualified_name: qualified_name(server_name, "get_prompt"),
+            description,
+            prompt_names: client.prompts().iter().map(|p| p.name.clone()).collect(),
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpGetPromptTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        let mut name = json!({
+            "type": "string",
+            "description": "Prompt template name"
+        });
+        if !self.prompt_names.is_empty() {
+            name["enum"] = json!(self.prompt_names);
+        }
+        json!({
+            "type": "object",
+            "properties": {
+                "name": name,
+                "arguments": {
+                    "type": "object",
+                    "description": "Template arguments (string values)",
+                    "additionalProperties": { "type": "string" }
+                }
+            },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let name = args.get("name").and_then(Value::as_str).unwrap_or_default();
+        if name.is_empty() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Missing required parameter: name".into()),
+                attachments: Vec::new(),
+            });
+        }
+        let mut arguments = match args.get("arguments") {
+            Some(Value::Object(arguments)) => arguments.clone(),
+            _ => serde_json::Map::new(),
+        };
+        if let Some(prompt) = self.client.prompts().iter().find(|p| p.name == name) {
+            let missing = completion::missing_prompt_arguments(prompt, &arguments);
+            if let Err(e) = completion::ask_missing(
+                &self.client,
+                &completion::prompt_ref(name),
+                &missing,
+                &mut arguments,
+            )
+            .await
+            {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to get prompt: {e}")),
+                    attachments: Vec::new(),
+                });
+            }
+        }
+
+        match self.client.get_prompt(name, Value::Object(arguments)).await {
+            Ok(result) => Ok(ToolResult {
+                success: true,
+                output: format_prompt(&result),
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to get prompt: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
 }
+
+/// Synthetic tool: read output of an MCP server's tools that was over
+/// `max_output_bytes` and saved to a file (`output_strategy = "spill"`).
+pub struct McpReadOutputTool {
+    qualified_name: String,
+    description: String,
+    /// Largest chunk returned at once: the server's `max_output_bytes`.
+    max_chunk: usize,
+}
+
+impl McpReadOutputTool {
+    pub fn new(server_name: &str, max_chunk: usize) -> Self {
+        Self {
+            qualified_name: qualified_name(server_name, "read_output"),
+            description: format!(
+                "Read saved output of an MCP server '{server_name}' tool in chunks of up to {max_chunk} bytes"
+            ),
+            max_chunk,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpReadOutputTool {
+    fn name(&self) -> &str {
+        &self.qualified_name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path of the saved output, as given in the tool result"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Byte offset to start reading at (default 0)"
+                },
+                "length": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": format!("Bytes to read (default and maximum {})", self.max_chunk)
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+        let path = args.get("path").and_then(Value::as_str).unwrap_or_default();
+        if path.is_empty() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Missing required parameter: path".into()),
+                attachments: Vec::new(),
+            });
+        }
+        let number = |key: &str| {
+            args.get(key)
+                .and_then(Value::as_u64)
+                .and_then(|n| usize::try_from(n).ok())
+        };
+        let offset = number("offset").unwrap_or(0);
+        let length = number("length").map_or(self.max_chunk, |n| n.min(self.max_chunk));
+
+        match output::read_chunk(path, offset, length) {
+            Ok(chunk) => Ok(ToolResult {
+                success: true,
+                output: chunk,
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to read output: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_content_becomes_attachments() {
+        let content: Vec<McpContent> = serde_json::from_value(json!([
+            { "type": "text", "text": "chart ready" },
+            { "type": "image", "data": "iVBORw0=", "mimeType": "image/png" },
+            { "type": "resource", "resource": { "uri": "file:///notes.md", "text": "# Notes" } },
+            { "type": "resource", "resource": {
+                "uri": "file:///report.pdf", "mimeType": "application/pdf", "blob": "JVBERi0=",
+            } },
+        ]))
+        .unwrap();
+
+        let (text, attachments) = split_content(&content);
+        assert_eq!(text, "chart ready\n# Notes");
+        assert_eq!(
+            attachments,
+            [
+                ToolAttachment {
+                    mime_type: "image/png".into(),
+                    data: "iVBORw0=".into(),
+                    uri: None,
+                },
+                ToolAttachment {
+                    mime_type: "application/pdf".into(),
+                    data: "JVBERi0=".into(),
+                    uri: Some("file:///report.pdf".into()),
+                },
+            ]
+        );
+    }
+}
@@ -1,34 +1,380 @@
+use super::config::{
+    McpLogLevel, McpOutputStrategy, McpRetryConfig, McpRetryOn, McpSanitizeMode, McpToolFilter,
+};
 use super::protocol::{
-    InitializeResult, JsonRpcRequest, McpToolDef, ResourceReadResult, ResourcesListResult,
-    ToolCallResult,
+    CompleteResult, Completion, InitializeResult, JsonRpcError, JsonRpcNotification,
+    JsonRpcRequest, JsonRpcResponse, McpPromptDef, McpResourceTemplate, McpRoot, McpToolDef,
+    PromptGetResult, PromptsListResult, ResourceReadResult, ResourceTemplatesListResult,
+    ResourcesListResult, ToolCallResult,
 };
-use super::transport::McpTransport;
+use super::transport::{HttpStatusError, McpTransport};
+use super::{audit, metrics, Notifier, Progress};
 use anyhow::{bail, Context, Result};
-use serde_json::{json, Value};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Duration;
+use futures::future::BoxFuture;
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+/// MCP protocol versions we speak, newest first. The newest is offered in
+/// `initialize`; a server may counter with any older one of them.
+pub(super) const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Oldest protocol version we speak, assumed until the handshake is done.
+const OLDEST_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Protocol features that only servers speaking a recent enough version
+/// understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolFeature {
+    /// `annotations` on tool definitions.
+    ToolAnnotations,
+    /// `outputSchema` on tools and `structuredContent` in their results.
+    StructuredOutput,
+    /// `elicitation/create` requests from the server.
+    Elicitation,
+}
+
+impl ProtocolFeature {
+    /// First protocol version with this feature.
+    pub fn since(self) -> &'static str {
+        match self {
+            Self::ToolAnnotations => "2025-03-26",
+            Self::StructuredOutput | Self::Elicitation => "2025-06-18",
+        }
+    }
+}
+
+/// First protocol version without JSON-RPC batches.
+const BATCHING_REMOVED_IN: &str = "2025-06-18";
+
+/// Notification a server sends after adding, removing or changing tools.
+const TOOLS_LIST_CHANGED: &str = "notifications/tools/list_changed";
+/// Notification a server sends when a subscribed resource changed.
+const RESOURCE_UPDATED: &str = "notifications/resources/updated";
+/// Notification a server sends while working on a request that carried a
+/// progress token.
+const PROGRESS: &str = "notifications/progress";
+/// Notification carrying a server log message.
+const LOG_MESSAGE: &str = "notifications/message";
+
+/// Notification telling the server to stop working on a request.
+const CANCELLED: &str = "notifications/cancelled";
+
+/// Request a server sends to have our LLM generate a message.
+const SAMPLING_CREATE_MESSAGE: &str = "sampling/createMessage";
+/// JSON-RPC error code for a sampling request that was refused or failed.
+const SAMPLING_REJECTED: i64 = -1;
+/// Request a server sends to ask the user for input mid-call.
+const ELICITATION_CREATE: &str = "elicitation/create";
+/// JSON-RPC error code for a request we failed to answer.
+const INTERNAL_ERROR: i64 = -32603;
+/// Request a server sends to learn which directories it may work in.
+const ROOTS_LIST: &str = "roots/list";
+/// Notification telling the server to list the roots again.
+const ROOTS_LIST_CHANGED: &str = "notifications/roots/list_changed";
+
+/// JSON-RPC error code for a method we do not implement.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Answers the params of a server's `sampling/createMessage` request with
+/// the result to send back.
+pub type SamplingHandler = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value>> + Send + Sync>;
+
+/// Answers the params of a server's `elicitation/create` request with the
+/// user's response.
+pub type ElicitationHandler = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value>> + Send + Sync>;
+
+/// Receives progress of one tool call.
+pub type ProgressCallback = Arc<dyn Fn(&Progress) + Send + Sync>;
+
+/// Optional hooks into a single tool call.
+#[derive(Default)]
+pub struct ToolCallOptions {
+    /// Receives the server's progress notifications for the call.
+    pub on_progress: Option<ProgressCallback>,
+    /// Abandons the call when cancelled.
+    pub cancel: Option<CancellationToken>,
+    /// The tool declared `idempotentHint`, so the call may be retried after
+    /// failures the server could have acted on anyway.
+    pub idempotent: bool,
+}
+
+/// Where server log messages go besides `tracing`.
+#[derive(Default)]
+struct LogForwarding {
+    /// Lowest level posted to conversations; set by [`McpClient::forward_logs`].
+    level: OnceLock<McpLogLevel>,
+    /// Request id → notifier of the conversation of tool calls in flight.
+    sinks: Mutex<HashMap<u64, Notifier>>,
+}
+
+/// Window [`CallLimits`] counts calls per.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// `max_concurrent_calls` and `rate_limit_per_minute` of a server.
+#[derive(Default)]
+struct CallLimits {
+    concurrency: Option<Arc<Semaphore>>,
+    /// Calls allowed per [`RATE_WINDOW`] and the start times of recent ones.
+    rate: Option<(usize, Mutex<VecDeque<Instant>>)>,
+}
+
+impl CallLimits {
+    /// Wait until a call may start; the permit holds its concurrency slot.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let permit = match &self.concurrency {
+            Some(semaphore) => Arc::clone(semaphore).acquire_owned().await.ok(),
+            None => None,
+        };
+        if let Some((limit, started)) = &self.rate {
+            loop {
+                let wait = {
+                    let mut started = started.lock().unwrap_or_else(PoisonError::into_inner);
+                    while started.front().is_some_and(|t| t.elapsed() >= RATE_WINDOW) {
+                        started.pop_front();
+                    }
+                    if started.len() < *limit {
+                        started.push_back(Instant::now());
+                        break;
+                    }
+                    RATE_WINDOW
+                        .saturating_sub(started.front().map_or(Duration::ZERO, Instant::elapsed))
+                };
+                tracing::debug!(
+                    wait_ms = wait.as_millis(),
+                    "MCP rate limit reached — waiting"
+                );
+                tokio::time::sleep(wait).await;
+            }
+        }
+        permit
+    }
+}
+
+/// Time spent waiting for the user to answer the server's questions
+/// (elicitation), which does not count against a call's timeout.
+#[derive(Default)]
+struct UserWait {
+    /// Questions being asked right now.
+    asking: AtomicUsize,
+    /// Total time of questions already answered.
+    waited_ms: AtomicU64,
+}
+
+/// A question in progress; counted as waited once dropped.
+struct Asking<'a>(&'a UserWait, Instant);
+
+impl Drop for Asking<'_> {
+    fn drop(&mut self) {
+        let waited = u64::try_from(self.1.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.0.waited_ms.fetch_add(waited, Ordering::Relaxed);
+        self.0.asking.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl UserWait {
+    /// Run `fut`, which waits for the user.
+    async fn during<T>(&self, fut: impl std::future::Future<Output = T>) -> T {
+        self.asking.fetch_add(1, Ordering::Relaxed);
+        let _asking = Asking(self, Instant::now());
+        fut.await
+    }
+
+    /// Run `fut` for at most `limit`, not counting time spent waiting for
+    /// the user meanwhile; `Err` when it ran out of time.
+    async fn timeout<F: std::future::Future>(
+        &self,
+        limit: Duration,
+        fut: F,
+    ) -> Result<F::Output, ()> {
+        let started = Instant::now();
+        let waited_before = self.waited_ms.load(Ordering::Relaxed);
+        let mut deadline = started + limit;
+        tokio::pin!(fut);
+        loop {
+            tokio::select! {
+                output = &mut fut => return Ok(output),
+                () = tokio::time::sleep_until(deadline.into()) => {}
+            }
+            if self.asking.load(Ordering::Relaxed) > 0 {
+                deadline = Instant::now() + Duration::from_secs(1);
+                continue;
+            }
+            let waited = self.waited_ms.load(Ordering::Relaxed) - waited_before;
+            deadline = started + limit + Duration::from_millis(waited);
+            if deadline <= Instant::now() {
+                return Err(());
+            }
+        }
+    }
+}
 
-/// MCP protocol version we advertise.
-const PROTOCOL_VERSION: &str = "2024-11-05";
+/// Subscribed resource URIs, each with the notifier of the conversation
+/// that subscribed (`None` outside channel turns).
+type Subscriptions = Arc<Mutex<HashMap<String, Option<Notifier>>>>;
 
 /// Client for a single MCP server.
+#[allow(clippy::struct_excessive_bools)]
 pub struct McpClient {
     pub server_name: String,
-    transport: Box<dyn McpTransport>,
+    transport: Arc<dyn McpTransport>,
     next_id: AtomicU64,
     timeout: Duration,
+    /// Set by [`Self::set_tool_timeouts`].
+    tool_timeouts: HashMap<String, Duration>,
+    /// Set by [`Self::set_retry_policy`].
+    retry: McpRetryConfig,
     has_resources: bool,
+    can_subscribe: bool,
+    has_prompts: bool,
+    has_completions: bool,
+    /// Prompt templates discovered at connect time.
+    prompts: Vec<McpPromptDef>,
+    /// Resource templates discovered at connect time.
+    resource_templates: Vec<McpResourceTemplate>,
+    /// Tools from the latest `tools/list`, refreshed after a server restart.
+    tools: RwLock<Vec<McpToolDef>>,
+    /// Which of the server's tools are bridged; set by [`Self::set_tool_filter`].
+    tool_filter: McpToolFilter,
+    /// How long bridged tools reuse results; set by [`Self::set_cache_ttl`].
+    cache_ttl: Option<Duration>,
+    /// Byte limit of tool output and how it is kept; set by
+    /// [`Self::set_output_limit`].
+    output_limit: Option<(usize, McpOutputStrategy)>,
+    /// How tool descriptions and results are scanned for prompt injection;
+    /// set by [`Self::set_sanitize`].
+    sanitize: McpSanitizeMode,
+    /// Whether tool annotations decide approval; set by
+    /// [`Self::set_trust_annotations`].
+    trust_annotations: bool,
+    /// Set by [`Self::set_call_limits`].
+    limits: CallLimits,
+    /// Cleared once the server failed a health check; its replacement
+    /// client is connected in the background.
+    available: AtomicBool,
+    /// Requests sent and not yet answered; [`Self::drain`] waits for none.
+    pending: Arc<watch::Sender<usize>>,
+    /// Set by [`Self::drain`]; new tool calls are refused.
+    draining: AtomicBool,
+    /// Version agreed on in the latest handshake.
+    protocol_version: Arc<RwLock<&'static str>>,
+    /// Transport generation the handshake last ran against.
+    initialized_generation: tokio::sync::Mutex<u64>,
+    /// Set when the tool list may differ from the bridged tools: on
+    /// `notifications/tools/list_changed` or after a restart.
+    tools_changed: Arc<AtomicBool>,
+    subscriptions: Subscriptions,
+    /// Subscribed resources updated since the last [`Self::take_resource_updates`].
+    resource_updates: Arc<Mutex<Vec<String>>>,
+    /// Progress token (the request id) → callback of tool calls in flight.
+    progress: Arc<Mutex<HashMap<u64, ProgressCallback>>>,
+    logs: Arc<LogForwarding>,
+    /// Set by [`Self::enable_sampling`].
+    sampling: Arc<OnceLock<SamplingHandler>>,
+    /// Set by [`Self::enable_elicitation`].
+    elicitation: Arc<OnceLock<ElicitationHandler>>,
+    /// Paused tool call timeouts while the user answers an elicitation.
+    user_wait: Arc<UserWait>,
+    /// Set by [`Self::enable_roots`].
+    roots: Arc<OnceLock<watch::Receiver<Vec<McpRoot>>>>,
+    /// Sends `notifications/roots/list_changed`; started by [`Self::initialize`].
+    roots_watcher: Option<tokio::task::AbortHandle>,
 }
 
 impl McpClient {
     /// Create a new client wrapping the given transport.
     pub fn new(server_name: String, transport: Box<dyn McpTransport>, timeout_secs: u64) -> Self {
+        let tools_changed = Arc::new(AtomicBool::new(false));
+        let subscriptions = Subscriptions::default();
+        let resource_updates = Arc::new(Mutex::new(Vec::new()));
+        let progress: Arc<Mutex<HashMap<u64, ProgressCallback>>> = Arc::default();
+        let logs: Arc<LogForwarding> = Arc::default();
+        let (flag, subscribed, updates, in_flight, forwarding, server) = (
+            Arc::clone(&tools_changed),
+            Arc::clone(&subscriptions),
+            Arc::clone(&resource_updates),
+            Arc::clone(&progress),
+            Arc::clone(&logs),
+            server_name.clone(),
+        );
+        transport.on_notification(Arc::new(move |method, params| match method {
+            TOOLS_LIST_CHANGED => flag.store(true, Ordering::Relaxed),
+            RESOURCE_UPDATED => {
+                if let Some(uri) = params.get("uri").and_then(Value::as_str) {
+                    resource_updated(&server, uri, &subscribed, &updates);
+                }
+            }
+            PROGRESS => progress_updated(params, &in_flight),
+            LOG_MESSAGE => log_received(&server, params, &forwarding),
+            _ => {}
+        }));
+        let sampling: Arc<OnceLock<SamplingHandler>> = Arc::default();
+        let elicitation: Arc<OnceLock<ElicitationHandler>> = Arc::default();
+        let roots: Arc<OnceLock<watch::Receiver<Vec<McpRoot>>>> = Arc::default();
+        let protocol_version = Arc::new(RwLock::new(OLDEST_PROTOCOL_VERSION));
+        let user_wait: Arc<UserWait> = Arc::default();
+        let (sampler, elicitor, listed, version, wait) = (
+            Arc::clone(&sampling),
+            Arc::clone(&elicitation),
+            Arc::clone(&roots),
+            Arc::clone(&protocol_version),
+            Arc::clone(&user_wait),
+        );
+        transport.on_request(Arc::new(move |method, params| {
+            let sampler = sampler.get().cloned();
+            let elicitor = elicitor.get().cloned().filter(|_| {
+                *version.read().unwrap_or_else(PoisonError::into_inner)
+                    >= ProtocolFeature::Elicitation.since()
+            });
+            let roots = listed.get().map(|rx| rx.borrow().clone());
+            let wait = Arc::clone(&wait);
+            Box::pin(async move {
+                answer_server_request(&method, params, sampler, elicitor, roots, &wait).await
+            })
+        }));
         Self {
             server_name,
-            transport,
+            transport: Arc::from(transport),
             next_id: AtomicU64::new(1),
             timeout: Duration::from_secs(timeout_secs),
+            tool_timeouts: HashMap::new(),
+            retry: McpRetryConfig {
+                max_attempts: 1,
+                ..McpRetryConfig::default()
+            },
             has_resources: false,
+            can_subscribe: false,
+            has_prompts: false,
+            has_completions: false,
+            prompts: Vec::new(),
+            resource_templates: Vec::new(),
+            tools: RwLock::new(Vec::new()),
+            tool_filter: McpToolFilter::default(),
+            cache_ttl: None,
+            output_limit: None,
+            sanitize: McpSanitizeMode::Off,
+            trust_annotations: false,
+            limits: CallLimits::default(),
+            available: AtomicBool::new(true),
+            pending: Arc::new(watch::channel(0).0),
+            draining: AtomicBool::new(false),
+            protocol_version,
+            initialized_generation: tokio::sync::Mutex::new(0),
+            tools_changed,
+            subscriptions,
+            resource_updates,
+            progress,
+            logs,
+            sampling,
+            elicitation,
+            user_wait,
+            roots,
+            roots_watcher: None,
         }
     }
 
@@ -36,14 +382,349 @@ impl McpClient {
         self.next_id.fetch_add(1, Ordering::Relaxed)
     }
 
+    /// Send `req`, retrying transport failures as the retry policy allows.
+    async fn traced_send(&self, req: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        self.send_with_retries(req, true).await
+    }
+
+    /// [`Self::traced_send`]; unless `repeatable`, only failures that show
+    /// the server never ran the request are retried, so a tool with side
+    /// effects is not run twice.
+    async fn send_with_retries(
+        &self,
+        req: &JsonRpcRequest,
+        repeatable: bool,
+    ) -> Result<JsonRpcResponse> {
+        let _pending = Pending::start(&self.pending);
+        let mut attempt = 1;
+        loop {
+            let err = match self.send_once(req).await {
+                Ok(resp) => return Ok(resp),
+                Err(e) => e,
+            };
+            // A stdio server that exited for good is not worth waiting for.
+            let retry = failure_kind(&err).filter(|(kind, _)| {
+                attempt < self.retry.max_attempts
+                    && self.retry.retry_on.contains(kind)
+                    && (repeatable || never_ran(&err))
+                    && self.transport.is_alive()
+            });
+            let Some((_, retry_after)) = retry else {
+                return Err(err);
+            };
+            let delay = self.retry.delay(attempt, retry_after);
+            tracing::warn!(
+                server = %self.server_name,
+                method = %req.method,
+                attempt,
+                retry_in_ms = delay.as_millis(),
+                "MCP request failed, retrying: {err:#}"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send `calls` (method and params) and return their results in order.
+    /// Up to protocol version 2025-03-26 they go in one JSON-RPC batch,
+    /// saving round trips on slow transports. Otherwise, or when the server
+    /// rejects the batch, they are sent one by one, concurrently.
+    pub async fn call_batch(&self, calls: Vec<(&str, Option<Value>)>) -> Vec<Result<Value>> {
+        if calls.len() > 1 && self.protocol_version() < BATCHING_REMOVED_IN {
+            let requests: Vec<JsonRpcRequest> = calls
+                .iter()
+                .map(|(method, params)| {
+                    JsonRpcRequest::new(self.next_id(), *method, params.clone())
+                })
+                .collect();
+            match tokio::time::timeout(self.timeout, self.send_batch(&requests)).await {
+                Ok(Ok(responses)) => {
+                    return requests
+                        .iter()
+                        .zip(responses)
+                        .map(|(req, resp)| rpc_result(&req.method, resp))
+                        .collect();
+                }
+                Ok(Err(e)) => tracing::debug!(
+                    server = %self.server_name,
+                    error = %e,
+                    "MCP batch failed; sending its requests one by one"
+                ),
+                Err(_) => {
+                    return requests
+                        .iter()
+                        .map(|req| Err(anyhow::anyhow!("MCP {} timed out", req.method)))
+                        .collect();
+                }
+            }
+        }
+        let sends = calls.into_iter().map(|(method, params)| async move {
+            let req = JsonRpcRequest::new(self.next_id(), method, params);
+            let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+                .await
+                .with_context(|| format!("MCP {method} timed out"))?
+                .with_context(|| format!("MCP {method} failed"))?;
+            rpc_result(method, resp)
+        });
+        futures::future::join_all(sends).await
+    }
+
+    /// Send `requests` as one batch inside an `mcp_batch` span, after the
+    /// handshake if the server restarted.
+    async fn send_batch(&self, requests: &[JsonRpcRequest]) -> Result<Vec<JsonRpcResponse>> {
+        let _pending = Pending::start(&self.pending);
+        self.ensure_initialized().await?;
+        let span = tracing::info_span!(
+            "mcp_batch",
+            server = %self.server_name,
+            requests = requests.len(),
+        );
+        self.transport.send_batch(requests).instrument(span).await
+    }
+
+    /// Send `req` inside an `mcp_request` span, so MCP round-trips show up
+    /// under the tool call that triggered them. If the transport restarted
+    /// the server, the handshake runs again first and a request lost to the
+    /// restart is retried once.
+    async fn send_once(&self, req: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        self.ensure_initialized().await?;
+        let generation = self.transport.generation();
+        let result = self.send_raw(req).await;
+        if self.transport.generation() == generation {
+            return result;
+        }
+        self.ensure_initialized().await?;
+        self.send_raw(req).await
+    }
+
+    async fn send_raw(&self, req: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let span = tracing::info_span!(
+            "mcp_request",
+            server = %self.server_name,
+            method = %req.method,
+            rpc_id = req.id,
+        );
+        self.transport.send(req).instrument(span).await
+    }
+
+    /// Re-run `initialize` and refresh the tool list when the server was
+    /// restarted since the last handshake: a fresh process rejects
+    /// everything until it has been initialized.
+    async fn ensure_initialized(&self) -> Result<()> {
+        let mut initialized = self.initialized_generation.lock().await;
+        let generation = self.transport.generation();
+        if *initialized == generation {
+            return Ok(());
+        }
+        tracing::info!(server = %self.server_name, "MCP server restarted — re-running initialize");
+        metrics::record_restart(&self.server_name, "crash");
+        self.handshake()
+            .await
+            .context("MCP re-initialize after restart failed")?;
+        *initialized = generation;
+        drop(initialized);
+        // The restarted server may offer different tools and has forgotten
+        // our subscriptions.
+        self.tools_changed.store(true, Ordering::Relaxed);
+        for uri in self.subscriptions() {
+            if let Err(e) = Box::pin(self.resource_request("resources/subscribe", &uri)).await {
+                tracing::warn!(
+                    server = %self.server_name,
+                    uri = %uri,
+                    error = %e,
+                    "MCP resource re-subscribe after restart failed"
+                );
+            }
+        }
+        // Boxed: `list_tools` sends through `traced_send`, which calls back here.
+        if let Err(e) = Box::pin(self.list_tools()).await {
+            tracing::warn!(
+                server = %self.server_name,
+                error = %e,
+                "MCP tools/list after restart failed — keeping previous tool schemas"
+            );
+        }
+        Ok(())
+    }
+
     /// Perform the MCP `initialize` handshake.
     pub async fn initialize(&mut self) -> Result<InitializeResult> {
+        let result = self.handshake().await?;
+        *self.initialized_generation.get_mut() = self.transport.generation();
+
+        // Track whether server supports resources
+        self.has_resources = result.capabilities.resources.is_some();
+        self.can_subscribe = result
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|r| r.get("subscribe"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        self.has_prompts = result.capabilities.prompts.is_some();
+        self.has_completions = result.capabilities.completions.is_some();
+        if self.roots_watcher.is_none() {
+            self.roots_watcher = self.watch_roots();
+        }
+
+        Ok(result)
+    }
+
+    /// Protocol version agreed on with the server.
+    pub fn protocol_version(&self) -> &'static str {
+        *self
+            .protocol_version
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Whether the negotiated protocol version includes `feature`.
+    pub fn supports(&self, feature: ProtocolFeature) -> bool {
+        self.protocol_version() >= feature.since()
+    }
+
+    /// Let the server ask our LLM for completions through `handler`. Must be
+    /// called before [`Self::initialize`], which advertises the capability.
+    pub fn enable_sampling(&self, handler: SamplingHandler) {
+        let _ = self.sampling.set(handler);
+    }
+
+    /// Let the server ask the user for input through `handler`. Must be
+    /// called before [`Self::initialize`], which advertises the capability;
+    /// requests are only answered when the negotiated protocol has them.
+    pub fn enable_elicitation(&self, handler: ElicitationHandler) {
+        let _ = self.elicitation.set(handler);
+    }
+
+    /// Post the server's log messages of `level` and above to the
+    /// conversation of the tool call running when they arrive.
+    pub fn forward_logs(&self, level: McpLogLevel) {
+        let _ = self.logs.level.set(level);
+    }
+
+    /// Only offer the server tools `filter` allows.
+    pub fn set_tool_filter(&mut self, filter: McpToolFilter) {
+        self.tool_filter = filter;
+    }
+
+    /// Give calls to the named tools their own timeout in seconds instead
+    /// of the server's.
+    pub fn set_tool_timeouts(&mut self, timeouts: &HashMap<String, u64>) {
+        self.tool_timeouts = timeouts
+            .iter()
+            .map(|(name, secs)| (name.clone(), Duration::from_secs(*secs)))
+            .collect();
+    }
+
+    /// Retry requests that fail in the transport as `policy` says. Without
+    /// a policy every request is tried once.
+    pub fn set_retry_policy(&mut self, policy: McpRetryConfig) {
+        self.retry = policy;
+    }
+
+    /// How long a call to the tool `name` may take.
+    pub fn tool_timeout(&self, name: &str) -> Duration {
+        self.tool_timeouts
+            .get(name)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    /// Let bridged tools answer repeated calls with identical arguments from
+    /// their cache for `ttl`.
+    pub fn set_cache_ttl(&mut self, ttl: Option<Duration>) {
+        self.cache_ttl = ttl;
+    }
+
+    /// Cap tool calls in flight at once and started per minute; 0 leaves
+    /// either unlimited.
+    pub fn set_call_limits(&mut self, max_concurrent: usize, per_minute: usize) {
+        self.limits = CallLimits {
+            concurrency: (max_concurrent > 0).then(|| Arc::new(Semaphore::new(max_concurrent))),
+            rate: (per_minute > 0).then(|| (per_minute, Mutex::default())),
+        };
+    }
+
+    /// How long bridged tools reuse results, `None` when caching is off.
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache_ttl
+    }
+
+    /// Shorten tool output over `max_bytes` with `strategy`; 0 leaves it
+    /// unlimited.
+    pub fn set_output_limit(&mut self, max_bytes: usize, strategy: McpOutputStrategy) {
+        self.output_limit = (max_bytes > 0).then_some((max_bytes, strategy));
+    }
+
+    /// Byte limit of tool output and how longer output is shortened, `None`
+    /// when output is unlimited.
+    pub fn output_limit(&self) -> Option<(usize, McpOutputStrategy)> {
+        self.output_limit
+    }
+
+    /// Scan tool descriptions and results for prompt injection with `mode`.
+    pub fn set_sanitize(&mut self, mode: McpSanitizeMode) {
+        self.sanitize = mode;
+    }
+
+    /// How tool descriptions and results are scanned for prompt injection.
+    pub fn sanitize_mode(&self) -> McpSanitizeMode {
+        self.sanitize
+    }
+
+    /// Let the server's tool annotations decide whether calls need approval.
+    pub fn set_trust_annotations(&mut self, trust: bool) {
+        self.trust_annotations = trust;
+    }
+
+    /// Whether the server's tool annotations decide approval.
+    pub fn trusts_annotations(&self) -> bool {
+        self.trust_annotations
+    }
+
+    /// Answer the server's `roots/list` requests from `roots`. Must be called
+    /// before [`Self::initialize`], which advertises the capability and
+    /// tells the server whenever `roots` changes.
+    pub fn enable_roots(&self, roots: watch::Receiver<Vec<McpRoot>>) {
+        let _ = self.roots.set(roots);
+    }
+
+    /// Send `notifications/roots/list_changed` each time the roots change,
+    /// until the client is dropped.
+    fn watch_roots(&self) -> Option<tokio::task::AbortHandle> {
+        let mut roots = self.roots.get()?.clone();
+        roots.mark_unchanged();
+        let transport = Arc::clone(&self.transport);
+        let server = self.server_name.clone();
+        let task = tokio::spawn(async move {
+            while roots.changed().await.is_ok() {
+                let notification = JsonRpcNotification::new(ROOTS_LIST_CHANGED, None);
+                if let Err(e) = transport.notify(&notification).await {
+                    tracing::debug!(server = %server, error = %e, "MCP roots notification failed");
+                }
+            }
+        });
+        Some(task.abort_handle())
+    }
+
+    /// `initialize` request plus the `initialized` notification.
+    async fn handshake(&self) -> Result<InitializeResult> {
+        let mut capabilities = json!({});
+        if self.sampling.get().is_some() {
+            capabilities["sampling"] = json!({});
+        }
+        if self.elicitation.get().is_some() {
+            capabilities["elicitation"] = json!({});
+        }
+        if self.roots.get().is_some() {
+            capabilities["roots"] = json!({ "listChanged": true });
+        }
         let req = JsonRpcRequest::new(
             self.next_id(),
             "initialize",
             Some(json!({
-                "protocolVersion": PROTOCOL_VERSION,
-                "capabilities": {},
+                "protocolVersion": PROTOCOL_VERSIONS[0],
+                "capabilities": capabilities,
                 "clientInfo": {
                     "name": "zeroclaw",
                     "version": env!("CARGO_PKG_VERSION")
@@ -51,7 +732,7 @@ impl McpClient {
             })),
         );
 
-        let resp = tokio::time::timeout(self.timeout, self.transport.send(&req))
+        let resp = tokio::time::timeout(self.timeout, self.send_raw(&req))
             .await
             .context("MCP initialize timed out")?
             .context("MCP initialize failed")?;
@@ -62,9 +743,29 @@ impl McpClient {
 
         let result: InitializeResult =
             serde_json::from_value(resp.result.context("MCP initialize: empty result")?)?;
-
-        // Track whether server supports resources
-        self.has_resources = result.capabilities.resources.is_some();
+        let Some(version) = PROTOCOL_VERSIONS
+            .iter()
+            .find(|v| **v == result.protocol_version)
+        else {
+            bail!(
+                "MCP server '{}' speaks protocol version {}, which is not supported (supported: {})",
+                self.server_name,
+                result.protocol_version,
+                PROTOCOL_VERSIONS.join(", ")
+            );
+        };
+        if *version != PROTOCOL_VERSIONS[0] {
+            tracing::debug!(
+                server = %self.server_name,
+                version,
+                "MCP server negotiated an older protocol version"
+            );
+        }
+        *self
+            .protocol_version
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = version;
+        self.transport.set_protocol_version(version);
 
         // Send initialized notification (no response expected, but we must send it)
         let notif =
@@ -75,11 +776,12 @@ impl McpClient {
         Ok(result)
     }
 
-    /// List tools available on this MCP server.
+    /// List the tools of this MCP server that pass the tool filter and
+    /// remember their schemas.
     pub async fn list_tools(&self) -> Result<Vec<McpToolDef>> {
         let req = JsonRpcRequest::new(self.next_id(), "tools/list", None);
 
-        let resp = tokio::time::timeout(self.timeout, self.transport.send(&req))
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
             .await
             .context("MCP tools/list timed out")?
             .context("MCP tools/list failed")?;
@@ -96,25 +798,160 @@ impl McpClient {
             .cloned()
             .unwrap_or_else(|| Value::Array(vec![]));
 
-        let tools: Vec<McpToolDef> = serde_json::from_value(tools_val)?;
+        let (tools, filtered): (Vec<McpToolDef>, Vec<McpToolDef>) =
+            serde_json::from_value::<Vec<McpToolDef>>(tools_val)?
+                .into_iter()
+                .partition(|t| self.tool_filter.allows(&t.name));
+        if !filtered.is_empty() {
+            let names: Vec<&str> = filtered.iter().map(|t| t.name.as_str()).collect();
+            tracing::info!(
+                server = %self.server_name,
+                filtered = %names.join(", "),
+                "MCP tools filtered out by include_tools/exclude_tools"
+            );
+        }
+        self.tools
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone_from(&tools);
         Ok(tools)
     }
 
+    /// Whether the server's tools changed since the last call, checking for
+    /// notifications that arrived between requests.
+    pub async fn take_tools_changed(&self) -> bool {
+        self.transport.poll_notifications().await;
+        self.tools_changed.swap(false, Ordering::Relaxed)
+    }
+
+    /// Names of the tools from the latest `tools/list`.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tools
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .map(|t| t.name.clone())
+            .collect()
+    }
+
+    /// Input schema of `tool` from the latest `tools/list`.
+    pub fn tool_schema(&self, tool: &str) -> Option<Value> {
+        self.tools
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .find(|t| t.name == tool)
+            .and_then(|t| t.input_schema.clone())
+    }
+
     /// Call a tool on this MCP server.
     pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<ToolCallResult> {
-        let req = JsonRpcRequest::new(
-            self.next_id(),
-            "tools/call",
-            Some(json!({
-                "name": name,
-                "arguments": arguments,
-            })),
-        );
-
-        let resp = tokio::time::timeout(self.timeout, self.transport.send(&req))
+        self.call_tool_with(name, arguments, ToolCallOptions::default())
             .await
-            .context("MCP tools/call timed out")?
-            .context("MCP tools/call failed")?;
+    }
+
+    /// [`Self::call_tool`] with progress reporting and cancellation. A call
+    /// that is cancelled, times out or is dropped before it completes sends
+    /// `notifications/cancelled`, so the server can stop working on it.
+    /// Calls over the server's concurrency or rate limit wait for a slot
+    /// first.
+    pub async fn call_tool_with(
+        &self,
+        name: &str,
+        arguments: Value,
+        options: ToolCallOptions,
+    ) -> Result<ToolCallResult> {
+        if self.draining.load(Ordering::Relaxed) {
+            bail!("MCP server '{}' is shutting down", self.server_name);
+        }
+        let slot = self.limits.acquire();
+        let _permit = match &options.cancel {
+            Some(cancel) => tokio::select! {
+                permit = slot => permit,
+                () = cancel.cancelled() => bail!("MCP tools/call cancelled"),
+            },
+            None => slot.await,
+        };
+        let audited = audit::enabled().then(|| arguments.clone());
+        let started = Instant::now();
+        let result = self.send_tool_call(name, arguments, options).await;
+        let failed = result.as_ref().map_or(true, |r| r.is_error);
+        metrics::record_call(&self.server_name, name, started.elapsed(), failed);
+        if let Some(arguments) = audited {
+            audit::record(
+                &self.server_name,
+                name,
+                &arguments,
+                &result,
+                started.elapsed(),
+            );
+        }
+        result
+    }
+
+    /// Send `tools/call` and wait for the result.
+    async fn send_tool_call(
+        &self,
+        name: &str,
+        arguments: Value,
+        options: ToolCallOptions,
+    ) -> Result<ToolCallResult> {
+        let id = self.next_id();
+        let mut params = json!({
+            "name": name,
+            "arguments": arguments,
+        });
+        if let Some(on_progress) = options.on_progress {
+            params["_meta"] = json!({ "progressToken": id });
+            self.progress
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(id, on_progress);
+        }
+        if let Some(notify) = super::notifier().filter(|_| self.logs.level.get().is_some()) {
+            self.logs
+                .sinks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(id, notify);
+        }
+        let req = JsonRpcRequest::new(id, "tools/call", Some(params));
+
+        let in_flight = InFlight::new(Arc::clone(&self.transport), id);
+        let timeout = self.tool_timeout(name);
+        let send = self
+            .user_wait
+            .timeout(timeout, self.send_with_retries(&req, options.idempotent));
+        let outcome = match options.cancel {
+            Some(cancel) => tokio::select! {
+                outcome = send => Some(outcome),
+                () = cancel.cancelled() => None,
+            },
+            None => Some(send.await),
+        };
+        self.progress
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        self.logs
+            .sinks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+        let resp = match outcome {
+            None => {
+                in_flight.cancel("Cancelled by the user");
+                bail!("MCP tools/call cancelled");
+            }
+            Some(Err(())) => {
+                in_flight.cancel("Timed out");
+                bail!("MCP tools/call timed out after {}s", timeout.as_secs());
+            }
+            Some(Ok(resp)) => {
+                in_flight.finish();
+                resp.context("MCP tools/call failed")?
+            }
+        };
 
         if let Some(err) = resp.error {
             bail!("MCP tools/call error: {err}");
@@ -135,7 +972,7 @@ impl McpClient {
     pub async fn list_resources(&self) -> Result<ResourcesListResult> {
         let req = JsonRpcRequest::new(self.next_id(), "resources/list", None);
 
-        let resp = tokio::time::timeout(self.timeout, self.transport.send(&req))
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
             .await
             .context("MCP resources/list timed out")?
             .context("MCP resources/list failed")?;
@@ -150,6 +987,19 @@ impl McpClient {
         Ok(result)
     }
 
+    /// Read several resources, in one batch where the server allows it.
+    pub async fn read_resources(&self, uris: &[String]) -> Vec<Result<ResourceReadResult>> {
+        let calls = uris
+            .iter()
+            .map(|uri| ("resources/read", Some(json!({ "uri": uri }))))
+            .collect();
+        self.call_batch(calls)
+            .await
+            .into_iter()
+            .map(|result| Ok(serde_json::from_value(result?)?))
+            .collect()
+    }
+
     /// Read a specific resource by URI.
     pub async fn read_resource(&self, uri: &str) -> Result<ResourceReadResult> {
         let req = JsonRpcRequest::new(
@@ -158,7 +1008,7 @@ impl McpClient {
             Some(json!({ "uri": uri })),
         );
 
-        let resp = tokio::time::timeout(self.timeout, self.transport.send(&req))
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
             .await
             .context("MCP resources/read timed out")?
             .context("MCP resources/read failed")?;
@@ -173,6 +1023,267 @@ impl McpClient {
         Ok(result)
     }
 
+    /// Resource templates discovered by
+    /// [`Self::discover_resource_templates`].
+    pub fn resource_templates(&self) -> &[McpResourceTemplate] {
+        &self.resource_templates
+    }
+
+    /// Fetch and remember the server's resource templates. Called once at
+    /// connect time; failures leave the list empty. Templates are optional,
+    /// so servers without them are only logged at debug level.
+    pub async fn discover_resource_templates(&mut self) {
+        if !self.has_resources {
+            return;
+        }
+        match self.list_resource_templates().await {
+            Ok(result) => self.resource_templates = result.resource_templates,
+            Err(e) => tracing::debug!(
+                server = %self.server_name,
+                error = %e,
+                "MCP resources/templates/list failed"
+            ),
+        }
+    }
+
+    /// List resource templates available on this MCP server.
+    pub async fn list_resource_templates(&self) -> Result<ResourceTemplatesListResult> {
+        let req = JsonRpcRequest::new(self.next_id(), "resources/templates/list", None);
+
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .context("MCP resources/templates/list timed out")?
+            .context("MCP resources/templates/list failed")?;
+
+        if let Some(err) = resp.error {
+            bail!("MCP resources/templates/list error: {err}");
+        }
+
+        let result: ResourceTemplatesListResult = serde_json::from_value(
+            resp.result
+                .context("MCP resources/templates/list: empty result")?,
+        )?;
+
+        Ok(result)
+    }
+
+    /// Whether this server supports `resources/subscribe`.
+    pub fn can_subscribe(&self) -> bool {
+        self.can_subscribe
+    }
+
+    /// Watch a resource. Updates are queued for [`Self::take_resource_updates`]
+    /// and, when subscribed during a channel turn, posted to that conversation.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        self.resource_request("resources/subscribe", uri).await?;
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(uri.to_string(), super::notifier());
+        Ok(())
+    }
+
+    /// Stop watching a resource. Returns `false` if it was not subscribed.
+    pub async fn unsubscribe_resource(&self, uri: &str) -> Result<bool> {
+        let removed = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(uri)
+            .is_some();
+        if removed {
+            self.resource_request("resources/unsubscribe", uri).await?;
+        }
+        Ok(removed)
+    }
+
+    /// Take over the resource subscriptions of `previous`, the client this
+    /// one replaces, and subscribe again on this connection.
+    pub async fn renew_subscriptions(&self, previous: &McpClient) {
+        let subscriptions = previous
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        for (uri, notify) in subscriptions {
+            match self.resource_request("resources/subscribe", &uri).await {
+                Ok(()) => {
+                    self.subscriptions
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(uri, notify);
+                }
+                Err(e) => tracing::warn!(
+                    server = %self.server_name,
+                    uri = %uri,
+                    error = %e,
+                    "MCP resource re-subscribe after reconnect failed"
+                ),
+            }
+        }
+    }
+
+    /// URIs of the subscribed resources.
+    pub fn subscriptions(&self) -> Vec<String> {
+        let mut uris: Vec<String> = self
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect();
+        uris.sort();
+        uris
+    }
+
+    /// Subscribed resources updated since the last call, checking for
+    /// notifications that arrived between requests.
+    pub async fn take_resource_updates(&self) -> Vec<String> {
+        self.transport.poll_notifications().await;
+        std::mem::take(
+            &mut *self
+                .resource_updates
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    async fn resource_request(&self, method: &str, uri: &str) -> Result<()> {
+        let req = JsonRpcRequest::new(self.next_id(), method, Some(json!({ "uri": uri })));
+
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .with_context(|| format!("MCP {method} timed out"))?
+            .with_context(|| format!("MCP {method} failed"))?;
+
+        if let Some(err) = resp.error {
+            bail!("MCP {method} error: {err}");
+        }
+        Ok(())
+    }
+
+    /// Whether this server advertises prompt support.
+    pub fn has_prompts(&self) -> bool {
+        self.has_prompts
+    }
+
+    /// Prompt templates discovered by [`Self::discover_prompts`].
+    pub fn prompts(&self) -> &[McpPromptDef] {
+        &self.prompts
+    }
+
+    /// Fetch and remember the server's prompt templates. Called once at
+    /// connect time; failures leave the list empty.
+    pub async fn discover_prompts(&mut self) {
+        if !self.has_prompts {
+            return;
+        }
+        match self.list_prompts().await {
+            Ok(result) => self.prompts = result.prompts,
+            Err(e) => tracing::warn!(
+                server = %self.server_name,
+                error = %e,
+                "MCP prompts/list failed"
+            ),
+        }
+    }
+
+    /// List prompt templates available on this MCP server.
+    pub async fn list_prompts(&self) -> Result<PromptsListResult> {
+        let req = JsonRpcRequest::new(self.next_id(), "prompts/list", None);
+
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .context("MCP prompts/list timed out")?
+            .context("MCP prompts/list failed")?;
+
+        if let Some(err) = resp.error {
+            bail!("MCP prompts/list error: {err}");
+        }
+
+        let result: PromptsListResult =
+            serde_json::from_value(resp.result.context("MCP prompts/list: empty result")?)?;
+
+        Ok(result)
+    }
+
+    /// Expand a prompt template with string `arguments`.
+    pub async fn get_prompt(&self, name: &str, arguments: Value) -> Result<PromptGetResult> {
+        let req = JsonRpcRequest::new(
+            self.next_id(),
+            "prompts/get",
+            Some(json!({ "name": name, "arguments": arguments })),
+        );
+
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .context("MCP prompts/get timed out")?
+            .context("MCP prompts/get failed")?;
+
+        if let Some(err) = resp.error {
+            bail!("MCP prompts/get error: {err}");
+        }
+
+        let result: PromptGetResult =
+            serde_json::from_value(resp.result.context("MCP prompts/get: empty result")?)?;
+
+        Ok(result)
+    }
+
+    /// Whether this server advertises argument completion.
+    pub fn has_completions(&self) -> bool {
+        self.has_completions
+    }
+
+    /// Suggestions for `argument` of the prompt or resource template named
+    /// by `reference` (a `ref/prompt` or `ref/resource` object), given what
+    /// has been typed so far and the arguments already `known`. Empty when
+    /// the server does not complete arguments.
+    pub async fn complete(
+        &self,
+        reference: &Value,
+        argument: &str,
+        value: &str,
+        known: &Map<String, Value>,
+    ) -> Result<Completion> {
+        if !self.has_completions {
+            return Ok(Completion::default());
+        }
+        let mut params = json!({
+            "ref": reference,
+            "argument": { "name": argument, "value": value },
+        });
+        if !known.is_empty() {
+            params["context"] = json!({ "arguments": known });
+        }
+        let req = JsonRpcRequest::new(self.next_id(), "completion/complete", Some(params));
+
+        let resp = tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .context("MCP completion/complete timed out")?
+            .context("MCP completion/complete failed")?;
+
+        if let Some(err) = resp.error {
+            bail!("MCP completion/complete error: {err}");
+        }
+
+        let result: CompleteResult = serde_json::from_value(
+            resp.result
+                .context("MCP completion/complete: empty result")?,
+        )?;
+
+        Ok(result.completion)
+    }
+
+    /// Refuse new tool calls and wait up to `timeout` for requests in flight
+    /// to be answered. Returns how many were still waiting.
+    pub async fn drain(&self, timeout: Duration) -> usize {
+        self.draining.store(true, Ordering::Relaxed);
+        let mut pending = self.pending.subscribe();
+        let _ = tokio::time::timeout(timeout, pending.wait_for(|n| *n == 0)).await;
+        *self.pending.borrow()
+    }
+
     /// Gracefully shut down the transport.
     pub async fn shutdown(&self) -> Result<()> {
         self.transport.shutdown().await
@@ -182,4 +1293,292 @@ impl McpClient {
     pub fn is_alive(&self) -> bool {
         self.transport.is_alive()
     }
+
+    /// Whether the server passed its health checks so far.
+    pub fn is_available(&self) -> bool {
+        self.available.load(Ordering::Relaxed)
+    }
+
+    /// Mark the server as failed; its tools stop calling it.
+    pub fn mark_unavailable(&self) {
+        self.available.store(false, Ordering::Relaxed);
+    }
+
+    /// Round-trip a `ping` to check the server still answers. An error reply
+    /// (e.g. from a server without `ping`) still counts as alive.
+    pub async fn ping(&self) -> Result<()> {
+        if !self.is_alive() {
+            bail!("MCP transport is closed");
+        }
+        let req = JsonRpcRequest::new(self.next_id(), "ping", None);
+        tokio::time::timeout(self.timeout, self.traced_send(&req))
+            .await
+            .context("MCP ping timed out")?
+            .context("MCP ping failed")?;
+        Ok(())
+    }
+}
+
+/// The kind of transport failure `err` is, with the server's
+/// `Retry-After` if it sent one; `None` for failures no retry can fix.
+fn failure_kind(err: &anyhow::Error) -> Option<(McpRetryOn, Option<Duration>)> {
+    err.chain().find_map(|cause| {
+        if let Some(status) = cause.downcast_ref::<HttpStatusError>() {
+            let kind = match status.status.as_u16() {
+                429 => McpRetryOn::RateLimit,
+                500..=599 => McpRetryOn::ServerError,
+                _ => return None,
+            };
+            return Some((kind, status.retry_after));
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() {
+                return Some((McpRetryOn::Timeout, None));
+            }
+            return (e.is_connect() || e.is_request() || e.is_body())
+                .then_some((McpRetryOn::Connection, None));
+        }
+        cause
+            .is::<std::io::Error>()
+            .then_some((McpRetryOn::Connection, None))
+    })
+}
+
+/// Whether `err` shows the server cannot have acted on the request: the
+/// connection was never made, or a rate limit said when to come back.
+fn never_ran(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(status) = cause.downcast_ref::<HttpStatusError>() {
+            return status.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                && status.retry_after.is_some();
+        }
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(reqwest::Error::is_connect)
+    })
+}
+
+/// Queue an update of `uri` if it is subscribed, publish it on the event bus
+/// and post it to the conversation that subscribed.
+fn resource_updated(
+    server: &str,
+    uri: &str,
+    subscriptions: &Subscriptions,
+    updates: &Mutex<Vec<String>>,
+) {
+    let Some(notify) = subscriptions
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(uri)
+        .cloned()
+    else {
+        return;
+    };
+    {
+        let mut pending = updates.lock().unwrap_or_else(PoisonError::into_inner);
+        if !pending.iter().any(|u| u == uri) {
+            pending.push(uri.to_string());
+        }
+    }
+    crate::events::emit(crate::events::Event::ResourceUpdated {
+        server: server.to_string(),
+        uri: uri.to_string(),
+    });
+    if let Some(notify) = notify {
+        notify(format!(
+            "🔔 MCP resource updated: {uri} (server '{server}')"
+        ));
+    }
+}
+
+/// Pass a progress notification to the tool call that asked for it.
+fn progress_updated(params: &Value, in_flight: &Mutex<HashMap<u64, ProgressCallback>>) {
+    let Some(token) = params.get("progressToken").and_then(Value::as_u64) else {
+        return;
+    };
+    let Some(callback) = in_flight
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&token)
+        .cloned()
+    else {
+        return;
+    };
+    callback(&Progress {
+        progress: params
+            .get("progress")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0),
+        total: params.get("total").and_then(Value::as_f64),
+        message: params
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    });
+}
+
+/// Log a server's `notifications/message` under its level and post it to
+/// the conversations of running tool calls when forwarding covers it.
+fn log_received(server: &str, params: &Value, logs: &LogForwarding) {
+    let level = params
+        .get("level")
+        .and_then(|level| serde::Deserialize::deserialize(level).ok())
+        .unwrap_or(McpLogLevel::Info);
+    let logger = params
+        .get("logger")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let text = match params.get("data") {
+        Some(Value::String(text)) => text.clone(),
+        Some(data) => data.to_string(),
+        None => String::new(),
+    };
+    match level {
+        McpLogLevel::Debug => tracing::debug!(server, logger, "MCP server log: {text}"),
+        McpLogLevel::Info | McpLogLevel::Notice => {
+            tracing::info!(server, logger, "MCP server log: {text}");
+        }
+        McpLogLevel::Warning => tracing::warn!(server, logger, "MCP server log: {text}"),
+        _ => tracing::error!(server, logger, "MCP server log: {text}"),
+    }
+
+    if logs.level.get().is_none_or(|min| level < *min) {
+        return;
+    }
+    let mut notifiers: Vec<Notifier> = Vec::new();
+    for notify in logs
+        .sinks
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .values()
+    {
+        if !notifiers.iter().any(|n| Arc::ptr_eq(n, notify)) {
+            notifiers.push(Arc::clone(notify));
+        }
+    }
+    let icon = if level >= McpLogLevel::Error {
+        "🛑"
+    } else {
+        "⚠️"
+    };
+    for notify in notifiers {
+        notify(format!(
+            "{icon} MCP server '{server}' {}: {text}",
+            level.as_str()
+        ));
+    }
+}
+
+/// Answer a request from the server: `ping`, and sampling, elicitation and
+/// roots when enabled.
+async fn answer_server_request(
+    method: &str,
+    params: Value,
+    sampler: Option<SamplingHandler>,
+    elicitor: Option<ElicitationHandler>,
+    roots: Option<Vec<McpRoot>>,
+    user_wait: &UserWait,
+) -> Result<Value, JsonRpcError> {
+    match (method, sampler, elicitor, roots) {
+        ("ping", ..) => Ok(json!({})),
+        (SAMPLING_CREATE_MESSAGE, Some(sample), _, _) => sample(params)
+            .await
+            .map_err(|e| JsonRpcError::new(SAMPLING_REJECTED, format!("{e:#}"))),
+        (ELICITATION_CREATE, _, Some(elicit), _) => user_wait
+            .during(elicit(params))
+            .await
+            .map_err(|e| JsonRpcError::new(INTERNAL_ERROR, format!("{e:#}"))),
+        (ROOTS_LIST, _, _, Some(roots)) => Ok(json!({ "roots": roots })),
+        _ => Err(JsonRpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {method}"),
+        )),
+    }
+}
+
+/// A request the server may still be working on. Unless [`Self::finish`]ed,
+/// the server is told to cancel it, also when the call is dropped mid-flight
+/// (e.g. the turn was aborted).
+struct InFlight {
+    transport: Option<Arc<dyn McpTransport>>,
+    request_id: u64,
+}
+
+impl InFlight {
+    fn new(transport: Arc<dyn McpTransport>, request_id: u64) -> Self {
+        Self {
+            transport: Some(transport),
+            request_id,
+        }
+    }
+
+    /// The server answered; nothing to cancel.
+    fn finish(mut self) {
+        self.transport = None;
+    }
+
+    fn cancel(mut self, reason: &str) {
+        if let Some(transport) = self.transport.take() {
+            send_cancelled(transport, self.request_id, reason);
+        }
+    }
+}
+
+/// The result of `resp` to a `method` request, or its error.
+fn rpc_result(method: &str, resp: JsonRpcResponse) -> Result<Value> {
+    if let Some(err) = resp.error {
+        bail!("MCP {method} error: {err}");
+    }
+    resp.result
+        .with_context(|| format!("MCP {method}: empty result"))
+}
+
+/// Counts a request as waiting for its answer until dropped, also when the
+/// call is abandoned mid-flight.
+struct Pending(Arc<watch::Sender<usize>>);
+
+impl Pending {
+    fn start(pending: &Arc<watch::Sender<usize>>) -> Self {
+        pending.send_modify(|n| *n += 1);
+        Self(Arc::clone(pending))
+    }
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        self.0.send_modify(|n| *n -= 1);
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        if let Some(watcher) = self.roots_watcher.take() {
+            watcher.abort();
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some(transport) = self.transport.take() {
+            send_cancelled(transport, self.request_id, "Request aborted");
+        }
+    }
+}
+
+/// Send `notifications/cancelled` in the background: the caller has given up
+/// on the request and must not wait for the server.
+fn send_cancelled(transport: Arc<dyn McpTransport>, request_id: u64, reason: &str) {
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let notification = JsonRpcNotification::new(
+        CANCELLED,
+        Some(json!({ "requestId": request_id, "reason": reason })),
+    );
+    runtime.spawn(async move {
+        if let Err(e) = transport.notify(&notification).await {
+            tracing::debug!(request_id, error = %e, "MCP cancel notification failed");
+        }
+    });
 }
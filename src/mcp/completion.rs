@@ -0,0 +1,175 @@
+//! MCP argument completion (`completion/complete`).
+//!
+//! When a prompt or resource template is used without some of its required
+//! arguments, the user of the conversation is asked for each one, with the
+//! server's suggestions offered as choices: as buttons on channels, as a
+//! numbered list in a terminal. Ending a reply with `?` asks the server for
+//! suggestions that complete what was typed. Servers that do not advertise
+//! `completions` get the question without suggestions.
+
+use super::client::McpClient;
+use super::elicitation::asker;
+use super::protocol::McpPromptDef;
+use super::uri_template;
+use anyhow::{bail, Result};
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+
+/// Answer that stops asking.
+const CANCEL: &str = "/cancel";
+/// Ending of an answer that asks for suggestions completing it.
+const MORE: char = '?';
+/// Suggestions offered per question.
+const MAX_SUGGESTIONS: usize = 10;
+/// Questions asked per argument before giving up.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Reference to a prompt for `completion/complete`.
+pub fn prompt_ref(name: &str) -> Value {
+    json!({ "type": "ref/prompt", "name": name })
+}
+
+/// Reference to a resource template for `completion/complete`.
+pub fn resource_ref(uri_template: &str) -> Value {
+    json!({ "type": "ref/resource", "uri": uri_template })
+}
+
+/// An argument that has no value yet.
+pub struct Missing {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Required arguments of `prompt` not in `arguments`.
+pub fn missing_prompt_arguments(
+    prompt: &McpPromptDef,
+    arguments: &Map<String, Value>,
+) -> Vec<Missing> {
+    prompt
+        .arguments
+        .iter()
+        .filter(|a| a.required && is_unset(arguments, &a.name))
+        .map(|a| Missing {
+            name: a.name.clone(),
+            description: a.description.clone(),
+        })
+        .collect()
+}
+
+/// Required variables of `uri_template` not in `arguments`.
+pub fn missing_template_variables(
+    uri_template: &str,
+    arguments: &Map<String, Value>,
+) -> Vec<Missing> {
+    uri_template::variables(uri_template)
+        .into_iter()
+        .filter(|v| v.required && is_unset(arguments, &v.name))
+        .map(|v| Missing {
+            description: Some(format!("value of {{{}}} in {uri_template}", v.name)),
+            name: v.name,
+        })
+        .collect()
+}
+
+fn is_unset(arguments: &Map<String, Value>, name: &str) -> bool {
+    arguments.get(name).is_none_or(Value::is_null)
+}
+
+/// Ask the user for each of `missing` and add the answers to `arguments`.
+/// Leaves `arguments` as they are when nobody can answer; fails when the
+/// user cancelled or gave no usable value.
+pub async fn ask_missing(
+    client: &McpClient,
+    reference: &Value,
+    missing: &[Missing],
+    arguments: &mut Map<String, Value>,
+) -> Result<()> {
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let Some(ask) = asker() else {
+        return Ok(());
+    };
+    for argument in missing {
+        let mut question = format!(
+            "✏️ MCP server '{}' needs {}",
+            client.server_name, argument.name
+        );
+        if let Some(description) = &argument.description {
+            let _ = write!(question, " — {description}");
+        }
+        question.push_str("\n(");
+        if client.has_completions() {
+            let _ = write!(
+                question,
+                "Pick a suggestion or type a value; end it with {MORE} for more suggestions. "
+            );
+        }
+        let _ = write!(question, "Reply {CANCEL} to stop.)");
+
+        let mut typed = String::new();
+        let mut value = None;
+        for _ in 0..MAX_ATTEMPTS {
+            let options = suggestions(client, reference, &argument.name, &typed, arguments).await;
+            let text = if !typed.is_empty() && options.is_empty() {
+                format!("No suggestions for '{typed}'.\n{question}")
+            } else {
+                question.clone()
+            };
+            let Some(answer) = ask(text, options.clone()).await else {
+                bail!("No answer for {}", argument.name);
+            };
+            let answer = answer.trim();
+            if answer == CANCEL {
+                bail!("Cancelled");
+            }
+            if let Some(prefix) = answer.strip_suffix(MORE) {
+                if client.has_completions() {
+                    typed = prefix.trim().to_string();
+                    continue;
+                }
+            }
+            let picked = answer
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| options.get(n.checked_sub(1)?))
+                .map_or(answer, String::as_str);
+            if !picked.is_empty() {
+                value = Some(picked.to_string());
+                break;
+            }
+        }
+        let Some(value) = value else {
+            bail!("No value given for {}", argument.name);
+        };
+        arguments.insert(argument.name.clone(), json!(value));
+    }
+    Ok(())
+}
+
+/// The server's suggestions for `argument` starting from `typed`; failures
+/// are logged and give none.
+async fn suggestions(
+    client: &McpClient,
+    reference: &Value,
+    argument: &str,
+    typed: &str,
+    arguments: &Map<String, Value>,
+) -> Vec<String> {
+    match client.complete(reference, argument, typed, arguments).await {
+        Ok(completion) => completion
+            .values
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .collect(),
+        Err(e) => {
+            tracing::warn!(
+                server = %client.server_name,
+                argument,
+                error = %e,
+                "MCP completion/complete failed"
+            );
+            Vec::new()
+        }
+    }
+}
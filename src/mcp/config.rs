@@ -1,8 +1,10 @@
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Top-level MCP configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpConfig {
     /// Whether MCP client support is enabled.
     #[serde(default)]
@@ -10,32 +12,381 @@ pub struct McpConfig {
     /// Named MCP server configurations.
     #[serde(default)]
     pub servers: HashMap<String, McpServerConfig>,
+    /// How servers that opted into sampling are answered.
+    #[serde(default)]
+    pub sampling: McpSamplingConfig,
+    /// Directories advertised to servers as roots besides the workspace;
+    /// relative paths are resolved against the workspace.
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Seconds between pings of each connected server; a server that fails
+    /// one is reconnected in the background (0 disables health checks).
+    #[serde(default = "default_health_check_secs")]
+    pub health_check_secs: u64,
+    /// Seconds shutdown waits for tool calls in flight to finish before
+    /// closing the servers.
+    #[serde(default = "default_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+    /// Audit log of tool calls (`[mcp.audit]`).
+    #[serde(default)]
+    pub audit: McpAuditConfig,
+    /// Named groups of servers (`[mcp.profiles.<name>]`); a conversation
+    /// with a profile only gets the tools of that profile's servers.
+    #[serde(default)]
+    pub profiles: HashMap<String, McpProfileConfig>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            servers: HashMap::new(),
+            sampling: McpSamplingConfig::default(),
+            roots: Vec::new(),
+            health_check_secs: default_health_check_secs(),
+            drain_timeout_secs: default_drain_timeout_secs(),
+            audit: McpAuditConfig::default(),
+            profiles: HashMap::new(),
+        }
+    }
 }
 
 /// Configuration for a single MCP server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct McpServerConfig {
-    /// Transport type: "stdio" (default) or "sse".
+    /// Transport type: "stdio" (default), "docker" (stdio inside a
+    /// container), "sse", or "http" (streamable HTTP).
     #[serde(default = "default_transport")]
     pub transport: String,
-    /// Command to spawn (stdio transport).
+    /// Command to spawn (stdio transport); with docker, the command run in
+    /// the container instead of the image's default.
     #[serde(default)]
     pub command: Option<String>,
-    /// Arguments for the command (stdio transport).
+    /// Arguments for the command (stdio and docker transports).
     #[serde(default)]
     pub args: Vec<String>,
     /// Environment variables for the subprocess.
     #[serde(default)]
     pub env: HashMap<String, String>,
-    /// URL for SSE transport.
+    /// Environment variables for the subprocess read from the OS keyring
+    /// when it is spawned, as `service/account` entries, e.g.
+    /// `GITHUB_TOKEN = "zeroclaw/github"` (needs the `keyring` feature).
+    #[serde(default)]
+    pub env_from_keyring: HashMap<String, String>,
+    /// Container to run the server in (docker transport).
+    #[serde(default)]
+    pub docker: Option<McpDockerConfig>,
+    /// Endpoint URL (sse and http transports).
     #[serde(default)]
     pub url: Option<String>,
+    /// HTTP headers sent with every request, e.g. `Authorization` (sse and
+    /// http transports).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Authorize with OAuth 2.1 and send the access token as a bearer token
+    /// (sse and http transports).
+    #[serde(default)]
+    pub oauth: Option<McpOAuthConfig>,
+    /// Certificates to trust and present (sse and http transports).
+    #[serde(default)]
+    pub tls: Option<McpTlsConfig>,
     /// Timeout in seconds for tool calls.
     #[serde(default = "default_timeout_secs")]
     pub timeout_secs: u64,
+    /// Timeouts in seconds for individual tools (server tool name →
+    /// seconds), overriding `timeout_secs` for calls to those tools.
+    #[serde(default)]
+    pub tool_timeouts: HashMap<String, u64>,
+    /// Seconds to wait for the server to start and list its tools before
+    /// it is skipped.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// How requests are retried after transport failures.
+    #[serde(default)]
+    pub retry: McpRetryConfig,
     /// Auto-restart subprocess on crash (stdio only).
     #[serde(default = "default_auto_restart")]
     pub auto_restart: bool,
+    /// Run the server inside the session sandbox (stdio only; requires
+    /// `runtime.kind = "isolated"`).
+    #[serde(default)]
+    pub isolated: bool,
+    /// Whether the server may ask our LLM for completions
+    /// (`sampling/createMessage`).
+    #[serde(default)]
+    pub sampling: McpSamplingMode,
+    /// Prefix of the server's tool names in place of `mcp__<server>__`,
+    /// e.g. `"gh_"` to name its `search` tool `gh_search`.
+    #[serde(default)]
+    pub name_prefix: Option<String>,
+    /// Glob patterns of the server tools to bridge (default: all).
+    #[serde(default)]
+    pub include_tools: Vec<String>,
+    /// Glob patterns of server tools never to bridge; wins over
+    /// `include_tools`.
+    #[serde(default)]
+    pub exclude_tools: Vec<String>,
+    /// Reuse a successful tool result for identical arguments for this many
+    /// seconds (0 = no caching).
+    #[serde(default)]
+    pub cache_ttl_secs: u64,
+    /// Most tool calls in flight at once; further calls wait (0 = no limit).
+    #[serde(default)]
+    pub max_concurrent_calls: usize,
+    /// Most tool calls started per minute; further calls wait for the
+    /// window to free up (0 = no limit).
+    #[serde(default)]
+    pub rate_limit_per_minute: usize,
+    /// Largest tool output passed to the model, in bytes (0 = no limit).
+    #[serde(default)]
+    pub max_output_bytes: usize,
+    /// What happens to output over `max_output_bytes`.
+    #[serde(default)]
+    pub output_strategy: McpOutputStrategy,
+    /// Scan the server's tool descriptions and results for prompt
+    /// injection: "flag" warns the model, "strip" removes what was found
+    /// (default: off).
+    #[serde(default)]
+    pub sanitize: McpSanitizeMode,
+    /// Let the server's tool annotations (`readOnlyHint`,
+    /// `destructiveHint`) decide approval. Off by default: the server
+    /// chooses them, so a read-only hint would let it skip approval.
+    #[serde(default)]
+    pub trust_annotations: bool,
+    /// Lowest level of the server's log messages to post to the
+    /// conversation of a running tool call, e.g. "warning" (default: none;
+    /// they are only logged).
+    #[serde(default)]
+    pub forward_logs: Option<McpLogLevel>,
+    /// Whether the server may ask the user for input in the middle of a
+    /// tool call (`elicitation/create`).
+    #[serde(default = "default_elicitation")]
+    pub elicitation: bool,
+    /// Replace `$(command)` in `command`, `args`, `env`, `url` and header
+    /// values with the command's output, e.g. to read a key from a
+    /// password manager. `${VAR}` is always replaced.
+    #[serde(default)]
+    pub expand_commands: bool,
+}
+
+/// A server's `include_tools` / `exclude_tools`, compiled.
+#[derive(Debug, Clone, Default)]
+pub struct McpToolFilter {
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl McpToolFilter {
+    pub fn from_config(config: &McpServerConfig) -> Result<Self> {
+        let compile = |patterns: &[String], key: &str| {
+            patterns
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p).with_context(|| format!("{key}: invalid glob \"{p}\""))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            include: compile(&config.include_tools, "include_tools")?,
+            exclude: compile(&config.exclude_tools, "exclude_tools")?,
+        })
+    }
+
+    /// Whether the server tool `name` should be bridged.
+    pub fn allows(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(name)))
+            && !self.exclude.iter().any(|p| p.matches(name))
+    }
+}
+
+/// Whether an MCP server may use sampling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSamplingMode {
+    /// Sampling is not offered to the server.
+    #[default]
+    Off,
+    /// Each request is shown to the user in the terminal for approval;
+    /// requests nobody can approve are rejected.
+    Ask,
+    /// Requests are answered without asking.
+    Auto,
+}
+
+/// Transport failures a request can be retried after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpRetryOn {
+    /// The connection could not be made or broke off, or the server
+    /// process closed its output.
+    Connection,
+    /// The HTTP request timed out. The server may have done the work
+    /// anyway, so this is not retried by default.
+    Timeout,
+    /// HTTP 5xx.
+    ServerError,
+    /// HTTP 429. The wait is at least the server's `Retry-After`.
+    RateLimit,
+}
+
+/// Retries of a server's requests (`[mcp.servers.<name>.retry]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpRetryConfig {
+    /// Tries per request, the first one included (default 1, no retries).
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Milliseconds to wait before the first retry; doubled for each
+    /// further one.
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Longest wait between tries in milliseconds.
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Wait a random 50–100% of each delay, so clients that failed
+    /// together do not all retry at the same moment.
+    #[serde(default = "default_retry_jitter")]
+    pub jitter: bool,
+    /// Failures that are retried; anything else fails at once.
+    #[serde(default = "default_retry_on")]
+    pub retry_on: Vec<McpRetryOn>,
+}
+
+impl Default for McpRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            base_delay_ms: default_retry_base_delay_ms(),
+            max_delay_ms: default_retry_max_delay_ms(),
+            jitter: default_retry_jitter(),
+            retry_on: default_retry_on(),
+        }
+    }
+}
+
+impl McpRetryConfig {
+    /// Wait before try `attempt + 1` after try `attempt` failed, at least
+    /// `retry_after` when the server asked for it.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let max = Duration::from_millis(self.max_delay_ms);
+        let backoff = Duration::from_millis(self.base_delay_ms)
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(max);
+        let backoff = if self.jitter {
+            backoff.mul_f64(rand::Rng::gen_range(&mut rand::thread_rng(), 0.5..=1.0))
+        } else {
+            backoff
+        };
+        retry_after.map_or(backoff, |wait| backoff.max(wait.min(max)))
+    }
+}
+
+/// How tool output over a server's `max_output_bytes` is shortened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpOutputStrategy {
+    /// Keep the start of the output.
+    #[default]
+    Truncate,
+    /// Keep the start and the end, dropping the middle.
+    HeadTail,
+    /// Save the whole output to a file and return its start with the path,
+    /// for the model to read the rest in chunks.
+    Spill,
+}
+
+/// What is done with suspected prompt injection in a server's tool
+/// descriptions and results. Invisible characters are removed unless off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpSanitizeMode {
+    /// Pass text through unchanged.
+    #[default]
+    Off,
+    /// Keep suspicious instructions, with a warning to the model ahead of
+    /// the text.
+    Flag,
+    /// Replace suspicious instructions with a placeholder.
+    Strip,
+}
+
+/// Severity of a server log message (`notifications/message`), lowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpLogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl McpLogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Notice => "notice",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Critical => "critical",
+            Self::Alert => "alert",
+            Self::Emergency => "emergency",
+        }
+    }
+}
+
+/// Sampling settings (`[mcp.sampling]`), shared by all servers.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpSamplingConfig {
+    /// Model used when no hint matches (default: `default_model`).
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Model hints servers send, mapped to models of the configured
+    /// provider. A hint matches a key it contains, e.g. `claude-3-sonnet`
+    /// matches `sonnet`.
+    #[serde(default)]
+    pub models: HashMap<String, String>,
+}
+
+/// Audit log settings (`[mcp.audit]`): one JSON line per tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpAuditConfig {
+    /// Whether tool calls are recorded.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Log file (default: `mcp_audit.jsonl` next to the config file).
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Results are cut off at this many characters.
+    #[serde(default = "default_audit_max_result_chars")]
+    pub max_result_chars: usize,
+}
+
+impl Default for McpAuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_result_chars: default_audit_max_result_chars(),
+        }
+    }
+}
+
+/// A named group of servers (`[mcp.profiles.<name>]`), picked per
+/// conversation with `/profile <name>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpProfileConfig {
+    /// Servers whose tools the profile registers.
+    #[serde(default)]
+    pub servers: Vec<String>,
+    /// Channels whose conversations use this profile until they pick
+    /// another, e.g. "cli" or "discord".
+    #[serde(default)]
+    pub channels: Vec<String>,
 }
 
 fn default_transport() -> String {
@@ -46,10 +397,54 @@ fn default_timeout_secs() -> u64 {
     30
 }
 
+fn default_connect_timeout_secs() -> u64 {
+    60
+}
+
+fn default_retry_max_attempts() -> u32 {
+    1
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_retry_max_delay_ms() -> u64 {
+    10_000
+}
+
+fn default_retry_jitter() -> bool {
+    true
+}
+
+fn default_retry_on() -> Vec<McpRetryOn> {
+    vec![
+        McpRetryOn::Connection,
+        McpRetryOn::ServerError,
+        McpRetryOn::RateLimit,
+    ]
+}
+
+fn default_health_check_secs() -> u64 {
+    30
+}
+
+fn default_drain_timeout_secs() -> u64 {
+    10
+}
+
+fn default_audit_max_result_chars() -> usize {
+    2000
+}
+
 fn default_auto_restart() -> bool {
     true
 }
 
+fn default_elicitation() -> bool {
+    true
+}
+
 impl Default for McpServerConfig {
     fn default() -> Self {
         Self {
@@ -57,9 +452,433 @@ impl Default for McpServerConfig {
             command: None,
             args: Vec::new(),
             env: HashMap::new(),
+            env_from_keyring: HashMap::new(),
+            docker: None,
             url: None,
+            headers: HashMap::new(),
+            oauth: None,
+            tls: None,
             timeout_secs: default_timeout_secs(),
+            tool_timeouts: HashMap::new(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            retry: McpRetryConfig::default(),
             auto_restart: default_auto_restart(),
+            isolated: false,
+            sampling: McpSamplingMode::Off,
+            name_prefix: None,
+            include_tools: Vec::new(),
+            exclude_tools: Vec::new(),
+            cache_ttl_secs: 0,
+            max_concurrent_calls: 0,
+            rate_limit_per_minute: 0,
+            max_output_bytes: 0,
+            output_strategy: McpOutputStrategy::default(),
+            sanitize: McpSanitizeMode::default(),
+            trust_annotations: false,
+            forward_logs: None,
+            elicitation: default_elicitation(),
+            expand_commands: false,
+        }
+    }
+}
+
+/// Longest a `$(command)` in a config value may run.
+const EXPAND_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl McpServerConfig {
+    /// This config with `${VAR}` (and, with `expand_commands`, `$(command)`)
+    /// replaced in `command`, `args`, `env`, `url`, header values and the
+    /// docker image and volumes, and with the `env_from_keyring` secrets
+    /// added to `env`, so secrets need not be stored in the config file.
+    pub async fn expanded(&self) -> Result<Self> {
+        let commands = self.expand_commands;
+        let expand = |key: String, value: String| async move {
+            expand(&value, commands)
+                .await
+                .map_err(|e| anyhow::anyhow!("{key}: {e}"))
+        };
+        let mut config = self.clone();
+        if let Some(command) = config.command.take() {
+            config.command = Some(expand("command".into(), command).await?);
+        }
+        for arg in &mut config.args {
+            *arg = expand("args".into(), std::mem::take(arg)).await?;
+        }
+        for (var, value) in &mut config.env {
+            *value = expand(format!("env.{var}"), std::mem::take(value)).await?;
+        }
+        for (var, entry) in &self.env_from_keyring {
+            let secret = keyring_secret(entry)
+                .await
+                .map_err(|e| anyhow::anyhow!("env_from_keyring.{var}: {e}"))?;
+            config.env.insert(var.clone(), secret);
+        }
+        if let Some(url) = config.url.take() {
+            config.url = Some(expand("url".into(), url).await?);
+        }
+        for (name, value) in &mut config.headers {
+            *value = expand(format!("headers.{name}"), std::mem::take(value)).await?;
+        }
+        if let Some(docker) = &mut config.docker {
+            docker.image = expand("docker.image".into(), std::mem::take(&mut docker.image)).await?;
+            for volume in &mut docker.volumes {
+                *volume = expand("docker.volumes".into(), std::mem::take(volume)).await?;
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// `value` with each `${VAR}` replaced by the environment variable and,
+/// when `commands` is set, each `$(command)` by its trimmed output. An
+/// unset variable or a failing command is an error.
+async fn expand(value: &str, commands: bool) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        if let Some(var) = after
+            .strip_prefix('{')
+            .and_then(|r| r.split_once('}'))
+            .map(|(var, _)| var)
+            .filter(|var| is_var_name(var))
+        {
+            let value = std::env::var(var)
+                .map_err(|_| anyhow::anyhow!("${{{var}}} is not set in the environment"))?;
+            expanded.push_str(&value);
+            rest = &after[var.len() + 2..];
+        } else if let Some(len) = after
+            .strip_prefix('(')
+            .and_then(closing_paren)
+            .filter(|_| commands)
+        {
+            expanded.push_str(&run(&after[1..=len]).await?);
+            rest = &after[len + 2..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Length of `text` up to the `)` closing an already opened parenthesis.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Output of `command` run by `sh -c`, without the trailing newline.
+async fn run(command: &str) -> Result<String> {
+    let output = tokio::time::timeout(
+        EXPAND_COMMAND_TIMEOUT,
+        tokio::process::Command::new("sh")
+            .args(["-c", command])
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("$({command}) timed out"))?
+    .with_context(|| format!("Failed to run $({command})"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "$({command}) failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\n', '\r'])
+        .to_string())
+}
+
+/// Service and account of a keyring entry written `service/account`.
+pub fn keyring_entry(entry: &str) -> Option<(&str, &str)> {
+    entry
+        .split_once('/')
+        .filter(|(service, account)| !service.is_empty() && !account.is_empty())
+}
+
+/// The secret stored in the OS keyring under `entry`: the macOS Keychain,
+/// the Secret Service on Linux, or the Windows Credential Manager.
+#[cfg(feature = "keyring")]
+async fn keyring_secret(entry: &str) -> Result<String> {
+    let (service, account) = keyring_entry(entry)
+        .ok_or_else(|| anyhow::anyhow!("\"{entry}\" is not a service/account entry"))?;
+    let key = keyring::Entry::new(service, account)?;
+    // Keyring backends block, and the Secret Service one runs its own
+    // executor, so the lookup leaves the runtime's threads.
+    tokio::task::spawn_blocking(move || key.get_password())
+        .await?
+        .with_context(|| format!("cannot read \"{entry}\" from the OS keyring"))
+}
+
+#[cfg(not(feature = "keyring"))]
+#[allow(clippy::unused_async)] // same signature as the keyring build
+async fn keyring_secret(_entry: &str) -> Result<String> {
+    anyhow::bail!("this build has no keyring support (build with --features keyring)")
+}
+
+/// Container of a server with `transport = "docker"`
+/// (`[mcp.servers.<name>.docker]`). The server runs with `docker run -i`
+/// and speaks stdio through it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpDockerConfig {
+    /// Image to run, e.g. `mcp/github`.
+    pub image: String,
+    /// Bind mounts as `host:container[:ro]`; the host path may start
+    /// with `~`.
+    #[serde(default)]
+    pub volumes: Vec<String>,
+    /// Network to attach to, e.g. `none` (default: Docker's default
+    /// bridge).
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+impl McpDockerConfig {
+    /// Arguments of `docker` that run this container with `command` and
+    /// `args`. Variables in `env` are passed by name, so their values
+    /// come from the environment of the `docker` process and stay out of
+    /// the process list.
+    pub fn run_args(
+        &self,
+        server: &str,
+        command: Option<&str>,
+        args: &[String],
+        env: &HashMap<String, String>,
+    ) -> Vec<String> {
+        let mut run: Vec<String> = ["run", "-i", "--rm", "--init"].map(Into::into).into();
+        run.push(format!("--label=zeroclaw.mcp.server={server}"));
+        if let Some(network) = &self.network {
+            run.push(format!("--network={network}"));
         }
+        for volume in &self.volumes {
+            run.push("-v".into());
+            run.push(shellexpand::tilde(volume).into_owned());
+        }
+        let mut vars: Vec<&String> = env.keys().collect();
+        vars.sort();
+        for var in vars {
+            run.push("-e".into());
+            run.push(var.clone());
+        }
+        run.push(self.image.clone());
+        run.extend(command.map(ToOwned::to_owned));
+        run.extend(args.iter().cloned());
+        run
+    }
+}
+
+/// OAuth settings for a remote MCP server (`[mcp.servers.<name>.oauth]`).
+///
+/// Endpoints are discovered from the server's metadata. Without a
+/// `client_id` the client registers itself dynamically.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpOAuthConfig {
+    /// Pre-registered client id; skips dynamic client registration.
+    #[serde(default)]
+    pub client_id: Option<String>,
+    /// Secret for a confidential pre-registered client.
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Scopes to request.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Fixed port for the loopback redirect (default: any free port).
+    #[serde(default)]
+    pub redirect_port: Option<u16>,
+}
+
+/// TLS settings for a remote MCP server (`[mcp.servers.<name>.tls]`),
+/// e.g. a gateway behind an internal CA that requires client
+/// certificates. Paths may start with `~`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct McpTlsConfig {
+    /// PEM file with CA certificates to trust in addition to the system
+    /// roots.
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    /// PEM file with the client certificate (chain) for mutual TLS.
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    /// PEM file with the private key of `client_cert`.
+    #[serde(default)]
+    pub client_key: Option<String>,
+    /// Accept any server certificate, including self-signed and expired
+    /// ones. Only for testing: it makes the connection open to
+    /// interception.
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+}
+
+impl McpTlsConfig {
+    /// `builder` set up with these certificates and checks.
+    pub fn apply(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        let read = |key: &str, path: &str| {
+            let path = shellexpand::tilde(path).into_owned();
+            std::fs::read(&path).with_context(|| format!("tls.{key}: cannot read {path}"))
+        };
+        let mut builder = builder.danger_accept_invalid_certs(self.danger_accept_invalid_certs);
+        if let Some(path) = &self.ca_cert {
+            for cert in reqwest::Certificate::from_pem_bundle(&read("ca_cert", path)?)
+                .context("tls.ca_cert: invalid PEM certificate")?
+            {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let mut pem = read("client_cert", cert)?;
+                pem.push(b'\n');
+                pem.extend(read("client_key", key)?);
+                let identity = reqwest::Identity::from_pem(&pem)
+                    .context("tls: invalid client certificate or key")?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => anyhow::bail!("tls: client_cert and client_key must be set together"),
+        }
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn env_vars_and_commands_are_expanded() {
+        std::env::set_var("ZC_MCP_EXPAND_TEST_KEY", "s3cret");
+        let mut config = McpServerConfig {
+            url: Some("https://mcp.example.com/${ZC_MCP_EXPAND_TEST_KEY}".into()),
+            args: vec!["--token=$(echo tok; echo) $5".into()],
+            headers: HashMap::from([(
+                "Authorization".into(),
+                "Bearer ${ZC_MCP_EXPAND_TEST_KEY}".into(),
+            )]),
+            ..McpServerConfig::default()
+        };
+
+        let expanded = config.expanded().await.unwrap();
+        assert_eq!(
+            expanded.url.as_deref(),
+            Some("https://mcp.example.com/s3cret")
+        );
+        assert_eq!(expanded.headers["Authorization"], "Bearer s3cret");
+        assert_eq!(expanded.args, ["--token=$(echo tok; echo) $5"]);
+
+        config.expand_commands = true;
+        let expanded = config.expanded().await.unwrap();
+        assert_eq!(expanded.args, ["--token=tok $5"]);
+
+        config.env = HashMap::from([("KEY".into(), "${ZC_MCP_EXPAND_TEST_UNSET}".into())]);
+        let err = config.expanded().await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "env.KEY: ${ZC_MCP_EXPAND_TEST_UNSET} is not set in the environment"
+        );
+        config.env.clear();
+        config.args = vec!["$(exit 3)".into()];
+        assert!(config.expanded().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn keyring_entries_name_a_service_and_account() {
+        assert_eq!(
+            keyring_entry("zeroclaw/github"),
+            Some(("zeroclaw", "github"))
+        );
+        assert_eq!(
+            keyring_entry("zeroclaw/org/repo"),
+            Some(("zeroclaw", "org/repo"))
+        );
+        assert_eq!(keyring_entry("github"), None);
+        assert_eq!(keyring_entry("/github"), None);
+
+        let config = McpServerConfig {
+            env_from_keyring: HashMap::from([("GITHUB_TOKEN".into(), "github".into())]),
+            ..McpServerConfig::default()
+        };
+        let err = config.expanded().await.unwrap_err().to_string();
+        assert!(err.starts_with("env_from_keyring.GITHUB_TOKEN: "), "{err}");
+    }
+
+    #[test]
+    fn tls_settings_need_readable_pem_files() {
+        let builder = || reqwest::Client::builder();
+        let insecure = McpTlsConfig {
+            danger_accept_invalid_certs: true,
+            ..McpTlsConfig::default()
+        };
+        assert!(insecure.apply(builder()).unwrap().build().is_ok());
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("ca.pem").display().to_string();
+        let err = McpTlsConfig {
+            ca_cert: Some(missing.clone()),
+            ..McpTlsConfig::default()
+        }
+        .apply(builder())
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("tls.ca_cert: cannot read {missing}")
+        );
+
+        let half = McpTlsConfig {
+            client_cert: Some(missing),
+            ..McpTlsConfig::default()
+        };
+        assert_eq!(
+            half.apply(builder()).unwrap_err().to_string(),
+            "tls: client_cert and client_key must be set together"
+        );
+    }
+
+    #[test]
+    fn docker_servers_run_with_their_container_settings() {
+        let docker = McpDockerConfig {
+            image: "mcp/github".into(),
+            volumes: vec!["/srv/repos:/repos:ro".into()],
+            network: Some("none".into()),
+        };
+        let env = HashMap::from([("GITHUB_TOKEN".to_string(), "ghp_x".to_string())]);
+        assert_eq!(
+            docker.run_args("github", None, &["--read-only".into()], &env),
+            [
+                "run",
+                "-i",
+                "--rm",
+                "--init",
+                "--label=zeroclaw.mcp.server=github",
+                "--network=none",
+                "-v",
+                "/srv/repos:/repos:ro",
+                "-e",
+                "GITHUB_TOKEN",
+                "mcp/github",
+                "--read-only",
+            ]
+        );
     }
 }
@@ -0,0 +1,344 @@
+//! MCP elicitation (`elicitation/create`).
+//!
+//! A server may need more input from the user in the middle of a tool
+//! call, e.g. which of several accounts to use. The tool call waits while
+//! the user is asked field by field in the conversation the call belongs
+//! to: through the turn's [`Asker`] on channels, on stdin in a terminal.
+//! When nobody can answer (daemons, cron jobs) the request is cancelled.
+
+use super::client::ElicitationHandler;
+use super::Asker;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::fmt::Write;
+use std::io::IsTerminal;
+use std::sync::Arc;
+
+/// Answers that decline or cancel the whole request.
+const DECLINE: &str = "/decline";
+const CANCEL: &str = "/cancel";
+/// Answer that skips an optional field.
+const SKIP: &str = "-";
+/// Invalid answers accepted per field before the request is cancelled.
+const MAX_ATTEMPTS: usize = 3;
+
+/// Handler for the elicitation requests of `server`.
+pub fn handler(server: &str) -> ElicitationHandler {
+    let server = server.to_string();
+    Arc::new(move |params| {
+        let server = server.clone();
+        Box::pin(async move { Ok(elicit(&server, &params).await) })
+    })
+}
+
+/// The turn's asker, or stdin when the turn runs in a terminal.
+pub(super) fn asker() -> Option<Asker> {
+    let turn = super::current_turn();
+    if let Some(ask) = turn.ask {
+        return Some(ask);
+    }
+    if turn.notify.is_some() || !std::io::stdin().is_terminal() {
+        return None;
+    }
+    Some(Arc::new(|question: String, options: Vec<String>| {
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                use std::io::{BufRead, Write};
+
+                eprintln!("\n{question}");
+                for (i, option) in options.iter().enumerate() {
+                    eprintln!("   {}. {option}", i + 1);
+                }
+                eprint!("   > ");
+                let _ = std::io::stderr().flush();
+                let mut line = String::new();
+                std::io::stdin().lock().read_line(&mut line).ok()?;
+                Some(line.trim().to_string())
+            })
+            .await
+            .ok()
+            .flatten()
+        })
+    }))
+}
+
+/// What a field of the requested schema takes.
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    /// Allowed values with the labels shown for them.
+    Choice(Vec<(String, String)>),
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    label: String,
+    description: Option<String>,
+    kind: Kind,
+    required: bool,
+}
+
+/// The fields of a `requestedSchema`, required ones first in the order the
+/// server listed them.
+fn fields(schema: &Value) -> Vec<Field> {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut names: Vec<&String> = properties.keys().collect();
+    names.sort_by_key(|name| {
+        required
+            .iter()
+            .position(|r| r == name)
+            .unwrap_or(usize::MAX)
+    });
+    names
+        .into_iter()
+        .map(|name| {
+            let property = &properties[name];
+            let text = |key: &str| property.get(key).and_then(Value::as_str);
+            let kind = match property.get("enum").and_then(Value::as_array) {
+                Some(values) => {
+                    let labels = property.get("enumNames").and_then(Value::as_array);
+                    Kind::Choice(
+                        values
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(i, value)| {
+                                let value = value.as_str()?.to_string();
+                                let label = labels
+                                    .and_then(|l| l.get(i)?.as_str())
+                                    .map_or_else(|| value.clone(), ToOwned::to_owned);
+                                Some((value, label))
+                            })
+                            .collect(),
+                    )
+                }
+                None => match text("type") {
+                    Some("number") => Kind::Number,
+                    Some("integer") => Kind::Integer,
+                    Some("boolean") => Kind::Boolean,
+                    _ => Kind::Text,
+                },
+            };
+            Field {
+                name: name.clone(),
+                label: text("title").unwrap_or(name).to_string(),
+                description: text("description").map(ToOwned::to_owned),
+                kind,
+                required: required.contains(&name.as_str()),
+            }
+        })
+        .collect()
+}
+
+impl Field {
+    /// Question for this field and the answers to offer as buttons.
+    fn question(&self) -> (String, Vec<String>) {
+        let mut question = format!("✏️ {}", self.label);
+        if let Some(description) = &self.description {
+            let _ = write!(question, " — {description}");
+        }
+        if !self.required {
+            let _ = write!(question, " (optional; reply {SKIP} to skip)");
+        }
+        let options = match &self.kind {
+            Kind::Choice(choices) => choices.iter().map(|(_, label)| label.clone()).collect(),
+            Kind::Boolean => vec!["yes".into(), "no".into()],
+            _ => Vec::new(),
+        };
+        (question, options)
+    }
+
+    /// The value of `answer`, or why it does not fit the field.
+    fn parse(&self, answer: &str) -> Result<Value, String> {
+        match &self.kind {
+            Kind::Text if answer.is_empty() => Err("The answer is empty".into()),
+            Kind::Text => Ok(json!(answer)),
+            Kind::Number => answer
+                .parse::<f64>()
+                .map(|n| json!(n))
+                .map_err(|_| format!("'{answer}' is not a number")),
+            Kind::Integer => answer
+                .parse::<i64>()
+                .map(|n| json!(n))
+                .map_err(|_| format!("'{answer}' is not a whole number")),
+            // 1 and 2 are the numbers of the yes and no options.
+            Kind::Boolean => match answer.to_ascii_lowercase().as_str() {
+                "yes" | "y" | "true" | "1" => Ok(json!(true)),
+                "no" | "n" | "false" | "2" => Ok(json!(false)),
+                _ => Err(format!("'{answer}' is neither yes nor no")),
+            },
+            Kind::Choice(choices) => {
+                let picked = answer
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| choices.get(n.checked_sub(1)?))
+                    .or_else(|| {
+                        choices.iter().find(|(value, label)| {
+                            value.eq_ignore_ascii_case(answer) || label.eq_ignore_ascii_case(answer)
+                        })
+                    });
+                picked
+                    .map(|(value, _)| json!(value))
+                    .ok_or_else(|| format!("'{answer}' is not one of the options"))
+            }
+        }
+    }
+}
+
+/// Ask the user for what the server requests and build the
+/// `elicitation/create` result.
+async fn elicit(server: &str, params: &Value) -> Value {
+    let cancel = json!({ "action": "cancel" });
+    let Some(ask) = asker() else {
+        tracing::warn!(
+            server = %server,
+            "MCP server asked for user input but nobody can answer; cancelled"
+        );
+        return cancel;
+    };
+    let message = params
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let fields = fields(params.get("requestedSchema").unwrap_or(&Value::Null));
+    let mut intro = Some(format!(
+        "❓ MCP server '{server}' asks: {message}\n(Reply {DECLINE} to refuse or {CANCEL} to stop.)\n"
+    ));
+
+    if fields.is_empty() {
+        let question = intro.take().unwrap_or_default();
+        return match ask(question, vec!["accept".into(), "decline".into()]).await {
+            Some(answer) if matches!(answer.trim(), "accept" | "1" | "yes" | "y") => {
+                json!({ "action": "accept", "content": {} })
+            }
+            Some(answer) if answer.trim() != CANCEL => json!({ "action": "decline" }),
+            _ => cancel,
+        };
+    }
+
+    let mut content = Map::new();
+    for field in &fields {
+        let (question, options) = field.question();
+        let mut problem = None;
+        let mut value = None;
+        for _ in 0..MAX_ATTEMPTS {
+            let text = match (intro.take(), problem.take()) {
+                (Some(intro), _) => format!("{intro}{question}"),
+                (None, Some(problem)) => format!("⚠️ {problem}.\n{question}"),
+                (None, None) => question.clone(),
+            };
+            let Some(answer) = ask(text, options.clone()).await else {
+                return cancel;
+            };
+            let answer = answer.trim();
+            match answer {
+                CANCEL => return cancel,
+                DECLINE => return json!({ "action": "decline" }),
+                SKIP if !field.required => break,
+                _ => match field.parse(answer) {
+                    Ok(parsed) => {
+                        value = Some(parsed);
+                        break;
+                    }
+                    Err(e) => problem = Some(e),
+                },
+            }
+        }
+        match value {
+            Some(value) => {
+                content.insert(field.name.clone(), value);
+            }
+            None if problem.is_some() => return cancel,
+            None => {}
+        }
+    }
+    json!({ "action": "accept", "content": content })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Answers with `answers` in order and records the questions.
+    fn scripted(answers: &[&str]) -> (Asker, Arc<Mutex<Vec<(String, Vec<String>)>>>) {
+        let answers: Arc<Mutex<VecDeque<String>>> =
+            Arc::new(Mutex::new(answers.iter().map(|a| (*a).into()).collect()));
+        let asked = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&asked);
+        let ask: Asker = Arc::new(move |question, options| {
+            record.lock().unwrap().push((question, options));
+            let answer = answers.lock().unwrap().pop_front();
+            Box::pin(async move { answer })
+        });
+        (ask, asked)
+    }
+
+    async fn run(answers: &[&str], params: Value) -> (Value, Vec<(String, Vec<String>)>) {
+        let (ask, asked) = scripted(answers);
+        let turn = super::super::Turn {
+            ask: Some(ask),
+            ..super::super::Turn::default()
+        };
+        let result = super::super::scope(turn, elicit("github", &params)).await;
+        let asked = asked.lock().unwrap().clone();
+        (result, asked)
+    }
+
+    fn params() -> Value {
+        json!({
+            "message": "Which repository should I use?",
+            "requestedSchema": {
+                "type": "object",
+                "properties": {
+                    "private": { "type": "boolean", "title": "Private" },
+                    "repo": { "type": "string", "enum": ["web", "api"], "enumNames": ["Website", "API server"] },
+                    "stars": { "type": "integer", "title": "Minimum stars" },
+                },
+                "required": ["repo", "private"],
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn fields_are_asked_one_by_one() {
+        let (result, asked) = run(&["2", "maybe", "yes", "-"], params()).await;
+        assert_eq!(
+            result,
+            json!({ "action": "accept", "content": { "repo": "api", "private": true } })
+        );
+        assert_eq!(asked.len(), 4);
+        assert!(asked[0]
+            .0
+            .starts_with("❓ MCP server 'github' asks: Which repository should I use?"));
+        assert_eq!(asked[0].1, ["Website", "API server"]);
+        assert!(asked[2].0.starts_with("⚠️ 'maybe' is neither yes nor no."));
+        assert_eq!(asked[2].1, ["yes", "no"]);
+        assert!(asked[3]
+            .0
+            .contains("Minimum stars (optional; reply - to skip)"));
+    }
+
+    #[tokio::test]
+    async fn the_user_can_decline_or_cancel() {
+        let (result, _) = run(&["website", "/decline"], params()).await;
+        assert_eq!(result, json!({ "action": "decline" }));
+        let (result, asked) = run(&[], params()).await;
+        assert_eq!(result, json!({ "action": "cancel" }));
+        assert_eq!(asked.len(), 1);
+    }
+}
@@ -0,0 +1,216 @@
+//! `zeroclaw mcp import` — MCP servers from the config files of other MCP
+//! hosts.
+//!
+//! Claude Desktop and Cursor list servers under `mcpServers`, VS Code under
+//! `servers` (or `mcp.servers` in `settings.json`). Each entry has either a
+//! `command` with `args` and `env`, or a `url` with `headers`.
+
+use super::config::McpServerConfig;
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Hosts whose config can be imported, as named on the command line.
+pub const SOURCES: [&str; 3] = ["claude-desktop", "cursor", "vscode"];
+
+/// Where `source` keeps its MCP servers by default.
+pub fn default_path(source: &str) -> Result<PathBuf> {
+    let dirs = directories::BaseDirs::new().context("Could not find the home directory")?;
+    Ok(match source {
+        "claude-desktop" => dirs
+            .config_dir()
+            .join("Claude")
+            .join("claude_desktop_config.json"),
+        "cursor" => dirs.home_dir().join(".cursor").join("mcp.json"),
+        "vscode" => PathBuf::from(".vscode").join("mcp.json"),
+        other => bail!(
+            "Unknown source \"{other}\" (expected one of {})",
+            SOURCES.join(", ")
+        ),
+    })
+}
+
+/// The servers defined in the file at `path`, sorted by name.
+pub fn read(path: &Path) -> Result<Vec<(String, McpServerConfig)>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&text).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// The servers of an MCP host config file, sorted by name. Comments are
+/// allowed, as VS Code writes them.
+pub fn parse(text: &str) -> Result<Vec<(String, McpServerConfig)>> {
+    let root: Value = serde_json::from_str(&strip_comments(text))?;
+    let servers = root
+        .get("mcpServers")
+        .or_else(|| root.get("servers"))
+        .or_else(|| root.get("mcp")?.get("servers"))
+        .and_then(Value::as_object)
+        .context("No mcpServers or servers object found")?;
+
+    let mut imported = Vec::new();
+    for (name, entry) in servers {
+        if entry.get("disabled").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        imported.push((
+            name.clone(),
+            server(entry).with_context(|| format!("Server \"{name}\""))?,
+        ));
+    }
+    imported.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(imported)
+}
+
+fn server(entry: &Value) -> Result<McpServerConfig> {
+    let text = |key: &str| entry.get(key).and_then(Value::as_str).map(env_refs);
+    let map = |key: &str| -> HashMap<String, String> {
+        entry
+            .get(key)
+            .and_then(Value::as_object)
+            .map(|values| {
+                values
+                    .iter()
+                    .filter_map(|(k, v)| Some((k.clone(), env_refs(v.as_str()?))))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let url = text("url").or_else(|| text("serverUrl"));
+    let transport = match entry.get("type").and_then(Value::as_str) {
+        Some("sse") => "sse",
+        Some("http" | "streamable-http" | "streamableHttp") => "http",
+        Some("stdio") => "stdio",
+        // Hosts that infer the transport connect to `.../sse` URLs over SSE.
+        None => match &url {
+            None => "stdio",
+            Some(url) if url.trim_end_matches('/').ends_with("/sse") => "sse",
+            Some(_) => "http",
+        },
+        Some(other) => bail!("unsupported type \"{other}\""),
+    };
+    if transport == "stdio" && entry.get("command").is_none() {
+        bail!("has neither a command nor a url");
+    }
+    Ok(McpServerConfig {
+        transport: transport.into(),
+        command: text("command"),
+        args: entry
+            .get("args")
+            .and_then(Value::as_array)
+            .map(|args| {
+                args.iter()
+                    .filter_map(Value::as_str)
+                    .map(env_refs)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        env: map("env"),
+        url: url.filter(|_| transport != "stdio"),
+        headers: map("headers"),
+        ..McpServerConfig::default()
+    })
+}
+
+/// VS Code's `${env:VAR}` as `${VAR}`, which zeroclaw expands when the
+/// server connects.
+fn env_refs(value: &str) -> String {
+    value.replace("${env:", "${")
+}
+
+/// `text` without `//` and `/* */` comments outside of strings.
+fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_configs_become_server_configs() {
+        let claude = r#"{
+            "mcpServers": {
+                "github": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-github"],
+                    "env": { "GITHUB_TOKEN": "ghp_x" }
+                },
+                "old": { "command": "old-server", "disabled": true },
+                "linear": { "url": "https://mcp.linear.app/sse" }
+            }
+        }"#;
+        let servers = parse(claude).unwrap();
+        let names: Vec<&str> = servers.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["github", "linear"]);
+        let github = &servers[0].1;
+        assert_eq!(github.transport, "stdio");
+        assert_eq!(github.command.as_deref(), Some("npx"));
+        assert_eq!(github.args, ["-y", "@modelcontextprotocol/server-github"]);
+        assert_eq!(github.env["GITHUB_TOKEN"], "ghp_x");
+        assert_eq!(servers[1].1.transport, "sse");
+
+        let vscode = r#"{
+            // Workspace servers
+            "servers": {
+                "tracker": {
+                    "type": "http",
+                    "url": "https://tracker.example.com/mcp", /* hosted */
+                    "headers": { "Authorization": "Bearer ${env:TRACKER_TOKEN}" }
+                }
+            }
+        }"#;
+        let servers = parse(vscode).unwrap();
+        let tracker = &servers[0].1;
+        assert_eq!(tracker.transport, "http");
+        assert_eq!(
+            tracker.url.as_deref(),
+            Some("https://tracker.example.com/mcp")
+        );
+        assert_eq!(tracker.headers["Authorization"], "Bearer ${TRACKER_TOKEN}");
+
+        assert!(parse(r#"{ "servers": { "x": { "type": "ws" } } }"#).is_err());
+        assert!(parse("{}").is_err());
+    }
+}
@@ -0,0 +1,134 @@
+//! Prometheus metrics of MCP tool calls, served by the gateway at
+//! `GET /metrics`.
+//!
+//! - `zeroclaw_mcp_calls_total{server,tool}`: tool calls made.
+//! - `zeroclaw_mcp_call_errors_total{server,tool}`: calls that failed or
+//!   returned an error result.
+//! - `zeroclaw_mcp_call_duration_seconds{server,tool}`: time from sending a
+//!   call to its result, without the wait for a concurrency or rate limit.
+//! - `zeroclaw_mcp_restarts_total{server,reason}`: servers restarted after a
+//!   crash (`crash`) or reconnected after a failed health check
+//!   (`health_check`).
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Latency buckets in seconds, from quick lookups to long batch tools.
+const DURATION_BUCKETS: [f64; 12] = [
+    0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 120.0,
+];
+
+struct Metrics {
+    registry: Registry,
+    calls: IntCounterVec,
+    errors: IntCounterVec,
+    duration: HistogramVec,
+    restarts: IntCounterVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| {
+    let calls = IntCounterVec::new(
+        Opts::new("zeroclaw_mcp_calls_total", "MCP tool calls made"),
+        &["server", "tool"],
+    )
+    .expect("valid metric");
+    let errors = IntCounterVec::new(
+        Opts::new(
+            "zeroclaw_mcp_call_errors_total",
+            "MCP tool calls that failed or returned an error result",
+        ),
+        &["server", "tool"],
+    )
+    .expect("valid metric");
+    let duration = HistogramVec::new(
+        HistogramOpts::new(
+            "zeroclaw_mcp_call_duration_seconds",
+            "Time from sending an MCP tool call to its result",
+        )
+        .buckets(DURATION_BUCKETS.to_vec()),
+        &["server", "tool"],
+    )
+    .expect("valid metric");
+    let restarts = IntCounterVec::new(
+        Opts::new(
+            "zeroclaw_mcp_restarts_total",
+            "MCP servers restarted after a crash or a failed health check",
+        ),
+        &["server", "reason"],
+    )
+    .expect("valid metric");
+
+    let registry = Registry::new();
+    for metric in [
+        Box::new(calls.clone()) as Box<dyn prometheus::core::Collector>,
+        Box::new(errors.clone()),
+        Box::new(duration.clone()),
+        Box::new(restarts.clone()),
+    ] {
+        registry.register(metric).expect("metric registered once");
+    }
+    Metrics {
+        registry,
+        calls,
+        errors,
+        duration,
+        restarts,
+    }
+});
+
+/// Count a finished call to `tool` on `server`.
+pub fn record_call(server: &str, tool: &str, elapsed: Duration, failed: bool) {
+    let labels = [server, tool];
+    METRICS.calls.with_label_values(&labels).inc();
+    if failed {
+        METRICS.errors.with_label_values(&labels).inc();
+    }
+    METRICS
+        .duration
+        .with_label_values(&labels)
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Count a restart of `server`; `reason` is `crash` or `health_check`.
+pub fn record_restart(server: &str, reason: &str) {
+    METRICS.restarts.with_label_values(&[server, reason]).inc();
+}
+
+/// All MCP metrics in the Prometheus text format.
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::warn!(error = %e, "Failed to encode MCP metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// Content type of [`render`]'s output.
+pub fn content_type() -> String {
+    TextEncoder::new().format_type().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calls_and_restarts_are_rendered_with_their_labels() {
+        record_call("metrics-test", "search", Duration::from_millis(30), false);
+        record_call("metrics-test", "search", Duration::from_secs(3), true);
+        record_restart("metrics-test", "crash");
+        let text = render();
+        assert!(text.contains(r#"zeroclaw_mcp_calls_total{server="metrics-test",tool="search"} 2"#));
+        assert!(text
+            .contains(r#"zeroclaw_mcp_call_errors_total{server="metrics-test",tool="search"} 1"#));
+        assert!(text.contains(
+            r#"zeroclaw_mcp_call_duration_seconds_bucket{server="metrics-test",tool="search",le="0.05"} 1"#
+        ));
+        assert!(
+            text.contains(r#"zeroclaw_mcp_restarts_total{reason="crash",server="metrics-test"} 1"#)
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Registry names of bridged MCP tools.
+//!
+//! A server's tools are named `mcp__<server>__<tool>` unless the server
+//! sets a `name_prefix`, which replaces `mcp__<server>__`. Names longer
+//! than providers accept are shortened and given a hash of the server and
+//! tool, so two long names never end up the same. [`origin`] maps any name
+//! handed out here back to its server and tool.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, PoisonError, RwLock};
+
+/// Longest tool name some providers accept.
+pub const MAX_NAME_LEN: usize = 64;
+
+#[derive(Default)]
+struct Registry {
+    /// Server → the prefix of its tool names, for servers with a
+    /// `name_prefix`.
+    prefixes: HashMap<String, String>,
+    /// Name → (server, tool), for names that cannot be parsed back.
+    origins: HashMap<String, (String, String)>,
+}
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(RwLock::default);
+
+/// Name the tools of `server` with `prefix` instead of `mcp__<server>__`,
+/// or go back to the default with `None`.
+pub fn set_prefix(server: &str, prefix: Option<&str>) {
+    let mut registry = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+    match prefix {
+        Some(prefix) => registry.prefixes.insert(server.into(), prefix.into()),
+        None => registry.prefixes.remove(server),
+    };
+}
+
+/// Registry name of MCP tool `tool` on `server`.
+pub fn qualified_name(server: &str, tool: &str) -> String {
+    let mut registry = REGISTRY.write().unwrap_or_else(PoisonError::into_inner);
+    let name = match registry.prefixes.get(server) {
+        Some(prefix) => format!("{prefix}{tool}"),
+        None => format!("mcp__{server}__{tool}"),
+    };
+    let origin = (server.to_string(), tool.to_string());
+    if name.len() <= MAX_NAME_LEN && !registry.prefixes.contains_key(server) {
+        return name;
+    }
+    let hash = fnv1a(&format!("{server}\0{tool}"));
+    // The longer hash only comes into play if the short one collides.
+    let candidates = [format!("_{:08x}", hash >> 32), format!("_{hash:016x}")];
+    let mut short = name.clone();
+    for suffix in candidates {
+        if name.len() > MAX_NAME_LEN {
+            short = truncate(&name, MAX_NAME_LEN - suffix.len()) + &suffix;
+        }
+        match registry.origins.get(&short) {
+            Some(taken) if *taken != origin => {}
+            _ => break,
+        }
+    }
+    if let Some(taken) = registry.origins.get(&short).filter(|t| **t != origin) {
+        tracing::warn!(
+            name = %short,
+            server = %server,
+            tool = %tool,
+            "MCP tool name is already used by tool '{}' of server '{}'",
+            taken.1,
+            taken.0
+        );
+    }
+    registry.origins.insert(short.clone(), origin);
+    short
+}
+
+/// Server and tool a registry name stands for, `None` for tools that are
+/// not bridged from MCP.
+pub fn origin(name: &str) -> Option<(String, String)> {
+    let registry = REGISTRY.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(origin) = registry.origins.get(name) {
+        return Some(origin.clone());
+    }
+    let (server, tool) = name.strip_prefix("mcp__")?.split_once("__")?;
+    // A server with a prefix does not own the default names of its tools.
+    if registry.prefixes.contains_key(server) {
+        return None;
+    }
+    Some((server.to_string(), tool.to_string()))
+}
+
+/// The first `len` bytes of `name`, cut back to a character boundary.
+fn truncate(name: &str, len: usize) -> String {
+    let mut end = len;
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    name[..end].to_string()
+}
+
+/// FNV-1a: stable across runs and Rust versions, so a shortened name stays
+/// the same in conversation histories.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_names_are_shortened_and_map_back() {
+        let server = "github-enterprise-server";
+        let name = qualified_name(server, "create_pull_request_review_comment");
+        assert_eq!(name.len(), MAX_NAME_LEN);
+        assert!(name.starts_with("mcp__github-enterprise-server__create_pull_request_r"));
+        assert_eq!(
+            origin(&name),
+            Some((server.into(), "create_pull_request_review_comment".into()))
+        );
+        let other = qualified_name(server, "create_pull_request_review_comment_reply");
+        assert_ne!(name, other);
+        assert_eq!(
+            qualified_name(server, "create_pull_request_review_comment"),
+            name
+        );
+
+        assert_eq!(qualified_name("gh", "search"), "mcp__gh__search");
+        assert_eq!(
+            origin("mcp__gh__search"),
+            Some(("gh".into(), "search".into()))
+        );
+        assert_eq!(origin("shell"), None);
+
+        set_prefix("names-test-ghe", Some("ghe_"));
+        assert_eq!(qualified_name("names-test-ghe", "search"), "ghe_search");
+        assert_eq!(
+            origin("ghe_search"),
+            Some(("names-test-ghe".into(), "search".into()))
+        );
+        assert_eq!(origin("mcp__names-test-ghe__search"), None);
+        set_prefix("names-test-ghe", None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Limits on the size of MCP tool output.
+//!
+//! Output over a server's `max_output_bytes` is shortened before it reaches
+//! the model: cut off after the limit (`truncate`), cut in the middle
+//! (`head_tail`), or saved whole to a file in the system temp directory
+//! (`spill`), of which the model is shown the start and told how to read
+//! the rest with the server's `read_output` tool.
+
+use super::config::McpOutputStrategy;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Directory spilled output is saved to.
+pub fn spill_dir() -> PathBuf {
+    std::env::temp_dir().join("zeroclaw-mcp-output")
+}
+
+/// `text` shortened to about `max` bytes with `strategy`, with a note
+/// saying what was left out. `read_tool` is the name of the tool that
+/// reads spilled output in chunks.
+pub fn limit(text: String, max: usize, strategy: McpOutputStrategy, read_tool: &str) -> String {
+    let len = text.len();
+    if len <= max {
+        return text;
+    }
+    match strategy {
+        McpOutputStrategy::Truncate => truncate(&text, max),
+        McpOutputStrategy::HeadTail => head_tail(&text, max),
+        McpOutputStrategy::Spill => match spill(&text, &spill_dir()) {
+            Ok(path) => {
+                let head = &text[..floor_boundary(&text, max)];
+                format!(
+                    "{head}\n\n[Output is {len} bytes, over the limit of {max}; the first {} bytes \
+                     are shown above. The full output is saved to {}: read the rest with \
+                     `{read_tool}`, starting at offset {}.]",
+                    head.len(),
+                    path.display(),
+                    head.len()
+                )
+            }
+            Err(e) => {
+                tracing::warn!("Failed to save MCP tool output: {e:#}");
+                head_tail(&text, max)
+            }
+        },
+    }
+}
+
+/// The first `max` bytes of `text`.
+fn truncate(text: &str, max: usize) -> String {
+    let head = &text[..floor_boundary(text, max)];
+    format!(
+        "{head}\n\n[Output truncated: showing the first {} of {} bytes]",
+        head.len(),
+        text.len()
+    )
+}
+
+/// The first and last `max / 2` bytes of `text`.
+fn head_tail(text: &str, max: usize) -> String {
+    let head = &text[..floor_boundary(text, max / 2)];
+    let tail = &text[ceil_boundary(text, text.len() - (max - max / 2))..];
+    format!(
+        "{head}\n\n[... {} bytes omitted ...]\n\n{tail}",
+        text.len() - head.len() - tail.len()
+    )
+}
+
+/// Save `text` to a new file in `dir` and return its path.
+fn spill(text: &str, dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{}.txt", uuid::Uuid::new_v4()));
+    std::fs::write(&path, text).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Up to `length` bytes of the spilled output at `path` from byte `offset`,
+/// moved to character boundaries, with a note of the range and what
+/// remains. Only files in [`spill_dir`] can be read.
+pub fn read_chunk(path: &str, offset: usize, length: usize) -> Result<String> {
+    read_chunk_in(&spill_dir(), path, offset, length)
+}
+
+fn read_chunk_in(dir: &Path, path: &str, offset: usize, length: usize) -> Result<String> {
+    let file = Path::new(path)
+        .canonicalize()
+        .with_context(|| format!("No saved output at {path}"))?;
+    let dir = dir.canonicalize().context("No saved output")?;
+    if file.parent() != Some(dir.as_path()) {
+        bail!("{path} is not saved MCP tool output");
+    }
+    let text = std::fs::read_to_string(&file).with_context(|| format!("Failed to read {path}"))?;
+    if offset >= text.len() {
+        bail!(
+            "Offset {offset} is past the end of the output ({} bytes)",
+            text.len()
+        );
+    }
+    let start = floor_boundary(&text, offset);
+    let end = floor_boundary(&text, start.saturating_add(length.max(1)));
+    // A single character longer than `length` is still returned whole.
+    let end = if end > start {
+        end
+    } else {
+        ceil_boundary(&text, start + 1)
+    };
+    let note = if end < text.len() {
+        format!(
+            "[bytes {start}..{end} of {}; continue at offset {end}]",
+            text.len()
+        )
+    } else {
+        format!("[bytes {start}..{end} of {}; end of output]", text.len())
+    };
+    Ok(format!("{}\n\n{note}", &text[start..end]))
+}
+
+/// The largest character boundary of `text` at or before `index`.
+fn floor_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// The smallest character boundary of `text` at or after `index`.
+fn ceil_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_output_is_shortened_with_each_strategy() {
+        let text = "é".repeat(50);
+        assert_eq!(
+            limit(text.clone(), 100, McpOutputStrategy::Truncate, "read"),
+            text
+        );
+        assert_eq!(
+            limit(text.clone(), 9, McpOutputStrategy::Truncate, "read"),
+            "éééé\n\n[Output truncated: showing the first 8 of 100 bytes]"
+        );
+        assert_eq!(
+            limit(text.clone(), 9, McpOutputStrategy::HeadTail, "read"),
+            "éé\n\n[... 92 bytes omitted ...]\n\néé"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = spill(&text, dir.path()).unwrap();
+        let path = path.to_str().unwrap();
+        assert_eq!(
+            read_chunk_in(dir.path(), path, 0, 5).unwrap(),
+            "éé\n\n[bytes 0..4 of 100; continue at offset 4]"
+        );
+        assert_eq!(
+            read_chunk_in(dir.path(), path, 97, 10).unwrap(),
+            "éé\n\n[bytes 96..100 of 100; end of output]"
+        );
+        assert!(read_chunk_in(dir.path(), path, 100, 10).is_err());
+
+        let elsewhere = tempfile::NamedTempFile::new().unwrap();
+        let err = read_chunk_in(dir.path(), elsewhere.path().to_str().unwrap(), 0, 10)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is not saved MCP tool output"), "{err}");
+    }
+}
@@ -0,0 +1,178 @@
+//! Named groups of MCP servers (`[mcp.profiles.<name>]`) picked per
+//! conversation.
+//!
+//! A conversation uses the profile it picked with `/profile <name>`, or
+//! else the profile that lists its channel. A conversation without a
+//! profile gets every server's tools, as does one that picked
+//! `/profile all`.
+
+use super::config::McpProfileConfig;
+use crate::tools::Tool;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+/// Argument of `/profile` that turns every server on.
+pub const ALL: &str = "all";
+
+/// Profiles by name, as in `[mcp.profiles]`.
+pub type Profiles = HashMap<String, McpProfileConfig>;
+
+/// The profile each conversation picked, by channel and sender; `None` for
+/// all servers.
+static PICKED: LazyLock<Mutex<Picks>> = LazyLock::new(Mutex::default);
+
+type Picks = HashMap<(String, String), Option<String>>;
+
+/// The profile of the conversation with `sender` on `channel`, `None` when
+/// it gets every server's tools.
+pub fn active(profiles: &Profiles, channel: &str, sender: &str) -> Option<String> {
+    let key = (channel.to_string(), sender.to_string());
+    if let Some(picked) = PICKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+    {
+        return picked.clone().filter(|name| profiles.contains_key(name));
+    }
+    channel_default(profiles, channel).map(ToOwned::to_owned)
+}
+
+/// The profile that lists `channel`; the first by name if several do.
+fn channel_default<'a>(profiles: &'a Profiles, channel: &str) -> Option<&'a str> {
+    profiles
+        .iter()
+        .filter(|(_, profile)| profile.channels.iter().any(|c| c == channel))
+        .map(|(name, _)| name.as_str())
+        .min()
+}
+
+/// Whether the tools of `server` are registered under `profile`.
+pub fn allows(profiles: &Profiles, profile: Option<&str>, server: &str) -> bool {
+    profile
+        .and_then(|name| profiles.get(name))
+        .is_none_or(|profile| profile.servers.iter().any(|s| s == server))
+}
+
+/// Answer `/profile [name]` from the conversation with `sender` on
+/// `channel`: with a name, switch the conversation to that profile (or to
+/// every server with `all`); without, describe the current one.
+pub fn command(profiles: &Profiles, channel: &str, sender: &str, arg: &str) -> String {
+    let mut names: Vec<&str> = profiles.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    if names.is_empty() {
+        return "No MCP profiles are configured ([mcp.profiles]).".into();
+    }
+    let usage = format!(
+        "Profiles: {}. Use /profile <name>, or /profile {ALL} for every server.",
+        names.join(", ")
+    );
+    let arg = arg.trim();
+    if arg.is_empty() {
+        return match active(profiles, channel, sender) {
+            Some(name) => format!("MCP profile: {}\n{usage}", describe(profiles, &name)),
+            None => format!("MCP profile: none (every server)\n{usage}"),
+        };
+    }
+    let picked = if arg == ALL {
+        None
+    } else if profiles.contains_key(arg) {
+        Some(arg.to_string())
+    } else {
+        return format!("No MCP profile named '{arg}'. {usage}");
+    };
+    let reply = match &picked {
+        Some(name) => format!("MCP profile set to {}.", describe(profiles, name)),
+        None => "Every MCP server's tools are on.".into(),
+    };
+    PICKED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert((channel.to_string(), sender.to_string()), picked);
+    reply
+}
+
+/// `name (servers: a, b)`.
+fn describe(profiles: &Profiles, name: &str) -> String {
+    let servers = profiles
+        .get(name)
+        .map(|profile| profile.servers.join(", "))
+        .unwrap_or_default();
+    format!("{name} (servers: {servers})")
+}
+
+/// Move bridged tools between `registry` and `parked` so `registry` holds
+/// only the MCP tools `profile` allows. Other tools stay registered.
+pub fn sort_tools(
+    profiles: &Profiles,
+    profile: Option<&str>,
+    registry: &mut Vec<Box<dyn Tool>>,
+    parked: &mut Vec<Box<dyn Tool>>,
+) {
+    registry.append(parked);
+    let (kept, left_out) = registry.drain(..).partition(|tool| {
+        super::names::origin(tool.name())
+            .is_none_or(|(server, _)| allows(profiles, profile, &server))
+    });
+    *registry = kept;
+    *parked = left_out;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profiles() -> Profiles {
+        HashMap::from([
+            (
+                "coding".to_string(),
+                McpProfileConfig {
+                    servers: vec!["github".into(), "filesystem".into()],
+                    channels: vec!["cli".into()],
+                },
+            ),
+            (
+                "research".to_string(),
+                McpProfileConfig {
+                    servers: vec!["web".into()],
+                    channels: vec!["discord".into()],
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn conversations_use_their_channel_profile_until_they_pick_another() {
+        let profiles = profiles();
+        assert_eq!(
+            active(&profiles, "cli", "profiles-test").as_deref(),
+            Some("coding")
+        );
+        assert_eq!(active(&profiles, "telegram", "profiles-test"), None);
+        assert!(allows(&profiles, Some("coding"), "github"));
+        assert!(!allows(&profiles, Some("coding"), "web"));
+        assert!(allows(&profiles, None, "web"));
+
+        assert_eq!(
+            command(&profiles, "cli", "profiles-test", "research"),
+            "MCP profile set to research (servers: web)."
+        );
+        assert_eq!(
+            active(&profiles, "cli", "profiles-test").as_deref(),
+            Some("research")
+        );
+        assert_eq!(
+            active(&profiles, "cli", "someone-else").as_deref(),
+            Some("coding")
+        );
+        assert!(command(&profiles, "cli", "profiles-test", "nope")
+            .starts_with("No MCP profile named 'nope'. Profiles: coding, research."));
+
+        command(&profiles, "cli", "profiles-test", ALL);
+        assert_eq!(active(&profiles, "cli", "profiles-test"), None);
+        assert_eq!(
+            command(&profiles, "cli", "profiles-test", ""),
+            "MCP profile: none (every server)\nProfiles: coding, research. \
+             Use /profile <name>, or /profile all for every server."
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 // ── JSON-RPC 2.0 ────────────────────────────────────────────────
 
@@ -23,6 +23,25 @@ impl JsonRpcRequest {
     }
 }
 
+/// A JSON-RPC notification: a message without an id that gets no response.
+#[derive(Debug, Serialize)]
+pub struct JsonRpcNotification {
+    pub jsonrpc: &'static str,
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcNotification {
+    pub fn new(method: impl Into<String>, params: Option<Value>) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            method: method.into(),
+            params,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct JsonRpcResponse {
     #[allow(dead_code)]
@@ -32,13 +51,63 @@ pub struct JsonRpcResponse {
     pub error: Option<JsonRpcError>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcError {
     pub code: i64,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<Value>,
 }
 
+impl JsonRpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// A JSON-RPC message sent to our MCP server (`zeroclaw mcp-serve`): a
+/// request, or a notification when `id` is absent.
+#[derive(Debug, Deserialize)]
+pub struct JsonRpcIncoming {
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Our answer to a request from a server we are connected to, or from a
+/// host of `zeroclaw mcp-serve`. The id is echoed as sent (number or
+/// string).
+#[derive(Debug, Serialize)]
+pub struct JsonRpcReply {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcReply {
+    pub fn new(id: Value, outcome: Result<Value, JsonRpcError>) -> Self {
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
 impl std::fmt::Display for JsonRpcError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "JSON-RPC error {}: {}", self.code, self.message)
@@ -56,6 +125,8 @@ pub struct ServerCapabilities {
     pub resources: Option<Value>,
     #[serde(default)]
     pub prompts: Option<Value>,
+    #[serde(default)]
+    pub completions: Option<Value>,
 }
 
 /// MCP initialize response.
@@ -84,12 +155,53 @@ pub struct McpToolDef {
     pub description: Option<String>,
     #[serde(rename = "inputSchema", default)]
     pub input_schema: Option<Value>,
+    /// JSON Schema of the tool's `structuredContent` (protocol 2025-06-18).
+    #[serde(rename = "outputSchema", default)]
+    pub output_schema: Option<Value>,
+    /// Hints about the tool's behaviour (protocol 2025-03-26).
+    #[serde(default)]
+    pub annotations: Option<McpToolAnnotations>,
+}
+
+/// Tool annotations. They are hints given by the server, not guarantees;
+/// unknown fields are kept so nothing the server sent is lost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The tool does not modify its environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    /// The tool may perform destructive updates; only meaningful when it is
+    /// not read-only. The protocol's default is `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    /// Repeating a call with the same arguments has no further effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    /// The tool interacts with an open world of external entities.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl McpToolAnnotations {
+    pub fn is_read_only(&self) -> bool {
+        self.read_only_hint == Some(true)
+    }
+
+    pub fn is_destructive(&self) -> bool {
+        !self.is_read_only() && self.destructive_hint.unwrap_or(true)
+    }
 }
 
 /// Tool call result content item.
 #[derive(Debug, Deserialize)]
 pub struct McpContent {
-    #[serde(rename = "type")]
+    /// Absent in `resources/read` contents.
+    #[serde(rename = "type", default)]
     pub content_type: String,
     #[serde(default)]
     pub text: Option<String>,
@@ -97,12 +209,18 @@ pub struct McpContent {
     pub data: Option<String>,
     #[serde(rename = "mimeType", default)]
     pub mime_type: Option<String>,
+    /// `{ uri, mimeType, text | blob }` of a `resource` item.
+    #[serde(default)]
+    pub resource: Option<Value>,
 }
 
 /// Result of `tools/call`.
 #[derive(Debug, Deserialize)]
 pub struct ToolCallResult {
     pub content: Vec<McpContent>,
+    /// JSON result of tools with an `outputSchema` (protocol 2025-06-18).
+    #[serde(rename = "structuredContent", default)]
+    pub structured_content: Option<Value>,
     #[serde(rename = "isError", default)]
     pub is_error: bool,
 }
@@ -124,8 +242,150 @@ pub struct ResourcesListResult {
     pub resources: Vec<McpResourceDef>,
 }
 
+/// Parameterized resource from `resources/templates/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResourceTemplate {
+    /// RFC 6570 URI template, e.g. `weather://{city}`.
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", default)]
+    pub mime_type: Option<String>,
+}
+
+/// Result of `resources/templates/list`.
+#[derive(Debug, Deserialize)]
+pub struct ResourceTemplatesListResult {
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<McpResourceTemplate>,
+}
+
 /// Result of `resources/read`.
 #[derive(Debug, Deserialize)]
 pub struct ResourceReadResult {
     pub contents: Vec<McpContent>,
 }
+
+/// Argument accepted by a prompt template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Prompt template from `prompts/list`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptDef {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// Result of `prompts/list`.
+#[derive(Debug, Deserialize)]
+pub struct PromptsListResult {
+    pub prompts: Vec<McpPromptDef>,
+}
+
+/// One message of an expanded prompt.
+#[derive(Debug, Deserialize)]
+pub struct McpPromptMessage {
+    pub role: String,
+    pub content: McpPromptContent,
+}
+
+/// Prompt message content: text, or an embedded resource with text.
+#[derive(Debug, Deserialize)]
+pub struct McpPromptContent {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub resource: Option<Value>,
+}
+
+impl McpPromptContent {
+    /// The text carried by this content, if any (images are skipped).
+    pub fn as_text(&self) -> Option<&str> {
+        self.text
+            .as_deref()
+            .or_else(|| self.resource.as_ref()?.get("text")?.as_str())
+    }
+}
+
+/// Result of `prompts/get`.
+#[derive(Debug, Deserialize)]
+pub struct PromptGetResult {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub messages: Vec<McpPromptMessage>,
+}
+
+/// Suggestions from `completion/complete`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+/// Result of `completion/complete`.
+#[derive(Debug, Deserialize)]
+pub struct CompleteResult {
+    pub completion: Completion,
+}
+
+/// Params of a `sampling/createMessage` request from a server.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageParams {
+    pub messages: Vec<SamplingMessage>,
+    #[serde(default)]
+    pub model_preferences: Option<ModelPreferences>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    pub max_tokens: u32,
+}
+
+/// One message of a sampling request.
+#[derive(Debug, Deserialize)]
+pub struct SamplingMessage {
+    pub role: String,
+    pub content: McpContent,
+}
+
+/// The server's model wishes; hints are model names in order of preference.
+#[derive(Debug, Default, Deserialize)]
+pub struct ModelPreferences {
+    #[serde(default)]
+    pub hints: Vec<ModelHint>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelHint {
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// A directory the server may work in, listed by `roots/list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct McpRoot {
+    /// `file://` URI of the directory.
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
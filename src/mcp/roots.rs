@@ -0,0 +1,95 @@
+//! MCP roots (`roots/list`).
+//!
+//! Tells filesystem-oriented servers which directories zeroclaw works in:
+//! the workspace plus the extra directories in `[mcp] roots`. Every client
+//! watches the same list and sends `notifications/roots/list_changed` when
+//! a config reload moves the workspace or edits `roots`.
+
+use super::protocol::McpRoot;
+use crate::config::Config;
+use std::path::Path;
+use std::sync::LazyLock;
+use tokio::sync::watch;
+
+static ROOTS: LazyLock<watch::Sender<Vec<McpRoot>>> =
+    LazyLock::new(|| watch::channel(Vec::new()).0);
+
+/// Set the roots from `config`. Called at startup and after every config
+/// reload; connected servers are only notified when the list changed.
+pub fn update(config: &Config) {
+    let roots = from_config(config);
+    ROOTS.send_if_modified(|current| {
+        if *current == roots {
+            return false;
+        }
+        *current = roots;
+        true
+    });
+}
+
+/// The current roots, updated whenever [`update`] changes them.
+pub fn subscribe() -> watch::Receiver<Vec<McpRoot>> {
+    ROOTS.subscribe()
+}
+
+/// The workspace directory followed by `[mcp] roots`, which may use `~` and
+/// be relative to the workspace. Duplicates and paths that cannot be turned
+/// into a `file://` URI are skipped.
+pub fn from_config(config: &Config) -> Vec<McpRoot> {
+    let workspace = &config.workspace_dir;
+    let extra = config.mcp.roots.iter().map(|dir| {
+        let dir = workspace.join(shellexpand::tilde(dir).as_ref());
+        let name = dir.file_name().map(|n| n.to_string_lossy().into_owned());
+        (dir, name)
+    });
+
+    let mut roots: Vec<McpRoot> = Vec::new();
+    for (dir, name) in std::iter::once((workspace.clone(), Some("workspace".into()))).chain(extra) {
+        let Some(uri) = file_uri(&dir) else {
+            tracing::warn!(path = %dir.display(), "Skipping MCP root that is not an absolute path");
+            continue;
+        };
+        if roots.iter().all(|root| root.uri != uri) {
+            roots.push(McpRoot { uri, name });
+        }
+    }
+    roots
+}
+
+fn file_uri(dir: &Path) -> Option<String> {
+    reqwest::Url::from_directory_path(dir)
+        .ok()
+        .map(|url| url.as_str().trim_end_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn workspace_comes_first_and_extra_roots_resolve_against_it() {
+        let mut config = Config {
+            workspace_dir: PathBuf::from("/srv/zeroclaw"),
+            ..Config::default()
+        };
+        config.mcp.roots = vec![
+            "projects/web app".into(),
+            "/data/shared".into(),
+            "/srv/zeroclaw".into(),
+        ];
+
+        let roots = from_config(&config);
+        let uris: Vec<&str> = roots.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            [
+                "file:///srv/zeroclaw",
+                "file:///srv/zeroclaw/projects/web%20app",
+                "file:///data/shared",
+            ]
+        );
+        assert_eq!(roots[0].name.as_deref(), Some("workspace"));
+        assert_eq!(roots[1].name.as_deref(), Some("web app"));
+    }
+}
@@ -0,0 +1,300 @@
+//! MCP sampling (`sampling/createMessage`).
+//!
+//! Servers with `sampling = "ask"` or `"auto"` may ask zeroclaw's LLM to
+//! write a message for them, e.g. to summarize a document in the middle of
+//! a tool call. Requests go to the configured provider with the model from
+//! `[mcp.sampling]`: the first server model hint found in `models`, then
+//! `model`, then `default_model`. In `ask` mode the user approves each
+//! request in the terminal; requests nobody can approve (channel turns,
+//! daemons) are rejected.
+
+use super::client::SamplingHandler;
+use super::config::McpSamplingMode;
+use super::protocol::{CreateMessageParams, ModelPreferences};
+use crate::config::Config;
+use crate::providers::{ChatMessage, Provider};
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::io::IsTerminal;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::OnceCell;
+
+static SAMPLER: OnceLock<Arc<Sampler>> = OnceLock::new();
+
+/// Remember the provider settings sampling requests use. Called once at
+/// startup.
+pub fn init(config: &Config) {
+    let _ = SAMPLER.set(Arc::new(Sampler::new(config.clone(), OnceCell::new())));
+}
+
+/// Handler for a server's sampling requests, or `None` when the server has
+/// sampling off or sampling was not set up.
+pub fn handler(server: &str, mode: McpSamplingMode) -> Option<SamplingHandler> {
+    if mode == McpSamplingMode::Off {
+        return None;
+    }
+    Some(Arc::clone(SAMPLER.get()?).handler(server, mode))
+}
+
+/// Answers sampling requests with the configured provider, created on first
+/// use.
+pub struct Sampler {
+    config: Config,
+    provider: OnceCell<Arc<dyn Provider>>,
+}
+
+impl Sampler {
+    fn new(config: Config, provider: OnceCell<Arc<dyn Provider>>) -> Self {
+        Self { config, provider }
+    }
+
+    fn handler(self: Arc<Self>, server: &str, mode: McpSamplingMode) -> SamplingHandler {
+        let server = server.to_string();
+        Arc::new(move |params| {
+            let (sampler, server) = (Arc::clone(&self), server.clone());
+            Box::pin(async move { sampler.create_message(&server, mode, params).await })
+        })
+    }
+
+    async fn create_message(
+        &self,
+        server: &str,
+        mode: McpSamplingMode,
+        params: Value,
+    ) -> Result<Value> {
+        let request: CreateMessageParams =
+            serde_json::from_value(params).context("Invalid sampling/createMessage params")?;
+        let model = self.pick_model(request.model_preferences.as_ref());
+
+        let mut messages = Vec::new();
+        if let Some(system) = request.system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        for message in &request.messages {
+            let Some(text) = message.content.text.as_deref() else {
+                bail!(
+                    "Unsupported sampling content type: {}",
+                    message.content.content_type
+                );
+            };
+            messages.push(match message.role.as_str() {
+                "assistant" => ChatMessage::assistant(text),
+                _ => ChatMessage::user(text),
+            });
+        }
+
+        if mode == McpSamplingMode::Ask && !approve(server, &model, &messages).await {
+            bail!("User rejected sampling request");
+        }
+
+        let provider = self
+            .provider
+            .get_or_try_init(|| async {
+                let provider_name = self
+                    .config
+                    .default_provider
+                    .as_deref()
+                    .unwrap_or("openrouter");
+                crate::providers::create_routed_provider(
+                    provider_name,
+                    self.config.api_key.as_deref(),
+                    self.config.api_url.as_deref(),
+                    &self.config.reliability,
+                    &self.config.model_routes,
+                    &model,
+                )
+                .map(Arc::<dyn Provider>::from)
+            })
+            .await?;
+        let temperature = request
+            .temperature
+            .unwrap_or(self.config.default_temperature);
+        tracing::info!(server = %server, model = %model, "Answering MCP sampling request");
+        let text = provider
+            .chat_with_history(&messages, &model, temperature)
+            .await?;
+
+        Ok(json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": text },
+            "model": model,
+            "stopReason": "endTurn",
+        }))
+    }
+
+    /// The model for a request: the first hint that contains a key of
+    /// `[mcp.sampling.models]` (the longest key if several match), then
+    /// `[mcp.sampling] model`, then `default_model`.
+    fn pick_model(&self, preferences: Option<&ModelPreferences>) -> String {
+        let settings = &self.config.mcp.sampling;
+        let hints = preferences
+            .into_iter()
+            .flat_map(|p| p.hints.iter().filter_map(|hint| hint.name.as_deref()));
+        for hint in hints {
+            let mapped = settings
+                .models
+                .iter()
+                .filter(|(key, _)| hint.contains(key.as_str()))
+                .max_by(|a, b| a.0.len().cmp(&b.0.len()).then_with(|| b.0.cmp(a.0)));
+            if let Some((_, model)) = mapped {
+                return model.clone();
+            }
+        }
+        settings
+            .model
+            .clone()
+            .or_else(|| self.config.default_model.clone())
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4".into())
+    }
+}
+
+/// Ask the user in the terminal whether a server may run `messages`. Fails
+/// closed when nobody is at the terminal or the turn belongs to a channel.
+async fn approve(server: &str, model: &str, messages: &[ChatMessage]) -> bool {
+    if super::notifier().is_some() || !std::io::stdin().is_terminal() {
+        tracing::warn!(
+            server = %server,
+            "MCP sampling request needs approval but nobody can approve it; rejected"
+        );
+        return false;
+    }
+    let preview = messages
+        .iter()
+        .rfind(|m| m.role == "user")
+        .map(|m| truncate_with_ellipsis(&m.content, 200))
+        .unwrap_or_default();
+    let question = format!(
+        "\n🧠 MCP server '{server}' asks the LLM ({model}):\n   {preview}\n   Allow? [y/N]: "
+    );
+    tokio::task::spawn_blocking(move || {
+        use std::io::{BufRead, Write};
+
+        eprint!("{question}");
+        let _ = std::io::stderr().flush();
+        let mut line = String::new();
+        if std::io::stdin().lock().read_line(&mut line).is_err() {
+            return false;
+        }
+        matches!(line.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+    })
+    .await
+    .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Echoes the conversation it was given and records the model.
+    #[derive(Default)]
+    struct EchoProvider {
+        models: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            unreachable!("sampling sends the whole conversation")
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            self.models.lock().unwrap().push(model.to_string());
+            let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+            Ok(roles.join(","))
+        }
+    }
+
+    fn sampler(provider: Arc<EchoProvider>) -> Arc<Sampler> {
+        let mut config = Config {
+            default_model: Some("default-model".into()),
+            ..Config::default()
+        };
+        config
+            .mcp
+            .sampling
+            .models
+            .insert("sonnet".into(), "anthropic/claude-sonnet-4".into());
+        config
+            .mcp
+            .sampling
+            .models
+            .insert("haiku".into(), "anthropic/claude-haiku-4".into());
+        let provider: Arc<dyn Provider> = provider;
+        Arc::new(Sampler::new(config, OnceCell::new_with(Some(provider))))
+    }
+
+    fn request(hints: &[&str]) -> Value {
+        let hints: Vec<Value> = hints.iter().map(|name| json!({ "name": name })).collect();
+        json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize the file" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Which file?" } },
+                { "role": "user", "content": { "type": "text", "text": "README.md" } }
+            ],
+            "modelPreferences": { "hints": hints },
+            "systemPrompt": "Be brief",
+            "maxTokens": 100
+        })
+    }
+
+    #[tokio::test]
+    async fn auto_mode_answers_with_the_mapped_model() {
+        let provider = Arc::new(EchoProvider::default());
+        let handler = sampler(Arc::clone(&provider)).handler("docs", McpSamplingMode::Auto);
+
+        let result = handler(request(&["gpt-4o", "claude-3-5-sonnet"]))
+            .await
+            .unwrap();
+        assert_eq!(result["role"], "assistant");
+        assert_eq!(result["content"]["text"], "system,user,assistant,user");
+        assert_eq!(result["model"], "anthropic/claude-sonnet-4");
+
+        handler(request(&["gpt-4o"])).await.unwrap();
+        assert_eq!(
+            *provider.models.lock().unwrap(),
+            ["anthropic/claude-sonnet-4", "default-model"]
+        );
+    }
+
+    #[tokio::test]
+    async fn ask_mode_rejects_requests_nobody_can_approve() {
+        let provider = Arc::new(EchoProvider::default());
+        let handler = sampler(Arc::clone(&provider)).handler("docs", McpSamplingMode::Ask);
+        let turn = crate::mcp::Turn {
+            notify: Some(Arc::new(|_| {})),
+            ..crate::mcp::Turn::default()
+        };
+
+        let err = crate::mcp::scope(turn, handler(request(&[])))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("rejected"));
+        assert!(provider.models.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn non_text_content_is_refused() {
+        let handler =
+            sampler(Arc::new(EchoProvider::default())).handler("docs", McpSamplingMode::Auto);
+        let params = json!({
+            "messages": [{ "role": "user", "content": { "type": "image", "data": "", "mimeType": "image/png" } }],
+            "maxTokens": 10
+        });
+        let err = handler(params).await.unwrap_err();
+        assert!(err.to_string().contains("image"));
+    }
+}
@@ -0,0 +1,374 @@
+//! zeroclaw in the MCP server role (`zeroclaw mcp-serve`).
+//!
+//! MCP hosts such as Claude Desktop get the built-in tools (`memory_store`,
+//! `memory_recall`, `shell`, ...) as MCP tools and the recorded session
+//! transcripts as `zeroclaw://sessions/<id>` resources, over stdio or
+//! streamable HTTP (`POST /mcp`, one JSON response per request).
+
+use super::client::PROTOCOL_VERSIONS;
+use super::protocol::{JsonRpcError, JsonRpcIncoming, JsonRpcReply, McpResourceDef};
+use crate::config::Config;
+use crate::security::pairing::{is_public_bind, PairingGuard};
+use crate::sessions::export::{self, ExportFormat};
+use crate::tools::Tool;
+use anyhow::{bail, Result};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::post,
+    Router,
+};
+use serde_json::{json, Value};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// URI prefix of session transcript resources.
+const SESSION_URI_PREFIX: &str = "zeroclaw://sessions/";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Answers MCP requests with the tools and transcripts it was built with.
+pub struct McpServer {
+    tools: Vec<Box<dyn Tool>>,
+    workspace_dir: PathBuf,
+}
+
+impl McpServer {
+    pub fn new(tools: Vec<Box<dyn Tool>>, workspace_dir: PathBuf) -> Self {
+        Self {
+            tools,
+            workspace_dir,
+        }
+    }
+
+    /// Answer one JSON-RPC message; notifications get no reply.
+    pub async fn handle(&self, message: &str) -> Option<JsonRpcReply> {
+        let incoming: JsonRpcIncoming = match serde_json::from_str(message) {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                return Some(JsonRpcReply::new(
+                    Value::Null,
+                    Err(JsonRpcError::new(PARSE_ERROR, format!("Parse error: {e}"))),
+                ))
+            }
+        };
+        let id = incoming.id?;
+        let outcome = self.dispatch(&incoming.method, &incoming.params).await;
+        Some(JsonRpcReply::new(id, outcome))
+    }
+
+    async fn dispatch(&self, method: &str, params: &Value) -> Result<Value, JsonRpcError> {
+        match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": negotiate_version(params),
+                "capabilities": { "tools": {}, "resources": {} },
+                "serverInfo": { "name": "zeroclaw", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.list_tools() })),
+            "tools/call" => self.call_tool(params).await,
+            "resources/list" => self.list_resources(),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": [] })),
+            "resources/read" => self.read_resource(params),
+            _ => Err(JsonRpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {method}"),
+            )),
+        }
+    }
+
+    fn list_tools(&self) -> Vec<Value> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.parameters_schema(),
+                })
+            })
+            .collect()
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let name = params["name"].as_str().unwrap_or_default();
+        let Some(tool) = self.tools.iter().find(|t| t.name() == name) else {
+            return Err(JsonRpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown tool: {name}"),
+            ));
+        };
+        let arguments = match &params["arguments"] {
+            Value::Null => json!({}),
+            arguments => arguments.clone(),
+        };
+        let (text, is_error, attachments) = match tool.execute(arguments).await {
+            Ok(result) if result.success => (result.output, false, result.attachments),
+            Ok(result) => (result.error.unwrap_or(result.output), true, Vec::new()),
+            Err(e) => (format!("{e:#}"), true, Vec::new()),
+        };
+        let mut content = vec![json!({ "type": "text", "text": text })];
+        content.extend(attachments.into_iter().map(|a| match a.marker_kind() {
+            "IMAGE" => json!({ "type": "image", "data": a.data, "mimeType": a.mime_type }),
+            "AUDIO" => json!({ "type": "audio", "data": a.data, "mimeType": a.mime_type }),
+            _ => json!({ "type": "resource", "resource": {
+                "uri": a.uri.unwrap_or_else(|| "zeroclaw://attachment".into()),
+                "mimeType": a.mime_type,
+                "blob": a.data,
+            } }),
+        }));
+        Ok(json!({
+            "content": content,
+            "isError": is_error,
+        }))
+    }
+
+    fn list_resources(&self) -> Result<Value, JsonRpcError> {
+        let sessions = crate::sessions::list(&self.workspace_dir)
+            .map_err(|e| JsonRpcError::new(-1, format!("{e:#}")))?;
+        let resources: Vec<McpResourceDef> = sessions
+            .into_iter()
+            .map(|s| McpResourceDef {
+                uri: format!("{SESSION_URI_PREFIX}{}", crate::sessions::file_id(&s.id)),
+                name: format!("Session {}", s.id),
+                description: Some(format!(
+                    "{} messages, last active {}",
+                    s.messages,
+                    s.last_active.format("%Y-%m-%d %H:%M UTC")
+                )),
+                mime_type: Some("text/markdown".into()),
+            })
+            .collect();
+        Ok(json!({ "resources": resources }))
+    }
+
+    fn read_resource(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let uri = params["uri"].as_str().unwrap_or_default();
+        let Some(encoded) = uri.strip_prefix(SESSION_URI_PREFIX) else {
+            return Err(JsonRpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown resource: {uri}"),
+            ));
+        };
+        let session_id = crate::sessions::session_id_of(encoded);
+        let text = crate::sessions::load(&self.workspace_dir, &session_id)
+            .and_then(|transcript| export::render(&transcript, ExportFormat::Markdown))
+            .map_err(|e| JsonRpcError::new(INVALID_PARAMS, format!("{e:#}")))?;
+        Ok(json!({
+            "contents": [{ "uri": uri, "mimeType": "text/markdown", "text": text }],
+        }))
+    }
+}
+
+/// The protocol version the client asked for in `initialize` when we speak
+/// it, otherwise the newest one we do.
+fn negotiate_version(params: &Value) -> &'static str {
+    let requested = params["protocolVersion"].as_str().unwrap_or_default();
+    PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| **v == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0])
+}
+
+/// Run `zeroclaw mcp-serve`: serve the built-in tools (only those named in
+/// `tools`, when given) over stdio, or over HTTP when `http` is set.
+pub async fn run(config: Config, tools: &[String], http: Option<(String, u16)>) -> Result<()> {
+    let mut registry = crate::cron::scheduler::build_tool_registry(&config)?;
+    if !tools.is_empty() {
+        if let Some(unknown) = tools
+            .iter()
+            .find(|name| !registry.iter().any(|t| t.name() == name.as_str()))
+        {
+            bail!("Unknown tool '{unknown}' in --tools");
+        }
+        registry.retain(|t| tools.iter().any(|name| name == t.name()));
+    }
+    let server = Arc::new(McpServer::new(registry, config.workspace_dir.clone()));
+    match http {
+        Some((host, port)) => serve_http(server, &config, &host, port).await,
+        None => serve_stdio(server).await,
+    }
+}
+
+/// Read one JSON-RPC message per line from stdin and write the replies to
+/// stdout, until stdin closes.
+async fn serve_stdio(server: Arc<McpServer>) -> Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = server.handle(&line).await {
+            let mut out = serde_json::to_string(&reply)?;
+            out.push('\n');
+            stdout.write_all(out.as_bytes()).await?;
+            stdout.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct HttpState {
+    server: Arc<McpServer>,
+    pairing: Arc<PairingGuard>,
+}
+
+/// Serve `POST /mcp` on `host:port`, with the gateway's bind and pairing
+/// rules: paired bearer tokens are required unless pairing is disabled.
+async fn serve_http(server: Arc<McpServer>, config: &Config, host: &str, port: u16) -> Result<()> {
+    if is_public_bind(host) && !config.gateway.allow_public_bind {
+        bail!(
+            "🛑 Refusing to bind to {host} — the MCP server would be exposed to the internet.\n\
+             Fix: use --host 127.0.0.1 (default), or set\n\
+             [gateway] allow_public_bind = true in config.toml (NOT recommended)."
+        );
+    }
+    let pairing = Arc::new(PairingGuard::new(
+        config.gateway.require_pairing,
+        &config.gateway.paired_tokens,
+    ));
+    if pairing.require_pairing() && !pairing.is_paired() {
+        bail!("No paired tokens yet: pair a client with the gateway first (POST /pair), or set [gateway] require_pairing = false");
+    }
+
+    let addr: SocketAddr = format!("{host}:{port}").parse()?;
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let actual_port = listener.local_addr()?.port();
+    println!("🦀 ZeroClaw MCP server listening on http://{host}:{actual_port}/mcp");
+    if pairing.require_pairing() {
+        println!("  🔒 Pairing: ACTIVE (bearer token required)");
+    } else {
+        println!("  ⚠️  Pairing: DISABLED (all requests accepted)");
+    }
+    println!("  Press Ctrl+C to stop.\n");
+
+    let app = Router::new()
+        .route("/mcp", post(handle_http))
+        .with_state(HttpState { server, pairing });
+    crate::shutdown::install_signal_handler();
+    let shutdown = crate::shutdown::global();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { shutdown.triggered().await })
+        .await?;
+    Ok(())
+}
+
+/// POST /mcp — one JSON-RPC message in, its reply out (202 for
+/// notifications).
+async fn handle_http(State(state): State<HttpState>, headers: HeaderMap, body: String) -> Response {
+    if state.pairing.require_pairing() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({ "error": "Unauthorized" })),
+            )
+                .into_response();
+        }
+    }
+    match state.server.handle(&body).await {
+        Some(reply) => Json(reply).into_response(),
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::traits::ToolResult;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the text back"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({ "type": "object", "properties": { "text": { "type": "string" } } })
+        }
+
+        async fn execute(&self, args: Value) -> Result<ToolResult> {
+            let text = args["text"].as_str().unwrap_or_default().to_string();
+            Ok(ToolResult {
+                success: !text.is_empty(),
+                output: text,
+                error: Some("nothing to echo".into()),
+                attachments: Vec::new(),
+            })
+        }
+    }
+
+    async fn request(server: &McpServer, method: &str, params: Value) -> Value {
+        let message = json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params });
+        let reply = server.handle(&message.to_string()).await.unwrap();
+        serde_json::to_value(reply).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_tools_and_session_transcripts() {
+        let tmp = TempDir::new().unwrap();
+        crate::sessions::record_turn(tmp.path(), "cli-1", "hi", "hello there", &[]).unwrap();
+        let server = McpServer::new(vec![Box::new(EchoTool)], tmp.path().to_path_buf());
+
+        let init = request(&server, "initialize", json!({})).await;
+        assert_eq!(init["id"], 7);
+        assert_eq!(init["result"]["serverInfo"]["name"], "zeroclaw");
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+        let older = json!({ "protocolVersion": "2024-11-05" });
+        let init = request(&server, "initialize", older).await;
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle(&notification.to_string()).await.is_none());
+
+        let tools = request(&server, "tools/list", json!({})).await;
+        assert_eq!(tools["result"]["tools"][0]["name"], "echo");
+        assert_eq!(tools["result"]["tools"][0]["inputSchema"]["type"], "object");
+
+        let ok = request(
+            &server,
+            "tools/call",
+            json!({ "name": "echo", "arguments": { "text": "ping" } }),
+        )
+        .await;
+        assert_eq!(ok["result"]["content"][0]["text"], "ping");
+        assert_eq!(ok["result"]["isError"], false);
+        let failed = request(&server, "tools/call", json!({ "name": "echo" })).await;
+        assert_eq!(failed["result"]["content"][0]["text"], "nothing to echo");
+        assert_eq!(failed["result"]["isError"], true);
+        let unknown = request(&server, "tools/call", json!({ "name": "nope" })).await;
+        assert_eq!(unknown["error"]["code"], INVALID_PARAMS);
+
+        let resources = request(&server, "resources/list", json!({})).await;
+        let uri = resources["result"]["resources"][0]["uri"].as_str().unwrap();
+        assert_eq!(uri, "zeroclaw://sessions/cli-1");
+        let read = request(&server, "resources/read", json!({ "uri": uri })).await;
+        assert!(read["result"]["contents"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("hello there"));
+
+        let missing = request(&server, "prompts/list", json!({})).await;
+        assert_eq!(missing["error"]["code"], METHOD_NOT_FOUND);
+        let garbage = server.handle("not json").await.unwrap();
+        assert_eq!(garbage.error.unwrap().code, PARSE_ERROR);
+    }
+}
@@ -0,0 +1,214 @@
+//! Stderr of stdio MCP servers.
+//!
+//! Every line a server writes to stderr is logged at debug level and kept
+//! in a per-server ring buffer of the last [`MAX_LINES`] lines. Whenever
+//! the server goes quiet the buffer is mirrored to `mcp_logs/<server>.log`
+//! next to the config file, so `zeroclaw mcp logs <server>` can show it
+//! from another process.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+/// Lines kept per server.
+const MAX_LINES: usize = 500;
+/// Longer lines are cut off at this many characters.
+const MAX_LINE_CHARS: usize = 2000;
+/// Quiet time after which the buffer is written to disk.
+const FLUSH_AFTER: Duration = Duration::from_millis(250);
+
+type Buffer = Arc<Mutex<VecDeque<String>>>;
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+static BUFFERS: LazyLock<Mutex<HashMap<String, Buffer>>> = LazyLock::new(Mutex::default);
+
+/// Remember where stderr logs are written. Called once at startup; without
+/// it stderr is only kept in memory.
+pub fn init(config: &Config) {
+    let _ = DIR.set(log_dir(config));
+}
+
+/// Directory holding the stderr logs of all servers.
+pub fn log_dir(config: &Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("mcp_logs")
+}
+
+/// Log file of `server` inside `dir`. Characters that are not safe in a
+/// file name are replaced by `_`.
+fn log_path(dir: &Path, server: &str) -> PathBuf {
+    let name: String = server
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{name}.log"))
+}
+
+/// Read `stderr` of `server` in the background until it closes. Restarts
+/// of the same server keep appending to its buffer.
+pub(super) fn capture(server: &str, stderr: impl AsyncRead + Unpin + Send + 'static) {
+    let path = DIR.get().map(|dir| log_path(dir, server));
+    capture_to(server, stderr, path);
+}
+
+fn capture_to(
+    server: &str,
+    stderr: impl AsyncRead + Unpin + Send + 'static,
+    path: Option<PathBuf>,
+) {
+    let buffer = Arc::clone(
+        BUFFERS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(server.to_string())
+            .or_default(),
+    );
+    let server = server.to_string();
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        let mut unsaved = false;
+        loop {
+            let next = if unsaved {
+                match tokio::time::timeout(FLUSH_AFTER, lines.next_line()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        save(&buffer, path.as_deref()).await;
+                        unsaved = false;
+                        continue;
+                    }
+                }
+            } else {
+                lines.next_line().await
+            };
+            let Ok(Some(mut line)) = next else {
+                break;
+            };
+            tracing::debug!(server = %server, "MCP server stderr: {line}");
+            if let Some((cut, _)) = line.char_indices().nth(MAX_LINE_CHARS) {
+                line.truncate(cut);
+                line.push('…');
+            }
+            let mut kept = buffer.lock().unwrap_or_else(PoisonError::into_inner);
+            if kept.len() == MAX_LINES {
+                kept.pop_front();
+            }
+            kept.push_back(line);
+            unsaved = true;
+        }
+        if unsaved {
+            save(&buffer, path.as_deref()).await;
+        }
+    });
+}
+
+async fn save(buffer: &Buffer, path: Option<&Path>) {
+    let Some(path) = path else {
+        return;
+    };
+    let mut text = String::new();
+    for line in buffer.lock().unwrap_or_else(PoisonError::into_inner).iter() {
+        text.push_str(line);
+        text.push('\n');
+    }
+    if let Some(parent) = path.parent() {
+        let _ = tokio::fs::create_dir_all(parent).await;
+    }
+    // Written aside and renamed so readers never see a partial file.
+    let partial = path.with_extension("log.partial");
+    let saved = match tokio::fs::write(&partial, text).await {
+        Ok(()) => tokio::fs::rename(&partial, path).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
+        tracing::debug!(path = %path.display(), "Failed to save MCP server stderr: {e}");
+    }
+}
+
+/// The last `lines` stderr lines of `server` captured by this process.
+pub fn tail(server: &str, lines: usize) -> Vec<String> {
+    let buffers = BUFFERS.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(buffer) = buffers.get(server) else {
+        return Vec::new();
+    };
+    let kept = buffer.lock().unwrap_or_else(PoisonError::into_inner);
+    kept.iter()
+        .skip(kept.len().saturating_sub(lines))
+        .cloned()
+        .collect()
+}
+
+/// The last `lines` stderr lines of `server` saved by any process using
+/// `config`, `None` if nothing was captured yet.
+pub fn read_saved(config: &Config, server: &str, lines: usize) -> Result<Option<Vec<String>>> {
+    let path = log_path(&log_dir(config), server);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let all: Vec<&str> = text.lines().collect();
+    Ok(Some(
+        all[all.len().saturating_sub(lines)..]
+            .iter()
+            .map(|line| (*line).to_string())
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stderr_is_kept_in_a_ring_buffer_and_saved() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        let path = log_path(&log_dir(&config), "ring/test");
+        assert!(path.ends_with("mcp_logs/ring_test.log"));
+
+        let text: String = (0..MAX_LINES + 5).map(|i| format!("line {i}\n")).collect();
+        capture_to(
+            "ring/test",
+            std::io::Cursor::new(text.into_bytes()),
+            Some(path),
+        );
+
+        let last = format!("line {}", MAX_LINES + 4);
+        let mut saved = None;
+        for _ in 0..100 {
+            saved = read_saved(&config, "ring/test", 2).unwrap();
+            if saved
+                .as_ref()
+                .is_some_and(|lines| lines.last() == Some(&last))
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            saved.unwrap(),
+            [format!("line {}", MAX_LINES + 3), last.clone()]
+        );
+        let kept = tail("ring/test", usize::MAX);
+        assert_eq!(kept.len(), MAX_LINES);
+        assert_eq!(kept[0], "line 5");
+        assert_eq!(kept.last(), Some(&last));
+        assert!(read_saved(&config, "other", 10).unwrap().is_none());
+    }
+}
@@ -1,22 +1,109 @@
-use super::protocol::{JsonRpcRequest, JsonRpcResponse};
+use super::auth::OAuthSession;
+use super::config::McpTlsConfig;
+use super::protocol::{
+    JsonRpcError, JsonRpcNotification, JsonRpcReply, JsonRpcRequest, JsonRpcResponse,
+};
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use futures::future::BoxFuture;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, watch, Mutex};
+
+/// Callback for server notifications (JSON-RPC messages without an id),
+/// given the notification method and params.
+pub type NotificationHandler = Arc<dyn Fn(&str, &serde_json::Value) + Send + Sync>;
+
+/// Answers requests the server sends us (e.g. `sampling/createMessage`),
+/// given the method and params.
+pub type RequestHandler = Arc<
+    dyn Fn(String, serde_json::Value) -> BoxFuture<'static, Result<serde_json::Value, JsonRpcError>>
+        + Send
+        + Sync,
+>;
+
+/// JSON-RPC error code for a method we do not implement.
+const METHOD_NOT_FOUND: i64 = -32601;
 
 /// Transport abstraction for MCP communication.
 #[async_trait]
 pub trait McpTransport: Send + Sync {
     /// Send a JSON-RPC request and receive the response.
     async fn send(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse>;
+    /// Send several requests as one JSON-RPC batch and receive their
+    /// responses in the same order. Transports without batching send them
+    /// one after another.
+    async fn send_batch(&self, requests: &[JsonRpcRequest]) -> Result<Vec<JsonRpcResponse>> {
+        let mut responses = Vec::with_capacity(requests.len());
+        for request in requests {
+            responses.push(self.send(request).await?);
+        }
+        Ok(responses)
+    }
+    /// Send a JSON-RPC notification; no response is awaited.
+    async fn notify(&self, notification: &JsonRpcNotification) -> Result<()>;
     /// Gracefully shut down the transport.
     async fn shutdown(&self) -> Result<()>;
     /// Check if the transport is still alive.
     fn is_alive(&self) -> bool;
+    /// Number of times the transport has restarted the server. A fresh
+    /// server must be initialized again before it accepts requests.
+    fn generation(&self) -> u64 {
+        0
+    }
+    /// Protocol version agreed on in the handshake, for transports that send
+    /// it along with every later message.
+    fn set_protocol_version(&self, _version: &str) {}
+    /// Register the callback for notifications that arrive on this transport.
+    fn on_notification(&self, _handler: NotificationHandler) {}
+    /// Register the callback that answers requests from the server. Without
+    /// one, server requests are answered with "method not found".
+    fn on_request(&self, _handler: RequestHandler) {}
+    /// Handle notifications the server sent since the last request, without
+    /// waiting for new ones.
+    async fn poll_notifications(&self) {}
+}
+
+/// Method and params of a JSON-RPC notification, `None` for responses and
+/// non-JSON lines.
+fn parse_notification(message: &str) -> Option<(String, serde_json::Value)> {
+    let mut value: serde_json::Value = serde_json::from_str(message).ok()?;
+    if value.get("id").is_some() {
+        return None;
+    }
+    let method = value.get("method")?.as_str()?.to_string();
+    Some((method, value["params"].take()))
+}
+
+/// Id, method and params of a request from the server, `None` for anything
+/// else.
+fn parse_server_request(message: &str) -> Option<(serde_json::Value, String, serde_json::Value)> {
+    let mut value: serde_json::Value = serde_json::from_str(message).ok()?;
+    let method = value.get("method")?.as_str()?.to_string();
+    let id = value.get_mut("id")?.take();
+    Some((id, method, value["params"].take()))
+}
+
+/// Run the handler for a server request and build the reply.
+async fn answer_request(
+    on_request: Option<&RequestHandler>,
+    id: serde_json::Value,
+    method: String,
+    params: serde_json::Value,
+) -> JsonRpcReply {
+    let outcome = match on_request {
+        Some(handler) => handler(method, params).await,
+        None => Err(JsonRpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {method}"),
+        )),
+    };
+    JsonRpcReply::new(id, outcome)
 }
 
 // ── Stdio Transport ─────────────────────────────────────────────
@@ -27,8 +114,10 @@ struct StdioInner {
     reader: BufReader<tokio::process::ChildStdout>,
 }
 
-/// Spawn a child process and return its inner handles.
+/// Spawn a child process and return its inner handles. Its stderr is
+/// captured under `server`.
 fn spawn_child(
+    server: &str,
     command: &str,
     args: &[String],
     env: &HashMap<String, String>,
@@ -43,6 +132,7 @@ fn spawn_child(
     for (k, v) in env {
         cmd.env(k, v);
     }
+    crate::security::limits::apply(&mut cmd);
 
     let mut child = cmd
         .spawn()
@@ -50,6 +140,9 @@ fn spawn_child(
 
     let stdin = child.stdin.take().context("No stdin on MCP child")?;
     let stdout = child.stdout.take().context("No stdout on MCP child")?;
+    if let Some(stderr) = child.stderr.take() {
+        super::stderr::capture(server, stderr);
+    }
     let reader = BufReader::new(stdout);
 
     Ok(StdioInner {
@@ -59,14 +152,9 @@ fn spawn_child(
     })
 }
 
-/// Send a request over stdio and read the matching response.
-async fn stdio_send(
-    inner: &mut StdioInner,
-    alive: &AtomicBool,
-    request: &JsonRpcRequest,
-) -> Result<JsonRpcResponse> {
-    // Serialize request as single line
-    let mut line = serde_json::to_string(request)?;
+/// Write one message to the server as a single line.
+async fn stdio_write(inner: &mut StdioInner, message: &impl Serialize) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
     line.push('\n');
 
     inner
@@ -78,26 +166,62 @@ async fn stdio_send(
         .stdin
         .flush()
         .await
-        .context("Failed to flush MCP stdin")?;
+        .context("Failed to flush MCP stdin")
+}
+
+/// Send a request over stdio and read the matching response. Requests the
+/// server makes while working on it are answered in between.
+async fn stdio_send(
+    inner: &mut StdioInner,
+    alive: &AtomicBool,
+    on_notification: Option<&NotificationHandler>,
+    on_request: Option<&RequestHandler>,
+    request: &JsonRpcRequest,
+) -> Result<JsonRpcResponse> {
+    stdio_write(inner, request).await?;
 
     // Read response lines, skipping empty lines and JSON-RPC notifications (no id)
+    let limit = crate::security::limits::max_output_bytes();
     let mut buf = String::new();
     loop {
         buf.clear();
-        let n = inner
-            .reader
+        let n = (&mut inner.reader)
+            .take(u64::try_from(limit).unwrap_or(u64::MAX))
             .read_line(&mut buf)
             .await
             .context("Failed to read from MCP stdout")?;
         if n == 0 {
             alive.store(false, Ordering::Relaxed);
-            bail!("MCP server closed stdout (EOF)");
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "MCP server closed stdout (EOF)",
+            )
+            .into());
+        }
+        if n == limit && !buf.ends_with('\n') {
+            // The rest of the oversized message is still buffered; the
+            // stream cannot be resynchronised, so treat the server as dead.
+            alive.store(false, Ordering::Relaxed);
+            bail!(
+                "MCP server message exceeded {limit} bytes (security.resources.max_output_bytes)"
+            );
         }
 
         let trimmed = buf.trim();
         if trimmed.is_empty() {
             continue;
         }
+        if let Some((id, method, params)) = parse_server_request(trimmed) {
+            let reply = answer_request(on_request, id, method, params).await;
+            stdio_write(inner, &reply).await?;
+            continue;
+        }
+        if let Some((method, params)) = parse_notification(trimmed) {
+            if let Some(handler) = on_notification {
+                handler(&method, &params);
+            }
+            continue;
+        }
 
         // Try to parse as JSON-RPC response
         match serde_json::from_str::<JsonRpcResponse>(trimmed) {
@@ -115,6 +239,31 @@ async fn stdio_send(
     }
 }
 
+/// Dispatch notifications already waiting on stdout. Only complete lines
+/// that can be read without blocking are consumed.
+fn drain_notifications(inner: &mut StdioInner, on_notification: Option<&NotificationHandler>) {
+    use futures::FutureExt;
+
+    loop {
+        let has_line = matches!(
+            inner.reader.fill_buf().now_or_never(),
+            Some(Ok(buf)) if buf.contains(&b'\n')
+        );
+        if !has_line {
+            return;
+        }
+        let mut line = String::new();
+        if !matches!(inner.reader.read_line(&mut line).now_or_never(), Some(Ok(n)) if n > 0) {
+            return;
+        }
+        if let (Some((method, params)), Some(handler)) =
+            (parse_notification(line.trim()), on_notification)
+        {
+            handler(&method, &params);
+        }
+    }
+}
+
 /// Kill a stdio child, giving it a grace period.
 async fn kill_child(inner: &mut StdioInner) {
     drop(inner.stdin.shutdown().await);
@@ -131,7 +280,11 @@ async fn kill_child(inner: &mut StdioInner) {
 pub struct StdioTransport {
     inner: Mutex<StdioInner>,
     alive: Arc<AtomicBool>,
+    restarts: AtomicU64,
+    on_notification: std::sync::OnceLock<NotificationHandler>,
+    on_request: std::sync::OnceLock<RequestHandler>,
     // Spawn config (retained for auto-restart)
+    server: String,
     command: String,
     args: Vec<String>,
     env: HashMap<String, String>,
@@ -139,18 +292,23 @@ pub struct StdioTransport {
 }
 
 impl StdioTransport {
-    /// Spawn the MCP server subprocess.
+    /// Spawn the MCP server subprocess; `server` names it in the stderr logs.
     pub fn spawn(
+        server: &str,
         command: &str,
         args: &[String],
         env: &HashMap<String, String>,
         auto_restart: bool,
     ) -> Result<Self> {
-        let child_inner = spawn_child(command, args, env)?;
+        let child_inner = spawn_child(server, command, args, env)?;
 
         Ok(Self {
             inner: Mutex::new(child_inner),
             alive: Arc::new(AtomicBool::new(true)),
+            restarts: AtomicU64::new(0),
+            on_notification: std::sync::OnceLock::new(),
+            on_request: std::sync::OnceLock::new(),
+            server: server.to_string(),
             command: command.to_string(),
             args: args.to_vec(),
             env: env.clone(),
@@ -171,9 +329,10 @@ impl StdioTransport {
         kill_child(&mut inner).await;
 
         // Spawn fresh process
-        match spawn_child(&self.command, &self.args, &self.env) {
+        match spawn_child(&self.server, &self.command, &self.args, &self.env) {
             Ok(new_inner) => {
                 *inner = new_inner;
+                self.restarts.fetch_add(1, Ordering::Relaxed);
                 self.alive.store(true, Ordering::Relaxed);
                 tracing::info!(command = %self.command, "MCP server restarted successfully");
                 Ok(true)
@@ -192,7 +351,15 @@ impl McpTransport for StdioTransport {
         // First attempt
         {
             let mut inner = self.inner.lock().await;
-            match stdio_send(&mut inner, &self.alive, request).await {
+            let sent = stdio_send(
+                &mut inner,
+                &self.alive,
+                self.on_notification.get(),
+                self.on_request.get(),
+                request,
+            )
+            .await;
+            match sent {
                 Ok(resp) => return Ok(resp),
                 Err(e) => {
                     if !self.auto_restart {
@@ -203,13 +370,16 @@ impl McpTransport for StdioTransport {
             }
         }
 
-        // Auto-restart and retry once
+        // The fresh server has not been initialized, so the request is not
+        // replayed here: `McpClient` sees the new generation, re-runs the
+        // handshake and retries it.
         self.try_restart().await?;
+        bail!("MCP server restarted; request was not delivered")
+    }
 
-        // Re-initialize after restart (caller must handle this via McpClient)
-        // For now, retry the send directly — the client's initialize will re-run on next call
+    async fn notify(&self, notification: &JsonRpcNotification) -> Result<()> {
         let mut inner = self.inner.lock().await;
-        stdio_send(&mut inner, &self.alive, request).await
+        stdio_write(&mut inner, notification).await
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -222,28 +392,500 @@ impl McpTransport for StdioTransport {
     fn is_alive(&self) -> bool {
         self.alive.load(Ordering::Relaxed)
     }
+
+    fn generation(&self) -> u64 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    fn on_notification(&self, handler: NotificationHandler) {
+        let _ = self.on_notification.set(handler);
+    }
+
+    fn on_request(&self, handler: RequestHandler) {
+        let _ = self.on_request.set(handler);
+    }
+
+    async fn poll_notifications(&self) {
+        // A request in flight reads pending notifications itself.
+        if let Ok(mut inner) = self.inner.try_lock() {
+            drain_notifications(&mut inner, self.on_notification.get());
+        }
+    }
 }
 
 // ── SSE Transport ───────────────────────────────────────────────
 
-/// SSE-based MCP transport: sends JSON-RPC over HTTP POST, receives via SSE.
-pub struct SseTransport {
+/// Longest the SSE stream may stay silent, keepalive comments included,
+/// before it is taken for dead and reopened.
+const SSE_IDLE_TIMEOUT: Duration = Duration::from_secs(120);
+/// Wait before reopening a dropped SSE stream unless the server set one
+/// with `retry:`; doubled after each failed attempt up to [`SSE_RETRY_MAX`].
+const SSE_RETRY_MIN: Duration = Duration::from_secs(1);
+const SSE_RETRY_MAX: Duration = Duration::from_secs(30);
+
+/// Where an SSE server takes our messages.
+#[derive(Debug, Clone, PartialEq)]
+enum SseEndpoint {
+    /// The URL of the stream's `endpoint` event; responses arrive on the
+    /// stream.
+    Stream(String),
+    /// The server has no event stream: messages are POSTed to the
+    /// configured URL and answered in the response body.
+    Direct,
+}
+
+/// State shared by an [`SseTransport`] and the task reading its stream.
+struct SseShared {
     url: String,
     client: reqwest::Client,
-    alive: AtomicBool,
+    /// Client of the long-lived GET, without the request timeout.
+    stream_client: reqwest::Client,
+    auth: std::sync::OnceLock<Arc<OAuthSession>>,
+    /// `None` until the stream told us where to POST, or why it could not
+    /// be opened.
+    endpoint: watch::Sender<Option<Result<SseEndpoint, String>>>,
+    /// Requests waiting for their response on the stream, by id.
+    pending: std::sync::Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>,
+    /// Id of the last event received, sent back as `Last-Event-ID` when
+    /// the stream is reopened so the server replays what was missed.
+    last_event_id: std::sync::Mutex<Option<String>>,
+    /// Bumped when a reopened stream starts a new session.
+    generation: AtomicU64,
+    on_notification: std::sync::OnceLock<NotificationHandler>,
+    on_request: std::sync::OnceLock<RequestHandler>,
 }
 
-impl SseTransport {
-    pub fn new(url: &str, timeout_secs: u64) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(timeout_secs))
-            .build()
-            .unwrap_or_default();
+/// One event of an SSE stream.
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+}
+
+/// Raw bytes of an SSE stream, cut into events only once an event is
+/// complete, so a multi-byte character or a `\r\n` split across network
+/// chunks arrives intact. Holds at most `max_output_bytes`
+/// (security.resources), like a stdio message.
+struct SseBuffer {
+    transport: &'static str,
+    bytes: Vec<u8>,
+    limit: usize,
+}
 
+impl SseBuffer {
+    fn new(transport: &'static str) -> Self {
         Self {
-            url: url.to_string(),
-            client,
+            transport,
+            bytes: Vec::new(),
+            limit: crate::security::limits::max_output_bytes(),
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Result<()> {
+        self.bytes.extend_from_slice(chunk);
+        if self.bytes.len() > self.limit {
+            bail!(
+                "{} transport: SSE event exceeded {} bytes (security.resources.max_output_bytes)",
+                self.transport,
+                self.limit
+            );
+        }
+        Ok(())
+    }
+
+    /// The next complete event, up to and including its blank line, with
+    /// line endings turned into `\n`.
+    fn next_event(&mut self) -> Option<String> {
+        let mut line_start = 0;
+        let mut i = 0;
+        while i < self.bytes.len() {
+            let width = match self.bytes[i] {
+                b'\n' => 1,
+                b'\r' => match self.bytes.get(i + 1) {
+                    Some(b'\n') => 2,
+                    Some(_) => 1,
+                    // Maybe the first half of a `\r\n`
+                    None => return None,
+                },
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            if i == line_start {
+                let event: Vec<u8> = self.bytes.drain(..i + width).collect();
+                return Some(Self::decode(&event));
+            }
+            i += width;
+            line_start = i;
+        }
+        None
+    }
+
+    /// Whatever is left once the stream ended.
+    fn rest(self) -> String {
+        Self::decode(&self.bytes)
+    }
+
+    fn decode(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes)
+            .replace("\r\n", "\n")
+            .replace('\r', "\n")
+    }
+}
+
+/// Parse one event (the lines up to a blank line). Comment lines, which
+/// servers send as keepalives, are skipped.
+fn parse_sse_event(block: &str) -> SseEvent {
+    let mut event = SseEvent::default();
+    let mut data: Vec<&str> = Vec::new();
+    for line in block.lines() {
+        if line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "id" => event.id = Some(value.to_string()),
+            "event" => event.event = Some(value.to_string()),
+            "data" => data.push(value),
+            "retry" => event.retry = value.parse().ok().map(Duration::from_millis),
+            _ => {}
+        }
+    }
+    event.data = (!data.is_empty()).then(|| data.join("\n"));
+    event
+}
+
+impl SseShared {
+    /// POST `message` to `url`.
+    async fn post(&self, url: &str, message: &impl Serialize) -> Result<reqwest::Response> {
+        let resp = send_authorized(self.auth.get().map(Arc::as_ref), || {
+            self.client.post(url).json(message)
+        })
+        .await
+        .context("SSE transport: POST failed")?;
+        if !resp.status().is_success() {
+            return Err(HttpStatusError::new("SSE", &resp, url).into());
+        }
+        Ok(resp)
+    }
+
+    /// Hand a message from the stream to whoever waits for it.
+    fn dispatch(self: &Arc<Self>, data: &str) {
+        if let Some((id, method, params)) = parse_server_request(data) {
+            let shared = Arc::clone(self);
+            tokio::spawn(async move {
+                let reply = answer_request(shared.on_request.get(), id, method, params).await;
+                let endpoint = shared.endpoint.borrow().clone();
+                if let Some(Ok(SseEndpoint::Stream(url))) = endpoint {
+                    if let Err(e) = shared.post(&url, &reply).await {
+                        tracing::warn!(error = %e, "MCP SSE: reply to server request failed");
+                    }
+                }
+            });
+            return;
+        }
+        if let Some((method, params)) = parse_notification(data) {
+            if let Some(handler) = self.on_notification.get() {
+                handler(&method, &params);
+            }
+            return;
+        }
+        let messages = match serde_json::from_str(data) {
+            Ok(serde_json::Value::Array(items)) => items,
+            Ok(other) => vec![other],
+            Err(_) => return,
+        };
+        let mut pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        for message in messages {
+            let Ok(rpc) = serde_json::from_value::<JsonRpcResponse>(message) else {
+                continue;
+            };
+            match rpc.id.and_then(|id| pending.remove(&id)) {
+                Some(waiting) => {
+                    let _ = waiting.send(rpc);
+                }
+                None => tracing::debug!("MCP SSE: skipping response nobody waits for"),
+            }
+        }
+    }
+
+    /// Record where to POST. A different endpoint than before means the
+    /// server started a new session, which must be initialized again.
+    fn set_endpoint(&self, endpoint: SseEndpoint) {
+        self.endpoint.send_if_modified(|current| {
+            match current {
+                Some(Ok(known)) if *known == endpoint => return false,
+                Some(Ok(_)) => {
+                    self.generation.fetch_add(1, Ordering::Relaxed);
+                }
+                _ => {}
+            }
+            *current = Some(Ok(endpoint));
+            true
+        });
+    }
+}
+
+/// Keep the event stream of `shared` open until the transport shuts down:
+/// a stream that ends, fails, or goes silent is reopened with
+/// `Last-Event-ID`, so the server can replay the events we missed. When
+/// the first attempt fails, the task ends and the error goes to the
+/// waiting callers.
+async fn run_sse_stream(shared: Arc<SseShared>) {
+    let mut delay = SSE_RETRY_MIN;
+    loop {
+        match read_sse_stream(&shared, &mut delay).await {
+            Ok(()) => tracing::debug!(url = %shared.url, "MCP SSE stream ended; reopening"),
+            Err(e) if shared.endpoint.borrow().is_none() => {
+                if e.is::<HttpStatusError>() {
+                    tracing::debug!(
+                        url = %shared.url,
+                        error = %e,
+                        "MCP SSE: no event stream; POSTing to the URL directly"
+                    );
+                    shared.set_endpoint(SseEndpoint::Direct);
+                } else {
+                    shared.endpoint.send_replace(Some(Err(format!("{e:#}"))));
+                }
+                return;
+            }
+            Err(e) => tracing::warn!(url = %shared.url, error = %e, "MCP SSE stream dropped"),
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(SSE_RETRY_MAX);
+    }
+}
+
+/// Open the event stream and handle its events until it closes. `delay`
+/// is reset once the stream is open and takes the server's `retry:`.
+async fn read_sse_stream(shared: &Arc<SseShared>, delay: &mut Duration) -> Result<()> {
+    use futures::StreamExt;
+
+    let last_event_id = shared
+        .last_event_id
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .clone();
+    let resp = send_authorized(shared.auth.get().map(Arc::as_ref), || {
+        let get = shared
+            .stream_client
+            .get(&shared.url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        match &last_event_id {
+            Some(id) => get.header("Last-Event-ID", id),
+            None => get,
+        }
+    })
+    .await
+    .context("SSE transport: GET failed")?;
+    let is_stream = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if !resp.status().is_success() || !is_stream {
+        return Err(HttpStatusError::new("SSE", &resp, &shared.url).into());
+    }
+    *delay = SSE_RETRY_MIN;
+
+    let base = reqwest::Url::parse(&shared.url)?;
+    let mut stream = resp.bytes_stream();
+    let mut buffer = SseBuffer::new("SSE");
+    loop {
+        let Ok(chunk) = tokio::time::timeout(SSE_IDLE_TIMEOUT, stream.next()).await else {
+            bail!(
+                "SSE transport: stream silent for {}s",
+                SSE_IDLE_TIMEOUT.as_secs()
+            );
+        };
+        let Some(chunk) = chunk else {
+            return Ok(());
+        };
+        let chunk = chunk.context("SSE transport: stream read failed")?;
+        buffer.push(&chunk)?;
+        while let Some(block) = buffer.next_event() {
+            let event = parse_sse_event(&block);
+            if let Some(retry) = event.retry {
+                *delay = retry;
+            }
+            if let Some(id) = event.id {
+                *shared
+                    .last_event_id
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(id);
+            }
+            let Some(data) = event.data else {
+                continue;
+            };
+            if event.event.as_deref() == Some("endpoint") {
+                let url = base
+                    .join(data.trim())
+                    .context("SSE transport: invalid endpoint")?;
+                // Messages carry the server's credentials; keep them on its origin
+                if url.origin() != base.origin() {
+                    bail!("SSE transport: endpoint {url} is not on the server's origin");
+                }
+                shared.set_endpoint(SseEndpoint::Stream(url.to_string()));
+            } else {
+                shared.dispatch(&data);
+            }
+        }
+    }
+}
+
+/// Removes a request from the pending ones when its caller stops waiting.
+struct Waiting<'a>(&'a SseShared, u64);
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&self.1);
+    }
+}
+
+/// MCP over HTTP with server-sent events (spec 2024-11-05): a long-lived
+/// GET stream names the endpoint to POST messages to and carries the
+/// responses, notifications and server requests. The stream is reopened
+/// with `Last-Event-ID` when it drops or stops sending keepalives. Servers
+/// without a stream are sent plain POSTs answered in the response body.
+pub struct SseTransport {
+    shared: Arc<SseShared>,
+    /// The task reading the stream, started by the first message.
+    reader: std::sync::Mutex<Option<tokio::task::AbortHandle>>,
+    alive: AtomicBool,
+}
+
+impl SseTransport {
+    pub fn new(
+        url: &str,
+        timeout_secs: u64,
+        headers: &HashMap<String, String>,
+        tls: Option<&McpTlsConfig>,
+    ) -> Result<Self> {
+        Ok(Self {
+            shared: Arc::new(SseShared {
+                url: url.to_string(),
+                client: http_client(Some(timeout_secs), headers, tls)?,
+                stream_client: http_client(None, headers, tls)?,
+                auth: std::sync::OnceLock::new(),
+                endpoint: watch::Sender::new(None),
+                pending: std::sync::Mutex::new(HashMap::new()),
+                last_event_id: std::sync::Mutex::new(None),
+                generation: AtomicU64::new(0),
+                on_notification: std::sync::OnceLock::new(),
+                on_request: std::sync::OnceLock::new(),
+            }),
+            reader: std::sync::Mutex::new(None),
             alive: AtomicBool::new(true),
+        })
+    }
+
+    /// Send an OAuth bearer token with every request.
+    pub fn with_auth(self, auth: Option<Arc<OAuthSession>>) -> Self {
+        if let Some(auth) = auth {
+            let _ = self.shared.auth.set(auth);
+        }
+        self
+    }
+
+    /// Where to POST, once the stream (started here if needed) said so.
+    /// When the stream could not be opened, the next call tries again.
+    async fn endpoint(&self) -> Result<SseEndpoint> {
+        {
+            let mut reader = self
+                .reader
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if reader.is_none() {
+                if !self.is_alive() {
+                    bail!("SSE transport: shut down");
+                }
+                let task = tokio::spawn(run_sse_stream(Arc::clone(&self.shared)));
+                *reader = Some(task.abort_handle());
+            }
+        }
+        let mut endpoint = self.shared.endpoint.subscribe();
+        let endpoint = endpoint
+            .wait_for(Option::is_some)
+            .await
+            .context("SSE transport: stream closed")?
+            .clone();
+        match endpoint {
+            Some(Ok(endpoint)) => Ok(endpoint),
+            Some(Err(e)) => {
+                let mut reader = self
+                    .reader
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                let reset = self.shared.endpoint.send_if_modified(|current| {
+                    let failed = matches!(current, Some(Err(_)));
+                    if failed {
+                        *current = None;
+                    }
+                    failed
+                });
+                if reset {
+                    *reader = None;
+                }
+                bail!("SSE transport: {e}")
+            }
+            None => unreachable!("wait_for returned an empty endpoint"),
+        }
+    }
+
+    /// Send `request` to a server without an event stream and read the
+    /// response from the body, as JSON or as SSE events.
+    async fn send_direct(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let body = self
+            .shared
+            .post(&self.shared.url, request)
+            .await?
+            .text()
+            .await?;
+        if let Ok(rpc) = serde_json::from_str::<JsonRpcResponse>(&body) {
+            return Ok(rpc);
+        }
+        // Notifications (e.g. progress) may come before the response.
+        for block in body.replace("\r\n", "\n").split("\n\n") {
+            let Some(data) = parse_sse_event(block).data else {
+                continue;
+            };
+            if let Some((method, params)) = parse_notification(&data) {
+                if let Some(handler) = self.shared.on_notification.get() {
+                    handler(&method, &params);
+                }
+                continue;
+            }
+            if let Some(rpc) = serde_json::from_str(&data)
+                .ok()
+                .and_then(|v| find_response(v, request.id))
+            {
+                return Ok(rpc);
+            }
+        }
+        bail!("SSE transport: no valid JSON-RPC response in body")
+    }
+}
+
+impl Drop for SseTransport {
+    fn drop(&mut self) {
+        if let Some(reader) = self
+            .reader
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+        {
+            reader.abort();
         }
     }
 }
@@ -251,45 +893,921 @@ impl SseTransport {
 #[async_trait]
 impl McpTransport for SseTransport {
     async fn send(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
-        let resp = self
+        let SseEndpoint::Stream(url) = self.endpoint().await? else {
+            return self.send_direct(request).await;
+        };
+        let (tx, rx) = oneshot::channel();
+        self.shared
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(request.id, tx);
+        let _waiting = Waiting(&self.shared, request.id);
+
+        // Some servers answer in the POST body instead of on the stream.
+        let body = self.shared.post(&url, request).await?.text().await?;
+        if let Some(rpc) = serde_json::from_str(&body)
+            .ok()
+            .and_then(|v| find_response(v, request.id))
+        {
+            return Ok(rpc);
+        }
+        rx.await.map_err(|_| {
+            anyhow::anyhow!(
+                "SSE transport: closed before the response to request {}",
+                request.id
+            )
+        })
+    }
+
+    async fn send_batch(&self, requests: &[JsonRpcRequest]) -> Result<Vec<JsonRpcResponse>> {
+        let SseEndpoint::Stream(url) = self.endpoint().await? else {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(self.send_direct(request).await?);
+            }
+            return Ok(responses);
+        };
+        let mut waiting = Vec::with_capacity(requests.len());
+        let mut receivers = Vec::with_capacity(requests.len());
+        for request in requests {
+            let (tx, rx) = oneshot::channel();
+            self.shared
+                .pending
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .insert(request.id, tx);
+            waiting.push(Waiting(&self.shared, request.id));
+            receivers.push(rx);
+        }
+
+        let body = self.shared.post(&url, &requests).await?.text().await?;
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&body) {
+            if let Some(responses) = requests
+                .iter()
+                .map(|request| find_response(value.clone(), request.id))
+                .collect::<Option<Vec<_>>>()
+            {
+                return Ok(responses);
+            }
+        }
+        let mut responses = Vec::with_capacity(requests.len());
+        for (request, rx) in requests.iter().zip(receivers) {
+            responses.push(rx.await.map_err(|_| {
+                anyhow::anyhow!(
+                    "SSE transport: closed before the response to request {}",
+                    request.id
+                )
+            })?);
+        }
+        Ok(responses)
+    }
+
+    async fn notify(&self, notification: &JsonRpcNotification) -> Result<()> {
+        let url = match self.endpoint().await? {
+            SseEndpoint::Stream(url) => url,
+            SseEndpoint::Direct => self.shared.url.clone(),
+        };
+        self.shared.post(&url, notification).await?;
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.alive.store(false, Ordering::Relaxed);
+        if let Some(reader) = self
+            .reader
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+        {
+            reader.abort();
+        }
+        self.shared
+            .pending
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+        Ok(())
+    }
+
+    fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Relaxed)
+    }
+
+    fn generation(&self) -> u64 {
+        self.shared.generation.load(Ordering::Relaxed)
+    }
+
+    fn on_notification(&self, handler: NotificationHandler) {
+        let _ = self.shared.on_notification.set(handler);
+    }
+
+    fn on_request(&self, handler: RequestHandler) {
+        let _ = self.shared.on_request.set(handler);
+    }
+}
+
+// ── Streamable HTTP Transport ───────────────────────────────────
+
+/// Header carrying the session id a streamable HTTP server assigns at
+/// `initialize`.
+const SESSION_HEADER: &str = "Mcp-Session-Id";
+/// Header carrying the negotiated protocol version on every request after
+/// `initialize` (spec 2025-06-18).
+const PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+
+/// Streamable HTTP MCP transport (spec 2025-03-26): every message is POSTed
+/// to a single endpoint, and the server answers with either a JSON body or
+/// an SSE stream that carries the response (possibly after server
+/// notifications). The session id from `initialize` is sent back on every
+/// later request and the session is closed with `DELETE` on shutdown.
+pub struct StreamableHttpTransport {
+    url: String,
+    client: reqwest::Client,
+    auth: Option<Arc<OAuthSession>>,
+    session_id: std::sync::Mutex<Option<String>>,
+    protocol_version: std::sync::Mutex<Option<String>>,
+    on_notification: std::sync::OnceLock<NotificationHandler>,
+    on_request: std::sync::OnceLock<RequestHandler>,
+    alive: AtomicBool,
+}
+
+impl StreamableHttpTransport {
+    pub fn new(
+        url: &str,
+        timeout_secs: u64,
+        headers: &HashMap<String, String>,
+        tls: Option<&McpTlsConfig>,
+    ) -> Result<Self> {
+        Ok(Self {
+            url: url.to_string(),
+            client: http_client(Some(timeout_secs), headers, tls)?,
+            auth: None,
+            session_id: std::sync::Mutex::new(None),
+            protocol_version: std::sync::Mutex::new(None),
+            on_notification: std::sync::OnceLock::new(),
+            on_request: std::sync::OnceLock::new(),
+            alive: AtomicBool::new(true),
+        })
+    }
+
+    /// Send an OAuth bearer token with every request.
+    pub fn with_auth(mut self, auth: Option<Arc<OAuthSession>>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.session_id
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set_session_id(&self, session_id: Option<String>) {
+        *self
+            .session_id
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = session_id;
+    }
+
+    /// POST request for `message`, carrying the session id and protocol
+    /// version once the handshake established them.
+    fn post(&self, message: &impl Serialize) -> reqwest::RequestBuilder {
+        let mut post = self
             .client
             .post(&self.url)
-            .json(request)
-            .send()
-            .await
-            .context("SSE transport: POST failed")?;
+            .header(
+                reqwest::header::ACCEPT,
+                "application/json, text/event-stream",
+            )
+            .json(message);
+        if let Some(session_id) = self.session_id() {
+            post = post.header(SESSION_HEADER, session_id);
+        }
+        if let Some(version) = self
+            .protocol_version
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .as_deref()
+        {
+            post = post.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        post
+    }
 
+    /// POST a message that gets no JSON-RPC response (a notification or our
+    /// reply to a server request).
+    async fn post_message(&self, message: &impl Serialize) -> Result<()> {
+        let resp = send_authorized(self.auth.as_deref(), || self.post(message))
+            .await
+            .context("Streamable HTTP transport: POST failed")?;
         if !resp.status().is_success() {
-            bail!("SSE transport: HTTP {} from {}", resp.status(), self.url);
+            return Err(HttpStatusError::new("Streamable HTTP", &resp, &self.url).into());
         }
+        Ok(())
+    }
 
-        let body = resp.text().await?;
-        // Parse the response — SSE servers may return JSON-RPC directly or as SSE events
-        // Try direct JSON-RPC first
-        if let Ok(rpc) = serde_json::from_str::<JsonRpcResponse>(&body) {
-            return Ok(rpc);
-        }
+    /// Read SSE events from `resp` until the responses to requests `ids`
+    /// arrived, and return them in that order. Notifications sent before
+    /// go to `on_notification`; server requests are answered with a
+    /// separate POST.
+    async fn read_sse_responses(
+        &self,
+        resp: reqwest::Response,
+        ids: &[u64],
+    ) -> Result<Vec<JsonRpcResponse>> {
+        use futures::StreamExt;
 
-        // Try parsing SSE event format: look for "data:" lines
-        for line in body.lines() {
-            let line = line.trim();
-            if let Some(data) = line.strip_prefix("data:") {
-                let data = data.trim();
-                if let Ok(rpc) = serde_json::from_str::<JsonRpcResponse>(data) {
-                    return Ok(rpc);
+        let mut found: HashMap<u64, JsonRpcResponse> = HashMap::new();
+        let mut stream = resp.bytes_stream();
+        let mut buffer = SseBuffer::new("Streamable HTTP");
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Streamable HTTP transport: stream read failed")?;
+            buffer.push(&chunk)?;
+            while let Some(event) = buffer.next_event() {
+                let Some(data) = sse_event_data(&event) else {
+                    continue;
+                };
+                if let Some((request_id, method, params)) = parse_server_request(&data) {
+                    let reply =
+                        answer_request(self.on_request.get(), request_id, method, params).await;
+                    self.post_message(&reply).await?;
+                    continue;
+                }
+                if let Some((method, params)) = parse_notification(&data) {
+                    if let Some(handler) = self.on_notification.get() {
+                        handler(&method, &params);
+                    }
+                    continue;
+                }
+                let value = serde_json::from_str(&data).unwrap_or_default();
+                if !collect_responses(&value, ids, &mut found) {
+                    tracing::debug!("MCP streamable HTTP: skipping server message");
+                }
+                if found.len() == ids.len() {
+                    return Ok(ids.iter().filter_map(|id| found.remove(id)).collect());
                 }
             }
         }
+        if let Some(value) =
+            sse_event_data(&buffer.rest()).and_then(|data| serde_json::from_str(&data).ok())
+        {
+            collect_responses(&value, ids, &mut found);
+        }
+        if found.len() == ids.len() {
+            return Ok(ids.iter().filter_map(|id| found.remove(id)).collect());
+        }
+        let missing: Vec<String> = ids
+            .iter()
+            .filter(|id| !found.contains_key(id))
+            .map(ToString::to_string)
+            .collect();
+        bail!(
+            "Streamable HTTP transport: stream ended without a response to request {}",
+            missing.join(", ")
+        )
+    }
 
-        bail!("SSE transport: no valid JSON-RPC response in body")
+    /// POST `message` (one request or a batch) and read the responses to
+    /// `ids`, from a JSON body or an SSE stream. A notification
+    /// acknowledged with 202 gets no responses.
+    async fn exchange(
+        &self,
+        message: &impl Serialize,
+        ids: &[u64],
+    ) -> Result<Vec<JsonRpcResponse>> {
+        let resp = send_authorized(self.auth.as_deref(), || self.post(message))
+            .await
+            .context("Streamable HTTP transport: POST failed")?;
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND && self.session_id().is_some() {
+            // The server dropped our session; a new `initialize` starts one.
+            self.set_session_id(None);
+            self.set_protocol_version("");
+            bail!("Streamable HTTP transport: session expired at {}", self.url);
+        }
+        if !status.is_success() {
+            return Err(HttpStatusError::new("Streamable HTTP", &resp, &self.url).into());
+        }
+        if let Some(session_id) = resp
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            self.set_session_id(Some(session_id.to_string()));
+        }
+        if status == reqwest::StatusCode::ACCEPTED {
+            return Ok(Vec::new());
+        }
+
+        let is_stream = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/event-stream"));
+        if is_stream {
+            return self.read_sse_responses(resp, ids).await;
+        }
+
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .context("Streamable HTTP transport: invalid JSON body")?;
+        ids.iter()
+            .map(|&id| {
+                find_response(body.clone(), id).with_context(|| {
+                    format!(
+                        "Streamable HTTP transport: no JSON-RPC response to request {id} in body"
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// A response with an unsuccessful HTTP status. Kept as its own type so
+/// the client's retry policy can tell rate limits and server errors from
+/// failures that will not go away.
+#[derive(Debug, thiserror::Error)]
+#[error("{transport} transport: HTTP {status} from {url}")]
+pub struct HttpStatusError {
+    transport: &'static str,
+    pub status: reqwest::StatusCode,
+    url: String,
+    /// The server's `Retry-After`, when given in seconds.
+    pub retry_after: Option<std::time::Duration>,
+}
+
+impl HttpStatusError {
+    fn new(transport: &'static str, resp: &reqwest::Response, url: &str) -> Self {
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()?.trim().parse().ok())
+            .map(std::time::Duration::from_secs);
+        Self {
+            transport,
+            status: resp.status(),
+            url: url.to_string(),
+            retry_after,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn for_tests(status: u16, retry_after: Option<std::time::Duration>) -> Self {
+        Self {
+            transport: "test",
+            status: reqwest::StatusCode::from_u16(status).unwrap(),
+            url: "http://mcp.test".into(),
+            retry_after,
+        }
+    }
+}
+
+/// HTTP client for the remote transports, with `headers` (e.g.
+/// `Authorization`) sent on every request and the server's TLS settings.
+/// Without a timeout, requests may run for as long as the server streams.
+fn http_client(
+    timeout_secs: Option<u64>,
+    headers: &HashMap<String, String>,
+    tls: Option<&McpTlsConfig>,
+) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().default_headers(header_map(headers)?);
+    if let Some(timeout_secs) = timeout_secs {
+        builder = builder.timeout(Duration::from_secs(timeout_secs));
+    }
+    if let Some(tls) = tls {
+        builder = tls.apply(builder)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// `headers` from the config, checked.
+fn header_map(headers: &HashMap<String, String>) -> Result<reqwest::header::HeaderMap> {
+    let mut map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        map.insert(
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("invalid header name \"{name}\""))?,
+            reqwest::header::HeaderValue::from_str(value)
+                .with_context(|| format!("invalid value for header \"{name}\""))?,
+        );
+    }
+    Ok(map)
+}
+
+/// Send the request built by `build`, with a bearer token when the server
+/// uses OAuth. A token the server rejects (401) is refreshed and the request
+/// retried once.
+async fn send_authorized(
+    auth: Option<&OAuthSession>,
+    build: impl Fn() -> reqwest::RequestBuilder,
+) -> Result<reqwest::Response> {
+    let Some(auth) = auth else {
+        return Ok(build().send().await?);
+    };
+    let resp = build().bearer_auth(auth.bearer().await?).send().await?;
+    if resp.status() != reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(resp);
+    }
+    auth.invalidate().await;
+    Ok(build().bearer_auth(auth.bearer().await?).send().await?)
+}
+
+/// The response to request `id` among one or more JSON-RPC messages
+/// (a single object or a batch array).
+fn find_response(value: serde_json::Value, id: u64) -> Option<JsonRpcResponse> {
+    let messages = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+    messages
+        .into_iter()
+        .filter_map(|message| serde_json::from_value::<JsonRpcResponse>(message).ok())
+        .find(|rpc| rpc.id == Some(id) && (rpc.result.is_some() || rpc.error.is_some()))
+}
+
+/// Move the responses to `ids` found in `value` (one message or a batch)
+/// into `found`; whether there were any.
+fn collect_responses(
+    value: &serde_json::Value,
+    ids: &[u64],
+    found: &mut HashMap<u64, JsonRpcResponse>,
+) -> bool {
+    let mut any = false;
+    for &id in ids {
+        if let Some(rpc) = find_response(value.clone(), id) {
+            found.insert(id, rpc);
+            any = true;
+        }
+    }
+    any
+}
+
+/// Payload of one SSE event: its `data:` lines joined by newlines.
+fn sse_event_data(event: &str) -> Option<String> {
+    let data: Vec<&str> = event
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!data.is_empty()).then(|| data.join("\n"))
+}
+
+#[async_trait]
+impl McpTransport for StreamableHttpTransport {
+    async fn send(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        // Notifications are acknowledged without a body.
+        Ok(self
+            .exchange(request, &[request.id])
+            .await?
+            .pop()
+            .unwrap_or(JsonRpcResponse {
+                jsonrpc: None,
+                id: None,
+                result: None,
+                error: None,
+            }))
+    }
+
+    async fn send_batch(&self, requests: &[JsonRpcRequest]) -> Result<Vec<JsonRpcResponse>> {
+        let ids: Vec<u64> = requests.iter().map(|request| request.id).collect();
+        let responses = self.exchange(&requests, &ids).await?;
+        if responses.len() != ids.len() {
+            bail!("Streamable HTTP transport: batch acknowledged without responses");
+        }
+        Ok(responses)
+    }
+
+    async fn notify(&self, notification: &JsonRpcNotification) -> Result<()> {
+        self.post_message(notification).await
     }
 
     async fn shutdown(&self) -> Result<()> {
         self.alive.store(false, Ordering::Relaxed);
+        if let Some(session_id) = self.session_id() {
+            // Best effort: servers may not support explicit termination.
+            let _ = send_authorized(self.auth.as_deref(), || {
+                self.client
+                    .delete(&self.url)
+                    .header(SESSION_HEADER, &session_id)
+            })
+            .await;
+            self.set_session_id(None);
+        }
         Ok(())
     }
 
     fn is_alive(&self) -> bool {
         self.alive.load(Ordering::Relaxed)
     }
+
+    fn set_protocol_version(&self, version: &str) {
+        // Only versions that define the header expect it.
+        let header = (version >= "2025-06-18").then(|| version.to_string());
+        *self
+            .protocol_version
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = header;
+    }
+
+    fn on_notification(&self, handler: NotificationHandler) {
+        let _ = self.on_notification.set(handler);
+    }
+
+    fn on_request(&self, handler: RequestHandler) {
+        let _ = self.on_request.set(handler);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+
+    /// Minimal streamable HTTP server: JSON for `initialize` (assigning a
+    /// session), 202 for notifications, SSE for everything else, and 404
+    /// for an unknown session.
+    async fn handle(headers: HeaderMap, Json(body): Json<Value>) -> Response {
+        let session = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok());
+        let id = body["id"].clone();
+        match body["method"].as_str().unwrap_or_default() {
+            "initialize" => (
+                [(SESSION_HEADER, "session-1")],
+                Json(json!({ "jsonrpc": "2.0", "id": id, "result": { "ok": true } })),
+            )
+                .into_response(),
+            _ if session != Some("session-1") => StatusCode::NOT_FOUND.into_response(),
+            "notifications/initialized" => StatusCode::ACCEPTED.into_response(),
+            _ => {
+                let notification = json!({ "jsonrpc": "2.0", "method": "notifications/progress" });
+                let response =
+                    json!({ "jsonrpc": "2.0", "id": id, "result": { "session": session } });
+                (
+                    [(axum::http::header::CONTENT_TYPE, "text/event-stream")],
+                    format!(
+                        "event: message\r\ndata: {notification}\r\n\r\ndata: {response}\r\n\r\n"
+                    ),
+                )
+                    .into_response()
+            }
+        }
+    }
+
+    async fn serve() -> String {
+        let app = Router::new().route("/mcp", post(handle).delete(|| async { StatusCode::OK }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/mcp")
+    }
+
+    #[tokio::test]
+    async fn streamable_http_tracks_session_and_reads_sse_responses() {
+        let url = serve().await;
+        let transport = StreamableHttpTransport::new(&url, 5, &HashMap::new(), None).unwrap();
+
+        let init = transport
+            .send(&JsonRpcRequest::new(1, "initialize", None))
+            .await
+            .unwrap();
+        assert_eq!(init.result.unwrap()["ok"], true);
+        assert_eq!(transport.session_id().as_deref(), Some("session-1"));
+
+        let ack = transport
+            .send(&JsonRpcRequest::new(2, "notifications/initialized", None))
+            .await
+            .unwrap();
+        assert!(ack.result.is_none() && ack.error.is_none());
+
+        let listed = transport
+            .send(&JsonRpcRequest::new(3, "tools/list", None))
+            .await
+            .unwrap();
+        assert_eq!(listed.id, Some(3));
+        assert_eq!(listed.result.unwrap()["session"], "session-1");
+
+        transport.shutdown().await.unwrap();
+        assert!(transport.session_id().is_none());
+        assert!(!transport.is_alive());
+    }
+
+    #[tokio::test]
+    async fn streamable_http_clears_an_expired_session() {
+        let url = serve().await;
+        let transport = StreamableHttpTransport::new(&url, 5, &HashMap::new(), None).unwrap();
+        transport.set_session_id(Some("stale".into()));
+
+        let err = transport
+            .send(&JsonRpcRequest::new(1, "tools/list", None))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("session expired"));
+        assert!(transport.session_id().is_none());
+    }
+
+    #[test]
+    fn batch_bodies_yield_the_matching_response() {
+        let batch = json!([
+            { "jsonrpc": "2.0", "method": "notifications/message" },
+            { "jsonrpc": "2.0", "id": 7, "result": {} },
+            { "jsonrpc": "2.0", "id": 8, "result": { "mine": true } },
+        ]);
+        let rpc = find_response(batch, 8).unwrap();
+        assert_eq!(rpc.result.unwrap()["mine"], true);
+        assert_eq!(
+            sse_event_data("id: 1\ndata: {\"a\":\ndata: 1}"),
+            Some("{\"a\":\n1}".into())
+        );
+    }
+
+    #[tokio::test]
+    async fn stdio_notifications_between_requests_are_polled() {
+        let transport = StdioTransport::spawn(
+            "test",
+            "sh",
+            &[
+                "-c".into(),
+                r#"echo '{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}'; sleep 1"#
+                    .into(),
+            ],
+            &HashMap::new(),
+            false,
+        )
+        .unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        let sink = Arc::clone(&seen);
+        transport.on_notification(Arc::new(move |method, _| {
+            sink.lock().unwrap().push(method.to_string());
+        }));
+
+        for _ in 0..50 {
+            transport.poll_notifications().await;
+            if !seen.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(*seen.lock().unwrap(), ["notifications/tools/list_changed"]);
+        transport.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stdio_server_requests_are_answered_before_the_response() {
+        // Asks us something, then echoes our reply back as its response.
+        let script = r#"read request
+echo '{"jsonrpc":"2.0","id":"s1","method":"sampling/createMessage","params":{"n":2}}'
+read reply
+echo "{\"jsonrpc\":\"2.0\",\"id\":7,\"result\":$reply}""#;
+        let transport = StdioTransport::spawn(
+            "test",
+            "sh",
+            &["-c".into(), script.into()],
+            &HashMap::new(),
+            false,
+        )
+        .unwrap();
+        transport.on_request(Arc::new(|method, params| {
+            Box::pin(async move {
+                Ok(json!({ "method": method, "doubled": params["n"].as_i64().unwrap() * 2 }))
+            })
+        }));
+
+        let response = transport
+            .send(&JsonRpcRequest::new(7, "tools/call", None))
+            .await
+            .unwrap();
+        let reply = response.result.unwrap();
+        assert_eq!(reply["id"], "s1");
+        assert_eq!(reply["result"]["method"], "sampling/createMessage");
+        assert_eq!(reply["result"]["doubled"], 4);
+        transport.shutdown().await.unwrap();
+    }
+
+    /// Events of the SSE test server: what it sent on the stream, and the
+    /// `Last-Event-ID` of each GET.
+    #[derive(Default)]
+    struct SseServer {
+        responses: std::sync::Mutex<Vec<String>>,
+        resumed_from: std::sync::Mutex<Vec<Option<String>>>,
+        added: tokio::sync::Notify,
+    }
+
+    impl SseServer {
+        async fn wait_for_responses(&self, count: usize) {
+            loop {
+                let added = self.added.notified();
+                if self.responses.lock().unwrap().len() >= count {
+                    return;
+                }
+                added.await;
+            }
+        }
+
+        /// Response `n` (1-based) as an SSE event with id `n`.
+        fn event(&self, n: usize) -> String {
+            let data = &self.responses.lock().unwrap()[n - 1];
+            format!("id: {n}\nevent: message\ndata: {data}\n\n")
+        }
+    }
+
+    /// Legacy SSE server: the first stream drops right after the first
+    /// response; a reopened stream replays what came after `Last-Event-ID`.
+    async fn sse_stream(
+        axum::extract::State(server): axum::extract::State<Arc<SseServer>>,
+        headers: HeaderMap,
+    ) -> Response {
+        use futures::StreamExt;
+
+        let last = headers
+            .get("Last-Event-ID")
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        server.resumed_from.lock().unwrap().push(last.clone());
+        let resumed = last.is_some();
+        let endpoint = "retry: 10\n\nevent: endpoint\ndata: /messages?session=1\n\n: keepalive\n\n";
+        let replay = futures::stream::once(async move {
+            match last {
+                None => {
+                    server.wait_for_responses(1).await;
+                    server.event(1)
+                }
+                Some(last) => {
+                    server.wait_for_responses(2).await;
+                    let from: usize = last.parse().unwrap();
+                    let sent = server.responses.lock().unwrap().len();
+                    (from + 1..=sent).map(|n| server.event(n)).collect()
+                }
+            }
+        });
+        let body = futures::stream::iter([endpoint.to_string()]).chain(replay);
+        // The first stream ends after its event; a reopened one stays open.
+        let body = if resumed {
+            body.chain(futures::stream::pending()).boxed()
+        } else {
+            body.boxed()
+        };
+        (
+            [(axum::http::header::CONTENT_TYPE, "text/event-stream")],
+            axum::body::Body::from_stream(body.map(Ok::<_, std::convert::Infallible>)),
+        )
+            .into_response()
+    }
+
+    async fn sse_message(
+        axum::extract::State(server): axum::extract::State<Arc<SseServer>>,
+        Json(body): Json<Value>,
+    ) -> StatusCode {
+        if let Some(id) = body.get("id") {
+            let response =
+                json!({ "jsonrpc": "2.0", "id": id, "result": { "echo": body["method"] } });
+            server.responses.lock().unwrap().push(response.to_string());
+            server.added.notify_waiters();
+        }
+        StatusCode::ACCEPTED
+    }
+
+    #[tokio::test]
+    async fn sse_stream_is_resumed_with_last_event_id() {
+        let server = Arc::new(SseServer::default());
+        let app = Router::new()
+            .route("/sse", axum::routing::get(sse_stream))
+            .route("/messages", post(sse_message))
+            .with_state(Arc::clone(&server));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let transport =
+            SseTransport::new(&format!("http://{addr}/sse"), 5, &HashMap::new(), None).unwrap();
+        let send = |id, method| {
+            let request = JsonRpcRequest::new(id, method, None);
+            let transport = &transport;
+            async move {
+                tokio::time::timeout(Duration::from_secs(10), transport.send(&request))
+                    .await
+                    .unwrap()
+                    .unwrap()
+            }
+        };
+        assert_eq!(
+            send(1, "ping").await.result,
+            Some(json!({ "echo": "ping" }))
+        );
+        // Sent while the stream is down; its response is replayed.
+        assert_eq!(
+            send(2, "tools/list").await.result,
+            Some(json!({ "echo": "tools/list" }))
+        );
+        assert_eq!(
+            *server.resumed_from.lock().unwrap(),
+            [None, Some("1".to_string())]
+        );
+        // Same endpoint after the reconnect: the session was kept.
+        assert_eq!(transport.generation(), 0);
+        transport.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sse_endpoint_on_another_origin_is_rejected() {
+        let app = Router::new().route(
+            "/sse",
+            axum::routing::get(|| async {
+                (
+                    [(axum::http::header::CONTENT_TYPE, "text/event-stream")],
+                    "event: endpoint\ndata: http://evil.example/messages\n\n",
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let transport =
+            SseTransport::new(&format!("http://{addr}/sse"), 5, &HashMap::new(), None).unwrap();
+        let err = transport
+            .send(&JsonRpcRequest::new(1, "initialize", None))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("not on the server's origin"),
+            "{err:#}"
+        );
+    }
+
+    #[tokio::test]
+    async fn sse_without_a_stream_posts_to_the_url() {
+        let url = serve().await;
+        let transport = SseTransport::new(&url, 5, &HashMap::new(), None).unwrap();
+        let response = transport
+            .send(&JsonRpcRequest::new(1, "initialize", None))
+            .await
+            .unwrap();
+        assert_eq!(response.result, Some(json!({ "ok": true })));
+    }
+
+    #[test]
+    fn sse_buffer_waits_for_whole_events() {
+        let mut buffer = SseBuffer::new("test");
+        let data = "data: {\"text\":\"héllo\"}\r\n\r\n".as_bytes();
+        // Split inside the two-byte é and inside the closing \r\n\r\n
+        let split_char = data.iter().position(|&b| b >= 0x80).unwrap() + 1;
+        let split_end = data.len() - 3;
+        buffer.push(&data[..split_char]).unwrap();
+        assert_eq!(buffer.next_event(), None);
+        buffer.push(&data[split_char..split_end]).unwrap();
+        assert_eq!(buffer.next_event(), None);
+        buffer.push(&data[split_end..]).unwrap();
+        assert_eq!(
+            buffer.next_event().as_deref(),
+            Some("data: {\"text\":\"héllo\"}\n\n")
+        );
+
+        buffer
+            .push(b"event: a\rdata: 1\r\rdata: 2\n\ndata: tail")
+            .unwrap();
+        assert_eq!(
+            buffer.next_event().as_deref(),
+            Some("event: a\ndata: 1\n\n")
+        );
+        assert_eq!(buffer.next_event().as_deref(), Some("data: 2\n\n"));
+        assert_eq!(buffer.next_event(), None);
+        assert_eq!(buffer.rest(), "data: tail");
+
+        let mut small = SseBuffer {
+            limit: 8,
+            ..SseBuffer::new("test")
+        };
+        assert!(small
+            .push(b"data: 123456789")
+            .unwrap_err()
+            .to_string()
+            .contains("exceeded 8 bytes"));
+    }
+
+    #[test]
+    fn sse_events_skip_comments() {
+        assert_eq!(
+            parse_sse_event(": keepalive\nid: 7\nevent: message\ndata: a\ndata:b\nretry: 500\n"),
+            SseEvent {
+                id: Some("7".into()),
+                event: Some("message".into()),
+                data: Some("a\nb".into()),
+                retry: Some(Duration::from_millis(500)),
+            }
+        );
+        assert_eq!(parse_sse_event(": ping\n"), SseEvent::default());
+    }
+
+    #[test]
+    fn invalid_headers_are_rejected() {
+        let headers = HashMap::from([("Bad Header".to_string(), "x".to_string())]);
+        assert!(StreamableHttpTransport::new("http://localhost/mcp", 5, &headers, None).is_err());
+    }
 }
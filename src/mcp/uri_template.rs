@@ -0,0 +1,166 @@
+//! URI templates (RFC 6570) of MCP resource templates, e.g.
+//! `weather://{city}/forecast{?days}`.
+//!
+//! Supports the operators `+ # . / ; ? &` with string values; the prefix
+//! (`:N`) and explode (`*`) modifiers are accepted and ignored.
+
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+/// A template variable, optional when it sits in a query (`{?x}`, `{&x}`).
+#[derive(Debug, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub required: bool,
+}
+
+/// The `{...}` expressions of `template` as (operator, variable names).
+fn expressions(template: &str) -> impl Iterator<Item = (Option<char>, Vec<&str>)> {
+    template.split('{').skip(1).filter_map(|rest| {
+        let (expression, _) = rest.split_once('}')?;
+        let operator = expression.chars().next().filter(|c| "+#./;?&".contains(*c));
+        let names = expression[operator.map_or(0, char::len_utf8)..]
+            .split(',')
+            .map(|name| name.split([':', '*']).next().unwrap_or_default().trim())
+            .filter(|name| !name.is_empty())
+            .collect();
+        Some((operator, names))
+    })
+}
+
+/// Variables of `template` in order of first appearance.
+pub fn variables(template: &str) -> Vec<Variable> {
+    let mut variables: Vec<Variable> = Vec::new();
+    for (operator, names) in expressions(template) {
+        for name in names {
+            if !variables.iter().any(|v| v.name == name) {
+                variables.push(Variable {
+                    name: name.to_string(),
+                    required: !matches!(operator, Some('?' | '&')),
+                });
+            }
+        }
+    }
+    variables
+}
+
+/// `template` with its expressions replaced by the values in `args`.
+/// Missing variables expand to nothing; non-string values are used as JSON.
+pub fn expand(template: &str, args: &Map<String, Value>) -> String {
+    let mut uri = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        uri.push_str(&rest[..start]);
+        let expression = &rest[start..=start + len];
+        if let Some((operator, names)) = expressions(expression).next() {
+            expand_expression(&mut uri, operator, &names, args);
+        }
+        rest = &rest[start + len + 1..];
+    }
+    uri.push_str(rest);
+    uri
+}
+
+fn expand_expression(
+    uri: &mut String,
+    operator: Option<char>,
+    names: &[&str],
+    args: &Map<String, Value>,
+) {
+    let (first, separator, named, reserved) = match operator {
+        None => ("", ",", false, false),
+        Some('+') => ("", ",", false, true),
+        Some('#') => ("#", ",", false, true),
+        Some('.') => (".", ".", false, false),
+        Some('/') => ("/", "/", false, false),
+        Some(';') => (";", ";", true, false),
+        Some('?') => ("?", "&", true, false),
+        _ => ("&", "&", true, false),
+    };
+    let values: Vec<(&str, String)> = names
+        .iter()
+        .filter_map(|name| {
+            let value = match args.get(*name)? {
+                Value::Null => return None,
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            Some((*name, encode(&value, reserved)))
+        })
+        .collect();
+    for (i, (name, value)) in values.iter().enumerate() {
+        uri.push_str(if i == 0 { first } else { separator });
+        if named {
+            uri.push_str(name);
+            if !value.is_empty() || operator != Some(';') {
+                uri.push('=');
+            }
+        }
+        uri.push_str(value);
+    }
+}
+
+/// Percent-encode `value`, keeping unreserved characters and, for `+` and
+/// `#` expressions, reserved ones.
+fn encode(value: &str, reserved: bool) -> String {
+    let mut encoded = String::new();
+    for c in value.chars() {
+        let keep = c.is_ascii_alphanumeric()
+            || "-._~".contains(c)
+            || (reserved && ":/?#[]@!$&'()*+,;=".contains(c));
+        if keep {
+            encoded.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn templates_expand_with_their_variables() {
+        let template = "weather://{city}/forecast{/unit}{?days,lang}";
+        assert_eq!(
+            variables(template),
+            [
+                Variable {
+                    name: "city".into(),
+                    required: true
+                },
+                Variable {
+                    name: "unit".into(),
+                    required: true
+                },
+                Variable {
+                    name: "days".into(),
+                    required: false
+                },
+                Variable {
+                    name: "lang".into(),
+                    required: false
+                },
+            ]
+        );
+        let args = json!({ "city": "São Paulo", "unit": "c", "days": 3 });
+        assert_eq!(
+            expand(template, args.as_object().unwrap()),
+            "weather://S%C3%A3o%20Paulo/forecast/c?days=3"
+        );
+        let args = json!({ "path": "docs/a b.md" });
+        assert_eq!(
+            expand("file:///{+path}", args.as_object().unwrap()),
+            "file:///docs/a%20b.md"
+        );
+    }
+}
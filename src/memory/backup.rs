@@ -0,0 +1,253 @@
+//! Point-in-time copies of `brain.db`.
+//!
+//! Backups are written with SQLite's online backup API, so they are
+//! consistent while the agent keeps writing, and land in
+//! `memory.backup_dir` as `brain-<time>.db`. `zeroclaw daemon` takes one on
+//! `memory.backup_schedule` and keeps the newest `memory.backup_keep`.
+//! `zeroclaw memory backup` takes one now; `zeroclaw memory restore
+//! --backup FILE` copies one back over `brain.db`. With
+//! `memory.encryption_key` set, backups are encrypted with the same key.
+
+use super::cipher;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use rusqlite::backup::Backup;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Pages copied per backup step; the source is unlocked between steps
+const PAGES_PER_STEP: std::ffi::c_int = 256;
+
+fn database_path(config: &Config) -> PathBuf {
+    config.workspace_dir.join("memory").join("brain.db")
+}
+
+/// `memory.backup_dir`, relative to the workspace, or
+/// `<workspace>/memory/backups`
+pub fn backup_dir(config: &Config) -> PathBuf {
+    match &config.memory.backup_dir {
+        Some(dir) => config.workspace_dir.join(dir),
+        None => config.workspace_dir.join("memory").join("backups"),
+    }
+}
+
+/// When `schedule` (a cron expression in local time) next fires after `from`
+pub fn next_backup(schedule: &str, from: DateTime<Local>) -> Result<DateTime<Local>> {
+    let normalized = crate::cron::normalize_expression(schedule)?;
+    let cron = ::cron::Schedule::from_str(&normalized)
+        .with_context(|| format!("Invalid cron expression: {schedule}"))?;
+    cron.after(&from)
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No future occurrence for expression: {schedule}"))
+}
+
+/// Open `path`, keyed with `memory.encryption_key` when one is set
+fn open(path: &Path, key: Option<&str>) -> Result<Connection> {
+    let conn =
+        Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    if let Some(key) = key {
+        cipher::unlock(&conn, path, key)?;
+    }
+    Ok(conn)
+}
+
+fn encryption_key(config: &Config) -> Result<Option<String>> {
+    config
+        .memory
+        .encryption_key
+        .as_deref()
+        .map(cipher::resolve_key)
+        .transpose()
+}
+
+fn copy(from: &Connection, to: &mut Connection) -> Result<()> {
+    Backup::new(from, to)?.run_to_completion(PAGES_PER_STEP, Duration::from_millis(10), None)?;
+    Ok(())
+}
+
+/// Copy `brain.db` into the backup directory without pruning old backups
+fn snapshot(config: &Config, key: Option<&str>) -> Result<PathBuf> {
+    let source = database_path(config);
+    if !source.exists() {
+        anyhow::bail!("{} does not exist yet", source.display());
+    }
+    let dir = backup_dir(config);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let mut target = dir.join(format!("brain-{stamp}.db"));
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("brain-{stamp}-{n}.db"));
+        n += 1;
+    }
+
+    let from = open(&source, key)?;
+    let mut to = open(&target, key)?;
+    if let Err(e) = copy(&from, &mut to) {
+        drop(to);
+        let _ = std::fs::remove_file(&target);
+        return Err(e.context(format!("Failed to back up {}", source.display())));
+    }
+    Ok(target)
+}
+
+/// Back up `brain.db` now, then delete all but the newest
+/// `memory.backup_keep` backups. Returns the new backup.
+pub fn create(config: &Config) -> Result<PathBuf> {
+    let key = encryption_key(config)?;
+    let target = snapshot(config, key.as_deref())?;
+    let keep = config.memory.backup_keep;
+    if keep > 0 {
+        for old in list(config)?.into_iter().skip(keep) {
+            std::fs::remove_file(&old)
+                .with_context(|| format!("Failed to delete old backup {}", old.display()))?;
+        }
+    }
+    Ok(target)
+}
+
+/// Backups in the backup directory, newest first
+pub fn list(config: &Config) -> Result<Vec<PathBuf>> {
+    let dir = backup_dir(config);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("brain-") && name.ends_with(".db"))
+        })
+        .collect();
+    // The timestamp in the name sorts chronologically; compare stems so
+    // `brain-<time>-1` sorts after `brain-<time>`
+    backups.sort_by(|a, b| b.file_stem().cmp(&a.file_stem()));
+    Ok(backups)
+}
+
+/// Replace `brain.db` with the backup `file`, given as a path or as a name
+/// in the backup directory. The current database is backed up first;
+/// returns that copy, if there was a database to copy.
+pub fn restore(config: &Config, file: &Path) -> Result<Option<PathBuf>> {
+    let source = if file.exists() {
+        file.to_path_buf()
+    } else {
+        backup_dir(config).join(file)
+    };
+    if !source.exists() {
+        anyhow::bail!("No backup at {}", source.display());
+    }
+    let key = encryption_key(config)?;
+
+    let from = open(&source, key.as_deref())?;
+    let has_memories = from
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'memories'",
+            [],
+            |_| Ok(()),
+        )
+        .is_ok();
+    if !has_memories {
+        anyhow::bail!("{} is not a memory database", source.display());
+    }
+    let version = crate::db::current_version(&from)?;
+    let latest = super::sqlite::MIGRATIONS.last().map_or(0, |m| m.version);
+    if version > latest {
+        anyhow::bail!(
+            "{} is at schema version {version} but this zeroclaw only knows up to {latest}; \
+             upgrade zeroclaw to restore it",
+            source.display()
+        );
+    }
+
+    let target = database_path(config);
+    let previous = if target.exists() {
+        Some(snapshot(config, key.as_deref())?)
+    } else {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        None
+    };
+    let mut to = open(&target, key.as_deref())?;
+    copy(&from, &mut to).with_context(|| format!("Failed to restore {}", source.display()))?;
+    Ok(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn config(tmp: &TempDir) -> Config {
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.memory.backup_keep = 2;
+        config
+    }
+
+    #[tokio::test]
+    async fn backups_rotate_and_restore() {
+        let tmp = TempDir::new().unwrap();
+        let config = config(&tmp);
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("before", "kept in the backup", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let first = create(&config).unwrap();
+        create(&config).unwrap();
+        create(&config).unwrap();
+        let backups = list(&config).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(!backups.contains(&first), "oldest backup is pruned");
+
+        mem.store("after", "not in the backup", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let name = backups[1].file_name().unwrap();
+        let previous = restore(&config, Path::new(name)).unwrap().unwrap();
+        drop(mem);
+
+        let restored = SqliteMemory::new(tmp.path()).unwrap();
+        assert!(restored.get("before").await.unwrap().is_some());
+        assert!(restored.get("after").await.unwrap().is_none());
+        let kept = open(&previous, None).unwrap();
+        let count: i64 = kept
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn restore_rejects_other_databases() {
+        let tmp = TempDir::new().unwrap();
+        let config = config(&tmp);
+        let other = tmp.path().join("other.db");
+        Connection::open(&other)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (body TEXT);")
+            .unwrap();
+
+        let err = restore(&config, &other).unwrap_err();
+        assert!(err.to_string().contains("not a memory database"));
+    }
+
+    #[test]
+    fn schedule_uses_cron_syntax() {
+        let from = Local::now();
+        let next = next_backup("0 3 * * *", from).unwrap();
+        assert!(next > from);
+        assert_eq!(next.format("%H:%M").to_string(), "03:00");
+        assert!(next_backup("daily", from).is_err());
+    }
+}
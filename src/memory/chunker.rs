@@ -106,6 +106,39 @@ pub fn chunk_markdown(text: &str, max_tokens: usize) -> Vec<Chunk> {
     chunks
 }
 
+/// Split text without headings (plain text, source code) into chunks under
+/// `max_tokens`: paragraphs are packed together, and paragraphs too long
+/// for one chunk are split on lines. `#` lines are content, not headings.
+pub fn chunk_plain(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    let max_chars = max_tokens * 4;
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for para in split_on_blank_lines(text) {
+        if current.len() + para.len() > max_chars && !current.trim().is_empty() {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if para.len() > max_chars {
+            pieces.extend(split_on_lines(&para, max_chars));
+        } else {
+            current.push_str(&para);
+            current.push('\n');
+        }
+    }
+    pieces.push(current);
+
+    pieces
+        .iter()
+        .map(|piece| piece.trim_end())
+        .filter(|piece| !piece.trim().is_empty())
+        .enumerate()
+        .map(|(index, content)| Chunk {
+            index,
+            content: content.to_string(),
+            heading: None,
+        })
+        .collect()
+}
+
 /// Split text into `(heading, body)` sections.
 fn split_on_headings(text: &str) -> Vec<(Option<String>, String)> {
     let mut sections = Vec::new();
@@ -357,6 +390,22 @@ mod tests {
         assert_eq!(chunks[0].heading.as_deref(), Some("# Just a heading"));
     }
 
+    #[test]
+    fn plain_chunks_keep_hash_lines_as_content() {
+        let code =
+            "# config loader\nimport os\n\n\n# read it\ndef load():\n    return os.environ\n";
+        let chunks = chunk_plain(code, 512);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].heading.is_none());
+        assert!(chunks[0].content.contains("    return os.environ"));
+
+        let long = "line of text\n".repeat(100);
+        let chunks = chunk_plain(&long, 20);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.content.len() <= 80));
+        assert!(chunk_plain("  \n\n", 512).is_empty());
+    }
+
     #[test]
     fn no_content_loss() {
         let text = "# A\nContent A line 1\nContent A line 2\n\n## B\nContent B\n\n## C\nContent C";
@@ -0,0 +1,218 @@
+//! Encryption at rest for `brain.db` (SQLCipher).
+//!
+//! With `memory.encryption_key` set, the SQLite backend opens the database
+//! with that key; builds need the `sqlcipher` feature. The key comes from an
+//! environment variable (`env:ZEROCLAW_MEMORY_KEY`) or the OS keyring
+//! (`keyring:zeroclaw/memory`, needs the `keyring` feature).
+//! `zeroclaw memory encrypt` converts an existing plaintext database.
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+const NO_SQLCIPHER: &str = "this build has no SQLCipher support (build with --features sqlcipher)";
+
+/// First bytes of every unencrypted SQLite database
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Where the key comes from, parsed from `memory.encryption_key`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource<'a> {
+    Env(&'a str),
+    Keyring(&'a str),
+}
+
+impl<'a> KeySource<'a> {
+    pub fn parse(source: &'a str) -> Result<Self> {
+        if let Some(var) = source.strip_prefix("env:").filter(|var| !var.is_empty()) {
+            return Ok(Self::Env(var));
+        }
+        if let Some(entry) = source.strip_prefix("keyring:") {
+            if crate::mcp::config::keyring_entry(entry).is_some() {
+                return Ok(Self::Keyring(entry));
+            }
+        }
+        anyhow::bail!(
+            "\"{source}\" is not a key source (expected env:VAR or keyring:service/account)"
+        )
+    }
+
+    /// Read the key
+    pub fn resolve(&self) -> Result<String> {
+        let key = match self {
+            Self::Env(var) => std::env::var(var)
+                .with_context(|| format!("memory encryption key: ${var} is not set"))?,
+            Self::Keyring(entry) => keyring_password(entry)?,
+        };
+        if key.is_empty() {
+            anyhow::bail!("memory encryption key is empty");
+        }
+        Ok(key)
+    }
+}
+
+/// Resolve `memory.encryption_key` to the key itself
+pub fn resolve_key(source: &str) -> Result<String> {
+    KeySource::parse(source)?.resolve()
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_password(entry: &str) -> Result<String> {
+    let (service, account) = crate::mcp::config::keyring_entry(entry)
+        .ok_or_else(|| anyhow::anyhow!("\"{entry}\" is not a service/account entry"))?;
+    let key = keyring::Entry::new(service, account)?;
+    // The Secret Service backend runs its own executor, which must not start
+    // on a runtime thread; memory is opened from sync code on those threads.
+    std::thread::spawn(move || key.get_password())
+        .join()
+        .map_err(|_| anyhow::anyhow!("keyring lookup panicked"))?
+        .with_context(|| format!("cannot read \"{entry}\" from the OS keyring"))
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_password(_entry: &str) -> Result<String> {
+    anyhow::bail!("this build has no keyring support (build with --features keyring)")
+}
+
+/// Whether `path` is an unencrypted SQLite database
+pub fn is_plaintext(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+        .is_ok_and(|()| &header == PLAINTEXT_HEADER)
+}
+
+/// Key a freshly opened connection; must run before any other statement.
+pub fn unlock(conn: &Connection, path: &Path, key: &str) -> Result<()> {
+    if !cfg!(feature = "sqlcipher") {
+        anyhow::bail!(NO_SQLCIPHER);
+    }
+    if is_plaintext(path) {
+        anyhow::bail!(
+            "{} is not encrypted yet; run `zeroclaw memory encrypt` first",
+            path.display()
+        );
+    }
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .with_context(|| {
+            format!(
+                "cannot decrypt {} with memory.encryption_key",
+                path.display()
+            )
+        })
+}
+
+/// Encrypt the plaintext database at `path` with `key` in place. The
+/// plaintext original is kept next to it; returns where.
+pub fn encrypt_database(path: &Path, key: &str) -> Result<PathBuf> {
+    if !cfg!(feature = "sqlcipher") {
+        anyhow::bail!(NO_SQLCIPHER);
+    }
+    if !is_plaintext(path) {
+        anyhow::bail!("{} does not exist or is already encrypted", path.display());
+    }
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "database".into());
+    let encrypted = path.with_file_name(format!("{file_name}.encrypting"));
+    let _ = std::fs::remove_file(&encrypted);
+
+    {
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        // Fold the WAL into the main file so the export sees everything
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![encrypted.to_string_lossy(), key],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))
+            .context("Failed to write the encrypted copy")?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+    }
+
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let plaintext = path.with_file_name(format!("{file_name}.plaintext-{stamp}.bak"));
+    std::fs::rename(path, &plaintext)?;
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(path.with_file_name(format!("{file_name}{suffix}")));
+    }
+    std::fs::rename(&encrypted, path)?;
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn key_sources_parse_and_resolve() {
+        assert_eq!(
+            KeySource::parse("env:ZC_TEST_MEMORY_KEY").unwrap(),
+            KeySource::Env("ZC_TEST_MEMORY_KEY")
+        );
+        assert_eq!(
+            KeySource::parse("keyring:zeroclaw/memory").unwrap(),
+            KeySource::Keyring("zeroclaw/memory")
+        );
+        for bad in ["hunter2", "env:", "keyring:zeroclaw"] {
+            assert!(KeySource::parse(bad).is_err(), "{bad}");
+        }
+
+        std::env::set_var("ZC_TEST_MEMORY_KEY", "s3cret");
+        assert_eq!(resolve_key("env:ZC_TEST_MEMORY_KEY").unwrap(), "s3cret");
+        std::env::remove_var("ZC_TEST_MEMORY_KEY");
+        assert!(resolve_key("env:ZC_TEST_MEMORY_KEY").is_err());
+    }
+
+    #[test]
+    fn plaintext_detection() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.db");
+        assert!(!is_plaintext(&path));
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x)")
+            .unwrap();
+        assert!(is_plaintext(&path));
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[test]
+    fn keys_need_the_sqlcipher_feature() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.db");
+        let conn = Connection::open(&path).unwrap();
+        let err = unlock(&conn, &path, "k").unwrap_err();
+        assert!(err.to_string().contains("--features sqlcipher"));
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[test]
+    fn encrypt_then_unlock() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("brain.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "PRAGMA journal_mode = WAL; CREATE TABLE t (x); INSERT INTO t VALUES ('secret')",
+            )
+            .unwrap();
+        }
+
+        let plaintext = encrypt_database(&path, "k").unwrap();
+        assert!(is_plaintext(&plaintext));
+        assert!(!is_plaintext(&path));
+        assert!(encrypt_database(&path, "k").is_err());
+
+        let conn = Connection::open(&path).unwrap();
+        assert!(unlock(&conn, &path, "wrong").is_err());
+        let conn = Connection::open(&path).unwrap();
+        unlock(&conn, &path, "k").unwrap();
+        let x: String = conn.query_row("SELECT x FROM t", [], |r| r.get(0)).unwrap();
+        assert_eq!(x, "secret");
+    }
+}
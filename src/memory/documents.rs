@@ -0,0 +1,629 @@
+//! Local files ingested for retrieval.
+//!
+//! `zeroclaw ingest <path>` reads Markdown, plain text, source code and
+//! (with `--features rag-pdf`) PDF files, splits them into chunks of up to
+//! `memory.chunk_max_tokens`, embeds the chunks with the memory embedder and
+//! stores them in the `documents` and `document_chunks` tables of
+//! `brain.db`. The `docs_search` tool searches those chunks with the same
+//! keyword and vector fusion as `recall`, and cites each hit by file and line.
+//! Chat memories never mix with documents.
+
+use super::chunker::{self, Chunk};
+use super::cipher;
+use super::embeddings::EmbeddingProvider;
+use super::vector;
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Files larger than this are skipped
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Extensions read as text when walking a directory. A file named on the
+/// command line is read whatever its extension, if it is UTF-8.
+const TEXT_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "mdx", "txt", "rst", "org", "adoc", "tex", "rs", "py", "js", "mjs", "ts",
+    "tsx", "jsx", "go", "java", "kt", "c", "h", "cc", "cpp", "hpp", "cs", "rb", "php", "swift",
+    "scala", "sh", "bash", "zsh", "fish", "lua", "sql", "html", "css", "scss", "vue", "svelte",
+    "toml", "yaml", "yml", "json", "ini", "cfg", "proto", "ex", "exs", "hs", "ml", "r", "jl",
+    "dart", "zig", "nix",
+];
+
+/// Directories never walked into
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "__pycache__", "dist"];
+
+/// One ingested chunk found by [`DocumentStore::search`]
+#[derive(Debug, Clone)]
+pub struct DocumentHit {
+    /// Absolute path of the file
+    pub source: String,
+    /// First line of the chunk in the file, 1-based; `None` for PDFs
+    pub line: Option<usize>,
+    /// Markdown heading the chunk sits under
+    pub heading: Option<String>,
+    pub content: String,
+    pub score: f64,
+}
+
+impl DocumentHit {
+    /// `path:line`, or just the path when the line is unknown
+    pub fn citation(&self) -> String {
+        match self.line {
+            Some(line) => format!("{}:{line}", self.source),
+            None => self.source.clone(),
+        }
+    }
+}
+
+/// An ingested file, as listed by `zeroclaw ingest --list`
+#[derive(Debug, Clone)]
+pub struct DocumentInfo {
+    pub source: String,
+    pub chunks: usize,
+    pub ingested_at: String,
+}
+
+/// What one `zeroclaw ingest` run did
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    /// Files (re)ingested, with their chunk counts
+    pub ingested: Vec<(String, usize)>,
+    /// Files whose content had not changed since they were last ingested
+    pub unchanged: usize,
+    /// Files that could not be read or embedded, with the reason
+    pub skipped: Vec<(String, String)>,
+    /// Files ingested before from the walked directory that no longer exist
+    pub removed: usize,
+}
+
+/// The ingested documents in `brain.db`
+pub struct DocumentStore {
+    conn: Mutex<Connection>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    vector_weight: f32,
+    keyword_weight: f32,
+    rrf_k: f32,
+}
+
+impl DocumentStore {
+    /// Open `<workspace>/memory/brain.db`, keyed with `key` when the database
+    /// is encrypted, and bring its schema up to date
+    pub fn open(
+        workspace_dir: &Path,
+        embedder: Arc<dyn EmbeddingProvider>,
+        key: Option<&str>,
+    ) -> Result<Self> {
+        let db_path = workspace_dir.join("memory").join("brain.db");
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open {}", db_path.display()))?;
+        if let Some(key) = key {
+            cipher::unlock(&conn, &db_path, key)?;
+        }
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;")?;
+        crate::db::migrate(&conn, &db_path, super::sqlite::MIGRATIONS)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+            embedder,
+            vector_weight: 0.7,
+            keyword_weight: 0.3,
+            rrf_k: vector::DEFAULT_RRF_K,
+        })
+    }
+
+    /// Weigh vector and keyword rankings as `recall` does
+    #[must_use]
+    pub fn with_weights(mut self, vector_weight: f32, keyword_weight: f32, rrf_k: f32) -> Self {
+        self.vector_weight = vector_weight;
+        self.keyword_weight = keyword_weight;
+        self.rrf_k = rrf_k;
+        self
+    }
+
+    fn content_hash(text: &str) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(text.as_bytes()))
+    }
+
+    /// Store `text` as the document `source`, replacing an earlier version.
+    /// Returns the number of chunks, or `None` when `text` is unchanged.
+    pub async fn ingest_text(
+        &self,
+        source: &str,
+        text: &str,
+        markdown: bool,
+        with_lines: bool,
+        max_tokens: usize,
+    ) -> Result<Option<usize>> {
+        let hash = Self::content_hash(text);
+        let stored: Option<String> = self
+            .conn
+            .lock()
+            .query_row(
+                "SELECT content_hash FROM documents WHERE source = ?1",
+                params![source],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if stored.as_deref() == Some(hash.as_str()) {
+            return Ok(None);
+        }
+
+        let chunks = if markdown {
+            chunker::chunk_markdown(text, max_tokens)
+        } else {
+            chunker::chunk_plain(text, max_tokens)
+        };
+        let lines = if with_lines {
+            start_lines(text, &chunks).into_iter().map(Some).collect()
+        } else {
+            vec![None; chunks.len()]
+        };
+        // Embed before touching the database, so a failing embedder leaves
+        // the previous version in place
+        let embeddings = if self.embedder.dimensions() == 0 || chunks.is_empty() {
+            vec![None; chunks.len()]
+        } else {
+            let texts: Vec<&str> = chunks.iter().map(|c| c.content.as_str()).collect();
+            let embedded = self.embedder.embed(&texts).await?;
+            if embedded.len() != chunks.len() {
+                anyhow::bail!(
+                    "embedder returned {} vectors for {} chunks",
+                    embedded.len(),
+                    chunks.len()
+                );
+            }
+            embedded.into_iter().map(Some).collect()
+        };
+
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM documents WHERE source = ?1", params![source])?;
+        tx.execute(
+            "INSERT INTO documents (source, content_hash, ingested_at) VALUES (?1, ?2, ?3)",
+            params![source, hash, Utc::now().to_rfc3339()],
+        )?;
+        let document_id = tx.last_insert_rowid();
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO document_chunks
+                     (document_id, chunk_index, heading, line, content, embedding)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for ((chunk, line), embedding) in chunks.iter().zip(&lines).zip(&embeddings) {
+                insert.execute(params![
+                    document_id,
+                    i64::try_from(chunk.index)?,
+                    chunk.heading,
+                    line.map(i64::try_from).transpose()?,
+                    chunk.content,
+                    embedding.as_deref().map(vector::vec_to_bytes),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(Some(chunks.len()))
+    }
+
+    /// Read and store the file at `path` (absolute). Returns the number of
+    /// chunks, or `None` when it is unchanged.
+    pub async fn ingest_file(&self, path: &Path, max_tokens: usize) -> Result<Option<usize>> {
+        let size = std::fs::metadata(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+            .len();
+        if size > MAX_FILE_BYTES {
+            anyhow::bail!("larger than {} MB", MAX_FILE_BYTES / (1024 * 1024));
+        }
+        let extension = extension(path);
+        let source = path.display().to_string();
+        if extension == "pdf" {
+            let text = read_pdf(path)?;
+            return self
+                .ingest_text(&source, &text, false, false, max_tokens)
+                .await;
+        }
+        let bytes = std::fs::read(path)?;
+        if bytes.contains(&0) {
+            anyhow::bail!("binary file");
+        }
+        let text = String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("not UTF-8 text"))?;
+        let markdown = matches!(extension.as_str(), "md" | "markdown" | "mdx");
+        self.ingest_text(&source, &text, markdown, true, max_tokens)
+            .await
+    }
+
+    /// Remove the document `source`, or every document under it when it
+    /// is a directory. Returns how many were removed.
+    pub fn forget(&self, source: &str) -> Result<usize> {
+        let conn = self.conn.lock();
+        let prefix = format!(
+            "{}{}",
+            source.trim_end_matches('/'),
+            std::path::MAIN_SEPARATOR
+        );
+        let removed = conn.execute(
+            "DELETE FROM documents WHERE source = ?1 OR substr(source, 1, length(?2)) = ?2",
+            params![source, prefix],
+        )?;
+        Ok(removed)
+    }
+
+    /// Ingested documents, by path
+    pub fn list(&self) -> Result<Vec<DocumentInfo>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT source, ingested_at,
+                    (SELECT COUNT(*) FROM document_chunks WHERE document_id = documents.id)
+             FROM documents ORDER BY source",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(DocumentInfo {
+                source: row.get(0)?,
+                ingested_at: row.get(1)?,
+                chunks: usize::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn keyword_search(&self, query: &str, limit: usize) -> Result<Vec<(String, f32)>> {
+        let fts_query: String = query
+            .split_whitespace()
+            .map(|w| format!("\"{}\"", w.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT rowid, bm25(document_chunks_fts) AS score FROM document_chunks_fts
+             WHERE document_chunks_fts MATCH ?1
+             ORDER BY score
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![fts_query, i64::try_from(limit)?], |row| {
+            let id: i64 = row.get(0)?;
+            let score: f64 = row.get(1)?;
+            #[allow(clippy::cast_possible_truncation)]
+            Ok((id.to_string(), (-score) as f32))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn vector_search(&self, query_embedding: &[f32], limit: usize) -> Result<Vec<(String, f32)>> {
+        let rows: Vec<(i64, Vec<u8>)> = {
+            let conn = self.conn.lock();
+            let mut stmt = conn
+                .prepare("SELECT id, embedding FROM document_chunks WHERE embedding IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let mut scored: Vec<(String, f32)> = rows
+            .into_iter()
+            .map(|(id, blob)| {
+                let sim = vector::cosine_similarity(query_embedding, &vector::bytes_to_vec(&blob));
+                (id.to_string(), sim)
+            })
+            .filter(|(_, sim)| *sim > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+
+    /// The `limit` chunks most relevant to `query`, best first
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<DocumentHit>> {
+        if query.trim().is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let candidates = limit * 2;
+        let vector_results = if self.embedder.dimensions() == 0 {
+            Vec::new()
+        } else {
+            match self.embedder.embed_one(query).await {
+                Ok(embedding) => self.vector_search(&embedding, candidates)?,
+                Err(e) => {
+                    tracing::warn!(
+                        provider = self.embedder.name(),
+                        "embedding for docs_search failed, using keyword search only: {e}"
+                    );
+                    Vec::new()
+                }
+            }
+        };
+        // A query FTS5 cannot parse leaves vector search alone
+        let keyword_results = self.keyword_search(query, candidates).unwrap_or_default();
+        let merged = vector::rrf_merge(
+            &vector_results,
+            &keyword_results,
+            self.vector_weight,
+            self.keyword_weight,
+            self.rrf_k,
+            limit,
+        );
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT documents.source, document_chunks.line, document_chunks.heading,
+                    document_chunks.content
+             FROM document_chunks JOIN documents ON documents.id = document_chunks.document_id
+             WHERE document_chunks.id = ?1",
+        )?;
+        let mut hits = Vec::new();
+        for scored in merged {
+            let hit = stmt
+                .query_row(params![scored.id], |row| {
+                    Ok(DocumentHit {
+                        source: row.get(0)?,
+                        line: row
+                            .get::<_, Option<i64>>(1)?
+                            .and_then(|line| usize::try_from(line).ok()),
+                        heading: row.get(2)?,
+                        content: row.get(3)?,
+                        score: f64::from(scored.final_score),
+                    })
+                })
+                .optional()?;
+            hits.extend(hit);
+        }
+        Ok(hits)
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+#[cfg(feature = "rag-pdf")]
+fn read_pdf(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)?;
+    pdf_extract::extract_text_from_mem(&bytes).map_err(|e| anyhow::anyhow!("unreadable PDF: {e}"))
+}
+
+#[cfg(not(feature = "rag-pdf"))]
+fn read_pdf(_path: &Path) -> Result<String> {
+    anyhow::bail!("PDFs need a build with --features rag-pdf")
+}
+
+/// The line each chunk starts on, 1-based. Chunks split from one section
+/// all repeat its heading, so each is found by its first other line; only
+/// the section's first chunk starts at the heading.
+fn start_lines(text: &str, chunks: &[Chunk]) -> Vec<usize> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut cursor = 0;
+    let mut previous_heading = None;
+    let mut starts = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let heading = chunk.heading.as_deref();
+        let first = chunk
+            .content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && Some(*line) != heading)
+            .or_else(|| chunk.content.lines().next().map(str::trim))
+            .unwrap_or("");
+        if let Some(offset) = lines[cursor..].iter().position(|l| l.trim() == first) {
+            cursor += offset;
+        }
+        let mut start = cursor;
+        if let Some(heading) = heading.filter(|h| previous_heading != Some(*h)) {
+            if let Some(back) = lines[..=start.min(lines.len().saturating_sub(1))]
+                .iter()
+                .rposition(|l| l.trim() == heading)
+            {
+                start = back;
+            }
+        }
+        previous_heading = heading;
+        starts.push(start + 1);
+    }
+    starts
+}
+
+/// Files under `path` to ingest: `path` itself if it is a file, else the
+/// text, code and PDF files below it, skipping hidden and build directories
+pub fn collect_files(path: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, out: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        for path in entries {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                if !SKIPPED_DIRS.contains(&name) {
+                    walk(&path, out);
+                }
+            } else {
+                let extension = extension(&path);
+                if extension == "pdf" || TEXT_EXTENSIONS.contains(&extension.as_str()) {
+                    out.push(path);
+                }
+            }
+        }
+    }
+
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+    let mut files = Vec::new();
+    walk(path, &mut files);
+    files
+}
+
+/// Ingest the file or directory at `path`. Ingesting a directory again also
+/// removes documents from it whose files are gone.
+pub async fn ingest(
+    store: &DocumentStore,
+    path: &Path,
+    max_tokens: usize,
+    progress: &(dyn Fn(&Path) + Send + Sync),
+) -> Result<IngestReport> {
+    let root = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut report = IngestReport::default();
+    let mut seen = HashSet::new();
+    for file in collect_files(&root) {
+        progress(&file);
+        let source = file.display().to_string();
+        match store.ingest_file(&file, max_tokens).await {
+            Ok(Some(chunks)) => report.ingested.push((source.clone(), chunks)),
+            Ok(None) => report.unchanged += 1,
+            Err(e) => report.skipped.push((source.clone(), format!("{e:#}"))),
+        }
+        seen.insert(source);
+    }
+    if root.is_dir() {
+        let prefix = format!("{}{}", root.display(), std::path::MAIN_SEPARATOR);
+        for document in store.list()? {
+            if document.source.starts_with(&prefix) && !seen.contains(&document.source) {
+                report.removed += store.forget(&document.source)?;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// The document store for `config`, with its memory embedder
+pub fn open(config: &Config) -> Result<DocumentStore> {
+    let memory = &config.memory;
+    let key = memory
+        .encryption_key
+        .as_deref()
+        .map(cipher::resolve_key)
+        .transpose()?;
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(DocumentStore::open(
+        &config.workspace_dir,
+        super::build_embedder(memory, config.api_key.as_deref()),
+        key.as_deref(),
+    )?
+    .with_weights(
+        memory.vector_weight as f32,
+        memory.keyword_weight as f32,
+        memory.rrf_k as f32,
+    ))
+}
+
+/// `zeroclaw ingest`
+pub async fn handle_command(
+    config: &Config,
+    path: Option<&Path>,
+    list: bool,
+    forget: Option<&Path>,
+) -> Result<()> {
+    let store = open(config)?;
+    if list {
+        let documents = store.list()?;
+        if documents.is_empty() {
+            println!("No documents ingested yet.");
+        }
+        for document in documents {
+            println!(
+                "{}  ({} chunks, {})",
+                document.source, document.chunks, document.ingested_at
+            );
+        }
+        return Ok(());
+    }
+    if let Some(target) = forget {
+        let source = target
+            .canonicalize()
+            .unwrap_or_else(|_| target.to_path_buf());
+        let removed = store.forget(&source.display().to_string())?;
+        println!("Removed {removed} documents.");
+        return Ok(());
+    }
+    let Some(path) = path else {
+        anyhow::bail!("Give a file or directory to ingest");
+    };
+
+    let report = ingest(&store, path, config.memory.chunk_max_tokens, &|file| {
+        eprintln!("  {}", file.display());
+    })
+    .await?;
+    for (source, reason) in &report.skipped {
+        eprintln!("Skipped {source}: {reason}");
+    }
+    let chunks: usize = report.ingested.iter().map(|(_, n)| n).sum();
+    println!(
+        "Ingested {} files ({chunks} chunks), {} unchanged, {} skipped, {} removed.",
+        report.ingested.len(),
+        report.unchanged,
+        report.skipped.len(),
+        report.removed
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::embeddings::NoopEmbedding;
+    use tempfile::TempDir;
+
+    fn store(tmp: &TempDir) -> DocumentStore {
+        DocumentStore::open(tmp.path(), Arc::new(NoopEmbedding), None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn ingest_search_and_cite_by_line() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("notes");
+        std::fs::create_dir_all(docs.join(".git")).unwrap();
+        std::fs::write(
+            docs.join("setup.md"),
+            "# Setup\n\nInstall the toolchain first.\n\n## Flashing\n\nHold BOOT while pressing RESET to enter the bootloader.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            docs.join("main.py"),
+            "# entry point\nimport sys\n\n\ndef main():\n    print('bootloader ready')\n",
+        )
+        .unwrap();
+        std::fs::write(docs.join(".git").join("HEAD"), "ref: main").unwrap();
+        std::fs::write(docs.join("logo.png"), [0x89, b'P', b'N', b'G', 0]).unwrap();
+        let store = store(&tmp);
+
+        let report = ingest(&store, &docs, 512, &|_| {}).await.unwrap();
+        assert_eq!(report.ingested.len(), 2);
+        assert!(report.skipped.is_empty());
+
+        let hits = store.search("BOOT RESET", 5).await.unwrap();
+        assert!(hits[0].citation().ends_with("setup.md:5"), "{hits:?}");
+        assert_eq!(hits[0].heading.as_deref(), Some("## Flashing"));
+        let hits = store.search("bootloader", 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+
+        // Unchanged files are skipped; deleted ones are dropped
+        std::fs::remove_file(docs.join("main.py")).unwrap();
+        let report = ingest(&store, &docs, 512, &|_| {}).await.unwrap();
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.removed, 1);
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.search("print", 5).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn start_lines_follow_the_text() {
+        let text = "# Title\nintro\n\n## A\none\n\ntwo\n";
+        let chunks = chunker::chunk_markdown(text, 512);
+        assert_eq!(start_lines(text, &chunks), [1, 4]);
+    }
+}
@@ -1,4 +1,7 @@
 use async_trait::async_trait;
+use std::path::PathBuf;
+#[cfg(feature = "local-embeddings")]
+use std::sync::Arc;
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -42,12 +45,16 @@ impl EmbeddingProvider for NoopEmbedding {
 
 // ── OpenAI-compatible embedding provider ─────────────────────
 
+/// Texts sent per embedding request unless configured otherwise
+pub const DEFAULT_BATCH_SIZE: usize = 64;
+
 pub struct OpenAiEmbedding {
     client: reqwest::Client,
     base_url: String,
     api_key: String,
     model: String,
     dims: usize,
+    batch_size: usize,
 }
 
 impl OpenAiEmbedding {
@@ -58,9 +65,16 @@ impl OpenAiEmbedding {
             api_key: api_key.to_string(),
             model: model.to_string(),
             dims,
+            batch_size: DEFAULT_BATCH_SIZE,
         }
     }
 
+    /// Send at most `batch_size` texts per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
     fn has_explicit_api_path(&self) -> bool {
         let Ok(url) = reqwest::Url::parse(&self.base_url) else {
             return false;
@@ -89,34 +103,26 @@ impl OpenAiEmbedding {
             format!("{}/v1/embeddings", self.base_url)
         }
     }
-}
-
-#[async_trait]
-impl EmbeddingProvider for OpenAiEmbedding {
-    fn name(&self) -> &str {
-        "openai"
-    }
 
-    fn dimensions(&self) -> usize {
-        self.dims
-    }
-
-    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
-        if texts.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let body = serde_json::json!({
+    fn request_body(&self, texts: &[&str]) -> serde_json::Value {
+        let mut body = serde_json::json!({
             "model": self.model,
             "input": texts,
         });
+        // Only the v3 models can shorten their vectors.
+        if self.model.starts_with("text-embedding-3") {
+            body["dimensions"] = self.dims.into();
+        }
+        body
+    }
 
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
         let resp = self
             .client
             .post(self.embeddings_url())
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&body)
+            .json(&self.request_body(texts))
             .send()
             .await?;
 
@@ -132,26 +138,312 @@ impl EmbeddingProvider for OpenAiEmbedding {
             .and_then(|d| d.as_array())
             .ok_or_else(|| anyhow::anyhow!("Invalid embedding response: missing 'data'"))?;
 
-        let mut embeddings = Vec::with_capacity(data.len());
-        for item in data {
-            let embedding = item
-                .get("embedding")
-                .and_then(|e| e.as_array())
-                .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+        let mut items: Vec<&serde_json::Value> = data.iter().collect();
+        items.sort_by_key(|item| item.get("index").and_then(serde_json::Value::as_u64));
+        items
+            .into_iter()
+            .map(|item| {
+                item.get("embedding")
+                    .map(parse_vector)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbedding {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            embeddings.extend(self.embed_batch(batch).await?);
+        }
+        Ok(embeddings)
+    }
+}
+
+/// A JSON array of numbers as a vector
+fn parse_vector(value: &serde_json::Value) -> anyhow::Result<Vec<f32>> {
+    let values = value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Invalid embedding: not an array"))?;
+    #[allow(clippy::cast_possible_truncation)]
+    Ok(values
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect())
+}
+
+// ── Ollama embedding provider ────────────────────────────────
+
+/// Ollama's address when `embedding_provider = "ollama"`
+pub const OLLAMA_DEFAULT_URL: &str = "http://localhost:11434";
+
+/// Model used with Ollama when `embedding_model` is still an OpenAI model
+pub const OLLAMA_DEFAULT_MODEL: &str = "nomic-embed-text";
 
-            #[allow(clippy::cast_possible_truncation)]
-            let vec: Vec<f32> = embedding
-                .iter()
-                .filter_map(|v| v.as_f64().map(|f| f as f32))
-                .collect();
+pub struct OllamaEmbedding {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dims: usize,
+    batch_size: usize,
+}
 
-            embeddings.push(vec);
+impl OllamaEmbedding {
+    pub fn new(base_url: &str, api_key: Option<&str>, model: &str, dims: usize) -> Self {
+        let model = if model.starts_with("text-embedding-") {
+            OLLAMA_DEFAULT_MODEL
+        } else {
+            model
+        };
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|k| !k.is_empty()).map(str::to_string),
+            model: model.to_string(),
+            dims,
+            batch_size: DEFAULT_BATCH_SIZE,
         }
+    }
+
+    /// Send at most `batch_size` texts per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
 
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut request = self
+            .client
+            .post(format!("{}/api/embed", self.base_url))
+            .json(&serde_json::json!({
+                "model": self.model,
+                "input": texts,
+            }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request.send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama embedding error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        let embeddings = json
+            .get("embeddings")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid Ollama response: missing 'embeddings'"))?;
+        if embeddings.len() != texts.len() {
+            anyhow::bail!(
+                "Ollama returned {} embeddings for {} texts",
+                embeddings.len(),
+                texts.len()
+            );
+        }
+        embeddings.iter().map(parse_vector).collect()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbedding {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            embeddings.extend(self.embed_batch(batch).await?);
+        }
         Ok(embeddings)
     }
 }
 
+// ── Local ONNX embedding provider ────────────────────────────
+
+/// Local model used when `embedding_model` names none of the local models
+pub const DEFAULT_LOCAL_MODEL: &str = "bge-small-en-v1.5";
+
+/// Where local embedding models are downloaded to and loaded from
+pub fn local_models_dir() -> PathBuf {
+    directories::UserDirs::new().map_or_else(
+        || PathBuf::from(".zeroclaw/models"),
+        |u| u.home_dir().join(".zeroclaw").join("models"),
+    )
+}
+
+/// Common names of the local models, with or without their organisation
+#[cfg(feature = "local-embeddings")]
+const LOCAL_MODEL_ALIASES: &[(&str, fastembed::EmbeddingModel)] = &[
+    (
+        "bge-small-en-v1.5",
+        fastembed::EmbeddingModel::BGESmallENV15,
+    ),
+    ("bge-base-en-v1.5", fastembed::EmbeddingModel::BGEBaseENV15),
+    (
+        "bge-large-en-v1.5",
+        fastembed::EmbeddingModel::BGELargeENV15,
+    ),
+    ("all-MiniLM-L6-v2", fastembed::EmbeddingModel::AllMiniLML6V2),
+    (
+        "all-MiniLM-L12-v2",
+        fastembed::EmbeddingModel::AllMiniLML12V2,
+    ),
+    (
+        "all-mpnet-base-v2",
+        fastembed::EmbeddingModel::AllMpnetBaseV2,
+    ),
+    (
+        "nomic-embed-text-v1.5",
+        fastembed::EmbeddingModel::NomicEmbedTextV15,
+    ),
+    (
+        "multilingual-e5-small",
+        fastembed::EmbeddingModel::MultilingualE5Small,
+    ),
+    (
+        "multilingual-e5-base",
+        fastembed::EmbeddingModel::MultilingualE5Base,
+    ),
+];
+
+/// The local model called `name`: a common name such as
+/// `bge-small-en-v1.5` or `BAAI/bge-small-en-v1.5`, or a fastembed model
+/// name such as `BGESmallENV15Q`, in any case.
+#[cfg(feature = "local-embeddings")]
+pub fn local_model(name: &str) -> Option<fastembed::ModelInfo<fastembed::EmbeddingModel>> {
+    let name = name.trim();
+    let short = name.rsplit('/').next().unwrap_or(name);
+    let model = LOCAL_MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(short))
+        .map(|(_, model)| model.clone())
+        .or_else(|| name.parse().ok())?;
+    fastembed::TextEmbedding::get_model_info(&model)
+        .ok()
+        .cloned()
+}
+
+/// Runs an ONNX embedding model in-process; no API key or service needed.
+/// The model is downloaded into [`local_models_dir`] on first use.
+#[cfg(feature = "local-embeddings")]
+pub struct LocalEmbedding {
+    model: fastembed::EmbeddingModel,
+    model_code: String,
+    dims: usize,
+    cache_dir: PathBuf,
+    engine: Arc<std::sync::Mutex<Option<fastembed::TextEmbedding>>>,
+}
+
+#[cfg(feature = "local-embeddings")]
+impl LocalEmbedding {
+    pub fn new(model_name: &str, cache_dir: PathBuf) -> Self {
+        let info = local_model(model_name).unwrap_or_else(|| {
+            if !model_name.starts_with("text-embedding-") {
+                tracing::warn!(
+                    "Unknown local embedding model '{model_name}', using {DEFAULT_LOCAL_MODEL}"
+                );
+            }
+            local_model(DEFAULT_LOCAL_MODEL).expect("default local model is supported")
+        });
+        Self {
+            model: info.model,
+            model_code: info.model_code,
+            dims: info.dim,
+            cache_dir,
+            engine: Arc::default(),
+        }
+    }
+
+    /// Repository the model is downloaded from
+    pub fn model_code(&self) -> &str {
+        &self.model_code
+    }
+
+    /// Load the model, downloading it first when it is not cached yet
+    pub async fn load(&self) -> anyhow::Result<()> {
+        self.with_engine(|_| Ok(())).await
+    }
+
+    async fn with_engine<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut fastembed::TextEmbedding) -> anyhow::Result<T> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        let engine = Arc::clone(&self.engine);
+        let model = self.model.clone();
+        let cache_dir = self.cache_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut slot = engine
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let engine = match &mut *slot {
+                Some(loaded) => loaded,
+                empty => {
+                    tracing::info!("Loading local embedding model {model:?}");
+                    let options = fastembed::TextInitOptions::new(model)
+                        .with_cache_dir(cache_dir)
+                        .with_show_download_progress(false);
+                    empty.insert(fastembed::TextEmbedding::try_new(options)?)
+                }
+            };
+            f(engine)
+        })
+        .await?
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+#[async_trait]
+impl EmbeddingProvider for LocalEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let texts: Vec<String> = texts.iter().map(ToString::to_string).collect();
+        self.with_engine(move |engine| engine.embed(texts, None))
+            .await
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+fn create_local_provider(model: &str) -> Box<dyn EmbeddingProvider> {
+    Box::new(LocalEmbedding::new(model, local_models_dir()))
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+fn create_local_provider(_model: &str) -> Box<dyn EmbeddingProvider> {
+    tracing::warn!(
+        "embedding_provider = \"local\" needs a build with --features local-embeddings; \
+         using keyword search only"
+    );
+    Box::new(NoopEmbedding)
+}
+
 // ── Factory ──────────────────────────────────────────────────
 
 pub fn create_embedding_provider(
@@ -159,21 +451,31 @@ pub fn create_embedding_provider(
     api_key: Option<&str>,
     model: &str,
     dims: usize,
+    batch_size: usize,
 ) -> Box<dyn EmbeddingProvider> {
     match provider {
         "openai" => {
             let key = api_key.unwrap_or("");
-            Box::new(OpenAiEmbedding::new(
-                "https://api.openai.com",
-                key,
-                model,
-                dims,
-            ))
+            Box::new(
+                OpenAiEmbedding::new("https://api.openai.com", key, model, dims)
+                    .with_batch_size(batch_size),
+            )
         }
+        "ollama" => Box::new(
+            OllamaEmbedding::new(OLLAMA_DEFAULT_URL, api_key, model, dims)
+                .with_batch_size(batch_size),
+        ),
+        name if name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:").unwrap_or("");
+            Box::new(
+                OllamaEmbedding::new(base_url, api_key, model, dims).with_batch_size(batch_size),
+            )
+        }
+        "local" => create_local_provider(model),
         name if name.starts_with("custom:") => {
             let base_url = name.strip_prefix("custom:").unwrap_or("");
             let key = api_key.unwrap_or("");
-            Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
+            Box::new(OpenAiEmbedding::new(base_url, key, model, dims).with_batch_size(batch_size))
         }
         _ => Box::new(NoopEmbedding),
     }
@@ -199,20 +501,61 @@ mod tests {
 
     #[test]
     fn factory_none() {
-        let p = create_embedding_provider("none", None, "model", 1536);
+        let p = create_embedding_provider("none", None, "model", 1536, DEFAULT_BATCH_SIZE);
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_openai() {
-        let p = create_embedding_provider("openai", Some("key"), "text-embedding-3-small", 1536);
+        let p = create_embedding_provider(
+            "openai",
+            Some("key"),
+            "text-embedding-3-small",
+            1536,
+            DEFAULT_BATCH_SIZE,
+        );
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
 
+    #[cfg(not(feature = "local-embeddings"))]
+    #[test]
+    fn factory_local_without_feature_returns_noop() {
+        let p =
+            create_embedding_provider("local", None, "bge-small-en-v1.5", 384, DEFAULT_BATCH_SIZE);
+        assert_eq!(p.name(), "none");
+    }
+
+    #[cfg(feature = "local-embeddings")]
+    #[test]
+    fn local_models_resolve_by_any_name() {
+        let by_alias = local_model("BAAI/bge-small-en-v1.5").unwrap();
+        assert_eq!(by_alias.model, fastembed::EmbeddingModel::BGESmallENV15);
+        assert_eq!(by_alias.dim, 384);
+        let by_name = local_model("allminilml6v2q").unwrap();
+        assert_eq!(by_name.model, fastembed::EmbeddingModel::AllMiniLML6V2Q);
+        assert!(local_model("text-embedding-3-small").is_none());
+
+        let p = create_embedding_provider(
+            "local",
+            None,
+            "text-embedding-3-small",
+            1536,
+            DEFAULT_BATCH_SIZE,
+        );
+        assert_eq!(p.name(), "local");
+        assert_eq!(p.dimensions(), 384);
+    }
+
     #[test]
     fn factory_custom_url() {
-        let p = create_embedding_provider("custom:http://localhost:1234", None, "model", 768);
+        let p = create_embedding_provider(
+            "custom:http://localhost:1234",
+            None,
+            "model",
+            768,
+            DEFAULT_BATCH_SIZE,
+        );
         assert_eq!(p.name(), "openai"); // uses OpenAiEmbedding internally
         assert_eq!(p.dimensions(), 768);
     }
@@ -243,30 +586,99 @@ mod tests {
 
     #[test]
     fn factory_empty_string_returns_noop() {
-        let p = create_embedding_provider("", None, "model", 1536);
+        let p = create_embedding_provider("", None, "model", 1536, DEFAULT_BATCH_SIZE);
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_unknown_provider_returns_noop() {
-        let p = create_embedding_provider("cohere", None, "model", 1536);
+        let p = create_embedding_provider("cohere", None, "model", 1536, DEFAULT_BATCH_SIZE);
         assert_eq!(p.name(), "none");
     }
 
     #[test]
     fn factory_custom_empty_url() {
         // "custom:" with no URL — should still construct without panic
-        let p = create_embedding_provider("custom:", None, "model", 768);
+        let p = create_embedding_provider("custom:", None, "model", 768, DEFAULT_BATCH_SIZE);
         assert_eq!(p.name(), "openai");
     }
 
     #[test]
     fn factory_openai_no_api_key() {
-        let p = create_embedding_provider("openai", None, "text-embedding-3-small", 1536);
+        let p = create_embedding_provider(
+            "openai",
+            None,
+            "text-embedding-3-small",
+            1536,
+            DEFAULT_BATCH_SIZE,
+        );
         assert_eq!(p.name(), "openai");
         assert_eq!(p.dimensions(), 1536);
     }
 
+    #[test]
+    fn factory_ollama() {
+        let p = create_embedding_provider("ollama", None, "text-embedding-3-small", 768, 8);
+        assert_eq!(p.name(), "ollama");
+        let p = create_embedding_provider("ollama:http://gpu-box:11434", None, "mxbai", 1024, 8);
+        assert_eq!(p.name(), "ollama");
+        assert_eq!(p.dimensions(), 1024);
+    }
+
+    #[test]
+    fn ollama_replaces_openai_default_model() {
+        let p = OllamaEmbedding::new(OLLAMA_DEFAULT_URL, None, "text-embedding-3-small", 768);
+        assert_eq!(p.model, OLLAMA_DEFAULT_MODEL);
+        let p = OllamaEmbedding::new(OLLAMA_DEFAULT_URL, Some(""), "all-minilm", 384);
+        assert_eq!(p.model, "all-minilm");
+        assert!(p.api_key.is_none());
+    }
+
+    #[tokio::test]
+    async fn ollama_embeds_in_batches() {
+        use axum::{routing::post, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static REQUESTS: AtomicUsize = AtomicUsize::new(0);
+        let app = Router::new().route(
+            "/api/embed",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                REQUESTS.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(body["model"], "nomic-embed-text");
+                let embeddings: Vec<Vec<f32>> = body["input"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|text| vec![text.as_str().unwrap().len() as f32, 1.0])
+                    .collect();
+                Json(serde_json::json!({ "embeddings": embeddings }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let p =
+            create_embedding_provider(&format!("ollama:{base}"), None, "nomic-embed-text", 2, 2);
+        let vectors = p.embed(&["a", "bb", "ccc", "dddd", "eeeee"]).await.unwrap();
+        assert_eq!(REQUESTS.load(Ordering::SeqCst), 3);
+        let lengths: Vec<f32> = vectors.iter().map(|v| v[0]).collect();
+        assert_eq!(lengths, [1.0, 2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn openai_asks_v3_models_for_configured_dimensions() {
+        let p = OpenAiEmbedding::new("https://api.openai.com", "k", "text-embedding-3-small", 512);
+        assert_eq!(p.request_body(&["x"])["dimensions"], 512);
+        let p = OpenAiEmbedding::new(
+            "https://api.openai.com",
+            "k",
+            "text-embedding-ada-002",
+            1536,
+        );
+        assert!(p.request_body(&["x"]).get("dimensions").is_none());
+    }
+
     #[test]
     fn openai_trailing_slash_stripped() {
         let p = OpenAiEmbedding::new("https://api.openai.com/", "key", "model", 1536);
@@ -17,6 +17,8 @@ struct HygieneReport {
     purged_memory_archives: u64,
     purged_session_archives: u64,
     pruned_conversation_rows: u64,
+    #[serde(default)]
+    expired_session_files: u64,
 }
 
 impl HygieneReport {
@@ -26,6 +28,7 @@ impl HygieneReport {
             + self.purged_memory_archives
             + self.purged_session_archives
             + self.pruned_conversation_rows
+            + self.expired_session_files
     }
 }
 
@@ -54,10 +57,20 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
         )?,
         archived_session_files: archive_session_files(workspace_dir, config.archive_after_days)?,
         purged_memory_archives: purge_memory_archives(workspace_dir, config.purge_after_days)?,
-        purged_session_archives: purge_session_archives(workspace_dir, config.purge_after_days)?,
+        // A transcript retention replaces the archive purge for session logs
+        purged_session_archives: if config.transcript_retention_days == 0 {
+            purge_session_archives(workspace_dir, config.purge_after_days)?
+        } else {
+            0
+        },
         pruned_conversation_rows: prune_conversation_rows(
             workspace_dir,
             config.conversation_retention_days,
+            config.encryption_key.as_deref(),
+        )?,
+        expired_session_files: expire_session_files(
+            workspace_dir,
+            config.transcript_retention_days,
         )?,
     };
 
@@ -65,12 +78,13 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
 
     if report.total_actions() > 0 {
         tracing::info!(
-            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={}",
+            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={} expired_sessions={}",
             report.archived_memory_files,
             report.archived_session_files,
             report.purged_memory_archives,
             report.purged_session_archives,
             report.pruned_conversation_rows,
+            report.expired_session_files,
         );
     }
 
@@ -295,7 +309,40 @@ fn purge_session_archives(workspace_dir: &Path, purge_after_days: u32) -> Result
     Ok(removed)
 }
 
-fn prune_conversation_rows(workspace_dir: &Path, retention_days: u32) -> Result<u64> {
+/// Delete session transcripts, archived or not, recorded more than
+/// `retention_days` days ago
+fn expire_session_files(workspace_dir: &Path, retention_days: u32) -> Result<u64> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+
+    let sessions_dir = workspace_dir.join("sessions");
+    let cutoff_date = Local::now().date_naive() - Duration::days(i64::from(retention_days));
+    let mut removed = 0_u64;
+    for dir in [sessions_dir.clone(), sessions_dir.join("archive")] {
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(filename) = path.file_name().and_then(|f| f.to_str()) else {
+                continue;
+            };
+            if path.is_file() && date_prefix(filename).is_some_and(|date| date < cutoff_date) {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+fn prune_conversation_rows(
+    workspace_dir: &Path,
+    retention_days: u32,
+    encryption_key: Option<&str>,
+) -> Result<u64> {
     if retention_days == 0 {
         return Ok(0);
     }
@@ -305,7 +352,10 @@ fn prune_conversation_rows(workspace_dir: &Path, retention_days: u32) -> Result<
         return Ok(0);
     }
 
-    let conn = Connection::open(db_path)?;
+    let conn = Connection::open(&db_path)?;
+    if let Some(source) = encryption_key {
+        super::cipher::unlock(&conn, &db_path, &super::cipher::resolve_key(source)?)?;
+    }
     // Use WAL so hygiene pruning doesn't block agent reads
     conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
     let cutoff = (Local::now() - Duration::days(i64::from(retention_days))).to_rfc3339();
@@ -442,6 +492,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn transcript_retention_deletes_old_sessions_archived_or_not() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        let archive = workspace.join("sessions").join("archive");
+        fs::create_dir_all(&archive).unwrap();
+        let day = |ago: i64| {
+            (Local::now().date_naive() - Duration::days(ago))
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+        let expired = archive.join(format!("{}-cli-a.jsonl", day(100)));
+        let kept = archive.join(format!("{}-cli-b.jsonl", day(60)));
+        let recent = workspace
+            .join("sessions")
+            .join(format!("{}-cli-c.jsonl", day(1)));
+        for path in [&expired, &kept, &recent] {
+            fs::write(path, "{}").unwrap();
+        }
+
+        let mut cfg = default_cfg();
+        cfg.transcript_retention_days = 90;
+        run_if_due(&cfg, workspace).unwrap();
+
+        assert!(!expired.exists());
+        assert!(kept.exists(), "the archive purge no longer applies");
+        assert!(recent.exists());
+    }
+
     #[test]
     fn skips_second_run_within_cadence_window() {
         let tmp = TempDir::new().unwrap();
@@ -1,5 +1,6 @@
+use super::namespace::{self, DEFAULT_NAMESPACE};
 use super::sqlite::SqliteMemory;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{ListOrder, Memory, MemoryCategory, MemoryEntry, TimeRange};
 use async_trait::async_trait;
 use chrono::Local;
 use std::collections::HashSet;
@@ -231,6 +232,9 @@ impl LucidMemory {
                 timestamp: now.clone(),
                 session_id: None,
                 score: Some((1.0 - rank as f64 * 0.05).max(0.1)),
+                namespace: None,
+                tags: Vec::new(),
+                match_kind: None,
             });
         }
 
@@ -321,7 +325,10 @@ impl Memory for LucidMemory {
         self.local
             .store(key, content, category.clone(), session_id)
             .await?;
-        self.sync_to_lucid_async(key, content, &category).await;
+        // Lucid keeps one pool, so only the owner's memories are synced
+        if namespace::current() == DEFAULT_NAMESPACE {
+            self.sync_to_lucid_async(key, content, &category).await;
+        }
         Ok(())
     }
 
@@ -335,6 +342,7 @@ impl Memory for LucidMemory {
         if limit == 0
             || local_results.len() >= limit
             || local_results.len() >= self.local_hit_threshold
+            || namespace::current() != DEFAULT_NAMESPACE
         {
             return Ok(local_results);
         }
@@ -364,10 +372,88 @@ impl Memory for LucidMemory {
         }
     }
 
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        // Tags only exist locally
+        self.local
+            .recall_tagged(query, limit, session_id, tags)
+            .await
+    }
+
+    async fn recall_in_range(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+        range: &TimeRange,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if range.is_unbounded() {
+            return self.recall_tagged(query, limit, session_id, tags).await;
+        }
+        // Lucid's store has no creation times to filter by
+        self.local
+            .recall_in_range(query, limit, session_id, tags, range)
+            .await
+    }
+
+    async fn set_tags(&self, key: &str, tags: &[String]) -> anyhow::Result<()> {
+        self.local.set_tags(key, tags).await
+    }
+
+    async fn set_importance(&self, key: &str, importance: f64) -> anyhow::Result<()> {
+        self.local.set_importance(key, importance).await
+    }
+
+    async fn set_expiry(
+        &self,
+        key: &str,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> anyhow::Result<()> {
+        self.local.set_expiry(key, expires_at).await
+    }
+
+    async fn tags(&self) -> anyhow::Result<Vec<(String, usize)>> {
+        self.local.tags().await
+    }
+
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         self.local.get(key).await
     }
 
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        self.local.get_by_id(id).await
+    }
+
+    async fn find_similar(
+        &self,
+        content: &str,
+        category: &MemoryCategory,
+    ) -> anyhow::Result<Option<MemoryEntry>> {
+        self.local.find_similar(content, category).await
+    }
+
+    async fn update(&self, key: &str, content: &str) -> anyhow::Result<bool> {
+        if !self.local.update(key, content).await? {
+            return Ok(false);
+        }
+        if namespace::current() == DEFAULT_NAMESPACE {
+            if let Some(entry) = self.local.get(key).await? {
+                self.sync_to_lucid_async(key, content, &entry.category)
+                    .await;
+            }
+        }
+        Ok(true)
+    }
+
     async fn list(
         &self,
         category: Option<&MemoryCategory>,
@@ -376,6 +462,15 @@ impl Memory for LucidMemory {
         self.local.list(category, session_id).await
     }
 
+    async fn list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        order: ListOrder,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.local.list_page(offset, limit, order).await
+    }
+
     async fn forget(&self, key: &str) -> anyhow::Result<bool> {
         self.local.forget(key).await
     }
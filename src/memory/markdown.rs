@@ -1,4 +1,4 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{MatchKind, Memory, MemoryCategory, MemoryEntry};
 use async_trait::async_trait;
 use chrono::Local;
 use std::path::{Path, PathBuf};
@@ -91,6 +91,9 @@ impl MarkdownMemory {
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    namespace: None,
+                    tags: Vec::new(),
+                    match_kind: None,
                 }
             })
             .collect()
@@ -175,6 +178,7 @@ impl Memory for MarkdownMemory {
                     #[allow(clippy::cast_precision_loss)]
                     let score = matched as f64 / keywords.len() as f64;
                     entry.score = Some(score);
+                    entry.match_kind = Some(MatchKind::Like);
                     Some(entry)
                 } else {
                     None
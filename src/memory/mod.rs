@@ -1,15 +1,23 @@
 pub mod backend;
+pub mod backup;
 pub mod chunker;
+pub mod cipher;
+pub mod documents;
 pub mod embeddings;
 pub mod hygiene;
 pub mod lucid;
 pub mod markdown;
+pub mod namespace;
 pub mod none;
+pub mod postgres;
+pub mod rerank;
 pub mod response_cache;
 pub mod snapshot;
 pub mod sqlite;
 pub mod traits;
+pub mod transfer;
 pub mod vector;
+pub mod vector_index;
 
 #[allow(unused_imports)]
 pub use backend::{
@@ -19,13 +27,17 @@ pub use backend::{
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
 pub use none::NoneMemory;
+pub use postgres::PostgresMemory;
 pub use response_cache::ResponseCache;
-pub use sqlite::SqliteMemory;
+pub use sqlite::{FtsTokenizer, SqliteMemory};
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{
+    BudgetAction, DedupStrategy, ListOrder, MatchKind, MemoryCategory, MemoryEntry, TimeRange,
+};
 
 use crate::config::MemoryConfig;
+use anyhow::Context;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -44,6 +56,9 @@ where
             let local = sqlite_builder()?;
             Ok(Box::new(LucidMemory::new(workspace_dir, local)))
         }
+        MemoryBackendKind::Postgres => {
+            anyhow::bail!("memory backend 'postgres' needs memory.postgres_url{unknown_context}")
+        }
         MemoryBackendKind::Markdown => Ok(Box::new(MarkdownMemory::new(workspace_dir))),
         MemoryBackendKind::None => Ok(Box::new(NoneMemory::new())),
         MemoryBackendKind::Unknown => {
@@ -55,6 +70,126 @@ where
     }
 }
 
+/// The embedding provider from `config`
+fn build_embedder(
+    config: &MemoryConfig,
+    api_key: Option<&str>,
+) -> Arc<dyn embeddings::EmbeddingProvider> {
+    Arc::from(embeddings::create_embedding_provider(
+        &config.embedding_provider,
+        api_key,
+        &config.embedding_model,
+        config.embedding_dimensions,
+        config.embedding_batch_size,
+    ))
+}
+
+/// The second-stage reranker from `memory.reranker`, if any
+fn build_reranker(config: &MemoryConfig) -> Option<Arc<dyn rerank::Reranker>> {
+    rerank::create_reranker(
+        &config.reranker,
+        config.reranker_api_key.as_deref(),
+        config.reranker_model.as_deref(),
+        embeddings::local_models_dir(),
+    )
+    .map(Arc::from)
+}
+
+/// The SQLite backend with the embedding provider from `config`
+fn build_sqlite_memory(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    api_key: Option<&str>,
+) -> anyhow::Result<SqliteMemory> {
+    let embedder = build_embedder(config, api_key);
+    let dimensions = embedder.dimensions();
+
+    let key = config
+        .encryption_key
+        .as_deref()
+        .map(cipher::resolve_key)
+        .transpose()?;
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mut mem = SqliteMemory::open(
+        workspace_dir,
+        embedder,
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+        config.embedding_cache_size,
+        key.as_deref(),
+    )?
+    .with_rrf_k(config.rrf_k as f32)
+    .with_recency_decay(
+        config.recency_weight as f32,
+        config.recency_half_life_days as f32,
+    );
+    let tokenizer: FtsTokenizer = config.fts_tokenizer.parse()?;
+    if mem.set_fts_tokenizer(tokenizer, config.fts_remove_diacritics)? {
+        tracing::info!(
+            "Rebuilt the memory keyword index with the {} tokenizer",
+            config.fts_tokenizer
+        );
+    }
+    if let Some(reranker) = build_reranker(config) {
+        mem = mem.with_reranker(reranker, config.rerank_candidates);
+    }
+    match config.vector_store.as_str() {
+        "qdrant" => {
+            mem = mem.with_vector_index(Arc::new(vector_index::QdrantIndex::new(
+                &config.qdrant_url,
+                config.qdrant_api_key.as_deref(),
+                &config.qdrant_collection,
+                dimensions,
+            )));
+        }
+        #[cfg(feature = "lancedb")]
+        "lancedb" => {
+            mem = mem.with_vector_index(Arc::new(vector_index::LanceIndex::new(
+                &workspace_dir.join("memory").join("lancedb"),
+                dimensions,
+            )));
+        }
+        #[cfg(not(feature = "lancedb"))]
+        "lancedb" => {
+            anyhow::bail!("vector_store = \"lancedb\" needs a build with --features lancedb")
+        }
+        _ => {}
+    }
+    Ok(mem)
+}
+
+/// The Postgres backend at `memory.postgres_url`
+fn build_postgres_memory(
+    config: &MemoryConfig,
+    api_key: Option<&str>,
+) -> anyhow::Result<PostgresMemory> {
+    let Some(url) = &config.postgres_url else {
+        anyhow::bail!("memory backend 'postgres' needs memory.postgres_url");
+    };
+    if config.max_memories > 0 {
+        anyhow::bail!(
+            "the postgres memory backend cannot enforce memory.max_memories; set it to 0"
+        );
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    let mut mem = PostgresMemory::new(
+        url,
+        build_embedder(config, api_key),
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+    )?
+    .with_rrf_k(config.rrf_k as f32)
+    .with_recency_decay(
+        config.recency_weight as f32,
+        config.recency_half_life_days as f32,
+    );
+    if let Some(reranker) = build_reranker(config) {
+        mem = mem.with_reranker(reranker, config.rerank_candidates);
+    }
+    Ok(mem)
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
@@ -67,7 +202,9 @@ pub fn create_memory(
     }
 
     // If snapshot_on_hygiene is enabled, export core memories during hygiene.
-    if config.snapshot_enabled && config.snapshot_on_hygiene {
+    // Snapshots are plaintext, so an encrypted database never writes one.
+    let encrypted = config.encryption_key.is_some();
+    if config.snapshot_enabled && config.snapshot_on_hygiene && !encrypted {
         if let Err(e) = snapshot::export_snapshot(workspace_dir) {
             tracing::warn!("memory snapshot skipped: {e}");
         }
@@ -76,6 +213,7 @@ pub fn create_memory(
     // Auto-hydration: if brain.db is missing but MEMORY_SNAPSHOT.md exists,
     // restore the "soul" from the snapshot before creating the backend.
     if config.auto_hydrate
+        && !encrypted
         && matches!(
             classify_memory_backend(&config.backend),
             MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
@@ -95,38 +233,56 @@ pub fn create_memory(
         }
     }
 
-    fn build_sqlite_memory(
-        config: &MemoryConfig,
-        workspace_dir: &Path,
-        api_key: Option<&str>,
-    ) -> anyhow::Result<SqliteMemory> {
-        let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-            Arc::from(embeddings::create_embedding_provider(
-                &config.embedding_provider,
-                api_key,
-                &config.embedding_model,
-                config.embedding_dimensions,
-            ));
-
-        #[allow(clippy::cast_possible_truncation)]
-        let mem = SqliteMemory::with_embedder(
-            workspace_dir,
-            embedder,
-            config.vector_weight as f32,
-            config.keyword_weight as f32,
-            config.embedding_cache_size,
-        )?;
-        Ok(mem)
+    if classify_memory_backend(&config.backend) == MemoryBackendKind::Postgres {
+        return Ok(Box::new(build_postgres_memory(config, api_key)?));
     }
 
     create_memory_with_sqlite_builder(
         &config.backend,
         workspace_dir,
-        || build_sqlite_memory(config, workspace_dir, api_key),
+        || {
+            let memory = build_sqlite_memory(config, workspace_dir, api_key)?;
+            // Best-effort budget pass; the daemon repeats it on every prune
+            if config.max_memories > 0 {
+                if let Err(e) = apply_budget(config, &memory) {
+                    tracing::warn!("memory budget skipped: {e}");
+                }
+            }
+            Ok(memory)
+        },
         "",
     )
 }
 
+fn apply_budget(config: &MemoryConfig, memory: &SqliteMemory) -> anyhow::Result<usize> {
+    let removed = memory.enforce_budget(config.max_memories, config.budget_action.parse()?)?;
+    if removed > 0 {
+        tracing::info!(
+            "Memory budget of {} exceeded: {} {removed} memories",
+            config.max_memories,
+            if config.budget_action == "delete" {
+                "deleted"
+            } else {
+                "archived"
+            }
+        );
+    }
+    Ok(removed)
+}
+
+/// Enforce `memory.max_memories` on the SQLite store, if a budget is set
+pub fn enforce_budget(config: &crate::config::Config) -> anyhow::Result<usize> {
+    if config.memory.max_memories == 0
+        || !matches!(
+            classify_memory_backend(&config.memory.backend),
+            MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+        )
+    {
+        return Ok(0);
+    }
+    apply_budget(&config.memory, &open_search_index(config)?)
+}
+
 pub fn create_memory_for_migration(
     backend: &str,
     workspace_dir: &Path,
@@ -171,6 +327,416 @@ pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Opt
     }
 }
 
+/// The SQLite store behind the `sqlite` and `lucid` backends
+pub fn open_search_index(config: &crate::config::Config) -> anyhow::Result<SqliteMemory> {
+    if !matches!(
+        classify_memory_backend(&config.memory.backend),
+        MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+    ) {
+        anyhow::bail!(
+            "memory backend '{}' has no search index",
+            config.memory.backend
+        );
+    }
+    build_sqlite_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    )
+}
+
+/// Delete expired memories in every namespace of the configured backend
+pub async fn prune_expired(config: &crate::config::Config) -> anyhow::Result<usize> {
+    if classify_memory_backend(&config.memory.backend) == MemoryBackendKind::Postgres {
+        let memory = build_postgres_memory(&config.memory, config.api_key.as_deref())?;
+        return memory.prune_expired().await;
+    }
+    open_search_index(config)?.prune_expired()
+}
+
+/// Names the embedder that computed exported vectors
+fn embedding_model_tag(config: &MemoryConfig) -> String {
+    format!("{}:{}", config.embedding_provider, config.embedding_model)
+}
+
+/// The memory stored under `key_or_id` as a key, else as an id
+pub async fn lookup(memory: &dyn Memory, key_or_id: &str) -> anyhow::Result<Option<MemoryEntry>> {
+    match memory.get(key_or_id).await? {
+        Some(entry) => Ok(Some(entry)),
+        None => memory.get_by_id(key_or_id).await,
+    }
+}
+
+pub async fn handle_command(
+    command: crate::MemoryCommands,
+    config: &crate::config::Config,
+) -> anyhow::Result<()> {
+    match command {
+        crate::MemoryCommands::List {
+            offset,
+            limit,
+            oldest,
+            namespace,
+        } => {
+            let memory = create_memory(
+                &config.memory,
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            )?;
+            let order = if oldest {
+                ListOrder::OldestFirst
+            } else {
+                ListOrder::NewestFirst
+            };
+            let (total, page) = namespace::scope(namespace, async {
+                Ok::<_, anyhow::Error>((
+                    memory.count().await?,
+                    memory.list_page(offset, limit, order).await?,
+                ))
+            })
+            .await?;
+            if page.is_empty() {
+                if total == 0 {
+                    println!("No memories stored yet.");
+                } else {
+                    println!("No memories past offset {offset} ({total} stored).");
+                }
+                return Ok(());
+            }
+
+            let end = offset + page.len();
+            println!("🧠 Memories {}-{end} of {total}:", offset + 1);
+            for entry in page {
+                let namespace = entry
+                    .namespace
+                    .map_or_else(String::new, |ns| format!("{ns} | "));
+                println!(
+                    "- {namespace}{} | {} | {} | {}",
+                    entry.key, entry.category, entry.timestamp, entry.content
+                );
+            }
+            if end < total {
+                println!("More: zeroclaw memory list --offset {end}");
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Forget {
+            target,
+            query,
+            yes,
+            namespace,
+        } => {
+            let memory = create_memory(
+                &config.memory,
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            )?;
+            namespace::scope(namespace, async {
+                let entry = if query {
+                    let matches = memory.recall(&target, 5, None).await?;
+                    if matches.len() > 1 {
+                        println!("{} memories match '{target}':", matches.len());
+                        for entry in &matches {
+                            println!("- {} | {} | {}", entry.key, entry.id, entry.content);
+                        }
+                    }
+                    matches.into_iter().next()
+                } else {
+                    lookup(memory.as_ref(), &target).await?
+                };
+                let Some(entry) = entry else {
+                    println!("No memory found for '{target}'.");
+                    return Ok(());
+                };
+                if !yes
+                    && !dialoguer::Confirm::new()
+                        .with_prompt(format!("Forget '{}': {}?", entry.key, entry.content))
+                        .default(false)
+                        .interact()
+                        .context("Confirmation needs a terminal; pass --yes to skip it")?
+                {
+                    println!("Nothing forgotten.");
+                    return Ok(());
+                }
+                // A search match may be a shared memory; forget it there
+                let forgotten = match entry.namespace.clone() {
+                    Some(ns) if ns != namespace::current() => {
+                        namespace::scope(ns, memory.forget(&entry.key)).await?
+                    }
+                    _ => memory.forget(&entry.key).await?,
+                };
+                if forgotten {
+                    println!("🗑️  Forgot {}", entry.key);
+                } else {
+                    println!(
+                        "The {} backend keeps every entry; nothing forgotten.",
+                        memory.name()
+                    );
+                }
+                Ok(())
+            })
+            .await
+        }
+        crate::MemoryCommands::Update {
+            target,
+            content,
+            namespace,
+        } => {
+            let memory = create_memory(
+                &config.memory,
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            )?;
+            namespace::scope(namespace, async {
+                let Some(entry) = lookup(memory.as_ref(), &target).await? else {
+                    anyhow::bail!("No memory found with key or id '{target}'");
+                };
+                if !memory.update(&entry.key, &content).await? {
+                    anyhow::bail!("Memory '{}' disappeared before it was updated", entry.key);
+                }
+                println!("✅ Updated {}", entry.key);
+                Ok(())
+            })
+            .await
+        }
+        crate::MemoryCommands::Export {
+            format,
+            output,
+            namespace,
+        } => {
+            let memory = open_search_index(config)?;
+            let model = embedding_model_tag(&config.memory);
+            let mut records = memory.export_records(&namespace)?;
+            for record in &mut records {
+                if record.embedding.is_some() {
+                    record.embedding_model = Some(model.clone());
+                }
+            }
+            let rendered = transfer::write(&records, format.parse()?)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!(
+                        "✅ Exported {} memories to {}",
+                        records.len(),
+                        path.display()
+                    );
+                }
+                None => print!("{rendered}"),
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Import {
+            file,
+            format,
+            namespace,
+        } => {
+            let format = match format {
+                Some(format) => format.parse()?,
+                None => transfer::Format::from_path(&file),
+            };
+            let text = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let mut records = transfer::read(&text, format)?;
+            let model = embedding_model_tag(&config.memory);
+            for record in &mut records {
+                if let Some(ns) = &namespace {
+                    record.namespace.clone_from(ns);
+                }
+                if record
+                    .embedding_model
+                    .as_ref()
+                    .is_some_and(|from| *from != model)
+                {
+                    record.embedding = None;
+                }
+            }
+            let memory = open_search_index(config)?;
+            let imported = memory.import_records(&records)?;
+            let embedded = memory.reindex().await?;
+            println!(
+                "✅ Imported {imported} memories from {} ({embedded} newly embedded)",
+                file.display()
+            );
+            Ok(())
+        }
+        crate::MemoryCommands::Archived { namespace } => {
+            let memory = open_search_index(config)?;
+            let archived = memory.archived(&namespace)?;
+            if archived.is_empty() {
+                println!("No archived memories.");
+                return Ok(());
+            }
+            println!("🗄️  {} archived memories:", archived.len());
+            for entry in archived {
+                let namespace = entry.namespace.unwrap_or_default();
+                println!(
+                    "- {namespace} | {} | archived {} | {}",
+                    entry.key, entry.timestamp, entry.content
+                );
+            }
+            println!("Restore one: zeroclaw memory restore <key> --namespace <namespace>");
+            Ok(())
+        }
+        crate::MemoryCommands::Restore {
+            backup: Some(file), ..
+        } => {
+            let previous = backup::restore(config, &file)?;
+            if let Some(previous) = previous {
+                println!("Saved the replaced database as {}", previous.display());
+            }
+            println!("✅ Restored the memory database from {}", file.display());
+            if config.memory.vector_store != "sqlite" {
+                println!(
+                    "Run `zeroclaw memory reindex` to bring the {} vector store in line.",
+                    config.memory.vector_store
+                );
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Restore {
+            key: Some(key),
+            namespace,
+            ..
+        } => {
+            let memory = open_search_index(config)?;
+            if memory.restore_archived(&namespace, &key).await? {
+                println!("✅ Restored {key}");
+            } else {
+                println!("No archived memory '{key}' in namespace '{namespace}'.");
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Restore { key: None, .. } => {
+            anyhow::bail!("Give the key of an archived memory, or --backup FILE")
+        }
+        crate::MemoryCommands::Backup { list: true } => {
+            let backups = backup::list(config)?;
+            if backups.is_empty() {
+                println!("No backups in {}", backup::backup_dir(config).display());
+            }
+            for path in backups {
+                let size = std::fs::metadata(&path).map_or(0, |meta| meta.len());
+                println!("{}  {} KB", path.display(), size / 1024);
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Backup { list: false } => {
+            if !matches!(
+                classify_memory_backend(&config.memory.backend),
+                MemoryBackendKind::Sqlite | MemoryBackendKind::Lucid
+            ) {
+                anyhow::bail!(
+                    "memory backend '{}' has no database to back up",
+                    config.memory.backend
+                );
+            }
+            let path = backup::create(config)?;
+            println!("✅ Backed up the memory database to {}", path.display());
+            Ok(())
+        }
+        crate::MemoryCommands::Reindex {
+            embeddings: true,
+            per_minute,
+        } if classify_memory_backend(&config.memory.backend) != MemoryBackendKind::Postgres => {
+            let memory = open_search_index(config)?;
+            let embedded = memory
+                .backfill_embeddings(per_minute, &|done, total| {
+                    eprintln!("  embedded {done}/{total}");
+                })
+                .await?;
+            println!("✅ Embedded {embedded} memories");
+            Ok(())
+        }
+        crate::MemoryCommands::Reindex { .. } => {
+            if classify_memory_backend(&config.memory.backend) == MemoryBackendKind::Postgres {
+                let memory = build_postgres_memory(&config.memory, config.api_key.as_deref())?;
+                let embedded = memory.reindex().await?;
+                println!("✅ Embedded {embedded} memories");
+                return Ok(());
+            }
+            let memory = open_search_index(config)?;
+            let embedded = memory.reindex().await?;
+            println!("✅ Rebuilt the keyword index and embedded {embedded} memories");
+            Ok(())
+        }
+        crate::MemoryCommands::Migrate { dry_run } => {
+            if classify_memory_backend(&config.memory.backend) == MemoryBackendKind::Postgres {
+                println!(
+                    "The postgres backend creates its table on first use; nothing to migrate."
+                );
+                return Ok(());
+            }
+            let dbs: Vec<_> = crate::db::databases(config)
+                .into_iter()
+                .filter(|db| db.name == "memory")
+                .collect();
+            if dry_run {
+                crate::db::print_status(&dbs)
+            } else {
+                crate::db::apply(&dbs)
+            }
+        }
+        crate::MemoryCommands::Encrypt => {
+            let Some(source) = &config.memory.encryption_key else {
+                anyhow::bail!(
+                    "set memory.encryption_key (env:VAR or keyring:service/account) first"
+                );
+            };
+            let key = cipher::resolve_key(source)?;
+            let db_path = config.workspace_dir.join("memory").join("brain.db");
+            let plaintext = cipher::encrypt_database(&db_path, &key)?;
+            println!("🔒 Encrypted {}", db_path.display());
+            println!(
+                "The unencrypted original is at {}; delete it once zeroclaw opens the encrypted database.",
+                plaintext.display()
+            );
+            Ok(())
+        }
+        crate::MemoryCommands::Model { clear } => {
+            let dir = embeddings::local_models_dir();
+            if clear {
+                if dir.exists() {
+                    std::fs::remove_dir_all(&dir)
+                        .with_context(|| format!("Failed to remove {}", dir.display()))?;
+                    println!("🗑️  Removed local embedding models from {}", dir.display());
+                } else {
+                    println!("No local embedding models downloaded.");
+                }
+                return Ok(());
+            }
+            if config.memory.embedding_provider != "local" {
+                println!("Note: memory.embedding_provider is not \"local\", so the model is not used yet.");
+            }
+            fetch_local_model(&config.memory.embedding_model, dir).await
+        }
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+async fn fetch_local_model(model: &str, dir: std::path::PathBuf) -> anyhow::Result<()> {
+    let provider = embeddings::LocalEmbedding::new(model, dir.clone());
+    println!(
+        "⬇️  Fetching {} into {}",
+        provider.model_code(),
+        dir.display()
+    );
+    provider.load().await?;
+    println!(
+        "✅ {} is ready ({} dimensions)",
+        provider.model_code(),
+        embeddings::EmbeddingProvider::dimensions(&provider)
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+#[allow(clippy::unused_async)] // same signature as the feature-enabled version
+async fn fetch_local_model(_model: &str, _dir: std::path::PathBuf) -> anyhow::Result<()> {
+    anyhow::bail!("This build has no local embeddings; rebuild with --features local-embeddings")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +775,27 @@ mod tests {
         assert_eq!(mem.name(), "lucid");
     }
 
+    #[test]
+    fn factory_postgres_needs_a_url() {
+        let tmp = TempDir::new().unwrap();
+        let mut cfg = MemoryConfig {
+            backend: "postgres".into(),
+            ..MemoryConfig::default()
+        };
+        let error = create_memory(&cfg, tmp.path(), None).err().unwrap();
+        assert!(error.to_string().contains("memory.postgres_url"));
+
+        // Connecting waits for first use
+        cfg.postgres_url = Some("postgres://zeroclaw@localhost/zeroclaw".into());
+        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        assert_eq!(mem.name(), "postgres");
+
+        // It has no archive, so a budget is refused rather than ignored
+        cfg.max_memories = 100;
+        let error = create_memory(&cfg, tmp.path(), None).err().unwrap();
+        assert!(error.to_string().contains("memory.max_memories"));
+    }
+
     #[test]
     fn factory_none_uses_noop_memory() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,73 @@
+//! Memory namespaces.
+//!
+//! Every memory belongs to one namespace. Channel messages run in
+//! `<channel>:<sender>` unless `[memory.namespaces]` maps the sender or the
+//! whole channel to a fixed name; the CLI, gateway and scheduled jobs use
+//! `default`. Backends read the namespace of the running task through
+//! [`current`], so the same `Memory` serves every conversation without
+//! mixing their facts.
+//!
+//! Memories meant for everyone live in `shared`: recall reads it alongside
+//! the active namespace, and only the owner's own conversations may store
+//! there.
+
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Namespace of the owner's own turns and of memories stored before namespaces
+pub const DEFAULT_NAMESPACE: &str = "default";
+/// Pseudo-namespace that reads across all namespaces
+pub const ALL_NAMESPACES: &str = "*";
+/// Namespace every conversation recalls from in addition to its own
+pub const SHARED_NAMESPACE: &str = "shared";
+
+tokio::task_local! {
+    static CURRENT_NAMESPACE: String;
+}
+
+/// Run `fut` with `namespace` as the active memory namespace.
+pub async fn scope<F: Future>(namespace: String, fut: F) -> F::Output {
+    CURRENT_NAMESPACE.scope(namespace, fut).await
+}
+
+/// Namespace of the turn running on this task, `default` outside any scope.
+pub fn current() -> String {
+    CURRENT_NAMESPACE
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| DEFAULT_NAMESPACE.to_string())
+}
+
+/// Namespace for a channel message: an explicit mapping for `channel:sender`,
+/// then for `channel`, else `channel:sender` itself.
+#[allow(clippy::implicit_hasher)]
+pub fn for_sender(mapping: &HashMap<String, String>, channel: &str, sender: &str) -> String {
+    let derived = format!("{channel}:{sender}");
+    mapping
+        .get(&derived)
+        .or_else(|| mapping.get(channel))
+        .cloned()
+        .unwrap_or(derived)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sender_namespace_prefers_explicit_mappings() {
+        let mapping = HashMap::from([
+            ("telegram".to_string(), "default".to_string()),
+            ("discord:42".to_string(), "mods".to_string()),
+        ]);
+        assert_eq!(for_sender(&mapping, "telegram", "alice"), "default");
+        assert_eq!(for_sender(&mapping, "discord", "42"), "mods");
+        assert_eq!(for_sender(&mapping, "discord", "7"), "discord:7");
+    }
+
+    #[tokio::test]
+    async fn scope_sets_current_namespace() {
+        assert_eq!(current(), DEFAULT_NAMESPACE);
+        let inside = scope("discord:7".into(), async { current() }).await;
+        assert_eq!(inside, "discord:7");
+    }
+}
@@ -0,0 +1,384 @@
+//! Second-stage reranking of recall results.
+//!
+//! Keyword and vector search score the query and each memory separately, so
+//! a short query matches plenty of memories that merely share a word with
+//! it. A cross-encoder reads the query and a memory together and scores how
+//! well one answers the other. With `memory.reranker` set, `recall` fetches
+//! `memory.rerank_candidates` first-stage hits, rescores them and returns
+//! the best `limit` in the reranker's order.
+
+use super::traits::MemoryEntry;
+use async_trait::async_trait;
+use std::path::PathBuf;
+#[cfg(feature = "local-embeddings")]
+use std::sync::Arc;
+
+/// Scores how relevant documents are to a query
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// Reranker name
+    fn name(&self) -> &str;
+
+    /// One relevance score in `0.0..=1.0` per document, in input order
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>>;
+}
+
+/// Reorder `entries` by their relevance to `query`, best first, and keep
+/// `limit` of them; each entry's score becomes its relevance. If the
+/// reranker fails, the first-stage order is kept.
+pub async fn rerank_entries(
+    reranker: &dyn Reranker,
+    query: &str,
+    mut entries: Vec<MemoryEntry>,
+    limit: usize,
+) -> Vec<MemoryEntry> {
+    if entries.len() > 1 {
+        let documents: Vec<&str> = entries.iter().map(|e| e.content.as_str()).collect();
+        match reranker.rerank(query, &documents).await {
+            Ok(scores) if scores.len() == entries.len() => {
+                for (entry, score) in entries.iter_mut().zip(scores) {
+                    entry.score = Some(f64::from(score));
+                }
+                entries.sort_by(|a, b| b.score.unwrap_or(0.0).total_cmp(&a.score.unwrap_or(0.0)));
+            }
+            Ok(scores) => tracing::warn!(
+                "{} reranker returned {} scores for {} memories; keeping search order",
+                reranker.name(),
+                scores.len(),
+                entries.len()
+            ),
+            Err(e) => tracing::warn!(
+                "{} reranker failed, keeping search order: {e}",
+                reranker.name()
+            ),
+        }
+    }
+    entries.truncate(limit);
+    entries
+}
+
+// ── Rerank APIs ──────────────────────────────────────────────
+
+/// Cohere's rerank endpoint
+pub const COHERE_RERANK_URL: &str = "https://api.cohere.com/v2/rerank";
+
+/// Jina's rerank endpoint
+pub const JINA_RERANK_URL: &str = "https://api.jina.ai/v1/rerank";
+
+/// A hosted reranker speaking the `/rerank` API shared by Cohere, Jina and
+/// Voyage: `{model, query, documents}` in, `{index, relevance_score}` out
+pub struct ApiReranker {
+    client: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl ApiReranker {
+    pub fn new(url: &str, api_key: Option<&str>, model: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            api_key: api_key.filter(|k| !k.is_empty()).map(ToString::to_string),
+            model: model.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Reranker for ApiReranker {
+    fn name(&self) -> &str {
+        "api"
+    }
+
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+        let mut request = self.client.post(&self.url).json(&serde_json::json!({
+            "model": self.model,
+            "query": query,
+            "documents": documents,
+            "top_n": documents.len(),
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Rerank API error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        let results = json
+            .get("results")
+            .or_else(|| json.get("data"))
+            .and_then(|r| r.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid rerank response: missing 'results'"))?;
+        let mut scores = vec![0.0; documents.len()];
+        for item in results {
+            let index = item
+                .get("index")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|i| usize::try_from(i).ok())
+                .filter(|&i| i < documents.len())
+                .ok_or_else(|| anyhow::anyhow!("Invalid rerank item: bad 'index'"))?;
+            let score = item
+                .get("relevance_score")
+                .and_then(serde_json::Value::as_f64)
+                .ok_or_else(|| anyhow::anyhow!("Invalid rerank item: missing 'relevance_score'"))?;
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                scores[index] = score as f32;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+// ── Local cross-encoder ──────────────────────────────────────
+
+/// Local reranker used when `reranker_model` names none of the local models
+pub const DEFAULT_LOCAL_RERANKER: &str = "bge-reranker-base";
+
+/// Common names of the local rerankers, with or without their organisation
+#[cfg(feature = "local-embeddings")]
+const LOCAL_RERANKER_ALIASES: &[(&str, fastembed::RerankerModel)] = &[
+    (
+        "bge-reranker-base",
+        fastembed::RerankerModel::BGERerankerBase,
+    ),
+    (
+        "bge-reranker-v2-m3",
+        fastembed::RerankerModel::BGERerankerV2M3,
+    ),
+    (
+        "jina-reranker-v1-turbo-en",
+        fastembed::RerankerModel::JINARerankerV1TurboEn,
+    ),
+    (
+        "jina-reranker-v2-base-multilingual",
+        fastembed::RerankerModel::JINARerankerV2BaseMultiligual,
+    ),
+];
+
+/// The local reranker called `name`, e.g. `bge-reranker-base` or
+/// `BAAI/bge-reranker-base`, in any case
+#[cfg(feature = "local-embeddings")]
+pub fn local_reranker_model(name: &str) -> Option<fastembed::RerankerModel> {
+    let name = name.trim();
+    let short = name.rsplit('/').next().unwrap_or(name);
+    LOCAL_RERANKER_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(short))
+        .map(|(_, model)| model.clone())
+        .or_else(|| name.parse().ok())
+}
+
+/// Runs an ONNX cross-encoder in-process. The model is downloaded into
+/// `cache_dir` on first use.
+#[cfg(feature = "local-embeddings")]
+pub struct LocalReranker {
+    model: fastembed::RerankerModel,
+    cache_dir: PathBuf,
+    engine: Arc<std::sync::Mutex<Option<fastembed::TextRerank>>>,
+}
+
+#[cfg(feature = "local-embeddings")]
+impl LocalReranker {
+    pub fn new(model_name: &str, cache_dir: PathBuf) -> Self {
+        let model = local_reranker_model(model_name).unwrap_or_else(|| {
+            if !model_name.is_empty() {
+                tracing::warn!(
+                    "Unknown local reranker '{model_name}', using {DEFAULT_LOCAL_RERANKER}"
+                );
+            }
+            local_reranker_model(DEFAULT_LOCAL_RERANKER).expect("default reranker is supported")
+        });
+        Self {
+            model,
+            cache_dir,
+            engine: Arc::default(),
+        }
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+#[async_trait]
+impl Reranker for LocalReranker {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    async fn rerank(&self, query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+        let engine = Arc::clone(&self.engine);
+        let model = self.model.clone();
+        let cache_dir = self.cache_dir.clone();
+        let query = query.to_string();
+        let documents: Vec<String> = documents.iter().map(ToString::to_string).collect();
+        tokio::task::spawn_blocking(move || {
+            let mut slot = engine
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let engine = match &mut *slot {
+                Some(loaded) => loaded,
+                empty => {
+                    tracing::info!("Loading local reranker {model:?}");
+                    let options = fastembed::RerankInitOptions::new(model)
+                        .with_cache_dir(cache_dir)
+                        .with_show_download_progress(false);
+                    empty.insert(fastembed::TextRerank::try_new(options)?)
+                }
+            };
+            let docs: Vec<&str> = documents.iter().map(String::as_str).collect();
+            let results = engine.rerank(query.as_str(), docs, false, None)?;
+            // Cross-encoders return logits; squash them into 0..1
+            let mut scores = vec![0.0; documents.len()];
+            for result in results {
+                if let Some(slot) = scores.get_mut(result.index) {
+                    *slot = 1.0 / (1.0 + (-result.score).exp());
+                }
+            }
+            Ok(scores)
+        })
+        .await?
+    }
+}
+
+// ── Factory ──────────────────────────────────────────────────
+
+/// The reranker named by `memory.reranker`, or `None` for `"none"`.
+/// `model` falls back to the provider's default when empty.
+pub fn create_reranker(
+    provider: &str,
+    api_key: Option<&str>,
+    model: Option<&str>,
+    cache_dir: PathBuf,
+) -> Option<Box<dyn Reranker>> {
+    let model = model.map(str::trim).filter(|m| !m.is_empty());
+    match provider {
+        "cohere" => Some(Box::new(ApiReranker::new(
+            COHERE_RERANK_URL,
+            api_key,
+            model.unwrap_or("rerank-v3.5"),
+        ))),
+        "jina" => Some(Box::new(ApiReranker::new(
+            JINA_RERANK_URL,
+            api_key,
+            model.unwrap_or("jina-reranker-v2-base-multilingual"),
+        ))),
+        name if name.starts_with("custom:") => Some(Box::new(ApiReranker::new(
+            name.strip_prefix("custom:").unwrap_or(""),
+            api_key,
+            model.unwrap_or(""),
+        ))),
+        "local" => create_local_reranker(model.unwrap_or(""), cache_dir),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+fn create_local_reranker(model: &str, cache_dir: PathBuf) -> Option<Box<dyn Reranker>> {
+    Some(Box::new(LocalReranker::new(model, cache_dir)))
+}
+
+#[cfg(not(feature = "local-embeddings"))]
+fn create_local_reranker(_model: &str, _cache_dir: PathBuf) -> Option<Box<dyn Reranker>> {
+    tracing::warn!(
+        "memory.reranker = \"local\" needs a build with --features local-embeddings; \
+         recall results are not reranked"
+    );
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryCategory;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    /// A `/rerank` endpoint that scores documents containing the whole
+    /// query 0.9 and others 0.1, best first like the real APIs
+    async fn fake_rerank_api() -> String {
+        let app = Router::new().route(
+            "/rerank",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                let query = body["query"].as_str().unwrap().to_string();
+                let mut results: Vec<serde_json::Value> = body["documents"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, doc)| {
+                        let score = if doc.as_str().unwrap().contains(&query) {
+                            0.9
+                        } else {
+                            0.1
+                        };
+                        serde_json::json!({ "index": index, "relevance_score": score })
+                    })
+                    .collect();
+                results.sort_by(|a, b| {
+                    b["relevance_score"]
+                        .as_f64()
+                        .unwrap()
+                        .total_cmp(&a["relevance_score"].as_f64().unwrap())
+                });
+                Json(serde_json::json!({ "results": results }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}/rerank")
+    }
+
+    fn entry(key: &str, content: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category: MemoryCategory::Core,
+            timestamp: String::new(),
+            session_id: None,
+            score: Some(0.5),
+            namespace: None,
+            tags: Vec::new(),
+            match_kind: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn api_reranker_reorders_candidates() {
+        let url = fake_rerank_api().await;
+        let reranker =
+            create_reranker(&format!("custom:{url}"), None, Some("test"), PathBuf::new()).unwrap();
+        let candidates = vec![
+            entry("a", "the coffee machine is broken"),
+            entry("b", "user drinks tea, never coffee"),
+            entry("c", "user prefers oat milk in coffee"),
+        ];
+
+        let ranked = rerank_entries(reranker.as_ref(), "milk in coffee", candidates, 2).await;
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].key, "c");
+        assert!((ranked[0].score.unwrap() - 0.9).abs() < 1e-6);
+
+        // An unreachable reranker leaves the search order alone
+        let down = ApiReranker::new("http://127.0.0.1:9/rerank", None, "test");
+        let kept = rerank_entries(&down, "coffee", vec![entry("a", "x"), entry("b", "y")], 5).await;
+        assert_eq!(kept[0].key, "a");
+        assert_eq!(kept[0].score, Some(0.5));
+    }
+
+    #[test]
+    fn factory_names() {
+        assert!(create_reranker("none", None, None, PathBuf::new()).is_none());
+        assert_eq!(
+            create_reranker("cohere", Some("k"), None, PathBuf::new())
+                .unwrap()
+                .name(),
+            "api"
+        );
+    }
+}
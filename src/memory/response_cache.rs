@@ -5,6 +5,7 @@
 //! configurable TTL (default: 1 hour). The cache is optional and disabled by
 //! default — users opt in via `[memory] response_cache_enabled = true`.
 
+use crate::db::Migration;
 use anyhow::Result;
 use chrono::{Duration, Local};
 use parking_lot::Mutex;
@@ -12,6 +13,30 @@ use rusqlite::{params, Connection};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
+/// Schema history of `response_cache.db`; see [`crate::db`].
+pub(crate) const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "create response_cache",
+    up: create_base_schema,
+}];
+
+fn create_base_schema(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS response_cache (
+            prompt_hash TEXT PRIMARY KEY,
+            model       TEXT NOT NULL,
+            response    TEXT NOT NULL,
+            token_count INTEGER NOT NULL DEFAULT 0,
+            created_at  TEXT NOT NULL,
+            accessed_at TEXT NOT NULL,
+            hit_count   INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_rc_accessed ON response_cache(accessed_at);
+        CREATE INDEX IF NOT EXISTS idx_rc_created ON response_cache(created_at);",
+    )?;
+    Ok(())
+}
+
 /// Response cache backed by a dedicated SQLite database.
 ///
 /// Lives alongside `brain.db` as `response_cache.db` so it can be
@@ -39,19 +64,7 @@ impl ResponseCache {
              PRAGMA temp_store   = MEMORY;",
         )?;
 
-        conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS response_cache (
-                prompt_hash TEXT PRIMARY KEY,
-                model       TEXT NOT NULL,
-                response    TEXT NOT NULL,
-                token_count INTEGER NOT NULL DEFAULT 0,
-                created_at  TEXT NOT NULL,
-                accessed_at TEXT NOT NULL,
-                hit_count   INTEGER NOT NULL DEFAULT 0
-            );
-            CREATE INDEX IF NOT EXISTS idx_rc_accessed ON response_cache(accessed_at);
-            CREATE INDEX IF NOT EXISTS idx_rc_created ON response_cache(created_at);",
-        )?;
+        crate::db::migrate(&conn, &db_path, MIGRATIONS)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
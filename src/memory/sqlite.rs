@@ -1,21 +1,441 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::namespace::{self, ALL_NAMESPACES};
+use super::rerank::{self, Reranker};
+use super::traits::{BudgetAction, ListOrder, Memory, MemoryCategory, MemoryEntry, TimeRange};
+use super::transfer::MemoryRecord;
 use super::vector;
+use super::vector_index::{VectorIndex, VectorPoint};
+use crate::db::Migration;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Schema history of `brain.db`; see [`crate::db`].
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create memories, FTS index and embedding cache",
+        up: create_base_schema,
+    },
+    Migration {
+        version: 2,
+        name: "add memories.session_id",
+        up: add_session_id,
+    },
+    Migration {
+        version: 3,
+        name: "add memories.namespace, unique per namespace and key",
+        up: add_namespace,
+    },
+    Migration {
+        version: 4,
+        name: "create memory_tags",
+        up: create_memory_tags,
+    },
+    Migration {
+        version: 5,
+        name: "add memories.expires_at",
+        up: add_expires_at,
+    },
+    Migration {
+        version: 6,
+        name: "add memories.created_jd, indexed creation time",
+        up: index_created_at,
+    },
+    Migration {
+        version: 7,
+        name: "add memories.importance and access counts, create archived_memories",
+        up: add_importance_and_archive,
+    },
+    Migration {
+        version: 8,
+        name: "create documents and document_chunks for ingested files",
+        up: create_documents,
+    },
+];
+
+/// Keyword hits ranked by relevance, plus the ids only found by substring
+type KeywordHits = (Vec<(String, f32)>, HashSet<String>);
+
+/// Columns read into a [`MemoryEntry`] by [`SqliteMemory::entry_from_row`]
+const ENTRY_COLUMNS: &str = "id, key, content, category, created_at, session_id, namespace,
+    (SELECT group_concat(tag, char(31)) FROM memory_tags WHERE memory_id = memories.id)";
+
+/// Excludes memories past their `expires_at` (UTC, `YYYY-MM-DDTHH:MM:SSZ`)
+const NOT_EXPIRED: &str = "(memories.expires_at IS NULL
+    OR memories.expires_at > strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))";
+
+/// How much a memory is worth keeping under a budget: its importance, or for
+/// memories stored without one, 0.3 plus 0.1 per recall, up to 1
+const PRIORITY: &str = "COALESCE(memories.importance, MIN(1.0, 0.3 + 0.1 * memories.access_count))";
+
+/// Columns copied between `memories` and `archived_memories`
+const ARCHIVED_COLUMNS: &str = "id, key, content, category, namespace, session_id, embedding,
+    created_at, updated_at, expires_at, importance, access_count, accessed_at";
+
+/// Format of `memories.expires_at`, which compares as text
+const EXPIRES_AT_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Creation time as a Julian day, comparable across UTC offsets; indexed
+const CREATED_AT: &str = "memories.created_jd";
+
+/// Row filter shared by the recall searches; binds `:session`, `:namespace`,
+/// `:tags` (a JSON array of tags every hit must carry) and the creation time
+/// bounds `:after` and `:before`. The `shared` namespace is always included.
+const RECALL_FILTER: &str = "(:session IS NULL OR memories.session_id = :session)
+    AND (:namespace = '*' OR memories.namespace IN (:namespace, 'shared'))
+    AND (:after IS NULL OR memories.created_jd >= julianday(:after))
+    AND (:before IS NULL OR memories.created_jd < julianday(:before))
+    AND (:tags IS NULL OR memories.id IN (
+        SELECT memory_id FROM memory_tags
+        WHERE tag IN (SELECT value FROM json_each(:tags))
+        GROUP BY memory_id HAVING COUNT(*) = json_array_length(:tags)))";
+
+/// Which memories a recall may return
+struct RecallFilter<'a> {
+    session_id: Option<&'a str>,
+    namespace: &'a str,
+    tags: Option<&'a str>,
+    after: Option<String>,
+    before: Option<String>,
+}
+
+impl RecallFilter<'_> {
+    fn params(&self) -> Vec<(&str, &dyn rusqlite::ToSql)> {
+        vec![
+            (":session", &self.session_id),
+            (":namespace", &self.namespace),
+            (":tags", &self.tags),
+            (":after", &self.after),
+            (":before", &self.before),
+        ]
+    }
+}
+
+/// Tags are trimmed, lowercased and deduplicated; empty ones are dropped
+pub(super) fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// How the keyword index splits memories into tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FtsTokenizer {
+    /// Words split on Unicode whitespace and punctuation (SQLite's default)
+    Unicode61,
+    /// `unicode61` words reduced to their English stems, so "running"
+    /// matches "runs"
+    Porter,
+    /// Every three-character sequence; matches inside words and works for
+    /// scripts without spaces between words
+    Trigram,
+}
+
+impl std::str::FromStr for FtsTokenizer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "unicode61" => Ok(Self::Unicode61),
+            "porter" => Ok(Self::Porter),
+            "trigram" => Ok(Self::Trigram),
+            other => anyhow::bail!(
+                "Unknown FTS tokenizer '{other}' (expected unicode61, porter or trigram)"
+            ),
+        }
+    }
+}
+
+impl FtsTokenizer {
+    /// The FTS5 `tokenize` option for this tokenizer
+    fn spec(self, remove_diacritics: bool) -> String {
+        match (self, remove_diacritics) {
+            (Self::Unicode61, true) => "unicode61 remove_diacritics 2".into(),
+            (Self::Unicode61, false) => "unicode61 remove_diacritics 0".into(),
+            (Self::Porter, true) => "porter unicode61 remove_diacritics 2".into(),
+            (Self::Porter, false) => "porter unicode61 remove_diacritics 0".into(),
+            (Self::Trigram, true) => "trigram remove_diacritics 1".into(),
+            (Self::Trigram, false) => "trigram".into(),
+        }
+    }
+}
+
+/// The `tokenize` option `memories_fts` was created with; `None` for the
+/// default tokenizer
+fn fts_tokenize_option(conn: &Connection) -> anyhow::Result<Option<String>> {
+    let sql: String = conn.query_row(
+        "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'memories_fts'",
+        [],
+        |row| row.get(0),
+    )?;
+    Ok(sql
+        .split_once("tokenize = '")
+        .and_then(|(_, rest)| rest.split_once('\'').map(|(option, _)| option.to_string())))
+}
+
+/// Memories `reindex` embeds per round; the provider batches within it.
+const REINDEX_CHUNK: usize = 256;
+
+/// Recall hits `find_similar` compares against the new content
+const SIMILAR_CANDIDATES: usize = 10;
+
+/// How many more hits than needed to ask a vector index for, since some
+/// fail the session, tag or expiry filter
+const INDEX_OVERFETCH: usize = 4;
+
+fn create_base_schema(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "-- Core memories table
+        CREATE TABLE IF NOT EXISTS memories (
+            id          TEXT PRIMARY KEY,
+            key         TEXT NOT NULL UNIQUE,
+            content     TEXT NOT NULL,
+            category    TEXT NOT NULL DEFAULT 'core',
+            embedding   BLOB,
+            created_at  TEXT NOT NULL,
+            updated_at  TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
+        CREATE INDEX IF NOT EXISTS idx_memories_key ON memories(key);
+
+        -- FTS5 full-text search (BM25 scoring)
+        CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
+            key, content, content=memories, content_rowid=rowid
+        );
+
+        -- FTS5 triggers: keep in sync with memories table
+        CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
+            INSERT INTO memories_fts(rowid, key, content)
+            VALUES (new.rowid, new.key, new.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, key, content)
+            VALUES ('delete', old.rowid, old.key, old.content);
+        END;
+        CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, key, content)
+            VALUES ('delete', old.rowid, old.key, old.content);
+            INSERT INTO memories_fts(rowid, key, content)
+            VALUES (new.rowid, new.key, new.content);
+        END;
+
+        -- Embedding cache with LRU eviction
+        CREATE TABLE IF NOT EXISTS embedding_cache (
+            content_hash TEXT PRIMARY KEY,
+            embedding    BLOB NOT NULL,
+            created_at   TEXT NOT NULL,
+            accessed_at  TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+    )?;
+    Ok(())
+}
+
+fn add_session_id(conn: &Connection) -> anyhow::Result<()> {
+    // Databases from before versioning may already have the column.
+    let has_session_id: bool = conn
+        .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
+        .query_row([], |row| row.get::<_, String>(0))?
+        .contains("session_id");
+    if !has_session_id {
+        conn.execute_batch(
+            "ALTER TABLE memories ADD COLUMN session_id TEXT;
+             CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id);",
+        )?;
+    }
+    Ok(())
+}
+
+/// Rebuild `memories` with a namespace column; keys become unique per
+/// namespace. Rowids are kept, so the FTS index stays valid.
+fn add_namespace(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS memories_ai;
+        DROP TRIGGER IF EXISTS memories_ad;
+        DROP TRIGGER IF EXISTS memories_au;
+
+        CREATE TABLE memories_v3 (
+            id          TEXT PRIMARY KEY,
+            key         TEXT NOT NULL,
+            content     TEXT NOT NULL,
+            category    TEXT NOT NULL DEFAULT 'core',
+            embedding   BLOB,
+            created_at  TEXT NOT NULL,
+            updated_at  TEXT NOT NULL,
+            session_id  TEXT,
+            namespace   TEXT NOT NULL DEFAULT 'default',
+            UNIQUE (namespace, key)
+        );
+        INSERT INTO memories_v3
+            (rowid, id, key, content, category, embedding, created_at, updated_at, session_id)
+        SELECT rowid, id, key, content, category, embedding, created_at, updated_at, session_id
+        FROM memories;
+        DROP TABLE memories;
+        ALTER TABLE memories_v3 RENAME TO memories;
+
+        CREATE INDEX idx_memories_category ON memories(category);
+        CREATE INDEX idx_memories_key ON memories(key);
+        CREATE INDEX idx_memories_session ON memories(session_id);
+
+        CREATE TRIGGER memories_ai AFTER INSERT ON memories BEGIN
+            INSERT INTO memories_fts(rowid, key, content)
+            VALUES (new.rowid, new.key, new.content);
+        END;
+        CREATE TRIGGER memories_ad AFTER DELETE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, key, content)
+            VALUES ('delete', old.rowid, old.key, old.content);
+        END;
+        CREATE TRIGGER memories_au AFTER UPDATE ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, key, content)
+            VALUES ('delete', old.rowid, old.key, old.content);
+            INSERT INTO memories_fts(rowid, key, content)
+            VALUES (new.rowid, new.key, new.content);
+        END;",
+    )?;
+    Ok(())
+}
+
+fn create_memory_tags(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE memory_tags (
+            memory_id TEXT NOT NULL,
+            tag       TEXT NOT NULL,
+            PRIMARY KEY (memory_id, tag)
+        );
+        CREATE INDEX idx_memory_tags_tag ON memory_tags(tag);
+        CREATE TRIGGER memories_tags_ad AFTER DELETE ON memories BEGIN
+            DELETE FROM memory_tags WHERE memory_id = old.id;
+        END;",
+    )?;
+    Ok(())
+}
+
+fn add_expires_at(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN expires_at TEXT;
+         CREATE INDEX idx_memories_expires ON memories(expires_at);",
+    )?;
+    Ok(())
+}
+
+/// `created_at` keeps the writer's UTC offset, so it does not sort as text;
+/// `created_jd` holds it as a Julian day number. Triggers fill it in, since
+/// an index on `julianday(created_at)` would reject rows whose `created_at`
+/// is relative, like 'now'. The FTS update trigger narrows to the columns it
+/// indexes so that filling `created_jd` does not rewrite the FTS row.
+fn index_created_at(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "DROP TRIGGER memories_au;
+        CREATE TRIGGER memories_au AFTER UPDATE OF key, content ON memories BEGIN
+            INSERT INTO memories_fts(memories_fts, rowid, key, content)
+            VALUES ('delete', old.rowid, old.key, old.content);
+            INSERT INTO memories_fts(rowid, key, content)
+            VALUES (new.rowid, new.key, new.content);
+        END;
+
+        ALTER TABLE memories ADD COLUMN created_jd REAL;
+        UPDATE memories SET created_jd = julianday(created_at);
+        CREATE INDEX idx_memories_created ON memories(created_jd);
+
+        CREATE TRIGGER memories_created_ai AFTER INSERT ON memories BEGIN
+            UPDATE memories SET created_jd = julianday(new.created_at) WHERE rowid = new.rowid;
+        END;
+        CREATE TRIGGER memories_created_au AFTER UPDATE OF created_at ON memories BEGIN
+            UPDATE memories SET created_jd = julianday(new.created_at) WHERE rowid = new.rowid;
+        END;",
+    )?;
+    Ok(())
+}
+
+fn add_importance_and_archive(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE memories ADD COLUMN importance REAL;
+        ALTER TABLE memories ADD COLUMN access_count INTEGER NOT NULL DEFAULT 0;
+        ALTER TABLE memories ADD COLUMN accessed_at TEXT;
+
+        CREATE TABLE archived_memories (
+            id           TEXT PRIMARY KEY,
+            key          TEXT NOT NULL,
+            content      TEXT NOT NULL,
+            category     TEXT NOT NULL,
+            namespace    TEXT NOT NULL,
+            session_id   TEXT,
+            embedding    BLOB,
+            created_at   TEXT NOT NULL,
+            updated_at   TEXT NOT NULL,
+            expires_at   TEXT,
+            importance   REAL,
+            access_count INTEGER NOT NULL DEFAULT 0,
+            accessed_at  TEXT,
+            tags         TEXT NOT NULL DEFAULT '[]',
+            archived_at  TEXT NOT NULL
+        );
+        CREATE INDEX idx_archived_memories_key ON archived_memories(namespace, key);",
+    )?;
+    Ok(())
+}
+
+/// Files ingested by `zeroclaw ingest`, chunked for `docs_search`; see
+/// [`super::documents`]
+fn create_documents(conn: &Connection) -> anyhow::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE documents (
+            id           INTEGER PRIMARY KEY,
+            source       TEXT NOT NULL UNIQUE,
+            content_hash TEXT NOT NULL,
+            ingested_at  TEXT NOT NULL
+        );
+        CREATE TABLE document_chunks (
+            id          INTEGER PRIMARY KEY,
+            document_id INTEGER NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            heading     TEXT,
+            line        INTEGER,
+            content     TEXT NOT NULL,
+            embedding   BLOB
+        );
+        CREATE INDEX idx_document_chunks_document ON document_chunks(document_id);
+        CREATE TRIGGER documents_ad AFTER DELETE ON documents BEGIN
+            DELETE FROM document_chunks WHERE document_id = old.id;
+        END;
+
+        CREATE VIRTUAL TABLE document_chunks_fts USING fts5(
+            heading, content, content=document_chunks, content_rowid=id
+        );
+        CREATE TRIGGER document_chunks_ai AFTER INSERT ON document_chunks BEGIN
+            INSERT INTO document_chunks_fts(rowid, heading, content)
+            VALUES (new.id, new.heading, new.content);
+        END;
+        CREATE TRIGGER document_chunks_ad AFTER DELETE ON document_chunks BEGIN
+            INSERT INTO document_chunks_fts(document_chunks_fts, rowid, heading, content)
+            VALUES ('delete', old.id, old.heading, old.content);
+        END;",
+    )?;
+    Ok(())
+}
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
 /// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
-/// - **Hybrid Merge**: weighted fusion of vector + keyword results
+/// - **Hybrid Merge**: reciprocal rank fusion of vector + keyword results
+/// - **Namespaces**: every query is scoped to [`namespace::current`]
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
+/// - **Vector Index**: optionally searches embeddings in a [`VectorIndex`]
+///   instead of scoring every row
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
 pub struct SqliteMemory {
     conn: Mutex<Connection>,
@@ -23,7 +443,13 @@ pub struct SqliteMemory {
     embedder: Arc<dyn EmbeddingProvider>,
     vector_weight: f32,
     keyword_weight: f32,
+    rrf_k: f32,
+    recency_weight: f32,
+    recency_half_life_days: f32,
     cache_max: usize,
+    vector_index: Option<Arc<dyn VectorIndex>>,
+    reranker: Option<Arc<dyn Reranker>>,
+    rerank_candidates: usize,
 }
 
 impl SqliteMemory {
@@ -43,6 +469,26 @@ impl SqliteMemory {
         vector_weight: f32,
         keyword_weight: f32,
         cache_max: usize,
+    ) -> anyhow::Result<Self> {
+        Self::open(
+            workspace_dir,
+            embedder,
+            vector_weight,
+            keyword_weight,
+            cache_max,
+            None,
+        )
+    }
+
+    /// Like [`Self::with_embedder`], opening the database with a SQLCipher
+    /// `key` when given; see [`super::cipher`]
+    pub fn open(
+        workspace_dir: &Path,
+        embedder: Arc<dyn EmbeddingProvider>,
+        vector_weight: f32,
+        keyword_weight: f32,
+        cache_max: usize,
+        key: Option<&str>,
     ) -> anyhow::Result<Self> {
         let db_path = workspace_dir.join("memory").join("brain.db");
 
@@ -51,6 +497,9 @@ impl SqliteMemory {
         }
 
         let conn = Connection::open(&db_path)?;
+        if let Some(key) = key {
+            super::cipher::unlock(&conn, &db_path, key)?;
+        }
 
         // ── Production-grade PRAGMA tuning ──────────────────────
         // WAL mode: concurrent reads during writes, crash-safe
@@ -66,7 +515,7 @@ impl SqliteMemory {
              PRAGMA temp_store   = MEMORY;",
         )?;
 
-        Self::init_schema(&conn)?;
+        Self::init_schema(&conn, &db_path)?;
 
         Ok(Self {
             conn: Mutex::new(conn),
@@ -74,69 +523,357 @@ impl SqliteMemory {
             embedder,
             vector_weight,
             keyword_weight,
+            rrf_k: vector::DEFAULT_RRF_K,
+            recency_weight: 0.0,
+            recency_half_life_days: 0.0,
             cache_max,
+            vector_index: None,
+            reranker: None,
+            rerank_candidates: 0,
         })
     }
 
-    /// Initialize all tables: memories, FTS5, `embedding_cache`
-    fn init_schema(conn: &Connection) -> anyhow::Result<()> {
-        conn.execute_batch(
-            "-- Core memories table
-            CREATE TABLE IF NOT EXISTS memories (
-                id          TEXT PRIMARY KEY,
-                key         TEXT NOT NULL UNIQUE,
-                content     TEXT NOT NULL,
-                category    TEXT NOT NULL DEFAULT 'core',
-                embedding   BLOB,
-                created_at  TEXT NOT NULL,
-                updated_at  TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_memories_category ON memories(category);
-            CREATE INDEX IF NOT EXISTS idx_memories_key ON memories(key);
+    /// Set the rank fusion constant used by `recall`
+    #[must_use]
+    pub fn with_rrf_k(mut self, k: f32) -> Self {
+        self.rrf_k = k;
+        self
+    }
 
-            -- FTS5 full-text search (BM25 scoring)
-            CREATE VIRTUAL TABLE IF NOT EXISTS memories_fts USING fts5(
-                key, content, content=memories, content_rowid=rowid
-            );
+    /// Let `recall` favour recently stored memories; see [`vector::recency_factor`]
+    #[must_use]
+    pub fn with_recency_decay(mut self, weight: f32, half_life_days: f32) -> Self {
+        self.recency_weight = weight;
+        self.recency_half_life_days = half_life_days;
+        self
+    }
 
-            -- FTS5 triggers: keep in sync with memories table
-            CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-
-            -- Embedding cache with LRU eviction
-            CREATE TABLE IF NOT EXISTS embedding_cache (
-                content_hash TEXT PRIMARY KEY,
-                embedding    BLOB NOT NULL,
-                created_at   TEXT NOT NULL,
-                accessed_at  TEXT NOT NULL
-            );
-            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+    /// Search embeddings in `index`; `brain.db` keeps its copy, from which
+    /// `reindex` rebuilds the index
+    #[must_use]
+    pub fn with_vector_index(mut self, index: Arc<dyn VectorIndex>) -> Self {
+        self.vector_index = Some(index);
+        self
+    }
+
+    /// Rescore up to `candidates` search hits with `reranker` before
+    /// `recall` returns the best of them
+    #[must_use]
+    pub fn with_reranker(mut self, reranker: Arc<dyn Reranker>, candidates: usize) -> Self {
+        self.reranker = Some(reranker);
+        self.rerank_candidates = candidates;
+        self
+    }
+
+    /// Switch the keyword index to `tokenizer`, rebuilding it from
+    /// `memories` when it was built with a different one. Returns whether
+    /// it was rebuilt.
+    pub fn set_fts_tokenizer(
+        &self,
+        tokenizer: FtsTokenizer,
+        remove_diacritics: bool,
+    ) -> anyhow::Result<bool> {
+        let spec = tokenizer.spec(remove_diacritics);
+        let mut conn = self.conn.lock();
+        if fts_tokenize_option(&conn)?.as_deref() == Some(spec.as_str()) {
+            return Ok(false);
+        }
+        // The sync triggers name memories_fts and pick up the new table.
+        let tx = conn.transaction()?;
+        tx.execute_batch(&format!(
+            "DROP TABLE memories_fts;
+             CREATE VIRTUAL TABLE memories_fts USING fts5(
+                 key, content, content=memories, content_rowid=rowid, tokenize = '{spec}'
+             );
+             INSERT INTO memories_fts(memories_fts) VALUES('rebuild');"
+        ))?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Delete memories whose `expires_at` has passed, in every namespace.
+    /// Their vectors stay in an external index, where recall skips them,
+    /// until the next `reindex`.
+    pub fn prune_expired(&self) -> anyhow::Result<usize> {
+        let conn = self.conn.lock();
+        let affected =
+            conn.execute(&format!("DELETE FROM memories WHERE NOT {NOT_EXPIRED}"), [])?;
+        Ok(affected)
+    }
+
+    /// Keep at most `max` memories outside the pinned category, across all
+    /// namespaces. The lowest-priority ones beyond that are archived or
+    /// deleted; the least recently used go first among equals. Returns
+    /// how many were removed.
+    pub fn enforce_budget(&self, max: usize, action: BudgetAction) -> anyhow::Result<usize> {
+        let pinned = crate::agent::memory_loader::PINNED_CATEGORY;
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let total: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM memories WHERE category != ?1",
+            params![pinned],
+            |row| row.get(0),
         )?;
+        let excess = total.saturating_sub(i64::try_from(max).unwrap_or(i64::MAX));
+        if excess <= 0 {
+            return Ok(0);
+        }
 
-        // Migration: add session_id column if not present (safe to run repeatedly)
-        let has_session_id: bool = conn
-            .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
-            .query_row([], |row| row.get::<_, String>(0))?
-            .contains("session_id");
-        if !has_session_id {
-            conn.execute_batch(
-                "ALTER TABLE memories ADD COLUMN session_id TEXT;
-                 CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id);",
+        let ids: Vec<String> = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT id FROM memories WHERE category != ?1
+                 ORDER BY {PRIORITY}, julianday(COALESCE(accessed_at, updated_at)), rowid
+                 LIMIT ?2"
+            ))?;
+            let rows = stmt.query_map(params![pinned, excess], |row| row.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let now = Local::now().to_rfc3339();
+        for id in &ids {
+            if action == BudgetAction::Archive {
+                tx.execute(
+                    &format!(
+                        "INSERT OR REPLACE INTO archived_memories ({ARCHIVED_COLUMNS}, tags, archived_at)
+                         SELECT {ARCHIVED_COLUMNS},
+                                (SELECT json_group_array(tag) FROM memory_tags
+                                 WHERE memory_id = memories.id),
+                                ?2
+                         FROM memories WHERE id = ?1"
+                    ),
+                    params![id, now],
+                )?;
+            }
+            tx.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
+
+    /// Archived memories in `namespace` (`*` for all), most recently
+    /// archived first; `timestamp` is when each was archived
+    pub fn archived(&self, namespace: &str) -> anyhow::Result<Vec<MemoryEntry>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, key, content, category, archived_at, session_id, namespace,
+                    (SELECT group_concat(value, char(31)) FROM json_each(tags))
+             FROM archived_memories
+             WHERE ?1 = '*' OR namespace = ?1
+             ORDER BY archived_at DESC, rowid DESC",
+        )?;
+        let rows = stmt.query_map(params![namespace], |row| Self::entry_from_row(row, None))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Move the memory archived under `key` in `namespace` back, with its
+    /// tags and embedding. Returns `false` when nothing is archived there.
+    pub async fn restore_archived(&self, namespace: &str, key: &str) -> anyhow::Result<bool> {
+        let embedding = {
+            let mut conn = self.conn.lock();
+            let tx = conn.transaction()?;
+            let archived: Option<(String, Option<Vec<u8>>)> = tx
+                .query_row(
+                    "SELECT id, embedding FROM archived_memories
+                     WHERE namespace = ?1 AND key = ?2
+                     ORDER BY archived_at DESC LIMIT 1",
+                    params![namespace, key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            let Some((id, embedding)) = archived else {
+                return Ok(false);
+            };
+            let in_use = tx
+                .query_row(
+                    "SELECT 1 FROM memories WHERE namespace = ?1 AND key = ?2",
+                    params![namespace, key],
+                    |_| Ok(()),
+                )
+                .optional()?
+                .is_some();
+            if in_use {
+                anyhow::bail!(
+                    "A memory with key '{key}' exists again in namespace '{namespace}'; forget it first"
+                );
+            }
+            // Counts as a use, so the next budget pass does not pick it first
+            tx.execute(
+                &format!(
+                    "INSERT INTO memories ({ARCHIVED_COLUMNS})
+                     SELECT id, key, content, category, namespace, session_id, embedding,
+                            created_at, updated_at, expires_at, importance, access_count, ?2
+                     FROM archived_memories WHERE id = ?1"
+                ),
+                params![id, Local::now().to_rfc3339()],
+            )?;
+            tx.execute(
+                "INSERT INTO memory_tags (memory_id, tag)
+                 SELECT ?1, value FROM archived_memories, json_each(archived_memories.tags)
+                 WHERE archived_memories.id = ?1",
+                params![id],
             )?;
+            tx.execute("DELETE FROM archived_memories WHERE id = ?1", params![id])?;
+            tx.commit()?;
+            embedding
+        };
+        self.index_embedding(
+            namespace,
+            key,
+            embedding.map(|bytes| vector::bytes_to_vec(&bytes)),
+        )
+        .await;
+        Ok(true)
+    }
+
+    /// Count a recall of `entries` toward their priority
+    fn record_access(&self, entries: &[MemoryEntry]) -> anyhow::Result<()> {
+        if entries.is_empty() {
+            return Ok(());
         }
+        let now = Local::now().to_rfc3339();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "UPDATE memories SET access_count = access_count + 1, accessed_at = ?1 WHERE id = ?2",
+        )?;
+        for entry in entries {
+            stmt.execute(params![now, entry.id])?;
+        }
+        Ok(())
+    }
 
+    /// Every memory in `namespace` (`*` for all), expired ones included,
+    /// oldest first
+    pub fn export_records(&self, namespace: &str) -> anyhow::Result<Vec<MemoryRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, key, content, category, namespace, session_id, created_at, updated_at,
+                    expires_at, embedding,
+                    (SELECT group_concat(tag, char(31)) FROM memory_tags WHERE memory_id = memories.id)
+             FROM memories
+             WHERE ?1 = '*' OR namespace = ?1
+             ORDER BY created_at, rowid",
+        )?;
+        let rows = stmt.query_map(params![namespace], |row| {
+            Ok(MemoryRecord {
+                id: row.get(0)?,
+                key: row.get(1)?,
+                content: row.get(2)?,
+                category: row.get(3)?,
+                namespace: row.get(4)?,
+                session_id: row.get(5)?,
+                created_at: row.get(6)?,
+                updated_at: row.get(7)?,
+                expires_at: row.get(8)?,
+                embedding: row
+                    .get::<_, Option<Vec<u8>>>(9)?
+                    .map(|bytes| vector::bytes_to_vec(&bytes)),
+                embedding_model: None,
+                tags: row
+                    .get::<_, Option<String>>(10)?
+                    .map(|tags| tags.split('\u{1f}').map(str::to_string).collect())
+                    .unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Write `records` back, replacing memories with the same namespace and
+    /// key. Ids and timestamps are kept unless the id is taken by another
+    /// memory; embeddings are kept when they fit the current embedder, and
+    /// `reindex` computes the rest. Returns how many memories were written.
+    pub fn import_records(&self, records: &[MemoryRecord]) -> anyhow::Result<usize> {
+        let dimensions = self.embedder.dimensions();
+        let now = Local::now().to_rfc3339();
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for record in records {
+            if record.namespace == ALL_NAMESPACES {
+                anyhow::bail!(
+                    "Memory '{}' has namespace '{ALL_NAMESPACES}', which cannot hold memories",
+                    record.key
+                );
+            }
+            let existing: Option<String> = tx
+                .query_row(
+                    "SELECT id FROM memories WHERE namespace = ?1 AND key = ?2",
+                    params![record.namespace, record.key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let embedding = record
+                .embedding
+                .as_deref()
+                .filter(|embedding| dimensions > 0 && embedding.len() == dimensions)
+                .map(vector::vec_to_bytes);
+            let created_at = record.created_at.as_deref().unwrap_or(&now);
+            let updated_at = record.updated_at.as_deref().unwrap_or(created_at);
+
+            let id = if let Some(id) = existing {
+                tx.execute(
+                    "UPDATE memories SET content = ?1, category = ?2, embedding = ?3,
+                        created_at = ?4, updated_at = ?5, session_id = ?6, expires_at = ?7
+                     WHERE id = ?8",
+                    params![
+                        record.content,
+                        record.category,
+                        embedding,
+                        created_at,
+                        updated_at,
+                        record.session_id,
+                        record.expires_at,
+                        id
+                    ],
+                )?;
+                id
+            } else {
+                let id_taken = record.id.is_empty()
+                    || tx
+                        .query_row(
+                            "SELECT 1 FROM memories WHERE id = ?1",
+                            params![record.id],
+                            |_| Ok(()),
+                        )
+                        .optional()?
+                        .is_some();
+                let id = if id_taken {
+                    Uuid::new_v4().to_string()
+                } else {
+                    record.id.clone()
+                };
+                tx.execute(
+                    "INSERT INTO memories (id, key, content, category, embedding, created_at,
+                        updated_at, session_id, namespace, expires_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        id,
+                        record.key,
+                        record.content,
+                        record.category,
+                        embedding,
+                        created_at,
+                        updated_at,
+                        record.session_id,
+                        record.namespace,
+                        record.expires_at
+                    ],
+                )?;
+                id
+            };
+
+            tx.execute("DELETE FROM memory_tags WHERE memory_id = ?1", params![id])?;
+            for tag in normalize_tags(&record.tags) {
+                tx.execute(
+                    "INSERT INTO memory_tags (memory_id, tag) VALUES (?1, ?2)",
+                    params![id, tag],
+                )?;
+            }
+        }
+        tx.commit()?;
+        Ok(records.len())
+    }
+
+    /// Bring the schema up to date: memories, FTS5, `embedding_cache`
+    fn init_schema(conn: &Connection, db_path: &Path) -> anyhow::Result<()> {
+        crate::db::migrate(conn, db_path, MIGRATIONS)?;
         Ok(())
     }
 
@@ -158,6 +895,25 @@ impl SqliteMemory {
         }
     }
 
+    /// Map a row selected as [`ENTRY_COLUMNS`]
+    fn entry_from_row(row: &rusqlite::Row, score: Option<f64>) -> rusqlite::Result<MemoryEntry> {
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: row.get(2)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: row.get(5)?,
+            score,
+            namespace: row.get(6)?,
+            tags: row
+                .get::<_, Option<String>>(7)?
+                .map(|tags| tags.split('\u{1f}').map(str::to_string).collect())
+                .unwrap_or_default(),
+            match_kind: None,
+        })
+    }
+
     /// Deterministic content hash for embedding cache.
     /// Uses SHA-256 (truncated) instead of DefaultHasher, which is
     /// explicitly documented as unstable across Rust versions.
@@ -182,66 +938,86 @@ impl SqliteMemory {
         }
 
         let hash = Self::content_hash(text);
-        let now = Local::now().to_rfc3339();
-
-        // Check cache
-        {
-            let conn = self.conn.lock();
+        if let Some(cached) = self.cached_embedding(&hash)? {
+            return Ok(Some(cached));
+        }
 
-            let mut stmt =
-                conn.prepare("SELECT embedding FROM embedding_cache WHERE content_hash = ?1")?;
-            let cached: Option<Vec<u8>> = stmt.query_row(params![hash], |row| row.get(0)).ok();
+        // Compute embedding
+        let embedding = self.embedder.embed_one(text).await?;
+        self.cache_embedding(&hash, &embedding)?;
+        Ok(Some(embedding))
+    }
 
-            if let Some(bytes) = cached {
-                // Update accessed_at for LRU
-                conn.execute(
-                    "UPDATE embedding_cache SET accessed_at = ?1 WHERE content_hash = ?2",
-                    params![now, hash],
-                )?;
-                return Ok(Some(vector::bytes_to_vec(&bytes)));
+    /// Like [`Self::get_or_compute_embedding`], but a failing embedding
+    /// service only costs vector search: the error is logged and `None`
+    /// returned, so keyword search still works.
+    async fn embedding_or_none(&self, text: &str, purpose: &str) -> Option<Vec<f32>> {
+        match self.get_or_compute_embedding(text).await {
+            Ok(embedding) => embedding,
+            Err(e) => {
+                tracing::warn!(
+                    provider = self.embedder.name(),
+                    "embedding for {purpose} failed, using keyword search only: {e}"
+                );
+                None
             }
         }
+    }
 
-        // Compute embedding
-        let embedding = self.embedder.embed_one(text).await?;
-        let bytes = vector::vec_to_bytes(&embedding);
+    fn cached_embedding(&self, hash: &str) -> anyhow::Result<Option<Vec<f32>>> {
+        let conn = self.conn.lock();
 
-        // Store in cache + LRU eviction
-        {
-            let conn = self.conn.lock();
+        let mut stmt =
+            conn.prepare("SELECT embedding FROM embedding_cache WHERE content_hash = ?1")?;
+        let cached: Option<Vec<u8>> = stmt.query_row(params![hash], |row| row.get(0)).ok();
 
-            conn.execute(
-                "INSERT OR REPLACE INTO embedding_cache (content_hash, embedding, created_at, accessed_at)
-                 VALUES (?1, ?2, ?3, ?4)",
-                params![hash, bytes, now, now],
-            )?;
+        let Some(bytes) = cached else {
+            return Ok(None);
+        };
+        // Update accessed_at for LRU
+        conn.execute(
+            "UPDATE embedding_cache SET accessed_at = ?1 WHERE content_hash = ?2",
+            params![Local::now().to_rfc3339(), hash],
+        )?;
+        Ok(Some(vector::bytes_to_vec(&bytes)))
+    }
 
-            // LRU eviction: keep only cache_max entries
-            #[allow(clippy::cast_possible_wrap)]
-            let max = self.cache_max as i64;
-            conn.execute(
-                "DELETE FROM embedding_cache WHERE content_hash IN (
-                    SELECT content_hash FROM embedding_cache
-                    ORDER BY accessed_at ASC
-                    LIMIT MAX(0, (SELECT COUNT(*) FROM embedding_cache) - ?1)
-                )",
-                params![max],
-            )?;
-        }
+    /// Store in cache + LRU eviction
+    fn cache_embedding(&self, hash: &str, embedding: &[f32]) -> anyhow::Result<()> {
+        let conn = self.conn.lock();
+        let now = Local::now().to_rfc3339();
 
-        Ok(Some(embedding))
+        conn.execute(
+            "INSERT OR REPLACE INTO embedding_cache (content_hash, embedding, created_at, accessed_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![hash, vector::vec_to_bytes(embedding), now, now],
+        )?;
+
+        // LRU eviction: keep only cache_max entries
+        #[allow(clippy::cast_possible_wrap)]
+        let max = self.cache_max as i64;
+        conn.execute(
+            "DELETE FROM embedding_cache WHERE content_hash IN (
+                SELECT content_hash FROM embedding_cache
+                ORDER BY accessed_at ASC
+                LIMIT MAX(0, (SELECT COUNT(*) FROM embedding_cache) - ?1)
+            )",
+            params![max],
+        )?;
+        Ok(())
     }
 
     /// FTS5 BM25 keyword search
     fn fts5_search(
         conn: &Connection,
         query: &str,
+        filter: &RecallFilter<'_>,
         limit: usize,
     ) -> anyhow::Result<Vec<(String, f32)>> {
         // Escape FTS5 special chars and build query
         let fts_query: String = query
             .split_whitespace()
-            .map(|w| format!("\"{w}\""))
+            .map(|w| format!("\"{}\"", w.replace('"', "\"\"")))
             .collect::<Vec<_>>()
             .join(" OR ");
 
@@ -249,18 +1025,23 @@ impl SqliteMemory {
             return Ok(Vec::new());
         }
 
-        let sql = "SELECT m.id, bm25(memories_fts) as score
-                   FROM memories_fts f
-                   JOIN memories m ON m.rowid = f.rowid
-                   WHERE memories_fts MATCH ?1
-                   ORDER BY score
-                   LIMIT ?2";
+        let sql = format!(
+            "SELECT memories.id, bm25(memories_fts) as score
+             FROM memories_fts f
+             JOIN memories ON memories.rowid = f.rowid
+             WHERE memories_fts MATCH :query AND {NOT_EXPIRED} AND {RECALL_FILTER}
+             ORDER BY score
+             LIMIT :limit"
+        );
 
-        let mut stmt = conn.prepare(sql)?;
+        let mut stmt = conn.prepare(&sql)?;
         #[allow(clippy::cast_possible_wrap)]
         let limit_i64 = limit as i64;
+        let mut params = filter.params();
+        params.push((":query", &fts_query));
+        params.push((":limit", &limit_i64));
 
-        let rows = stmt.query_map(params![fts_query, limit_i64], |row| {
+        let rows = stmt.query_map(params.as_slice(), |row| {
             let id: String = row.get(0)?;
             let score: f64 = row.get(1)?;
             // BM25 returns negative scores (lower = better), negate for ranking
@@ -275,24 +1056,99 @@ impl SqliteMemory {
         Ok(results)
     }
 
-    /// Vector similarity search: scan embeddings and compute cosine similarity
-    fn vector_search(
+    /// Substring search over key and content, newest first.
+    /// Catches partial words and tokens that FTS5 splits differently.
+    fn like_search(
         conn: &Connection,
-        query_embedding: &[f32],
+        query: &str,
+        filter: &RecallFilter<'_>,
         limit: usize,
     ) -> anyhow::Result<Vec<(String, f32)>> {
-        let mut stmt =
-            conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+        let keywords: Vec<(String, String)> = query
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, w)| (format!(":kw{i}"), format!("%{w}%")))
+            .collect();
+        if keywords.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let rows = stmt.query_map([], |row| {
-            let id: String = row.get(0)?;
-            let blob: Vec<u8> = row.get(1)?;
-            Ok((id, blob))
-        })?;
+        let where_clause = keywords
+            .iter()
+            .map(|(name, _)| format!("(content LIKE {name} OR key LIKE {name})"))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let sql = format!(
+            "SELECT id FROM memories
+             WHERE ({where_clause}) AND {NOT_EXPIRED} AND {RECALL_FILTER}
+             ORDER BY updated_at DESC
+             LIMIT :limit"
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        #[allow(clippy::cast_possible_wrap)]
+        let limit_i64 = limit as i64;
+        let mut params = filter.params();
+        params.push((":limit", &limit_i64));
+        for (name, pattern) in &keywords {
+            params.push((name.as_str(), pattern));
+        }
+        let rows = stmt.query_map(params.as_slice(), |row| row.get::<_, String>(0))?;
 
-        let mut scored: Vec<(String, f32)> = Vec::new();
+        let mut results = Vec::new();
         for row in rows {
-            let (id, blob) = row?;
+            results.push((row?, 0.0));
+        }
+        Ok(results)
+    }
+
+    /// Keyword ranking: BM25 hits first, then substring-only hits, whose
+    /// ids are also returned on their own
+    fn keyword_search(
+        &self,
+        query: &str,
+        filter: &RecallFilter<'_>,
+        limit: usize,
+    ) -> anyhow::Result<KeywordHits> {
+        let conn = self.conn.lock();
+        // A query FTS5 cannot parse still gets substring matches
+        let mut results = Self::fts5_search(&conn, query, filter, limit).unwrap_or_default();
+        let mut substring = HashSet::new();
+        if results.len() < limit {
+            for hit in Self::like_search(&conn, query, filter, limit)? {
+                if results.len() >= limit {
+                    break;
+                }
+                if !results.iter().any(|(id, _)| *id == hit.0) {
+                    substring.insert(hit.0.clone());
+                    results.push(hit);
+                }
+            }
+        }
+        Ok((results, substring))
+    }
+
+    /// Vector similarity search: load embeddings, then compute cosine similarity
+    /// without holding the connection lock
+    fn vector_search(
+        &self,
+        query_embedding: &[f32],
+        filter: &RecallFilter<'_>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let rows: Vec<(String, Vec<u8>)> = {
+            let conn = self.conn.lock();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, embedding FROM memories
+                 WHERE embedding IS NOT NULL AND {NOT_EXPIRED} AND {RECALL_FILTER}"
+            ))?;
+            let rows = stmt.query_map(filter.params().as_slice(), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let mut scored: Vec<(String, f32)> = Vec::new();
+        for (id, blob) in rows {
             let emb = vector::bytes_to_vec(&blob);
             let sim = vector::cosine_similarity(query_embedding, &emb);
             if sim > 0.0 {
@@ -305,8 +1161,163 @@ impl SqliteMemory {
         Ok(scored)
     }
 
+    /// Vector search through the external index: over-fetch, then keep the
+    /// hits that are still in `brain.db` and pass `filter`
+    async fn indexed_search(
+        &self,
+        index: &dyn VectorIndex,
+        query_embedding: &[f32],
+        filter: &RecallFilter<'_>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let hits = index
+            .search(query_embedding, filter.namespace, limit * INDEX_OVERFETCH)
+            .await?;
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT 1 FROM memories
+             WHERE id = :id AND embedding IS NOT NULL AND {NOT_EXPIRED} AND {RECALL_FILTER}"
+        ))?;
+        let mut results = Vec::new();
+        for (id, sim) in hits {
+            if results.len() >= limit {
+                break;
+            }
+            if sim <= 0.0 {
+                continue;
+            }
+            let mut params = filter.params();
+            params.push((":id", &id));
+            if stmt.exists(params.as_slice())? {
+                results.push((id, sim));
+            }
+        }
+        Ok(results)
+    }
+
+    /// The newest memories passing `filter`, for a recall by time alone
+    fn list_in_range(
+        &self,
+        filter: &RecallFilter<'_>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories
+             WHERE {CREATED_AT} IS NOT NULL AND {NOT_EXPIRED} AND {RECALL_FILTER}
+             ORDER BY {CREATED_AT} DESC
+             LIMIT :limit"
+        ))?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let mut params = filter.params();
+        params.push((":limit", &limit));
+        let rows = stmt.query_map(params.as_slice(), |row| Self::entry_from_row(row, None))?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    /// Hybrid recall: keyword and vector rankings fused, limited to `filter`
+    async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: RecallFilter<'_>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Compute query embedding (async, before lock)
+        let query_embedding = self.embedding_or_none(query, "recall").await;
+
+        // Keyword and vector search run side by side; each ranks up to
+        // 2 × limit candidates for fusion. A vector index answers before
+        // the keyword search starts.
+        let candidates = limit * 2;
+        let filter = &filter;
+        let indexed = match (&self.vector_index, query_embedding.as_deref()) {
+            (Some(index), Some(qe)) => Some(
+                self.indexed_search(index.as_ref(), qe, filter, candidates)
+                    .await
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("memory {} search failed: {e}", index.name());
+                        Vec::new()
+                    }),
+            ),
+            _ => None,
+        };
+        let ((keyword_results, substring), vector_results) = std::thread::scope(|s| {
+            let brute_force = query_embedding.as_deref().filter(|_| indexed.is_none());
+            let vector = brute_force.map(|qe| {
+                s.spawn(move || {
+                    self.vector_search(qe, filter, candidates)
+                        .unwrap_or_else(|e| {
+                            tracing::warn!("memory vector search failed: {e}");
+                            Vec::new()
+                        })
+                })
+            });
+            let keyword = self
+                .keyword_search(query, filter, candidates)
+                .unwrap_or_else(|e| {
+                    tracing::warn!("memory keyword search failed: {e}");
+                    (Vec::new(), HashSet::new())
+                });
+            let vector = vector
+                .and_then(|handle| handle.join().ok())
+                .or(indexed)
+                .unwrap_or_default();
+            (keyword, vector)
+        });
+
+        let merged = vector::rrf_merge(
+            &vector_results,
+            &keyword_results,
+            self.vector_weight,
+            self.keyword_weight,
+            self.rrf_k,
+            candidates,
+        );
+
+        // Fetch full entries for merged results, then let age discount them
+        let now = Local::now();
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS}, updated_at FROM memories WHERE id = ?1"
+        ))?;
+        let mut results = Vec::new();
+        for scored in &merged {
+            if let Ok(entry) = stmt.query_row(params![scored.id], |row| {
+                let updated_at: String = row.get(8)?;
+                #[allow(clippy::cast_possible_truncation)]
+                let age_days = DateTime::parse_from_rfc3339(&updated_at).map_or(0.0, |at| {
+                    (now - at.with_timezone(&Local)).num_seconds() as f32 / 86_400.0
+                });
+                let score = scored.final_score
+                    * vector::recency_factor(
+                        age_days,
+                        self.recency_half_life_days,
+                        self.recency_weight,
+                    );
+                Self::entry_from_row(row, Some(f64::from(score)))
+            }) {
+                results.push(MemoryEntry {
+                    match_kind: Some(scored.match_kind(substring.contains(&scored.id))),
+                    ..entry
+                });
+            }
+        }
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit);
+
+        Ok(results)
+    }
+
     /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
     pub async fn reindex(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
@@ -315,37 +1326,182 @@ impl SqliteMemory {
             conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
         }
 
-        // Step 2: Re-embed all memories that lack embeddings
-        if self.embedder.dimensions() == 0 {
-            return Ok(0);
+        // Step 2: Embed all memories that lack embeddings
+        let count = self.backfill_embeddings(0, &|_, _| {}).await?;
+
+        // Step 3: Rebuild the vector index from the stored embeddings
+        if let Some(index) = &self.vector_index {
+            index.clear().await?;
+            let mut last_rowid = 0_i64;
+            loop {
+                let (points, next_rowid) = {
+                    let conn = self.conn.lock();
+                    let mut stmt = conn.prepare(
+                        "SELECT rowid, id, namespace, embedding FROM memories
+                         WHERE embedding IS NOT NULL AND rowid > ?1
+                         ORDER BY rowid LIMIT ?2",
+                    )?;
+                    #[allow(clippy::cast_possible_wrap)]
+                    let rows =
+                        stmt.query_map(params![last_rowid, REINDEX_CHUNK as i64], |row| {
+                            Ok((
+                                row.get::<_, i64>(0)?,
+                                VectorPoint {
+                                    id: row.get(1)?,
+                                    namespace: row.get(2)?,
+                                    embedding: vector::bytes_to_vec(&row.get::<_, Vec<u8>>(3)?),
+                                },
+                            ))
+                        })?;
+                    let rows: Vec<(i64, VectorPoint)> = rows.collect::<Result<_, _>>()?;
+                    let next_rowid = rows.last().map(|(rowid, _)| *rowid);
+                    (
+                        rows.into_iter().map(|(_, point)| point).collect::<Vec<_>>(),
+                        next_rowid,
+                    )
+                };
+                let Some(next_rowid) = next_rowid else { break };
+                index.upsert(&points).await?;
+                last_rowid = next_rowid;
+            }
         }
 
-        let entries: Vec<(String, String)> = {
-            let conn = self.conn.lock();
+        Ok(count)
+    }
 
-            let mut stmt =
-                conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
-            let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
-            })?;
-            rows.filter_map(std::result::Result::ok).collect()
+    /// Embed memories stored without a vector, oldest first, sending at
+    /// most `per_minute` texts per minute to the provider (0 for no
+    /// limit). Each batch is saved as it completes, so an interrupted run
+    /// resumes where it stopped. `progress` gets the number embedded so
+    /// far and the number that lacked a vector at the start. Returns how
+    /// many were embedded.
+    pub async fn backfill_embeddings(
+        &self,
+        per_minute: usize,
+        progress: &(dyn Fn(usize, usize) + Send + Sync),
+    ) -> anyhow::Result<usize> {
+        if self.embedder.dimensions() == 0 {
+            return Ok(0);
+        }
+        let total: i64 = self.conn.lock().query_row(
+            "SELECT COUNT(*) FROM memories WHERE embedding IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        let total = usize::try_from(total).unwrap_or(0);
+        let batch = if per_minute > 0 {
+            per_minute.min(REINDEX_CHUNK)
+        } else {
+            REINDEX_CHUNK
         };
 
+        // Walk by rowid so a memory the provider cannot embed is not retried
+        let mut last_rowid = 0_i64;
         let mut count = 0;
-        for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
-                let bytes = vector::vec_to_bytes(&emb);
+        loop {
+            let started = std::time::Instant::now();
+            let chunk: Vec<(i64, String, String, String)> = {
                 let conn = self.conn.lock();
-                conn.execute(
-                    "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-                    params![bytes, id],
+                let mut stmt = conn.prepare(
+                    "SELECT rowid, id, namespace, content FROM memories
+                     WHERE embedding IS NULL AND rowid > ?1
+                     ORDER BY rowid LIMIT ?2",
                 )?;
-                count += 1;
+                #[allow(clippy::cast_possible_wrap)]
+                let rows = stmt.query_map(params![last_rowid, batch as i64], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+                })?;
+                rows.collect::<Result<_, _>>()?
+            };
+            let Some((rowid, ..)) = chunk.last() else {
+                break;
+            };
+            last_rowid = *rowid;
+
+            // Uncached contents go to the provider in one batch.
+            let mut vectors: Vec<Option<Vec<f32>>> = Vec::with_capacity(chunk.len());
+            let mut missing = Vec::new();
+            for (i, (.., content)) in chunk.iter().enumerate() {
+                let cached = self.cached_embedding(&Self::content_hash(content))?;
+                if cached.is_none() {
+                    missing.push(i);
+                }
+                vectors.push(cached);
             }
-        }
+            if !missing.is_empty() {
+                let texts: Vec<&str> = missing.iter().map(|&i| chunk[i].3.as_str()).collect();
+                let computed = self.embedder.embed(&texts).await?;
+                for (&i, embedding) in missing.iter().zip(computed) {
+                    self.cache_embedding(&Self::content_hash(&chunk[i].3), &embedding)?;
+                    vectors[i] = Some(embedding);
+                }
+            }
+
+            let mut points = Vec::new();
+            {
+                let mut conn = self.conn.lock();
+                let tx = conn.transaction()?;
+                for ((_, id, namespace, _), embedding) in chunk.iter().zip(vectors) {
+                    let Some(embedding) = embedding else { continue };
+                    tx.execute(
+                        "UPDATE memories SET embedding = ?1 WHERE id = ?2",
+                        params![vector::vec_to_bytes(&embedding), id],
+                    )?;
+                    points.push(VectorPoint {
+                        id: id.clone(),
+                        namespace: namespace.clone(),
+                        embedding,
+                    });
+                }
+                tx.commit()?;
+            }
+            count += points.len();
+            if let Some(index) = &self.vector_index {
+                if let Err(e) = index.upsert(&points).await {
+                    tracing::warn!(
+                        "Failed to index backfilled embeddings in {}: {e:#}",
+                        index.name()
+                    );
+                }
+            }
+            progress(count, total);
 
+            if per_minute > 0 && !missing.is_empty() {
+                #[allow(clippy::cast_precision_loss)]
+                let share =
+                    Duration::from_secs_f64(missing.len() as f64 * 60.0 / per_minute as f64);
+                tokio::time::sleep(share.saturating_sub(started.elapsed())).await;
+            }
+        }
         Ok(count)
     }
+
+    /// Copy one memory's embedding into the vector index, if there is one.
+    /// A failure only costs recall quality until the next `reindex`.
+    async fn index_embedding(&self, namespace: &str, key: &str, embedding: Option<Vec<f32>>) {
+        let (Some(index), Some(embedding)) = (&self.vector_index, embedding) else {
+            return;
+        };
+        let id: Option<String> = self
+            .conn
+            .lock()
+            .query_row(
+                "SELECT id FROM memories WHERE namespace = ?1 AND key = ?2",
+                params![namespace, key],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap_or(None);
+        let Some(id) = id else { return };
+        let point = VectorPoint {
+            id,
+            namespace: namespace.to_string(),
+            embedding,
+        };
+        if let Err(e) = index.upsert(&[point]).await {
+            tracing::warn!("memory {} update failed: {e}", index.name());
+        }
+    }
 }
 
 #[async_trait]
@@ -361,29 +1517,37 @@ impl Memory for SqliteMemory {
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> anyhow::Result<()> {
-        // Compute embedding (async, before lock)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        // Compute embedding (async, before lock); `reindex` fills it in
+        // later if the provider is down now.
+        let embedding = self.embedding_or_none(content, "store").await;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
+
+        let namespace = namespace::current();
+        if namespace == ALL_NAMESPACES {
+            anyhow::bail!("Memories are stored in one namespace, not '{ALL_NAMESPACES}'");
+        }
 
-        let conn = self.conn.lock();
-        let now = Local::now().to_rfc3339();
-        let cat = Self::category_to_str(&category);
-        let id = Uuid::new_v4().to_string();
+        {
+            let conn = self.conn.lock();
+            let now = Local::now().to_rfc3339();
+            let cat = Self::category_to_str(&category);
+            let id = Uuid::new_v4().to_string();
 
-        conn.execute(
-            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(key) DO UPDATE SET
+            conn.execute(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id, namespace)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+             ON CONFLICT(namespace, key) DO UPDATE SET
                 content = excluded.content,
                 category = excluded.category,
                 embedding = excluded.embedding,
                 updated_at = excluded.updated_at,
-                session_id = excluded.session_id",
-            params![id, key, content, cat, embedding_bytes, now, now, session_id],
+                session_id = excluded.session_id,
+                expires_at = NULL",
+            params![id, key, content, cat, embedding_bytes, now, now, session_id, namespace],
         )?;
+        }
 
+        self.index_embedding(&namespace, key, embedding).await;
         Ok(())
     }
 
@@ -393,148 +1557,66 @@ impl Memory for SqliteMemory {
         limit: usize,
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
-        if query.trim().is_empty() {
-            return Ok(Vec::new());
-        }
-
-        // Compute query embedding (async, before lock)
-        let query_embedding = self.get_or_compute_embedding(query).await?;
-
-        let conn = self.conn.lock();
+        self.recall_in_range(query, limit, session_id, &[], &TimeRange::default())
+            .await
+    }
 
-        // FTS5 BM25 keyword search
-        let keyword_results = Self::fts5_search(&conn, query, limit * 2).unwrap_or_default();
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_in_range(query, limit, session_id, tags, &TimeRange::default())
+            .await
+    }
 
-        // Vector similarity search (if embeddings available)
-        let vector_results = if let Some(ref qe) = query_embedding {
-            Self::vector_search(&conn, qe, limit * 2).unwrap_or_default()
-        } else {
-            Vec::new()
+    async fn recall_in_range(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+        range: &TimeRange,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let tags = normalize_tags(tags);
+        let namespace = namespace::current();
+        let tags_json = (!tags.is_empty()).then(|| serde_json::json!(tags).to_string());
+        let filter = RecallFilter {
+            session_id,
+            namespace: &namespace,
+            tags: tags_json.as_deref(),
+            after: range.after.map(|at| at.to_rfc3339()),
+            before: range.before.map(|at| at.to_rfc3339()),
         };
-
-        // Hybrid merge
-        let merged = if vector_results.is_empty() {
-            // No embeddings — use keyword results only
-            keyword_results
-                .iter()
-                .map(|(id, score)| vector::ScoredResult {
-                    id: id.clone(),
-                    vector_score: None,
-                    keyword_score: Some(*score),
-                    final_score: *score,
-                })
-                .collect::<Vec<_>>()
+        let entries = if query.trim().is_empty() && !range.is_unbounded() {
+            self.list_in_range(&filter, limit)?
+        } else if let Some(reranker) = &self.reranker {
+            let candidates = self
+                .search(query, limit.max(self.rerank_candidates), filter)
+                .await?;
+            rerank::rerank_entries(reranker.as_ref(), query, candidates, limit).await
         } else {
-            vector::hybrid_merge(
-                &vector_results,
-                &keyword_results,
-                self.vector_weight,
-                self.keyword_weight,
-                limit,
-            )
+            self.search(query, limit, filter).await?
         };
-
-        // Fetch full entries for merged results
-        let mut results = Vec::new();
-        for scored in &merged {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories WHERE id = ?1",
-            )?;
-            if let Ok(entry) = stmt.query_row(params![scored.id], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: Some(f64::from(scored.final_score)),
-                })
-            }) {
-                // Filter by session_id if requested
-                if let Some(sid) = session_id {
-                    if entry.session_id.as_deref() != Some(sid) {
-                        continue;
-                    }
-                }
-                results.push(entry);
-            }
+        if let Err(e) = self.record_access(&entries) {
+            tracing::warn!("memory access tracking failed: {e}");
         }
-
-        // If hybrid returned nothing, fall back to LIKE search
-        if results.is_empty() {
-            let keywords: Vec<String> =
-                query.split_whitespace().map(|w| format!("%{w}%")).collect();
-            if !keywords.is_empty() {
-                let conditions: Vec<String> = keywords
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| {
-                        format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
-                    })
-                    .collect();
-                let where_clause = conditions.join(" OR ");
-                let sql = format!(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
-                     WHERE {where_clause}
-                     ORDER BY updated_at DESC
-                     LIMIT ?{}",
-                    keywords.len() * 2 + 1
-                );
-                let mut stmt = conn.prepare(&sql)?;
-                let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-                for kw in &keywords {
-                    param_values.push(Box::new(kw.clone()));
-                    param_values.push(Box::new(kw.clone()));
-                }
-                #[allow(clippy::cast_possible_wrap)]
-                param_values.push(Box::new(limit as i64));
-                let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                    param_values.iter().map(AsRef::as_ref).collect();
-                let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok(MemoryEntry {
-                        id: row.get(0)?,
-                        key: row.get(1)?,
-                        content: row.get(2)?,
-                        category: Self::str_to_category(&row.get::<_, String>(3)?),
-                        timestamp: row.get(4)?,
-                        session_id: row.get(5)?,
-                        score: Some(1.0),
-                    })
-                })?;
-                for row in rows {
-                    let entry = row?;
-                    if let Some(sid) = session_id {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
-                        }
-                    }
-                    results.push(entry);
-                }
-            }
-        }
-
-        results.truncate(limit);
-        Ok(results)
+        Ok(entries)
     }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let conn = self.conn.lock();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, key, content, category, created_at, session_id FROM memories WHERE key = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories
+             WHERE key = ?1 AND (?2 = '*' OR namespace = ?2) AND {NOT_EXPIRED}
+             ORDER BY updated_at DESC"
+        ))?;
 
-        let mut rows = stmt.query_map(params![key], |row| {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: row.get(5)?,
-                score: None,
-            })
+        let mut rows = stmt.query_map(params![key, namespace::current()], |row| {
+            Self::entry_from_row(row, None)
         })?;
 
         match rows.next() {
@@ -543,34 +1625,116 @@ impl Memory for SqliteMemory {
         }
     }
 
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        let conn = self.conn.lock();
+        let entry = conn
+            .query_row(
+                &format!(
+                    "SELECT {ENTRY_COLUMNS} FROM memories
+                     WHERE id = ?1 AND (?2 = '*' OR namespace = ?2) AND {NOT_EXPIRED}"
+                ),
+                params![id, namespace::current()],
+                |row| Self::entry_from_row(row, None),
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    async fn find_similar(
+        &self,
+        content: &str,
+        category: &MemoryCategory,
+    ) -> anyhow::Result<Option<MemoryEntry>> {
+        let namespace = namespace::current();
+        let filter = RecallFilter {
+            session_id: None,
+            namespace: &namespace,
+            tags: None,
+            after: None,
+            before: None,
+        };
+        let candidates = self.search(content, SIMILAR_CANDIDATES, filter).await?;
+        // Already cached by the search above
+        let embedding = self.embedding_or_none(content, "dedup").await;
+
+        let conn = self.conn.lock();
+        let mut best: Option<MemoryEntry> = None;
+        for mut entry in candidates {
+            // Shared memories are recalled here but cannot be updated
+            if entry.category != *category || entry.namespace.as_deref() != Some(&namespace) {
+                continue;
+            }
+            let mut similarity = vector::text_similarity(content, &entry.content);
+            if let Some(embedding) = &embedding {
+                let stored: Option<Vec<u8>> = conn.query_row(
+                    "SELECT embedding FROM memories WHERE id = ?1",
+                    params![entry.id],
+                    |row| row.get(0),
+                )?;
+                if let Some(bytes) = stored {
+                    similarity = similarity.max(vector::cosine_similarity(
+                        embedding,
+                        &vector::bytes_to_vec(&bytes),
+                    ));
+                }
+            }
+            if best
+                .as_ref()
+                .map_or(true, |best| best.score < Some(f64::from(similarity)))
+            {
+                entry.score = Some(f64::from(similarity));
+                best = Some(entry);
+            }
+        }
+        Ok(best)
+    }
+
+    async fn update(&self, key: &str, content: &str) -> anyhow::Result<bool> {
+        // Same as `store`: embed first, and `reindex` catches up on failures.
+        // The FTS row follows through the update trigger.
+        let embedding = self.embedding_or_none(content, "update").await;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
+
+        let namespace = namespace::current();
+        let affected = self.conn.lock().execute(
+            &format!(
+                "UPDATE memories SET content = ?1, embedding = ?2, updated_at = ?3
+                 WHERE key = ?4 AND namespace = ?5 AND {NOT_EXPIRED}"
+            ),
+            params![
+                content,
+                embedding_bytes,
+                Local::now().to_rfc3339(),
+                key,
+                namespace
+            ],
+        )?;
+        if affected > 0 {
+            self.index_embedding(&namespace, key, embedding).await;
+        }
+        Ok(affected > 0)
+    }
+
     async fn list(
         &self,
         category: Option<&MemoryCategory>,
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
         let conn = self.conn.lock();
+        let namespace = namespace::current();
 
         let mut results = Vec::new();
 
-        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: row.get(5)?,
-                score: None,
-            })
-        };
+        let row_mapper = |row: &rusqlite::Row| Self::entry_from_row(row, None);
 
         if let Some(cat) = category {
             let cat_str = Self::category_to_str(cat);
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories
-                 WHERE category = ?1 ORDER BY updated_at DESC",
-            )?;
-            let rows = stmt.query_map(params![cat_str], row_mapper)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE category = ?1 AND (?2 = '*' OR namespace = ?2) AND {NOT_EXPIRED}
+                 ORDER BY updated_at DESC"
+            ))?;
+            let rows = stmt.query_map(params![cat_str, namespace], row_mapper)?;
             for row in rows {
                 let entry = row?;
                 if let Some(sid) = session_id {
@@ -581,11 +1745,12 @@ impl Memory for SqliteMemory {
                 results.push(entry);
             }
         } else {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories
-                 ORDER BY updated_at DESC",
-            )?;
-            let rows = stmt.query_map([], row_mapper)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE (?1 = '*' OR namespace = ?1) AND {NOT_EXPIRED}
+                 ORDER BY updated_at DESC"
+            ))?;
+            let rows = stmt.query_map(params![namespace], row_mapper)?;
             for row in rows {
                 let entry = row?;
                 if let Some(sid) = session_id {
@@ -600,21 +1765,136 @@ impl Memory for SqliteMemory {
         Ok(results)
     }
 
-    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+    async fn list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        order: ListOrder,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
         let conn = self.conn.lock();
-        let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
-        Ok(affected > 0)
+        let direction = match order {
+            ListOrder::NewestFirst => "DESC",
+            ListOrder::OldestFirst => "ASC",
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories
+             WHERE (?3 = '*' OR namespace = ?3) AND {NOT_EXPIRED}
+             ORDER BY updated_at {direction}, rowid {direction} LIMIT ?1 OFFSET ?2"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                i64::try_from(limit).unwrap_or(i64::MAX),
+                i64::try_from(offset).unwrap_or(i64::MAX),
+                namespace::current()
+            ],
+            |row| Self::entry_from_row(row, None),
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn set_tags(&self, key: &str, tags: &[String]) -> anyhow::Result<()> {
+        let tags = normalize_tags(tags);
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let id: String = tx
+            .query_row(
+                "SELECT id FROM memories WHERE key = ?1 AND namespace = ?2",
+                params![key, namespace::current()],
+                |row| row.get(0),
+            )
+            .map_err(|_| anyhow::anyhow!("No memory with key '{key}'"))?;
+        tx.execute("DELETE FROM memory_tags WHERE memory_id = ?1", params![id])?;
+        for tag in &tags {
+            tx.execute(
+                "INSERT INTO memory_tags (memory_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn tags(&self) -> anyhow::Result<Vec<(String, usize)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT t.tag, COUNT(*) FROM memory_tags t
+             JOIN memories ON memories.id = t.memory_id
+             WHERE (?1 = '*' OR memories.namespace = ?1) AND {NOT_EXPIRED}
+             GROUP BY t.tag
+             ORDER BY COUNT(*) DESC, t.tag"
+        ))?;
+        let rows = stmt.query_map(params![namespace::current()], |row| {
+            let count: i64 = row.get(1)?;
+            Ok((row.get(0)?, usize::try_from(count).unwrap_or(0)))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    async fn set_importance(&self, key: &str, importance: f64) -> anyhow::Result<()> {
+        if !(0.0..=1.0).contains(&importance) {
+            anyhow::bail!("Importance must be between 0 and 1, not {importance}");
+        }
+        let affected = self.conn.lock().execute(
+            "UPDATE memories SET importance = ?1 WHERE key = ?2 AND namespace = ?3",
+            params![importance, key, namespace::current()],
+        )?;
+        if affected == 0 {
+            anyhow::bail!("No memory with key '{key}'");
+        }
+        Ok(())
+    }
+
+    async fn set_expiry(&self, key: &str, expires_at: Option<DateTime<Utc>>) -> anyhow::Result<()> {
+        let conn = self.conn.lock();
+        let affected = conn.execute(
+            "UPDATE memories SET expires_at = ?1 WHERE key = ?2 AND namespace = ?3",
+            params![
+                expires_at.map(|at| at.format(EXPIRES_AT_FORMAT).to_string()),
+                key,
+                namespace::current()
+            ],
+        )?;
+        if affected == 0 {
+            anyhow::bail!("No memory with key '{key}'");
+        }
+        Ok(())
+    }
+
+    async fn forget(&self, key: &str) -> anyhow::Result<bool> {
+        let ids: Vec<String> = {
+            let conn = self.conn.lock();
+            let mut stmt = conn.prepare(
+                "DELETE FROM memories WHERE key = ?1 AND (?2 = '*' OR namespace = ?2) RETURNING id",
+            )?;
+            let rows = stmt.query_map(params![key, namespace::current()], |row| row.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        if let Some(index) = &self.vector_index {
+            if let Err(e) = index.remove(&ids).await {
+                tracing::warn!("memory {} removal failed: {e}", index.name());
+            }
+        }
+        Ok(!ids.is_empty())
     }
 
     async fn count(&self) -> anyhow::Result<usize> {
         let conn = self.conn.lock();
-        let count: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM memories WHERE (?1 = '*' OR namespace = ?1) AND {NOT_EXPIRED}"),
+            params![namespace::current()],
+            |row| row.get(0),
+        )?;
         #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
         Ok(count as usize)
     }
 
     async fn health_check(&self) -> bool {
-        self.conn.lock().execute_batch("SELECT 1").is_ok()
+        // Take (and release) the write lock so a read-only or stuck database
+        // is reported, not just an unreadable one.
+        self.conn
+            .lock()
+            .execute_batch("BEGIN IMMEDIATE; ROLLBACK;")
+            .is_ok()
     }
 }
 
@@ -779,6 +2059,256 @@ mod tests {
         assert_eq!(daily.len(), 1);
     }
 
+    #[tokio::test]
+    async fn sqlite_list_page_is_newest_first() {
+        let (_tmp, mem) = temp_sqlite();
+        for key in ["a", "b", "c"] {
+            mem.store(key, key, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let keys = |entries: Vec<MemoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.key).collect()
+        };
+
+        let first = mem.list_page(0, 2, ListOrder::NewestFirst).await.unwrap();
+        assert_eq!(keys(first), ["c", "b"]);
+        let second = mem.list_page(2, 2, ListOrder::NewestFirst).await.unwrap();
+        assert_eq!(keys(second), ["a"]);
+        let oldest = mem.list_page(0, 2, ListOrder::OldestFirst).await.unwrap();
+        assert_eq!(keys(oldest), ["a", "b"]);
+        assert!(mem
+            .list_page(3, 2, ListOrder::NewestFirst)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Embeds each text as `[len, 1]`, or fails while `down` is set;
+    /// counts the texts per call.
+    #[derive(Default)]
+    struct FlakyEmbedder {
+        down: std::sync::atomic::AtomicBool,
+        calls: parking_lot::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for FlakyEmbedder {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                anyhow::bail!("embedding service unreachable");
+            }
+            self.calls.lock().push(texts.len());
+            #[allow(clippy::cast_precision_loss)]
+            Ok(texts.iter().map(|t| vec![t.len() as f32, 1.0]).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn embedding_outage_falls_back_to_keywords_and_reindex_catches_up() {
+        let tmp = TempDir::new().unwrap();
+        let embedder = Arc::new(FlakyEmbedder::default());
+        embedder
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let mem = SqliteMemory::with_embedder(tmp.path(), embedder.clone(), 0.7, 0.3, 100).unwrap();
+
+        mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "editor",
+            "User edits with Helix",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        let found = mem.recall("Rust", 5, None).await.unwrap();
+        assert_eq!(found[0].key, "lang");
+
+        embedder
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(mem.reindex().await.unwrap(), 2);
+        assert_eq!(*embedder.calls.lock(), [2]);
+        let embedded: i64 = mem
+            .conn
+            .lock()
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(embedded, 2);
+    }
+
+    #[tokio::test]
+    async fn interrupted_backfill_resumes_where_it_stopped() {
+        let tmp = TempDir::new().unwrap();
+        let embedder = Arc::new(FlakyEmbedder::default());
+        embedder
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let mem = SqliteMemory::with_embedder(tmp.path(), embedder.clone(), 0.7, 0.3, 0).unwrap();
+        let stored = REINDEX_CHUNK + 10;
+        for i in 0..stored {
+            mem.store(
+                &format!("fact_{i}"),
+                &format!("Fact number {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        // The provider goes down again after the first batch
+        embedder
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        let seen = parking_lot::Mutex::new(Vec::new());
+        let err = mem
+            .backfill_embeddings(0, &|done, total| {
+                seen.lock().push((done, total));
+                embedder
+                    .down
+                    .store(true, std::sync::atomic::Ordering::SeqCst);
+            })
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unreachable"));
+        assert_eq!(*seen.lock(), [(REINDEX_CHUNK, stored)]);
+
+        embedder
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        seen.lock().clear();
+        let resumed = mem
+            .backfill_embeddings(6000, &|done, total| seen.lock().push((done, total)))
+            .await
+            .unwrap();
+        assert_eq!(resumed, 10);
+        assert_eq!(*seen.lock(), [(10, 10)]);
+        assert_eq!(*embedder.calls.lock(), [REINDEX_CHUNK, 10]);
+    }
+
+    /// Puts texts mentioning Miso (and the query "pets") on one axis, the rest on another
+    struct MisoEmbedder;
+
+    #[async_trait]
+    impl EmbeddingProvider for MisoEmbedder {
+        fn name(&self) -> &str {
+            "miso"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    if t.contains("Miso") || *t == "pets" {
+                        vec![1.0, 0.0]
+                    } else {
+                        vec![0.0, 1.0]
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn recall_fuses_keyword_and_vector_rankings() {
+        let tmp = TempDir::new().unwrap();
+        let mem =
+            SqliteMemory::with_embedder(tmp.path(), Arc::new(MisoEmbedder), 0.7, 0.3, 100).unwrap();
+        for (key, content) in [
+            ("cat", "User owns a cat named Miso"),
+            ("vet", "Miso needs the vet because pets are due for shots"),
+            ("lease", "The lease allows pets"),
+            ("editor", "User edits with Helix"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let found = mem.recall("pets", 10, None).await.unwrap();
+        let keys: Vec<&str> = found.iter().map(|e| e.key.as_str()).collect();
+        // vet is found by both searches, cat only by meaning, lease only by keyword
+        assert_eq!(keys, ["vet", "cat", "lease"]);
+        let scores: Vec<f64> = found.iter().map(|e| e.score.unwrap()).collect();
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+        assert!(scores[0] <= 1.0);
+
+        let found = mem.recall("pets", 1, None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].key, "vet");
+    }
+
+    #[tokio::test]
+    async fn recall_through_a_vector_index_skips_deleted_memories() {
+        use crate::memory::vector_index::{tests::fake_qdrant, QdrantIndex};
+
+        let tmp = TempDir::new().unwrap();
+        let index = Arc::new(QdrantIndex::new(&fake_qdrant().await, None, "memories", 2));
+        let mem = SqliteMemory::with_embedder(tmp.path(), Arc::new(MisoEmbedder), 0.7, 0.3, 100)
+            .unwrap()
+            .with_vector_index(index.clone());
+        for (key, content) in [
+            ("cat", "User owns a cat named Miso"),
+            ("vet", "Miso needs the vet because pets are due for shots"),
+            ("lease", "The lease allows pets"),
+            ("editor", "User edits with Helix"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let found = mem.recall("pets", 10, None).await.unwrap();
+        let keys: Vec<&str> = found.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["vet", "cat", "lease"]);
+
+        assert!(mem.forget("cat").await.unwrap());
+        let miso = index.search(&[1.0, 0.0], "*", 10).await.unwrap();
+        assert_eq!(miso.iter().filter(|(_, sim)| *sim > 0.0).count(), 1);
+
+        // Rows deleted behind the index's back leave a vector that recall skips
+        mem.conn
+            .lock()
+            .execute("DELETE FROM memories WHERE key = 'vet'", [])
+            .unwrap();
+        let found = mem.recall("pets", 10, None).await.unwrap();
+        let keys: Vec<&str> = found.iter().map(|e| e.key.as_str()).collect();
+        assert_eq!(keys, ["lease"]);
+
+        mem.reindex().await.unwrap();
+        let ids: Vec<String> = index
+            .search(&[1.0, 1.0], "*", 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        for key in ["lease", "editor"] {
+            assert!(ids.contains(&mem.get(key).await.unwrap().unwrap().id));
+        }
+    }
+
     #[tokio::test]
     async fn sqlite_count_empty() {
         let (_tmp, mem) = temp_sqlite();
@@ -873,6 +2403,85 @@ mod tests {
         }
     }
 
+    fn fts_matches(mem: &SqliteMemory, query: &str) -> i64 {
+        mem.conn
+            .lock()
+            .query_row(
+                "SELECT COUNT(*) FROM memories_fts WHERE memories_fts MATCH ?1",
+                params![query],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn fts_tokenizer_change_rebuilds_the_index() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store(
+            "morning",
+            "Went running at the café",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(fts_matches(&mem, "\"run\""), 0);
+
+        assert!(mem.set_fts_tokenizer(FtsTokenizer::Porter, true).unwrap());
+        assert!(!mem.set_fts_tokenizer(FtsTokenizer::Porter, true).unwrap());
+        assert_eq!(fts_matches(&mem, "\"run\""), 1);
+        assert_eq!(fts_matches(&mem, "\"cafe\""), 1);
+
+        // Triggers keep the rebuilt index in sync
+        mem.store("evening", "Swimming laps", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(fts_matches(&mem, "\"swim\""), 1);
+
+        assert!(mem.set_fts_tokenizer(FtsTokenizer::Trigram, false).unwrap());
+        assert_eq!(fts_matches(&mem, "\"nnin\""), 1);
+        assert_eq!(fts_matches(&mem, "\"cafe\""), 0);
+    }
+
+    /// Scores memories mentioning oat milk as relevant, the rest not
+    struct OatReranker;
+
+    #[async_trait]
+    impl Reranker for OatReranker {
+        fn name(&self) -> &str {
+            "oat"
+        }
+
+        async fn rerank(&self, _query: &str, documents: &[&str]) -> anyhow::Result<Vec<f32>> {
+            Ok(documents
+                .iter()
+                .map(|d| if d.contains("oat milk") { 0.95 } else { 0.05 })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn reranker_rescores_search_candidates() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_reranker(Arc::new(OatReranker), 10);
+        for (key, content) in [
+            ("a", "coffee coffee coffee machine"),
+            ("b", "coffee beans from the market, coffee"),
+            ("c", "user takes oat milk in coffee"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+
+        let found = mem.recall("coffee", 1, None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].key, "c");
+        assert!((found[0].score.unwrap() - 0.95).abs() < 1e-6);
+    }
+
     #[tokio::test]
     async fn fts5_multi_word_query() {
         let (_tmp, mem) = temp_sqlite();
@@ -1562,6 +3171,246 @@ mod tests {
         assert_eq!(results[0].key, "k1");
     }
 
+    #[tokio::test]
+    async fn namespaces_keep_memories_apart() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("pet", "Owner has a dog", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        namespace::scope("discord:7".into(), async {
+            mem.store("pet", "Member has a cat", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            let found = mem.recall("cat dog", 10, None).await.unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(found[0].namespace.as_deref(), Some("discord:7"));
+            assert_eq!(mem.count().await.unwrap(), 1);
+            assert!(mem.forget("pet").await.unwrap());
+        })
+        .await;
+
+        let entry = mem.get("pet").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Owner has a dog");
+        assert_eq!(entry.namespace.as_deref(), Some("default"));
+
+        namespace::scope("telegram:9".into(), async {
+            mem.store("pet", "Member has a fish", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        })
+        .await;
+        namespace::scope(ALL_NAMESPACES.into(), async {
+            assert_eq!(mem.count().await.unwrap(), 2);
+            assert_eq!(mem.recall("dog fish", 10, None).await.unwrap().len(), 2);
+            assert!(mem
+                .store("x", "y", MemoryCategory::Core, None)
+                .await
+                .is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn tags_survive_upserts_and_stay_in_their_namespace() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("stack", "Uses Axum", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.set_tags("stack", &["project-x".into()]).await.unwrap();
+        mem.store("stack", "Uses Axum and SQLx", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let tagged = mem
+            .recall_tagged("Axum", 5, None, &["PROJECT-X".into()])
+            .await
+            .unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].tags, ["project-x"]);
+
+        namespace::scope("discord:7".into(), async {
+            assert!(mem.tags().await.unwrap().is_empty());
+            assert!(mem.set_tags("stack", &["x".into()]).await.is_err());
+        })
+        .await;
+        assert_eq!(mem.tags().await.unwrap(), [("project-x".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn expired_memories_are_hidden_then_pruned() {
+        let (_tmp, mem) = temp_sqlite();
+        for key in ["trip", "home"] {
+            mem.store(key, &format!("{key} plans"), MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let past = Utc::now() - chrono::Duration::hours(1);
+        mem.set_expiry("trip", Some(past)).await.unwrap();
+        mem.set_expiry("home", Some(Utc::now() + chrono::Duration::days(1)))
+            .await
+            .unwrap();
+
+        assert!(mem.get("trip").await.unwrap().is_none());
+        assert_eq!(mem.recall("plans", 10, None).await.unwrap().len(), 1);
+        assert_eq!(mem.count().await.unwrap(), 1);
+        assert_eq!(mem.prune_expired().unwrap(), 1);
+
+        // Storing a key again makes it permanent
+        mem.set_expiry("home", Some(past)).await.unwrap();
+        mem.store("home", "home plans", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(mem.prune_expired().unwrap(), 0);
+        assert!(mem.get("home").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn recall_in_range_filters_by_creation_time() {
+        let (_tmp, mem) = temp_sqlite();
+        // Creation times in different UTC offsets still compare by instant
+        for (key, created_at) in [
+            ("kickoff", "2025-03-03T09:00:00.123456789+02:00"),
+            ("review", "2025-03-10T23:30:00-05:00"),
+            ("retro", "2025-03-17T08:00:00Z"),
+        ] {
+            mem.store(key, &format!("meeting {key}"), MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            mem.conn
+                .lock()
+                .execute(
+                    "UPDATE memories SET created_at = ?1 WHERE key = ?2",
+                    params![created_at, key],
+                )
+                .unwrap();
+        }
+        let at = |s: &str| Some(DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc));
+        let keys = |entries: Vec<MemoryEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.key).collect()
+        };
+
+        let week = TimeRange {
+            after: at("2025-03-11T00:00:00Z"),
+            before: at("2025-03-18T00:00:00Z"),
+        };
+        let found = mem
+            .recall_in_range("meeting", 10, None, &[], &week)
+            .await
+            .unwrap();
+        assert_eq!(keys(found), ["review", "retro"]);
+
+        // No query lists the range, newest first
+        let found = mem.recall_in_range("", 10, None, &[], &week).await.unwrap();
+        assert_eq!(keys(found), ["retro", "review"]);
+
+        let before = TimeRange {
+            after: None,
+            before: at("2025-03-10T00:00:00Z"),
+        };
+        let found = mem
+            .recall_in_range(" ", 10, None, &[], &before)
+            .await
+            .unwrap();
+        assert_eq!(keys(found), ["kickoff"]);
+        assert!(mem
+            .recall_in_range("", 10, None, &[], &TimeRange::default())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn budget_archives_the_least_valued_memories_reversibly() {
+        let (_tmp, mem) = temp_sqlite();
+        let pinned = MemoryCategory::Custom(crate::agent::memory_loader::PINNED_CATEGORY.into());
+        mem.store("rules", "Always answer in English", pinned, None)
+            .await
+            .unwrap();
+        for key in ["keep", "used", "stale"] {
+            mem.store(key, &format!("{key} note"), MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        mem.set_importance("keep", 0.9).await.unwrap();
+        assert!(mem.set_importance("keep", 1.5).await.is_err());
+        mem.set_tags("stale", &["old".into()]).await.unwrap();
+        for _ in 0..2 {
+            assert_eq!(mem.recall("used", 5, None).await.unwrap().len(), 1);
+        }
+
+        // Pinned memories are outside the budget
+        assert_eq!(mem.enforce_budget(3, BudgetAction::Archive).unwrap(), 0);
+        assert_eq!(mem.enforce_budget(2, BudgetAction::Archive).unwrap(), 1);
+        assert!(mem.get("stale").await.unwrap().is_none());
+        let archived = mem.archived("*").unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].key, "stale");
+        assert_eq!(archived[0].tags, ["old"]);
+
+        assert!(mem.restore_archived("default", "stale").await.unwrap());
+        assert!(!mem.restore_archived("default", "stale").await.unwrap());
+        let restored = mem.get("stale").await.unwrap().unwrap();
+        assert_eq!(restored.tags, ["old"]);
+        assert!(mem.archived("*").unwrap().is_empty());
+
+        assert_eq!(mem.enforce_budget(1, BudgetAction::Delete).unwrap(), 2);
+        assert!(mem.get("keep").await.unwrap().is_some());
+        assert!(mem.get("rules").await.unwrap().is_some());
+        assert!(mem.archived("*").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recency_decay_prefers_fresh_memories() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path())
+            .unwrap()
+            .with_recency_decay(0.5, 30.0);
+        mem.store("tea", "User likes tea", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("coffee", "User likes coffee", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let long_ago = (Local::now() - chrono::Duration::days(90)).to_rfc3339();
+        mem.conn
+            .lock()
+            .execute(
+                "UPDATE memories SET updated_at = ?1 WHERE key = 'coffee'",
+                params![long_ago],
+            )
+            .unwrap();
+
+        let found = mem.recall("User likes", 2, None).await.unwrap();
+        assert_eq!(found[0].key, "tea");
+        assert!(found[1].score.unwrap() < found[0].score.unwrap() * 0.6);
+    }
+
+    #[tokio::test]
+    async fn namespace_migration_keeps_existing_memories() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = tmp.path().join("memory").join("brain.db");
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            crate::db::migrate(&conn, &db_path, &MIGRATIONS[..2]).unwrap();
+            conn.execute(
+                "INSERT INTO memories (id, key, content, category, created_at, updated_at)
+                 VALUES ('id1', 'lang', 'User prefers Rust', 'core', '2025-01-01', '2025-01-01')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let found = mem.recall("Rust", 5, None).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].namespace.as_deref(), Some("default"));
+        mem.store("lang", "User prefers Go", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert_eq!(mem.recall("Go", 5, None).await.unwrap().len(), 1);
+        assert!(mem.recall("Rust", 5, None).await.unwrap().is_empty());
+    }
+
     #[tokio::test]
     async fn schema_migration_idempotent_on_reopen() {
         let tmp = TempDir::new().unwrap();
@@ -1583,4 +3432,94 @@ mod tests {
             assert_eq!(results[0].session_id.as_deref(), Some("sess-x"));
         }
     }
+
+    #[tokio::test]
+    async fn export_then_import_keeps_ids_timestamps_tags_and_vectors() {
+        let miso = || -> Arc<dyn EmbeddingProvider> { Arc::new(MisoEmbedder) };
+        let src_dir = TempDir::new().unwrap();
+        let src = SqliteMemory::with_embedder(src_dir.path(), miso(), 0.7, 0.3, 100).unwrap();
+        src.store("cat", "User's cat is Miso", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        src.set_tags("cat", &["pets".into()]).await.unwrap();
+        namespace::scope("discord:7".into(), async {
+            src.store("note", "Guest note", MemoryCategory::Daily, Some("s1"))
+                .await
+                .unwrap();
+        })
+        .await;
+        let records = src.export_records(ALL_NAMESPACES).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].embedding.as_deref(), Some(&[1.0, 0.0][..]));
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = SqliteMemory::with_embedder(dst_dir.path(), miso(), 0.7, 0.3, 100).unwrap();
+        assert_eq!(dst.import_records(&records).unwrap(), 2);
+        assert_eq!(dst.import_records(&records).unwrap(), 2);
+        assert_eq!(dst.export_records(ALL_NAMESPACES).unwrap(), records);
+        assert_eq!(dst.recall("Miso", 5, None).await.unwrap()[0].tags, ["pets"]);
+        assert_eq!(dst.reindex().await.unwrap(), 0);
+
+        // Vectors from another embedder are dropped and recomputed
+        let plain_dir = TempDir::new().unwrap();
+        let plain = SqliteMemory::new(plain_dir.path()).unwrap();
+        plain.import_records(&records).unwrap();
+        assert!(plain
+            .export_records(ALL_NAMESPACES)
+            .unwrap()
+            .iter()
+            .all(|r| r.embedding.is_none()));
+    }
+
+    #[tokio::test]
+    async fn find_similar_uses_embeddings_within_a_category() {
+        let tmp = TempDir::new().unwrap();
+        let mem =
+            SqliteMemory::with_embedder(tmp.path(), Arc::new(MisoEmbedder), 0.7, 0.3, 100).unwrap();
+        mem.store("cat", "User's cat is Miso", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("diary", "Miso slept all day", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+
+        let similar = mem
+            .find_similar("Miso is the user's cat", &MemoryCategory::Core)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(similar.key, "cat");
+        assert!((similar.score.unwrap() - 1.0).abs() < 1e-6);
+        assert!(mem
+            .find_similar("Prefers tea", &MemoryCategory::Core)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[cfg(feature = "sqlcipher")]
+    #[tokio::test]
+    async fn encrypted_database_needs_its_key() {
+        let tmp = TempDir::new().unwrap();
+        let open = |key| {
+            SqliteMemory::open(
+                tmp.path(),
+                Arc::new(crate::memory::embeddings::NoopEmbedding),
+                0.7,
+                0.3,
+                100,
+                key,
+            )
+        };
+        let mem = open(Some("k")).unwrap();
+        mem.store("pin", "1234", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        assert!(open(None).is_err());
+        assert!(open(Some("wrong")).is_err());
+        let mem = open(Some("k")).unwrap();
+        assert_eq!(mem.get("pin").await.unwrap().unwrap().content, "1234");
+    }
 }
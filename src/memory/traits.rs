@@ -11,6 +11,40 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    /// Namespace the entry belongs to, for backends that keep them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// How a recall found the entry; unset outside of search results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_kind: Option<MatchKind>,
+}
+
+/// Which search found a recalled memory, from the strongest evidence to
+/// the weakest
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// Both the keyword index and embedding similarity
+    Hybrid,
+    /// Embedding similarity alone
+    Vector,
+    /// The full-text keyword index alone
+    Fts,
+    /// Only the substring fallback, for queries the keyword index missed
+    Like,
+}
+
+impl std::fmt::Display for MatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hybrid => write!(f, "fts+vector"),
+            Self::Vector => write!(f, "vector"),
+            Self::Fts => write!(f, "fts"),
+            Self::Like => write!(f, "like"),
+        }
+    }
 }
 
 /// Memory categories for organization
@@ -38,6 +72,93 @@ impl std::fmt::Display for MemoryCategory {
     }
 }
 
+/// Order of [`Memory::list_page`] results, by when entries were last stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+}
+
+impl std::str::FromStr for ListOrder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "newest" => Ok(Self::NewestFirst),
+            "oldest" => Ok(Self::OldestFirst),
+            other => anyhow::bail!("Unknown order '{other}' (expected newest or oldest)"),
+        }
+    }
+}
+
+/// Creation times a recall is limited to: from `after` (inclusive) until
+/// `before` (exclusive)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeRange {
+    pub after: Option<chrono::DateTime<chrono::Utc>>,
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TimeRange {
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+}
+
+/// What `memory_store` does when the new content nearly repeats a memory
+/// stored under another key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// Store it anyway
+    Off,
+    /// Keep the existing memory and drop the new content
+    Skip,
+    /// Append the new content to the existing memory
+    Merge,
+    /// Replace the existing memory's content with the new content
+    Update,
+}
+
+impl std::str::FromStr for DedupStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "off" => Ok(Self::Off),
+            "skip" => Ok(Self::Skip),
+            "merge" => Ok(Self::Merge),
+            "update" => Ok(Self::Update),
+            other => anyhow::bail!(
+                "Unknown dedup strategy '{other}' (expected off, skip, merge or update)"
+            ),
+        }
+    }
+}
+
+/// What a memory budget pass does with the memories it removes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetAction {
+    /// Move them to the archive, from which they can be restored
+    Archive,
+    /// Delete them
+    Delete,
+}
+
+impl std::str::FromStr for BudgetAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "archive" => Ok(Self::Archive),
+            "delete" => Ok(Self::Delete),
+            other => {
+                anyhow::bail!("Unknown budget action '{other}' (expected archive or delete)")
+            }
+        }
+    }
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -61,9 +182,101 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall memories matching a query that carry every one of `tags`
+    async fn recall_tagged(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if tags.is_empty() {
+            return self.recall(query, limit, session_id).await;
+        }
+        anyhow::bail!("The {} memory backend does not support tags", self.name())
+    }
+
+    /// Like [`Self::recall_tagged`], limited to memories created within
+    /// `range`. With a bounded range an empty query lists that range's
+    /// memories, newest first.
+    async fn recall_in_range(
+        &self,
+        query: &str,
+        limit: usize,
+        session_id: Option<&str>,
+        tags: &[String],
+        range: &TimeRange,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if range.is_unbounded() {
+            return self.recall_tagged(query, limit, session_id, tags).await;
+        }
+        anyhow::bail!(
+            "The {} memory backend does not support time ranges",
+            self.name()
+        )
+    }
+
+    /// Replace the tags of the memory stored under `key`
+    async fn set_tags(&self, _key: &str, _tags: &[String]) -> anyhow::Result<()> {
+        anyhow::bail!("The {} memory backend does not support tags", self.name())
+    }
+
+    /// Set how much the memory stored under `key` is worth keeping, from 0
+    /// to 1, when a memory budget forces some out
+    async fn set_importance(&self, _key: &str, _importance: f64) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "The {} memory backend does not support importance",
+            self.name()
+        )
+    }
+
+    /// Forget the memory stored under `key` once `expires_at` passes;
+    /// `None` keeps it indefinitely. Storing the key again clears the expiry.
+    async fn set_expiry(
+        &self,
+        _key: &str,
+        _expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("The {} memory backend does not support expiry", self.name())
+    }
+
+    /// Every tag in use, with how many memories carry it, most used first
+    async fn tags(&self) -> anyhow::Result<Vec<(String, usize)>> {
+        Ok(Vec::new())
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
+    /// Get a specific memory by its id
+    async fn get_by_id(&self, id: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(self
+            .list(None, None)
+            .await?
+            .into_iter()
+            .find(|entry| entry.id == id))
+    }
+
+    /// The memory in `category` most similar to `content`, with its
+    /// similarity (0.0–1.0) as the score. Backends that cannot update
+    /// memories in place find nothing, so they never deduplicate.
+    async fn find_similar(
+        &self,
+        _content: &str,
+        _category: &MemoryCategory,
+    ) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(None)
+    }
+
+    /// Replace the content of the memory stored under `key`, keeping its
+    /// category, session, tags and expiry. Returns whether a memory matched.
+    async fn update(&self, _key: &str, _content: &str) -> anyhow::Result<bool> {
+        anyhow::bail!(
+            "The {} memory backend does not support updating memories",
+            self.name()
+        )
+    }
+
     /// List all memory keys, optionally filtered by category and/or session
     async fn list(
         &self,
@@ -71,6 +284,21 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// One page of all memories, skipping `offset` entries in `order`
+    async fn list_page(
+        &self,
+        offset: usize,
+        limit: usize,
+        order: ListOrder,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let mut entries = self.list(None, None).await?;
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        if order == ListOrder::OldestFirst {
+            entries.reverse();
+        }
+        Ok(entries.into_iter().skip(offset).take(limit).collect())
+    }
+
     /// Remove a memory by key
     async fn forget(&self, key: &str) -> anyhow::Result<bool>;
 
@@ -117,6 +345,9 @@ mod tests {
             timestamp: "2026-02-16T00:00:00Z".into(),
             session_id: Some("session-abc".into()),
             score: Some(0.98),
+            namespace: None,
+            tags: Vec::new(),
+            match_kind: Some(MatchKind::Fts),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -128,5 +359,7 @@ mod tests {
         assert_eq!(parsed.category, MemoryCategory::Core);
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
+        assert_eq!(parsed.match_kind, Some(MatchKind::Fts));
+        assert!(json.contains("\"match_kind\":\"fts\""));
     }
 }
@@ -0,0 +1,281 @@
+//! Memory export/import.
+//!
+//! `zeroclaw memory export` writes every memory as a [`MemoryRecord`], either
+//! one JSON object per line (lossless, embeddings included) or as Markdown
+//! meant for reading and editing. `zeroclaw memory import` reads both back.
+//! Markdown import also accepts a plain notes file: every `## heading`
+//! becomes a memory keyed by the heading, with the text below it as content.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// One memory with everything needed to restore it elsewhere
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    /// Empty when the source had none; import assigns a fresh id
+    #[serde(default)]
+    pub id: String,
+    pub key: String,
+    pub content: String,
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default = "default_namespace")]
+    pub namespace: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// RFC 3339; import uses the current time when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// `provider:model` that computed `embedding`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+}
+
+fn default_category() -> String {
+    "core".into()
+}
+
+fn default_namespace() -> String {
+    super::namespace::DEFAULT_NAMESPACE.into()
+}
+
+/// Export file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Jsonl,
+    Markdown,
+}
+
+impl Format {
+    /// Format implied by a file name: Markdown for `.md`/`.markdown`, else JSONL
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md" | "markdown") => Self::Markdown,
+            _ => Self::Jsonl,
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "jsonl" => Ok(Self::Jsonl),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => anyhow::bail!("Unknown format '{other}' (expected jsonl or markdown)"),
+        }
+    }
+}
+
+pub fn write(records: &[MemoryRecord], format: Format) -> anyhow::Result<String> {
+    match format {
+        Format::Jsonl => to_jsonl(records),
+        Format::Markdown => Ok(to_markdown(records)),
+    }
+}
+
+pub fn read(text: &str, format: Format) -> anyhow::Result<Vec<MemoryRecord>> {
+    match format {
+        Format::Jsonl => from_jsonl(text),
+        Format::Markdown => Ok(from_markdown(text)),
+    }
+}
+
+fn to_jsonl(records: &[MemoryRecord]) -> anyhow::Result<String> {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn from_jsonl(text: &str) -> anyhow::Result<Vec<MemoryRecord>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("Line {}: not a memory record: {e}", i + 1))
+        })
+        .collect()
+}
+
+/// Metadata lines at the end of a Markdown section, in the order written
+const MARKDOWN_FIELDS: [&str; 8] = [
+    "id",
+    "category",
+    "namespace",
+    "session",
+    "tags",
+    "created",
+    "updated",
+    "expires",
+];
+
+fn to_markdown(records: &[MemoryRecord]) -> String {
+    let mut out = String::from("# ZeroClaw memories\n");
+    for record in records {
+        let _ = write!(
+            out,
+            "\n## {}\n\n{}\n\n- id: {}\n- category: {}\n- namespace: {}\n",
+            record.key,
+            record.content.trim_end(),
+            record.id,
+            record.category,
+            record.namespace
+        );
+        let optional = [
+            ("session", record.session_id.clone()),
+            (
+                "tags",
+                (!record.tags.is_empty()).then(|| record.tags.join(", ")),
+            ),
+            ("created", record.created_at.clone()),
+            ("updated", record.updated_at.clone()),
+            ("expires", record.expires_at.clone()),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value {
+                let _ = writeln!(out, "- {field}: {value}");
+            }
+        }
+    }
+    out
+}
+
+/// Split a `- field: value` metadata line
+fn metadata_line(line: &str) -> Option<(&str, &str)> {
+    let (field, value) = line.strip_prefix("- ")?.split_once(": ")?;
+    MARKDOWN_FIELDS
+        .contains(&field)
+        .then_some((field, value.trim()))
+}
+
+fn from_markdown(text: &str) -> Vec<MemoryRecord> {
+    let mut records = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    for line in text.lines().chain(std::iter::once("## ")) {
+        if let Some(heading) = line.strip_prefix("## ") {
+            if let Some((key, body)) = current.take() {
+                records.extend(markdown_record(key, &body));
+            }
+            current = Some((heading.trim().trim_matches('`').to_string(), Vec::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push(line);
+        }
+    }
+    records
+}
+
+fn markdown_record(key: String, body: &[&str]) -> Option<MemoryRecord> {
+    let mut end = body.len();
+    while end > 0 && body[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    let mut meta_start = end;
+    while meta_start > 0 && metadata_line(body[meta_start - 1]).is_some() {
+        meta_start -= 1;
+    }
+
+    let content = body[..meta_start].join("\n").trim().to_string();
+    if key.is_empty() || content.is_empty() {
+        return None;
+    }
+    let mut record = MemoryRecord {
+        key,
+        content,
+        category: default_category(),
+        namespace: default_namespace(),
+        ..MemoryRecord::default()
+    };
+    for (field, value) in body[meta_start..end]
+        .iter()
+        .filter_map(|l| metadata_line(l))
+    {
+        let value = value.to_string();
+        match field {
+            "id" => record.id = value,
+            "category" => record.category = value,
+            "namespace" => record.namespace = value,
+            "session" => record.session_id = Some(value),
+            "tags" => {
+                record.tags = value
+                    .split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+            }
+            "created" => record.created_at = Some(value),
+            "updated" => record.updated_at = Some(value),
+            _ => record.expires_at = Some(value),
+        }
+    }
+    Some(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> MemoryRecord {
+        MemoryRecord {
+            id: "3f1c".into(),
+            key: "dog".into(),
+            content: "User's dog is named Biscuit.\n\nHe is a beagle.".into(),
+            category: "core".into(),
+            namespace: "telegram:alice".into(),
+            session_id: Some("s1".into()),
+            tags: vec!["pets".into(), "family".into()],
+            created_at: Some("2026-01-02T03:04:05+00:00".into()),
+            updated_at: Some("2026-02-02T03:04:05+00:00".into()),
+            expires_at: None,
+            embedding: Some(vec![0.5, -0.25]),
+            embedding_model: Some("openai:text-embedding-3-small".into()),
+        }
+    }
+
+    #[test]
+    fn jsonl_roundtrip_is_lossless() {
+        let records = vec![sample(), MemoryRecord::default()];
+        let text = write(&records, Format::Jsonl).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(read(&text, Format::Jsonl).unwrap(), records);
+        assert!(read("{\"key\": 1}", Format::Jsonl)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Line 1"));
+    }
+
+    #[test]
+    fn markdown_roundtrip_keeps_all_but_embeddings() {
+        let text = write(&[sample()], Format::Markdown).unwrap();
+        let expected = MemoryRecord {
+            embedding: None,
+            embedding_model: None,
+            ..sample()
+        };
+        assert_eq!(read(&text, Format::Markdown).unwrap(), vec![expected]);
+    }
+
+    #[test]
+    fn markdown_notes_become_core_memories() {
+        let notes = "# My notes\n\nintro\n\n## Coffee order\n\nOat flat white\n- no sugar\n\n## `empty`\n\n";
+        let records = read(notes, Format::Markdown).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key, "Coffee order");
+        assert_eq!(records[0].content, "Oat flat white\n- no sugar");
+        assert_eq!(records[0].category, "core");
+        assert_eq!(records[0].namespace, "default");
+        assert!(records[0].id.is_empty());
+    }
+}
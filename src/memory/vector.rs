@@ -1,4 +1,6 @@
-// Vector operations — cosine similarity, normalization, hybrid merge.
+// Vector operations — cosine similarity, normalization, hybrid merge, rank fusion.
+
+use super::traits::MatchKind;
 
 /// Cosine similarity between two vectors. Returns 0.0–1.0.
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
@@ -63,6 +65,19 @@ pub struct ScoredResult {
     pub final_score: f32,
 }
 
+impl ScoredResult {
+    /// Which search found this hit; `substring` marks keyword hits that
+    /// only the substring fallback found
+    pub fn match_kind(&self, substring: bool) -> MatchKind {
+        match (self.vector_score.is_some(), self.keyword_score.is_some()) {
+            (true, true) => MatchKind::Hybrid,
+            (true, false) => MatchKind::Vector,
+            _ if substring => MatchKind::Like,
+            _ => MatchKind::Fts,
+        }
+    }
+}
+
 /// Hybrid merge: combine vector and keyword results with weighted fusion.
 ///
 /// Normalizes each score set to [0, 1], then computes:
@@ -131,6 +146,111 @@ pub fn hybrid_merge(
     results
 }
 
+/// Default RRF smoothing constant; higher values flatten the rank curve
+pub const DEFAULT_RRF_K: f32 = 60.0;
+
+/// Reciprocal rank fusion: combine vector and keyword rankings by position.
+///
+/// Each list contributes `weight / (k + rank)` for every id it ranks (rank
+/// starts at 1), so cosine and BM25 scores never need to share a scale. An id
+/// found by both lists adds both contributions. Final scores are divided by the
+/// best possible sum over the non-empty lists, so a top hit everywhere scores 1.0.
+///
+/// Deduplicates by id, keeping each list's best rank. Ties keep input order.
+pub fn rrf_merge(
+    vector_results: &[(String, f32)], // (id, cosine_similarity), best first
+    keyword_results: &[(String, f32)], // (id, bm25_score), best first
+    vector_weight: f32,
+    keyword_weight: f32,
+    k: f32,
+    limit: usize,
+) -> Vec<ScoredResult> {
+    use std::collections::HashMap;
+
+    let k = k.max(0.0);
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut results: Vec<ScoredResult> = Vec::new();
+    let mut best = 0.0_f32;
+
+    for (is_vector, list, weight) in [
+        (true, vector_results, vector_weight),
+        (false, keyword_results, keyword_weight),
+    ] {
+        if !list.is_empty() {
+            best += weight / (k + 1.0);
+        }
+        for (rank, (id, score)) in list.iter().enumerate() {
+            let slot = *index.entry(id.clone()).or_insert_with(|| {
+                results.push(ScoredResult {
+                    id: id.clone(),
+                    vector_score: None,
+                    keyword_score: None,
+                    final_score: 0.0,
+                });
+                results.len() - 1
+            });
+            let entry = &mut results[slot];
+            let seen = if is_vector {
+                &mut entry.vector_score
+            } else {
+                &mut entry.keyword_score
+            };
+            if seen.is_some() {
+                continue;
+            }
+            *seen = Some(*score);
+            #[allow(clippy::cast_precision_loss)]
+            let contribution = weight / (k + (rank + 1) as f32);
+            entry.final_score += contribution;
+        }
+    }
+
+    if best > f32::EPSILON {
+        for r in &mut results {
+            r.final_score /= best;
+        }
+    }
+
+    results.sort_by(|a, b| {
+        b.final_score
+            .partial_cmp(&a.final_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.truncate(limit);
+    results
+}
+
+/// Recency factor applied to recall scores.
+///
+/// A memory's relevance halves every `half_life_days`; `weight` (0–1) sets how
+/// much of the score that decay may take away. Fresh memories keep 1.0.
+pub fn recency_factor(age_days: f32, half_life_days: f32, weight: f32) -> f32 {
+    if weight <= 0.0 || half_life_days <= 0.0 {
+        return 1.0;
+    }
+    let weight = weight.min(1.0);
+    let decay = 0.5_f32.powf(age_days.max(0.0) / half_life_days);
+    (1.0 - weight) + weight * decay
+}
+
+/// Word overlap of two texts (Jaccard index over lowercase words), 0.0–1.0
+pub fn text_similarity(a: &str, b: &str) -> f32 {
+    fn words(text: &str) -> std::collections::HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+    let (a, b) = (words(a), words(b));
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let similarity = a.intersection(&b).count() as f32 / union as f32;
+    similarity
+}
+
 #[cfg(test)]
 #[allow(
     clippy::float_cmp,
@@ -243,6 +363,56 @@ mod tests {
         assert!(merged.is_empty());
     }
 
+    #[test]
+    fn rrf_merge_rewards_agreement() {
+        let vec_results = vec![("a".into(), 0.9), ("b".into(), 0.8)];
+        let kw_results = vec![("b".into(), 12.0), ("c".into(), 3.0)];
+        let merged = rrf_merge(&vec_results, &kw_results, 0.5, 0.5, 60.0, 10);
+        let ids: Vec<&str> = merged.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["b", "a", "c"]);
+        assert_eq!(merged[0].vector_score, Some(0.8));
+        assert_eq!(merged[0].keyword_score, Some(12.0));
+        assert!(merged[0].final_score <= 1.0);
+    }
+
+    #[test]
+    fn rrf_merge_weights_pick_the_winner() {
+        let vec_results = vec![("v".into(), 0.9)];
+        let kw_results = vec![("k".into(), 7.0)];
+        let merged = rrf_merge(&vec_results, &kw_results, 0.7, 0.3, 60.0, 10);
+        assert_eq!(merged[0].id, "v");
+        let merged = rrf_merge(&vec_results, &kw_results, 0.2, 0.8, 60.0, 10);
+        assert_eq!(merged[0].id, "k");
+    }
+
+    #[test]
+    fn rrf_merge_single_list_tops_out_at_one() {
+        let kw_results = vec![("x".into(), 10.0), ("y".into(), 5.0), ("x".into(), 1.0)];
+        let merged = rrf_merge(&[], &kw_results, 0.7, 0.3, 60.0, 10);
+        assert_eq!(merged.len(), 2);
+        assert!((merged[0].final_score - 1.0).abs() < 1e-6);
+        assert_eq!(merged[0].keyword_score, Some(10.0));
+        assert!(merged[1].final_score < merged[0].final_score);
+    }
+
+    #[test]
+    fn rrf_merge_limit_and_empty() {
+        let vec_results: Vec<(String, f32)> = (0..20).map(|i| (format!("item_{i}"), 0.5)).collect();
+        let merged = rrf_merge(&vec_results, &[], 1.0, 0.0, DEFAULT_RRF_K, 5);
+        assert_eq!(merged.len(), 5);
+        assert_eq!(merged[0].id, "item_0");
+        assert!(rrf_merge(&[], &[], 0.7, 0.3, DEFAULT_RRF_K, 10).is_empty());
+    }
+
+    #[test]
+    fn recency_factor_halves_the_weighted_part() {
+        assert_eq!(recency_factor(0.0, 30.0, 0.2), 1.0);
+        assert!((recency_factor(30.0, 30.0, 0.2) - 0.9).abs() < 1e-6);
+        assert!(recency_factor(365.0, 30.0, 0.2) > 0.8);
+        assert_eq!(recency_factor(365.0, 30.0, 0.0), 1.0);
+        assert_eq!(recency_factor(365.0, 0.0, 0.2), 1.0);
+    }
+
     // ── Edge cases: cosine similarity ────────────────────────────
 
     #[test]
@@ -399,4 +569,14 @@ mod tests {
         assert_eq!(merged.len(), 1);
         assert_eq!(merged[0].id, "only");
     }
+
+    #[test]
+    fn text_similarity_ignores_case_and_punctuation() {
+        let fact = "User's dog is named Biscuit.";
+        assert_eq!(text_similarity(fact, "user's dog is named biscuit"), 1.0);
+        let renamed = text_similarity(fact, "User's dog is named Max");
+        assert!((renamed - 5.0 / 7.0).abs() < 1e-6);
+        assert_eq!(text_similarity(fact, "Prefers tea"), 0.0);
+        assert_eq!(text_similarity("", "..."), 0.0);
+    }
 }
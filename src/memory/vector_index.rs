@@ -0,0 +1,793 @@
+//! External nearest-neighbour indexes for memory embeddings.
+//!
+//! `brain.db` keeps every memory, its metadata and its embedding; a
+//! [`VectorIndex`] holds a copy of the embeddings so recall can ask for the
+//! nearest ones instead of scoring every row. Hits are checked against
+//! `brain.db` before they are used, so a vector left behind by a deleted
+//! memory is skipped, and `reindex` rebuilds the index from scratch.
+
+use super::namespace::{ALL_NAMESPACES, SHARED_NAMESPACE};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// One memory's embedding as stored in an index
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorPoint {
+    pub id: String,
+    pub namespace: String,
+    pub embedding: Vec<f32>,
+}
+
+#[async_trait]
+pub trait VectorIndex: Send + Sync {
+    /// Index name, for logs
+    fn name(&self) -> &str;
+
+    /// Insert or replace the embeddings of `points`
+    async fn upsert(&self, points: &[VectorPoint]) -> anyhow::Result<()>;
+
+    /// Drop the embeddings of the memories with these ids
+    async fn remove(&self, ids: &[String]) -> anyhow::Result<()>;
+
+    /// Drop every embedding
+    async fn clear(&self) -> anyhow::Result<()>;
+
+    /// Up to `limit` `(memory id, cosine similarity)` pairs nearest to
+    /// `embedding` within `namespace` and the shared one (`*` for all),
+    /// best first
+    async fn search(
+        &self,
+        embedding: &[f32],
+        namespace: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>>;
+}
+
+// ── Qdrant ───────────────────────────────────────────────────
+
+/// Embeddings in a Qdrant collection, over its REST API. The collection is
+/// created on first write with cosine distance and a keyword index on
+/// `namespace`.
+pub struct QdrantIndex {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    collection: String,
+    dimensions: usize,
+    ready: AtomicBool,
+}
+
+impl QdrantIndex {
+    pub fn new(base_url: &str, api_key: Option<&str>, collection: &str, dimensions: usize) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|k| !k.is_empty()).map(str::to_string),
+            collection: collection.to_string(),
+            dimensions,
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    /// Qdrant point ids are UUIDs or integers; other memory ids map to a
+    /// UUID derived from their hash
+    fn point_id(memory_id: &str) -> String {
+        Uuid::parse_str(memory_id)
+            .unwrap_or_else(|_| {
+                let digest = Sha256::digest(memory_id.as_bytes());
+                let mut bytes = [0u8; 16];
+                bytes.copy_from_slice(&digest[..16]);
+                Uuid::from_bytes(bytes)
+            })
+            .to_string()
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(
+            method,
+            format!("{}/collections/{}{path}", self.base_url, self.collection),
+        );
+        match &self.api_key {
+            Some(key) => request.header("api-key", key),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> anyhow::Result<serde_json::Value> {
+        let resp = request.send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant error {status}: {text}");
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Create the collection unless it exists; an existing one must hold
+    /// vectors of the embedder's size
+    async fn ensure_collection(&self) -> anyhow::Result<()> {
+        if self.ready.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let resp = self.request(reqwest::Method::GET, "").send().await?;
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            self.send(
+                self.request(reqwest::Method::PUT, "")
+                    .json(&serde_json::json!({
+                        "vectors": { "size": self.dimensions, "distance": "Cosine" }
+                    })),
+            )
+            .await?;
+            self.send(self.request(reqwest::Method::PUT, "/index?wait=true").json(
+                &serde_json::json!({
+                    "field_name": "namespace",
+                    "field_schema": "keyword"
+                }),
+            ))
+            .await?;
+        } else {
+            let status = resp.status();
+            if !status.is_success() {
+                let text = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Qdrant error {status}: {text}");
+            }
+            let info: serde_json::Value = resp.json().await?;
+            let size = info["result"]["config"]["params"]["vectors"]["size"].as_u64();
+            if size != Some(self.dimensions as u64) {
+                anyhow::bail!(
+                    "Qdrant collection '{}' holds {}-dimensional vectors but embeddings have {}; \
+                     run `zeroclaw memory reindex` to rebuild it",
+                    self.collection,
+                    size.map_or_else(|| "unknown".to_string(), |s| s.to_string()),
+                    self.dimensions
+                );
+            }
+        }
+        self.ready.store(true, Ordering::Release);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl VectorIndex for QdrantIndex {
+    fn name(&self) -> &str {
+        "qdrant"
+    }
+
+    async fn upsert(&self, points: &[VectorPoint]) -> anyhow::Result<()> {
+        if points.is_empty() {
+            return Ok(());
+        }
+        self.ensure_collection().await?;
+        let points: Vec<serde_json::Value> = points
+            .iter()
+            .map(|point| {
+                serde_json::json!({
+                    "id": Self::point_id(&point.id),
+                    "vector": point.embedding,
+                    "payload": { "memory_id": point.id, "namespace": point.namespace },
+                })
+            })
+            .collect();
+        self.send(
+            self.request(reqwest::Method::PUT, "/points?wait=true")
+                .json(&serde_json::json!({ "points": points })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn remove(&self, ids: &[String]) -> anyhow::Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let points: Vec<String> = ids.iter().map(|id| Self::point_id(id)).collect();
+        let resp = self
+            .request(reqwest::Method::POST, "/points/delete?wait=true")
+            .json(&serde_json::json!({ "points": points }))
+            .send()
+            .await?;
+        // Nothing to remove before the first write
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant error {status}: {text}");
+        }
+        Ok(())
+    }
+
+    async fn clear(&self) -> anyhow::Result<()> {
+        self.ready.store(false, Ordering::Release);
+        let resp = self.request(reqwest::Method::DELETE, "").send().await?;
+        if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Qdrant error {status}: {text}");
+        }
+        Ok(())
+    }
+
+    async fn search(
+        &self,
+        embedding: &[f32],
+        namespace: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        self.ensure_collection().await?;
+        let mut body = serde_json::json!({
+            "vector": embedding,
+            "limit": limit,
+            "with_payload": ["memory_id"],
+        });
+        if namespace != ALL_NAMESPACES {
+            body["filter"] = serde_json::json!({
+                "must": [{
+                    "key": "namespace",
+                    "match": { "any": [namespace, SHARED_NAMESPACE] }
+                }]
+            });
+        }
+        let json = self
+            .send(
+                self.request(reqwest::Method::POST, "/points/search")
+                    .json(&body),
+            )
+            .await?;
+        let hits = json["result"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Invalid Qdrant response: missing 'result'"))?;
+        #[allow(clippy::cast_possible_truncation)]
+        Ok(hits
+            .iter()
+            .filter_map(|hit| {
+                let id = hit["payload"]["memory_id"].as_str()?;
+                let score = hit["score"].as_f64()? as f32;
+                Some((id.to_string(), score))
+            })
+            .collect())
+    }
+}
+
+// ── LanceDB ──────────────────────────────────────────────────
+
+/// Embeddings in a LanceDB table stored next to `brain.db`, searched in
+/// process. The table is created on first write; once it is large enough
+/// for one, an IVF-PQ index with cosine distance is built.
+#[cfg(feature = "lancedb")]
+pub struct LanceIndex {
+    path: std::path::PathBuf,
+    dimensions: usize,
+    table: tokio::sync::Mutex<Option<lancedb::Table>>,
+    indexed: AtomicBool,
+}
+
+#[cfg(feature = "lancedb")]
+impl LanceIndex {
+    const TABLE: &'static str = "memory_embeddings";
+    /// Fewest rows worth an ANN index; smaller tables are scanned
+    const INDEX_MIN_ROWS: usize = 50_000;
+
+    pub fn new(path: &std::path::Path, dimensions: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            dimensions,
+            table: tokio::sync::Mutex::new(None),
+            indexed: AtomicBool::new(false),
+        }
+    }
+
+    async fn connect(&self) -> anyhow::Result<lancedb::Connection> {
+        let Some(uri) = self.path.to_str() else {
+            anyhow::bail!("LanceDB path {} is not UTF-8", self.path.display());
+        };
+        Ok(lancedb::connect(uri).execute().await?)
+    }
+
+    fn schema(&self) -> anyhow::Result<std::sync::Arc<lancedb::arrow::arrow_schema::Schema>> {
+        use lancedb::arrow::arrow_schema::{DataType, Field, Schema};
+
+        let item = std::sync::Arc::new(Field::new("item", DataType::Float32, true));
+        Ok(std::sync::Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("namespace", DataType::Utf8, false),
+            Field::new(
+                "embedding",
+                DataType::FixedSizeList(item, i32::try_from(self.dimensions)?),
+                false,
+            ),
+        ])))
+    }
+
+    /// The embeddings table, opened on first use and created by the first
+    /// write (`create`); `None` while nothing was written yet
+    async fn table(&self, create: bool) -> anyhow::Result<Option<lancedb::Table>> {
+        use lancedb::arrow::arrow_schema::DataType;
+
+        let mut cached = self.table.lock().await;
+        if let Some(table) = cached.as_ref() {
+            return Ok(Some(table.clone()));
+        }
+        let db = self.connect().await?;
+        let table = match db.open_table(Self::TABLE).execute().await {
+            Ok(table) => {
+                let schema = table.schema().await?;
+                let size = match schema.field_with_name("embedding").map(|f| f.data_type()) {
+                    Ok(DataType::FixedSizeList(_, size)) => usize::try_from(*size).ok(),
+                    _ => None,
+                };
+                if size != Some(self.dimensions) {
+                    anyhow::bail!(
+                        "LanceDB table in {} holds {}-dimensional vectors but embeddings have {}; \
+                         run `zeroclaw memory reindex` to rebuild it",
+                        self.path.display(),
+                        size.map_or_else(|| "unknown".to_string(), |s| s.to_string()),
+                        self.dimensions
+                    );
+                }
+                table
+            }
+            Err(lancedb::Error::TableNotFound { .. }) if create => {
+                db.create_empty_table(Self::TABLE, self.schema()?)
+                    .execute()
+                    .await?
+            }
+            Err(lancedb::Error::TableNotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        *cached = Some(table.clone());
+        Ok(Some(table))
+    }
+
+    /// Build the vector index once the table has grown enough for one
+    async fn index_if_large(&self, table: &lancedb::Table) -> anyhow::Result<()> {
+        use lancedb::index::{vector::IvfPqIndexBuilder, Index};
+
+        if self.indexed.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        if !table.list_indices().await?.is_empty() {
+            self.indexed.store(true, Ordering::Release);
+            return Ok(());
+        }
+        if table.count_rows(None).await? < Self::INDEX_MIN_ROWS {
+            return Ok(());
+        }
+        tracing::info!("Building the LanceDB vector index for memory embeddings");
+        table
+            .create_index(
+                &["embedding"],
+                Index::IvfPq(
+                    IvfPqIndexBuilder::default().distance_type(lancedb::DistanceType::Cosine),
+                ),
+            )
+            .execute()
+            .await?;
+        self.indexed.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// SQL list of string literals, for `IN (...)` filters
+    fn sql_list<'a>(values: impl IntoIterator<Item = &'a str>) -> String {
+        values
+            .into_iter()
+            .map(|value| format!("'{}'", value.replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(feature = "lancedb")]
+#[async_trait]
+impl VectorIndex for LanceIndex {
+    fn name(&self) -> &str {
+        "lancedb"
+    }
+
+    async fn upsert(&self, points: &[VectorPoint]) -> anyhow::Result<()> {
+        use lancedb::arrow::arrow_array::{
+            types::Float32Type, FixedSizeListArray, RecordBatch, RecordBatchIterator, StringArray,
+        };
+
+        if points.is_empty() {
+            return Ok(());
+        }
+        if let Some(point) = points.iter().find(|p| p.embedding.len() != self.dimensions) {
+            anyhow::bail!(
+                "Embedding of memory {} has {} dimensions, expected {}",
+                point.id,
+                point.embedding.len(),
+                self.dimensions
+            );
+        }
+        let Some(table) = self.table(true).await? else {
+            return Ok(());
+        };
+        let schema = self.schema()?;
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                std::sync::Arc::new(StringArray::from_iter_values(
+                    points.iter().map(|p| p.id.as_str()),
+                )),
+                std::sync::Arc::new(StringArray::from_iter_values(
+                    points.iter().map(|p| p.namespace.as_str()),
+                )),
+                std::sync::Arc::new(
+                    FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+                        points
+                            .iter()
+                            .map(|p| Some(p.embedding.iter().copied().map(Some))),
+                        i32::try_from(self.dimensions)?,
+                    ),
+                ),
+            ],
+        )?;
+        let mut merge = table.merge_insert(&["id"]);
+        merge
+            .when_matched_update_all(None)
+            .when_not_matched_insert_all();
+        merge
+            .execute(Box::new(RecordBatchIterator::new([Ok(batch)], schema)))
+            .await?;
+        self.index_if_large(&table).await
+    }
+
+    async fn remove(&self, ids: &[String]) -> anyhow::Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        // Nothing to remove before the first write
+        let Some(table) = self.table(false).await? else {
+            return Ok(());
+        };
+        let filter = format!("id IN ({})", Self::sql_list(ids.iter().map(String::as_str)));
+        table.delete(filter.as_str()).await?;
+        Ok(())
+    }
+
+    async fn clear(&self) -> anyhow::Result<()> {
+        let mut cached = self.table.lock().await;
+        *cached = None;
+        self.indexed.store(false, Ordering::Release);
+        match self.connect().await?.drop_table(Self::TABLE, &[]).await {
+            Ok(()) | Err(lancedb::Error::TableNotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn search(
+        &self,
+        embedding: &[f32],
+        namespace: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        use futures_util::TryStreamExt;
+        use lancedb::arrow::arrow_array::{Array, Float32Array, RecordBatch, StringArray};
+        use lancedb::query::{ExecutableQuery, QueryBase};
+
+        let Some(table) = self.table(false).await? else {
+            return Ok(Vec::new());
+        };
+        let mut query = table
+            .query()
+            .nearest_to(embedding)?
+            .distance_type(lancedb::DistanceType::Cosine)
+            .limit(limit);
+        if namespace != ALL_NAMESPACES {
+            query = query.only_if(format!(
+                "namespace IN ({})",
+                Self::sql_list([namespace, SHARED_NAMESPACE])
+            ));
+        }
+        let batches: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+
+        let mut hits = Vec::new();
+        for batch in &batches {
+            let ids = batch
+                .column_by_name("id")
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let distances = batch
+                .column_by_name("_distance")
+                .and_then(|c| c.as_any().downcast_ref::<Float32Array>());
+            let (Some(ids), Some(distances)) = (ids, distances) else {
+                anyhow::bail!("Invalid LanceDB result: missing id or _distance");
+            };
+            for row in 0..batch.num_rows() {
+                // Cosine distance is 1 - similarity
+                hits.push((ids.value(row).to_string(), 1.0 - distances.value(row)));
+            }
+        }
+        hits.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(hits)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::routing::{get, post, put};
+    use axum::{Json, Router};
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    /// Collections by name: vector size, and each point id → (vector, payload)
+    type Collections =
+        Arc<Mutex<HashMap<String, (u64, HashMap<String, (Vec<f32>, serde_json::Value)>)>>>;
+
+    /// Just enough of Qdrant's REST API for [`QdrantIndex`]; returns its URL
+    pub(crate) async fn fake_qdrant() -> String {
+        let state: Collections = Arc::default();
+        let app = Router::new()
+            .route(
+                "/collections/{name}",
+                get(
+                    |State(state): State<Collections>, Path(name): Path<String>| async move {
+                        let state = state.lock();
+                        let Some((size, _)) = state.get(&name) else {
+                            return (StatusCode::NOT_FOUND, Json(serde_json::json!({})));
+                        };
+                        (
+                            StatusCode::OK,
+                            Json(serde_json::json!({
+                                "result": { "config": { "params": { "vectors": { "size": size } } } }
+                            })),
+                        )
+                    },
+                )
+                .put(
+                    |State(state): State<Collections>,
+                     Path(name): Path<String>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        let size = body["vectors"]["size"].as_u64().unwrap();
+                        state.lock().insert(name, (size, HashMap::new()));
+                        Json(serde_json::json!({ "result": true }))
+                    },
+                )
+                .delete(
+                    |State(state): State<Collections>, Path(name): Path<String>| async move {
+                        state.lock().remove(&name);
+                        Json(serde_json::json!({ "result": true }))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/index",
+                put(|| async { Json(serde_json::json!({ "result": {} })) }),
+            )
+            .route(
+                "/collections/{name}/points",
+                put(
+                    |State(state): State<Collections>,
+                     Path(name): Path<String>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        let mut state = state.lock();
+                        let Some((_, points)) = state.get_mut(&name) else {
+                            return (StatusCode::NOT_FOUND, Json(serde_json::json!({})));
+                        };
+                        for point in body["points"].as_array().unwrap() {
+                            let vector = point["vector"]
+                                .as_array()
+                                .unwrap()
+                                .iter()
+                                .map(|x| x.as_f64().unwrap() as f32)
+                                .collect();
+                            points.insert(
+                                point["id"].as_str().unwrap().to_string(),
+                                (vector, point["payload"].clone()),
+                            );
+                        }
+                        (StatusCode::OK, Json(serde_json::json!({ "result": {} })))
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points/delete",
+                post(
+                    |State(state): State<Collections>,
+                     Path(name): Path<String>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        let mut state = state.lock();
+                        let Some((_, points)) = state.get_mut(&name) else {
+                            return StatusCode::NOT_FOUND;
+                        };
+                        for id in body["points"].as_array().unwrap() {
+                            points.remove(id.as_str().unwrap());
+                        }
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .route(
+                "/collections/{name}/points/search",
+                post(
+                    |State(state): State<Collections>,
+                     Path(name): Path<String>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        let query: Vec<f32> = body["vector"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|x| x.as_f64().unwrap() as f32)
+                            .collect();
+                        let namespaces = body["filter"]["must"][0]["match"]["any"]
+                            .as_array()
+                            .cloned();
+                        let state = state.lock();
+                        let mut hits: Vec<serde_json::Value> = state
+                            .get(&name)
+                            .into_iter()
+                            .flat_map(|(_, points)| points)
+                            .filter(|(_, (_, payload))| {
+                                namespaces
+                                    .as_ref()
+                                    .map_or(true, |any| any.contains(&payload["namespace"]))
+                            })
+                            .map(|(id, (vector, payload))| {
+                                serde_json::json!({
+                                    "id": id,
+                                    "score": super::super::vector::cosine_similarity(&query, vector),
+                                    "payload": { "memory_id": payload["memory_id"] },
+                                })
+                            })
+                            .collect();
+                        hits.sort_by(|a, b| {
+                            b["score"].as_f64().partial_cmp(&a["score"].as_f64()).unwrap()
+                        });
+                        hits.truncate(body["limit"].as_u64().unwrap() as usize);
+                        Json(serde_json::json!({ "result": hits }))
+                    },
+                ),
+            )
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    fn point(id: &str, namespace: &str, embedding: [f32; 3]) -> VectorPoint {
+        VectorPoint {
+            id: id.into(),
+            namespace: namespace.into(),
+            embedding: embedding.to_vec(),
+        }
+    }
+
+    #[test]
+    fn point_ids_are_uuids() {
+        let id = Uuid::new_v4().to_string();
+        assert_eq!(QdrantIndex::point_id(&id), id);
+        let derived = QdrantIndex::point_id("imported-note-7");
+        assert!(Uuid::parse_str(&derived).is_ok());
+        assert_eq!(derived, QdrantIndex::point_id("imported-note-7"));
+        assert_ne!(derived, QdrantIndex::point_id("imported-note-8"));
+    }
+
+    #[tokio::test]
+    async fn qdrant_searches_within_a_namespace() {
+        let index = QdrantIndex::new(&fake_qdrant().await, Some("k"), "memories", 3);
+        index
+            .upsert(&[
+                point("a", "default", [1.0, 0.0, 0.0]),
+                point("b", "default", [0.6, 0.8, 0.0]),
+                point("c", "team", [1.0, 0.0, 0.0]),
+                point("d", "shared", [0.0, 1.0, 0.0]),
+            ])
+            .await
+            .unwrap();
+
+        let hits = index.search(&[1.0, 0.1, 0.0], "default", 5).await.unwrap();
+        let ids: Vec<&str> = hits.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "d"]);
+        assert!(hits[0].1 > hits[1].1);
+        assert_eq!(
+            index.search(&[1.0, 0.0, 0.0], "*", 5).await.unwrap().len(),
+            4
+        );
+
+        index.remove(&["a".into()]).await.unwrap();
+        let hits = index.search(&[1.0, 0.0, 0.0], "default", 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+
+        index.clear().await.unwrap();
+        assert!(index
+            .search(&[1.0, 0.0, 0.0], "*", 5)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "lancedb")]
+    #[tokio::test]
+    async fn lancedb_searches_within_a_namespace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let index = LanceIndex::new(&tmp.path().join("lancedb"), 3);
+        assert!(index
+            .search(&[1.0, 0.0, 0.0], "*", 5)
+            .await
+            .unwrap()
+            .is_empty());
+        index.remove(&["a".into()]).await.unwrap();
+
+        index
+            .upsert(&[
+                point("a", "default", [1.0, 0.0, 0.0]),
+                point("b", "default", [0.6, 0.8, 0.0]),
+                point("c", "team", [1.0, 0.0, 0.0]),
+                point("d", "shared", [0.0, 1.0, 0.0]),
+                point("o'hara", "team's", [1.0, 0.0, 0.0]),
+            ])
+            .await
+            .unwrap();
+        // Upserting again replaces rather than duplicates
+        index
+            .upsert(&[point("b", "default", [0.6, 0.8, 0.0])])
+            .await
+            .unwrap();
+
+        let hits = index.search(&[1.0, 0.1, 0.0], "default", 5).await.unwrap();
+        let ids: Vec<&str> = hits.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "d"]);
+        assert!(hits[0].1 > hits[1].1);
+        assert!((hits[0].1 - 0.995).abs() < 0.01);
+        let hits = index.search(&[1.0, 0.0, 0.0], "team's", 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(
+            index.search(&[1.0, 0.0, 0.0], "*", 10).await.unwrap().len(),
+            5
+        );
+
+        index.remove(&["a".into(), "o'hara".into()]).await.unwrap();
+        let hits = index.search(&[1.0, 0.0, 0.0], "default", 5).await.unwrap();
+        assert_eq!(hits.len(), 2);
+
+        // Another handle on the same files sees the same table
+        let reopened = LanceIndex::new(&tmp.path().join("lancedb"), 3);
+        assert_eq!(
+            reopened
+                .search(&[1.0, 0.0, 0.0], "*", 10)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+        let err = LanceIndex::new(&tmp.path().join("lancedb"), 4)
+            .search(&[1.0, 0.0, 0.0, 0.0], "*", 5)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("memory reindex"));
+
+        index.clear().await.unwrap();
+        assert!(index
+            .search(&[1.0, 0.0, 0.0], "*", 5)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn qdrant_rejects_a_collection_of_another_size() {
+        let base = fake_qdrant().await;
+        QdrantIndex::new(&base, None, "memories", 3)
+            .upsert(&[point("a", "default", [1.0, 0.0, 0.0])])
+            .await
+            .unwrap();
+        let err = QdrantIndex::new(&base, None, "memories", 4)
+            .search(&[1.0, 0.0, 0.0, 0.0], "*", 5)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("memory reindex"));
+    }
+}
@@ -114,6 +114,16 @@ async fn migrate_openclaw_memory(
 }
 
 fn target_memory_backend(config: &Config) -> Result<Box<dyn Memory>> {
+    // Postgres lives outside the workspace; open it the way the agent does
+    if memory::classify_memory_backend(&config.memory.backend)
+        == memory::MemoryBackendKind::Postgres
+    {
+        return memory::create_memory(
+            &config.memory,
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+        );
+    }
     memory::create_memory_for_migration(&config.memory.backend, &config.workspace_dir)
 }
 
@@ -0,0 +1,213 @@
+//! `observability.log_format = "json"`: one JSON object per log event.
+//!
+//! Fields of every enclosing span (`session_id`, `channel`, `request_id`,
+//! `turn_id`, `tool`, ...) are flattened into the event, so every line logged
+//! during an agent turn can be correlated without parsing messages.
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::Write;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Env var overriding `observability.log_format`.
+pub const LOG_FORMAT_ENV: &str = "ZEROCLAW_LOG_FORMAT";
+
+/// Log format from `ZEROCLAW_LOG_FORMAT` or `observability.log_format` in the
+/// active config file (honouring `--profile`). Logging is set up before the
+/// config is loaded, so this reads the raw file and defaults to `"text"`.
+pub fn configured_log_format() -> String {
+    if let Ok(format) = std::env::var(LOG_FORMAT_ENV) {
+        if !format.trim().is_empty() {
+            return format.trim().to_ascii_lowercase();
+        }
+    }
+    let profile = std::env::var(crate::config::schema::PROFILE_ENV)
+        .ok()
+        .filter(|p| !p.trim().is_empty());
+    crate::config::Config::resolve_config_path()
+        .ok()
+        .and_then(|path| crate::config::schema::read_config_source(&path, profile.as_deref()).ok())
+        .and_then(|raw| raw.parse::<toml::Table>().ok())
+        .and_then(|table| {
+            table
+                .get("observability")?
+                .get("log_format")?
+                .as_str()
+                .map(str::to_ascii_lowercase)
+        })
+        .unwrap_or_else(|| "text".into())
+}
+
+/// Formats events as JSON lines written to `make_writer`.
+pub struct JsonLayer<W> {
+    make_writer: W,
+}
+
+impl<W> JsonLayer<W> {
+    pub fn new(make_writer: W) -> Self {
+        Self { make_writer }
+    }
+}
+
+/// Recorded fields of a span, stored in its extensions.
+struct SpanFields(Map<String, Value>);
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().into(), format!("{value:?}").into());
+    }
+}
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+            values.record(&mut JsonVisitor(fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut object = Map::new();
+        object.insert(
+            "timestamp".into(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+                .into(),
+        );
+        object.insert("level".into(), meta.level().as_str().into());
+        object.insert("target".into(), meta.target().into());
+
+        // Outer spans first so the innermost value wins on name clashes.
+        if let Some(scope) = ctx.event_scope(event) {
+            let mut names = Vec::new();
+            for span in scope.from_root() {
+                names.push(Value::from(span.name()));
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    object.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+                }
+            }
+            object.insert("spans".into(), Value::Array(names));
+        }
+
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+        object.extend(fields);
+
+        let mut line = serde_json::to_vec(&Value::Object(object)).unwrap_or_default();
+        line.push(b'\n');
+        let _ = self.make_writer.make_writer().write_all(&line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn events_carry_span_fields_as_json() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let message = tracing::info_span!(
+                "channel_message",
+                channel = "telegram",
+                session_id = "telegram_alice",
+                request_id = "msg-1"
+            );
+            let _message = message.enter();
+            let tool =
+                tracing::info_span!("tool_call", tool = "shell", success = tracing::field::Empty);
+            let _tool = tool.enter();
+            tool.record("success", true);
+            tracing::warn!(exit_code = 2, "tool finished");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let event: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(event["level"], "WARN");
+        assert_eq!(event["message"], "tool finished");
+        assert_eq!(event["channel"], "telegram");
+        assert_eq!(event["session_id"], "telegram_alice");
+        assert_eq!(event["request_id"], "msg-1");
+        assert_eq!(event["tool"], "shell");
+        assert_eq!(event["success"], true);
+        assert_eq!(event["exit_code"], 2);
+        assert_eq!(
+            event["spans"],
+            serde_json::json!(["channel_message", "tool_call"])
+        );
+    }
+}
@@ -1,7 +1,9 @@
+pub mod json_log;
 pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
+pub mod otel_trace;
 pub mod traits;
 pub mod verbose;
 
@@ -87,6 +89,7 @@ mod tests {
             backend: "otel".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -97,6 +100,7 @@ mod tests {
             backend: "opentelemetry".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -107,6 +111,7 @@ mod tests {
             backend: "otlp".into(),
             otel_endpoint: Some("http://127.0.0.1:19999".into()),
             otel_service_name: Some("test".into()),
+            ..ObservabilityConfig::default()
         };
         assert_eq!(create_observer(&cfg).name(), "otel");
     }
@@ -2,11 +2,25 @@ use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use opentelemetry::metrics::{Counter, Gauge, Histogram};
 use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
 use opentelemetry::{global, KeyValue};
-use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::time::SystemTime;
 
+/// HTTP client for OTLP exporters. Batch and periodic exporters run on their
+/// own threads without a Tokio runtime, so the async reqwest client would
+/// panic there; the blocking client is built off the runtime for the same reason.
+pub(crate) fn blocking_http_client() -> Result<reqwest::blocking::Client, String> {
+    std::thread::spawn(|| {
+        reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+    })
+    .join()
+    .map_err(|_| "OTLP HTTP client thread panicked".to_string())?
+    .map_err(|e| format!("Failed to build OTLP HTTP client: {e}"))
+}
+
 /// OpenTelemetry-backed observer — exports traces and metrics via OTLP.
 pub struct OtelObserver {
     tracer_provider: SdkTracerProvider,
@@ -40,6 +54,7 @@ impl OtelObserver {
         // ── Trace exporter ──────────────────────────────────────
         let span_exporter = opentelemetry_otlp::SpanExporter::builder()
             .with_http()
+            .with_http_client(blocking_http_client()?)
             .with_endpoint(endpoint)
             .build()
             .map_err(|e| format!("Failed to create OTLP span exporter: {e}"))?;
@@ -58,6 +73,7 @@ impl OtelObserver {
         // ── Metric exporter ─────────────────────────────────────
         let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
             .with_http()
+            .with_http_client(blocking_http_client()?)
             .with_endpoint(endpoint)
             .build()
             .map_err(|e| format!("Failed to create OTLP metric exporter: {e}"))?;
@@ -0,0 +1,248 @@
+//! `observability.otel_traces = true`: export the agent's tracing spans
+//! (`session`, `channel_message`, `turn`, `llm_call`, `tool_call`,
+//! `mcp_request`) to an OTLP collector.
+//!
+//! Parent/child structure is preserved, so a slow turn shows up in Jaeger or
+//! Tempo as one flame graph with its session and turn ids as attributes.
+//! Only spans from this crate are exported; `RUST_LOG` still applies.
+
+use crate::config::ObservabilityConfig;
+use anyhow::{Context as _, Result};
+use opentelemetry::trace::{
+    Span as _, SpanKind, Status, TraceContextExt as _, Tracer as _, TracerProvider as _,
+};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{WithExportConfig, WithHttpConfig};
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider, Tracer};
+use std::fmt;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Converts tracing spans into OpenTelemetry spans.
+pub struct OtelTraceLayer {
+    tracer: Tracer,
+}
+
+/// Flushes and shuts down the exporter when dropped.
+pub struct OtelTraceGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtelTraceGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry traces: {e}");
+        }
+    }
+}
+
+impl OtelTraceLayer {
+    /// Build the layer and an OTLP/HTTP exporter from `[observability]`.
+    pub fn from_config(config: &ObservabilityConfig) -> Result<(Self, OtelTraceGuard)> {
+        let endpoint = config
+            .otel_endpoint
+            .as_deref()
+            .unwrap_or("http://localhost:4318");
+        let service_name = config.otel_service_name.as_deref().unwrap_or("zeroclaw");
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_http_client(super::otel::blocking_http_client().map_err(anyhow::Error::msg)?)
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .with_headers(config.otel_headers.clone())
+            .build()
+            .context("Failed to create OTLP span exporter")?;
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.otel_sample_ratio.clamp(0.0, 1.0),
+            ))))
+            .with_resource(
+                opentelemetry_sdk::Resource::builder()
+                    .with_service_name(service_name.to_string())
+                    .build(),
+            )
+            .build();
+
+        let layer = Self::new(provider.tracer("zeroclaw"));
+        Ok((layer, OtelTraceGuard { provider }))
+    }
+
+    pub fn new(tracer: Tracer) -> Self {
+        Self { tracer }
+    }
+}
+
+/// The OTel span backing a tracing span, stored in its extensions.
+struct OtelSpan(opentelemetry_sdk::trace::Span);
+
+struct AttributeVisitor<'a>(&'a mut Vec<KeyValue>);
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push(KeyValue::new(field.name(), value.to_string()));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(v) => self.0.push(KeyValue::new(field.name(), v)),
+            Err(_) => self.0.push(KeyValue::new(field.name(), value.to_string())),
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .push(KeyValue::new(field.name(), format!("{value:?}")));
+    }
+}
+
+impl<S> Layer<S> for OtelTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        if !attrs.metadata().target().starts_with("zeroclaw") {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        // Nearest enclosing span that was exported becomes the OTel parent.
+        let parent = span.scope().skip(1).find_map(|ancestor| {
+            ancestor
+                .extensions()
+                .get::<OtelSpan>()
+                .map(|otel| otel.0.span_context().clone())
+        });
+        let parent_cx = match parent {
+            Some(span_context) => Context::new().with_remote_span_context(span_context),
+            None => Context::new(),
+        };
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+        let builder = self
+            .tracer
+            .span_builder(attrs.metadata().name())
+            .with_kind(SpanKind::Internal)
+            .with_attributes(attributes);
+        let otel = self.tracer.build_with_context(builder, &parent_cx);
+        span.extensions_mut().insert(OtelSpan(otel));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(OtelSpan(otel)) = extensions.get_mut::<OtelSpan>() {
+            let mut attributes = Vec::new();
+            values.record(&mut AttributeVisitor(&mut attributes));
+            otel.set_attributes(attributes);
+        }
+    }
+
+    /// Log events become span events; errors also mark the span as failed.
+    fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(OtelSpan(otel)) = extensions.get_mut::<OtelSpan>() else {
+            return;
+        };
+
+        let mut attributes = Vec::new();
+        event.record(&mut AttributeVisitor(&mut attributes));
+        let message = attributes
+            .iter()
+            .position(|kv| kv.key.as_str() == "message")
+            .map(|i| attributes.remove(i).value.to_string())
+            .unwrap_or_else(|| event.metadata().name().to_string());
+        attributes.push(KeyValue::new("level", event.metadata().level().as_str()));
+
+        if *event.metadata().level() == Level::ERROR {
+            otel.set_status(Status::error(message.clone()));
+        }
+        otel.add_event(message, attributes);
+    }
+
+    fn on_close(&self, id: Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let otel = span.extensions_mut().remove::<OtelSpan>();
+        if let Some(OtelSpan(mut otel)) = otel {
+            otel.end();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SpanData};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a opentelemetry::Value> {
+        span.attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == key)
+            .map(|kv| &kv.value)
+    }
+
+    #[test]
+    fn nested_spans_export_with_parents_and_attributes() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber =
+            tracing_subscriber::registry().with(OtelTraceLayer::new(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let turn = tracing::info_span!("turn", turn_id = "t-1", channel = "cli");
+            let _turn = turn.enter();
+            let tool = tracing::info_span!("tool_call", tool = "shell");
+            let _tool = tool.enter();
+            tracing::error!("command failed");
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 2);
+        let tool = spans.iter().find(|s| s.name == "tool_call").unwrap();
+        let turn = spans.iter().find(|s| s.name == "turn").unwrap();
+
+        assert_eq!(tool.parent_span_id, turn.span_context.span_id());
+        assert_eq!(tool.span_context.trace_id(), turn.span_context.trace_id());
+        assert_eq!(
+            attribute(turn, "turn_id"),
+            Some(&opentelemetry::Value::from("t-1"))
+        );
+        assert_eq!(
+            attribute(tool, "tool"),
+            Some(&opentelemetry::Value::from("shell"))
+        );
+        assert_eq!(tool.events.len(), 1);
+        assert_eq!(tool.events[0].name, "command failed");
+        assert!(matches!(tool.status, Status::Error { .. }));
+    }
+}
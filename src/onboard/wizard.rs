@@ -101,6 +101,8 @@ pub fn run_wizard() -> Result<Config> {
     let config = Config {
         workspace_dir: workspace_dir.clone(),
         config_path: config_path.clone(),
+        profile: None,
+        sealed_keys: Vec::new(),
         api_key: if api_key.is_empty() {
             None
         } else {
@@ -112,6 +114,8 @@ pub fn run_wizard() -> Result<Config> {
         default_temperature: 0.7,
         observability: ObservabilityConfig::default(),
         autonomy: AutonomyConfig::default(),
+        tool_policy: crate::config::ToolPolicyConfig::default(),
+        security: crate::config::SecurityConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
@@ -133,6 +137,14 @@ pub fn run_wizard() -> Result<Config> {
         agents: std::collections::HashMap::new(),
         hardware: hardware_config,
         mcp: crate::mcp::config::McpConfig::default(),
+        postprocess: crate::config::PostprocessConfig::default(),
+        racing: crate::config::RacingConfig::default(),
+        reload: crate::config::ReloadConfig::default(),
+        shutdown: crate::config::ShutdownConfig::default(),
+        sessions: crate::config::SessionsConfig::default(),
+        hooks: std::collections::HashMap::new(),
+        workspaces: std::collections::HashMap::new(),
+        events: crate::config::EventsConfig::default(),
     };
 
     println!(
@@ -266,11 +278,37 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         archive_after_days: if profile.uses_sqlite_hygiene { 7 } else { 0 },
         purge_after_days: if profile.uses_sqlite_hygiene { 30 } else { 0 },
         conversation_retention_days: 30,
-        embedding_provider: "none".to_string(),
+        transcript_retention_days: 0,
+        embedding_provider: MemoryConfig::default().embedding_provider,
         embedding_model: "text-embedding-3-small".to_string(),
         embedding_dimensions: 1536,
+        embedding_batch_size: 64,
         vector_weight: 0.7,
         keyword_weight: 0.3,
+        rrf_k: 60.0,
+        recency_weight: 0.1,
+        recency_half_life_days: 30.0,
+        prune_interval_minutes: 60,
+        dedup_strategy: "update".into(),
+        dedup_threshold: 0.9,
+        max_memories: 0,
+        budget_action: "archive".into(),
+        encryption_key: None,
+        backup_schedule: None,
+        backup_dir: None,
+        backup_keep: 7,
+        postgres_url: None,
+        fts_tokenizer: "unicode61".into(),
+        fts_remove_diacritics: true,
+        vector_store: "sqlite".into(),
+        qdrant_url: "http://localhost:6333".into(),
+        qdrant_api_key: None,
+        qdrant_collection: "zeroclaw_memories".into(),
+        reranker: "none".into(),
+        reranker_model: None,
+        reranker_api_key: None,
+        rerank_candidates: 20,
+        namespaces: std::collections::HashMap::new(),
         embedding_cache_size: if profile.uses_sqlite_hygiene {
             10000
         } else {
@@ -322,6 +360,8 @@ pub fn run_quick_setup(
     let config = Config {
         workspace_dir: workspace_dir.clone(),
         config_path: config_path.clone(),
+        profile: None,
+        sealed_keys: Vec::new(),
         api_key: credential_override.map(String::from),
         api_url: None,
         default_provider: Some(provider_name.clone()),
@@ -329,6 +369,8 @@ pub fn run_quick_setup(
         default_temperature: 0.7,
         observability: ObservabilityConfig::default(),
         autonomy: AutonomyConfig::default(),
+        tool_policy: crate::config::ToolPolicyConfig::default(),
+        security: crate::config::SecurityConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
@@ -350,6 +392,14 @@ pub fn run_quick_setup(
         agents: std::collections::HashMap::new(),
         hardware: crate::config::HardwareConfig::default(),
         mcp: crate::mcp::config::McpConfig::default(),
+        postprocess: crate::config::PostprocessConfig::default(),
+        racing: crate::config::RacingConfig::default(),
+        reload: crate::config::ReloadConfig::default(),
+        shutdown: crate::config::ShutdownConfig::default(),
+        sessions: crate::config::SessionsConfig::default(),
+        hooks: std::collections::HashMap::new(),
+        workspaces: std::collections::HashMap::new(),
+        events: crate::config::EventsConfig::default(),
     };
 
     config.save()?;
@@ -2067,7 +2117,10 @@ fn setup_tool_mode() -> Result<(ComposioConfig, SecretsConfig)> {
         .default(true)
         .interact()?;
 
-    let secrets_config = SecretsConfig { encrypt };
+    let secrets_config = SecretsConfig {
+        encrypt,
+        master_key_file: None,
+    };
 
     if encrypt {
         println!(
@@ -3758,7 +3811,15 @@ fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()>
          - **memory_recall** — Search memory\n\
            - Use when: you need prior decisions, user preferences, or historical context.\n\
            - Don't use when: the answer is already in current files/conversation.\n\
-         - **memory_forget** — Delete a memory entry\n\
+         - **memory_list** — List stored memories, newest first\n\
+           - Use when: reviewing or cleaning up everything that is remembered.\n\
+           - Don't use when: looking for something specific; use memory_recall.\n\
+         - **memory_tags** — List the tags on stored memories\n\
+           - Use when: picking tags for memory_store or a tag filter for memory_recall.\n\
+         - **memory_update** — Correct a memory entry by key or id\n\
+           - Use when: a remembered fact is wrong or outdated.\n\
+           - Don't use when: adding a new fact; use memory_store.\n\
+         - **memory_forget** — Delete a memory entry (by key, id, or a confirmed query)\n\
            - Use when: memory is incorrect, stale, or explicitly requested to be removed.\n\
            - Don't use when: uncertain about impact; verify before deleting.\n\n\
          ---\n\
@@ -4421,6 +4482,7 @@ mod tests {
             "file_write",
             "memory_store",
             "memory_recall",
+            "memory_update",
             "memory_forget",
         ] {
             assert!(
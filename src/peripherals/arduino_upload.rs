@@ -55,6 +55,7 @@ impl Tool for ArduinoUploadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Code cannot be empty".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -67,6 +68,7 @@ impl Tool for ArduinoUploadTool {
                     "arduino-cli not found. Install it: https://arduino.github.io/arduino-cli/"
                         .into(),
                 ),
+                attachments: Vec::new(),
             });
         }
 
@@ -80,6 +82,7 @@ impl Tool for ArduinoUploadTool {
                 success: false,
                 output: format!("Failed to create sketch dir: {}", e),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -89,6 +92,7 @@ impl Tool for ArduinoUploadTool {
                 success: false,
                 output: format!("Failed to write sketch: {}", e),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -108,6 +112,7 @@ impl Tool for ArduinoUploadTool {
                     success: false,
                     output: format!("arduino-cli compile failed: {}", e),
                     error: Some(e.to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -119,6 +124,7 @@ impl Tool for ArduinoUploadTool {
                 success: false,
                 output: format!("Compile failed:\n{}", stderr),
                 error: Some("Arduino compile error".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -135,6 +141,7 @@ impl Tool for ArduinoUploadTool {
                     success: false,
                     output: format!("arduino-cli upload failed: {}", e),
                     error: Some(e.to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -147,6 +154,7 @@ impl Tool for ArduinoUploadTool {
                 success: false,
                 output: format!("Upload failed:\n{}", stderr),
                 error: Some("Arduino upload error".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -156,6 +164,7 @@ impl Tool for ArduinoUploadTool {
                 "Sketch compiled and uploaded successfully. The Arduino is now running your code."
                     .into(),
             error: None,
+            attachments: Vec::new(),
         })
     }
 }
@@ -68,12 +68,14 @@ impl Tool for UnoQGpioReadTool {
                         success: false,
                         output: resp.clone(),
                         error: Some(resp),
+                        attachments: Vec::new(),
                     })
                 } else {
                     Ok(ToolResult {
                         success: true,
                         output: resp,
                         error: None,
+                        attachments: Vec::new(),
                     })
                 }
             }
@@ -81,6 +83,7 @@ impl Tool for UnoQGpioReadTool {
                 success: false,
                 output: format!("Bridge error: {}", e),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -132,12 +135,14 @@ impl Tool for UnoQGpioWriteTool {
                         success: false,
                         output: resp.clone(),
                         error: Some(resp),
+                        attachments: Vec::new(),
                     })
                 } else {
                     Ok(ToolResult {
                         success: true,
                         output: "done".into(),
                         error: None,
+                        attachments: Vec::new(),
                     })
                 }
             }
@@ -145,6 +150,7 @@ impl Tool for UnoQGpioWriteTool {
                 success: false,
                 output: format!("Bridge error: {}", e),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
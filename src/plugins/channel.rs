@@ -0,0 +1,177 @@
+use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
+use anyhow::{bail, Context};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::ChildStdin;
+use tokio::sync::Mutex;
+
+/// A channel declared in a plugin manifest. The plugin runs for as long as
+/// the channel listens; messages travel as JSON lines over stdin/stdout.
+pub struct PluginChannel {
+    name: String,
+    executable: PathBuf,
+    workspace_dir: PathBuf,
+    stdin: Arc<Mutex<Option<ChildStdin>>>,
+}
+
+/// One incoming message line from the plugin.
+#[derive(Deserialize)]
+struct IncomingLine {
+    sender: String,
+    content: String,
+    #[serde(default)]
+    reply_target: Option<String>,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+impl PluginChannel {
+    pub fn new(name: &str, executable: PathBuf, workspace_dir: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            executable,
+            workspace_dir,
+            stdin: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> Option<ChannelMessage> {
+        let incoming: IncomingLine = match serde_json::from_str(line) {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                tracing::warn!("Plugin channel {}: ignoring line: {e}", self.name);
+                return None;
+            }
+        };
+        Some(ChannelMessage {
+            id: incoming
+                .id
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+            reply_target: incoming
+                .reply_target
+                .unwrap_or_else(|| incoming.sender.clone()),
+            sender: incoming.sender,
+            content: incoming.content,
+            channel: self.name.clone(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+    }
+}
+
+#[async_trait]
+impl Channel for PluginChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let mut line = serde_json::json!({
+            "recipient": message.recipient,
+            "content": message.content,
+            "subject": message.subject,
+        })
+        .to_string();
+        line.push('\n');
+
+        let mut guard = self.stdin.lock().await;
+        let Some(stdin) = guard.as_mut() else {
+            bail!("plugin channel {} is not running", self.name);
+        };
+        stdin.write_all(line.as_bytes()).await?;
+        stdin.flush().await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let mut cmd = tokio::process::Command::new(&self.executable);
+        cmd.arg("channel")
+            .arg(&self.name)
+            .current_dir(&self.workspace_dir)
+            .env("ZEROCLAW_WORKSPACE", &self.workspace_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true);
+        crate::security::limits::apply(&mut cmd);
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to start plugin channel {}", self.name))?;
+        *self.stdin.lock().await = child.stdin.take();
+        let stdout = child.stdout.take().context("plugin stdout unavailable")?;
+
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(message) = self.parse_line(&line) {
+                if tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        }
+
+        *self.stdin.lock().await = None;
+        let status = child.wait().await?;
+        // Returning an error lets the channel supervisor restart the plugin.
+        bail!("plugin channel {} exited ({status})", self.name)
+    }
+
+    async fn health_check(&self) -> bool {
+        self.executable.is_file()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::write_plugin;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn relays_lines_in_both_directions() {
+        let tmp = TempDir::new().unwrap();
+        let dir = write_plugin(
+            tmp.path(),
+            "relay",
+            "[plugin]\nname = \"relay\"\nexecutable = \"run.sh\"\n",
+            "echo '{\"sender\": \"ada\", \"content\": \"hello\"}'\n\
+             read reply\n\
+             echo \"$reply\" > reply.json",
+        );
+        let channel = Arc::new(PluginChannel::new(
+            "relay",
+            dir.join("run.sh"),
+            tmp.path().to_path_buf(),
+        ));
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let listener = {
+            let channel = channel.clone();
+            tokio::spawn(async move { channel.listen(tx).await })
+        };
+        let message = rx.recv().await.unwrap();
+        assert_eq!(message.channel, "relay");
+        assert_eq!(message.sender, "ada");
+        assert_eq!(message.reply_target, "ada");
+        assert_eq!(message.content, "hello");
+
+        channel
+            .send(&SendMessage::new("hi ada", message.reply_target))
+            .await
+            .unwrap();
+        assert!(listener.await.unwrap().is_err(), "exit is reported");
+        let reply: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(tmp.path().join("reply.json")).unwrap())
+                .unwrap();
+        assert_eq!(reply["recipient"], "ada");
+        assert_eq!(reply["content"], "hi ada");
+    }
+}
@@ -0,0 +1,537 @@
+//! Plugins discovered in `~/.zeroclaw/plugins/`.
+//!
+//! Each plugin is a directory with a `plugin.toml` manifest and an
+//! executable. The manifest declares the tools, channels and providers the
+//! plugin adds; they are registered alongside the built-ins at startup.
+//!
+//! ```toml
+//! [plugin]
+//! name = "weather"
+//! version = "0.1.0"
+//! description = "Forecasts from the national weather service"
+//! executable = "bin/weather"          # relative to the plugin directory
+//!
+//! [[tools]]
+//! name = "weather_forecast"
+//! description = "Forecast for a city"
+//! parameters = { type = "object", properties = { city = { type = "string" } }, required = ["city"] }
+//!
+//! [[channels]]
+//! name = "mastodon"
+//!
+//! [[providers]]
+//! name = "local-llm"                  # used as default_provider = "plugin:local-llm"
+//! ```
+//!
+//! The executable is invoked as `<executable> <kind> <name>`:
+//!
+//! - `tool <name>`: JSON arguments on stdin; stdout is either a JSON
+//!   `{"success", "output", "error"}` object or plain text (exit status
+//!   decides success).
+//! - `provider <name>`: `{"model", "temperature", "messages": [{"role",
+//!   "content"}]}` on stdin; the reply text on stdout.
+//! - `channel <name>`: long-running. Each stdout line is an incoming
+//!   `{"sender", "content", "reply_target"?, "id"?}` message; each stdin line
+//!   is an outgoing `{"recipient", "content", "subject"?}` message.
+//!
+//! Manifests may name a `wasm` component instead of an executable; those are
+//! listed but not loaded, since this build has no WASM runtime.
+//! `zeroclaw plugins disable <name>` leaves a `.disabled` marker in the
+//! plugin directory.
+
+mod channel;
+mod provider;
+mod tool;
+
+pub use channel::PluginChannel;
+pub use provider::PluginProvider;
+pub use tool::PluginTool;
+
+use crate::channels::Channel;
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use crate::tools::Tool;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+pub const MANIFEST_FILE: &str = "plugin.toml";
+const DISABLED_MARKER: &str = ".disabled";
+/// Provider names of the form `plugin:<name>` resolve to plugin providers.
+pub const PROVIDER_PREFIX: &str = "plugin:";
+
+static PLUGINS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginManifest {
+    pub plugin: PluginMeta,
+    #[serde(default)]
+    pub tools: Vec<PluginToolSpec>,
+    #[serde(default)]
+    pub channels: Vec<PluginComponent>,
+    #[serde(default)]
+    pub providers: Vec<PluginComponent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginMeta {
+    pub name: String,
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    /// Program to run, relative to the plugin directory
+    #[serde(default)]
+    pub executable: Option<String>,
+    /// WASM component, relative to the plugin directory
+    #[serde(default)]
+    pub wasm: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments
+    #[serde(default = "default_parameters")]
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginComponent {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_version() -> String {
+    "0.1.0".to_string()
+}
+
+fn default_parameters() -> serde_json::Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+/// A discovered plugin directory.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+    pub enabled: bool,
+}
+
+impl Plugin {
+    pub fn name(&self) -> &str {
+        &self.manifest.plugin.name
+    }
+
+    pub fn kind(&self) -> &'static str {
+        if self.manifest.plugin.executable.is_some() {
+            "executable"
+        } else {
+            "wasm"
+        }
+    }
+
+    /// The plugin's executable, which must stay inside its directory.
+    pub fn executable(&self) -> Result<PathBuf> {
+        let Some(rel) = self.manifest.plugin.executable.as_deref() else {
+            bail!(
+                "plugin '{}' is a WASM component; this build has no WASM runtime",
+                self.name()
+            );
+        };
+        let path = self
+            .dir
+            .join(rel)
+            .canonicalize()
+            .with_context(|| format!("plugin '{}': executable {rel} not found", self.name()))?;
+        let dir = self.dir.canonicalize()?;
+        if !path.starts_with(&dir) {
+            bail!(
+                "plugin '{}': executable {rel} is outside the plugin directory",
+                self.name()
+            );
+        }
+        Ok(path)
+    }
+}
+
+/// Run `<exe> <kind> <name>` once with `input` on stdin and collect its
+/// output, killing it after `timeout`.
+pub(crate) async fn invoke(
+    exe: &Path,
+    kind: &str,
+    name: &str,
+    input: &[u8],
+    cwd: Option<&Path>,
+    timeout: Duration,
+) -> Result<std::process::Output> {
+    let mut cmd = tokio::process::Command::new(exe);
+    cmd.arg(kind)
+        .arg(name)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(cwd) = cwd {
+        cmd.current_dir(cwd).env("ZEROCLAW_WORKSPACE", cwd);
+    }
+    crate::security::limits::apply(&mut cmd);
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("Failed to start plugin {}", exe.display()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A plugin may exit without reading its input; that is not an error.
+        let _ = stdin.write_all(input).await;
+    }
+    tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .with_context(|| {
+            format!(
+                "plugin {kind} '{name}' timed out after {}s",
+                timeout.as_secs()
+            )
+        })?
+        .with_context(|| format!("plugin {kind} '{name}' failed"))
+}
+
+/// `plugins/` next to `config.toml`.
+pub fn plugins_dir(config: &Config) -> PathBuf {
+    config
+        .config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("plugins")
+}
+
+/// Remember the plugins directory for lookups that have no config at hand
+/// (provider names). Called once at startup.
+pub fn init(config: &Config) {
+    let _ = PLUGINS_DIR.set(plugins_dir(config));
+}
+
+fn global_dir() -> Option<PathBuf> {
+    PLUGINS_DIR.get().cloned().or_else(|| {
+        directories::UserDirs::new().map(|u| u.home_dir().join(".zeroclaw").join("plugins"))
+    })
+}
+
+fn load_plugin(dir: &Path) -> Result<Plugin> {
+    let path = dir.join(MANIFEST_FILE);
+    let raw =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let manifest: PluginManifest =
+        toml::from_str(&raw).with_context(|| format!("Invalid {}", path.display()))?;
+    match (&manifest.plugin.executable, &manifest.plugin.wasm) {
+        (None, None) => bail!("{}: set plugin.executable or plugin.wasm", path.display()),
+        (Some(_), Some(_)) => bail!(
+            "{}: set only one of plugin.executable and plugin.wasm",
+            path.display()
+        ),
+        _ => {}
+    }
+    Ok(Plugin {
+        manifest,
+        dir: dir.to_path_buf(),
+        enabled: !dir.join(DISABLED_MARKER).exists(),
+    })
+}
+
+/// Every plugin directory under `dir`, sorted by name. Broken manifests are
+/// returned as errors so `plugins list` can show them.
+pub fn discover(dir: &Path) -> Vec<Result<Plugin>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .collect();
+    dirs.sort();
+    dirs.iter().map(|path| load_plugin(path)).collect()
+}
+
+/// Enabled plugins that can run in this build; problems are logged.
+fn loadable(dir: &Path) -> Vec<(Plugin, PathBuf)> {
+    discover(dir)
+        .into_iter()
+        .filter_map(|plugin| match plugin {
+            Ok(plugin) if plugin.enabled => match plugin.executable() {
+                Ok(exe) => Some((plugin, exe)),
+                Err(e) => {
+                    tracing::warn!("Skipping plugin: {e:#}");
+                    None
+                }
+            },
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Skipping plugin: {e:#}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Tools from enabled plugins.
+pub fn tools(config: &Config, security: &Arc<SecurityPolicy>) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for (plugin, exe) in loadable(&plugins_dir(config)) {
+        for spec in &plugin.manifest.tools {
+            tools.push(Box::new(PluginTool::new(
+                plugin.name(),
+                spec.clone(),
+                exe.clone(),
+                config.workspace_dir.clone(),
+                security.clone(),
+            )));
+        }
+    }
+    tools
+}
+
+/// Channels from enabled plugins, paired with a display name.
+pub fn channels(config: &Config) -> Vec<(String, Arc<dyn Channel>)> {
+    let mut channels: Vec<(String, Arc<dyn Channel>)> = Vec::new();
+    for (plugin, exe) in loadable(&plugins_dir(config)) {
+        for component in &plugin.manifest.channels {
+            channels.push((
+                format!("{} (plugin {})", component.name, plugin.name()),
+                Arc::new(PluginChannel::new(
+                    &component.name,
+                    exe.clone(),
+                    config.workspace_dir.clone(),
+                )),
+            ));
+        }
+    }
+    channels
+}
+
+/// Provider `name` (without the `plugin:` prefix) from an enabled plugin.
+pub fn provider(name: &str) -> Result<PluginProvider> {
+    let Some(dir) = global_dir() else {
+        bail!("Could not locate the plugins directory");
+    };
+    for (plugin, exe) in loadable(&dir) {
+        if plugin.manifest.providers.iter().any(|p| p.name == name) {
+            return Ok(PluginProvider::new(name, exe));
+        }
+    }
+    bail!(
+        "No enabled plugin provides '{PROVIDER_PREFIX}{name}' (see `zeroclaw plugins list`, plugins in {})",
+        dir.display()
+    )
+}
+
+fn find(dir: &Path, name: &str) -> Result<Plugin> {
+    discover(dir)
+        .into_iter()
+        .flatten()
+        .find(|plugin| plugin.name() == name)
+        .with_context(|| format!("No plugin named '{name}' in {}", dir.display()))
+}
+
+/// Enable or disable a plugin by name.
+pub fn set_enabled(dir: &Path, name: &str, enabled: bool) -> Result<()> {
+    let plugin = find(dir, name)?;
+    let marker = plugin.dir.join(DISABLED_MARKER);
+    if enabled {
+        if marker.exists() {
+            fs::remove_file(&marker)?;
+        }
+    } else {
+        fs::write(&marker, "")?;
+    }
+    Ok(())
+}
+
+fn components(plugin: &Plugin) -> String {
+    let m = &plugin.manifest;
+    let mut parts = Vec::new();
+    for (count, what) in [
+        (m.tools.len(), "tool"),
+        (m.channels.len(), "channel"),
+        (m.providers.len(), "provider"),
+    ] {
+        if count > 0 {
+            parts.push(format!(
+                "{count} {what}{}",
+                if count == 1 { "" } else { "s" }
+            ));
+        }
+    }
+    if parts.is_empty() {
+        "nothing registered".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+pub fn handle_command(command: crate::PluginCommands, config: &Config) -> Result<()> {
+    let dir = plugins_dir(config);
+    match command {
+        crate::PluginCommands::List => {
+            let plugins = discover(&dir);
+            if plugins.is_empty() {
+                println!("No plugins installed.");
+                println!("  Add one as {}/<name>/{MANIFEST_FILE}", dir.display());
+                return Ok(());
+            }
+
+            println!("🔌 Plugins ({}) in {}:", plugins.len(), dir.display());
+            for plugin in plugins {
+                match plugin {
+                    Ok(plugin) => {
+                        let status = match (plugin.enabled, plugin.executable()) {
+                            (false, _) => "disabled".to_string(),
+                            (true, Ok(_)) => "enabled".to_string(),
+                            (true, Err(e)) => format!("not loaded: {e}"),
+                        };
+                        println!(
+                            "- {} v{} [{}] {} — {}",
+                            plugin.name(),
+                            plugin.manifest.plugin.version,
+                            plugin.kind(),
+                            components(&plugin),
+                            status
+                        );
+                    }
+                    Err(e) => println!("- ⚠️  {e:#}"),
+                }
+            }
+            Ok(())
+        }
+        crate::PluginCommands::Info { name } => {
+            let plugin = find(&dir, &name)?;
+            let meta = &plugin.manifest.plugin;
+            println!("🔌 {} v{} ({})", meta.name, meta.version, plugin.kind());
+            if !meta.description.is_empty() {
+                println!("   {}", meta.description);
+            }
+            println!("   Directory: {}", plugin.dir.display());
+            println!(
+                "   Status:    {}",
+                if plugin.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+            for tool in &plugin.manifest.tools {
+                println!("   Tool:      {} — {}", tool.name, tool.description);
+            }
+            for channel in &plugin.manifest.channels {
+                println!("   Channel:   {}", channel.name);
+            }
+            for provider in &plugin.manifest.providers {
+                println!("   Provider:  {PROVIDER_PREFIX}{}", provider.name);
+            }
+            Ok(())
+        }
+        crate::PluginCommands::Enable { name } => {
+            set_enabled(&dir, &name, true)?;
+            println!("✅ Enabled plugin {name} (restart running servers to load it)");
+            Ok(())
+        }
+        crate::PluginCommands::Disable { name } => {
+            set_enabled(&dir, &name, false)?;
+            println!("⏸️  Disabled plugin {name}");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Write a plugin whose executable is a shell script.
+    #[cfg(unix)]
+    pub(crate) fn write_plugin(root: &Path, name: &str, manifest: &str, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        let exe = dir.join("run.sh");
+        fs::write(&exe, format!("#!/bin/sh\n{script}")).unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discover_reports_plugins_and_toggles_enabled() {
+        let tmp = TempDir::new().unwrap();
+        write_plugin(
+            tmp.path(),
+            "weather",
+            r#"
+[plugin]
+name = "weather"
+executable = "run.sh"
+
+[[tools]]
+name = "weather_forecast"
+description = "Forecast"
+
+[[providers]]
+name = "local-llm"
+"#,
+            "echo sunny",
+        );
+        write_plugin(
+            tmp.path(),
+            "escape",
+            "[plugin]\nname = \"escape\"\nexecutable = \"../weather/run.sh\"\n",
+            "",
+        );
+        fs::create_dir_all(tmp.path().join("broken")).unwrap();
+        fs::write(tmp.path().join("broken").join(MANIFEST_FILE), "[plugin]\n").unwrap();
+
+        let found = discover(tmp.path());
+        assert_eq!(found.len(), 3);
+        assert!(found[0].is_err(), "broken manifest is reported");
+        let escape = found[1].as_ref().unwrap();
+        assert!(escape.executable().is_err(), "executable must stay inside");
+        let weather = found[2].as_ref().unwrap();
+        assert!(weather.enabled);
+        assert_eq!(components(weather), "1 tool, 1 provider");
+        assert_eq!(
+            weather.manifest.tools[0].parameters,
+            serde_json::json!({"type": "object", "properties": {}})
+        );
+
+        set_enabled(tmp.path(), "weather", false).unwrap();
+        assert!(!find(tmp.path(), "weather").unwrap().enabled);
+        assert!(loadable(tmp.path()).is_empty());
+        set_enabled(tmp.path(), "weather", true).unwrap();
+        assert_eq!(loadable(tmp.path()).len(), 1);
+        assert!(set_enabled(tmp.path(), "missing", true).is_err());
+    }
+
+    #[test]
+    fn wasm_plugins_are_listed_but_not_loaded() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("calc");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            "[plugin]\nname = \"calc\"\nwasm = \"calc.wasm\"\n",
+        )
+        .unwrap();
+
+        let plugin = discover(tmp.path()).remove(0).unwrap();
+        assert_eq!(plugin.kind(), "wasm");
+        assert!(plugin.executable().is_err());
+        assert!(loadable(tmp.path()).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use super::invoke;
+use crate::providers::{ChatMessage, Provider};
+use anyhow::bail;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Model calls can be slow; give plugins the same room as HTTP providers.
+const PROVIDER_TIMEOUT_SECS: u64 = 300;
+
+/// A provider declared in a plugin manifest, selected as `plugin:<name>`.
+pub struct PluginProvider {
+    name: String,
+    executable: PathBuf,
+}
+
+impl PluginProvider {
+    pub fn new(name: &str, executable: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            executable,
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for PluginProvider {
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let mut messages = Vec::new();
+        if let Some(system) = system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(message));
+        self.chat_with_history(&messages, model, temperature).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = serde_json::json!({
+            "model": model,
+            "temperature": temperature,
+            "messages": messages,
+        });
+        let output = invoke(
+            &self.executable,
+            "provider",
+            &self.name,
+            request.to_string().as_bytes(),
+            None,
+            Duration::from_secs(PROVIDER_TIMEOUT_SECS),
+        )
+        .await?;
+        if !output.status.success() {
+            bail!(
+                "plugin provider '{}' failed ({}): {}",
+                self.name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::write_plugin;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn sends_history_as_json_and_returns_stdout() {
+        let tmp = TempDir::new().unwrap();
+        let dir = write_plugin(
+            tmp.path(),
+            "echo-llm",
+            "[plugin]\nname = \"echo-llm\"\nexecutable = \"run.sh\"\n",
+            "cat",
+        );
+        let provider = PluginProvider::new("echo", dir.join("run.sh"));
+
+        let reply = provider
+            .chat_with_system(Some("be brief"), "hi", "tiny", 0.2)
+            .await
+            .unwrap();
+        let request: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(request["model"], "tiny");
+        assert_eq!(request["messages"][0]["role"], "system");
+        assert_eq!(request["messages"][1]["content"], "hi");
+    }
+}
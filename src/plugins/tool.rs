@@ -0,0 +1,204 @@
+use super::{invoke, PluginToolSpec};
+use crate::security::SecurityPolicy;
+use crate::tools::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum plugin tool run time before kill.
+const TOOL_TIMEOUT_SECS: u64 = 60;
+/// Maximum output size in bytes (1MB).
+const MAX_OUTPUT_BYTES: usize = 1_048_576;
+
+/// A tool declared in a plugin manifest and run by the plugin executable.
+pub struct PluginTool {
+    plugin: String,
+    spec: PluginToolSpec,
+    executable: PathBuf,
+    workspace_dir: PathBuf,
+    security: Arc<SecurityPolicy>,
+}
+
+/// Structured reply a plugin may print instead of plain text.
+#[derive(Deserialize)]
+struct PluginReply {
+    success: bool,
+    #[serde(default)]
+    output: String,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+impl PluginTool {
+    pub fn new(
+        plugin: &str,
+        spec: PluginToolSpec,
+        executable: PathBuf,
+        workspace_dir: PathBuf,
+        security: Arc<SecurityPolicy>,
+    ) -> Self {
+        Self {
+            plugin: plugin.to_string(),
+            spec,
+            executable,
+            workspace_dir,
+            security,
+        }
+    }
+
+    fn failure(error: impl Into<String>) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error.into()),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+fn truncate(mut text: String) -> String {
+    if text.len() > MAX_OUTPUT_BYTES {
+        text.truncate(text.floor_char_boundary(MAX_OUTPUT_BYTES));
+        text.push_str("\n... [output truncated at 1MB]");
+    }
+    text
+}
+
+#[async_trait]
+impl Tool for PluginTool {
+    fn name(&self) -> &str {
+        &self.spec.name
+    }
+
+    fn description(&self) -> &str {
+        &self.spec.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.spec.parameters.clone()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        // Plugins are arbitrary programs, so they get the same gate as shell.
+        if !self.security.can_act() {
+            return Ok(Self::failure("Action blocked: autonomy is read-only"));
+        }
+        if self.security.is_rate_limited() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(Self::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+
+        let output = match invoke(
+            &self.executable,
+            "tool",
+            &self.spec.name,
+            args.to_string().as_bytes(),
+            Some(&self.workspace_dir),
+            Duration::from_secs(TOOL_TIMEOUT_SECS),
+        )
+        .await
+        {
+            Ok(output) => output,
+            Err(e) => return Ok(Self::failure(format!("plugin {}: {e:#}", self.plugin))),
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if let Ok(reply) = serde_json::from_str::<PluginReply>(&stdout) {
+            return Ok(ToolResult {
+                success: reply.success,
+                output: truncate(reply.output),
+                error: reply.error,
+                attachments: Vec::new(),
+            });
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Ok(ToolResult {
+            success: output.status.success(),
+            output: truncate(stdout),
+            error: if stderr.is_empty() {
+                None
+            } else {
+                Some(truncate(stderr))
+            },
+            attachments: Vec::new(),
+        })
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::plugins::tests::write_plugin;
+    use crate::security::AutonomyLevel;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn tool(root: &std::path::Path, script: &str, autonomy: AutonomyLevel) -> PluginTool {
+        let dir = write_plugin(
+            root,
+            "greeter",
+            "[plugin]\nname = \"greeter\"\nexecutable = \"run.sh\"\n",
+            script,
+        );
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: root.to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        PluginTool::new(
+            "greeter",
+            PluginToolSpec {
+                name: "greet".into(),
+                description: "Greet someone".into(),
+                parameters: json!({"type": "object"}),
+            },
+            dir.join("run.sh"),
+            root.to_path_buf(),
+            security,
+        )
+    }
+
+    #[tokio::test]
+    async fn runs_plugin_with_arguments_on_stdin() {
+        let tmp = TempDir::new().unwrap();
+        let plain = tool(
+            tmp.path(),
+            "read input; echo \"$1 $2 $input in $(basename \"$ZEROCLAW_WORKSPACE\")\"",
+            AutonomyLevel::Supervised,
+        );
+        let result = plain.execute(json!({"who": "ada"})).await.unwrap();
+        assert!(result.success);
+        let workspace = tmp.path().file_name().unwrap().to_string_lossy();
+        assert_eq!(
+            result.output,
+            format!("tool greet {{\"who\":\"ada\"}} in {workspace}")
+        );
+
+        let structured = tool(
+            tmp.path(),
+            "echo '{\"success\": false, \"error\": \"no such person\"}'",
+            AutonomyLevel::Supervised,
+        );
+        let result = structured.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("no such person"));
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_blocks_plugin_tools() {
+        let tmp = TempDir::new().unwrap();
+        let tool = tool(tmp.path(), "echo ran", AutonomyLevel::ReadOnly);
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
@@ -426,7 +426,10 @@ impl Provider for AnthropicProvider {
                 Some(NativeToolSpec {
                     name: func.get("name")?.as_str()?.to_string(),
                     description: func.get("description")?.as_str()?.to_string(),
-                    input_schema: func.get("parameters").cloned().unwrap_or(serde_json::json!({"type": "object", "properties": {}})),
+                    input_schema: func
+                        .get("parameters")
+                        .cloned()
+                        .unwrap_or(serde_json::json!({"type": "object", "properties": {}})),
                 })
             })
             .collect();
@@ -437,7 +440,11 @@ impl Provider for AnthropicProvider {
             system: system_prompt,
             messages: native_messages,
             temperature,
-            tools: if native_tools.is_empty() { None } else { Some(native_tools) },
+            tools: if native_tools.is_empty() {
+                None
+            } else {
+                Some(native_tools)
+            },
         };
 
         let req = self
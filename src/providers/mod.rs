@@ -5,6 +5,7 @@ pub mod gemini;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
+pub mod racing;
 pub mod reliable;
 pub mod router;
 pub mod traits;
@@ -437,6 +438,12 @@ pub fn create_provider_with_url(
             )))
         }
 
+        // ── Plugin providers ────────────────────────────────
+        // Format: "plugin:<name>" (see `zeroclaw plugins list`)
+        name if name.starts_with(crate::plugins::PROVIDER_PREFIX) => Ok(Box::new(
+            crate::plugins::provider(&name[crate::plugins::PROVIDER_PREFIX.len()..])?,
+        )),
+
         _ => anyhow::bail!(
             "Unknown provider: {name}. Check README for supported providers or run `zeroclaw onboard --interactive` to reconfigure.\n\
              Tip: Use \"custom:https://your-api.com\" for OpenAI-compatible endpoints.\n\
@@ -487,6 +494,47 @@ pub fn create_resilient_provider(
     Ok(Box::new(reliable))
 }
 
+/// Create the resilient provider chain, wrapped in a [`racing::RacingProvider`]
+/// when latency racing is enabled. The challenger gets its own retry chain.
+pub fn create_racing_provider(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    racing: &crate::config::RacingConfig,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let primary = create_resilient_provider(primary_name, api_key, api_url, reliability)?;
+    let challenger_name = racing.provider.trim();
+    if !racing.enabled || challenger_name.is_empty() {
+        return Ok(primary);
+    }
+    // The primary's model name rarely exists at another provider
+    let Some(challenger_model) = racing
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+    else {
+        tracing::warn!("racing.model is not set; replies are not raced");
+        return Ok(primary);
+    };
+
+    let challenger_key = racing
+        .api_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .or(api_key);
+    let challenger = create_resilient_provider(challenger_name, challenger_key, None, reliability)?;
+
+    Ok(Box::new(racing::RacingProvider::new(
+        (primary_name.to_string(), primary),
+        (challenger_name.to_string(), challenger),
+        challenger_model.to_string(),
+        racing.max_message_chars,
+    )))
+}
+
 /// Create a RouterProvider if model routes are configured, otherwise return a
 /// standard resilient provider. The router wraps individual providers per route,
 /// each with its own retry/fallback chain.
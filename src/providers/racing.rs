@@ -0,0 +1,277 @@
+use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::Provider;
+use async_trait::async_trait;
+use std::future::Future;
+
+/// Latency racing — sends short interactive requests to two providers at once
+/// and returns whichever completes first, dropping (and so cancelling) the other.
+///
+/// Requests whose latest user message exceeds `max_message_chars` go to the
+/// primary provider only, so long or expensive turns are never paid for twice.
+/// If the first provider to finish fails, the other one's result is used.
+pub struct RacingProvider {
+    primary: (String, Box<dyn Provider>),
+    challenger: (String, Box<dyn Provider>),
+    challenger_model: String,
+    max_message_chars: usize,
+}
+
+impl RacingProvider {
+    pub fn new(
+        primary: (String, Box<dyn Provider>),
+        challenger: (String, Box<dyn Provider>),
+        challenger_model: String,
+        max_message_chars: usize,
+    ) -> Self {
+        Self {
+            primary,
+            challenger,
+            challenger_model,
+            max_message_chars,
+        }
+    }
+
+    fn is_short(&self, message: &str) -> bool {
+        message.chars().count() <= self.max_message_chars
+    }
+
+    fn should_race(&self, messages: &[ChatMessage]) -> bool {
+        messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .is_some_and(|m| self.is_short(&m.content))
+    }
+
+    /// Native and prompt-guided tool calling produce different response
+    /// shapes, so tool requests are only raced between like providers.
+    fn tools_compatible(&self) -> bool {
+        self.primary.1.supports_native_tools() == self.challenger.1.supports_native_tools()
+    }
+
+    async fn race<T>(
+        &self,
+        primary: impl Future<Output = anyhow::Result<T>>,
+        challenger: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let (primary_name, challenger_name) = (&self.primary.0, &self.challenger.0);
+        tokio::pin!(primary);
+        tokio::pin!(challenger);
+
+        tokio::select! {
+            result = &mut primary => match result {
+                Ok(value) => {
+                    tracing::debug!(winner = primary_name.as_str(), "Provider race won");
+                    Ok(value)
+                }
+                Err(e) => {
+                    tracing::warn!(provider = primary_name.as_str(), error = %e, "Racing provider failed; awaiting challenger");
+                    challenger.await
+                }
+            },
+            result = &mut challenger => match result {
+                Ok(value) => {
+                    tracing::debug!(winner = challenger_name.as_str(), "Provider race won");
+                    Ok(value)
+                }
+                Err(e) => {
+                    tracing::warn!(provider = challenger_name.as_str(), error = %e, "Racing provider failed; awaiting primary");
+                    primary.await
+                }
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl Provider for RacingProvider {
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in [&self.primary, &self.challenger] {
+            if let Err(e) = provider.warmup().await {
+                tracing::warn!(provider = name.as_str(), "Warmup failed (non-fatal): {e}");
+            }
+        }
+        Ok(())
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let primary = self
+            .primary
+            .1
+            .chat_with_system(system_prompt, message, model, temperature);
+        if !self.is_short(message) {
+            return primary.await;
+        }
+        let challenger = self.challenger.1.chat_with_system(
+            system_prompt,
+            message,
+            &self.challenger_model,
+            temperature,
+        );
+        self.race(primary, challenger).await
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let primary = self
+            .primary
+            .1
+            .chat_with_history(messages, model, temperature);
+        if !self.should_race(messages) {
+            return primary.await;
+        }
+        let challenger =
+            self.challenger
+                .1
+                .chat_with_history(messages, &self.challenger_model, temperature);
+        self.race(primary, challenger).await
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let primary = self.primary.1.chat(request, model, temperature);
+        if !self.should_race(request.messages) || !self.tools_compatible() {
+            return primary.await;
+        }
+        let challenger = self
+            .challenger
+            .1
+            .chat(request, &self.challenger_model, temperature);
+        self.race(primary, challenger).await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let primary = self
+            .primary
+            .1
+            .chat_with_tools(messages, tools, model, temperature);
+        if !self.should_race(messages) || !self.tools_compatible() {
+            return primary.await;
+        }
+        let challenger =
+            self.challenger
+                .1
+                .chat_with_tools(messages, tools, &self.challenger_model, temperature);
+        self.race(primary, challenger).await
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.primary.1.supports_native_tools()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct DelayedProvider {
+        reply: &'static str,
+        delay: Duration,
+        fail: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    fn delayed(
+        reply: &'static str,
+        delay_ms: u64,
+        fail: bool,
+    ) -> (Box<dyn Provider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = DelayedProvider {
+            reply,
+            delay: Duration::from_millis(delay_ms),
+            fail,
+            calls: Arc::clone(&calls),
+        };
+        (Box::new(provider), calls)
+    }
+
+    #[async_trait]
+    impl Provider for DelayedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                anyhow::bail!("{} failed", self.reply);
+            }
+            Ok(format!("{}:{model}", self.reply))
+        }
+    }
+
+    fn racer(
+        primary: (Box<dyn Provider>, Arc<AtomicUsize>),
+        challenger: (Box<dyn Provider>, Arc<AtomicUsize>),
+    ) -> RacingProvider {
+        RacingProvider::new(
+            ("primary".into(), primary.0),
+            ("challenger".into(), challenger.0),
+            "fast-model".into(),
+            100,
+        )
+    }
+
+    #[tokio::test]
+    async fn faster_challenger_wins_short_requests() {
+        let provider = racer(delayed("slow", 200, false), delayed("fast", 5, false));
+        let reply = provider
+            .chat_with_system(None, "hi", "main-model", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "fast:fast-model");
+    }
+
+    #[tokio::test]
+    async fn failed_winner_falls_back_to_other_provider() {
+        let provider = racer(delayed("slow", 30, false), delayed("broken", 1, true));
+        let reply = provider
+            .chat_with_history(&[ChatMessage::user("hi")], "main-model", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "slow:main-model");
+    }
+
+    #[tokio::test]
+    async fn long_requests_only_hit_primary() {
+        let (challenger, challenger_calls) = delayed("fast", 1, false);
+        let provider = racer(
+            delayed("slow", 10, false),
+            (challenger, Arc::clone(&challenger_calls)),
+        );
+        let long = "x".repeat(500);
+        let reply = provider
+            .chat_with_system(None, &long, "main-model", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "slow:main-model");
+        assert_eq!(challenger_calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -1,8 +1,11 @@
-use super::traits::{ChatMessage, ChatResponse, StreamChunk, StreamOptions, StreamResult};
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
+};
 use super::Provider;
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
@@ -137,44 +140,29 @@ impl ReliableProvider {
             base
         }
     }
-}
 
-#[async_trait]
-impl Provider for ReliableProvider {
-    async fn warmup(&self) -> anyhow::Result<()> {
-        for (name, provider) in &self.providers {
-            tracing::info!(provider = name, "Warming up provider connection pool");
-            if provider.warmup().await.is_err() {
-                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
-            }
-        }
-        Ok(())
-    }
-
-    async fn chat_with_system(
-        &self,
-        system_prompt: Option<&str>,
-        message: &str,
-        model: &str,
-        temperature: f64,
-    ) -> anyhow::Result<String> {
+    /// Run `call` against each model in the fallback chain and each provider
+    /// in turn, retrying transient errors with backoff, until one succeeds.
+    async fn with_failover<'a, T, F, Fut>(&'a self, model: &'a str, call: F) -> anyhow::Result<T>
+    where
+        F: Fn(&'a dyn Provider, &'a str) -> Fut + Send + Sync,
+        Fut: Future<Output = anyhow::Result<T>> + Send,
+        T: Send,
+    {
         let models = self.model_chain(model);
         let mut failures = Vec::new();
 
-        for current_model in &models {
+        for current_model in models {
             for (provider_name, provider) in &self.providers {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_system(system_prompt, message, current_model, temperature)
-                        .await
-                    {
+                    match call(provider.as_ref(), current_model).await {
                         Ok(resp) => {
-                            if attempt > 0 || *current_model != model {
+                            if attempt > 0 || current_model != model {
                                 tracing::info!(
                                     provider = provider_name,
-                                    model = *current_model,
+                                    model = current_model,
                                     attempt,
                                     original_model = model,
                                     "Provider recovered (failover/retry)"
@@ -213,7 +201,7 @@ impl Provider for ReliableProvider {
                             if non_retryable {
                                 tracing::warn!(
                                     provider = provider_name,
-                                    model = *current_model,
+                                    model = current_model,
                                     "Non-retryable error, moving on"
                                 );
                                 break;
@@ -223,7 +211,7 @@ impl Provider for ReliableProvider {
                                 let wait = self.compute_backoff(backoff_ms, &e);
                                 tracing::warn!(
                                     provider = provider_name,
-                                    model = *current_model,
+                                    model = current_model,
                                     attempt = attempt + 1,
                                     backoff_ms = wait,
                                     "Provider call failed, retrying"
@@ -237,15 +225,15 @@ impl Provider for ReliableProvider {
 
                 tracing::warn!(
                     provider = provider_name,
-                    model = *current_model,
+                    model = current_model,
                     "Exhausted retries, trying next provider/model"
                 );
             }
 
-            if *current_model != model {
+            if current_model != model {
                 tracing::warn!(
                     original_model = model,
-                    fallback_model = *current_model,
+                    fallback_model = current_model,
                     "Model fallback exhausted all providers, trying next fallback model"
                 );
             }
@@ -256,6 +244,32 @@ impl Provider for ReliableProvider {
             failures.join("\n")
         )
     }
+}
+
+#[async_trait]
+impl Provider for ReliableProvider {
+    async fn warmup(&self) -> anyhow::Result<()> {
+        for (name, provider) in &self.providers {
+            tracing::info!(provider = name, "Warming up provider connection pool");
+            if provider.warmup().await.is_err() {
+                tracing::warn!(provider = name, "Warmup failed (non-fatal)");
+            }
+        }
+        Ok(())
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.with_failover(model, |provider, model| {
+            provider.chat_with_system(system_prompt, message, model, temperature)
+        })
+        .await
+    }
 
     async fn chat_with_history(
         &self,
@@ -263,94 +277,10 @@ impl Provider for ReliableProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let models = self.model_chain(model);
-        let mut failures = Vec::new();
-
-        for current_model in &models {
-            for (provider_name, provider) in &self.providers {
-                let mut backoff_ms = self.base_backoff_ms;
-
-                for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_history(messages, current_model, temperature)
-                        .await
-                    {
-                        Ok(resp) => {
-                            if attempt > 0 || *current_model != model {
-                                tracing::info!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    attempt,
-                                    original_model = model,
-                                    "Provider recovered (failover/retry)"
-                                );
-                            }
-                            return Ok(resp);
-                        }
-                        Err(e) => {
-                            let non_retryable = is_non_retryable(&e);
-                            let rate_limited = is_rate_limited(&e);
-
-                            let failure_reason = if rate_limited {
-                                "rate_limited"
-                            } else if non_retryable {
-                                "non_retryable"
-                            } else {
-                                "retryable"
-                            };
-                            failures.push(format!(
-                                "{provider_name}/{current_model} attempt {}/{}: {failure_reason}",
-                                attempt + 1,
-                                self.max_retries + 1
-                            ));
-
-                            if rate_limited {
-                                if let Some(new_key) = self.rotate_key() {
-                                    tracing::info!(
-                                        provider = provider_name,
-                                        "Rate limited, rotated API key (key ending ...{})",
-                                        &new_key[new_key.len().saturating_sub(4)..]
-                                    );
-                                }
-                            }
-
-                            if non_retryable {
-                                tracing::warn!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    "Non-retryable error, moving on"
-                                );
-                                break;
-                            }
-
-                            if attempt < self.max_retries {
-                                let wait = self.compute_backoff(backoff_ms, &e);
-                                tracing::warn!(
-                                    provider = provider_name,
-                                    model = *current_model,
-                                    attempt = attempt + 1,
-                                    backoff_ms = wait,
-                                    "Provider call failed, retrying"
-                                );
-                                tokio::time::sleep(Duration::from_millis(wait)).await;
-                                backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
-                            }
-                        }
-                    }
-                }
-
-                tracing::warn!(
-                    provider = provider_name,
-                    model = *current_model,
-                    "Exhausted retries, trying next provider/model"
-                );
-            }
-        }
-
-        anyhow::bail!(
-            "All providers/models failed. Attempts:\n{}",
-            failures.join("\n")
-        )
+        self.with_failover(model, |provider, model| {
+            provider.chat_with_history(messages, model, temperature)
+        })
+        .await
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -367,20 +297,23 @@ impl Provider for ReliableProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        // Delegate to first provider (no retry across providers for tool calls,
-        // since tool state is conversational and non-idempotent)
-        let (provider_name, provider) = self
-            .providers
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("No providers configured"))?;
+        // Model calls have no side effects; the tools they ask for run later
+        self.with_failover(model, |provider, model| {
+            provider.chat_with_tools(messages, tools, model, temperature)
+        })
+        .await
+    }
 
-        provider
-            .chat_with_tools(messages, tools, model, temperature)
-            .await
-            .map_err(|e| {
-                tracing::warn!(provider = provider_name, error = %e, "chat_with_tools failed");
-                e
-            })
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.with_failover(model, |provider, model| {
+            provider.chat(request, model, temperature)
+        })
+        .await
     }
 
     fn supports_streaming(&self) -> bool {
@@ -766,6 +699,45 @@ mod tests {
         assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn tool_turns_retry_and_fall_back() {
+        use crate::tools::ToolSpec;
+
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let messages = vec![ChatMessage::user("hello")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: Some(&tools),
+        };
+        let mock = |calls: &Arc<AtomicUsize>, fail_until_attempt, response| {
+            Box::new(MockProvider {
+                calls: Arc::clone(calls),
+                fail_until_attempt,
+                response,
+                error: "503 overloaded",
+            })
+        };
+
+        let primary_calls = Arc::new(AtomicUsize::new(0));
+        let fallback_calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![
+                ("primary".into(), mock(&primary_calls, usize::MAX, "never")),
+                ("fallback".into(), mock(&fallback_calls, 0, "fallback ok")),
+            ],
+            1,
+            1,
+        );
+        let response = provider.chat(request, "test", 0.0).await.unwrap();
+        assert_eq!(response.text.as_deref(), Some("fallback ok"));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
+    }
+
     // ── New tests: model failover ──
 
     #[tokio::test]
@@ -0,0 +1,381 @@
+//! Per-session sandbox runtime.
+//!
+//! Unlike [`DockerRuntime`](super::DockerRuntime), which starts a fresh
+//! container for every command, the isolated runtime starts one sandbox per
+//! zeroclaw process the first time it is needed and `exec`s every shell
+//! command — and every MCP server marked `isolated = true` — inside it. The
+//! only host path the sandbox sees is its own scratch directory, mounted at
+//! `/scratch`.
+//!
+//! The sandbox's main process just drains its stdin, which is a pipe held by
+//! zeroclaw: when zeroclaw exits (even on a crash) the pipe closes, the
+//! sandbox stops, and `--rm` removes it.
+
+use super::traits::RuntimeAdapter;
+use crate::config::IsolatedRuntimeConfig;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Mount point of the scratch directory inside the sandbox.
+const SCRATCH_MOUNT: &str = "/scratch";
+
+static SESSION: OnceLock<Arc<IsolationSession>> = OnceLock::new();
+
+/// The process-wide sandbox, created from the first config that asks for it.
+pub fn session(config: &IsolatedRuntimeConfig) -> Arc<IsolationSession> {
+    SESSION
+        .get_or_init(|| Arc::new(IsolationSession::new(config.clone())))
+        .clone()
+}
+
+/// The process-wide sandbox, if an isolated runtime has been created.
+pub fn active_session() -> Option<Arc<IsolationSession>> {
+    SESSION.get().cloned()
+}
+
+/// One sandbox container (or microVM) shared by everything in this process.
+pub struct IsolationSession {
+    config: IsolatedRuntimeConfig,
+    name: String,
+    scratch: PathBuf,
+    container: Mutex<Option<Child>>,
+}
+
+impl IsolationSession {
+    fn new(config: IsolatedRuntimeConfig) -> Self {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let name = format!("zeroclaw-{}-{}", std::process::id(), &id[..8]);
+        let base = config
+            .scratch_dir
+            .as_deref()
+            .map(|dir| PathBuf::from(shellexpand::tilde(dir).as_ref()))
+            .unwrap_or_else(|| std::env::temp_dir().join("zeroclaw-sandbox"));
+        let scratch = base.join(&name);
+        Self {
+            config,
+            name,
+            scratch,
+            container: Mutex::new(None),
+        }
+    }
+
+    /// Container name, unique per zeroclaw process.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Host directory mounted at `/scratch`.
+    pub fn scratch_dir(&self) -> &Path {
+        &self.scratch
+    }
+
+    /// Container engine binary: `krun` is podman with the krun OCI runtime.
+    fn engine(&self) -> &'static str {
+        match self.config.backend.trim() {
+            "podman" | "krun" => "podman",
+            _ => "docker",
+        }
+    }
+
+    /// Arguments that start the long-lived sandbox.
+    fn run_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![
+            "run".into(),
+            "--rm".into(),
+            "--init".into(),
+            "--interactive".into(),
+            "--name".into(),
+            self.name.clone(),
+        ];
+
+        if self.config.backend.trim() == "krun" {
+            args.extend(["--runtime".into(), "krun".into()]);
+        }
+
+        let network = self.config.network.trim();
+        if !network.is_empty() {
+            args.extend(["--network".into(), network.into()]);
+        }
+
+        if let Some(memory_limit_mb) = self.config.memory_limit_mb.filter(|mb| *mb > 0) {
+            args.extend(["--memory".into(), format!("{memory_limit_mb}m")]);
+        }
+
+        if let Some(cpu_limit) = self.config.cpu_limit.filter(|cpus| *cpus > 0.0) {
+            args.extend(["--cpus".into(), cpu_limit.to_string()]);
+        }
+
+        args.extend([
+            "--volume".into(),
+            format!("{}:{SCRATCH_MOUNT}:rw", self.scratch.display()),
+            "--workdir".into(),
+            SCRATCH_MOUNT.into(),
+            self.config.image.trim().into(),
+            "sh".into(),
+            "-c".into(),
+            "cat >/dev/null".into(),
+        ]);
+        args
+    }
+
+    /// Arguments that run `program args...` inside the sandbox.
+    fn exec_args(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+    ) -> Vec<String> {
+        let mut exec: Vec<String> = vec![
+            "exec".into(),
+            "--interactive".into(),
+            "--workdir".into(),
+            SCRATCH_MOUNT.into(),
+        ];
+        let mut env: Vec<_> = env.iter().collect();
+        env.sort();
+        for (key, value) in env {
+            exec.extend(["--env".into(), format!("{key}={value}")]);
+        }
+        exec.push(self.name.clone());
+        exec.push(program.into());
+        exec.extend(args.iter().cloned());
+        exec
+    }
+
+    /// Start the sandbox unless it is already running.
+    fn ensure_started(&self) -> Result<()> {
+        let mut container = self
+            .container
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(child) = container.as_mut() {
+            if child.try_wait()?.is_none() {
+                return Ok(());
+            }
+            tracing::warn!(sandbox = %self.name, "Sandbox exited — starting a new one");
+        }
+
+        std::fs::create_dir_all(&self.scratch).with_context(|| {
+            format!(
+                "Failed to create sandbox scratch dir {}",
+                self.scratch.display()
+            )
+        })?;
+
+        let engine = self.engine();
+        let mut child = Command::new(engine)
+            .args(self.run_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start sandbox with `{engine}`"))?;
+
+        let deadline = Instant::now() + Duration::from_secs(self.config.startup_timeout_secs);
+        loop {
+            if let Some(status) = child.try_wait()? {
+                let mut stderr = String::new();
+                if let Some(mut pipe) = child.stderr.take() {
+                    let _ = pipe.read_to_string(&mut stderr);
+                }
+                bail!(
+                    "Sandbox exited during startup ({status}): {}",
+                    stderr.trim()
+                );
+            }
+            if self.is_running() {
+                break;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                bail!(
+                    "Sandbox did not start within {}s (runtime.isolated.startup_timeout_secs)",
+                    self.config.startup_timeout_secs
+                );
+            }
+            std::thread::sleep(Duration::from_millis(200));
+        }
+
+        tracing::info!(
+            sandbox = %self.name,
+            backend = %self.config.backend,
+            scratch = %self.scratch.display(),
+            "Sandbox started"
+        );
+        *container = Some(child);
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        Command::new(self.engine())
+            .args(["inspect", "--format", "{{.State.Running}}", &self.name])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .is_ok_and(|out| String::from_utf8_lossy(&out.stdout).trim() == "true")
+    }
+
+    /// Rewrite `program args...` to run inside the sandbox, starting it if
+    /// needed. Returns the host program and its arguments.
+    pub fn wrap(
+        &self,
+        program: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        self.ensure_started()?;
+        Ok((
+            self.engine().to_string(),
+            self.exec_args(program, args, env),
+        ))
+    }
+}
+
+impl Drop for IsolationSession {
+    fn drop(&mut self) {
+        let container = self
+            .container
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(mut child) = container.take() {
+            // Closing stdin stops the sandbox; `rm -f` covers a wedged one.
+            drop(child.stdin.take());
+            let _ = Command::new(self.engine())
+                .args(["rm", "--force", &self.name])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Runtime that executes shell commands inside the per-session sandbox.
+pub struct IsolatedRuntime {
+    session: Arc<IsolationSession>,
+}
+
+impl IsolatedRuntime {
+    pub fn new(config: &IsolatedRuntimeConfig) -> Self {
+        Self {
+            session: session(config),
+        }
+    }
+}
+
+impl RuntimeAdapter for IsolatedRuntime {
+    fn name(&self) -> &str {
+        "isolated"
+    }
+
+    fn has_shell_access(&self) -> bool {
+        true
+    }
+
+    fn has_filesystem_access(&self) -> bool {
+        false
+    }
+
+    fn storage_path(&self) -> PathBuf {
+        PathBuf::from(SCRATCH_MOUNT).join(".zeroclaw")
+    }
+
+    fn supports_long_running(&self) -> bool {
+        true
+    }
+
+    fn memory_budget(&self) -> u64 {
+        self.session
+            .config
+            .memory_limit_mb
+            .map_or(0, |mb| mb.saturating_mul(1024 * 1024))
+    }
+
+    fn build_shell_command(
+        &self,
+        command: &str,
+        _workspace_dir: &Path,
+    ) -> anyhow::Result<tokio::process::Command> {
+        let (program, args) =
+            self.session
+                .wrap("sh", &["-c".into(), command.into()], &HashMap::new())?;
+        let mut process = tokio::process::Command::new(program);
+        process.args(args);
+        Ok(process)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_args_mount_scratch_and_apply_limits() {
+        let session = IsolationSession::new(IsolatedRuntimeConfig {
+            scratch_dir: Some("/tmp/zc-scratch".into()),
+            memory_limit_mb: Some(256),
+            cpu_limit: Some(0.5),
+            ..IsolatedRuntimeConfig::default()
+        });
+        let args = session.run_args().join(" ");
+
+        assert_eq!(session.engine(), "docker");
+        assert!(args.contains("--network none"));
+        assert!(args.contains("--memory 256m"));
+        assert!(args.contains("--cpus 0.5"));
+        assert!(args.contains(&format!(
+            "--volume /tmp/zc-scratch/{}:/scratch:rw",
+            session.name()
+        )));
+        assert!(args.contains("--workdir /scratch"));
+        assert!(args.ends_with("alpine:3.20 sh -c cat >/dev/null"));
+        assert!(!args.contains("/workspace"));
+    }
+
+    #[test]
+    fn krun_backend_uses_podman_with_krun_runtime() {
+        let session = IsolationSession::new(IsolatedRuntimeConfig {
+            backend: "krun".into(),
+            ..IsolatedRuntimeConfig::default()
+        });
+        assert_eq!(session.engine(), "podman");
+        assert!(session.run_args().join(" ").contains("--runtime krun"));
+    }
+
+    #[test]
+    fn exec_args_target_session_container() {
+        let session = IsolationSession::new(IsolatedRuntimeConfig::default());
+        let env = HashMap::from([("TOKEN".to_string(), "abc".to_string())]);
+        let args = session.exec_args("npx", &["-y".into(), "server".into()], &env);
+
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "--interactive",
+                "--workdir",
+                "/scratch",
+                "--env",
+                "TOKEN=abc",
+                session.name(),
+                "npx",
+                "-y",
+                "server",
+            ]
+        );
+    }
+
+    #[test]
+    fn sessions_get_distinct_names_and_scratch_dirs() {
+        let a = IsolationSession::new(IsolatedRuntimeConfig::default());
+        let b = IsolationSession::new(IsolatedRuntimeConfig::default());
+        assert_ne!(a.name(), b.name());
+        assert_ne!(a.scratch_dir(), b.scratch_dir());
+        assert!(a.name().starts_with("zeroclaw-"));
+    }
+}
@@ -1,8 +1,10 @@
 pub mod docker;
+pub mod isolated;
 pub mod native;
 pub mod traits;
 
 pub use docker::DockerRuntime;
+pub use isolated::IsolatedRuntime;
 pub use native::NativeRuntime;
 pub use traits::RuntimeAdapter;
 
@@ -13,13 +15,18 @@ pub fn create_runtime(config: &RuntimeConfig) -> anyhow::Result<Box<dyn RuntimeA
     match config.kind.as_str() {
         "native" => Ok(Box::new(NativeRuntime::new())),
         "docker" => Ok(Box::new(DockerRuntime::new(config.docker.clone()))),
+        "isolated" => Ok(Box::new(IsolatedRuntime::new(&config.isolated))),
         "cloudflare" => anyhow::bail!(
             "runtime.kind='cloudflare' is not implemented yet. Use runtime.kind='native' for now."
         ),
         other if other.trim().is_empty() => {
-            anyhow::bail!("runtime.kind cannot be empty. Supported values: native, docker")
+            anyhow::bail!(
+                "runtime.kind cannot be empty. Supported values: native, docker, isolated"
+            )
         }
-        other => anyhow::bail!("Unknown runtime kind '{other}'. Supported values: native, docker"),
+        other => anyhow::bail!(
+            "Unknown runtime kind '{other}'. Supported values: native, docker, isolated"
+        ),
     }
 }
 
@@ -49,6 +56,18 @@ mod tests {
         assert!(rt.has_shell_access());
     }
 
+    #[test]
+    fn factory_isolated() {
+        let cfg = RuntimeConfig {
+            kind: "isolated".into(),
+            ..RuntimeConfig::default()
+        };
+        let rt = create_runtime(&cfg).unwrap();
+        assert_eq!(rt.name(), "isolated");
+        assert!(rt.has_shell_access());
+        assert!(!rt.has_filesystem_access());
+    }
+
     #[test]
     fn factory_cloudflare_errors() {
         let cfg = RuntimeConfig {
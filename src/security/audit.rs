@@ -141,6 +141,12 @@ impl AuditEvent {
         self.security.sandbox_backend = sandbox_backend;
         self
     }
+
+    /// Mark the event as a policy violation
+    pub fn with_policy_violation(mut self) -> Self {
+        self.security.policy_violation = true;
+        self
+    }
 }
 
 /// Audit logger
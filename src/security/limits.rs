@@ -0,0 +1,178 @@
+//! Resource limits for child processes (`[security.resources]`).
+//!
+//! When enabled, every process zeroclaw spawns on behalf of the agent — shell
+//! commands, cron shell jobs, MCP stdio servers, plugins, git — gets CPU time,
+//! memory, open-file and file-size rlimits set between `fork` and `exec`, so a
+//! runaway child is killed by the kernel instead of taking the host down.
+//! Commands that run inside a container runtime are limited by the container;
+//! the rlimits then only apply to the local `docker`/`podman` client.
+
+use crate::config::ResourceLimitsConfig;
+use std::sync::OnceLock;
+
+static LIMITS: OnceLock<ResourceLimitsConfig> = OnceLock::new();
+
+/// Remember the configured limits for spawn sites that have no config at
+/// hand. Called once at startup.
+pub fn init(config: &ResourceLimitsConfig) {
+    let _ = LIMITS.set(config.clone());
+}
+
+/// Apply the configured limits to `cmd` (no-op when disabled or not unix).
+pub fn apply(cmd: &mut tokio::process::Command) {
+    if let Some(config) = LIMITS.get() {
+        apply_config(cmd, config);
+    }
+}
+
+/// Largest single response to read from a child (`usize::MAX` = no limit).
+pub fn max_output_bytes() -> usize {
+    LIMITS
+        .get()
+        .filter(|config| config.enabled && config.max_output_bytes > 0)
+        .map_or(usize::MAX, |config| config.max_output_bytes)
+}
+
+/// The rlimits to set, each `None` when unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rlimits {
+    cpu_secs: Option<u64>,
+    memory_bytes: Option<u64>,
+    open_files: Option<u64>,
+    file_size_bytes: Option<u64>,
+}
+
+impl Rlimits {
+    fn from_config(config: &ResourceLimitsConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let nonzero = |value: u64| (value > 0).then_some(value);
+        Some(Self {
+            cpu_secs: nonzero(config.max_cpu_time_seconds),
+            memory_bytes: nonzero(u64::from(config.max_memory_mb) * 1024 * 1024),
+            open_files: nonzero(config.max_open_files),
+            file_size_bytes: nonzero(config.max_file_size_mb.saturating_mul(1024 * 1024)),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn apply_config(cmd: &mut tokio::process::Command, config: &ResourceLimitsConfig) {
+    let Some(limits) = Rlimits::from_config(config) else {
+        return;
+    };
+    // SAFETY: the closure runs in the forked child before exec and only
+    // calls getrlimit/setrlimit, which are async-signal-safe.
+    unsafe {
+        cmd.pre_exec(move || sys::set_all(&limits));
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_config(_cmd: &mut tokio::process::Command, _config: &ResourceLimitsConfig) {}
+
+#[cfg(unix)]
+mod sys {
+    use super::Rlimits;
+    use std::io;
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    type Resource = libc::c_int;
+
+    /// Linux counts only real (writable, private) memory in `RLIMIT_DATA`, so
+    /// runtimes that reserve large address ranges (V8, Go) still start.
+    #[cfg(target_os = "linux")]
+    const MEMORY: Resource = libc::RLIMIT_DATA;
+    #[cfg(not(target_os = "linux"))]
+    const MEMORY: Resource = libc::RLIMIT_AS;
+
+    pub(super) fn set_all(limits: &Rlimits) -> io::Result<()> {
+        let pairs = [
+            (libc::RLIMIT_CPU, limits.cpu_secs),
+            (MEMORY, limits.memory_bytes),
+            (libc::RLIMIT_NOFILE, limits.open_files),
+            (libc::RLIMIT_FSIZE, limits.file_size_bytes),
+        ];
+        for (resource, value) in pairs {
+            if let Some(value) = value {
+                set(resource, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lower both soft and hard limit to `value`, never raising either.
+    fn set(resource: Resource, value: u64) -> io::Result<()> {
+        let mut current = libc::rlimit {
+            rlim_cur: 0,
+            rlim_max: 0,
+        };
+        // SAFETY: `current` is a valid, writable rlimit struct.
+        if unsafe { libc::getrlimit(resource, &raw mut current) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let value = libc::rlim_t::try_from(value).unwrap_or(libc::RLIM_INFINITY);
+        let limit = libc::rlimit {
+            rlim_cur: value.min(current.rlim_max),
+            rlim_max: value.min(current.rlim_max),
+        };
+        // SAFETY: `limit` is a valid rlimit struct.
+        if unsafe { libc::setrlimit(resource, &raw const limit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_config_sets_no_limits() {
+        assert_eq!(Rlimits::from_config(&ResourceLimitsConfig::default()), None);
+    }
+
+    #[test]
+    fn zero_values_mean_unlimited() {
+        let config = ResourceLimitsConfig {
+            enabled: true,
+            max_memory_mb: 256,
+            max_cpu_time_seconds: 0,
+            max_open_files: 64,
+            max_file_size_mb: 0,
+            ..ResourceLimitsConfig::default()
+        };
+        assert_eq!(
+            Rlimits::from_config(&config),
+            Some(Rlimits {
+                cpu_secs: None,
+                memory_bytes: Some(256 * 1024 * 1024),
+                open_files: Some(64),
+                file_size_bytes: None,
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn limits_reach_the_child_process() {
+        let config = ResourceLimitsConfig {
+            enabled: true,
+            max_open_files: 64,
+            max_cpu_time_seconds: 30,
+            ..ResourceLimitsConfig::default()
+        };
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg("ulimit -n; ulimit -t");
+        apply_config(&mut cmd, &config);
+
+        let output = cmd.output().await.unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines, ["64", "30"]);
+    }
+}
@@ -7,9 +7,11 @@ pub mod docker;
 pub mod firejail;
 #[cfg(feature = "sandbox-landlock")]
 pub mod landlock;
+pub mod limits;
 pub mod pairing;
 pub mod policy;
 pub mod secrets;
+pub mod tool_policy;
 pub mod traits;
 
 #[allow(unused_imports)]
@@ -137,7 +137,7 @@ impl Default for SecurityPolicy {
 
 /// Skip leading environment variable assignments (e.g. `FOO=bar cmd args`).
 /// Returns the remainder starting at the first non-assignment word.
-fn skip_env_assignments(s: &str) -> &str {
+pub(crate) fn skip_env_assignments(s: &str) -> &str {
     let mut rest = s;
     loop {
         let Some(word) = rest.split_whitespace().next() else {
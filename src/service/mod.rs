@@ -124,6 +124,40 @@ fn uninstall(config: &Config) -> Result<()> {
     anyhow::bail!("Service management is supported on macOS and Linux only")
 }
 
+/// Restart the daemon if it runs under the installed service unit.
+/// Returns whether a restart was issued.
+pub fn restart_if_installed(config: &Config) -> Result<bool> {
+    if cfg!(target_os = "macos") {
+        if !macos_service_file()?.exists() {
+            return Ok(false);
+        }
+        let out = run_capture(Command::new("launchctl").arg("list"))?;
+        if !out.lines().any(|line| line.contains(SERVICE_LABEL)) {
+            return Ok(false);
+        }
+        let _ = run_checked(Command::new("launchctl").arg("stop").arg(SERVICE_LABEL));
+        run_checked(Command::new("launchctl").arg("start").arg(SERVICE_LABEL))?;
+        Ok(true)
+    } else if cfg!(target_os = "linux") {
+        if !linux_service_file(config)?.exists() {
+            return Ok(false);
+        }
+        let state = run_capture(Command::new("systemctl").args([
+            "--user",
+            "is-active",
+            "zeroclaw.service",
+        ]))
+        .unwrap_or_default();
+        if state.trim() != "active" {
+            return Ok(false);
+        }
+        run_checked(Command::new("systemctl").args(["--user", "restart", "zeroclaw.service"]))?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
 fn install_macos(config: &Config) -> Result<()> {
     let file = macos_service_file()?;
     if let Some(parent) = file.parent() {
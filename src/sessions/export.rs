@@ -0,0 +1,274 @@
+//! Render a [`Transcript`] as Markdown, JSON or standalone HTML.
+//!
+//! Secrets are redacted from messages, tool arguments and tool results before
+//! rendering: built-in key/token patterns, `key=value` credentials, and any
+//! argument whose name looks like a credential.
+
+use super::{Transcript, TranscriptEntry};
+use crate::agent::loop_::{is_sensitive_key, scrub_credentials, ToolCallRecord};
+use crate::channels::postprocess::redact_secrets;
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::fmt::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            "html" => Ok(Self::Html),
+            other => bail!("Unknown export format '{other}' (expected md, json or html)"),
+        }
+    }
+}
+
+/// Redact and render a transcript.
+pub fn render(transcript: &Transcript, format: ExportFormat) -> Result<String> {
+    let transcript = redact(transcript);
+    Ok(match format {
+        ExportFormat::Markdown => markdown(&transcript),
+        ExportFormat::Json => serde_json::to_string_pretty(&transcript)? + "\n",
+        ExportFormat::Html => html(&transcript),
+    })
+}
+
+fn redact_text(text: &str) -> String {
+    scrub_credentials(&redact_secrets(text))
+}
+
+fn redact_value(value: &Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(redact_text(s)),
+        Value::Array(items) => Value::Array(items.iter().map(redact_value).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let value = if is_sensitive_key(key) {
+                        Value::String("[REDACTED]".into())
+                    } else {
+                        redact_value(value)
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn redact(transcript: &Transcript) -> Transcript {
+    Transcript {
+        session_id: transcript.session_id.clone(),
+        entries: transcript
+            .entries
+            .iter()
+            .map(|entry| TranscriptEntry {
+                timestamp: entry.timestamp,
+                role: entry.role.clone(),
+                content: redact_text(&entry.content),
+                tool_calls: entry
+                    .tool_calls
+                    .iter()
+                    .map(|call| ToolCallRecord {
+                        name: call.name.clone(),
+                        arguments: redact_value(&call.arguments),
+                        result: redact_text(&call.result),
+                        success: call.success,
+                        duration_ms: call.duration_ms,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn heading(entry: &TranscriptEntry) -> String {
+    let who = match entry.role.as_str() {
+        "user" => "👤 User",
+        "assistant" => "🤖 Assistant",
+        other => other,
+    };
+    format!(
+        "{who} — {}",
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
+    )
+}
+
+fn status(call: &ToolCallRecord) -> &'static str {
+    if call.success {
+        "✅"
+    } else {
+        "❌"
+    }
+}
+
+/// A code fence longer than any backtick run inside `text`.
+fn fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn pretty_arguments(call: &ToolCallRecord) -> String {
+    serde_json::to_string_pretty(&call.arguments).unwrap_or_else(|_| call.arguments.to_string())
+}
+
+fn markdown(transcript: &Transcript) -> String {
+    let mut out = format!("# Session `{}`\n\n", transcript.session_id);
+    if let (Some(first), Some(last)) = (transcript.entries.first(), transcript.entries.last()) {
+        let _ = writeln!(
+            out,
+            "_{} messages, {} – {}_\n",
+            transcript.entries.len(),
+            first.timestamp.format("%Y-%m-%d %H:%M UTC"),
+            last.timestamp.format("%Y-%m-%d %H:%M UTC")
+        );
+    }
+
+    for entry in &transcript.entries {
+        let _ = writeln!(out, "## {}\n", heading(entry));
+        for call in &entry.tool_calls {
+            let args = pretty_arguments(call);
+            let args_fence = fence(&args);
+            let result_fence = fence(&call.result);
+            let _ = writeln!(
+                out,
+                "### Tool call: `{}` {} ({} ms)\n\nArguments:\n\n{args_fence}json\n{args}\n{args_fence}\n\nResult:\n\n{result_fence}\n{}\n{result_fence}\n",
+                call.name,
+                status(call),
+                call.duration_ms,
+                call.result.trim_end()
+            );
+        }
+        let _ = writeln!(out, "{}\n", entry.content.trim_end());
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+.msg{border-left:4px solid #ccc;padding:.25rem 1rem;margin:1.5rem 0}.user{border-color:#3b82f6}.assistant{border-color:#10b981}\
+.meta{color:#666;font-size:.85rem}.content{white-space:pre-wrap}pre{background:#f5f5f5;padding:.75rem;overflow-x:auto}";
+
+fn html(transcript: &Transcript) -> String {
+    let title = escape_html(&transcript.session_id);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Session {title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>Session <code>{title}</code></h1>\n"
+    );
+
+    for entry in &transcript.entries {
+        let _ = writeln!(
+            out,
+            "<section class=\"msg {}\">\n<p class=\"meta\">{}</p>",
+            escape_html(&entry.role),
+            escape_html(&heading(entry))
+        );
+        for call in &entry.tool_calls {
+            let _ = writeln!(
+                out,
+                "<details>\n<summary>Tool call: <code>{}</code> {} ({} ms)</summary>\n<p>Arguments:</p>\n<pre>{}</pre>\n<p>Result:</p>\n<pre>{}</pre>\n</details>",
+                escape_html(&call.name),
+                status(call),
+                call.duration_ms,
+                escape_html(&pretty_arguments(call)),
+                escape_html(call.result.trim_end())
+            );
+        }
+        let _ = writeln!(
+            out,
+            "<div class=\"content\">{}</div>\n</section>",
+            escape_html(entry.content.trim_end())
+        );
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn transcript() -> Transcript {
+        Transcript {
+            session_id: "cli-1".into(),
+            entries: vec![
+                TranscriptEntry {
+                    timestamp: Utc::now(),
+                    role: "user".into(),
+                    content: "call the API with sk-abcdefghijklmnopqrstuvwxyz123456".into(),
+                    tool_calls: Vec::new(),
+                },
+                TranscriptEntry {
+                    timestamp: Utc::now(),
+                    role: "assistant".into(),
+                    content: "Done <b>ok</b>".into(),
+                    tool_calls: vec![ToolCallRecord {
+                        name: "http_request".into(),
+                        arguments: serde_json::json!({
+                            "url": "https://api.example.com",
+                            "api_key": "plain-value",
+                        }),
+                        result: "password=hunter2hunter2".into(),
+                        success: true,
+                        duration_ms: 12,
+                    }],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn every_format_redacts_secrets() {
+        for format in [
+            ExportFormat::Markdown,
+            ExportFormat::Json,
+            ExportFormat::Html,
+        ] {
+            let out = render(&transcript(), format).unwrap();
+            assert!(
+                !out.contains("sk-abcdefghijklmnopqrstuvwxyz123456"),
+                "{format:?}"
+            );
+            assert!(!out.contains("plain-value"), "{format:?}");
+            assert!(!out.contains("hunter2hunter2"), "{format:?}");
+            assert!(out.contains("https://api.example.com"), "{format:?}");
+        }
+    }
+
+    #[test]
+    fn markdown_and_html_include_tool_calls() {
+        let md = render(&transcript(), ExportFormat::Markdown).unwrap();
+        assert!(md.contains("### Tool call: `http_request` ✅ (12 ms)"));
+        assert!(md.contains("Done <b>ok</b>"));
+
+        let html = render(&transcript(), ExportFormat::Html).unwrap();
+        assert!(html.contains("<summary>Tool call: <code>http_request</code>"));
+        assert!(html.contains("Done &lt;b&gt;ok&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn format_parses_cli_names() {
+        assert_eq!(
+            "md".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!("HTML".parse::<ExportFormat>().unwrap(), ExportFormat::Html);
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+}
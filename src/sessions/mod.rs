@@ -0,0 +1,502 @@
+//! Session transcripts.
+//!
+//! Every CLI and channel turn is appended to
+//! `workspace/sessions/<date>-<session_id>.jsonl` — the user message, the
+//! reply, and the tool calls made in between. `zeroclaw sessions export` and
+//! the `session_transcript` tool render them via [`export`]. Together they
+//! are the agent's episodic memory: what was said and when, searched by the
+//! `conversation_search` tool through [`search`], while the memory backend
+//! holds distilled facts. Memory hygiene archives and purges these files like
+//! other session logs, and deletes them after
+//! `memory.transcript_retention_days`.
+
+pub mod export;
+
+use crate::agent::loop_::ToolCallRecord;
+use crate::config::Config;
+use crate::memory::TimeRange;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub use export::{render, ExportFormat};
+
+const TRANSCRIPT_EXT: &str = ".jsonl";
+/// `YYYY-MM-DD-` file name prefix.
+const DATE_PREFIX_LEN: usize = 11;
+
+tokio::task_local! {
+    static CURRENT_SESSION: String;
+}
+
+/// Run `fut` with `session_id` as the current session, so tools acting on
+/// "this conversation" can find it.
+pub async fn scope<F: Future>(session_id: String, fut: F) -> F::Output {
+    CURRENT_SESSION.scope(session_id, fut).await
+}
+
+/// Session id of the turn running on this task, if any.
+pub fn current_session_id() -> Option<String> {
+    CURRENT_SESSION.try_with(Clone::clone).ok()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub timestamp: DateTime<Utc>,
+    pub role: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub session_id: String,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub id: String,
+    pub messages: usize,
+    pub last_active: DateTime<Utc>,
+}
+
+pub fn sessions_dir(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("sessions")
+}
+
+/// File-name-safe form of a session id (`signal_+1 555` →
+/// `signal_%2B1%20555`). Other bytes are percent-encoded, so distinct ids
+/// never share a file. Also valid in a URI path.
+pub(crate) fn file_id(session_id: &str) -> String {
+    let mut id = String::with_capacity(session_id.len());
+    for byte in session_id.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'@') {
+            id.push(char::from(byte));
+        } else {
+            let _ = write!(id, "%{byte:02X}");
+        }
+    }
+    id
+}
+
+/// Session id a [`file_id`] was made from. Names written before ids were
+/// percent-encoded have no `%` and come back unchanged.
+pub(crate) fn session_id_of(file_id: &str) -> String {
+    let bytes = file_id.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| file_id.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Append one user/assistant exchange to the session's transcript. Callers
+/// check `sessions.record_transcripts` first.
+pub fn record_turn(
+    workspace_dir: &Path,
+    session_id: &str,
+    user_message: &str,
+    response: &str,
+    tool_calls: &[ToolCallRecord],
+) -> Result<()> {
+    let dir = sessions_dir(workspace_dir);
+    fs::create_dir_all(&dir)?;
+    let now = Utc::now();
+    let path = dir.join(format!(
+        "{}-{}{TRANSCRIPT_EXT}",
+        now.format("%Y-%m-%d"),
+        file_id(session_id)
+    ));
+
+    let entries = [
+        TranscriptEntry {
+            timestamp: now,
+            role: "user".into(),
+            content: user_message.to_string(),
+            tool_calls: Vec::new(),
+        },
+        TranscriptEntry {
+            timestamp: now,
+            role: "assistant".into(),
+            content: response.to_string(),
+            tool_calls: tool_calls.to_vec(),
+        },
+    ];
+    let mut lines = String::new();
+    for entry in &entries {
+        lines.push_str(&serde_json::to_string(entry)?);
+        lines.push('\n');
+    }
+
+    // One write per turn so concurrent appends don't interleave lines.
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .with_context(|| format!("Failed to append transcript {}", path.display()))
+}
+
+/// Session id encoded in a transcript file name, if it is one.
+fn session_of(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let stem = name.strip_suffix(TRANSCRIPT_EXT)?;
+    if stem.len() <= DATE_PREFIX_LEN || !stem.is_char_boundary(DATE_PREFIX_LEN) {
+        return None;
+    }
+    NaiveDate::parse_from_str(&stem[..DATE_PREFIX_LEN - 1], "%Y-%m-%d").ok()?;
+    Some(session_id_of(&stem[DATE_PREFIX_LEN..]))
+}
+
+/// Transcript files grouped by session, oldest file first. Includes files
+/// already moved to `sessions/archive` by memory hygiene.
+fn transcript_files(workspace_dir: &Path) -> Result<BTreeMap<String, Vec<PathBuf>>> {
+    let dir = sessions_dir(workspace_dir);
+    let mut sessions: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in [dir.clone(), dir.join("archive")] {
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if let Some(id) = session_of(&path) {
+                sessions.entry(id).or_default().push(path);
+            }
+        }
+    }
+    for paths in sessions.values_mut() {
+        paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    }
+    Ok(sessions)
+}
+
+fn read_entries(path: &Path) -> Result<Vec<TranscriptEntry>> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transcript {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping malformed transcript line in {}: {e}",
+                    path.display()
+                );
+                None
+            }
+        })
+        .collect())
+}
+
+/// Load the full transcript of a session.
+pub fn load(workspace_dir: &Path, session_id: &str) -> Result<Transcript> {
+    let Some(paths) = transcript_files(workspace_dir)?.remove(session_id) else {
+        bail!("No transcript found for session '{session_id}' (see `zeroclaw sessions list`)");
+    };
+
+    let mut entries = Vec::new();
+    for path in paths {
+        entries.extend(read_entries(&path)?);
+    }
+    entries.sort_by_key(|entry| entry.timestamp);
+    Ok(Transcript {
+        session_id: session_id.to_string(),
+        entries,
+    })
+}
+
+/// Every transcript entry of every session, tagged with its session id.
+pub fn all_entries(workspace_dir: &Path) -> Result<Vec<(String, TranscriptEntry)>> {
+    let mut all = Vec::new();
+    for (id, paths) in transcript_files(workspace_dir)? {
+        for path in paths {
+            all.extend(read_entries(&path)?.into_iter().map(|e| (id.clone(), e)));
+        }
+    }
+    Ok(all)
+}
+
+/// What [`search`] looks for in recorded turns.
+#[derive(Debug, Default)]
+pub struct EpisodeQuery<'a> {
+    /// Words that must all appear, ignoring case; empty matches every entry
+    pub text: &'a str,
+    /// Only this session; `None` searches all of them
+    pub session_id: Option<&'a str>,
+    /// Only entries with this role, "user" or "assistant"
+    pub role: Option<&'a str>,
+    pub range: TimeRange,
+}
+
+/// The most recent `limit` entries matching `query`, oldest first, each
+/// tagged with its session id.
+pub fn search(
+    workspace_dir: &Path,
+    query: &EpisodeQuery<'_>,
+    limit: usize,
+) -> Result<Vec<(String, TranscriptEntry)>> {
+    let words: Vec<String> = query
+        .text
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let wanted = query.session_id;
+    // Files are named by the UTC day their turns were recorded on
+    let first_day = query.range.after.map(|at| at.date_naive());
+    let last_day = query.range.before.map(|at| at.date_naive());
+
+    let mut found = Vec::new();
+    for (id, paths) in transcript_files(workspace_dir)? {
+        if wanted.is_some_and(|wanted| wanted != id) {
+            continue;
+        }
+        for path in paths {
+            let day = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.get(..DATE_PREFIX_LEN - 1))
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+            if let Some(day) = day {
+                if first_day.is_some_and(|first| day < first)
+                    || last_day.is_some_and(|last| day > last)
+                {
+                    continue;
+                }
+            }
+            for entry in read_entries(&path)? {
+                if query.role.is_some_and(|role| entry.role != role)
+                    || query
+                        .range
+                        .after
+                        .is_some_and(|after| entry.timestamp < after)
+                    || query
+                        .range
+                        .before
+                        .is_some_and(|before| entry.timestamp >= before)
+                {
+                    continue;
+                }
+                let content = entry.content.to_lowercase();
+                if words.iter().all(|word| content.contains(word.as_str())) {
+                    found.push((id.clone(), entry));
+                }
+            }
+        }
+    }
+    found.sort_by_key(|(_, entry)| entry.timestamp);
+    let skip = found.len().saturating_sub(limit);
+    Ok(found.split_off(skip))
+}
+
+/// All recorded sessions, most recently active first.
+pub fn list(workspace_dir: &Path) -> Result<Vec<SessionSummary>> {
+    let mut sessions = Vec::new();
+    for (id, paths) in transcript_files(workspace_dir)? {
+        let mut messages = 0;
+        let mut last_active = DateTime::<Utc>::MIN_UTC;
+        for path in &paths {
+            for entry in read_entries(path)? {
+                messages += 1;
+                last_active = last_active.max(entry.timestamp);
+            }
+        }
+        sessions.push(SessionSummary {
+            id,
+            messages,
+            last_active,
+        });
+    }
+    sessions.sort_by(|a, b| b.last_active.cmp(&a.last_active));
+    Ok(sessions)
+}
+
+pub fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::SessionCommands::List => {
+            let sessions = list(&config.workspace_dir)?;
+            if sessions.is_empty() {
+                println!("No session transcripts yet.");
+                if !config.sessions.record_transcripts {
+                    println!("Recording is off: set [sessions] record_transcripts = true");
+                }
+                return Ok(());
+            }
+
+            println!("💬 Sessions ({}):", sessions.len());
+            for session in sessions {
+                println!(
+                    "- {} | {} messages | last active {}",
+                    session.id,
+                    session.messages,
+                    session.last_active.format("%Y-%m-%d %H:%M UTC")
+                );
+            }
+            Ok(())
+        }
+        crate::SessionCommands::Export { id, format, output } => {
+            let format: ExportFormat = format.parse()?;
+            let transcript = load(&config.workspace_dir, &id)?;
+            let rendered = render(&transcript, format)?;
+            match output {
+                Some(path) => {
+                    fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("✅ Exported session {id} to {}", path.display());
+                }
+                None => print!("{rendered}"),
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn recorded_turns_load_and_list_by_session() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        let call = ToolCallRecord {
+            name: "shell".into(),
+            arguments: serde_json::json!({"command": "ls"}),
+            result: "README.md".into(),
+            success: true,
+            duration_ms: 3,
+        };
+
+        record_turn(
+            workspace,
+            "telegram_+1 555",
+            "what's here?",
+            "A README.",
+            &[call],
+        )
+        .unwrap();
+        record_turn(workspace, "telegram_+1 555", "thanks", "Anytime.", &[]).unwrap();
+        record_turn(workspace, "cli-other", "hi", "hello", &[]).unwrap();
+
+        let transcript = load(workspace, "telegram_+1 555").unwrap();
+        assert_eq!(transcript.session_id, "telegram_+1 555");
+        assert_eq!(transcript.entries.len(), 4);
+        assert_eq!(transcript.entries[1].tool_calls[0].name, "shell");
+        assert_eq!(transcript.entries[3].content, "Anytime.");
+
+        let sessions = list(workspace).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions
+            .iter()
+            .any(|s| s.id == "telegram_+1 555" && s.messages == 4));
+        assert!(load(workspace, "missing").is_err());
+    }
+
+    #[test]
+    fn similar_session_ids_keep_separate_transcripts() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        for id in ["a b", "a_b", "a/b", "a%20b"] {
+            record_turn(workspace, id, id, "ok", &[]).unwrap();
+        }
+
+        for id in ["a b", "a_b", "a/b", "a%20b"] {
+            let transcript = load(workspace, id).unwrap();
+            assert_eq!(transcript.entries.len(), 2);
+            assert_eq!(transcript.entries[0].content, id);
+        }
+        assert_eq!(list(workspace).unwrap().len(), 4);
+
+        // Files named before ids were encoded still load
+        let day = Utc::now().format("%Y-%m-%d");
+        fs::copy(
+            sessions_dir(workspace).join(format!("{day}-a_b.jsonl")),
+            sessions_dir(workspace).join(format!("{day}-signal__1_555.jsonl")),
+        )
+        .unwrap();
+        assert_eq!(load(workspace, "signal__1_555").unwrap().entries.len(), 2);
+    }
+
+    #[test]
+    fn search_filters_by_words_role_session_and_time() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+        record_turn(
+            workspace,
+            "telegram_alice",
+            "Book the Lisbon flight",
+            "Booked.",
+            &[],
+        )
+        .unwrap();
+        record_turn(
+            workspace,
+            "telegram_alice",
+            "What about hotels?",
+            "Three options in Lisbon.",
+            &[],
+        )
+        .unwrap();
+        record_turn(workspace, "telegram_bob", "lisbon weather", "Sunny.", &[]).unwrap();
+
+        let lisbon = EpisodeQuery {
+            text: "LISBON",
+            ..EpisodeQuery::default()
+        };
+        assert_eq!(search(workspace, &lisbon, 10).unwrap().len(), 3);
+
+        let alice_said = EpisodeQuery {
+            session_id: Some("telegram_alice"),
+            role: Some("user"),
+            ..lisbon
+        };
+        let found = search(workspace, &alice_said, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.content, "Book the Lisbon flight");
+
+        let everything = EpisodeQuery::default();
+        let latest = search(workspace, &everything, 2).unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].0, "telegram_bob");
+        assert_eq!(latest[1].1.content, "Sunny.");
+
+        let future = EpisodeQuery {
+            range: TimeRange {
+                after: Some(Utc::now() + chrono::Duration::hours(1)),
+                before: None,
+            },
+            ..EpisodeQuery::default()
+        };
+        assert!(search(workspace, &future, 10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn current_session_is_scoped_to_the_turn() {
+        assert_eq!(current_session_id(), None);
+        let inside = scope("cli-1".into(), async { current_session_id() }).await;
+        assert_eq!(inside.as_deref(), Some("cli-1"));
+    }
+}
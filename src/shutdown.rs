@@ -0,0 +1,122 @@
+//! Graceful shutdown for the long-running servers (daemon, gateway, channels).
+//!
+//! The first SIGINT/SIGTERM triggers the process-wide [`Shutdown`]: servers
+//! stop accepting new work, let in-flight turns finish until
+//! `shutdown.drain_timeout_secs`, flush state, and close their transports.
+//! A second signal exits immediately.
+
+use std::sync::{Arc, LazyLock, Once};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Exit code used when a second signal forces an immediate exit.
+const FORCED_EXIT_CODE: i32 = 130;
+
+/// A one-way "stop accepting work" flag that tasks can wait on.
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolves once [`trigger`](Self::trigger) has been called.
+    pub async fn triggered(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives as long as `self`, so this cannot fail.
+        let _ = rx.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Resolves `drain` after the trigger: the point where in-flight work is
+    /// abandoned.
+    pub async fn deadline(&self, drain: Duration) {
+        self.triggered().await;
+        tokio::time::sleep(drain).await;
+    }
+}
+
+static GLOBAL: LazyLock<Shutdown> = LazyLock::new(Shutdown::new);
+static SIGNAL_HANDLER: Once = Once::new();
+
+/// The process-wide shutdown flag, triggered by SIGINT/SIGTERM once
+/// [`install_signal_handler`] has run.
+pub fn global() -> &'static Shutdown {
+    &GLOBAL
+}
+
+/// Route SIGINT/SIGTERM to [`global`] instead of killing the process.
+/// Idempotent; must be called from within the Tokio runtime.
+pub fn install_signal_handler() {
+    SIGNAL_HANDLER.call_once(|| {
+        tokio::spawn(async {
+            signal().await;
+            tracing::info!("Shutdown requested; draining in-flight work (signal again to force)");
+            eprintln!("\n⏳ Shutting down — finishing in-flight work (Ctrl+C again to force)");
+            global().trigger();
+
+            signal().await;
+            eprintln!("⚠️  Forced exit");
+            std::process::exit(FORCED_EXIT_CODE);
+        });
+    });
+}
+
+#[cfg(unix)]
+async fn signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+    match signal(SignalKind::terminate()) {
+        Ok(mut term) => {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = term.recv() => {}
+            }
+        }
+        Err(_) => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn triggered_resolves_for_waiters_and_late_subscribers() {
+        let shutdown = Shutdown::new();
+        assert!(!shutdown.is_triggered());
+
+        let waiter = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.triggered().await }
+        });
+        shutdown.trigger();
+
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter should wake")
+            .unwrap();
+        assert!(shutdown.is_triggered());
+        tokio::time::timeout(Duration::from_secs(1), shutdown.triggered())
+            .await
+            .expect("already triggered");
+    }
+}
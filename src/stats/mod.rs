@@ -0,0 +1,428 @@
+//! `zeroclaw stats`: a usage dashboard over data zeroclaw already keeps.
+//!
+//! - **messages** — inbound messages per channel per day, from session
+//!   transcripts (`sessions.record_transcripts`).
+//! - **tokens and cost** — from `state/costs.jsonl`.
+//! - **tools** — most-used tools and their failure counts, from the tool
+//!   calls recorded in transcripts.
+//! - **memory** — entries added per day and the running total.
+//! - **MCP** — calls and error rate per server (tools named `mcp__<server>__*`).
+//!
+//! Printed as tables with sparklines, or as JSON with `--json`.
+
+use crate::config::Config;
+use crate::cost::CostRecord;
+use crate::sessions::TranscriptEntry;
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Tools listed in the "top tools" table.
+const TOP_TOOLS: usize = 10;
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TokenSeries {
+    pub input_tokens: Vec<u64>,
+    pub output_tokens: Vec<u64>,
+    pub cost_usd: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModelUsage {
+    pub requests: u64,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ToolUsage {
+    pub name: String,
+    pub calls: u64,
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MemoryGrowth {
+    pub total: u64,
+    pub added: Vec<u64>,
+    /// Entry count at the end of each day.
+    pub cumulative: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct McpUsage {
+    pub server: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+}
+
+/// Everything `zeroclaw stats` shows; every per-day series lines up with `days`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsReport {
+    pub days: Vec<NaiveDate>,
+    pub messages: BTreeMap<String, Vec<u64>>,
+    pub tokens: TokenSeries,
+    pub models: BTreeMap<String, ModelUsage>,
+    pub tools: Vec<ToolUsage>,
+    pub memory: MemoryGrowth,
+    pub mcp: Vec<McpUsage>,
+}
+
+/// Channel a session id belongs to: channel sessions are `<channel>_<sender>`,
+/// CLI sessions are bare UUIDs.
+fn channel_of(session_id: &str) -> &str {
+    session_id
+        .split_once('_')
+        .map_or("cli", |(channel, _)| channel)
+}
+
+/// Date part of a memory timestamp (RFC 3339 or `YYYY-MM-DD ...`).
+fn date_of(timestamp: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(timestamp.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Build the report for the `window` days ending `today`.
+pub fn aggregate(
+    today: NaiveDate,
+    window: u32,
+    transcripts: &[(String, TranscriptEntry)],
+    costs: &[CostRecord],
+    memory_timestamps: &[String],
+) -> StatsReport {
+    let days: Vec<NaiveDate> = (0..window.max(1))
+        .rev()
+        .filter_map(|back| today.checked_sub_days(chrono::Days::new(u64::from(back))))
+        .collect();
+    let first = days[0];
+    let slot = |date: NaiveDate| days.iter().position(|d| *d == date);
+
+    let mut messages: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    let mut tools: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    let mut mcp: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for (session_id, entry) in transcripts {
+        let Some(idx) = slot(entry.timestamp.with_timezone(&Local).date_naive()) else {
+            continue;
+        };
+        if entry.role == "user" {
+            messages
+                .entry(channel_of(session_id).to_string())
+                .or_insert_with(|| vec![0; days.len()])[idx] += 1;
+        }
+        for call in &entry.tool_calls {
+            let counts = tools.entry(call.name.clone()).or_default();
+            counts.0 += 1;
+            counts.1 += u64::from(!call.success);
+            if let Some((server, _)) = crate::mcp::names::origin(&call.name) {
+                let counts = mcp.entry(server).or_default();
+                counts.0 += 1;
+                counts.1 += u64::from(!call.success);
+            }
+        }
+    }
+
+    let mut tokens = TokenSeries {
+        input_tokens: vec![0; days.len()],
+        output_tokens: vec![0; days.len()],
+        cost_usd: vec![0.0; days.len()],
+    };
+    let mut models: BTreeMap<String, ModelUsage> = BTreeMap::new();
+    for record in costs {
+        let usage = &record.usage;
+        let Some(idx) = slot(usage.timestamp.with_timezone(&Local).date_naive()) else {
+            continue;
+        };
+        tokens.input_tokens[idx] += usage.input_tokens;
+        tokens.output_tokens[idx] += usage.output_tokens;
+        tokens.cost_usd[idx] += usage.cost_usd;
+        let model = models.entry(usage.model.clone()).or_default();
+        model.requests += 1;
+        model.total_tokens += usage.total_tokens;
+        model.cost_usd += usage.cost_usd;
+    }
+
+    let mut memory = MemoryGrowth {
+        total: memory_timestamps.len() as u64,
+        added: vec![0; days.len()],
+        cumulative: vec![0; days.len()],
+    };
+    let mut before_window = 0;
+    for date in memory_timestamps.iter().filter_map(|ts| date_of(ts)) {
+        match slot(date) {
+            Some(idx) => memory.added[idx] += 1,
+            None if date < first => before_window += 1,
+            None => {}
+        }
+    }
+    let mut running = before_window;
+    for (idx, added) in memory.added.iter().enumerate() {
+        running += added;
+        memory.cumulative[idx] = running;
+    }
+
+    let mut tools: Vec<ToolUsage> = tools
+        .into_iter()
+        .map(|(name, (calls, errors))| ToolUsage {
+            name,
+            calls,
+            errors,
+        })
+        .collect();
+    tools.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+    tools.truncate(TOP_TOOLS);
+
+    #[allow(clippy::cast_precision_loss)]
+    let mcp = mcp
+        .into_iter()
+        .map(|(server, (calls, errors))| McpUsage {
+            server,
+            calls,
+            errors,
+            error_rate: errors as f64 / calls as f64,
+        })
+        .collect();
+
+    StatsReport {
+        days,
+        messages,
+        tokens,
+        models,
+        tools,
+        memory,
+        mcp,
+    }
+}
+
+/// One bar per value, scaled to the largest.
+pub fn sparkline(values: &[f64]) -> String {
+    let max = values.iter().copied().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            if max <= 0.0 || *v <= 0.0 {
+                ' '
+            } else {
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let level = ((v / max) * (SPARK_BARS.len() - 1) as f64).round() as usize;
+                SPARK_BARS[level.min(SPARK_BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn spark_u64(values: &[u64]) -> String {
+    sparkline(&values.iter().map(|v| *v as f64).collect::<Vec<_>>())
+}
+
+fn print_report(report: &StatsReport) {
+    let (Some(first), Some(last)) = (report.days.first(), report.days.last()) else {
+        return;
+    };
+    println!("📊 Usage {first} → {last} ({} days)", report.days.len());
+
+    println!();
+    println!("Messages per channel");
+    if report.messages.is_empty() {
+        println!("  (none — enable [sessions] record_transcripts)");
+    }
+    for (channel, per_day) in &report.messages {
+        let total: u64 = per_day.iter().sum();
+        println!("  {channel:<14} {total:>7}  {}", spark_u64(per_day));
+    }
+
+    println!();
+    println!("Tokens and cost");
+    let input: u64 = report.tokens.input_tokens.iter().sum();
+    let output: u64 = report.tokens.output_tokens.iter().sum();
+    let cost: f64 = report.tokens.cost_usd.iter().sum();
+    println!(
+        "  input  {input:>12}  {}",
+        spark_u64(&report.tokens.input_tokens)
+    );
+    println!(
+        "  output {output:>12}  {}",
+        spark_u64(&report.tokens.output_tokens)
+    );
+    println!(
+        "  cost   {:>12}  {}",
+        format!("${cost:.2}"),
+        sparkline(&report.tokens.cost_usd)
+    );
+    for (model, usage) in &report.models {
+        println!(
+            "  {model:<40} {:>6} req {:>10} tok  ${:.2}",
+            usage.requests, usage.total_tokens, usage.cost_usd
+        );
+    }
+
+    println!();
+    println!("Top tools");
+    if report.tools.is_empty() {
+        println!("  (no tool calls recorded)");
+    }
+    for tool in &report.tools {
+        println!(
+            "  {:<40} {:>6} calls {:>4} failed",
+            tool.name, tool.calls, tool.errors
+        );
+    }
+
+    println!();
+    println!("Memory");
+    let added: u64 = report.memory.added.iter().sum();
+    println!(
+        "  {} entries, +{added} in period  {}",
+        report.memory.total,
+        spark_u64(&report.memory.cumulative)
+    );
+
+    if !report.mcp.is_empty() {
+        println!();
+        println!("MCP servers");
+        for server in &report.mcp {
+            println!(
+                "  {:<24} {:>6} calls {:>4} errors ({:.1}%)",
+                server.server,
+                server.calls,
+                server.errors,
+                server.error_rate * 100.0
+            );
+        }
+    }
+}
+
+pub async fn handle_command(config: &Config, days: u32, json: bool) -> Result<()> {
+    let transcripts = crate::sessions::all_entries(&config.workspace_dir)?;
+    let costs = crate::cost::load_records(&config.workspace_dir)?;
+    let memory_timestamps = match crate::memory::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+    ) {
+        Ok(memory) => memory
+            .list(None, None)
+            .await?
+            .into_iter()
+            .map(|entry| entry.timestamp)
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Memory unavailable for stats: {e}");
+            Vec::new()
+        }
+    };
+
+    let report = aggregate(
+        Local::now().date_naive(),
+        days,
+        &transcripts,
+        &costs,
+        &memory_timestamps,
+    );
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::loop_::ToolCallRecord;
+    use crate::cost::TokenUsage;
+    use chrono::{TimeZone, Utc};
+
+    fn at(date: NaiveDate, hour: u32) -> chrono::DateTime<Utc> {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn entry(date: NaiveDate, role: &str, tools: &[(&str, bool)]) -> TranscriptEntry {
+        TranscriptEntry {
+            timestamp: at(date, 12),
+            role: role.into(),
+            content: String::new(),
+            tool_calls: tools
+                .iter()
+                .map(|(name, success)| ToolCallRecord {
+                    name: (*name).into(),
+                    arguments: serde_json::json!({}),
+                    result: String::new(),
+                    success: *success,
+                    duration_ms: 1,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn aggregates_messages_tools_and_mcp_by_day() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let old = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let transcripts = vec![
+            ("telegram_42".to_string(), entry(today, "user", &[])),
+            (
+                "telegram_42".to_string(),
+                entry(
+                    today,
+                    "assistant",
+                    &[("shell", true), ("mcp__github__search", false)],
+                ),
+            ),
+            ("telegram_7".to_string(), entry(yesterday, "user", &[])),
+            (
+                "0b6c-uuid".to_string(),
+                entry(yesterday, "assistant", &[("mcp__github__search", true)]),
+            ),
+            ("0b6c-uuid".to_string(), entry(yesterday, "user", &[])),
+            ("telegram_42".to_string(), entry(old, "user", &[])),
+        ];
+
+        let report = aggregate(today, 3, &transcripts, &[], &[]);
+        assert_eq!(report.days.len(), 3);
+        assert_eq!(report.messages["telegram"], vec![0, 1, 1]);
+        assert_eq!(report.messages["cli"], vec![0, 1, 0]);
+        assert_eq!(report.tools[0].name, "mcp__github__search");
+        assert_eq!(report.tools[0].calls, 2);
+        assert_eq!(report.tools[0].errors, 1);
+        assert_eq!(report.mcp.len(), 1);
+        assert!((report.mcp[0].error_rate - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn aggregates_costs_and_memory_growth() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let mut usage = TokenUsage::new("gpt-test", 1000, 500, 1.0, 2.0);
+        usage.timestamp = at(today, 9);
+        let costs = vec![CostRecord {
+            id: "1".into(),
+            usage,
+            session_id: "s".into(),
+        }];
+        let memory = vec![
+            "2025-12-01T10:00:00+00:00".to_string(),
+            "2026-03-09T10:00:00+00:00".to_string(),
+            "2026-03-10 08:00:00".to_string(),
+        ];
+
+        let report = aggregate(today, 2, &[], &costs, &memory);
+        assert_eq!(report.tokens.input_tokens, vec![0, 1000]);
+        assert_eq!(report.models["gpt-test"].requests, 1);
+        assert_eq!(report.memory.total, 3);
+        assert_eq!(report.memory.added, vec![1, 1]);
+        assert_eq!(report.memory.cumulative, vec![2, 3]);
+    }
+
+    #[test]
+    fn sparkline_scales_to_max() {
+        assert_eq!(sparkline(&[0.0, 1.0, 2.0, 4.0]), " ▃▅█");
+        assert_eq!(sparkline(&[0.0, 0.0]), "  ");
+    }
+}
@@ -0,0 +1,235 @@
+use super::traits::{Tool, ToolResult};
+use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
+use crate::config::DelegateAgentConfig;
+use crate::observability::NoopObserver;
+use crate::providers::{self, ChatMessage, Provider};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default timeout for a full sub-agent run (all LLM calls and tool executions).
+const AGENT_TOOL_TIMEOUT_SECS: u64 = 300;
+
+/// Prefix for the tool names of agents exposed via `expose_as_tool`.
+pub const AGENT_TOOL_PREFIX: &str = "agent_";
+
+/// A configured sub-agent exposed as a single tool. Unlike `delegate`, which
+/// sends one prompt, invoking this tool runs a full agent loop with the
+/// sub-agent's own provider, model, system prompt and tool allowlist.
+pub struct AgentTool {
+    name: String,
+    agent_name: String,
+    description: String,
+    config: DelegateAgentConfig,
+    /// Tools the sub-agent may call (never includes agent tools or `delegate`).
+    tools: Arc<Vec<Box<dyn Tool>>>,
+    fallback_credential: Option<String>,
+}
+
+impl AgentTool {
+    pub fn new(
+        agent_name: &str,
+        config: DelegateAgentConfig,
+        tools: Vec<Box<dyn Tool>>,
+        fallback_credential: Option<String>,
+    ) -> Self {
+        let description = config.description.clone().unwrap_or_else(|| {
+            format!(
+                "Run the '{agent_name}' agent ({}/{}) on a task. It works autonomously \
+                 with its own tools and returns its final answer.",
+                config.provider, config.model
+            )
+        });
+        Self {
+            name: format!("{AGENT_TOOL_PREFIX}{agent_name}"),
+            agent_name: agent_name.to_string(),
+            description,
+            config,
+            tools: Arc::new(tools),
+            fallback_credential,
+        }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for AgentTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "task": {
+                    "type": "string",
+                    "minLength": 1,
+                    "description": "The task for the agent to complete"
+                },
+                "context": {
+                    "type": "string",
+                    "description": "Optional context to prepend (e.g. relevant code, prior findings)"
+                }
+            },
+            "required": ["task"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let task = args
+            .get("task")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'task' parameter"))?;
+        if task.is_empty() {
+            return Ok(Self::failure("'task' parameter must not be empty".into()));
+        }
+        let context = args
+            .get("context")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or("");
+
+        let credential = self
+            .config
+            .api_key
+            .as_deref()
+            .or(self.fallback_credential.as_deref());
+        let provider: Box<dyn Provider> =
+            match providers::create_provider(&self.config.provider, credential) {
+                Ok(p) => p,
+                Err(e) => {
+                    return Ok(Self::failure(format!(
+                        "Failed to create provider '{}' for agent '{}': {e}",
+                        self.config.provider, self.agent_name
+                    )));
+                }
+            };
+
+        let mut system_prompt = self.config.system_prompt.clone().unwrap_or_default();
+        if !self.tools.is_empty() {
+            system_prompt.push_str(&build_tool_instructions(&self.tools));
+        }
+        let user_message = if context.is_empty() {
+            task.to_string()
+        } else {
+            format!("[Context]\n{context}\n\n[Task]\n{task}")
+        };
+        let mut history = vec![
+            ChatMessage::system(system_prompt),
+            ChatMessage::user(user_message),
+        ];
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(AGENT_TOOL_TIMEOUT_SECS),
+            run_tool_call_loop(
+                provider.as_ref(),
+                &mut history,
+                &self.tools,
+                &NoopObserver,
+                &self.config.provider,
+                &self.config.model,
+                self.config.temperature.unwrap_or(0.7),
+                true,
+                None,
+                "agent_tool",
+                None,
+            ),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(response)) => {
+                let rendered = if response.trim().is_empty() {
+                    "[Empty response]".to_string()
+                } else {
+                    response
+                };
+                Ok(ToolResult {
+                    success: true,
+                    output: format!("[Agent '{}']\n{rendered}", self.agent_name),
+                    error: None,
+                    attachments: Vec::new(),
+                })
+            }
+            Ok(Err(e)) => Ok(Self::failure(format!(
+                "Agent '{}' failed: {e}",
+                self.agent_name
+            ))),
+            Err(_elapsed) => Ok(Self::failure(format!(
+                "Agent '{}' timed out after {AGENT_TOOL_TIMEOUT_SECS}s",
+                self.agent_name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn researcher() -> DelegateAgentConfig {
+        DelegateAgentConfig {
+            provider: "ollama".to_string(),
+            model: "llama3".to_string(),
+            system_prompt: Some("You research things.".to_string()),
+            api_key: None,
+            temperature: None,
+            max_depth: 3,
+            expose_as_tool: true,
+            description: None,
+            tools: vec![],
+            policy: None,
+        }
+    }
+
+    #[test]
+    fn name_and_schema() {
+        let tool = AgentTool::new("researcher", researcher(), vec![], None);
+        assert_eq!(tool.name(), "agent_researcher");
+        assert!(tool.description().contains("ollama/llama3"));
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["required"], json!(["task"]));
+    }
+
+    #[test]
+    fn custom_description_is_used() {
+        let mut config = researcher();
+        config.description = Some("Deep web research".into());
+        let tool = AgentTool::new("researcher", config, vec![], None);
+        assert_eq!(tool.description(), "Deep web research");
+    }
+
+    #[tokio::test]
+    async fn empty_task_is_rejected() {
+        let tool = AgentTool::new("researcher", researcher(), vec![], None);
+        let result = tool.execute(json!({"task": "  "})).await.unwrap();
+        assert!(!result.success);
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_provider_fails_gracefully() {
+        let mut config = researcher();
+        config.provider = "totally-invalid-provider".into();
+        let tool = AgentTool::new("researcher", config, vec![], None);
+        let result = tool.execute(json!({"task": "hi"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Failed to create provider"));
+    }
+}
@@ -633,6 +633,7 @@ impl BrowserTool {
                 success: true,
                 output: serde_json::to_string_pretty(&output).unwrap_or_default(),
                 error: None,
+                attachments: Vec::new(),
             })
         }
 
@@ -780,6 +781,7 @@ impl BrowserTool {
                     success: true,
                     output,
                     error: None,
+                    attachments: Vec::new(),
                 });
             }
 
@@ -797,6 +799,7 @@ impl BrowserTool {
                 success: false,
                 output: String::new(),
                 error,
+                attachments: Vec::new(),
             });
         }
 
@@ -805,6 +808,7 @@ impl BrowserTool {
                 success: true,
                 output: body,
                 error: None,
+                attachments: Vec::new(),
             });
         }
 
@@ -815,6 +819,7 @@ impl BrowserTool {
                 "computer-use sidecar request failed with status {status}: {}",
                 body.trim()
             )),
+            attachments: Vec::new(),
         })
     }
 
@@ -843,12 +848,14 @@ impl BrowserTool {
                 success: true,
                 output,
                 error: None,
+                attachments: Vec::new(),
             })
         } else {
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: resp.error,
+                attachments: Vec::new(),
             })
         }
     }
@@ -990,6 +997,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -998,6 +1006,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -1008,6 +1017,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(error.to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -1023,6 +1033,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Unknown action: {action_str}")),
+                attachments: Vec::new(),
             });
         }
 
@@ -1035,6 +1046,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some(unavailable_action_for_backend_error(action_str, backend)),
+                attachments: Vec::new(),
             });
         }
 
@@ -1045,6 +1057,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(e.to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
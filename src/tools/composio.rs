@@ -470,12 +470,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            attachments: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to list actions: {e}")),
+                        attachments: Vec::new(),
                     }),
                 }
             }
@@ -504,12 +506,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            attachments: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Action execution failed: {e}")),
+                        attachments: Vec::new(),
                     }),
                 }
             }
@@ -533,12 +537,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output: format!("Open this URL to connect {target}:\n{url}"),
                             error: None,
+                            attachments: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to get connection URL: {e}")),
+                        attachments: Vec::new(),
                     }),
                 }
             }
@@ -549,6 +555,7 @@ impl Tool for ComposioTool {
                 error: Some(format!(
                     "Unknown action '{action}'. Use 'list', 'execute', or 'connect'."
                 )),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -0,0 +1,259 @@
+use super::memory_recall::parse_time_bound;
+use super::traits::{Tool, ToolEffect, ToolResult};
+use crate::config::Config;
+use crate::memory::namespace::{self, DEFAULT_NAMESPACE};
+use crate::memory::TimeRange;
+use crate::sessions::{self, EpisodeQuery};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Longest excerpt of one message in the results
+const MAX_EXCERPT_CHARS: usize = 500;
+/// Messages returned when the model does not ask for a count
+const DEFAULT_LIMIT: usize = 20;
+/// Most messages the model may ask for
+const MAX_LIMIT: usize = 50;
+
+/// Search the recorded conversations (episodic memory) for what was said
+/// and when; `memory_recall` searches distilled facts instead.
+pub struct ConversationSearchTool {
+    config: Arc<Config>,
+}
+
+impl ConversationSearchTool {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+
+    fn failure(error: impl Into<String>) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error.into()),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+fn excerpt(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    match flat.char_indices().nth(MAX_EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &flat[..end]),
+        None => flat,
+    }
+}
+
+#[async_trait]
+impl Tool for ConversationSearchTool {
+    fn name(&self) -> &str {
+        "conversation_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search past conversation turns for what exactly was said and when, e.g. what the user asked on a given day. Returns matching messages with timestamps, oldest first. For stored facts and preferences use memory_recall."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words that must all appear in a message (case-insensitive); omit to list every message in the time range"
+                },
+                "after": {
+                    "type": "string",
+                    "description": "Only messages at or after this: a date (\"2025-03-01\"), an RFC 3339 time, or a span back from now (\"7d\", \"12h\")"
+                },
+                "before": {
+                    "type": "string",
+                    "description": "Only messages before this, in the same formats as 'after'"
+                },
+                "role": {
+                    "type": "string",
+                    "enum": ["user", "assistant"],
+                    "description": "Only messages from the user or only your replies"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max messages to return, the most recent ones (default: 20, at most 50)"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.config.sessions.record_transcripts {
+            return Ok(Self::failure(
+                "conversations are not recorded (sessions.record_transcripts=false)",
+            ));
+        }
+
+        let mut range = TimeRange::default();
+        for (name, bound) in [("after", &mut range.after), ("before", &mut range.before)] {
+            if let Some(value) = args.get(name).and_then(|v| v.as_str()) {
+                match parse_time_bound(name, value) {
+                    Ok(at) => *bound = Some(at),
+                    Err(e) => return Ok(Self::failure(e.to_string())),
+                }
+            }
+        }
+        let role = match args.get("role").and_then(|v| v.as_str()) {
+            Some(role @ ("user" | "assistant")) => Some(role),
+            Some(other) => {
+                return Ok(Self::failure(format!(
+                    "Invalid 'role' '{other}': expected user or assistant"
+                )))
+            }
+            None => None,
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |v| (v as usize).clamp(1, MAX_LIMIT));
+
+        // The owner's own turns may search every conversation; anyone else
+        // only their own, so one channel member never sees another's words.
+        let session = sessions::current_session_id();
+        let all_sessions = namespace::current() == DEFAULT_NAMESPACE;
+        if !all_sessions && session.is_none() {
+            return Ok(Self::failure("no active session"));
+        }
+        let query = EpisodeQuery {
+            text: args.get("query").and_then(|v| v.as_str()).unwrap_or(""),
+            session_id: if all_sessions {
+                None
+            } else {
+                session.as_deref()
+            },
+            role,
+            range,
+        };
+
+        let found = match sessions::search(&self.config.workspace_dir, &query, limit) {
+            Ok(found) => found,
+            Err(e) => return Ok(Self::failure(format!("Conversation search failed: {e}"))),
+        };
+        if found.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No recorded messages match.".into(),
+                error: None,
+                attachments: Vec::new(),
+            });
+        }
+
+        let mut output = format!("Found {} messages:\n", found.len());
+        for (session_id, entry) in &found {
+            let origin = if all_sessions {
+                format!(" ({session_id})")
+            } else {
+                String::new()
+            };
+            let _ = writeln!(
+                output,
+                "- {} {}{origin}: {}",
+                entry.timestamp.format("%Y-%m-%d %H:%M UTC"),
+                entry.role,
+                excerpt(&entry.content)
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+
+    fn effect(&self) -> ToolEffect {
+        ToolEffect::ReadOnly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn channel_members_only_search_their_own_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let workspace = &config.workspace_dir;
+        sessions::record_turn(
+            workspace,
+            "discord_alice",
+            "my locker code is 4512",
+            "Noted.",
+            &[],
+        )
+        .unwrap();
+        sessions::record_turn(
+            workspace,
+            "discord_bob",
+            "what's my locker code?",
+            "Not sure.",
+            &[],
+        )
+        .unwrap();
+        let tool = ConversationSearchTool::new(Arc::new(config));
+
+        let bob = namespace::scope(
+            "discord:bob".into(),
+            sessions::scope(
+                "discord_bob".into(),
+                tool.execute(json!({"query": "locker", "role": "user"})),
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(bob.output.contains("Found 1 messages"));
+        assert!(bob.output.contains("what's my locker code?"));
+        assert!(!bob.output.contains("4512"));
+
+        let owner = tool
+            .execute(json!({"query": "locker", "after": "1d"}))
+            .await
+            .unwrap();
+        assert!(owner
+            .output
+            .contains("user (discord_alice): my locker code is 4512"));
+        assert!(owner.output.contains("Found 2 messages"));
+
+        let bad = tool.execute(json!({"role": "system"})).await.unwrap();
+        assert!(!bad.success);
+    }
+
+    #[tokio::test]
+    async fn limit_is_capped() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        for i in 0..MAX_LIMIT {
+            sessions::record_turn(
+                &config.workspace_dir,
+                "cli",
+                &format!("note {i}"),
+                "ok",
+                &[],
+            )
+            .unwrap();
+        }
+        let tool = ConversationSearchTool::new(Arc::new(config));
+
+        let result = tool.execute(json!({"limit": 100_000})).await.unwrap();
+        assert!(result
+            .output
+            .contains(&format!("Found {MAX_LIMIT} messages")));
+    }
+}
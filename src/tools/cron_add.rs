@@ -1,6 +1,8 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, DeliveryConfig, JobType, Schedule, SessionTarget};
+use crate::cron::{
+    self, CatchUp, CronJobPatch, DeliveryConfig, JobType, PipelineStep, Schedule, SessionTarget,
+};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -24,7 +26,7 @@ impl Tool for CronAddTool {
     }
 
     fn description(&self) -> &str {
-        "Create a scheduled cron job (shell or agent) with cron/at/every schedules"
+        "Create a scheduled cron job (shell, agent, or pipeline of shell/agent/tool steps) with cron/at/every schedules"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -36,9 +38,19 @@ impl Tool for CronAddTool {
                     "type": "object",
                     "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms}"
                 },
-                "job_type": { "type": "string", "enum": ["shell", "agent"] },
+                "job_type": { "type": "string", "enum": ["shell", "agent", "pipeline"] },
                 "command": { "type": "string" },
                 "prompt": { "type": "string" },
+                "steps": {
+                    "type": "array",
+                    "description": "Pipeline steps, run in order: {kind:'shell',command} | {kind:'agent',prompt,model?} | {kind:'tool',name,args}. '{{output}}' is replaced with the previous step's output",
+                    "items": { "type": "object" }
+                },
+                "catch_up": {
+                    "type": "string",
+                    "enum": ["skip", "once", "all"],
+                    "description": "Runs missed while offline: skip them, run once, or replay all (default from config)"
+                },
                 "session_target": { "type": "string", "enum": ["isolated", "main"] },
                 "model": { "type": "string" },
                 "delivery": { "type": "object" },
@@ -54,6 +66,7 @@ impl Tool for CronAddTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -65,6 +78,7 @@ impl Tool for CronAddTool {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Invalid schedule: {e}")),
+                        attachments: Vec::new(),
                     });
                 }
             },
@@ -73,6 +87,7 @@ impl Tool for CronAddTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'schedule' parameter".to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -85,15 +100,19 @@ impl Tool for CronAddTool {
         let job_type = match args.get("job_type").and_then(serde_json::Value::as_str) {
             Some("agent") => JobType::Agent,
             Some("shell") => JobType::Shell,
+            Some("pipeline") => JobType::Pipeline,
             Some(other) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Invalid job_type: {other}")),
+                    attachments: Vec::new(),
                 });
             }
             None => {
-                if args.get("prompt").is_some() {
+                if args.get("steps").is_some() {
+                    JobType::Pipeline
+                } else if args.get("prompt").is_some() {
                     JobType::Agent
                 } else {
                     JobType::Shell
@@ -101,6 +120,36 @@ impl Tool for CronAddTool {
             }
         };
 
+        let catch_up = match args.get("catch_up") {
+            Some(v) => match serde_json::from_value::<CatchUp>(v.clone()) {
+                Ok(catch_up) => Some(catch_up),
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Invalid catch_up: {e}")),
+                        attachments: Vec::new(),
+                    });
+                }
+            },
+            None => None,
+        };
+
+        let delivery = match args.get("delivery") {
+            Some(v) => match serde_json::from_value::<DeliveryConfig>(v.clone()) {
+                Ok(cfg) => Some(cfg),
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Invalid delivery config: {e}")),
+                        attachments: Vec::new(),
+                    });
+                }
+            },
+            None => None,
+        };
+
         let default_delete_after_run = matches!(schedule, Schedule::At { .. });
         let delete_after_run = args
             .get("delete_after_run")
@@ -116,6 +165,7 @@ impl Tool for CronAddTool {
                             success: false,
                             output: String::new(),
                             error: Some("Missing 'command' for shell job".to_string()),
+                            attachments: Vec::new(),
                         });
                     }
                 };
@@ -125,6 +175,7 @@ impl Tool for CronAddTool {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Command blocked by security policy: {command}")),
+                        attachments: Vec::new(),
                     });
                 }
 
@@ -138,6 +189,7 @@ impl Tool for CronAddTool {
                             success: false,
                             output: String::new(),
                             error: Some("Missing 'prompt' for agent job".to_string()),
+                            attachments: Vec::new(),
                         });
                     }
                 };
@@ -150,6 +202,7 @@ impl Tool for CronAddTool {
                                 success: false,
                                 output: String::new(),
                                 error: Some(format!("Invalid session_target: {e}")),
+                                attachments: Vec::new(),
                             });
                         }
                     },
@@ -161,33 +214,79 @@ impl Tool for CronAddTool {
                     .and_then(serde_json::Value::as_str)
                     .map(str::to_string);
 
-                let delivery = match args.get("delivery") {
-                    Some(v) => match serde_json::from_value::<DeliveryConfig>(v.clone()) {
-                        Ok(cfg) => Some(cfg),
-                        Err(e) => {
+                cron::add_agent_job(
+                    &self.config,
+                    name,
+                    schedule,
+                    prompt,
+                    session_target,
+                    model,
+                    delivery,
+                    delete_after_run,
+                )
+            }
+            JobType::Pipeline => {
+                let steps = match args
+                    .get("steps")
+                    .map(|v| serde_json::from_value::<Vec<PipelineStep>>(v.clone()))
+                {
+                    Some(Ok(steps)) if !steps.is_empty() => steps,
+                    Some(Err(e)) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(format!("Invalid steps: {e}")),
+                            attachments: Vec::new(),
+                        });
+                    }
+                    _ => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some("Missing 'steps' for pipeline job".to_string()),
+                            attachments: Vec::new(),
+                        });
+                    }
+                };
+
+                for step in &steps {
+                    if let PipelineStep::Shell { command } = step {
+                        if !self.security.is_command_allowed(command) {
                             return Ok(ToolResult {
                                 success: false,
                                 output: String::new(),
-                                error: Some(format!("Invalid delivery config: {e}")),
+                                error: Some(format!(
+                                    "Command blocked by security policy: {command}"
+                                )),
+                                attachments: Vec::new(),
                             });
                         }
-                    },
-                    None => None,
-                };
+                    }
+                }
 
-                cron::add_agent_job(
+                cron::add_pipeline_job(
                     &self.config,
                     name,
                     schedule,
-                    prompt,
-                    session_target,
-                    model,
+                    steps,
                     delivery,
                     delete_after_run,
                 )
             }
         };
 
+        let result = match (result, catch_up) {
+            (Ok(job), Some(catch_up)) => cron::update_job(
+                &self.config,
+                &job.id,
+                CronJobPatch {
+                    catch_up: Some(catch_up),
+                    ..CronJobPatch::default()
+                },
+            ),
+            (result, _) => result,
+        };
+
         match result {
             Ok(job) => Ok(ToolResult {
                 success: true,
@@ -200,11 +299,13 @@ impl Tool for CronAddTool {
                     "enabled": job.enabled
                 }))?,
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
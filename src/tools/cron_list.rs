@@ -39,6 +39,7 @@ impl Tool for CronListTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -47,11 +48,13 @@ impl Tool for CronListTool {
                 success: true,
                 output: serde_json::to_string_pretty(&jobs)?,
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -41,6 +41,7 @@ impl Tool for CronRemoveTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -51,6 +52,7 @@ impl Tool for CronRemoveTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'job_id' parameter".to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -60,11 +62,13 @@ impl Tool for CronRemoveTool {
                 success: true,
                 output: format!("Removed cron job {job_id}"),
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
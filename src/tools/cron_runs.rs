@@ -56,6 +56,7 @@ impl Tool for CronRunsTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -66,6 +67,7 @@ impl Tool for CronRunsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'job_id' parameter".to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -94,12 +96,14 @@ impl Tool for CronRunsTool {
                     success: true,
                     output: serde_json::to_string_pretty(&runs)?,
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
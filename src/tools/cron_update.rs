@@ -1,6 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, CronJobPatch};
+use crate::cron::{self, CronJobPatch, PipelineStep};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -44,6 +44,7 @@ impl Tool for CronUpdateTool {
                 success: false,
                 output: String::new(),
                 error: Some("cron is disabled by config (cron.enabled=false)".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -54,6 +55,7 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'job_id' parameter".to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -65,6 +67,7 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'patch' parameter".to_string()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -76,16 +79,22 @@ impl Tool for CronUpdateTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Invalid patch payload: {e}")),
+                    attachments: Vec::new(),
                 });
             }
         };
 
-        if let Some(command) = &patch.command {
+        let step_commands = patch.steps.iter().flatten().filter_map(|step| match step {
+            PipelineStep::Shell { command } => Some(command),
+            _ => None,
+        });
+        for command in patch.command.iter().chain(step_commands) {
             if !self.security.is_command_allowed(command) {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Command blocked by security policy: {command}")),
+                    attachments: Vec::new(),
                 });
             }
         }
@@ -95,11 +104,13 @@ impl Tool for CronUpdateTool {
                 success: true,
                 output: serde_json::to_string_pretty(&job)?,
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(e.to_string()),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -106,6 +106,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'agent' parameter must not be empty".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -120,6 +121,7 @@ impl Tool for DelegateTool {
                 success: false,
                 output: String::new(),
                 error: Some("'prompt' parameter must not be empty".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -146,6 +148,7 @@ impl Tool for DelegateTool {
                             available.join(", ")
                         }
                     )),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -161,6 +164,7 @@ impl Tool for DelegateTool {
                     depth = self.depth,
                     max = agent_config.max_depth
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -183,6 +187,7 @@ impl Tool for DelegateTool {
                             "Failed to create provider '{}' for agent '{agent_name}': {e}",
                             agent_config.provider
                         )),
+                        attachments: Vec::new(),
                     });
                 }
             };
@@ -217,6 +222,7 @@ impl Tool for DelegateTool {
                     error: Some(format!(
                         "Agent '{agent_name}' timed out after {DELEGATE_TIMEOUT_SECS}s"
                     )),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -236,12 +242,14 @@ impl Tool for DelegateTool {
                         model = agent_config.model
                     ),
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Agent '{agent_name}' failed: {e}",)),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -262,6 +270,10 @@ mod tests {
                 api_key: None,
                 temperature: Some(0.3),
                 max_depth: 3,
+                expose_as_tool: false,
+                description: None,
+                tools: vec![],
+                policy: None,
             },
         );
         agents.insert(
@@ -273,6 +285,10 @@ mod tests {
                 api_key: Some("delegate-test-credential".to_string()),
                 temperature: None,
                 max_depth: 2,
+                expose_as_tool: false,
+                description: None,
+                tools: vec![],
+                policy: None,
             },
         );
         agents
@@ -380,6 +396,10 @@ mod tests {
                 api_key: None,
                 temperature: None,
                 max_depth: 3,
+                expose_as_tool: false,
+                description: None,
+                tools: vec![],
+                policy: None,
             },
         );
         let tool = DelegateTool::new(agents, None);
@@ -0,0 +1,157 @@
+use super::traits::{Tool, ToolEffect, ToolResult};
+use crate::config::Config;
+use crate::memory::documents::{self, DocumentStore};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+/// Search files ingested with `zeroclaw ingest` and cite where each passage
+/// comes from
+pub struct DocsSearchTool {
+    config: Arc<Config>,
+    store: OnceCell<DocumentStore>,
+}
+
+impl DocsSearchTool {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self {
+            config,
+            store: OnceCell::new(),
+        }
+    }
+
+    fn failure(error: impl Into<String>) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error.into()),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DocsSearchTool {
+    fn name(&self) -> &str {
+        "docs_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the user's ingested notes, documentation and code (added with `zeroclaw ingest`). Returns passages with their source file and line; cite them as [path:line] when you use them. For facts learned in conversation use memory_recall."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for, in words likely to appear in the documents"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max passages to return (default: 5)"
+                }
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| v as usize);
+
+        let store = match self
+            .store
+            .get_or_try_init(|| async { documents::open(&self.config) })
+            .await
+        {
+            Ok(store) => store,
+            Err(e) => return Ok(Self::failure(format!("Documents unavailable: {e}"))),
+        };
+        let hits = match store.search(query, limit).await {
+            Ok(hits) => hits,
+            Err(e) => return Ok(Self::failure(format!("Document search failed: {e}"))),
+        };
+        if hits.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No ingested documents match.".into(),
+                error: None,
+                attachments: Vec::new(),
+            });
+        }
+
+        let mut output = format!("Found {} passages:\n", hits.len());
+        for hit in &hits {
+            let heading = hit
+                .heading
+                .as_deref()
+                .map(|h| format!(" {}", h.trim_start_matches('#').trim()))
+                .unwrap_or_default();
+            let _ = writeln!(output, "\n[{}]{heading}\n{}", hit.citation(), hit.content);
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+
+    fn effect(&self) -> ToolEffect {
+        ToolEffect::ReadOnly
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn finds_ingested_passages_with_citations() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.memory.embedding_provider = "none".into();
+        let notes = tmp.path().join("wifi.md");
+        std::fs::write(
+            &notes,
+            "# Home network\n\nThe guest wifi password is on the fridge.\n",
+        )
+        .unwrap();
+        let store = documents::open(&config).unwrap();
+        documents::ingest(&store, &notes, 512, &|_| {})
+            .await
+            .unwrap();
+        let tool = DocsSearchTool::new(Arc::new(config));
+
+        let result = tool
+            .execute(json!({"query": "guest wifi password"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(
+            result.output.contains("wifi.md:1] Home network"),
+            "{}",
+            result.output
+        );
+        assert!(result.output.contains("on the fridge"));
+
+        let none = tool.execute(json!({"query": "kubernetes"})).await.unwrap();
+        assert!(none.output.contains("No ingested documents match"));
+    }
+}
@@ -51,6 +51,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -60,6 +61,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Path not allowed by security policy: {path}")),
+                attachments: Vec::new(),
             });
         }
 
@@ -71,6 +73,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -84,6 +87,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -96,6 +100,7 @@ impl Tool for FileReadTool {
                     "Resolved path escapes workspace: {}",
                     resolved_path.display()
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -110,6 +115,7 @@ impl Tool for FileReadTool {
                             "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
                             meta.len()
                         )),
+                        attachments: Vec::new(),
                     });
                 }
             }
@@ -118,6 +124,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to read file metadata: {e}")),
+                    attachments: Vec::new(),
                 });
             }
         }
@@ -127,11 +134,13 @@ impl Tool for FileReadTool {
                 success: true,
                 output: contents,
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to read file: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -66,11 +66,10 @@ impl GitOperationsTool {
     }
 
     async fn run_git_command(&self, args: &[&str]) -> anyhow::Result<String> {
-        let output = tokio::process::Command::new("git")
-            .args(args)
-            .current_dir(&self.workspace_dir)
-            .output()
-            .await?;
+        let mut cmd = tokio::process::Command::new("git");
+        cmd.args(args).current_dir(&self.workspace_dir);
+        crate::security::limits::apply(&mut cmd);
+        let output = cmd.output().await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -128,6 +127,7 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -207,6 +207,7 @@ impl GitOperationsTool {
             success: true,
             output: serde_json::to_string_pretty(&result).unwrap_or_default(),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -244,6 +245,7 @@ impl GitOperationsTool {
             output: serde_json::to_string_pretty(&json!({ "commits": commits }))
                 .unwrap_or_default(),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -276,6 +278,7 @@ impl GitOperationsTool {
             }))
             .unwrap_or_default(),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -315,11 +318,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Committed: {message}"),
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Commit failed: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -340,11 +345,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Staged: {paths}"),
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Add failed: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -376,11 +383,13 @@ impl GitOperationsTool {
                 success: true,
                 output: format!("Switched to branch: {branch_name}"),
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Checkout failed: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -413,11 +422,13 @@ impl GitOperationsTool {
                 success: true,
                 output: out,
                 error: None,
+                attachments: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Stash {action} failed: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -488,6 +499,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Missing 'operation' parameter".into()),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -510,6 +522,7 @@ impl Tool for GitOperationsTool {
                     success: false,
                     output: String::new(),
                     error: Some("Not in a git repository".into()),
+                    attachments: Vec::new(),
                 });
             }
         }
@@ -523,6 +536,7 @@ impl Tool for GitOperationsTool {
                     error: Some(
                         "Action blocked: git write operations require higher autonomy level".into(),
                     ),
+                    attachments: Vec::new(),
                 });
             }
 
@@ -532,6 +546,7 @@ impl Tool for GitOperationsTool {
                         success: false,
                         output: String::new(),
                         error: Some("Action blocked: read-only mode".into()),
+                        attachments: Vec::new(),
                     });
                 }
                 AutonomyLevel::Supervised | AutonomyLevel::Full => {}
@@ -544,6 +559,7 @@ impl Tool for GitOperationsTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -561,6 +577,7 @@ impl Tool for GitOperationsTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Unknown operation: {operation}")),
+                attachments: Vec::new(),
             }),
         }
     }
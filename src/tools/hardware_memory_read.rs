@@ -68,6 +68,7 @@ impl Tool for HardwareMemoryReadTool {
                     "No peripherals configured. Add nucleo-f401re to config.toml [peripherals.boards]."
                         .into(),
                 ),
+                attachments: Vec::new(),
             });
         }
 
@@ -87,6 +88,7 @@ impl Tool for HardwareMemoryReadTool {
                     "Memory read only supports nucleo-f401re, nucleo-f411re. Got: {}",
                     board
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -109,6 +111,7 @@ impl Tool for HardwareMemoryReadTool {
                         success: true,
                         output,
                         error: None,
+                        attachments: Vec::new(),
                     });
                 }
                 Err(e) => {
@@ -119,6 +122,7 @@ impl Tool for HardwareMemoryReadTool {
                             "probe-rs read failed: {}. Ensure Nucleo is connected via USB and built with --features probe.",
                             e
                         )),
+                        attachments: Vec::new(),
                     });
                 }
             }
@@ -133,6 +137,7 @@ impl Tool for HardwareMemoryReadTool {
                     "Memory read requires probe feature. Build with: cargo build --features hardware,probe"
                         .into(),
                 ),
+                attachments: Vec::new(),
             })
         }
     }
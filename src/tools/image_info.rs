@@ -166,6 +166,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Path not allowed: {path_str} (must be within workspace)"
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -174,6 +175,7 @@ impl Tool for ImageInfoTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("File not found: {path_str}")),
+                attachments: Vec::new(),
             });
         }
 
@@ -190,6 +192,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Image too large: {file_size} bytes (max {MAX_IMAGE_BYTES} bytes)"
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -224,6 +227,7 @@ impl Tool for ImageInfoTool {
             success: true,
             output,
             error: None,
+            attachments: Vec::new(),
         })
     }
 }
@@ -1,9 +1,14 @@
 use super::traits::{Tool, ToolResult};
+use crate::memory::namespace::{self, DEFAULT_NAMESPACE, SHARED_NAMESPACE};
 use crate::memory::Memory;
 use async_trait::async_trait;
 use serde_json::json;
+use std::fmt::Write;
 use std::sync::Arc;
 
+/// Matches a `query` lists before anything is forgotten
+const QUERY_PREVIEW: usize = 5;
+
 /// Let the agent forget/delete a memory entry
 pub struct MemoryForgetTool {
     memory: Arc<dyn Memory>,
@@ -22,7 +27,7 @@ impl Tool for MemoryForgetTool {
     }
 
     fn description(&self) -> &str {
-        "Remove a memory by key. Use to delete outdated facts or sensitive data. Returns whether the memory was found and removed."
+        "Remove a memory by key or id. Use to delete outdated facts or sensitive data. Without a key, pass a query to see matching memories first; add confirm: true to forget the top match."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -31,39 +36,116 @@ impl Tool for MemoryForgetTool {
             "properties": {
                 "key": {
                     "type": "string",
-                    "description": "The key of the memory to forget"
+                    "description": "The key or id of the memory to forget"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Find the memory to forget by search instead of key"
+                },
+                "confirm": {
+                    "type": "boolean",
+                    "description": "With query: forget the top match instead of only listing matches"
                 }
-            },
-            "required": ["key"]
+            }
         })
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let key = args
-            .get("key")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'key' parameter"))?;
+        let active = namespace::current();
+        // A query match may be shared, and is forgotten where it lives
+        let (key, elsewhere) = match (
+            args.get("key").and_then(|v| v.as_str()),
+            args.get("query").and_then(|v| v.as_str()),
+        ) {
+            (Some(key), _) => match crate::memory::lookup(self.memory.as_ref(), key).await {
+                Ok(Some(entry)) => (entry.key, None),
+                Ok(None) => (key.to_string(), None),
+                Err(e) => return Ok(failure(format!("Failed to forget memory: {e}"))),
+            },
+            (None, Some(query)) => {
+                let confirm = args
+                    .get("confirm")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                let mut matches = match self.memory.recall(query, QUERY_PREVIEW, None).await {
+                    Ok(matches) => matches,
+                    Err(e) => return Ok(failure(format!("Failed to search memories: {e}"))),
+                };
+                // Only the owner may remove memories everyone shares
+                if active != DEFAULT_NAMESPACE {
+                    matches.retain(|entry| entry.namespace.as_deref() != Some(SHARED_NAMESPACE));
+                }
+                let Some(top) = matches.first() else {
+                    return Ok(ToolResult {
+                        success: true,
+                        output: format!("No memories match: {query}"),
+                        error: None,
+                        attachments: Vec::new(),
+                    });
+                };
+                if !confirm {
+                    let mut output = format!("{} memories match '{query}':\n", matches.len());
+                    for entry in &matches {
+                        let shared = if entry.namespace.as_deref() == Some(SHARED_NAMESPACE) {
+                            ", shared"
+                        } else {
+                            ""
+                        };
+                        let _ = writeln!(
+                            output,
+                            "- {} (id {}{shared}): {}",
+                            entry.key, entry.id, entry.content
+                        );
+                    }
+                    let _ = write!(
+                        output,
+                        "Nothing was forgotten. Call memory_forget with a key, or with confirm: true to forget '{}'.",
+                        top.key
+                    );
+                    return Ok(ToolResult {
+                        success: true,
+                        output,
+                        error: None,
+                        attachments: Vec::new(),
+                    });
+                }
+                let elsewhere = top.namespace.clone().filter(|ns| *ns != active);
+                (top.key.clone(), elsewhere)
+            }
+            (None, None) => anyhow::bail!("Missing 'key' or 'query' parameter"),
+        };
 
-        match self.memory.forget(key).await {
+        let forgotten = match elsewhere {
+            Some(ns) => namespace::scope(ns, self.memory.forget(&key)).await,
+            None => self.memory.forget(&key).await,
+        };
+        match forgotten {
             Ok(true) => Ok(ToolResult {
                 success: true,
                 output: format!("Forgot memory: {key}"),
                 error: None,
+                attachments: Vec::new(),
             }),
             Ok(false) => Ok(ToolResult {
                 success: true,
                 output: format!("No memory found with key: {key}"),
                 error: None,
+                attachments: Vec::new(),
             }),
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Failed to forget memory: {e}")),
-            }),
+            Err(e) => Ok(failure(format!("Failed to forget memory: {e}"))),
         }
     }
 }
 
+fn failure(error: String) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error),
+        attachments: Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,6 +181,84 @@ mod tests {
         assert!(mem.get("temp").await.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn forget_by_id_and_by_confirmed_query() {
+        let (_tmp, mem) = test_mem();
+        for (key, content) in [
+            ("dog", "User's dog is named Biscuit"),
+            ("cat", "User's cat is named Miso"),
+        ] {
+            mem.store(key, content, MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let tool = MemoryForgetTool::new(mem.clone());
+
+        let id = mem.get("cat").await.unwrap().unwrap().id;
+        let result = tool.execute(json!({"key": id})).await.unwrap();
+        assert!(result.output.contains("Forgot memory: cat"));
+
+        let result = tool.execute(json!({"query": "Biscuit"})).await.unwrap();
+        assert!(result.output.contains("Nothing was forgotten"));
+        assert!(mem.get("dog").await.unwrap().is_some());
+
+        let result = tool
+            .execute(json!({"query": "Biscuit", "confirm": true}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Forgot memory: dog"));
+        assert_eq!(mem.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn confirmed_query_forgets_a_shared_match_where_it_lives() {
+        let (_tmp, mem) = test_mem();
+        let store = |ns: &str, content: &'static str| {
+            let mem = mem.clone();
+            namespace::scope(ns.to_string(), async move {
+                mem.store("rules", content, MemoryCategory::Core, None)
+                    .await
+                    .unwrap();
+            })
+        };
+        store("discord:alice", "No shoes indoors").await;
+        store(SHARED_NAMESPACE, "Server rules: be kind").await;
+        store(DEFAULT_NAMESPACE, "Owner's rules for the lab").await;
+        let tool = MemoryForgetTool::new(mem.clone());
+        let forget_kind = || tool.execute(json!({"query": "kind", "confirm": true}));
+
+        // Channel users cannot remove what everyone shares
+        let result = namespace::scope("discord:alice".into(), forget_kind())
+            .await
+            .unwrap();
+        assert!(
+            result.output.contains("No memories match"),
+            "{}",
+            result.output
+        );
+
+        let listed = tool.execute(json!({"query": "kind"})).await.unwrap();
+        assert!(listed.output.contains(", shared)"), "{}", listed.output);
+        let result = forget_kind().await.unwrap();
+        assert!(result.output.contains("Forgot memory: rules"));
+
+        let remaining = |ns: &str| {
+            let mem = mem.clone();
+            namespace::scope(ns.to_string(), async move {
+                mem.get("rules").await.unwrap().map(|entry| entry.content)
+            })
+        };
+        assert_eq!(remaining(SHARED_NAMESPACE).await, None);
+        assert_eq!(
+            remaining("discord:alice").await.as_deref(),
+            Some("No shoes indoors")
+        );
+        assert_eq!(
+            remaining(DEFAULT_NAMESPACE).await.as_deref(),
+            Some("Owner's rules for the lab")
+        );
+    }
+
     #[tokio::test]
     async fn forget_nonexistent() {
         let (_tmp, mem) = test_mem();
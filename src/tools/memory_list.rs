@@ -0,0 +1,181 @@
+use super::traits::{Tool, ToolEffect, ToolResult};
+use crate::memory::{ListOrder, Memory};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Entries per page when the model does not ask for a size
+const DEFAULT_LIMIT: usize = 20;
+/// Largest page the model may ask for
+const MAX_LIMIT: usize = 100;
+
+/// Let the agent page through everything in its memory
+pub struct MemoryListTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemoryListTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryListTool {
+    fn name(&self) -> &str {
+        "memory_list"
+    }
+
+    fn description(&self) -> &str {
+        "List all stored memories page by page, newest first. Use to browse or review memory; use memory_recall to search it."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "offset": {
+                    "type": "integer",
+                    "description": "Entries to skip (default: 0)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Entries per page (default: 20, max: 100)"
+                },
+                "order": {
+                    "type": "string",
+                    "enum": ["newest", "oldest"],
+                    "description": "Which entries come first (default: newest)"
+                }
+            }
+        })
+    }
+
+    fn effect(&self) -> ToolEffect {
+        ToolEffect::ReadOnly
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = args
+            .get("offset")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(0, |v| v as usize);
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |v| (v as usize).clamp(1, MAX_LIMIT));
+        let order: ListOrder = match args.get("order").and_then(|v| v.as_str()) {
+            Some(order) => order.parse()?,
+            None => ListOrder::default(),
+        };
+
+        let page = match self.memory.list_page(offset, limit, order).await {
+            Ok(page) => page,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Memory list failed: {e}")),
+                    attachments: Vec::new(),
+                })
+            }
+        };
+        let total = self.memory.count().await.unwrap_or(0);
+        if page.is_empty() {
+            let output = if total == 0 {
+                "No memories stored yet.".to_string()
+            } else {
+                format!("No memories past offset {offset} ({total} stored).")
+            };
+            return Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+                attachments: Vec::new(),
+            });
+        }
+
+        let end = offset + page.len();
+        let mut output = format!("Memories {}-{end} of {total}:\n", offset + 1);
+        for entry in &page {
+            let _ = writeln!(
+                output,
+                "- [{}] {} ({}): {}",
+                entry.category, entry.key, entry.timestamp, entry.content
+            );
+        }
+        if end < total {
+            let _ = write!(output, "More: call memory_list with offset {end}.");
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        (tmp, Arc::new(mem))
+    }
+
+    #[tokio::test]
+    async fn list_empty() {
+        let (_tmp, mem) = seeded_mem();
+        let tool = MemoryListTool::new(mem);
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No memories stored"));
+    }
+
+    #[tokio::test]
+    async fn list_pages_newest_first() {
+        let (_tmp, mem) = seeded_mem();
+        for key in ["first", "second", "third"] {
+            mem.store(key, &format!("{key} fact"), MemoryCategory::Core, None)
+                .await
+                .unwrap();
+        }
+        let tool = MemoryListTool::new(mem);
+
+        let result = tool.execute(json!({"limit": 2})).await.unwrap();
+        assert!(result.output.starts_with("Memories 1-2 of 3:"));
+        assert!(result.output.find("third").unwrap() < result.output.find("second").unwrap());
+        assert!(!result.output.contains("first fact"));
+        assert!(result.output.contains("offset 2"));
+
+        let result = tool
+            .execute(json!({"offset": 2, "limit": 2}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("first fact"));
+        assert!(!result.output.contains("More:"));
+
+        let result = tool
+            .execute(json!({"limit": 1, "order": "oldest"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("first fact"));
+    }
+
+    #[test]
+    fn name_and_schema() {
+        let (_tmp, mem) = seeded_mem();
+        let tool = MemoryListTool::new(mem);
+        assert_eq!(tool.name(), "memory_list");
+        assert_eq!(tool.effect(), ToolEffect::ReadOnly);
+        assert!(tool.parameters_schema()["properties"]["offset"].is_object());
+    }
+}
@@ -1,6 +1,8 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::namespace::{self, DEFAULT_NAMESPACE};
+use crate::memory::{Memory, TimeRange};
 use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use serde_json::json;
 use std::fmt::Write;
 use std::sync::Arc;
@@ -16,6 +18,29 @@ impl MemoryRecallTool {
     }
 }
 
+/// A time bound given as a date (local midnight), an RFC 3339 time, or a
+/// span back from now such as "7d" or "12h"
+pub(crate) fn parse_time_bound(name: &str, value: &str) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        if let Some(at) = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        {
+            return Ok(at.with_timezone(&Utc));
+        }
+    }
+    match crate::cron::parse_delay(value) {
+        Ok(span) => Ok(Utc::now() - span),
+        Err(_) => anyhow::bail!(
+            "Invalid '{name}': expected a date like 2025-03-01, an RFC 3339 time, or a span like \"7d\""
+        ),
+    }
+}
+
 #[async_trait]
 impl Tool for MemoryRecallTool {
     fn name(&self) -> &str {
@@ -23,7 +48,7 @@ impl Tool for MemoryRecallTool {
     }
 
     fn description(&self) -> &str {
-        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance."
+        "Search long-term memory for relevant facts, preferences, or context. Returns results ranked by relevance, each with a score (0-100%) and how it matched: fts+vector (strongest), vector, fts, or like (substring fallback, weakest). Use min_score to drop weak matches. For what exactly was said in past conversations use conversation_search."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -32,11 +57,32 @@ impl Tool for MemoryRecallTool {
             "properties": {
                 "query": {
                     "type": "string",
-                    "description": "Keywords or phrase to search for in memory"
+                    "description": "Keywords or phrase to search for in memory; may be empty with 'after' or 'before' to list that period's memories, newest first"
                 },
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return memories carrying all of these tags (see memory_tags)"
+                },
+                "namespace": {
+                    "type": "string",
+                    "description": "Search another memory namespace, or \"*\" for all (default: this conversation's)"
+                },
+                "after": {
+                    "type": "string",
+                    "description": "Only memories created at or after this: a date (\"2025-03-01\"), an RFC 3339 time, or a span back from now (\"7d\", \"12h\")"
+                },
+                "before": {
+                    "type": "string",
+                    "description": "Only memories created before this, in the same formats as 'after'"
+                },
+                "min_score": {
+                    "type": "number",
+                    "description": "Drop results scoring below this, from 0 to 1 (e.g. 0.3)"
                 }
             },
             "required": ["query"]
@@ -55,21 +101,124 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit, None).await {
+        let tags: Vec<String> = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let min_score = match args.get("min_score").map(serde_json::Value::as_f64) {
+            None => None,
+            Some(Some(min)) if (0.0..=1.0).contains(&min) => Some(min),
+            Some(_) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some("Invalid 'min_score': expected a number from 0 to 1".into()),
+                    attachments: Vec::new(),
+                })
+            }
+        };
+
+        let mut range = TimeRange::default();
+        for (name, bound) in [("after", &mut range.after), ("before", &mut range.before)] {
+            if let Some(value) = args.get(name).and_then(|v| v.as_str()) {
+                match parse_time_bound(name, value) {
+                    Ok(at) => *bound = Some(at),
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e.to_string()),
+                            attachments: Vec::new(),
+                        })
+                    }
+                }
+            }
+        }
+
+        let active = namespace::current();
+        let target = args
+            .get("namespace")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|ns| !ns.is_empty() && *ns != active);
+        // Only the owner's own conversations may read other people's memories
+        if target.is_some() && active != DEFAULT_NAMESPACE {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Namespace '{active}' can only recall its own memories"
+                )),
+                attachments: Vec::new(),
+            });
+        }
+        let recall = self
+            .memory
+            .recall_in_range(query, limit, None, &tags, &range);
+        let recalled = match target {
+            Some(ns) => namespace::scope(ns.to_string(), recall).await,
+            None => recall.await,
+        };
+
+        // Entries listed by time alone have no score and always pass
+        let mut dropped = 0;
+        let recalled = recalled.map(|mut entries| {
+            if let Some(min) = min_score {
+                let before = entries.len();
+                entries.retain(|e| e.score.map_or(true, |s| s >= min));
+                dropped = before - entries.len();
+            }
+            entries
+        });
+
+        match recalled {
+            Ok(entries) if entries.is_empty() && dropped > 0 => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "No memories scored at least {min:.2}; {dropped} weaker matches were left out.",
+                    min = min_score.unwrap_or_default()
+                ),
+                error: None,
+                attachments: Vec::new(),
+            }),
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
                 error: None,
+                attachments: Vec::new(),
             }),
             Ok(entries) => {
                 let mut output = format!("Found {} memories:\n", entries.len());
+                if dropped > 0 {
+                    let _ = writeln!(output, "({dropped} below min_score left out)");
+                }
                 for entry in &entries {
-                    let score = entry
-                        .score
-                        .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let score = match (entry.score, entry.match_kind) {
+                        (Some(s), Some(kind)) => format!(" [{:.0}% {kind}]", s * 100.0),
+                        (Some(s), None) => format!(" [{:.0}%]", s * 100.0),
+                        (None, _) => String::new(),
+                    };
+                    let mut origin = match (&target, &entry.namespace) {
+                        (Some(_), Some(ns)) => format!(" (namespace: {ns})"),
+                        _ => String::new(),
+                    };
+                    if !entry.tags.is_empty() {
+                        let _ = write!(origin, " #{}", entry.tags.join(" #"));
+                    }
+                    if !range.is_unbounded() {
+                        if let Some(date) = entry.timestamp.get(..10) {
+                            let _ = write!(origin, " (created {date})");
+                        }
+                    }
                     let _ = writeln!(
                         output,
-                        "- [{}] {}: {}{score}",
+                        "- [{}] {}: {}{score}{origin}",
                         entry.category, entry.key, entry.content
                     );
                 }
@@ -77,12 +226,14 @@ impl Tool for MemoryRecallTool {
                     success: true,
                     output,
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Memory recall failed: {e}")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -126,6 +277,40 @@ mod tests {
         assert!(result.output.contains("Found 1"));
     }
 
+    #[tokio::test]
+    async fn recall_shows_match_kind_and_honours_min_score() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "notes",
+            "Rust is used at work, alongside a lot of older Python and Go services",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        let tool = MemoryRecallTool::new(mem);
+
+        let result = tool.execute(json!({"query": "Rust"})).await.unwrap();
+        assert!(result.output.contains("[100% fts]"), "{}", result.output);
+
+        let strict = tool
+            .execute(json!({"query": "Rust", "min_score": 1.0}))
+            .await
+            .unwrap();
+        assert!(strict.success);
+        assert!(strict.output.contains("Found 1"), "{}", strict.output);
+        assert!(strict.output.contains("(1 below min_score left out)"));
+
+        let invalid = tool
+            .execute(json!({"query": "Rust", "min_score": 5}))
+            .await
+            .unwrap();
+        assert!(!invalid.success);
+    }
+
     #[tokio::test]
     async fn recall_respects_limit() {
         let (_tmp, mem) = seeded_mem();
@@ -149,6 +334,128 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_stays_in_namespace_unless_asked() {
+        let (_tmp, mem) = seeded_mem();
+        namespace::scope(
+            "discord:7".into(),
+            mem.store("pet", "Has a Rust-colored cat", MemoryCategory::Core, None),
+        )
+        .await
+        .unwrap();
+        let tool = MemoryRecallTool::new(mem);
+
+        let result = tool.execute(json!({"query": "Rust"})).await.unwrap();
+        assert!(result.output.contains("No memories found"));
+
+        let result = tool
+            .execute(json!({"query": "Rust", "namespace": "*"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("(namespace: discord:7)"));
+
+        let result = namespace::scope(
+            "telegram:9".into(),
+            tool.execute(json!({"query": "Rust", "namespace": "discord:7"})),
+        )
+        .await
+        .unwrap();
+        assert!(!result.success);
+        let result = namespace::scope(
+            "discord:7".into(),
+            tool.execute(json!({"query": "Rust", "namespace": "discord:7"})),
+        )
+        .await
+        .unwrap();
+        assert!(result.output.contains("Found 1"));
+    }
+
+    #[tokio::test]
+    async fn recall_filters_by_tags() {
+        let (_tmp, mem) = seeded_mem();
+        for (key, tags) in [
+            ("a", vec!["work"]),
+            ("b", vec!["work", "rust"]),
+            ("c", vec![]),
+        ] {
+            mem.store(key, "Rust notes", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            mem.set_tags(
+                key,
+                &tags.iter().map(|t| (*t).to_string()).collect::<Vec<_>>(),
+            )
+            .await
+            .unwrap();
+        }
+        let tool = MemoryRecallTool::new(mem);
+
+        let result = tool
+            .execute(json!({"query": "Rust", "tags": ["work"]}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 2"));
+        let result = tool
+            .execute(json!({"query": "Rust", "tags": ["Work", "rust"]}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("- [core] b: Rust notes"));
+        assert!(result.output.contains("#rust #work"));
+    }
+
+    #[test]
+    fn time_bounds_accept_dates_times_and_spans() {
+        let at = parse_time_bound("after", "2025-03-01T12:00:00+02:00").unwrap();
+        assert_eq!(at.to_rfc3339(), "2025-03-01T10:00:00+00:00");
+        let midnight = parse_time_bound("after", "2025-03-01").unwrap();
+        assert_eq!(
+            midnight.with_timezone(&Local).date_naive(),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+        let week_ago = parse_time_bound("after", "7d").unwrap();
+        let expected = Utc::now() - chrono::Duration::days(7);
+        assert!((week_ago - expected).num_seconds().abs() < 5);
+        let err = parse_time_bound("before", "last tuesday").unwrap_err();
+        assert!(err.to_string().contains("'before'"));
+    }
+
+    #[tokio::test]
+    async fn recall_filters_by_creation_time() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let tool = MemoryRecallTool::new(mem);
+
+        let result = tool
+            .execute(json!({"query": "Rust", "after": "1h"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        assert!(result.output.contains(&format!("(created {today})")));
+
+        let result = tool
+            .execute(json!({"query": "Rust", "before": "2000-01-01"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("No memories found"));
+
+        // An empty query lists the period
+        let result = tool
+            .execute(json!({"query": "", "after": "2000-01-01"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+
+        let result = tool
+            .execute(json!({"query": "Rust", "after": "soon"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
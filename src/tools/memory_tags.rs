@@ -0,0 +1,115 @@
+use super::traits::{Tool, ToolEffect, ToolResult};
+use crate::memory::Memory;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Let the agent see which tags its memories use
+pub struct MemoryTagsTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemoryTagsTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryTagsTool {
+    fn name(&self) -> &str {
+        "memory_tags"
+    }
+
+    fn description(&self) -> &str {
+        "List the tags used on stored memories, with how many memories carry each. Reuse these tags in memory_store and filter memory_recall by them."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    fn effect(&self) -> ToolEffect {
+        ToolEffect::ReadOnly
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let tags = match self.memory.tags().await {
+            Ok(tags) => tags,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Listing memory tags failed: {e}")),
+                    attachments: Vec::new(),
+                })
+            }
+        };
+        if tags.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No memories are tagged yet.".into(),
+                error: None,
+                attachments: Vec::new(),
+            });
+        }
+
+        let mut output = format!("{} tags:\n", tags.len());
+        for (tag, count) in &tags {
+            let _ = writeln!(output, "- {tag} ({count})");
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn test_mem() -> (TempDir, Arc<dyn Memory>) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        (tmp, Arc::new(mem))
+    }
+
+    #[tokio::test]
+    async fn lists_tags_by_use() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryTagsTool::new(mem.clone());
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.output.contains("No memories are tagged"));
+
+        for (key, tags) in [("a", ["work", "rust"]), ("b", ["work", "home"])] {
+            mem.store(key, "note", MemoryCategory::Core, None)
+                .await
+                .unwrap();
+            mem.set_tags(key, &tags.map(String::from)).await.unwrap();
+        }
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.output.starts_with("3 tags:\n- work (2)\n"));
+        assert!(result.output.contains("- rust (1)"));
+
+        mem.forget("a").await.unwrap();
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.output.contains("rust"));
+    }
+
+    #[test]
+    fn name_and_effect() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryTagsTool::new(mem);
+        assert_eq!(tool.name(), "memory_tags");
+        assert_eq!(tool.effect(), ToolEffect::ReadOnly);
+    }
+}
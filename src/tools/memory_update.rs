@@ -0,0 +1,150 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::Memory;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Let the agent correct a stored memory in place
+pub struct MemoryUpdateTool {
+    memory: Arc<dyn Memory>,
+}
+
+impl MemoryUpdateTool {
+    pub fn new(memory: Arc<dyn Memory>) -> Self {
+        Self { memory }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryUpdateTool {
+    fn name(&self) -> &str {
+        "memory_update"
+    }
+
+    fn description(&self) -> &str {
+        "Replace the content of an existing memory, found by key or id. Keeps its category, tags and expiry, and re-indexes it for search. Use to correct facts that turned out wrong."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "key": {
+                    "type": "string",
+                    "description": "The key or id of the memory to update"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "The corrected content"
+                }
+            },
+            "required": ["key", "content"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let key = args
+            .get("key")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'key' parameter"))?;
+        let content = args
+            .get("content")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'content' parameter"))?;
+
+        let result = match crate::memory::lookup(self.memory.as_ref(), key).await {
+            Ok(Some(entry)) => self
+                .memory
+                .update(&entry.key, content)
+                .await
+                .map(|updated| updated.then_some(entry.key)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(Some(key)) => Ok(ToolResult {
+                success: true,
+                output: format!("Updated memory: {key}"),
+                error: None,
+                attachments: Vec::new(),
+            }),
+            Ok(None) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "No memory found with key or id: {key}. Use memory_store to add it."
+                )),
+                attachments: Vec::new(),
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Failed to update memory: {e}")),
+                attachments: Vec::new(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn test_mem() -> (TempDir, Arc<dyn Memory>) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        (tmp, Arc::new(mem))
+    }
+
+    #[tokio::test]
+    async fn update_keeps_metadata_and_reindexes() {
+        let (_tmp, mem) = test_mem();
+        mem.store(
+            "dog",
+            "User's dog is named Biscut",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem.set_tags("dog", &["pets".into()]).await.unwrap();
+        let id = mem.get("dog").await.unwrap().unwrap().id;
+
+        let tool = MemoryUpdateTool::new(mem.clone());
+        let result = tool
+            .execute(json!({"key": id, "content": "User's dog is named Biscuit"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("Updated memory: dog"));
+
+        let entry = mem.get("dog").await.unwrap().unwrap();
+        assert_eq!(entry.id, id);
+        assert_eq!(entry.category, MemoryCategory::Core);
+        assert_eq!(entry.tags, vec!["pets"]);
+        assert!(mem.recall("Biscut", 5, None).await.unwrap().is_empty());
+        assert_eq!(mem.recall("Biscuit", 5, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn update_missing_memory_fails() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryUpdateTool::new(mem);
+        let result = tool
+            .execute(json!({"key": "nope", "content": "x"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("No memory found"));
+    }
+
+    #[tokio::test]
+    async fn update_requires_content() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryUpdateTool::new(mem);
+        assert!(tool.execute(json!({"key": "dog"})).await.is_err());
+    }
+}
@@ -1,6 +1,8 @@
+pub mod agent_tool;
 pub mod browser;
 pub mod browser_open;
 pub mod composio;
+pub mod conversation_search;
 pub mod cron_add;
 pub mod cron_list;
 pub mod cron_remove;
@@ -8,6 +10,7 @@ pub mod cron_run;
 pub mod cron_runs;
 pub mod cron_update;
 pub mod delegate;
+pub mod docs_search;
 pub mod file_read;
 pub mod file_write;
 pub mod git_operations;
@@ -17,18 +20,24 @@ pub mod hardware_memory_read;
 pub mod http_request;
 pub mod image_info;
 pub mod memory_forget;
+pub mod memory_list;
 pub mod memory_recall;
 pub mod memory_store;
+pub mod memory_tags;
+pub mod memory_update;
 pub mod pushover;
 pub mod schedule;
 pub mod schema;
 pub mod screenshot;
+pub mod session_transcript;
 pub mod shell;
 pub mod traits;
 
+pub use agent_tool::AgentTool;
 pub use browser::{BrowserTool, ComputerUseConfig};
 pub use browser_open::BrowserOpenTool;
 pub use composio::ComposioTool;
+pub use conversation_search::ConversationSearchTool;
 pub use cron_add::CronAddTool;
 pub use cron_list::CronListTool;
 pub use cron_remove::CronRemoveTool;
@@ -36,6 +45,7 @@ pub use cron_run::CronRunTool;
 pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
 pub use delegate::DelegateTool;
+pub use docs_search::DocsSearchTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use git_operations::GitOperationsTool;
@@ -45,19 +55,23 @@ pub use hardware_memory_read::HardwareMemoryReadTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
 pub use memory_forget::MemoryForgetTool;
+pub use memory_list::MemoryListTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
+pub use memory_tags::MemoryTagsTool;
+pub use memory_update::MemoryUpdateTool;
 pub use pushover::PushoverTool;
 pub use schedule::ScheduleTool;
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
+pub use session_transcript::SessionTranscriptTool;
 pub use shell::ShellTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{ToolAttachment, ToolEffect, ToolResult, ToolSpec};
 
 use crate::config::{Config, DelegateAgentConfig};
-use crate::memory::Memory;
+use crate::memory::{DedupStrategy, Memory};
 use crate::runtime::{NativeRuntime, RuntimeAdapter};
 use crate::security::SecurityPolicy;
 use std::collections::HashMap;
@@ -112,6 +126,7 @@ pub fn all_tools(
 }
 
 /// Create full tool registry including memory tools and optional Composio.
+/// Build the full registry, wrapped in the base `[tool_policy]`.
 #[allow(clippy::implicit_hasher, clippy::too_many_arguments)]
 pub fn all_tools_with_runtime(
     config: Arc<Config>,
@@ -126,9 +141,41 @@ pub fn all_tools_with_runtime(
     agents: &HashMap<String, DelegateAgentConfig>,
     fallback_api_key: Option<&str>,
     root_config: &crate::config::Config,
+) -> Vec<Box<dyn Tool>> {
+    let tools = build_tools(
+        config,
+        security,
+        runtime,
+        memory,
+        composio_key,
+        composio_entity_id,
+        browser_config,
+        http_config,
+        workspace_dir,
+        agents,
+        fallback_api_key,
+        root_config,
+    );
+    crate::security::tool_policy::enforce(root_config, tools)
+}
+
+#[allow(clippy::implicit_hasher, clippy::too_many_arguments)]
+fn build_tools(
+    config: Arc<Config>,
+    security: &Arc<SecurityPolicy>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    memory: Arc<dyn Memory>,
+    composio_key: Option<&str>,
+    composio_entity_id: Option<&str>,
+    browser_config: &crate::config::BrowserConfig,
+    http_config: &crate::config::HttpRequestConfig,
+    workspace_dir: &std::path::Path,
+    agents: &HashMap<String, DelegateAgentConfig>,
+    fallback_api_key: Option<&str>,
+    root_config: &crate::config::Config,
 ) -> Vec<Box<dyn Tool>> {
     let mut tools: Vec<Box<dyn Tool>> = vec![
-        Box::new(ShellTool::new(security.clone(), runtime)),
+        Box::new(ShellTool::new(security.clone(), runtime.clone())),
         Box::new(FileReadTool::new(security.clone())),
         Box::new(FileWriteTool::new(security.clone())),
         Box::new(CronAddTool::new(config.clone(), security.clone())),
@@ -137,9 +184,27 @@ pub fn all_tools_with_runtime(
         Box::new(CronUpdateTool::new(config.clone(), security.clone())),
         Box::new(CronRunTool::new(config.clone())),
         Box::new(CronRunsTool::new(config.clone())),
-        Box::new(MemoryStoreTool::new(memory.clone())),
+        Box::new(
+            MemoryStoreTool::new(memory.clone()).with_dedup(
+                root_config
+                    .memory
+                    .dedup_strategy
+                    .parse()
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("memory dedup disabled: {e}");
+                        DedupStrategy::Off
+                    }),
+                root_config.memory.dedup_threshold,
+            ),
+        ),
         Box::new(MemoryRecallTool::new(memory.clone())),
-        Box::new(MemoryForgetTool::new(memory)),
+        Box::new(MemoryListTool::new(memory.clone())),
+        Box::new(MemoryTagsTool::new(memory.clone())),
+        Box::new(MemoryUpdateTool::new(memory.clone())),
+        Box::new(MemoryForgetTool::new(memory.clone())),
+        Box::new(SessionTranscriptTool::new(config.clone())),
+        Box::new(ConversationSearchTool::new(config.clone())),
+        Box::new(DocsSearchTool::new(config.clone())),
         Box::new(ScheduleTool::new(security.clone(), root_config.clone())),
         Box::new(GitOperationsTool::new(
             security.clone(),
@@ -197,22 +262,71 @@ pub fn all_tools_with_runtime(
         }
     }
 
+    let delegate_fallback_credential = fallback_api_key.and_then(|value| {
+        let trimmed_value = value.trim();
+        (!trimmed_value.is_empty()).then(|| trimmed_value.to_owned())
+    });
+
     // Add delegation tool when agents are configured
     if !agents.is_empty() {
         let delegate_agents: HashMap<String, DelegateAgentConfig> = agents
             .iter()
             .map(|(name, cfg)| (name.clone(), cfg.clone()))
             .collect();
-        let delegate_fallback_credential = fallback_api_key.and_then(|value| {
-            let trimmed_value = value.trim();
-            (!trimmed_value.is_empty()).then(|| trimmed_value.to_owned())
-        });
         tools.push(Box::new(DelegateTool::new(
             delegate_agents,
-            delegate_fallback_credential,
+            delegate_fallback_credential.clone(),
         )));
     }
 
+    // Expose selected agents as standalone tools running a full agent loop.
+    // Their own registry is built without agents, so they cannot recurse.
+    for (name, agent) in agents.iter().filter(|(_, agent)| agent.expose_as_tool) {
+        let sub_tools: Vec<Box<dyn Tool>> = if agent.tools.is_empty() {
+            Vec::new()
+        } else {
+            let agent_tools = build_tools(
+                config.clone(),
+                security,
+                runtime.clone(),
+                memory.clone(),
+                composio_key,
+                composio_entity_id,
+                browser_config,
+                http_config,
+                workspace_dir,
+                &HashMap::new(),
+                fallback_api_key,
+                root_config,
+            )
+            .into_iter()
+            .filter(|tool| agent.tools.iter().any(|allowed| allowed == tool.name()))
+            .collect();
+            crate::security::tool_policy::ToolPolicy::from_config(
+                root_config,
+                agent.policy.as_deref(),
+            )
+            .wrap(agent_tools)
+        };
+        tools.push(Box::new(AgentTool::new(
+            name,
+            agent.clone(),
+            sub_tools,
+            delegate_fallback_credential.clone(),
+        )));
+    }
+
+    for tool in crate::plugins::tools(root_config, security) {
+        if tools.iter().any(|existing| existing.name() == tool.name()) {
+            tracing::warn!(
+                "Skipping plugin tool {}: a built-in tool has that name",
+                tool.name()
+            );
+            continue;
+        }
+        tools.push(tool);
+    }
+
     tools
 }
 
@@ -374,6 +488,7 @@ mod tests {
             success: true,
             output: "hello".into(),
             error: None,
+            attachments: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -388,6 +503,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            attachments: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -433,6 +549,10 @@ mod tests {
                 api_key: None,
                 temperature: None,
                 max_depth: 3,
+                expose_as_tool: false,
+                description: None,
+                tools: vec![],
+                policy: None,
             },
         );
 
@@ -453,6 +573,57 @@ mod tests {
         assert!(names.contains(&"delegate"));
     }
 
+    #[test]
+    fn all_tools_registers_agents_exposed_as_tools() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::default());
+        let mem_cfg = MemoryConfig {
+            backend: "markdown".into(),
+            ..MemoryConfig::default()
+        };
+        let mem: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+
+        let browser = BrowserConfig::default();
+        let http = crate::config::HttpRequestConfig::default();
+        let cfg = test_config(&tmp);
+
+        let mut agents = HashMap::new();
+        agents.insert(
+            "researcher".to_string(),
+            DelegateAgentConfig {
+                provider: "ollama".to_string(),
+                model: "llama3".to_string(),
+                system_prompt: None,
+                api_key: None,
+                temperature: None,
+                max_depth: 3,
+                expose_as_tool: true,
+                description: None,
+                tools: vec!["file_read".into(), "delegate".into()],
+                policy: None,
+            },
+        );
+
+        let tools = all_tools(
+            Arc::new(Config::default()),
+            &security,
+            mem,
+            None,
+            None,
+            &browser,
+            &http,
+            tmp.path(),
+            &agents,
+            None,
+            &cfg,
+        );
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert!(names.contains(&"delegate"));
+        assert!(names.contains(&"agent_researcher"));
+        assert_eq!(names.iter().filter(|n| n.starts_with("agent_")).count(), 1);
+    }
+
     #[test]
     fn all_tools_excludes_delegate_when_no_agents() {
         let tmp = TempDir::new().unwrap();
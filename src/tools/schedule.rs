@@ -121,6 +121,7 @@ impl Tool for ScheduleTool {
                 error: Some(format!(
                     "Unknown action '{other}'. Use create/add/once/list/get/cancel/remove/pause/resume."
                 )),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -135,6 +136,7 @@ impl ScheduleTool {
                 error: Some(format!(
                     "Security policy: read-only mode, cannot perform '{action}'"
                 )),
+                attachments: Vec::new(),
             });
         }
 
@@ -143,6 +145,7 @@ impl ScheduleTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".to_string()),
+                attachments: Vec::new(),
             });
         }
 
@@ -156,6 +159,7 @@ impl ScheduleTool {
                 success: true,
                 output: "No scheduled jobs.".to_string(),
                 error: None,
+                attachments: Vec::new(),
             });
         }
 
@@ -189,6 +193,7 @@ impl ScheduleTool {
             success: true,
             output: format!("Scheduled jobs ({}):\n{}", lines.len(), lines.join("\n")),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -209,12 +214,14 @@ impl ScheduleTool {
                     success: true,
                     output: serde_json::to_string_pretty(&detail)?,
                     error: None,
+                    attachments: Vec::new(),
                 })
             }
             Err(_) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Job '{id}' not found")),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -237,6 +244,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'add' requires 'expression' and forbids delay/run_at".into()),
+                        attachments: Vec::new(),
                     });
                 }
             }
@@ -246,6 +254,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'once' requires exactly one of 'delay' or 'run_at'".into()),
+                        attachments: Vec::new(),
                     });
                 }
                 if delay.is_some() && run_at.is_some() {
@@ -253,6 +262,7 @@ impl ScheduleTool {
                         success: false,
                         output: String::new(),
                         error: Some("'once' supports either delay or run_at, not both".into()),
+                        attachments: Vec::new(),
                     });
                 }
             }
@@ -269,6 +279,7 @@ impl ScheduleTool {
                             "Exactly one of 'expression', 'delay', or 'run_at' must be provided"
                                 .into(),
                         ),
+                        attachments: Vec::new(),
                     });
                 }
             }
@@ -286,6 +297,7 @@ impl ScheduleTool {
                     job.command
                 ),
                 error: None,
+                attachments: Vec::new(),
             });
         }
 
@@ -300,6 +312,7 @@ impl ScheduleTool {
                     job.command
                 ),
                 error: None,
+                attachments: Vec::new(),
             });
         }
 
@@ -318,6 +331,7 @@ impl ScheduleTool {
                 job.command
             ),
             error: None,
+            attachments: Vec::new(),
         })
     }
 
@@ -327,11 +341,13 @@ impl ScheduleTool {
                 success: true,
                 output: format!("Cancelled job {id}"),
                 error: None,
+                attachments: Vec::new(),
             },
             Err(error) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(error.to_string()),
+                attachments: Vec::new(),
             },
         }
     }
@@ -352,11 +368,13 @@ impl ScheduleTool {
                     format!("Resumed job {id}")
                 },
                 error: None,
+                attachments: Vec::new(),
             },
             Err(error) => ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(error.to_string()),
+                attachments: Vec::new(),
             },
         }
     }
@@ -0,0 +1,145 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::sessions::{self, ExportFormat};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Keep transcripts within a reasonable share of the context window.
+const MAX_TRANSCRIPT_CHARS: usize = 60_000;
+
+/// Returns the redacted transcript of the current conversation, so the agent
+/// can summarize it or save it as a document.
+pub struct SessionTranscriptTool {
+    config: Arc<Config>,
+}
+
+impl SessionTranscriptTool {
+    pub fn new(config: Arc<Config>) -> Self {
+        Self { config }
+    }
+
+    fn failure(error: impl Into<String>) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error.into()),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SessionTranscriptTool {
+    fn name(&self) -> &str {
+        "session_transcript"
+    }
+
+    fn description(&self) -> &str {
+        "Get the transcript of this conversation's earlier turns, including tool calls and results (secrets redacted). Use for summaries or exports of the conversation; save with file_write if a document is wanted."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "format": {
+                    "type": "string",
+                    "enum": ["md", "json", "html"],
+                    "description": "Transcript format (default: md)"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if !self.config.sessions.record_transcripts {
+            return Ok(Self::failure(
+                "transcripts are disabled by config (sessions.record_transcripts=false)",
+            ));
+        }
+        // Only the caller's own session: on shared channels another id would
+        // expose someone else's conversation.
+        let Some(session_id) = sessions::current_session_id() else {
+            return Ok(Self::failure("no active session"));
+        };
+        let format = match args.get("format").and_then(|v| v.as_str()) {
+            Some(format) => match format.parse::<ExportFormat>() {
+                Ok(format) => format,
+                Err(e) => return Ok(Self::failure(e.to_string())),
+            },
+            None => ExportFormat::Markdown,
+        };
+
+        let transcript = match sessions::load(&self.config.workspace_dir, &session_id) {
+            Ok(transcript) => transcript,
+            Err(_) => {
+                return Ok(ToolResult {
+                    success: true,
+                    output: "No earlier turns recorded in this session yet.".into(),
+                    error: None,
+                    attachments: Vec::new(),
+                })
+            }
+        };
+        let mut output = sessions::render(&transcript, format)?;
+        if output.len() > MAX_TRANSCRIPT_CHARS {
+            let mut end = MAX_TRANSCRIPT_CHARS;
+            while !output.is_char_boundary(end) {
+                end -= 1;
+            }
+            output.truncate(end);
+            output.push_str("\n\n[transcript truncated]");
+        }
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+            attachments: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn returns_only_the_current_session() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        sessions::record_turn(
+            &config.workspace_dir,
+            "cli-a",
+            "plan the trip",
+            "Here's a plan.",
+            &[],
+        )
+        .unwrap();
+        sessions::record_turn(
+            &config.workspace_dir,
+            "cli-b",
+            "other secret topic",
+            "ok",
+            &[],
+        )
+        .unwrap();
+        let tool = SessionTranscriptTool::new(Arc::new(config));
+
+        let outside = tool.execute(json!({})).await.unwrap();
+        assert!(!outside.success);
+
+        let result = sessions::scope("cli-a".into(), tool.execute(json!({})))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("plan the trip"));
+        assert!(!result.output.contains("other secret topic"));
+    }
+}
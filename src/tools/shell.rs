@@ -71,6 +71,7 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -81,6 +82,7 @@ impl Tool for ShellTool {
                     success: false,
                     output: String::new(),
                     error: Some(reason),
+                    attachments: Vec::new(),
                 });
             }
         }
@@ -90,6 +92,7 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                attachments: Vec::new(),
             });
         }
 
@@ -106,6 +109,7 @@ impl Tool for ShellTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to build runtime command: {e}")),
+                    attachments: Vec::new(),
                 });
             }
         };
@@ -117,6 +121,8 @@ impl Tool for ShellTool {
             }
         }
 
+        crate::security::limits::apply(&mut cmd);
+
         let result =
             tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
 
@@ -143,12 +149,14 @@ impl Tool for ShellTool {
                     } else {
                         Some(stderr)
                     },
+                    attachments: Vec::new(),
                 })
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute command: {e}")),
+                attachments: Vec::new(),
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -156,6 +164,7 @@ impl Tool for ShellTool {
                 error: Some(format!(
                     "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
                 )),
+                attachments: Vec::new(),
             }),
         }
     }
@@ -1,5 +1,9 @@
+use anyhow::Context;
 use async_trait::async_trait;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 
 /// Result of a tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +11,85 @@ pub struct ToolResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Binary content returned besides the text output, e.g. images from
+    /// MCP tools.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<ToolAttachment>,
+}
+
+/// Binary content returned by a tool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolAttachment {
+    pub mime_type: String,
+    /// Base64-encoded bytes.
+    pub data: String,
+    /// Where the content came from, e.g. an MCP resource URI.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
+impl ToolAttachment {
+    /// Channel media marker (`[IMAGE:...]`, ...) kind for this attachment.
+    pub fn marker_kind(&self) -> &'static str {
+        match self.mime_type.split('/').next().unwrap_or_default() {
+            "image" => "IMAGE",
+            "audio" => "AUDIO",
+            "video" => "VIDEO",
+            _ => "DOCUMENT",
+        }
+    }
+
+    /// Decode the attachment into a new file in `dir`.
+    pub fn save(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(self.data.trim())
+            .context("attachment is not valid base64")?;
+        let extension = match self.mime_type.as_str() {
+            "image/png" => "png",
+            "image/jpeg" => "jpg",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "audio/mpeg" => "mp3",
+            "audio/wav" => "wav",
+            "audio/ogg" => "ogg",
+            "video/mp4" => "mp4",
+            "application/pdf" => "pdf",
+            _ => "bin",
+        };
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.{extension}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    }
+}
+
+/// Directory tool attachments are saved to for the turn that received them.
+pub fn attachments_dir() -> PathBuf {
+    std::env::temp_dir().join("zeroclaw-attachments")
+}
+
+/// Save `attachments` to `dir` and describe them for the model, with the
+/// media marker that sends each file to the channel.
+pub fn describe_attachments(attachments: &[ToolAttachment], dir: &Path) -> String {
+    let mut note = String::from("Attachments:");
+    for attachment in attachments {
+        match attachment.save(dir) {
+            Ok(path) => {
+                let _ = write!(
+                    note,
+                    "\n- {} saved to {} (include [{}:{}] in your reply to send it)",
+                    attachment.mime_type,
+                    path.display(),
+                    attachment.marker_kind(),
+                    path.display()
+                );
+            }
+            Err(e) => {
+                let _ = write!(note, "\n- {} could not be saved: {e}", attachment.mime_type);
+            }
+        }
+    }
+    note
 }
 
 /// Description of a tool for the LLM
@@ -17,6 +100,16 @@ pub struct ToolSpec {
     pub parameters: serde_json::Value,
 }
 
+/// What a tool declares about its side effects; the approval workflow
+/// prompts for destructive tools and lets read-only ones run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolEffect {
+    #[default]
+    Unknown,
+    ReadOnly,
+    Destructive,
+}
+
 /// Core tool trait — implement for any capability
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -32,6 +125,11 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Side effects of calling the tool, for the approval workflow
+    fn effect(&self) -> ToolEffect {
+        ToolEffect::Unknown
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -42,6 +140,35 @@ pub trait Tool: Send + Sync {
     }
 }
 
+/// Lets one tool instance be shared between registries, e.g. MCP tools
+/// that survive a config reload while the rest of the registry is rebuilt.
+#[async_trait]
+impl<T: Tool + ?Sized> Tool for std::sync::Arc<T> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        (**self).parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        (**self).execute(args).await
+    }
+
+    fn effect(&self) -> ToolEffect {
+        (**self).effect()
+    }
+
+    fn spec(&self) -> ToolSpec {
+        (**self).spec()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,10 +203,36 @@ mod tests {
                     .unwrap_or_default()
                     .to_string(),
                 error: None,
+                attachments: Vec::new(),
             })
         }
     }
 
+    #[test]
+    fn attachments_are_saved_with_media_markers() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let attachments = [
+            ToolAttachment {
+                mime_type: "image/png".into(),
+                data: base64::engine::general_purpose::STANDARD.encode(b"\x89PNG"),
+                uri: None,
+            },
+            ToolAttachment {
+                mime_type: "application/zip".into(),
+                data: "not base64!".into(),
+                uri: None,
+            },
+        ];
+
+        let note = describe_attachments(&attachments, tmp.path());
+        let saved: Vec<_> = std::fs::read_dir(tmp.path()).unwrap().collect();
+        assert_eq!(saved.len(), 1);
+        let path = saved[0].as_ref().unwrap().path();
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x89PNG");
+        assert!(note.contains(&format!("[IMAGE:{}]", path.display())));
+        assert!(note.contains("application/zip could not be saved"));
+    }
+
     #[test]
     fn spec_uses_tool_metadata_and_schema() {
         let tool = DummyTool;
@@ -110,6 +263,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            attachments: Vec::new(),
         };
 
         let json = serde_json::to_string(&result).unwrap();
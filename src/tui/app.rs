@@ -0,0 +1,516 @@
+//! TUI state and key handling. Kept free of terminal I/O so it can be tested.
+
+use crate::approval::ApprovalResponse;
+use crate::memory::MemoryEntry;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Tool activity entries kept in the sidebar.
+const MAX_TOOL_ACTIVITY: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    User,
+    Assistant,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatLine {
+    pub role: Role,
+    pub text: String,
+}
+
+pub struct Session {
+    pub name: String,
+    pub lines: Vec<ChatLine>,
+    /// Lines scrolled back from the bottom of the transcript.
+    pub scroll_back: usize,
+}
+
+impl Session {
+    fn new(index: usize) -> Self {
+        Self {
+            name: format!("chat {}", index + 1),
+            lines: Vec::new(),
+            scroll_back: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolStatus {
+    Running,
+    Done { success: bool },
+}
+
+pub struct ToolActivity {
+    pub session: usize,
+    pub tool: String,
+    pub status: ToolStatus,
+    pub started: Instant,
+    pub duration: Option<Duration>,
+}
+
+/// A tool call waiting for the user's approve/deny decision.
+pub struct PendingApproval {
+    pub tool: String,
+    pub summary: String,
+    pub reply: oneshot::Sender<ApprovalResponse>,
+}
+
+/// Events delivered to the UI from the agent worker and background tasks.
+pub enum AgentEvent {
+    /// Assistant text produced between tool calls.
+    Text(String),
+    ToolStart(String),
+    ToolEnd {
+        tool: String,
+        duration: Duration,
+        success: bool,
+    },
+    TurnDone {
+        session: usize,
+        result: Result<String, String>,
+    },
+    Approval(PendingApproval),
+    Memories(Result<Vec<MemoryEntry>, String>),
+}
+
+/// What the event loop must do after a key press.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Send { session: usize, text: String },
+    RefreshMemory,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Input,
+    Memory,
+}
+
+#[derive(Default)]
+pub struct MemoryBrowser {
+    pub visible: bool,
+    pub loading: bool,
+    pub entries: Vec<MemoryEntry>,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+pub struct App {
+    pub title: String,
+    pub sessions: Vec<Session>,
+    pub current: usize,
+    pub input: String,
+    /// Cursor position in `input`, in chars.
+    pub cursor: usize,
+    /// Session whose turn is running, if any.
+    pub busy: Option<usize>,
+    pub tools: Vec<ToolActivity>,
+    pub approval: Option<PendingApproval>,
+    pub memory: MemoryBrowser,
+    pub focus: Focus,
+    pub show_help: bool,
+    pub status: Option<String>,
+}
+
+impl App {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            sessions: vec![Session::new(0)],
+            current: 0,
+            input: String::new(),
+            cursor: 0,
+            busy: None,
+            tools: Vec::new(),
+            approval: None,
+            memory: MemoryBrowser::default(),
+            focus: Focus::Input,
+            show_help: false,
+            status: None,
+        }
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.sessions[self.current]
+    }
+
+    fn push_line(&mut self, session: usize, role: Role, text: String) {
+        if let Some(s) = self.sessions.get_mut(session) {
+            s.lines.push(ChatLine { role, text });
+            s.scroll_back = 0;
+        }
+    }
+
+    pub fn apply(&mut self, event: AgentEvent) {
+        match event {
+            AgentEvent::Text(text) => {
+                if let Some(session) = self.busy {
+                    self.push_line(session, Role::Assistant, text);
+                }
+            }
+            AgentEvent::ToolStart(tool) => {
+                if self.tools.len() >= MAX_TOOL_ACTIVITY {
+                    self.tools.remove(0);
+                }
+                self.tools.push(ToolActivity {
+                    session: self.busy.unwrap_or(self.current),
+                    tool,
+                    status: ToolStatus::Running,
+                    started: Instant::now(),
+                    duration: None,
+                });
+            }
+            AgentEvent::ToolEnd {
+                tool,
+                duration,
+                success,
+            } => {
+                if let Some(entry) = self
+                    .tools
+                    .iter_mut()
+                    .rev()
+                    .find(|t| t.tool == tool && t.status == ToolStatus::Running)
+                {
+                    entry.status = ToolStatus::Done { success };
+                    entry.duration = Some(duration);
+                }
+            }
+            AgentEvent::TurnDone { session, result } => {
+                self.busy = None;
+                match result {
+                    Ok(text) => self.push_line(session, Role::Assistant, text),
+                    Err(e) => self.push_line(session, Role::Error, e),
+                }
+            }
+            AgentEvent::Approval(pending) => {
+                // A second request can only arrive after the first is answered;
+                // if the previous sender is still here its turn was abandoned.
+                self.approval = Some(pending);
+            }
+            AgentEvent::Memories(result) => {
+                self.memory.loading = false;
+                match result {
+                    Ok(entries) => {
+                        self.memory.entries = entries;
+                        self.memory.error = None;
+                    }
+                    Err(e) => self.memory.error = Some(e),
+                }
+                self.memory.selected = self
+                    .memory
+                    .selected
+                    .min(self.memory.entries.len().saturating_sub(1));
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+        if let Some(pending) = self.approval.take() {
+            let decision = match key.code {
+                KeyCode::Char('y' | 'Y') => ApprovalResponse::Yes,
+                KeyCode::Char('a' | 'A') => ApprovalResponse::Always,
+                KeyCode::Char('n' | 'N') | KeyCode::Esc => ApprovalResponse::No,
+                _ => {
+                    self.approval = Some(pending);
+                    return Action::None;
+                }
+            };
+            let _ = pending.reply.send(decision);
+            return Action::None;
+        }
+
+        if self.show_help {
+            self.show_help = false;
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Char('c' | 'd') if ctrl => return Action::Quit,
+            KeyCode::F(1) => {
+                self.show_help = true;
+                return Action::None;
+            }
+            KeyCode::F(2) => return self.toggle_memory(),
+            KeyCode::Tab if self.memory.visible => {
+                self.focus = match self.focus {
+                    Focus::Input => Focus::Memory,
+                    Focus::Memory => Focus::Input,
+                };
+                return Action::None;
+            }
+            KeyCode::Char('n') if ctrl => {
+                self.new_session();
+                return Action::None;
+            }
+            KeyCode::Left if ctrl => {
+                self.switch_session(-1);
+                return Action::None;
+            }
+            KeyCode::Right if ctrl => {
+                self.switch_session(1);
+                return Action::None;
+            }
+            KeyCode::PageUp => {
+                self.sessions[self.current].scroll_back += 10;
+                return Action::None;
+            }
+            KeyCode::PageDown => {
+                let session = &mut self.sessions[self.current];
+                session.scroll_back = session.scroll_back.saturating_sub(10);
+                return Action::None;
+            }
+            _ => {}
+        }
+
+        match self.focus {
+            Focus::Memory => self.handle_memory_key(key),
+            Focus::Input => self.handle_input_key(key),
+        }
+    }
+
+    fn toggle_memory(&mut self) -> Action {
+        self.memory.visible = !self.memory.visible;
+        if self.memory.visible {
+            self.focus = Focus::Memory;
+            self.memory.loading = true;
+            Action::RefreshMemory
+        } else {
+            self.focus = Focus::Input;
+            Action::None
+        }
+    }
+
+    fn handle_memory_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Up => self.memory.selected = self.memory.selected.saturating_sub(1),
+            KeyCode::Down => {
+                if self.memory.selected + 1 < self.memory.entries.len() {
+                    self.memory.selected += 1;
+                }
+            }
+            KeyCode::Char('r') => {
+                self.memory.loading = true;
+                return Action::RefreshMemory;
+            }
+            KeyCode::Esc => self.focus = Focus::Input,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Char(c) => {
+                let at = self.byte_offset(self.cursor);
+                self.input.insert(at, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let at = self.byte_offset(self.cursor);
+                self.input.remove(at);
+            }
+            KeyCode::Delete if self.cursor < self.input.chars().count() => {
+                let at = self.byte_offset(self.cursor);
+                self.input.remove(at);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.input.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.input.chars().count(),
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn byte_offset(&self, chars: usize) -> usize {
+        self.input
+            .char_indices()
+            .nth(chars)
+            .map_or(self.input.len(), |(i, _)| i)
+    }
+
+    fn submit(&mut self) -> Action {
+        let text = self.input.trim().to_string();
+        if text.is_empty() {
+            return Action::None;
+        }
+        match text.as_str() {
+            "/quit" | "/exit" => return Action::Quit,
+            "/new" => {
+                self.clear_input();
+                self.new_session();
+                return Action::None;
+            }
+            _ => {}
+        }
+        if self.busy.is_some() {
+            self.status = Some("Agent is busy — wait for the current turn to finish".into());
+            return Action::None;
+        }
+        self.clear_input();
+        self.status = None;
+        self.busy = Some(self.current);
+        self.push_line(self.current, Role::User, text.clone());
+        Action::Send {
+            session: self.current,
+            text,
+        }
+    }
+
+    fn clear_input(&mut self) {
+        self.input.clear();
+        self.cursor = 0;
+    }
+
+    fn new_session(&mut self) {
+        self.sessions.push(Session::new(self.sessions.len()));
+        self.current = self.sessions.len() - 1;
+    }
+
+    fn switch_session(&mut self, delta: isize) {
+        let count = self.sessions.len() as isize;
+        self.current = (self.current as isize + delta).rem_euclid(count) as usize;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::CONTROL)
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn typing_and_enter_sends_to_current_session() {
+        let mut app = App::new("test".into());
+        type_text(&mut app, "héllo");
+        app.handle_key(key(KeyCode::Left));
+        app.handle_key(key(KeyCode::Backspace));
+        assert_eq!(app.input, "hélo");
+
+        let action = app.handle_key(key(KeyCode::Enter));
+        assert_eq!(
+            action,
+            Action::Send {
+                session: 0,
+                text: "hélo".into()
+            }
+        );
+        assert_eq!(app.busy, Some(0));
+        assert!(app.input.is_empty());
+
+        type_text(&mut app, "again");
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::None);
+        assert!(app.status.is_some());
+    }
+
+    #[test]
+    fn turn_results_land_in_their_own_session() {
+        let mut app = App::new("test".into());
+        type_text(&mut app, "hi");
+        app.handle_key(key(KeyCode::Enter));
+        app.handle_key(ctrl(KeyCode::Char('n')));
+        assert_eq!(app.current, 1);
+
+        app.apply(AgentEvent::Text("thinking".into()));
+        app.apply(AgentEvent::TurnDone {
+            session: 0,
+            result: Ok("hello".into()),
+        });
+        assert!(app.sessions[1].lines.is_empty());
+        let texts: Vec<_> = app.sessions[0]
+            .lines
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(texts, ["hi", "thinking", "hello"]);
+        assert!(app.busy.is_none());
+
+        app.handle_key(ctrl(KeyCode::Right));
+        assert_eq!(app.current, 0);
+    }
+
+    #[test]
+    fn approval_keys_answer_pending_request() {
+        let mut app = App::new("test".into());
+        let (tx, mut rx) = oneshot::channel();
+        app.apply(AgentEvent::Approval(PendingApproval {
+            tool: "shell".into(),
+            summary: "command: ls".into(),
+            reply: tx,
+        }));
+
+        // Unrelated keys neither answer nor reach the input box.
+        app.handle_key(key(KeyCode::Char('x')));
+        assert!(app.approval.is_some());
+        assert!(app.input.is_empty());
+
+        app.handle_key(key(KeyCode::Char('a')));
+        assert!(app.approval.is_none());
+        assert_eq!(rx.try_recv().unwrap(), ApprovalResponse::Always);
+    }
+
+    #[test]
+    fn tool_activity_tracks_start_and_end() {
+        let mut app = App::new("test".into());
+        app.apply(AgentEvent::ToolStart("shell".into()));
+        app.apply(AgentEvent::ToolEnd {
+            tool: "shell".into(),
+            duration: Duration::from_millis(12),
+            success: false,
+        });
+        assert_eq!(app.tools[0].status, ToolStatus::Done { success: false });
+        assert_eq!(app.tools[0].duration, Some(Duration::from_millis(12)));
+    }
+
+    #[test]
+    fn memory_browser_toggle_requests_refresh_and_navigates() {
+        let mut app = App::new("test".into());
+        assert_eq!(app.handle_key(key(KeyCode::F(2))), Action::RefreshMemory);
+        assert_eq!(app.focus, Focus::Memory);
+
+        let entry = |key: &str| MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: "c".into(),
+            category: crate::memory::MemoryCategory::Core,
+            timestamp: String::new(),
+            session_id: None,
+            score: None,
+            namespace: None,
+            tags: Vec::new(),
+            match_kind: None,
+        };
+        app.apply(AgentEvent::Memories(Ok(vec![entry("a"), entry("b")])));
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.memory.selected, 1);
+
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.focus, Focus::Input);
+        type_text(&mut app, "r");
+        assert_eq!(app.input, "r");
+    }
+}
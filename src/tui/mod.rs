@@ -0,0 +1,220 @@
+//! Full-screen terminal chat (`zeroclaw agent --tui`, requires `--features tui`).
+//!
+//! The agent runs on a worker task; the UI talks to it over channels:
+//! key presses become [`app::Action`]s, and the worker, observer, and approval
+//! prompter report back as [`app::AgentEvent`]s.
+
+mod app;
+mod ui;
+
+use crate::agent::Agent;
+use crate::approval::{
+    summarize_args, ApprovalManager, ApprovalPrompter, ApprovalRequest, ApprovalResponse,
+};
+use crate::config::Config;
+use crate::observability::{self, MultiObserver, Observer, ObserverEvent};
+use crate::providers::ConversationMessage;
+use anyhow::Result;
+use app::{Action, AgentEvent, App, PendingApproval};
+use async_trait::async_trait;
+use crossterm::event::{self, Event, KeyEventKind};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Memory entries loaded into the browser pane.
+const MEMORY_BROWSER_LIMIT: usize = 500;
+const TICK: Duration = Duration::from_millis(120);
+
+type EventTx = mpsc::UnboundedSender<AgentEvent>;
+
+/// Forwards tool activity to the sidebar.
+struct TuiObserver {
+    events: EventTx,
+}
+
+impl Observer for TuiObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let _ = match event {
+            ObserverEvent::ToolCallStart { tool } => {
+                self.events.send(AgentEvent::ToolStart(tool.clone()))
+            }
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => self.events.send(AgentEvent::ToolEnd {
+                tool: tool.clone(),
+                duration: *duration,
+                success: *success,
+            }),
+            _ => Ok(()),
+        };
+    }
+
+    fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "tui"
+    }
+}
+
+/// Shows approval requests as a popup and waits for y/n/a.
+struct TuiPrompter {
+    events: EventTx,
+}
+
+#[async_trait]
+impl ApprovalPrompter for TuiPrompter {
+    async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse {
+        let (reply, decision) = oneshot::channel();
+        let pending = PendingApproval {
+            tool: request.tool_name.clone(),
+            summary: summarize_args(&request.arguments),
+            reply,
+        };
+        if self.events.send(AgentEvent::Approval(pending)).is_err() {
+            return ApprovalResponse::No;
+        }
+        decision.await.unwrap_or(ApprovalResponse::No)
+    }
+
+    fn channel(&self) -> &str {
+        "tui"
+    }
+}
+
+/// Runs turns one at a time, keeping a separate history per session.
+async fn agent_worker(
+    mut agent: Agent,
+    mut requests: mpsc::UnboundedReceiver<(usize, String)>,
+    events: EventTx,
+) {
+    let mut histories: Vec<Vec<ConversationMessage>> = Vec::new();
+    while let Some((session, text)) = requests.recv().await {
+        if histories.len() <= session {
+            histories.resize_with(session + 1, Vec::new);
+        }
+        agent.swap_history(std::mem::take(&mut histories[session]));
+        let result = agent.turn(&text).await.map_err(|e| e.to_string());
+        histories[session] = agent.swap_history(Vec::new());
+        let _ = events.send(AgentEvent::TurnDone { session, result });
+    }
+}
+
+fn refresh_memories(memory: Arc<dyn crate::memory::Memory>, events: EventTx) {
+    tokio::spawn(async move {
+        let result = memory
+            .list(None, None)
+            .await
+            .map(|mut entries| {
+                entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+                entries.truncate(MEMORY_BROWSER_LIMIT);
+                entries
+            })
+            .map_err(|e| e.to_string());
+        let _ = events.send(AgentEvent::Memories(result));
+    });
+}
+
+/// Reads terminal key events on a blocking thread until `stop` is set.
+fn spawn_key_reader(
+    keys: mpsc::UnboundedSender<event::KeyEvent>,
+    stop: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match event::poll(TICK) {
+                Ok(true) => {
+                    if let Ok(Event::Key(key)) = event::read() {
+                        if key.kind == KeyEventKind::Press && keys.send(key).is_err() {
+                            break;
+                        }
+                    }
+                }
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    })
+}
+
+pub async fn run(
+    config: Config,
+    provider_override: Option<String>,
+    model_override: Option<String>,
+    temperature: f64,
+) -> Result<()> {
+    let mut config = config;
+    if let Some(p) = provider_override {
+        config.default_provider = Some(p);
+    }
+    if let Some(m) = model_override {
+        config.default_model = Some(m);
+    }
+    config.default_temperature = temperature;
+
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+    let (text_tx, mut text_rx) = mpsc::unbounded_channel();
+
+    let mut agent = Agent::from_config(&config)?;
+    agent.set_observer(Arc::new(MultiObserver::new(vec![
+        observability::create_observer(&config.observability),
+        Box::new(TuiObserver {
+            events: event_tx.clone(),
+        }),
+    ])));
+    agent.set_approval(
+        ApprovalManager::from_config(&config.autonomy),
+        Arc::new(TuiPrompter {
+            events: event_tx.clone(),
+        }),
+    );
+    agent.set_text_sink(text_tx);
+    let memory = agent.memory();
+
+    let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+    let mut app = App::new(format!("{provider_name}/{}", agent.model_name()));
+
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+    let worker = tokio::spawn(agent_worker(agent, request_rx, event_tx.clone()));
+
+    let (key_tx, mut key_rx) = mpsc::unbounded_channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let reader = spawn_key_reader(key_tx, Arc::clone(&stop));
+
+    let mut terminal = ratatui::init();
+    let mut ticker = tokio::time::interval(TICK);
+    let mut tick = 0usize;
+
+    let result: Result<()> = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &app, tick)) {
+            break Err(e.into());
+        }
+        // Biased so assistant text queued before a TurnDone is shown first.
+        tokio::select! {
+            biased;
+            Some(text) = text_rx.recv() => app.apply(AgentEvent::Text(text)),
+            Some(event) = event_rx.recv() => app.apply(event),
+            key = key_rx.recv() => {
+                let Some(key) = key else { break Ok(()) };
+                match app.handle_key(key) {
+                    Action::None => {}
+                    Action::Quit => break Ok(()),
+                    Action::Send { session, text } => {
+                        let _ = request_tx.send((session, text));
+                    }
+                    Action::RefreshMemory => refresh_memories(Arc::clone(&memory), event_tx.clone()),
+                }
+            }
+            _ = ticker.tick() => tick = tick.wrapping_add(1),
+        }
+    };
+
+    ratatui::restore();
+    stop.store(true, Ordering::Relaxed);
+    worker.abort();
+    let _ = reader.join();
+    result
+}
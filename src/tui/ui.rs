@@ -0,0 +1,362 @@
+//! Rendering for the TUI. Layout:
+//!
+//! ```text
+//! ┌ session tabs ──────────────────────── provider/model ┐
+//! │ transcript                        │ tool activity    │
+//! │                                   │ (memory browser) │
+//! ├ input ───────────────────────────────────────────────┤
+//! └ key hints ───────────────────────────────────────────┘
+//! ```
+
+use super::app::{App, Focus, Role, ToolStatus};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use ratatui::Frame;
+
+const SIDEBAR_WIDTH: u16 = 38;
+const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+pub fn draw(frame: &mut Frame, app: &App, tick: usize) {
+    let [header, body, input, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(3),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [chat, sidebar] =
+        Layout::horizontal([Constraint::Min(20), Constraint::Length(SIDEBAR_WIDTH)]).areas(body);
+
+    draw_header(frame, app, header, tick);
+    draw_transcript(frame, app, chat);
+    if app.memory.visible {
+        let [tools, memory] =
+            Layout::vertical([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(sidebar);
+        draw_tools(frame, app, tools);
+        draw_memory(frame, app, memory);
+    } else {
+        draw_tools(frame, app, sidebar);
+    }
+    draw_input(frame, app, input);
+    draw_footer(frame, app, footer);
+
+    if app.approval.is_some() {
+        draw_approval(frame, app);
+    } else if app.show_help {
+        draw_help(frame);
+    }
+}
+
+fn draw_header(frame: &mut Frame, app: &App, area: Rect, tick: usize) {
+    let titles = app.sessions.iter().enumerate().map(|(i, s)| {
+        if app.busy == Some(i) {
+            format!("{} {}", s.name, SPINNER[tick % SPINNER.len()])
+        } else {
+            s.name.clone()
+        }
+    });
+    let [tabs, title] = Layout::horizontal([
+        Constraint::Min(10),
+        Constraint::Length(u16::try_from(app.title.chars().count() + 1).unwrap_or(u16::MAX)),
+    ])
+    .areas(area);
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(app.current)
+            .highlight_style(Style::new().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        tabs,
+    );
+    frame.render_widget(
+        Paragraph::new(app.title.as_str()).style(Style::new().fg(Color::DarkGray)),
+        title,
+    );
+}
+
+fn draw_transcript(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" 🦀 ZeroClaw ");
+    let inner = block.inner(area);
+    let width = usize::from(inner.width.max(1));
+
+    let mut lines: Vec<Line> = Vec::new();
+    for line in &app.session().lines {
+        let (label, style) = match line.role {
+            Role::User => ("you", Style::new().fg(Color::Green)),
+            Role::Assistant => ("zeroclaw", Style::new().fg(Color::Cyan)),
+            Role::Error => ("error", Style::new().fg(Color::Red)),
+        };
+        lines.push(Line::from(Span::styled(
+            format!("{label} ›"),
+            style.add_modifier(Modifier::BOLD),
+        )));
+        for text_line in line.text.lines() {
+            for wrapped in wrap_text(text_line, width) {
+                lines.push(Line::raw(wrapped));
+            }
+        }
+        lines.push(Line::raw(""));
+    }
+
+    let height = usize::from(inner.height);
+    let max_back = lines.len().saturating_sub(height);
+    let top = max_back.saturating_sub(app.session().scroll_back.min(max_back));
+    let visible: Vec<Line> = lines.into_iter().skip(top).take(height).collect();
+    frame.render_widget(Paragraph::new(visible).block(block), area);
+}
+
+fn draw_tools(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .tools
+        .iter()
+        .rev()
+        .map(|t| {
+            let (icon, timing) = match t.status {
+                ToolStatus::Running => ("⏳", format!("{}s", t.started.elapsed().as_secs())),
+                ToolStatus::Done { success } => (
+                    if success { "✅" } else { "❌" },
+                    t.duration
+                        .map(|d| format!("{}ms", d.as_millis()))
+                        .unwrap_or_default(),
+                ),
+            };
+            let session = &app.sessions[t.session.min(app.sessions.len() - 1)].name;
+            ListItem::new(format!("{icon} {} {timing} [{session}]", t.tool))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(" Tools ")),
+        area,
+    );
+}
+
+fn draw_memory(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focus == Focus::Memory;
+    let title = if app.memory.loading {
+        " Memory (loading…) ".to_string()
+    } else {
+        format!(" Memory ({}) ", app.memory.entries.len())
+    };
+    let block = Block::bordered().title(title).border_style(if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    });
+
+    if let Some(error) = &app.memory.error {
+        frame.render_widget(
+            Paragraph::new(error.as_str())
+                .style(Style::new().fg(Color::Red))
+                .wrap(Wrap { trim: true })
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [list_area, detail_area] =
+        Layout::vertical([Constraint::Percentage(55), Constraint::Percentage(45)]).areas(inner);
+
+    let items: Vec<ListItem> = app
+        .memory
+        .entries
+        .iter()
+        .map(|e| ListItem::new(format!("[{}] {}", e.category, e.key)))
+        .collect();
+    let mut state = ListState::default().with_selected(Some(app.memory.selected));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        list_area,
+        &mut state,
+    );
+
+    if let Some(entry) = app.memory.entries.get(app.memory.selected) {
+        frame.render_widget(
+            Paragraph::new(format!("{}\n\n{}", entry.timestamp, entry.content))
+                .wrap(Wrap { trim: false })
+                .block(Block::new().borders(ratatui::widgets::Borders::TOP)),
+            detail_area,
+        );
+    }
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focus == Focus::Input && app.approval.is_none();
+    let block = Block::bordered()
+        .title(" Message ")
+        .border_style(if focused {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new()
+        });
+    let inner = block.inner(area);
+
+    // Keep the cursor visible by scrolling long input horizontally.
+    let width = usize::from(inner.width.max(1));
+    let offset = app.cursor.saturating_sub(width - 1);
+    let shown: String = app.input.chars().skip(offset).take(width).collect();
+    frame.render_widget(Paragraph::new(shown).block(block), area);
+
+    if focused {
+        let x = inner.x + u16::try_from(app.cursor - offset).unwrap_or(inner.width);
+        frame.set_cursor_position((x, inner.y));
+    }
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let text = app.status.clone().unwrap_or_else(|| {
+        "Enter send · Ctrl+N new chat · Ctrl+←/→ switch · PgUp/PgDn scroll · F2 memory · F1 help · Ctrl+C quit"
+            .into()
+    });
+    frame.render_widget(
+        Paragraph::new(text).style(Style::new().fg(Color::DarkGray)),
+        area,
+    );
+}
+
+fn draw_approval(frame: &mut Frame, app: &App) {
+    let Some(pending) = &app.approval else {
+        return;
+    };
+    let area = centered(frame.area(), 60, 9);
+    frame.render_widget(Clear, area);
+    let text = vec![
+        Line::from(Span::styled(
+            format!("Agent wants to run: {}", pending.tool),
+            Style::new().add_modifier(Modifier::BOLD),
+        )),
+        Line::raw(""),
+        Line::raw(pending.summary.as_str()),
+        Line::raw(""),
+        Line::from(vec![
+            Span::styled("[y]", Style::new().fg(Color::Green)),
+            Span::raw(" approve   "),
+            Span::styled("[n]", Style::new().fg(Color::Red)),
+            Span::raw(" deny   "),
+            Span::styled("[a]", Style::new().fg(Color::Yellow)),
+            Span::raw(" always allow this tool"),
+        ]),
+    ];
+    frame.render_widget(
+        Paragraph::new(text).wrap(Wrap { trim: false }).block(
+            Block::bordered()
+                .title(" 🔧 Approval required ")
+                .border_style(Style::new().fg(Color::Yellow)),
+        ),
+        area,
+    );
+}
+
+fn draw_help(frame: &mut Frame) {
+    let area = centered(frame.area(), 56, 16);
+    frame.render_widget(Clear, area);
+    let rows = [
+        ("Enter", "send message"),
+        ("Ctrl+N, /new", "new chat session"),
+        ("Ctrl+← / Ctrl+→", "previous / next session"),
+        ("PgUp / PgDn", "scroll transcript"),
+        ("F2", "toggle memory browser"),
+        ("Tab", "focus input ⇄ memory"),
+        ("↑ / ↓, r", "select / refresh memories"),
+        ("y / n / a", "approve / deny / always (prompts)"),
+        ("Ctrl+C, /quit", "exit"),
+    ];
+    let lines: Vec<Line> = rows
+        .iter()
+        .map(|(keys, what)| {
+            Line::from(vec![
+                Span::styled(format!("{keys:<18}"), Style::new().fg(Color::Cyan)),
+                Span::raw(*what),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Keys (any key to close) ")),
+        area,
+    );
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Greedy word wrap to `width` columns (by char count); long words are split.
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_len = 0;
+    for word in text.split(' ') {
+        let word_len = word.chars().count();
+        if current_len > 0 && current_len + 1 + word_len > width {
+            lines.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if current_len > 0 {
+            current.push(' ');
+            current_len += 1;
+        }
+        let mut chars = word.chars().peekable();
+        while chars.peek().is_some() {
+            if current_len == width {
+                lines.push(std::mem::take(&mut current));
+                current_len = 0;
+            }
+            current.push(chars.next().unwrap_or_default());
+            current_len += 1;
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn wrap_text_breaks_on_words_and_splits_long_words() {
+        assert_eq!(wrap_text("hello big world", 9), ["hello big", "world"]);
+        assert_eq!(wrap_text("abcdefghij", 4), ["abcd", "efgh", "ij"]);
+        assert_eq!(wrap_text("", 10), [""]);
+    }
+
+    #[test]
+    fn draw_renders_transcript_and_approval_popup() {
+        let mut app = App::new("openrouter/test-model".into());
+        app.sessions[0].lines.push(super::super::app::ChatLine {
+            role: Role::Assistant,
+            text: "Hello from the agent".into(),
+        });
+        let (tx, _rx) = tokio::sync::oneshot::channel();
+        app.approval = Some(super::super::app::PendingApproval {
+            tool: "shell".into(),
+            summary: "command: ls".into(),
+            reply: tx,
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| draw(f, &app, 0)).unwrap();
+        let rendered: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(rendered.contains("Hello from the agent"));
+        assert!(rendered.contains("Agent wants to run: shell"));
+        assert!(rendered.contains("openrouter/test-model"));
+    }
+}
@@ -0,0 +1,553 @@
+//! `zeroclaw self-update`: install the newest GitHub release for this
+//! platform, verified against `SHA256SUMS` and (with `cosign` installed) the
+//! release signature, then restart the daemon service.
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+const REPOSITORY: &str = "zeroclaw-labs/zeroclaw";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+/// Identity of the release workflow that signs artifacts (cosign keyless).
+const SIGNER_IDENTITY: &str =
+    r"^https://github\.com/zeroclaw-labs/zeroclaw/\.github/workflows/release\.yml@refs/tags/v.*$";
+const SIGNER_ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+/// Which releases to follow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseChannel {
+    /// Full releases only
+    Stable,
+    /// Pre-releases too
+    Beta,
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stable" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => bail!("unknown release channel '{other}' (use stable or beta)"),
+        }
+    }
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone)]
+pub struct UpdateOptions {
+    pub channel: ReleaseChannel,
+    /// Only report whether an update is available
+    pub check: bool,
+    /// Reinstall even when already up to date
+    pub force: bool,
+    /// Fail instead of warning when the signature cannot be verified
+    pub require_signature: bool,
+    /// Leave the daemon service running the old binary
+    pub no_restart: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// A `major.minor.patch[-pre]` version, ordered like semver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+    numbers: [u64; 3],
+    pre: Vec<String>,
+}
+
+impl Version {
+    fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim().trim_start_matches('v');
+        let raw = raw.split('+').next().unwrap_or(raw);
+        let (core, pre) = match raw.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (raw, Vec::new()),
+        };
+        let mut numbers = [0_u64; 3];
+        let mut parts = core.split('.');
+        for slot in &mut numbers {
+            *slot = parts.next()?.parse().ok()?;
+        }
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self { numbers, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.numbers.cmp(&other.numbers).then_with(|| {
+            match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.pre.iter().zip(&other.pre) {
+                        let ord = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if ord != Ordering::Equal {
+                            return ord;
+                        }
+                    }
+                    self.pre.len().cmp(&other.pre.len())
+                }
+            }
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Rust target triple of the running binary, as used in release asset names.
+fn target_triple() -> Option<&'static str> {
+    if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        Some("x86_64-unknown-linux-gnu")
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        Some("aarch64-unknown-linux-gnu")
+    } else if cfg!(all(target_os = "macos", target_arch = "x86_64")) {
+        Some("x86_64-apple-darwin")
+    } else if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        Some("aarch64-apple-darwin")
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        Some("x86_64-pc-windows-msvc")
+    } else {
+        None
+    }
+}
+
+fn asset_name(target: &str) -> String {
+    let ext = if target.contains("windows") {
+        "zip"
+    } else {
+        "tar.gz"
+    };
+    format!("zeroclaw-{target}.{ext}")
+}
+
+fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "zeroclaw.exe"
+    } else {
+        "zeroclaw"
+    }
+}
+
+/// Newest release on `channel` that has an asset named `asset`.
+fn select_release<'a>(
+    releases: &'a [Release],
+    channel: ReleaseChannel,
+    asset: &str,
+) -> Option<(&'a Release, Version)> {
+    releases
+        .iter()
+        .filter(|r| !r.draft && (channel == ReleaseChannel::Beta || !r.prerelease))
+        .filter(|r| r.asset(asset).is_some())
+        .filter_map(|r| Version::parse(&r.tag_name).map(|v| (r, v)))
+        .max_by(|a, b| a.1.cmp(&b.1))
+}
+
+/// The checksum for `asset` in a `sha256sum` listing.
+fn expected_checksum(listing: &str, asset: &str) -> Option<String> {
+    listing.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim().trim_start_matches('*');
+        (name == asset).then(|| hash.to_ascii_lowercase())
+    })
+}
+
+fn client() -> Result<reqwest::Client> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::ACCEPT,
+        "application/vnd.github+json".parse()?,
+    );
+    headers.insert(
+        reqwest::header::USER_AGENT,
+        format!("zeroclaw/{}", env!("CARGO_PKG_VERSION")).parse()?,
+    );
+    if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+        if let Ok(value) = format!("Bearer {token}").parse() {
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+    }
+    Ok(reqwest::Client::builder()
+        .default_headers(headers)
+        .timeout(Duration::from_secs(300))
+        .build()?)
+}
+
+async fn download(client: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to download {url}"))?;
+    if !response.status().is_success() {
+        bail!("Download of {url} failed: HTTP {}", response.status());
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+async fn fetch_releases(client: &reqwest::Client) -> Result<Vec<Release>> {
+    let url = format!("https://api.github.com/repos/{REPOSITORY}/releases?per_page=30");
+    let body = download(client, &url).await?;
+    serde_json::from_slice(&body).context("Unexpected response from the GitHub releases API")
+}
+
+/// Verify the cosign signature of `archive` with the `cosign` CLI.
+/// Returns `Ok(false)` when it could not be checked.
+fn verify_signature(archive: &Path, signature: &Path, certificate: &Path) -> Result<bool> {
+    let output = match Command::new("cosign")
+        .arg("verify-blob")
+        .arg("--signature")
+        .arg(signature)
+        .arg("--certificate")
+        .arg(certificate)
+        .args(["--certificate-identity-regexp", SIGNER_IDENTITY])
+        .args(["--certificate-oidc-issuer", SIGNER_ISSUER])
+        .arg(archive)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).context("Failed to run cosign"),
+    };
+    if !output.status.success() {
+        bail!(
+            "Signature verification failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(true)
+}
+
+/// Replace `current` with `new` so that either the old or the new binary is
+/// in place at every moment.
+fn replace_binary(current: &Path, new: &Path) -> Result<()> {
+    let dir = current
+        .parent()
+        .context("Current executable has no parent directory")?;
+    // Stage next to the target so the final rename stays on one filesystem.
+    let staged = dir.join(format!(".{}.update", binary_name()));
+    fs::copy(new, &staged).with_context(|| {
+        format!(
+            "Cannot write to {} (try again with permission to replace the binary)",
+            dir.display()
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+
+    #[cfg(windows)]
+    {
+        // A running executable cannot be overwritten, but it can be renamed.
+        let old = current.with_extension("old.exe");
+        let _ = fs::remove_file(&old);
+        fs::rename(current, &old)?;
+    }
+
+    fs::rename(&staged, current).with_context(|| format!("Failed to replace {}", current.display()))
+}
+
+/// Restart the daemon so it runs the new binary.
+fn restart_daemon(config: &Config) {
+    match crate::service::restart_if_installed(config) {
+        Ok(true) => println!("🔄 Restarted the daemon service"),
+        Ok(false) => {
+            let state = crate::daemon::state_file_path(config);
+            let pid = fs::read_to_string(&state)
+                .ok()
+                .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+                .and_then(|snapshot| snapshot.get("pid").and_then(serde_json::Value::as_u64));
+            if let Some(pid) = pid {
+                println!(
+                    "ℹ️  A daemon (pid {pid}) may be running outside a service manager; restart it to use the new version."
+                );
+            }
+        }
+        Err(e) => println!("⚠️  Could not restart the daemon service: {e:#}"),
+    }
+}
+
+pub async fn run(config: &Config, options: UpdateOptions) -> Result<()> {
+    let current_version =
+        Version::parse(env!("CARGO_PKG_VERSION")).context("Unparseable package version")?;
+    let Some(target) = target_triple() else {
+        bail!("No prebuilt releases for this platform; build from source instead");
+    };
+    let asset = asset_name(target);
+
+    let exe = std::env::current_exe()?.canonicalize()?;
+    if exe.components().any(|c| c.as_os_str() == "Cellar") {
+        bail!(
+            "{} is managed by Homebrew; use `brew upgrade zeroclaw`",
+            exe.display()
+        );
+    }
+
+    let client = client()?;
+    let releases = fetch_releases(&client).await?;
+    let Some((release, version)) = select_release(&releases, options.channel, &asset) else {
+        bail!("No {asset} in any {:?} release", options.channel);
+    };
+
+    let newer = version > current_version;
+    if !newer && !options.force {
+        println!(
+            "✅ zeroclaw {} is up to date ({:?} channel)",
+            env!("CARGO_PKG_VERSION"),
+            options.channel
+        );
+        return Ok(());
+    }
+    if options.check {
+        println!(
+            "⬆️  {} is available (installed: {})",
+            release.tag_name,
+            env!("CARGO_PKG_VERSION")
+        );
+        return Ok(());
+    }
+
+    println!("⬇️  Downloading {} {asset}...", release.tag_name);
+    let archive_url = &release
+        .asset(&asset)
+        .expect("selected by asset")
+        .browser_download_url;
+    let archive = download(&client, archive_url).await?;
+
+    let Some(checksums) = release.asset(CHECKSUMS_ASSET) else {
+        bail!(
+            "{} has no {CHECKSUMS_ASSET}; refusing to install",
+            release.tag_name
+        );
+    };
+    let listing = String::from_utf8(download(&client, &checksums.browser_download_url).await?)?;
+    let expected = expected_checksum(&listing, &asset)
+        .with_context(|| format!("{CHECKSUMS_ASSET} has no entry for {asset}"))?;
+    let actual = hex::encode(Sha256::digest(&archive));
+    if actual != expected {
+        bail!("Checksum mismatch for {asset}: expected {expected}, got {actual}");
+    }
+    println!("🔒 Checksum verified");
+
+    let work = std::env::temp_dir().join(format!("zeroclaw-update-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&work)?;
+    let result = install(release, &asset, &archive, &client, &work, &exe, &options).await;
+    let _ = fs::remove_dir_all(&work);
+    result?;
+
+    println!("✅ Updated zeroclaw to {}", release.tag_name);
+    if !options.no_restart {
+        restart_daemon(config);
+    }
+    Ok(())
+}
+
+async fn install(
+    release: &Release,
+    asset: &str,
+    archive: &[u8],
+    client: &reqwest::Client,
+    work: &Path,
+    exe: &Path,
+    options: &UpdateOptions,
+) -> Result<()> {
+    let archive_path = work.join(asset);
+    fs::write(&archive_path, archive)?;
+
+    let signature = release.asset(&format!("{asset}.sig"));
+    let certificate = release.asset(&format!("{asset}.pem"));
+    let verified = match (signature, certificate) {
+        (Some(signature), Some(certificate)) => {
+            let sig_path = work.join(&signature.name);
+            let cert_path = work.join(&certificate.name);
+            fs::write(
+                &sig_path,
+                download(client, &signature.browser_download_url).await?,
+            )?;
+            fs::write(
+                &cert_path,
+                download(client, &certificate.browser_download_url).await?,
+            )?;
+            verify_signature(&archive_path, &sig_path, &cert_path)?
+        }
+        _ => false,
+    };
+    if verified {
+        println!("🔏 Signature verified");
+    } else if options.require_signature {
+        bail!(
+            "Could not verify the release signature (is cosign installed and the release signed?)"
+        );
+    } else {
+        println!(
+            "⚠️  Signature not verified (install cosign to check it); relying on the checksum"
+        );
+    }
+
+    let unpacked = work.join("unpacked");
+    fs::create_dir_all(&unpacked)?;
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(&unpacked)
+        .output()
+        .context("Failed to run tar to unpack the release")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to unpack {asset}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let new_binary = unpacked.join(binary_name());
+    if !new_binary.is_file() {
+        bail!("{asset} does not contain {}", binary_name());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&new_binary, fs::Permissions::from_mode(0o755))?;
+    }
+    let probe = Command::new(&new_binary)
+        .arg("--version")
+        .output()
+        .context("Downloaded binary does not run on this system")?;
+    if !probe.status.success() {
+        bail!("Downloaded binary does not run on this system");
+    }
+
+    replace_binary(exe, &new_binary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn release(tag: &str, prerelease: bool, assets: &[&str]) -> Release {
+        Release {
+            tag_name: tag.into(),
+            prerelease,
+            draft: false,
+            assets: assets
+                .iter()
+                .map(|name| Asset {
+                    name: (*name).into(),
+                    browser_download_url: format!("https://example.com/{tag}/{name}"),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn versions_order_like_semver() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(v("v0.2.0") > v("0.1.9"));
+        assert!(v("0.2.0") > v("0.2.0-beta.2"));
+        assert!(v("0.2.0-beta.10") > v("0.2.0-beta.2"));
+        assert!(v("0.2.0-rc.1") > v("0.2.0-beta.5"));
+        assert_eq!(v("v1.0.0+build.5"), v("1.0.0"));
+        assert!(Version::parse("nightly").is_none());
+        assert!(Version::parse("1.2").is_none());
+    }
+
+    #[test]
+    fn channel_decides_whether_prereleases_count() {
+        let asset = "zeroclaw-x86_64-unknown-linux-gnu.tar.gz";
+        let releases = vec![
+            release("v0.3.0-beta.1", true, &[asset]),
+            release("v0.2.1", false, &["zeroclaw-aarch64-apple-darwin.tar.gz"]),
+            release("v0.2.0", false, &[asset]),
+        ];
+
+        let (stable, _) = select_release(&releases, ReleaseChannel::Stable, asset).unwrap();
+        assert_eq!(
+            stable.tag_name, "v0.2.0",
+            "skips releases without our asset"
+        );
+        let (beta, _) = select_release(&releases, ReleaseChannel::Beta, asset).unwrap();
+        assert_eq!(beta.tag_name, "v0.3.0-beta.1");
+        assert!("nightly".parse::<ReleaseChannel>().is_err());
+    }
+
+    #[test]
+    fn checksum_listing_is_matched_by_asset_name() {
+        let listing = "ABC123  zeroclaw-x86_64-apple-darwin.tar.gz\n\
+                       def456 *zeroclaw-x86_64-pc-windows-msvc.zip\n";
+        assert_eq!(
+            expected_checksum(listing, "zeroclaw-x86_64-apple-darwin.tar.gz").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(
+            expected_checksum(listing, "zeroclaw-x86_64-pc-windows-msvc.zip").as_deref(),
+            Some("def456")
+        );
+        assert!(expected_checksum(listing, "zeroclaw-aarch64-apple-darwin.tar.gz").is_none());
+        assert_eq!(
+            asset_name("x86_64-pc-windows-msvc"),
+            "zeroclaw-x86_64-pc-windows-msvc.zip"
+        );
+    }
+
+    #[test]
+    fn replace_binary_swaps_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let current = tmp.path().join(binary_name());
+        let new = tmp.path().join("download");
+        fs::write(&current, "old").unwrap();
+        fs::write(&new, "new").unwrap();
+
+        replace_binary(&current, &new).unwrap();
+        assert_eq!(fs::read_to_string(&current).unwrap(), "new");
+        assert!(!tmp
+            .path()
+            .join(format!(".{}.update", binary_name()))
+            .exists());
+    }
+}